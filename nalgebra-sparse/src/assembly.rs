@@ -0,0 +1,516 @@
+//! Finite element style assembly of CSR matrices with a fixed sparsity pattern.
+//!
+//! In finite element methods, the global system matrix is the sum of small dense *element
+//! matrices*, each of which contributes to the rows and columns given by the nodes of its
+//! element. The sparsity pattern of the global matrix is therefore determined by the element
+//! connectivity alone, and typically does not change between time steps or iterations, even
+//! though the values do.
+//!
+//! This module lets the pattern be computed once with [`pattern_from_connectivity`], after which
+//! the element matrices are accumulated directly into the values of a [`CsrMatrix`] with that
+//! pattern, without going through an intermediate [`CooMatrix`](crate::coo::CooMatrix):
+//!
+//! - [`add_element_matrix`] locates every entry with a binary search in the rows of the matrix,
+//!   like [`CsrMatrix::get_entry_mut`].
+//! - A [`CsrAssembler`] precomputes the location of every entry of every element matrix once,
+//!   so that repeated assembly only needs to add the values. With the `rayon` feature enabled,
+//!   `CsrAssembler::par_assemble` assembles the elements in parallel, using a
+//!   [coloring](color_elements) of the elements such that the elements of the same color share
+//!   no nodes.
+//!
+//! # Example
+//!
+//! ```
+//! use nalgebra_sparse::assembly::{pattern_from_connectivity, CsrAssembler};
+//! use nalgebra_sparse::CsrMatrix;
+//! use nalgebra::{DMatrix, Matrix2};
+//!
+//! // A 1D mesh of four linear elements on the nodes 0, ..., 4
+//! let elements = vec![[0, 1], [1, 2], [2, 3], [3, 4]];
+//! let pattern = pattern_from_connectivity(5, &elements);
+//! let assembler = CsrAssembler::try_from_pattern(&pattern, &elements).unwrap();
+//!
+//! // The stiffness matrix of the Laplace operator, assembled in place
+//! let mut stiffness = CsrMatrix::try_from_pattern_and_values(pattern, vec![0.0; 13]).unwrap();
+//! let element_stiffness = Matrix2::new(1.0, -1.0, -1.0, 1.0);
+//! assembler.assemble(&mut stiffness, |_, mut element_matrix| {
+//!     element_matrix.copy_from(&element_stiffness)
+//! });
+//!
+//! assert_eq!(DMatrix::from(&stiffness), DMatrix::from_row_slice(5, 5, &[
+//!      1.0, -1.0,  0.0,  0.0,  0.0,
+//!     -1.0,  2.0, -1.0,  0.0,  0.0,
+//!      0.0, -1.0,  2.0, -1.0,  0.0,
+//!      0.0,  0.0, -1.0,  2.0, -1.0,
+//!      0.0,  0.0,  0.0, -1.0,  1.0,
+//! ]));
+//!
+//! // In the next time step, the values are reset and assembled again into the same pattern
+//! stiffness.values_mut().fill(0.0);
+//! assembler.assemble(&mut stiffness, |_, mut element_matrix| {
+//!     element_matrix.copy_from(&(2.0 * element_stiffness))
+//! });
+//! assert_eq!(stiffness.get_entry(2, 2).unwrap().into_value(), 4.0);
+//! ```
+
+use crate::csr::{CsrMatrix, CsrMatrixViewMut};
+use crate::ops::serial::{OperationError, OperationErrorKind};
+use crate::pattern::SparsityPattern;
+use crate::SparseEntryMut;
+
+use nalgebra::{ClosedAdd, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::Zero;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use std::mem::take;
+
+fn missing_entry_error(row: usize, col: usize) -> OperationError {
+    OperationError::from_kind_and_message(
+        OperationErrorKind::InvalidPattern,
+        format!(
+            "The entry ({}, {}) of the element matrix is not present in the pattern.",
+            row, col
+        ),
+    )
+}
+
+/// Computes the sparsity pattern of a matrix assembled from the given elements.
+///
+/// Each element is given by the indices of its nodes, and couples all of its nodes with each
+/// other. The resulting pattern is square with dimension `num_nodes`, and contains the entry
+/// `(i, j)` if and only if the nodes `i` and `j` belong to a common element.
+///
+/// # Panics
+///
+/// Panics if a node index is not smaller than `num_nodes`.
+#[must_use]
+pub fn pattern_from_connectivity<E>(num_nodes: usize, elements: &[E]) -> SparsityPattern
+where
+    E: AsRef<[usize]>,
+{
+    let mut neighbors = vec![Vec::new(); num_nodes];
+    for element in elements {
+        let nodes = element.as_ref();
+        for &i in nodes {
+            assert!(
+                i < num_nodes,
+                "Node index must be smaller than the number of nodes."
+            );
+            neighbors[i].extend_from_slice(nodes);
+        }
+    }
+
+    let mut offsets = Vec::with_capacity(num_nodes + 1);
+    let mut indices = Vec::new();
+    offsets.push(0);
+    for mut row in neighbors {
+        row.sort_unstable();
+        row.dedup();
+        indices.append(&mut row);
+        offsets.push(indices.len());
+    }
+
+    SparsityPattern::try_from_offsets_and_indices(num_nodes, num_nodes, offsets, indices)
+        .expect("Internal error: Invalid pattern during assembly from connectivity")
+}
+
+/// Partitions the elements into colors such that no two elements of the same color share a node.
+///
+/// Returns the indices of the elements of each color, in increasing order. The elements are
+/// colored greedily in the given order, which usually gives a number of colors close to the
+/// maximum number of elements that share a single node.
+///
+/// # Panics
+///
+/// Panics if a node index is not smaller than `num_nodes`.
+#[must_use]
+pub fn color_elements<E>(num_nodes: usize, elements: &[E]) -> Vec<Vec<usize>>
+where
+    E: AsRef<[usize]>,
+{
+    let mut colors: Vec<Vec<usize>> = Vec::new();
+    // For each color, whether a node belongs to an element of that color
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    for (element_index, element) in elements.iter().enumerate() {
+        let nodes = element.as_ref();
+        for &i in nodes {
+            assert!(
+                i < num_nodes,
+                "Node index must be smaller than the number of nodes."
+            );
+        }
+        let color = occupied
+            .iter()
+            .position(|occupied| nodes.iter().all(|&i| !occupied[i]))
+            .unwrap_or_else(|| {
+                colors.push(Vec::new());
+                occupied.push(vec![false; num_nodes]);
+                colors.len() - 1
+            });
+        for &i in nodes {
+            occupied[color][i] = true;
+        }
+        colors[color].push(element_index);
+    }
+    colors
+}
+
+/// Adds a dense element matrix to the rows and columns of `matrix` given by `nodes`.
+///
+/// The entry `(a, b)` of the element matrix is added to the entry `(nodes[a], nodes[b])` of the
+/// matrix, which is located with a binary search in row `nodes[a]`. Nodes may be repeated, in
+/// which case all contributions are summed.
+///
+/// # Errors
+///
+/// Returns an error if the pattern of `matrix` does not contain all the entries coupling the
+/// given nodes. In this case, the values of `matrix` may have been partially modified.
+///
+/// # Panics
+///
+/// Panics if the element matrix is not a square matrix with one row per node, or if a node
+/// index is out of bounds.
+pub fn add_element_matrix<'a, 'b, T>(
+    matrix: impl Into<CsrMatrixViewMut<'a, T>>,
+    nodes: &[usize],
+    element_matrix: impl Into<DMatrixView<'b, T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd,
+{
+    let mut matrix = matrix.into();
+    let element_matrix = element_matrix.into();
+    assert_eq!(
+        element_matrix.shape(),
+        (nodes.len(), nodes.len()),
+        "Element matrix must be square with one row per node."
+    );
+
+    for (a, &i) in nodes.iter().enumerate() {
+        let mut row = matrix.row_mut(i);
+        for (b, &j) in nodes.iter().enumerate() {
+            match row.get_entry_mut(j) {
+                Some(SparseEntryMut::NonZero(value)) => *value += element_matrix[(a, b)].clone(),
+                _ => return Err(missing_entry_error(i, j)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Assembles element matrices into CSR matrices with a fixed sparsity pattern.
+///
+/// The assembler stores the connectivity of the elements along with the location of every entry
+/// of every element matrix in the values of a CSR matrix with the given pattern. Assembling into
+/// such a matrix therefore requires no searching at all. With the `rayon` feature enabled, the
+/// assembler also stores a [coloring](color_elements) of the elements, which is used for parallel
+/// assembly.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrAssembler {
+    pattern: SparsityPattern,
+    // Offsets into `nodes` for each element, with length `num_elements + 1`
+    node_offsets: Vec<usize>,
+    nodes: Vec<usize>,
+    // Offsets into `positions` for each element, with length `num_elements + 1`
+    position_offsets: Vec<usize>,
+    // For each element, the position of each entry of the element matrix (in row-major order)
+    // relative to the start of its row in the values of the matrix
+    positions: Vec<usize>,
+    #[cfg(feature = "rayon")]
+    colors: Vec<Vec<usize>>,
+}
+
+impl CsrAssembler {
+    /// Try to construct an assembler for the given elements and CSR matrices with the given
+    /// pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern does not contain all the entries coupling the nodes of
+    /// each element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node index is out of bounds.
+    pub fn try_from_pattern<E>(
+        pattern: &SparsityPattern,
+        elements: &[E],
+    ) -> Result<Self, OperationError>
+    where
+        E: AsRef<[usize]>,
+    {
+        let mut node_offsets = Vec::with_capacity(elements.len() + 1);
+        let mut nodes = Vec::new();
+        let mut position_offsets = Vec::with_capacity(elements.len() + 1);
+        let mut positions = Vec::new();
+        node_offsets.push(0);
+        position_offsets.push(0);
+
+        for element in elements {
+            let element_nodes = element.as_ref();
+            for &i in element_nodes {
+                assert!(
+                    i < pattern.major_dim(),
+                    "Node index must be smaller than the number of rows."
+                );
+                let row = pattern.lane(i);
+                for &j in element_nodes {
+                    let position = row
+                        .binary_search(&j)
+                        .map_err(|_| missing_entry_error(i, j))?;
+                    positions.push(position);
+                }
+            }
+            nodes.extend_from_slice(element_nodes);
+            node_offsets.push(nodes.len());
+            position_offsets.push(positions.len());
+        }
+
+        Ok(Self {
+            pattern: pattern.clone(),
+            node_offsets,
+            nodes,
+            position_offsets,
+            positions,
+            #[cfg(feature = "rayon")]
+            colors: color_elements(pattern.major_dim(), elements),
+        })
+    }
+
+    /// The number of elements.
+    #[inline]
+    #[must_use]
+    pub fn num_elements(&self) -> usize {
+        self.node_offsets.len() - 1
+    }
+
+    /// The nodes of the element with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element index is out of bounds.
+    #[must_use]
+    pub fn element_nodes(&self, element_index: usize) -> &[usize] {
+        &self.nodes[self.node_offsets[element_index]..self.node_offsets[element_index + 1]]
+    }
+
+    /// The number of colors used for parallel assembly.
+    ///
+    /// *Only available if compiled with the feature `rayon`.*
+    #[cfg(feature = "rayon")]
+    #[inline]
+    #[must_use]
+    pub fn num_colors(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// The indices of the elements with the given color.
+    ///
+    /// *Only available if compiled with the feature `rayon`.*
+    ///
+    /// Elements of the same color share no nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the color is out of bounds.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn color_elements(&self, color: usize) -> &[usize] {
+        &self.colors[color]
+    }
+
+    fn element_positions(&self, element_index: usize) -> &[usize] {
+        &self.positions
+            [self.position_offsets[element_index]..self.position_offsets[element_index + 1]]
+    }
+
+    fn assert_compatible_matrix<T>(&self, matrix: &CsrMatrix<T>) {
+        assert!(
+            matrix.pattern() == &self.pattern,
+            "Matrix must have the pattern that the assembler was constructed with."
+        );
+    }
+
+    /// Checks that the entries of the element with the given index are stored where the
+    /// assembler expects them in `matrix`, which is much cheaper than comparing the full
+    /// patterns.
+    fn assert_compatible_element_entries<T>(&self, matrix: &CsrMatrix<T>, element_index: usize) {
+        let msg = "Matrix must have the pattern that the assembler was constructed with.";
+        assert_eq!(matrix.nrows(), self.pattern.major_dim(), "{}", msg);
+        assert_eq!(matrix.ncols(), self.pattern.minor_dim(), "{}", msg);
+        assert_eq!(matrix.nnz(), self.pattern.nnz(), "{}", msg);
+
+        let (row_offsets, col_indices, _) = matrix.csr_data();
+        let nodes = self.element_nodes(element_index);
+        let mut positions = self.element_positions(element_index).iter();
+        for &i in nodes {
+            let row_indices = &col_indices[row_offsets[i]..row_offsets[i + 1]];
+            for (&j, &position) in nodes.iter().zip(&mut positions) {
+                assert_eq!(row_indices.get(position), Some(&j), "{}", msg);
+            }
+        }
+    }
+
+    /// Adds the element matrix of the element with the given index to `matrix`.
+    ///
+    /// Only the entries of the element are checked against the pattern of `matrix`, so the cost
+    /// of the check is proportional to the size of the element matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix does not have the pattern that the assembler was constructed with,
+    /// or if the element matrix is not a square matrix with one row per node of the element.
+    pub fn add_element_matrix<'a, T>(
+        &self,
+        matrix: &mut CsrMatrix<T>,
+        element_index: usize,
+        element_matrix: impl Into<DMatrixView<'a, T>>,
+    ) where
+        T: Scalar + ClosedAdd,
+    {
+        self.assert_compatible_element_entries(matrix, element_index);
+        let element_matrix = element_matrix.into();
+        let num_nodes = self.element_nodes(element_index).len();
+        assert_eq!(
+            element_matrix.shape(),
+            (num_nodes, num_nodes),
+            "Element matrix must be square with one row per node."
+        );
+        self.add_element_matrix_unchecked(matrix, element_index, element_matrix);
+    }
+
+    /// Adds the element matrix to `matrix`, assuming that the matrix has the pattern of the
+    /// assembler and that the element matrix has the right dimensions.
+    fn add_element_matrix_unchecked<T>(
+        &self,
+        matrix: &mut CsrMatrix<T>,
+        element_index: usize,
+        element_matrix: DMatrixView<'_, T>,
+    ) where
+        T: Scalar + ClosedAdd,
+    {
+        let nodes = self.element_nodes(element_index);
+        let (row_offsets, _, values) = matrix.csr_data_mut();
+        let mut positions = self.element_positions(element_index).iter();
+        for (a, &i) in nodes.iter().enumerate() {
+            let row_values = &mut values[row_offsets[i]..row_offsets[i + 1]];
+            for (b, &position) in (0..nodes.len()).zip(&mut positions) {
+                row_values[position] += element_matrix[(a, b)].clone();
+            }
+        }
+    }
+
+    /// Assembles all elements into `matrix`.
+    ///
+    /// For each element, `element_matrix` is called with the index of the element and a
+    /// zero-initialized square matrix with one row per node of the element, which it must fill
+    /// with the element matrix. The element matrices are added to the existing values of
+    /// `matrix`, so the values should usually be reset to zero before assembly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix does not have the pattern that the assembler was constructed with.
+    pub fn assemble<T, F>(&self, matrix: &mut CsrMatrix<T>, mut element_matrix: F)
+    where
+        T: Scalar + ClosedAdd + Zero,
+        F: FnMut(usize, DMatrixViewMut<'_, T>),
+    {
+        self.assert_compatible_matrix(matrix);
+        let mut workspace = Vec::new();
+        for element_index in 0..self.num_elements() {
+            let n = self.element_nodes(element_index).len();
+            workspace.clear();
+            workspace.resize(n * n, T::zero());
+            element_matrix(
+                element_index,
+                DMatrixViewMut::from_slice(&mut workspace, n, n),
+            );
+            self.add_element_matrix_unchecked(
+                matrix,
+                element_index,
+                DMatrixView::from_slice(&workspace, n, n),
+            );
+        }
+    }
+
+    /// Assembles all elements into `matrix` in parallel.
+    ///
+    /// *Only available if compiled with the feature `rayon`.*
+    ///
+    /// This is the parallel counterpart of [`assemble`](Self::assemble). The colors are
+    /// assembled one after the other, and the elements of each color are assembled in parallel.
+    /// Since elements of the same color share no nodes, they write to disjoint rows of `matrix`.
+    /// The result may differ from the result of serial assembly by rounding errors, since the
+    /// contributions to each entry are summed in a different order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix does not have the pattern that the assembler was constructed with.
+    #[cfg(feature = "rayon")]
+    pub fn par_assemble<T, F>(&self, matrix: &mut CsrMatrix<T>, element_matrix: F)
+    where
+        T: Scalar + ClosedAdd + Zero + Send + Sync,
+        F: Fn(usize, DMatrixViewMut<'_, T>) + Sync,
+    {
+        self.assert_compatible_matrix(matrix);
+        for color in &self.colors {
+            // Hand out the rows of the matrix to the elements of this color
+            let (row_offsets, _, mut values) = matrix.csr_data_mut();
+            let mut rows = Vec::with_capacity(self.pattern.major_dim());
+            for i in 0..self.pattern.major_dim() {
+                let (row, remaining) =
+                    take(&mut values).split_at_mut(row_offsets[i + 1] - row_offsets[i]);
+                rows.push(Some(row));
+                values = remaining;
+            }
+
+            let tasks: Vec<_> = color
+                .iter()
+                .map(|&element_index| {
+                    let nodes = self.element_nodes(element_index);
+                    // Repeated nodes of the same element share a row
+                    let mut element_rows = Vec::with_capacity(nodes.len());
+                    let mut row_slots = Vec::with_capacity(nodes.len());
+                    for (a, &i) in nodes.iter().enumerate() {
+                        match rows[i].take() {
+                            Some(row) => {
+                                row_slots.push(element_rows.len());
+                                element_rows.push(row);
+                            }
+                            None => {
+                                let b = nodes[..a]
+                                    .iter()
+                                    .position(|&j| j == i)
+                                    .expect("Internal error: Elements of a color share a node");
+                                row_slots.push(row_slots[b]);
+                            }
+                        }
+                    }
+                    (element_index, element_rows, row_slots)
+                })
+                .collect();
+
+            tasks.into_par_iter().for_each_init(
+                Vec::new,
+                |workspace, (element_index, mut element_rows, row_slots)| {
+                    let n = row_slots.len();
+                    workspace.clear();
+                    workspace.resize(n * n, T::zero());
+                    element_matrix(element_index, DMatrixViewMut::from_slice(workspace, n, n));
+                    let element_matrix = DMatrixView::from_slice(workspace, n, n);
+                    let mut positions = self.element_positions(element_index).iter();
+                    for (a, &slot) in row_slots.iter().enumerate() {
+                        let row_values = &mut element_rows[slot];
+                        for (b, &position) in (0..n).zip(&mut positions) {
+                            row_values[position] += element_matrix[(a, b)].clone();
+                        }
+                    }
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! An implementation of the BSR (block compressed sparse row) sparse matrix format.
+//!
+//! This is the module-level documentation. See [`BsrMatrix`] for the main documentation of the
+//! BSR implementation.
+
+use crate::pattern::{SparsityPattern, SparsityPatternFormatError};
+use crate::{SparseFormatError, SparseFormatErrorKind};
+
+use nalgebra::{SMatrix, Scalar};
+
+use std::fmt;
+
+/// A BSR representation of a sparse matrix.
+///
+/// The Block Compressed Sparse Row (BSR) format stores a sparse matrix as a sparse arrangement
+/// of dense `B x B` blocks. Matrices arising from discretizations with several unknowns per
+/// node, such as elasticity or multiphysics problems, naturally have this structure. Compared
+/// to [`CsrMatrix`](crate::csr::CsrMatrix), BSR only stores a single column index per block
+/// instead of one per entry, and the dense blocks are amenable to vectorization.
+///
+/// The block size `B` is a compile-time constant.
+///
+/// # Usage
+///
+/// ```
+/// use nalgebra_sparse::bsr::BsrMatrix;
+/// use nalgebra_sparse::csr::CsrMatrix;
+/// use nalgebra_sparse::convert::serial::{convert_bsr_csr, convert_csr_bsr};
+/// use nalgebra::{DMatrix, Matrix2};
+/// use matrixcompare::assert_matrix_eq;
+///
+/// // A 4x6 matrix consisting of 2x3 blocks of size 2x2, of which three are non-zero
+/// let block_row_offsets = vec![0, 2, 3];
+/// let block_col_indices = vec![0, 2, 1];
+/// let blocks = vec![
+///     Matrix2::new(1.0, 2.0,
+///                  3.0, 4.0),
+///     Matrix2::new(5.0, 0.0,
+///                  0.0, 6.0),
+///     Matrix2::new(7.0, 8.0,
+///                  0.0, 9.0),
+/// ];
+/// let bsr = BsrMatrix::try_from_bsr_data(2, 3, block_row_offsets, block_col_indices, blocks)
+///     .expect("BSR data must conform to format specifications");
+/// assert_eq!(bsr.nrows(), 4);
+/// assert_eq!(bsr.ncols(), 6);
+///
+/// let dense = DMatrix::from_row_slice(4, 6, &[
+///     1.0, 2.0, 0.0, 0.0, 5.0, 0.0,
+///     3.0, 4.0, 0.0, 0.0, 0.0, 6.0,
+///     0.0, 0.0, 7.0, 8.0, 0.0, 0.0,
+///     0.0, 0.0, 0.0, 9.0, 0.0, 0.0,
+/// ]);
+/// assert_matrix_eq!(bsr, dense);
+///
+/// // BSR matrices can be converted to and from CSR
+/// let csr: CsrMatrix<f64> = convert_bsr_csr(&bsr);
+/// let bsr2: BsrMatrix<f64, 2> = convert_csr_bsr(&csr);
+/// assert_eq!(bsr, bsr2);
+/// ```
+///
+/// # Format
+///
+/// An `m x n` sparse matrix with `nnzb` non-zero blocks of size `B x B` in BSR format is an
+/// `(m / B) x (n / B)` block matrix represented by the following three arrays:
+///
+/// - `block_row_offsets`, an array of integers with length `m / B + 1`.
+/// - `block_col_indices`, an array of integers with length `nnzb`.
+/// - `blocks`, an array of `B x B` matrices with length `nnzb`.
+///
+/// These arrays have the same meaning and obey the same invariants as the corresponding arrays
+/// of the [CSR format](crate::csr::CsrMatrix), except that indices and offsets refer to blocks
+/// rather than individual entries. In particular, the block column indices in each block row
+/// must be sorted and unique. The block pattern is represented by a [`SparsityPattern`].
+///
+/// Every entry of a stored block is considered to be explicitly stored, even if it is zero.
+/// Consequently, the number of explicitly stored entries is `nnzb * B * B`.
+#[derive(Clone)]
+pub struct BsrMatrix<T, const B: usize> {
+    // Block rows are major, block columns are minor in the sparsity pattern
+    pattern: SparsityPattern,
+    blocks: Vec<SMatrix<T, B, B>>,
+}
+
+impl<T, const B: usize> BsrMatrix<T, B> {
+    /// Create a zero BSR matrix with `block_rows x block_cols` blocks and no explicitly stored
+    /// blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size `B` is zero.
+    pub fn zeros(block_rows: usize, block_cols: usize) -> Self {
+        assert!(B > 0, "Block size must be positive.");
+        Self {
+            pattern: SparsityPattern::zeros(block_rows, block_cols),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Try to construct a BSR matrix from raw BSR data.
+    ///
+    /// The dimensions `block_rows` and `block_cols` are given in blocks, so that the resulting
+    /// matrix has `block_rows * B` rows and `block_cols * B` columns.
+    ///
+    /// It is assumed that each block row contains unique and sorted block column indices that
+    /// are in bounds with respect to the number of block columns in the matrix. If this is not
+    /// the case, an error is returned to indicate the failure.
+    ///
+    /// An error is returned if the data given does not conform to the BSR storage format.
+    /// See the documentation for [BsrMatrix](struct.BsrMatrix.html) for more information.
+    pub fn try_from_bsr_data(
+        block_rows: usize,
+        block_cols: usize,
+        block_row_offsets: Vec<usize>,
+        block_col_indices: Vec<usize>,
+        blocks: Vec<SMatrix<T, B, B>>,
+    ) -> Result<Self, SparseFormatError> {
+        let pattern = SparsityPattern::try_from_offsets_and_indices(
+            block_rows,
+            block_cols,
+            block_row_offsets,
+            block_col_indices,
+        )
+        .map_err(pattern_format_error_to_bsr_error)?;
+        Self::try_from_pattern_and_blocks(pattern, blocks)
+    }
+
+    /// Try to construct a BSR matrix from a block sparsity pattern and associated blocks.
+    ///
+    /// Returns an error if the number of blocks does not match the number of minor indices
+    /// in the pattern, or if the block size `B` is zero.
+    pub fn try_from_pattern_and_blocks(
+        pattern: SparsityPattern,
+        blocks: Vec<SMatrix<T, B, B>>,
+    ) -> Result<Self, SparseFormatError> {
+        if B == 0 {
+            Err(SparseFormatError::from_kind_and_msg(
+                SparseFormatErrorKind::InvalidStructure,
+                "Block size must be positive",
+            ))
+        } else if pattern.nnz() == blocks.len() {
+            Ok(Self { pattern, blocks })
+        } else {
+            Err(SparseFormatError::from_kind_and_msg(
+                SparseFormatErrorKind::InvalidStructure,
+                "Number of blocks and block column indices must be the same",
+            ))
+        }
+    }
+
+    /// The number of rows in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn nrows(&self) -> usize {
+        self.pattern.major_dim() * B
+    }
+
+    /// The number of columns in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn ncols(&self) -> usize {
+        self.pattern.minor_dim() * B
+    }
+
+    /// The number of block rows in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn block_rows(&self) -> usize {
+        self.pattern.major_dim()
+    }
+
+    /// The number of block columns in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn block_cols(&self) -> usize {
+        self.pattern.minor_dim()
+    }
+
+    /// The size `B` of the (square) blocks.
+    #[inline]
+    #[must_use]
+    pub fn block_size(&self) -> usize {
+        B
+    }
+
+    /// The number of explicitly stored blocks in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn nnz_blocks(&self) -> usize {
+        self.pattern.nnz()
+    }
+
+    /// The number of explicitly stored entries in the matrix.
+    ///
+    /// This is the number of stored blocks times `B * B`, since every entry of a stored block
+    /// is explicitly stored, even if it is zero.
+    #[inline]
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.pattern.nnz() * B * B
+    }
+
+    /// The block row offsets defining part of the BSR format.
+    #[inline]
+    #[must_use]
+    pub fn block_row_offsets(&self) -> &[usize] {
+        self.pattern.major_offsets()
+    }
+
+    /// The block column indices defining part of the BSR format.
+    #[inline]
+    #[must_use]
+    pub fn block_col_indices(&self) -> &[usize] {
+        self.pattern.minor_indices()
+    }
+
+    /// The stored blocks, in the order given by the block column indices.
+    #[inline]
+    #[must_use]
+    pub fn blocks(&self) -> &[SMatrix<T, B, B>] {
+        &self.blocks
+    }
+
+    /// Mutable access to the stored blocks.
+    #[inline]
+    pub fn blocks_mut(&mut self) -> &mut [SMatrix<T, B, B>] {
+        &mut self.blocks
+    }
+
+    /// The block sparsity pattern of the matrix.
+    #[must_use]
+    pub fn pattern(&self) -> &SparsityPattern {
+        &self.pattern
+    }
+
+    /// Returns the block sparsity pattern and blocks associated with this matrix.
+    pub fn into_pattern_and_blocks(self) -> (SparsityPattern, Vec<SMatrix<T, B, B>>) {
+        (self.pattern, self.blocks)
+    }
+
+    /// Disassembles the BSR matrix into its underlying offset, index and block arrays.
+    ///
+    /// If the matrix contains the sole reference to the sparsity pattern,
+    /// then the data is returned as-is. Otherwise, the sparsity pattern is cloned.
+    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>, Vec<SMatrix<T, B, B>>) {
+        let (offsets, indices) = self.pattern.disassemble();
+        (offsets, indices, self.blocks)
+    }
+
+    /// An iterator over the stored blocks of the matrix.
+    ///
+    /// The iterator yields triplets `(block_row, block_col, block)` in row-major order.
+    pub fn block_iter(&self) -> impl Iterator<Item = (usize, usize, &SMatrix<T, B, B>)> {
+        self.pattern
+            .entries()
+            .zip(self.blocks.iter())
+            .map(|((bi, bj), block)| (bi, bj, block))
+    }
+
+    /// A mutable iterator over the stored blocks of the matrix.
+    ///
+    /// The iterator yields triplets `(block_row, block_col, block)` in row-major order.
+    pub fn block_iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (usize, usize, &mut SMatrix<T, B, B>)> {
+        self.pattern
+            .entries()
+            .zip(self.blocks.iter_mut())
+            .map(|((bi, bj), block)| (bi, bj, block))
+    }
+
+    /// An iterator over all explicitly stored entries of the matrix.
+    ///
+    /// The iterator yields triplets `(i, j, v)` for every entry of every stored block, including
+    /// entries that are zero. The entries are visited block by block in the order of
+    /// [`block_iter`](Self::block_iter), and in column-major order within each block.
+    pub fn triplet_iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.block_iter().flat_map(|(bi, bj, block)| {
+            block
+                .iter()
+                .enumerate()
+                .map(move |(idx, v)| (bi * B + idx % B, bj * B + idx / B, v))
+        })
+    }
+
+    /// Returns a reference to the block at the given block row and block column, if it is
+    /// explicitly stored.
+    ///
+    /// Returns `None` if the indices are out of bounds or the block is not stored.
+    #[must_use]
+    pub fn get_block(&self, block_row: usize, block_col: usize) -> Option<&SMatrix<T, B, B>> {
+        self.block_position(block_row, block_col)
+            .map(|idx| &self.blocks[idx])
+    }
+
+    /// Returns a mutable reference to the block at the given block row and block column, if it
+    /// is explicitly stored.
+    ///
+    /// Returns `None` if the indices are out of bounds or the block is not stored.
+    pub fn get_block_mut(
+        &mut self,
+        block_row: usize,
+        block_col: usize,
+    ) -> Option<&mut SMatrix<T, B, B>> {
+        self.block_position(block_row, block_col)
+            .map(move |idx| &mut self.blocks[idx])
+    }
+
+    fn block_position(&self, block_row: usize, block_col: usize) -> Option<usize> {
+        let offset = *self.pattern.major_offsets().get(block_row)?;
+        let lane = self.pattern.get_lane(block_row)?;
+        lane.binary_search(&block_col)
+            .ok()
+            .map(|local_idx| offset + local_idx)
+    }
+}
+
+impl<T: Scalar, const B: usize> BsrMatrix<T, B> {
+    /// Returns the transpose of this matrix.
+    ///
+    /// The block pattern is transposed and every block is transposed individually.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let transposed_pattern = self.pattern.transpose();
+        let mut positions = transposed_pattern.major_offsets().to_vec();
+        let mut blocks: Vec<Option<SMatrix<T, B, B>>> = vec![None; self.blocks.len()];
+        for (_, bj, block) in self.block_iter() {
+            blocks[positions[bj]] = Some(block.transpose());
+            positions[bj] += 1;
+        }
+        let blocks = blocks
+            .into_iter()
+            .map(|block| block.expect("Internal error: Every block must be visited"))
+            .collect();
+        Self {
+            pattern: transposed_pattern,
+            blocks,
+        }
+    }
+}
+
+// The derived implementations would not have the bounds required by `SMatrix`
+impl<T: Scalar, const B: usize> fmt::Debug for BsrMatrix<T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BsrMatrix")
+            .field("pattern", &self.pattern)
+            .field("blocks", &self.blocks)
+            .finish()
+    }
+}
+
+impl<T: Scalar, const B: usize> PartialEq for BsrMatrix<T, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.blocks == other.blocks
+    }
+}
+
+impl<T: Scalar + Eq, const B: usize> Eq for BsrMatrix<T, B> {}
+
+fn pattern_format_error_to_bsr_error(err: SparsityPatternFormatError) -> SparseFormatError {
+    use SparseFormatError as E;
+    use SparseFormatErrorKind as K;
+    use SparsityPatternFormatError::DuplicateEntry as PatternDuplicateEntry;
+    use SparsityPatternFormatError::*;
+
+    match err {
+        InvalidOffsetArrayLength => E::from_kind_and_msg(
+            K::InvalidStructure,
+            "Length of block row offset array is not equal to block_rows + 1.",
+        ),
+        InvalidOffsetFirstLast => E::from_kind_and_msg(
+            K::InvalidStructure,
+            "First or last block row offset is inconsistent with format specification.",
+        ),
+        NonmonotonicOffsets => E::from_kind_and_msg(
+            K::InvalidStructure,
+            "Block row offsets are not monotonically increasing.",
+        ),
+        NonmonotonicMinorIndices => E::from_kind_and_msg(
+            K::InvalidStructure,
+            "Block column indices are not monotonically increasing (sorted) within each block row.",
+        ),
+        MinorIndexOutOfBounds => E::from_kind_and_msg(
+            K::IndexOutOfBounds,
+            "Block column indices are out of bounds.",
+        ),
+        PatternDuplicateEntry => {
+            E::from_kind_and_msg(K::DuplicateEntry, "Matrix data contains duplicate blocks.")
+        }
+        IndexOverflow => E::from_kind_and_msg(
+            K::IndexOutOfBounds,
+            "Block dimensions can not be represented by the index type.",
+        ),
+    }
+}
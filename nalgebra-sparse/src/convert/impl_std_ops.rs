@@ -1,10 +1,22 @@
+use crate::bsr::BsrMatrix;
 use crate::convert::serial::*;
 use crate::coo::CooMatrix;
 use crate::csc::CscMatrix;
 use crate::csr::CsrMatrix;
+use crate::dia::DiaMatrix;
+use crate::ell::EllMatrix;
+use crate::pattern::SparseIndex;
+use crate::sell::SellMatrix;
+use crate::vector::SparseVector;
+#[cfg(feature = "legacy-sparse")]
+use crate::SparseFormatError;
+#[cfg(feature = "legacy-sparse")]
+use nalgebra::sparse::{CsMatrix as LegacyCsMatrix, CsVector as LegacyCsVector};
 use nalgebra::storage::RawStorage;
-use nalgebra::{ClosedAdd, DMatrix, Dim, Matrix, Scalar};
+use nalgebra::{ClosedAdd, DMatrix, DVector, Dim, Matrix, Scalar, Vector, U1};
 use num_traits::Zero;
+#[cfg(feature = "legacy-sparse")]
+use std::convert::TryFrom;
 
 impl<'a, T, R, C, S> From<&'a Matrix<T, R, C, S>> for CooMatrix<T>
 where
@@ -18,29 +30,32 @@ where
     }
 }
 
-impl<'a, T> From<&'a CooMatrix<T>> for DMatrix<T>
+impl<'a, T, I> From<&'a CooMatrix<T, I>> for DMatrix<T>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(coo: &'a CooMatrix<T>) -> Self {
+    fn from(coo: &'a CooMatrix<T, I>) -> Self {
         convert_coo_dense(coo)
     }
 }
 
-impl<'a, T> From<&'a CooMatrix<T>> for CsrMatrix<T>
+impl<'a, T, I> From<&'a CooMatrix<T, I>> for CsrMatrix<T, I>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CooMatrix<T>) -> Self {
+    fn from(matrix: &'a CooMatrix<T, I>) -> Self {
         convert_coo_csr(matrix)
     }
 }
 
-impl<'a, T> From<&'a CsrMatrix<T>> for CooMatrix<T>
+impl<'a, T, I> From<&'a CsrMatrix<T, I>> for CooMatrix<T, I>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CsrMatrix<T>) -> Self {
+    fn from(matrix: &'a CsrMatrix<T, I>) -> Self {
         convert_csr_coo(matrix)
     }
 }
@@ -57,29 +72,32 @@ where
     }
 }
 
-impl<'a, T> From<&'a CsrMatrix<T>> for DMatrix<T>
+impl<'a, T, I> From<&'a CsrMatrix<T, I>> for DMatrix<T>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CsrMatrix<T>) -> Self {
+    fn from(matrix: &'a CsrMatrix<T, I>) -> Self {
         convert_csr_dense(matrix)
     }
 }
 
-impl<'a, T> From<&'a CooMatrix<T>> for CscMatrix<T>
+impl<'a, T, I> From<&'a CooMatrix<T, I>> for CscMatrix<T, I>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CooMatrix<T>) -> Self {
+    fn from(matrix: &'a CooMatrix<T, I>) -> Self {
         convert_coo_csc(matrix)
     }
 }
 
-impl<'a, T> From<&'a CscMatrix<T>> for CooMatrix<T>
+impl<'a, T, I> From<&'a CscMatrix<T, I>> for CooMatrix<T, I>
 where
     T: Scalar + Zero,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CscMatrix<T>) -> Self {
+    fn from(matrix: &'a CscMatrix<T, I>) -> Self {
         convert_csc_coo(matrix)
     }
 }
@@ -96,29 +114,205 @@ where
     }
 }
 
-impl<'a, T> From<&'a CscMatrix<T>> for DMatrix<T>
+impl<'a, T, I> From<&'a CscMatrix<T, I>> for DMatrix<T>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CscMatrix<T>) -> Self {
+    fn from(matrix: &'a CscMatrix<T, I>) -> Self {
         convert_csc_dense(matrix)
     }
 }
 
-impl<'a, T> From<&'a CscMatrix<T>> for CsrMatrix<T>
+impl<'a, T, I> From<&'a CscMatrix<T, I>> for CsrMatrix<T, I>
 where
     T: Scalar,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CscMatrix<T>) -> Self {
+    fn from(matrix: &'a CscMatrix<T, I>) -> Self {
         convert_csc_csr(matrix)
     }
 }
 
-impl<'a, T> From<&'a CsrMatrix<T>> for CscMatrix<T>
+impl<'a, T, I> From<&'a CsrMatrix<T, I>> for CscMatrix<T, I>
 where
     T: Scalar,
+    I: SparseIndex,
 {
-    fn from(matrix: &'a CsrMatrix<T>) -> Self {
+    fn from(matrix: &'a CsrMatrix<T, I>) -> Self {
         convert_csr_csc(matrix)
     }
 }
+
+impl<'a, T, const B: usize> From<&'a BsrMatrix<T, B>> for CsrMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a BsrMatrix<T, B>) -> Self {
+        convert_bsr_csr(matrix)
+    }
+}
+
+impl<'a, T, const B: usize> From<&'a BsrMatrix<T, B>> for CooMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a BsrMatrix<T, B>) -> Self {
+        convert_bsr_coo(matrix)
+    }
+}
+
+impl<'a, T> From<&'a CsrMatrix<T>> for DiaMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    fn from(matrix: &'a CsrMatrix<T>) -> Self {
+        convert_csr_dia(matrix)
+    }
+}
+
+impl<'a, T> From<&'a DiaMatrix<T>> for CsrMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a DiaMatrix<T>) -> Self {
+        convert_dia_csr(matrix)
+    }
+}
+
+impl<'a, T> From<&'a CooMatrix<T>> for DiaMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    fn from(matrix: &'a CooMatrix<T>) -> Self {
+        convert_coo_dia(matrix)
+    }
+}
+
+impl<'a, T> From<&'a DiaMatrix<T>> for CooMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a DiaMatrix<T>) -> Self {
+        convert_dia_coo(matrix)
+    }
+}
+
+impl<'a, T> From<&'a CsrMatrix<T>> for EllMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    fn from(matrix: &'a CsrMatrix<T>) -> Self {
+        convert_csr_ell(matrix)
+    }
+}
+
+impl<'a, T> From<&'a EllMatrix<T>> for CsrMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a EllMatrix<T>) -> Self {
+        convert_ell_csr(matrix)
+    }
+}
+
+impl<'a, T> From<&'a CooMatrix<T>> for EllMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    fn from(matrix: &'a CooMatrix<T>) -> Self {
+        convert_coo_ell(matrix)
+    }
+}
+
+impl<'a, T> From<&'a EllMatrix<T>> for CooMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a EllMatrix<T>) -> Self {
+        convert_ell_coo(matrix)
+    }
+}
+
+impl<'a, T> From<&'a SellMatrix<T>> for CsrMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a SellMatrix<T>) -> Self {
+        convert_sell_csr(matrix)
+    }
+}
+
+impl<'a, T> From<&'a SellMatrix<T>> for CooMatrix<T>
+where
+    T: Scalar,
+{
+    fn from(matrix: &'a SellMatrix<T>) -> Self {
+        convert_sell_coo(matrix)
+    }
+}
+
+impl<'a, T, R, S> From<&'a Vector<T, R, S>> for SparseVector<T>
+where
+    T: Scalar + Zero,
+    R: Dim,
+    S: RawStorage<T, R, U1>,
+{
+    fn from(vector: &'a Vector<T, R, S>) -> Self {
+        convert_dense_sparse_vector(vector)
+    }
+}
+
+impl<'a, T> From<&'a SparseVector<T>> for DVector<T>
+where
+    T: Scalar + Zero,
+{
+    fn from(vector: &'a SparseVector<T>) -> Self {
+        convert_sparse_vector_dense(vector)
+    }
+}
+
+#[cfg(feature = "legacy-sparse")]
+impl<'a, T> TryFrom<&'a LegacyCsMatrix<T>> for CscMatrix<T>
+where
+    T: Scalar,
+{
+    type Error = SparseFormatError;
+
+    fn try_from(matrix: &'a LegacyCsMatrix<T>) -> Result<Self, Self::Error> {
+        convert_legacy_csc(matrix)
+    }
+}
+
+#[cfg(feature = "legacy-sparse")]
+impl<'a, T, I> From<&'a CscMatrix<T, I>> for LegacyCsMatrix<T>
+where
+    T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
+{
+    fn from(matrix: &'a CscMatrix<T, I>) -> Self {
+        convert_csc_legacy(matrix)
+    }
+}
+
+#[cfg(feature = "legacy-sparse")]
+impl<'a, T> TryFrom<&'a LegacyCsVector<T>> for SparseVector<T>
+where
+    T: Scalar,
+{
+    type Error = SparseFormatError;
+
+    fn try_from(vector: &'a LegacyCsVector<T>) -> Result<Self, Self::Error> {
+        convert_legacy_sparse_vector(vector)
+    }
+}
+
+#[cfg(feature = "legacy-sparse")]
+impl<'a, T> From<&'a SparseVector<T>> for LegacyCsVector<T>
+where
+    T: Scalar + Zero + ClosedAdd,
+{
+    fn from(vector: &'a SparseVector<T>) -> Self {
+        convert_sparse_vector_legacy(vector)
+    }
+}
@@ -30,6 +30,40 @@
 //! let _ = CscMatrix::from(&coo);
 //! ```
 //!
+//! Conversions involving the [BSR](crate::bsr::BsrMatrix), [DIA](crate::dia::DiaMatrix),
+//! [ELL](crate::ell::EllMatrix) and [SELL-C-σ](crate::sell::SellMatrix) formats go through CSR
+//! or COO. Conversions that need additional parameters, such as the block size of a BSR matrix
+//! or the slice height of a SELL-C-σ matrix, are only available as functions in the [`serial`]
+//! module.
+//!
+//! ```
+//! use nalgebra_sparse::{csr::CsrMatrix, dia::DiaMatrix, ell::EllMatrix};
+//! use nalgebra_sparse::bsr::BsrMatrix;
+//! use nalgebra_sparse::convert::serial::{convert_csr_bsr, convert_csr_sell};
+//! use nalgebra::DMatrix;
+//!
+//! let csr = CsrMatrix::from(&DMatrix::<f64>::identity(6, 6));
+//!
+//! // CSR <-> DIA, CSR <-> ELL
+//! let _ = CsrMatrix::from(&DiaMatrix::from(&csr));
+//! let _ = CsrMatrix::from(&EllMatrix::from(&csr));
+//!
+//! // CSR -> BSR with 3x3 blocks, CSR -> SELL-C-σ with C = 2 and σ = 4
+//! let bsr: BsrMatrix<f64, 3> = convert_csr_bsr(&csr);
+//! let sell = convert_csr_sell(&csr, 2, 4);
+//! let _ = CsrMatrix::from(&bsr);
+//! let _ = CsrMatrix::from(&sell);
+//! ```
+//!
+//! [Sparse vectors](crate::vector::SparseVector) can be converted to and from dense vectors in
+//! the same way.
+//!
+//! When the `legacy-sparse` feature is enabled, the matrices and vectors of the legacy
+//! `nalgebra::sparse` module can be converted to and from [CSC](crate::csc::CscMatrix) matrices
+//! and sparse vectors, which eases the migration to this crate. Conversions from the legacy
+//! types are implemented with `TryFrom`, since the legacy types do not enforce the invariants
+//! of the CSC format.
+//!
 //! The routines available here are able to provide more specialized APIs, giving
 //! more control over the conversion process. The routines are organized by backends.
 //! Currently, only the [`serial`] backend is available.
@@ -7,14 +7,26 @@ use std::ops::Add;
 
 use num_traits::Zero;
 
+#[cfg(feature = "legacy-sparse")]
+use nalgebra::sparse::{CsMatrix as LegacyCsMatrix, CsVector as LegacyCsVector};
 use nalgebra::storage::RawStorage;
-use nalgebra::{ClosedAdd, DMatrix, Dim, Matrix, Scalar};
+#[cfg(feature = "legacy-sparse")]
+use nalgebra::Dyn;
+use nalgebra::{ClosedAdd, DMatrix, DVector, Dim, Matrix, SMatrix, Scalar, Vector, U1};
 
+use crate::bsr::BsrMatrix;
 use crate::coo::CooMatrix;
 use crate::cs;
 use crate::csc::CscMatrix;
 use crate::csr::CsrMatrix;
+use crate::dia::{diagonal_len, DiaMatrix};
+use crate::ell::EllMatrix;
+use crate::pattern::SparseIndex;
+use crate::sell::SellMatrix;
 use crate::utils::{apply_permutation, compute_sort_permutation};
+use crate::vector::SparseVector;
+#[cfg(feature = "legacy-sparse")]
+use crate::SparseFormatError;
 
 /// Converts a dense matrix to [`CooMatrix`].
 pub fn convert_dense_coo<T, R, C, S>(dense: &Matrix<T, R, C, S>) -> CooMatrix<T>
@@ -39,9 +51,10 @@ where
 }
 
 /// Converts a [`CooMatrix`] to a dense matrix.
-pub fn convert_coo_dense<T>(coo: &CooMatrix<T>) -> DMatrix<T>
+pub fn convert_coo_dense<T, I>(coo: &CooMatrix<T, I>) -> DMatrix<T>
 where
     T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
 {
     let mut output = DMatrix::repeat(coo.nrows(), coo.ncols(), T::zero());
     for (i, j, v) in coo.triplet_iter() {
@@ -51,9 +64,10 @@ where
 }
 
 /// Converts a [`CooMatrix`] to a [`CsrMatrix`].
-pub fn convert_coo_csr<T>(coo: &CooMatrix<T>) -> CsrMatrix<T>
+pub fn convert_coo_csr<T, I>(coo: &CooMatrix<T, I>) -> CsrMatrix<T, I>
 where
     T: Scalar + Zero,
+    I: SparseIndex,
 {
     let (offsets, indices, values) = convert_coo_cs(
         coo.nrows(),
@@ -64,23 +78,31 @@ where
 
     // TODO: Avoid "try_from" since it validates the data? (requires unsafe, should benchmark
     // to see if it can be justified for performance reasons)
-    CsrMatrix::try_from_csr_data(coo.nrows(), coo.ncols(), offsets, indices, values)
+    CsrMatrix::try_from_csr_data_generic(coo.nrows(), coo.ncols(), offsets, indices, values)
         .expect("Internal error: Invalid CSR data during COO->CSR conversion")
 }
 
 /// Converts a [`CsrMatrix`] to a [`CooMatrix`].
-pub fn convert_csr_coo<T: Scalar>(csr: &CsrMatrix<T>) -> CooMatrix<T> {
-    let mut result = CooMatrix::new(csr.nrows(), csr.ncols());
-    for (i, j, v) in csr.triplet_iter() {
-        result.push(i, j, v.clone());
-    }
-    result
+pub fn convert_csr_coo<T, I>(csr: &CsrMatrix<T, I>) -> CooMatrix<T, I>
+where
+    T: Scalar,
+    I: SparseIndex,
+{
+    let (row_indices, col_indices) = csr
+        .pattern()
+        .entries()
+        .map(|(i, j)| (I::from_index(i), I::from_index(j)))
+        .unzip();
+    let values = csr.values().to_vec();
+    CooMatrix::try_from_triplets_generic(csr.nrows(), csr.ncols(), row_indices, col_indices, values)
+        .expect("Internal error: Invalid COO data during CSR->COO conversion")
 }
 
 /// Converts a [`CsrMatrix`] to a dense matrix.
-pub fn convert_csr_dense<T>(csr: &CsrMatrix<T>) -> DMatrix<T>
+pub fn convert_csr_dense<T, I>(csr: &CsrMatrix<T, I>) -> DMatrix<T>
 where
     T: Scalar + ClosedAdd + Zero,
+    I: SparseIndex,
 {
     let mut output = DMatrix::zeros(csr.nrows(), csr.ncols());
 
@@ -125,9 +147,10 @@ where
 }
 
 /// Converts a [`CooMatrix`] to a [`CscMatrix`].
-pub fn convert_coo_csc<T>(coo: &CooMatrix<T>) -> CscMatrix<T>
+pub fn convert_coo_csc<T, I>(coo: &CooMatrix<T, I>) -> CscMatrix<T, I>
 where
     T: Scalar + Zero,
+    I: SparseIndex,
 {
     let (offsets, indices, values) = convert_coo_cs(
         coo.ncols(),
@@ -138,26 +161,31 @@ where
 
     // TODO: Avoid "try_from" since it validates the data? (requires unsafe, should benchmark
     // to see if it can be justified for performance reasons)
-    CscMatrix::try_from_csc_data(coo.nrows(), coo.ncols(), offsets, indices, values)
+    CscMatrix::try_from_csc_data_generic(coo.nrows(), coo.ncols(), offsets, indices, values)
         .expect("Internal error: Invalid CSC data during COO->CSC conversion")
 }
 
 /// Converts a [`CscMatrix`] to a [`CooMatrix`].
-pub fn convert_csc_coo<T>(csc: &CscMatrix<T>) -> CooMatrix<T>
+pub fn convert_csc_coo<T, I>(csc: &CscMatrix<T, I>) -> CooMatrix<T, I>
 where
     T: Scalar,
+    I: SparseIndex,
 {
-    let mut coo = CooMatrix::new(csc.nrows(), csc.ncols());
-    for (i, j, v) in csc.triplet_iter() {
-        coo.push(i, j, v.clone());
-    }
-    coo
+    let (row_indices, col_indices) = csc
+        .pattern()
+        .entries()
+        .map(|(j, i)| (I::from_index(i), I::from_index(j)))
+        .unzip();
+    let values = csc.values().to_vec();
+    CooMatrix::try_from_triplets_generic(csc.nrows(), csc.ncols(), row_indices, col_indices, values)
+        .expect("Internal error: Invalid COO data during CSC->COO conversion")
 }
 
 /// Converts a [`CscMatrix`] to a dense matrix.
-pub fn convert_csc_dense<T>(csc: &CscMatrix<T>) -> DMatrix<T>
+pub fn convert_csc_dense<T, I>(csc: &CscMatrix<T, I>) -> DMatrix<T>
 where
     T: Scalar + ClosedAdd + Zero,
+    I: SparseIndex,
 {
     let mut output = DMatrix::zeros(csc.nrows(), csc.ncols());
 
@@ -199,9 +227,10 @@ where
 }
 
 /// Converts a [`CsrMatrix`] to a [`CscMatrix`].
-pub fn convert_csr_csc<T>(csr: &CsrMatrix<T>) -> CscMatrix<T>
+pub fn convert_csr_csc<T, I>(csr: &CsrMatrix<T, I>) -> CscMatrix<T, I>
 where
     T: Scalar,
+    I: SparseIndex,
 {
     let (offsets, indices, values) = cs::transpose_cs(
         csr.nrows(),
@@ -212,14 +241,15 @@ where
     );
 
     // TODO: Avoid data validity check?
-    CscMatrix::try_from_csc_data(csr.nrows(), csr.ncols(), offsets, indices, values)
+    CscMatrix::try_from_csc_data_generic(csr.nrows(), csr.ncols(), offsets, indices, values)
         .expect("Internal error: Invalid CSC data during CSR->CSC conversion")
 }
 
 /// Converts a [`CscMatrix`] to a [`CsrMatrix`].
-pub fn convert_csc_csr<T>(csc: &CscMatrix<T>) -> CsrMatrix<T>
+pub fn convert_csc_csr<T, I>(csc: &CscMatrix<T, I>) -> CsrMatrix<T, I>
 where
     T: Scalar,
+    I: SparseIndex,
 {
     let (offsets, indices, values) = cs::transpose_cs(
         csc.ncols(),
@@ -230,18 +260,419 @@ where
     );
 
     // TODO: Avoid data validity check?
-    CsrMatrix::try_from_csr_data(csc.nrows(), csc.ncols(), offsets, indices, values)
+    CsrMatrix::try_from_csr_data_generic(csc.nrows(), csc.ncols(), offsets, indices, values)
         .expect("Internal error: Invalid CSR data during CSC->CSR conversion")
 }
 
-fn convert_coo_cs<T>(
+/// Converts a [`CsrMatrix`] to a [`BsrMatrix`] with block size `B`.
+///
+/// Every block that contains at least one explicitly stored entry of the CSR matrix is stored,
+/// and the remaining entries of such blocks are set to zero.
+///
+/// # Panics
+///
+/// Panics if `B` is zero, or if the dimensions of the matrix are not multiples of `B`.
+pub fn convert_csr_bsr<T, const B: usize>(csr: &CsrMatrix<T>) -> BsrMatrix<T, B>
+where
+    T: Scalar + Zero,
+{
+    assert!(B > 0, "Block size must be positive.");
+    assert_eq!(
+        (csr.nrows() % B, csr.ncols() % B),
+        (0, 0),
+        "Matrix dimensions must be multiples of the block size."
+    );
+    let block_rows = csr.nrows() / B;
+    let block_cols = csr.ncols() / B;
+
+    let mut block_row_offsets = Vec::with_capacity(block_rows + 1);
+    let mut block_col_indices = Vec::new();
+    let mut blocks = Vec::new();
+    block_row_offsets.push(0);
+    for bi in 0..block_rows {
+        let rows = bi * B..(bi + 1) * B;
+        let mut lane: Vec<usize> = rows
+            .clone()
+            .flat_map(|i| csr.pattern().lane(i))
+            .map(|&j| j / B)
+            .collect();
+        lane.sort_unstable();
+        lane.dedup();
+
+        let offset = blocks.len();
+        blocks.extend(lane.iter().map(|_| SMatrix::<T, B, B>::zeros()));
+        for i in rows {
+            let row = csr.row(i);
+            for (&j, v) in row.col_indices().iter().zip(row.values()) {
+                let local_idx = lane
+                    .binary_search(&(j / B))
+                    .expect("Internal error: Block column must be present");
+                blocks[offset + local_idx][(i % B, j % B)] = v.clone();
+            }
+        }
+        block_col_indices.extend(lane);
+        block_row_offsets.push(block_col_indices.len());
+    }
+
+    BsrMatrix::try_from_bsr_data(
+        block_rows,
+        block_cols,
+        block_row_offsets,
+        block_col_indices,
+        blocks,
+    )
+    .expect("Internal error: Invalid BSR data during CSR->BSR conversion")
+}
+
+/// Converts a [`BsrMatrix`] to a [`CsrMatrix`].
+///
+/// All entries of the stored blocks are explicitly stored in the CSR matrix, including zeros.
+pub fn convert_bsr_csr<T, const B: usize>(bsr: &BsrMatrix<T, B>) -> CsrMatrix<T>
+where
+    T: Scalar,
+{
+    let mut row_offsets = Vec::with_capacity(bsr.nrows() + 1);
+    let mut col_indices = Vec::with_capacity(bsr.nnz());
+    let mut values = Vec::with_capacity(bsr.nnz());
+    row_offsets.push(0);
+    for bi in 0..bsr.block_rows() {
+        let range = bsr.block_row_offsets()[bi]..bsr.block_row_offsets()[bi + 1];
+        let lane_indices = &bsr.block_col_indices()[range.clone()];
+        let lane_blocks = &bsr.blocks()[range];
+        for r in 0..B {
+            for (&bj, block) in lane_indices.iter().zip(lane_blocks) {
+                for c in 0..B {
+                    col_indices.push(bj * B + c);
+                    values.push(block[(r, c)].clone());
+                }
+            }
+            row_offsets.push(col_indices.len());
+        }
+    }
+
+    CsrMatrix::try_from_csr_data(bsr.nrows(), bsr.ncols(), row_offsets, col_indices, values)
+        .expect("Internal error: Invalid CSR data during BSR->CSR conversion")
+}
+
+/// Converts a [`CooMatrix`] to a [`BsrMatrix`] with block size `B`.
+///
+/// Duplicate entries are summed.
+///
+/// # Panics
+///
+/// Panics if `B` is zero, or if the dimensions of the matrix are not multiples of `B`.
+pub fn convert_coo_bsr<T, const B: usize>(coo: &CooMatrix<T>) -> BsrMatrix<T, B>
+where
+    T: Scalar + Zero,
+{
+    convert_csr_bsr(&convert_coo_csr(coo))
+}
+
+/// Converts a [`BsrMatrix`] to a [`CooMatrix`].
+///
+/// All entries of the stored blocks are explicitly stored in the COO matrix, including zeros.
+pub fn convert_bsr_coo<T, const B: usize>(bsr: &BsrMatrix<T, B>) -> CooMatrix<T>
+where
+    T: Scalar,
+{
+    let mut coo = CooMatrix::new(bsr.nrows(), bsr.ncols());
+    coo.reserve(bsr.nnz());
+    for (i, j, v) in bsr.triplet_iter() {
+        coo.push(i, j, v.clone());
+    }
+    coo
+}
+
+/// Converts a [`CsrMatrix`] to a [`DiaMatrix`].
+///
+/// Every diagonal that contains at least one explicitly stored entry of the CSR matrix is stored,
+/// and the remaining entries of such diagonals are set to zero.
+pub fn convert_csr_dia<T>(csr: &CsrMatrix<T>) -> DiaMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    let (nrows, ncols) = (csr.nrows(), csr.ncols());
+    let diagonal_of = |i: usize, j: usize| j as isize - i as isize;
+
+    let mut diagonal_offsets: Vec<isize> = csr
+        .triplet_iter()
+        .map(|(i, j, _)| diagonal_of(i, j))
+        .collect();
+    diagonal_offsets.sort_unstable();
+    diagonal_offsets.dedup();
+
+    let mut value_offsets = Vec::with_capacity(diagonal_offsets.len());
+    let mut num_values = 0;
+    for &k in &diagonal_offsets {
+        value_offsets.push(num_values);
+        num_values += diagonal_len(nrows, ncols, k);
+    }
+
+    let mut values = vec![T::zero(); num_values];
+    for (i, j, v) in csr.triplet_iter() {
+        let index = diagonal_offsets
+            .binary_search(&diagonal_of(i, j))
+            .expect("Internal error: Diagonal must be present");
+        // The position of an entry on its diagonal is the smaller of its row and column index
+        values[value_offsets[index] + i.min(j)] = v.clone();
+    }
+
+    DiaMatrix::try_from_diagonals(nrows, ncols, diagonal_offsets, values)
+        .expect("Internal error: Invalid DIA data during CSR->DIA conversion")
+}
+
+/// Converts a [`DiaMatrix`] to a [`CsrMatrix`].
+///
+/// All entries of the stored diagonals are explicitly stored in the CSR matrix, including zeros.
+pub fn convert_dia_csr<T>(dia: &DiaMatrix<T>) -> CsrMatrix<T>
+where
+    T: Scalar,
+{
+    let mut row_offsets = Vec::with_capacity(dia.nrows() + 1);
+    let mut col_indices = Vec::with_capacity(dia.nnz());
+    let mut values = Vec::with_capacity(dia.nnz());
+    row_offsets.push(0);
+    for i in 0..dia.nrows() {
+        // Diagonals are sorted by increasing offset, so the column indices come out sorted
+        for (k, diagonal) in dia.diagonal_iter() {
+            let j = i as isize + k;
+            if j >= 0 && (j as usize) < dia.ncols() {
+                let j = j as usize;
+                col_indices.push(j);
+                values.push(diagonal[i.min(j)].clone());
+            }
+        }
+        row_offsets.push(col_indices.len());
+    }
+
+    CsrMatrix::try_from_csr_data(dia.nrows(), dia.ncols(), row_offsets, col_indices, values)
+        .expect("Internal error: Invalid CSR data during DIA->CSR conversion")
+}
+
+/// Converts a [`CooMatrix`] to a [`DiaMatrix`].
+///
+/// Duplicate entries are summed.
+pub fn convert_coo_dia<T>(coo: &CooMatrix<T>) -> DiaMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    convert_csr_dia(&convert_coo_csr(coo))
+}
+
+/// Converts a [`DiaMatrix`] to a [`CooMatrix`].
+///
+/// All entries of the stored diagonals are explicitly stored in the COO matrix, including zeros.
+pub fn convert_dia_coo<T>(dia: &DiaMatrix<T>) -> CooMatrix<T>
+where
+    T: Scalar,
+{
+    let mut coo = CooMatrix::new(dia.nrows(), dia.ncols());
+    coo.reserve(dia.nnz());
+    for (i, j, v) in dia.triplet_iter() {
+        coo.push(i, j, v.clone());
+    }
+    coo
+}
+
+/// Converts a [`CsrMatrix`] to an [`EllMatrix`].
+///
+/// The width of the ELL matrix is the largest number of explicitly stored entries in any row.
+pub fn convert_csr_ell<T>(csr: &CsrMatrix<T>) -> EllMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    let nrows = csr.nrows();
+    let row_lengths: Vec<usize> = csr.row_iter().map(|row| row.nnz()).collect();
+    let width = row_lengths.iter().copied().max().unwrap_or(0);
+
+    let mut col_indices = vec![0; nrows * width];
+    let mut values = vec![T::zero(); nrows * width];
+    for (i, row) in csr.row_iter().enumerate() {
+        for (k, (&j, v)) in row.col_indices().iter().zip(row.values()).enumerate() {
+            col_indices[k * nrows + i] = j;
+            values[k * nrows + i] = v.clone();
+        }
+    }
+
+    EllMatrix::try_from_ell_data(nrows, csr.ncols(), width, row_lengths, col_indices, values)
+        .expect("Internal error: Invalid ELL data during CSR->ELL conversion")
+}
+
+/// Converts an [`EllMatrix`] to a [`CsrMatrix`].
+pub fn convert_ell_csr<T>(ell: &EllMatrix<T>) -> CsrMatrix<T>
+where
+    T: Scalar,
+{
+    let mut row_offsets = Vec::with_capacity(ell.nrows() + 1);
+    let mut col_indices = Vec::with_capacity(ell.nnz());
+    let mut values = Vec::with_capacity(ell.nnz());
+    row_offsets.push(0);
+    for (i, j, v) in ell.triplet_iter() {
+        // The triplets are visited in row-major order, so we only need to close empty rows
+        while row_offsets.len() <= i {
+            row_offsets.push(col_indices.len());
+        }
+        col_indices.push(j);
+        values.push(v.clone());
+    }
+    while row_offsets.len() <= ell.nrows() {
+        row_offsets.push(col_indices.len());
+    }
+
+    CsrMatrix::try_from_csr_data(ell.nrows(), ell.ncols(), row_offsets, col_indices, values)
+        .expect("Internal error: Invalid CSR data during ELL->CSR conversion")
+}
+
+/// Converts a [`CooMatrix`] to an [`EllMatrix`].
+///
+/// Duplicate entries are summed.
+pub fn convert_coo_ell<T>(coo: &CooMatrix<T>) -> EllMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    convert_csr_ell(&convert_coo_csr(coo))
+}
+
+/// Converts an [`EllMatrix`] to a [`CooMatrix`].
+pub fn convert_ell_coo<T>(ell: &EllMatrix<T>) -> CooMatrix<T>
+where
+    T: Scalar,
+{
+    let mut coo = CooMatrix::new(ell.nrows(), ell.ncols());
+    coo.reserve(ell.nnz());
+    for (i, j, v) in ell.triplet_iter() {
+        coo.push(i, j, v.clone());
+    }
+    coo
+}
+
+/// Converts a [`CsrMatrix`] to a [`SellMatrix`] with slice height `slice_height` (`C`) and
+/// sorting window `sigma` (`σ`).
+///
+/// Within each window of `sigma` consecutive rows, the rows are stably sorted by decreasing
+/// number of explicitly stored entries. Choosing `sigma = 1` disables sorting, and choosing
+/// `slice_height = sigma = csr.nrows()` gives a format equivalent to ELL with sorted rows.
+///
+/// # Panics
+///
+/// Panics if `slice_height` or `sigma` is zero.
+pub fn convert_csr_sell<T>(csr: &CsrMatrix<T>, slice_height: usize, sigma: usize) -> SellMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    assert!(slice_height > 0, "Slice height must be positive.");
+    assert!(sigma > 0, "Sorting window must be positive.");
+    let nrows = csr.nrows();
+
+    let mut row_permutation: Vec<usize> = (0..nrows).collect();
+    for window in row_permutation.chunks_mut(sigma) {
+        window.sort_by_key(|&i| std::cmp::Reverse(csr.row(i).nnz()));
+    }
+    let row_lengths: Vec<usize> = row_permutation.iter().map(|&i| csr.row(i).nnz()).collect();
+
+    let mut slice_offsets = Vec::with_capacity(nrows / slice_height + 2);
+    let mut col_indices = Vec::new();
+    let mut values = Vec::new();
+    slice_offsets.push(0);
+    for (slice_rows, slice_lengths) in row_permutation
+        .chunks(slice_height)
+        .zip(row_lengths.chunks(slice_height))
+    {
+        let height = slice_rows.len();
+        let width = slice_lengths.iter().copied().max().unwrap_or(0);
+        let offset = col_indices.len();
+        col_indices.resize(offset + height * width, 0);
+        values.resize(offset + height * width, T::zero());
+        for (r, &i) in slice_rows.iter().enumerate() {
+            let row = csr.row(i);
+            for (k, (&j, v)) in row.col_indices().iter().zip(row.values()).enumerate() {
+                col_indices[offset + k * height + r] = j;
+                values[offset + k * height + r] = v.clone();
+            }
+        }
+        slice_offsets.push(col_indices.len());
+    }
+
+    SellMatrix::from_parts_unchecked(
+        nrows,
+        csr.ncols(),
+        slice_height,
+        sigma,
+        row_permutation,
+        slice_offsets,
+        row_lengths,
+        col_indices,
+        values,
+    )
+}
+
+/// Converts a [`SellMatrix`] to a [`CsrMatrix`].
+pub fn convert_sell_csr<T>(sell: &SellMatrix<T>) -> CsrMatrix<T>
+where
+    T: Scalar,
+{
+    let nrows = sell.nrows();
+    let mut stored_row_of = vec![0; nrows];
+    for (r, &i) in sell.row_permutation().iter().enumerate() {
+        stored_row_of[i] = r;
+    }
+
+    let mut row_offsets = Vec::with_capacity(nrows + 1);
+    let mut col_indices = Vec::with_capacity(sell.nnz());
+    let mut values = Vec::with_capacity(sell.nnz());
+    row_offsets.push(0);
+    for &r in &stored_row_of {
+        let slice = sell.slice(r / sell.slice_height());
+        let local_row = r - slice.first_row;
+        for k in 0..sell.row_lengths()[r] {
+            let idx = slice.offset + k * slice.height + local_row;
+            col_indices.push(sell.col_indices()[idx]);
+            values.push(sell.values()[idx].clone());
+        }
+        row_offsets.push(col_indices.len());
+    }
+
+    CsrMatrix::try_from_csr_data(nrows, sell.ncols(), row_offsets, col_indices, values)
+        .expect("Internal error: Invalid CSR data during SELL->CSR conversion")
+}
+
+/// Converts a [`CooMatrix`] to a [`SellMatrix`] with slice height `slice_height` (`C`) and
+/// sorting window `sigma` (`σ`).
+///
+/// Duplicate entries are summed. See [`convert_csr_sell`] for details.
+///
+/// # Panics
+///
+/// Panics if `slice_height` or `sigma` is zero.
+pub fn convert_coo_sell<T>(coo: &CooMatrix<T>, slice_height: usize, sigma: usize) -> SellMatrix<T>
+where
+    T: Scalar + Zero,
+{
+    convert_csr_sell(&convert_coo_csr(coo), slice_height, sigma)
+}
+
+/// Converts a [`SellMatrix`] to a [`CooMatrix`].
+pub fn convert_sell_coo<T>(sell: &SellMatrix<T>) -> CooMatrix<T>
+where
+    T: Scalar,
+{
+    let mut coo = CooMatrix::new(sell.nrows(), sell.ncols());
+    coo.reserve(sell.nnz());
+    for (i, j, v) in sell.triplet_iter() {
+        coo.push(i, j, v.clone());
+    }
+    coo
+}
+
+fn convert_coo_cs<T, I>(
     major_dim: usize,
-    major_indices: &[usize],
-    minor_indices: &[usize],
+    major_indices: &[I],
+    minor_indices: &[I],
     values: &[T],
-) -> (Vec<usize>, Vec<usize>, Vec<T>)
+) -> (Vec<I>, Vec<I>, Vec<T>)
 where
     T: Scalar + Zero,
+    I: SparseIndex,
 {
     assert_eq!(major_indices.len(), minor_indices.len());
     assert_eq!(minor_indices.len(), values.len());
@@ -249,7 +680,7 @@ where
 
     let (unsorted_major_offsets, unsorted_minor_idx, unsorted_vals) = {
         let mut offsets = vec![0usize; major_dim + 1];
-        let mut minor_idx = vec![0usize; nnz];
+        let mut minor_idx = vec![I::from_index(0); nnz];
         let mut vals = vec![T::zero(); nnz];
         coo_to_unsorted_cs(
             &mut offsets,
@@ -272,7 +703,7 @@ where
     let mut sorted_minor_idx = Vec::new();
     let mut sorted_vals = Vec::new();
 
-    sorted_major_offsets.push(0);
+    sorted_major_offsets.push(I::from_index(0));
 
     // We need some temporary storage when working with each lane. Since lanes often have a
     // very small number of non-zero entries, we try to amortize allocations across
@@ -289,7 +720,7 @@ where
 
         // Ensure that workspaces can hold enough data
         perm_workspace.resize(count, 0);
-        idx_workspace.resize(count, 0);
+        idx_workspace.resize(count, I::from_index(0));
         values_workspace.resize(count, T::zero());
         sort_lane(
             &mut idx_workspace[..count],
@@ -310,7 +741,8 @@ where
         );
 
         let new_col_count = sorted_minor_idx.len() - sorted_ja_current_len;
-        sorted_major_offsets.push(sorted_major_offsets.last().unwrap() + new_col_count);
+        let offset = sorted_major_offsets.last().unwrap().index() + new_col_count;
+        sorted_major_offsets.push(I::from_index(offset));
     }
 
     (sorted_major_offsets, sorted_minor_idx, sorted_vals)
@@ -320,13 +752,13 @@ where
 /// indices.
 ///
 /// Here `major/minor` is `row/col` for CSR and `col/row` for CSC.
-fn coo_to_unsorted_cs<T: Clone>(
+fn coo_to_unsorted_cs<T: Clone, I: SparseIndex>(
     major_offsets: &mut [usize],
-    cs_minor_idx: &mut [usize],
+    cs_minor_idx: &mut [I],
     cs_values: &mut [T],
     major_dim: usize,
-    major_indices: &[usize],
-    minor_indices: &[usize],
+    major_indices: &[I],
+    minor_indices: &[I],
     coo_values: &[T],
 ) {
     assert_eq!(major_offsets.len(), major_dim + 1);
@@ -337,7 +769,7 @@ fn coo_to_unsorted_cs<T: Clone>(
 
     // Count the number of occurrences of each row
     for major_idx in major_indices {
-        major_offsets[*major_idx] += 1;
+        major_offsets[major_idx.index()] += 1;
     }
 
     cs::convert_counts_to_offsets(major_offsets);
@@ -349,10 +781,11 @@ fn coo_to_unsorted_cs<T: Clone>(
         let mut current_counts = vec![0usize; major_dim + 1];
         let triplet_iter = major_indices.iter().zip(minor_indices).zip(coo_values);
         for ((i, j), value) in triplet_iter {
-            let current_offset = major_offsets[*i] + current_counts[*i];
+            let i = i.index();
+            let current_offset = major_offsets[i] + current_counts[i];
             cs_minor_idx[current_offset] = *j;
             cs_values[current_offset] = value.clone();
-            current_counts[*i] += 1;
+            current_counts[i] += 1;
         }
     }
 }
@@ -364,10 +797,10 @@ fn coo_to_unsorted_cs<T: Clone>(
 ///
 /// All input slices are expected to be of the same length. The contents of mutable slices
 /// can be arbitrary, as they are anyway overwritten.
-fn sort_lane<T: Clone>(
-    minor_idx_result: &mut [usize],
+fn sort_lane<T: Clone, I: SparseIndex>(
+    minor_idx_result: &mut [I],
     values_result: &mut [T],
-    minor_idx: &[usize],
+    minor_idx: &[I],
     values: &[T],
     workspace: &mut [usize],
 ) {
@@ -385,10 +818,10 @@ fn sort_lane<T: Clone>(
 
 /// Given *sorted* indices and corresponding scalar values, combines duplicates with the given
 /// associative combiner and calls the provided produce methods with combined indices and values.
-fn combine_duplicates<T: Clone>(
-    mut produce_idx: impl FnMut(usize),
+fn combine_duplicates<T: Clone, I: SparseIndex>(
+    mut produce_idx: impl FnMut(I),
     mut produce_value: impl FnMut(T),
-    idx_array: &[usize],
+    idx_array: &[I],
     values: &[T],
     combiner: impl Fn(T, T) -> T,
 ) {
@@ -409,3 +842,112 @@ fn combine_duplicates<T: Clone>(
         i = j;
     }
 }
+
+/// Converts a dense vector to a [`SparseVector`].
+///
+/// Only the non-zero entries of the dense vector are explicitly stored.
+pub fn convert_dense_sparse_vector<T, R, S>(dense: &Vector<T, R, S>) -> SparseVector<T>
+where
+    T: Scalar + Zero,
+    R: Dim,
+    S: RawStorage<T, R, U1>,
+{
+    let (indices, values) = dense
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| *v != &T::zero())
+        .map(|(i, v)| (i, v.clone()))
+        .unzip();
+    SparseVector::try_from_indices_and_values(dense.nrows(), indices, values)
+        .expect("Internal error: Invalid sparse vector data during dense->sparse vector conversion")
+}
+
+/// Converts a [`SparseVector`] to a dense vector.
+pub fn convert_sparse_vector_dense<T>(vector: &SparseVector<T>) -> DVector<T>
+where
+    T: Scalar + Zero,
+{
+    let mut output = DVector::zeros(vector.len());
+    for (i, v) in vector.iter() {
+        output[i] = v.clone();
+    }
+    output
+}
+
+/// Converts a matrix from the legacy `nalgebra::sparse` module to [`CscMatrix`].
+///
+/// The legacy format is also column-compressed, so only the data is copied. Row indices are
+/// sorted if necessary.
+///
+/// # Errors
+///
+/// Returns an error if the legacy matrix has duplicate entries in a column, or otherwise does
+/// not describe a valid CSC matrix.
+#[cfg(feature = "legacy-sparse")]
+pub fn convert_legacy_csc<T>(legacy: &LegacyCsMatrix<T>) -> Result<CscMatrix<T>, SparseFormatError>
+where
+    T: Scalar,
+{
+    let data = legacy.data();
+    // The legacy column offsets do not include the offset past the last column
+    let mut col_offsets = data.p().to_vec();
+    col_offsets.push(data.i().len());
+    CscMatrix::try_from_unsorted_csc_data(
+        legacy.nrows(),
+        legacy.ncols(),
+        col_offsets,
+        data.i().to_vec(),
+        data.values().to_vec(),
+    )
+}
+
+/// Converts a [`CscMatrix`] to a matrix of the legacy `nalgebra::sparse` module.
+#[cfg(feature = "legacy-sparse")]
+pub fn convert_csc_legacy<T, I>(csc: &CscMatrix<T, I>) -> LegacyCsMatrix<T>
+where
+    T: Scalar + Zero + ClosedAdd,
+    I: SparseIndex,
+{
+    let (rows, (cols, values)): (Vec<_>, (Vec<_>, Vec<_>)) = csc
+        .triplet_iter()
+        .map(|(i, j, v)| (i, (j, v.clone())))
+        .unzip();
+    LegacyCsMatrix::from_triplet(csc.nrows(), csc.ncols(), &rows, &cols, &values)
+}
+
+/// Converts a sparse vector from the legacy `nalgebra::sparse` module to [`SparseVector`].
+///
+/// # Errors
+///
+/// Returns an error if the legacy vector has duplicate entries, or otherwise does not describe
+/// a valid sparse vector.
+#[cfg(feature = "legacy-sparse")]
+pub fn convert_legacy_sparse_vector<T>(
+    legacy: &LegacyCsVector<T>,
+) -> Result<SparseVector<T>, SparseFormatError>
+where
+    T: Scalar,
+{
+    let data = legacy.data();
+    SparseVector::try_from_unsorted_indices_and_values(
+        legacy.nrows(),
+        data.i().to_vec(),
+        data.values().to_vec(),
+    )
+}
+
+/// Converts a [`SparseVector`] to a sparse vector of the legacy `nalgebra::sparse` module.
+#[cfg(feature = "legacy-sparse")]
+pub fn convert_sparse_vector_legacy<T>(vector: &SparseVector<T>) -> LegacyCsVector<T>
+where
+    T: Scalar + Zero + ClosedAdd,
+{
+    let cols = vec![0; vector.nnz()];
+    LegacyCsVector::from_triplet_generic(
+        Dyn(vector.len()),
+        U1,
+        vector.indices(),
+        &cols,
+        vector.values(),
+    )
+}
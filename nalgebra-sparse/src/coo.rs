@@ -3,7 +3,9 @@
 #[cfg(feature = "serde-serialize")]
 mod coo_serde;
 
+use crate::pattern::SparseIndex;
 use crate::SparseFormatError;
+use std::collections::TryReserveError;
 
 /// A COO representation of a sparse matrix.
 ///
@@ -39,16 +41,23 @@ use crate::SparseFormatError;
 /// let csr = CsrMatrix::from(&coo);
 /// let csc = CscMatrix::from(&coo);
 /// ```
+///
+/// # Index type
+///
+/// The row and column indices are stored as `usize` by default. A different
+/// [`SparseIndex`] type can be used by constructing the matrix with
+/// [`try_from_triplets_generic`](Self::try_from_triplets_generic), in which case the dimensions
+/// of the matrix must be representable by the index type.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CooMatrix<T> {
+pub struct CooMatrix<T, I = usize> {
     nrows: usize,
     ncols: usize,
-    row_indices: Vec<usize>,
-    col_indices: Vec<usize>,
+    row_indices: Vec<I>,
+    col_indices: Vec<I>,
     values: Vec<T>,
 }
 
-impl<T: na::Scalar> CooMatrix<T> {
+impl<T: na::Scalar, I: SparseIndex> CooMatrix<T, I> {
     /// Pushes a dense matrix into the sparse one.
     ///
     /// This adds the dense matrix `m` starting at the `r`th row and `c`th column
@@ -77,8 +86,8 @@ impl<T: na::Scalar> CooMatrix<T> {
 
         for (col_idx, col) in m.column_iter().enumerate() {
             for (row_idx, v) in col.iter().enumerate() {
-                self.row_indices.push(r + row_idx);
-                self.col_indices.push(c + col_idx);
+                self.row_indices.push(I::from_index(r + row_idx));
+                self.col_indices.push(I::from_index(c + col_idx));
                 self.values.push(v.clone());
             }
         }
@@ -122,6 +131,42 @@ impl<T> CooMatrix<T> {
         row_indices: Vec<usize>,
         col_indices: Vec<usize>,
         values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        Self::try_from_triplets_generic(nrows, ncols, row_indices, col_indices, values)
+    }
+}
+
+impl<T, I: SparseIndex> CooMatrix<T, I> {
+    /// Try to construct a COO matrix with an arbitrary index type from the given dimensions
+    /// and a collection of (i, j, v) triplets.
+    ///
+    /// This is the same as [`try_from_triplets`](CooMatrix::try_from_triplets), except that the
+    /// index type is not restricted to `usize`. In addition, an error is returned if the
+    /// dimensions can not be represented by the index type.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::{coo::CooMatrix, csr::CsrMatrix};
+    /// let row_indices: Vec<u32> = vec![1, 0, 1];
+    /// let col_indices: Vec<u32> = vec![0, 1, 0];
+    /// let values = vec![1.0, 2.0, 3.0];
+    /// let coo = CooMatrix::try_from_triplets_generic(2, 2, row_indices, col_indices, values)
+    ///     .unwrap();
+    ///
+    /// // Conversions preserve the index type
+    /// let csr = CsrMatrix::from(&coo);
+    /// assert_eq!(csr.row_offsets(), &[0u32, 1, 2]);
+    /// assert_eq!(csr.col_indices(), &[1u32, 0]);
+    /// assert_eq!(csr.values(), &[2.0, 4.0]);
+    /// ```
+    pub fn try_from_triplets_generic(
+        nrows: usize,
+        ncols: usize,
+        row_indices: Vec<I>,
+        col_indices: Vec<I>,
+        values: Vec<T>,
     ) -> Result<Self, SparseFormatError> {
         use crate::SparseFormatErrorKind::*;
         if row_indices.len() != col_indices.len() {
@@ -136,10 +181,16 @@ impl<T> CooMatrix<T> {
             ));
         }
 
-        let row_indices_in_bounds = row_indices.iter().all(|i| *i < nrows);
-        let col_indices_in_bounds = col_indices.iter().all(|j| *j < ncols);
+        let in_bounds = |index: &I, dim: usize| matches!(index.try_index(), Some(k) if k < dim);
+        let row_indices_in_bounds = row_indices.iter().all(|i| in_bounds(i, nrows));
+        let col_indices_in_bounds = col_indices.iter().all(|j| in_bounds(j, ncols));
 
-        if !row_indices_in_bounds {
+        if I::try_from_index(nrows).is_none() || I::try_from_index(ncols).is_none() {
+            Err(SparseFormatError::from_kind_and_msg(
+                IndexOutOfBounds,
+                "Matrix dimensions can not be represented by the index type.",
+            ))
+        } else if !row_indices_in_bounds {
             Err(SparseFormatError::from_kind_and_msg(
                 IndexOutOfBounds,
                 "Row index out of bounds.",
@@ -167,7 +218,7 @@ impl<T> CooMatrix<T> {
             .iter()
             .zip(&self.col_indices)
             .zip(&self.values)
-            .map(|((i, j), v)| (*i, *j, v))
+            .map(|((i, j), v)| (i.index(), j.index(), v))
     }
 
     /// A mutable iterator over triplets (i, j, v).
@@ -177,7 +228,7 @@ impl<T> CooMatrix<T> {
             .iter()
             .zip(&self.col_indices)
             .zip(self.values.iter_mut())
-            .map(|((i, j), v)| (*i, *j, v))
+            .map(|((i, j), v)| (i.index(), j.index(), v))
     }
 
     /// Reserves capacity for COO matrix by at least `additional` elements.
@@ -204,6 +255,17 @@ impl<T> CooMatrix<T> {
         self.values.reserve(additional);
     }
 
+    /// Try to reserve capacity for COO matrix by at least `additional` elements.
+    ///
+    /// Unlike [`reserve`](Self::reserve), an error is returned if the capacity overflows or the
+    /// allocation fails, in which case the capacity of some of the triplet arrays may still have
+    /// been increased.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.row_indices.try_reserve(additional)?;
+        self.col_indices.try_reserve(additional)?;
+        self.values.try_reserve(additional)
+    }
+
     /// Push a single triplet to the matrix.
     ///
     /// This adds the value `v` to the `i`th row and `j`th column in the matrix.
@@ -216,8 +278,8 @@ impl<T> CooMatrix<T> {
     pub fn push(&mut self, i: usize, j: usize, v: T) {
         assert!(i < self.nrows);
         assert!(j < self.ncols);
-        self.row_indices.push(i);
-        self.col_indices.push(j);
+        self.row_indices.push(I::from_index(i));
+        self.col_indices.push(I::from_index(j));
         self.values.push(v);
     }
 
@@ -228,6 +290,98 @@ impl<T> CooMatrix<T> {
         self.values.clear();
     }
 
+    /// Sums duplicate triplets in place, so that each entry `(i, j)` is stored at most once.
+    ///
+    /// The summed value is stored in the position of the first occurrence of the entry, and the
+    /// order of the remaining triplets is preserved. Returns the number of removed triplets.
+    pub fn sum_duplicates(&mut self) -> usize
+    where
+        T: Clone + na::ClosedAdd,
+    {
+        let nnz = self.nnz();
+        let mut order: Vec<usize> = (0..nnz).collect();
+        // The sort is stable, so the first occurrence of each entry comes first in its group
+        order.sort_by_key(|&k| (self.row_indices[k], self.col_indices[k]));
+
+        let mut keep = vec![false; nnz];
+        let mut group_begin = 0;
+        while group_begin < nnz {
+            let first = order[group_begin];
+            keep[first] = true;
+            let mut k = group_begin + 1;
+            while k < nnz
+                && self.row_indices[order[k]] == self.row_indices[first]
+                && self.col_indices[order[k]] == self.col_indices[first]
+            {
+                let duplicate = self.values[order[k]].clone();
+                self.values[first] += duplicate;
+                k += 1;
+            }
+            group_begin = k;
+        }
+
+        let mut num_kept = 0;
+        for (k, keep) in keep.into_iter().enumerate() {
+            if keep {
+                self.row_indices.swap(num_kept, k);
+                self.col_indices.swap(num_kept, k);
+                self.values.swap(num_kept, k);
+                num_kept += 1;
+            }
+        }
+        self.row_indices.truncate(num_kept);
+        self.col_indices.truncate(num_kept);
+        self.values.truncate(num_kept);
+        nnz - num_kept
+    }
+
+    /// Sums duplicate triplets and sorts the triplets in row-major order, in place.
+    ///
+    /// Afterwards, each entry `(i, j)` is stored at most once, and the triplets are ordered
+    /// by row index and then by column index. Returns the number of removed triplets.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::coo::CooMatrix;
+    /// let mut coo = CooMatrix::new(2, 2);
+    /// coo.push(1, 0, 1.0);
+    /// coo.push(0, 1, 2.0);
+    /// coo.push(1, 0, 3.0);
+    ///
+    /// let num_removed = coo.compress();
+    /// assert_eq!(num_removed, 1);
+    /// assert_eq!(coo.row_indices(), &[0, 1]);
+    /// assert_eq!(coo.col_indices(), &[1, 0]);
+    /// assert_eq!(coo.values(), &[2.0, 4.0]);
+    /// ```
+    pub fn compress(&mut self) -> usize
+    where
+        T: Clone + na::ClosedAdd,
+    {
+        let num_removed = self.sum_duplicates();
+
+        let mut order: Vec<usize> = (0..self.nnz()).collect();
+        order.sort_unstable_by_key(|&k| (self.row_indices[k], self.col_indices[k]));
+
+        // Entry `order[k]` moves to position `k`. The permutation is applied in place by following
+        // each of its cycles, marking the visited positions as fixed points.
+        for start in 0..order.len() {
+            let mut k = start;
+            while order[k] != start {
+                let next = order[k];
+                self.row_indices.swap(k, next);
+                self.col_indices.swap(k, next);
+                self.values.swap(k, next);
+                order[k] = k;
+                k = next;
+            }
+            order[k] = k;
+        }
+        num_removed
+    }
+
     /// The number of rows in the matrix.
     #[inline]
     #[must_use]
@@ -255,13 +409,13 @@ impl<T> CooMatrix<T> {
 
     /// The row indices of the explicitly stored entries.
     #[must_use]
-    pub fn row_indices(&self) -> &[usize] {
+    pub fn row_indices(&self) -> &[I] {
         &self.row_indices
     }
 
     /// The column indices of the explicitly stored entries.
     #[must_use]
-    pub fn col_indices(&self) -> &[usize] {
+    pub fn col_indices(&self) -> &[I] {
         &self.col_indices
     }
 
@@ -289,7 +443,35 @@ impl<T> CooMatrix<T> {
     /// assert_eq!(col_idx, vec![1, 2]);
     /// assert_eq!(val, vec![1.0, 2.0]);
     /// ```
-    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    pub fn disassemble(self) -> (Vec<I>, Vec<I>, Vec<T>) {
         (self.row_indices, self.col_indices, self.values)
     }
+
+    /// Converts the row and column indices of the matrix to a different index type.
+    ///
+    /// Returns an error if the dimensions of the matrix can not be represented by the new
+    /// index type.
+    pub fn try_cast_indices<J: SparseIndex>(self) -> Result<CooMatrix<T, J>, SparseFormatError> {
+        if J::try_from_index(self.nrows).is_none() || J::try_from_index(self.ncols).is_none() {
+            return Err(SparseFormatError::from_kind_and_msg(
+                crate::SparseFormatErrorKind::IndexOutOfBounds,
+                "Matrix dimensions can not be represented by the index type.",
+            ));
+        }
+
+        // All indices are bounded by the dimensions
+        let cast = |indices: Vec<I>| -> Vec<J> {
+            indices
+                .into_iter()
+                .map(|index| J::from_index(index.index()))
+                .collect()
+        };
+        Ok(CooMatrix {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            row_indices: cast(self.row_indices),
+            col_indices: cast(self.col_indices),
+            values: self.values,
+        })
+    }
 }
@@ -1,12 +1,13 @@
-use std::mem::replace;
+use std::mem::{replace, take};
 use std::ops::Range;
 
 use num_traits::One;
 
-use nalgebra::Scalar;
+use nalgebra::{ClosedAdd, ClosedMul, Scalar};
 
-use crate::pattern::SparsityPattern;
+use crate::pattern::{SparseIndex, SparsityPattern, SparsityPatternIter};
 use crate::utils::{apply_permutation, compute_sort_permutation};
+use crate::vector::SparseVector;
 use crate::{SparseEntry, SparseEntryMut, SparseFormatError, SparseFormatErrorKind};
 
 /// An abstract compressed matrix.
@@ -17,8 +18,8 @@ use crate::{SparseEntry, SparseEntryMut, SparseFormatError, SparseFormatErrorKin
 /// A CSR matrix is obtained by associating rows with the major dimension, while a CSC matrix
 /// is obtained by associating columns with the major dimension.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CsMatrix<T> {
-    sparsity_pattern: SparsityPattern,
+pub struct CsMatrix<T, I = usize> {
+    sparsity_pattern: SparsityPattern<I>,
     values: Vec<T>,
 }
 
@@ -31,10 +32,12 @@ impl<T> CsMatrix<T> {
             values: vec![],
         }
     }
+}
 
+impl<T, I: SparseIndex> CsMatrix<T, I> {
     #[inline]
     #[must_use]
-    pub fn pattern(&self) -> &SparsityPattern {
+    pub fn pattern(&self) -> &SparsityPattern<I> {
         &self.sparsity_pattern
     }
 
@@ -52,7 +55,7 @@ impl<T> CsMatrix<T> {
     /// Returns the raw data represented as a tuple `(major_offsets, minor_indices, values)`.
     #[inline]
     #[must_use]
-    pub fn cs_data(&self) -> (&[usize], &[usize], &[T]) {
+    pub fn cs_data(&self) -> (&[I], &[I], &[T]) {
         let pattern = self.pattern();
         (
             pattern.major_offsets(),
@@ -63,7 +66,7 @@ impl<T> CsMatrix<T> {
 
     /// Returns the raw data represented as a tuple `(major_offsets, minor_indices, values)`.
     #[inline]
-    pub fn cs_data_mut(&mut self) -> (&[usize], &[usize], &mut [T]) {
+    pub fn cs_data_mut(&mut self) -> (&[I], &[I], &mut [T]) {
         let pattern = &mut self.sparsity_pattern;
         (
             pattern.major_offsets(),
@@ -73,12 +76,12 @@ impl<T> CsMatrix<T> {
     }
 
     #[inline]
-    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern, &mut [T]) {
+    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern<I>, &mut [T]) {
         (&self.sparsity_pattern, &mut self.values)
     }
 
     #[inline]
-    pub fn from_pattern_and_values(pattern: SparsityPattern, values: Vec<T>) -> Self {
+    pub fn from_pattern_and_values(pattern: SparsityPattern<I>, values: Vec<T>) -> Self {
         assert_eq!(
             pattern.nnz(),
             values.len(),
@@ -90,44 +93,50 @@ impl<T> CsMatrix<T> {
         }
     }
 
-    /// Internal method for simplifying access to a lane's data
-    #[inline]
-    #[must_use]
-    pub fn get_index_range(&self, row_index: usize) -> Option<Range<usize>> {
-        let row_begin = *self.sparsity_pattern.major_offsets().get(row_index)?;
-        let row_end = *self.sparsity_pattern.major_offsets().get(row_index + 1)?;
-        Some(row_begin..row_end)
-    }
-
-    pub fn take_pattern_and_values(self) -> (SparsityPattern, Vec<T>) {
+    pub fn take_pattern_and_values(self) -> (SparsityPattern<I>, Vec<T>) {
         (self.sparsity_pattern, self.values)
     }
 
     #[inline]
-    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    pub fn disassemble(self) -> (Vec<I>, Vec<I>, Vec<T>) {
         let (offsets, indices) = self.sparsity_pattern.disassemble();
         (offsets, indices, self.values)
     }
 
     #[inline]
-    pub fn into_pattern_and_values(self) -> (SparsityPattern, Vec<T>) {
+    pub fn into_pattern_and_values(self) -> (SparsityPattern<I>, Vec<T>) {
         (self.sparsity_pattern, self.values)
     }
 
+    /// Returns a view of the matrix, which borrows its data.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CsMatrixView<'_, T, I> {
+        CsMatrixView {
+            major_offsets: self.sparsity_pattern.major_offsets(),
+            minor_indices: self.sparsity_pattern.minor_indices(),
+            minor_dim: self.sparsity_pattern.minor_dim(),
+            values: &self.values,
+        }
+    }
+
+    /// Returns a view of the matrix that allows mutation of its values.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CsMatrixViewMut<'_, T, I> {
+        CsMatrixViewMut {
+            major_offsets: self.sparsity_pattern.major_offsets(),
+            minor_indices: self.sparsity_pattern.minor_indices(),
+            minor_dim: self.sparsity_pattern.minor_dim(),
+            values: &mut self.values,
+        }
+    }
+
     /// Returns an entry for the given major/minor indices, or `None` if the indices are out
     /// of bounds.
     #[must_use]
     pub fn get_entry(&self, major_index: usize, minor_index: usize) -> Option<SparseEntry<'_, T>> {
-        let row_range = self.get_index_range(major_index)?;
-        let (_, minor_indices, values) = self.cs_data();
-        let minor_indices = &minor_indices[row_range.clone()];
-        let values = &values[row_range];
-        get_entry_from_slices(
-            self.pattern().minor_dim(),
-            minor_indices,
-            values,
-            minor_index,
-        )
+        self.as_view().get_entry(major_index, minor_index)
     }
 
     /// Returns a mutable entry for the given major/minor indices, or `None` if the indices are out
@@ -137,48 +146,340 @@ impl<T> CsMatrix<T> {
         major_index: usize,
         minor_index: usize,
     ) -> Option<SparseEntryMut<'_, T>> {
-        let row_range = self.get_index_range(major_index)?;
-        let minor_dim = self.pattern().minor_dim();
-        let (_, minor_indices, values) = self.cs_data_mut();
-        let minor_indices = &minor_indices[row_range.clone()];
-        let values = &mut values[row_range];
-        get_mut_entry_from_slices(minor_dim, minor_indices, values, minor_index)
+        self.as_view_mut().into_entry_mut(major_index, minor_index)
+    }
+
+    #[must_use]
+    pub fn get_lane(&self, index: usize) -> Option<CsLane<'_, T, I>> {
+        self.as_view().get_lane(index)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get_lane_mut(&mut self, index: usize) -> Option<CsLaneMut<'_, T, I>> {
+        self.as_view_mut().into_lane_mut(index)
+    }
+
+    #[inline]
+    pub fn lane_iter(&self) -> CsLaneIter<'_, T, I> {
+        self.as_view().lane_iter()
+    }
+
+    #[inline]
+    pub fn lane_iter_mut(&mut self) -> CsLaneIterMut<'_, T, I> {
+        self.as_view_mut().into_lane_iter_mut()
+    }
+}
+
+/// A borrowed abstract compressed matrix.
+///
+/// The data is assumed to have been validated, i.e. it must describe a valid sparsity pattern
+/// and contain exactly one value per explicitly stored entry.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsMatrixView<'a, T, I = usize> {
+    major_offsets: &'a [I],
+    minor_indices: &'a [I],
+    minor_dim: usize,
+    values: &'a [T],
+}
+
+// Manual implementations, since the derives would require `T: Copy` and `I: Copy`
+impl<'a, T, I> Clone for CsMatrixView<'a, T, I> {
+    fn clone(&self) -> Self {
+        *self
     }
+}
+
+impl<'a, T, I> Copy for CsMatrixView<'a, T, I> {}
 
+/// A borrowed abstract compressed matrix with mutable values.
+///
+/// See [`CsMatrixView`] for the requirements on the data.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsMatrixViewMut<'a, T, I = usize> {
+    major_offsets: &'a [I],
+    minor_indices: &'a [I],
+    minor_dim: usize,
+    values: &'a mut [T],
+}
+
+impl<'a, T, I: SparseIndex> CsMatrixView<'a, T, I> {
+    /// Creates a view from data that has already been validated.
+    #[inline]
+    pub fn from_cs_data_unchecked(
+        minor_dim: usize,
+        major_offsets: &'a [I],
+        minor_indices: &'a [I],
+        values: &'a [T],
+    ) -> Self {
+        debug_assert_eq!(minor_indices.len(), values.len());
+        Self {
+            major_offsets,
+            minor_indices,
+            minor_dim,
+            values,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn major_dim(&self) -> usize {
+        self.major_offsets.len() - 1
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn minor_dim(&self) -> usize {
+        self.minor_dim
+    }
+
+    #[inline]
     #[must_use]
-    pub fn get_lane(&self, index: usize) -> Option<CsLane<'_, T>> {
+    pub fn nnz(&self) -> usize {
+        self.minor_indices.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> &'a [T] {
+        self.values
+    }
+
+    /// Returns the raw data represented as a tuple `(major_offsets, minor_indices, values)`.
+    #[inline]
+    #[must_use]
+    pub fn cs_data(&self) -> (&'a [I], &'a [I], &'a [T]) {
+        (self.major_offsets, self.minor_indices, self.values)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get_index_range(&self, index: usize) -> Option<Range<usize>> {
+        let begin = self.major_offsets.get(index)?.index();
+        let end = self.major_offsets.get(index + 1)?.index();
+        Some(begin..end)
+    }
+
+    /// The minor indices of the given lane.
+    ///
+    /// Panics if the lane index is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn lane_indices(&self, index: usize) -> &'a [I] {
+        &self.minor_indices[self.get_index_range(index).unwrap()]
+    }
+
+    #[must_use]
+    pub fn get_entry(&self, major_index: usize, minor_index: usize) -> Option<SparseEntry<'a, T>> {
+        let range = self.get_index_range(major_index)?;
+        get_entry_from_slices(
+            self.minor_dim,
+            &self.minor_indices[range.clone()],
+            &self.values[range],
+            minor_index,
+        )
+    }
+
+    #[must_use]
+    pub fn get_lane(&self, index: usize) -> Option<CsLane<'a, T, I>> {
         let range = self.get_index_range(index)?;
-        let (_, minor_indices, values) = self.cs_data();
         Some(CsLane {
-            minor_indices: &minor_indices[range.clone()],
-            values: &values[range],
-            minor_dim: self.pattern().minor_dim(),
+            minor_indices: &self.minor_indices[range.clone()],
+            values: &self.values[range],
+            minor_dim: self.minor_dim,
         })
     }
 
     #[inline]
+    pub fn lane_iter(&self) -> CsLaneIter<'a, T, I> {
+        CsLaneIter {
+            current_lane_idx: 0,
+            view: *self,
+        }
+    }
+
+    #[inline]
+    pub fn entries(&self) -> SparsityPatternIter<'a, I> {
+        SparsityPatternIter::from_offsets_and_indices(self.major_offsets, self.minor_indices)
+    }
+
+    /// Computes the matrix with major and minor roles switched, which is the transpose when
+    /// interpreted in the same format.
     #[must_use]
-    pub fn get_lane_mut(&mut self, index: usize) -> Option<CsLaneMut<'_, T>> {
-        let range = self.get_index_range(index)?;
-        let minor_dim = self.pattern().minor_dim();
-        let (_, minor_indices, values) = self.cs_data_mut();
-        Some(CsLaneMut {
+    pub fn transpose(&self) -> CsMatrix<T, I>
+    where
+        T: Scalar,
+    {
+        let (offsets, indices, values) = transpose_cs(
+            self.major_dim(),
+            self.minor_dim,
+            self.major_offsets,
+            self.minor_indices,
+            self.values,
+        );
+        // TODO: Skip checks
+        let pattern = SparsityPattern::try_from_offsets_and_indices_generic(
+            self.minor_dim,
+            self.major_dim(),
+            offsets,
+            indices,
+        )
+        .expect("Internal error: Transpose should never fail.");
+        CsMatrix::from_pattern_and_values(pattern, values)
+    }
+
+    /// Copies the data into an owned matrix.
+    #[must_use]
+    pub fn into_owned(self) -> CsMatrix<T, I>
+    where
+        T: Clone,
+    {
+        // TODO: Skip checks, the data has already been validated
+        let pattern = SparsityPattern::try_from_offsets_and_indices_generic(
+            self.major_dim(),
+            self.minor_dim,
+            self.major_offsets.to_vec(),
+            self.minor_indices.to_vec(),
+        )
+        .expect("Internal error: Views must always hold valid data.");
+        CsMatrix::from_pattern_and_values(pattern, self.values.to_vec())
+    }
+}
+
+impl<'a, T, I: SparseIndex> CsMatrixViewMut<'a, T, I> {
+    /// Creates a mutable view from data that has already been validated.
+    #[inline]
+    pub fn from_cs_data_unchecked(
+        minor_dim: usize,
+        major_offsets: &'a [I],
+        minor_indices: &'a [I],
+        values: &'a mut [T],
+    ) -> Self {
+        debug_assert_eq!(minor_indices.len(), values.len());
+        Self {
+            major_offsets,
+            minor_indices,
             minor_dim,
-            minor_indices: &minor_indices[range.clone()],
-            values: &mut values[range],
+            values,
+        }
+    }
+
+    /// Reborrows the data as an immutable view.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CsMatrixView<'_, T, I> {
+        CsMatrixView {
+            major_offsets: self.major_offsets,
+            minor_indices: self.minor_indices,
+            minor_dim: self.minor_dim,
+            values: self.values,
+        }
+    }
+
+    /// Reborrows the data as a mutable view with a shorter lifetime.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CsMatrixViewMut<'_, T, I> {
+        CsMatrixViewMut {
+            major_offsets: self.major_offsets,
+            minor_indices: self.minor_indices,
+            minor_dim: self.minor_dim,
+            values: self.values,
+        }
+    }
+
+    /// Converts the mutable view into an immutable view with the same lifetime.
+    #[inline]
+    #[must_use]
+    pub fn into_view(self) -> CsMatrixView<'a, T, I> {
+        CsMatrixView {
+            major_offsets: self.major_offsets,
+            minor_indices: self.minor_indices,
+            minor_dim: self.minor_dim,
+            values: self.values,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn major_dim(&self) -> usize {
+        self.major_offsets.len() - 1
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn minor_dim(&self) -> usize {
+        self.minor_dim
+    }
+
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        self.values
+    }
+
+    /// Returns the raw data represented as a tuple `(major_offsets, minor_indices, values)`.
+    #[inline]
+    pub fn into_cs_data_mut(self) -> (&'a [I], &'a [I], &'a mut [T]) {
+        (self.major_offsets, self.minor_indices, self.values)
+    }
+
+    #[must_use]
+    pub fn into_entry_mut(
+        self,
+        major_index: usize,
+        minor_index: usize,
+    ) -> Option<SparseEntryMut<'a, T>> {
+        let range = self.as_view().get_index_range(major_index)?;
+        get_mut_entry_from_slices(
+            self.minor_dim,
+            &self.minor_indices[range.clone()],
+            &mut self.values[range],
+            minor_index,
+        )
+    }
+
+    #[must_use]
+    pub fn get_entry_mut(
+        &mut self,
+        major_index: usize,
+        minor_index: usize,
+    ) -> Option<SparseEntryMut<'_, T>> {
+        self.as_view_mut().into_entry_mut(major_index, minor_index)
+    }
+
+    #[must_use]
+    pub fn into_lane_mut(self, index: usize) -> Option<CsLaneMut<'a, T, I>> {
+        let range = self.as_view().get_index_range(index)?;
+        Some(CsLaneMut {
+            minor_dim: self.minor_dim,
+            minor_indices: &self.minor_indices[range.clone()],
+            values: &mut self.values[range],
         })
     }
 
+    #[must_use]
+    pub fn get_lane_mut(&mut self, index: usize) -> Option<CsLaneMut<'_, T, I>> {
+        self.as_view_mut().into_lane_mut(index)
+    }
+
     #[inline]
-    pub fn lane_iter(&self) -> CsLaneIter<'_, T> {
-        CsLaneIter::new(self.pattern(), self.values())
+    pub fn into_lane_iter_mut(self) -> CsLaneIterMut<'a, T, I> {
+        CsLaneIterMut {
+            current_lane_idx: 0,
+            major_offsets: self.major_offsets,
+            minor_indices: self.minor_indices,
+            minor_dim: self.minor_dim,
+            remaining_values: self.values,
+        }
     }
 
     #[inline]
-    pub fn lane_iter_mut(&mut self) -> CsLaneIterMut<'_, T> {
-        CsLaneIterMut::new(&self.sparsity_pattern, &mut self.values)
+    pub fn lane_iter_mut(&mut self) -> CsLaneIterMut<'_, T, I> {
+        self.as_view_mut().into_lane_iter_mut()
     }
+}
 
+impl<T> CsMatrix<T> {
     #[inline]
     #[must_use]
     pub fn filter<P>(&self, predicate: P) -> Self
@@ -215,6 +516,48 @@ impl<T> CsMatrix<T> {
         Self::from_pattern_and_values(new_pattern, new_values)
     }
 
+    /// Removes the explicit entries for which the predicate returns `false`, in place.
+    ///
+    /// Returns the number of removed entries.
+    pub fn retain<P>(&mut self, mut predicate: P) -> usize
+    where
+        P: FnMut(usize, usize, &T) -> bool,
+    {
+        let (major_dim, minor_dim) = (self.pattern().major_dim(), self.pattern().minor_dim());
+        let pattern = replace(&mut self.sparsity_pattern, SparsityPattern::zeros(0, 0));
+        let mut values = take(&mut self.values);
+        let (mut offsets, mut indices) = pattern.disassemble();
+
+        let mut nnz = 0;
+        let mut lane_begin = 0;
+        for i in 0..major_dim {
+            let lane_end = offsets[i + 1];
+            for k in lane_begin..lane_end {
+                if predicate(i, indices[k], &values[k]) {
+                    indices.swap(nnz, k);
+                    values.swap(nnz, k);
+                    nnz += 1;
+                }
+            }
+            lane_begin = lane_end;
+            offsets[i + 1] = nnz;
+        }
+
+        let num_removed = indices.len() - nnz;
+        indices.truncate(nnz);
+        values.truncate(nnz);
+
+        // Removing entries preserves the order of the remaining minor indices, so the
+        // pattern is still valid
+        self.sparsity_pattern = unsafe {
+            SparsityPattern::from_offset_and_indices_unchecked(
+                major_dim, minor_dim, offsets, indices,
+            )
+        };
+        self.values = values;
+        num_removed
+    }
+
     /// Returns the diagonal of the matrix as a sparse matrix.
     #[must_use]
     pub fn diagonal_as_matrix(&self) -> Self
@@ -240,14 +583,217 @@ impl<T: Scalar + One> CsMatrix<T> {
     }
 }
 
-fn get_entry_from_slices<'a, T>(
+impl<T> CsMatrix<T> {
+    /// Computes the Kronecker product of two matrices, with the pattern of the result computed
+    /// directly from the patterns of the factors.
+    ///
+    /// The product is the same for CSR and CSC, since the Kronecker product of the transposes
+    /// is the transpose of the Kronecker product.
+    #[must_use]
+    pub fn kronecker(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedMul,
+    {
+        let (rhs_major_dim, rhs_minor_dim) = (rhs.pattern().major_dim(), rhs.pattern().minor_dim());
+        let major_dim = self.pattern().major_dim() * rhs_major_dim;
+        let minor_dim = self.pattern().minor_dim() * rhs_minor_dim;
+        let nnz = self.pattern().nnz() * rhs.pattern().nnz();
+
+        let mut offsets = Vec::with_capacity(major_dim + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut values = Vec::with_capacity(nnz);
+        offsets.push(0);
+        for lhs_lane in self.lane_iter() {
+            for rhs_lane in rhs.lane_iter() {
+                for (&j, a_ij) in lhs_lane.minor_indices().iter().zip(lhs_lane.values()) {
+                    for (&l, b_kl) in rhs_lane.minor_indices().iter().zip(rhs_lane.values()) {
+                        indices.push(j * rhs_minor_dim + l);
+                        values.push(a_ij.clone() * b_kl.clone());
+                    }
+                }
+                offsets.push(indices.len());
+            }
+        }
+
+        let pattern =
+            SparsityPattern::try_from_offsets_and_indices(major_dim, minor_dim, offsets, indices)
+                .expect("Internal error: Kronecker product pattern must always be valid.");
+        Self::from_pattern_and_values(pattern, values)
+    }
+
+    /// Computes the Kronecker sum `A ⊗ I + I ⊗ B` of two square matrices `A` (`self`) and `B`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either matrix is not square.
+    #[must_use]
+    pub fn kronecker_sum(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        let n = self.pattern().major_dim();
+        let m = rhs.pattern().major_dim();
+        assert_eq!(
+            n,
+            self.pattern().minor_dim(),
+            "The left-hand side of the Kronecker sum must be square."
+        );
+        assert_eq!(
+            m,
+            rhs.pattern().minor_dim(),
+            "The right-hand side of the Kronecker sum must be square."
+        );
+
+        let mut offsets = Vec::with_capacity(n * m + 1);
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        offsets.push(0);
+        for (i, lhs_lane) in self.lane_iter().enumerate() {
+            for (k, rhs_lane) in rhs.lane_iter().enumerate() {
+                // Lane i * m + k holds the entries a_ij at j * m + k and the entries b_kl
+                // at i * m + l, both of which are sorted by their minor index
+                let lhs_entries = lhs_lane
+                    .minor_indices()
+                    .iter()
+                    .zip(lhs_lane.values())
+                    .map(|(&j, a_ij)| (j * m + k, a_ij.clone()));
+                let rhs_entries = rhs_lane
+                    .minor_indices()
+                    .iter()
+                    .zip(rhs_lane.values())
+                    .map(|(&l, b_kl)| (i * m + l, b_kl.clone()));
+                push_merged_lane(&mut indices, &mut values, lhs_entries, rhs_entries);
+                offsets.push(indices.len());
+            }
+        }
+
+        let pattern = SparsityPattern::try_from_offsets_and_indices(n * m, n * m, offsets, indices)
+            .expect("Internal error: Kronecker sum pattern must always be valid.");
+        Self::from_pattern_and_values(pattern, values)
+    }
+
+    /// Constructs the block diagonal matrix with the given matrices as its diagonal blocks.
+    #[must_use]
+    pub fn block_diag(blocks: &[&Self]) -> Self
+    where
+        T: Clone,
+    {
+        let major_dim = blocks.iter().map(|b| b.pattern().major_dim()).sum();
+        let minor_dim = blocks.iter().map(|b| b.pattern().minor_dim()).sum();
+        let nnz = blocks.iter().map(|b| b.pattern().nnz()).sum();
+
+        let mut offsets = Vec::with_capacity(major_dim + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut values = Vec::with_capacity(nnz);
+        offsets.push(0);
+        let mut minor_offset = 0;
+        for block in blocks {
+            let (block_offsets, block_indices, block_values) = block.cs_data();
+            let nnz_offset = indices.len();
+            offsets.extend(block_offsets[1..].iter().map(|&offset| offset + nnz_offset));
+            indices.extend(block_indices.iter().map(|&j| j + minor_offset));
+            values.extend_from_slice(block_values);
+            minor_offset += block.pattern().minor_dim();
+        }
+
+        let pattern =
+            SparsityPattern::try_from_offsets_and_indices(major_dim, minor_dim, offsets, indices)
+                .expect("Internal error: Block diagonal pattern must always be valid.");
+        Self::from_pattern_and_values(pattern, values)
+    }
+
+    /// Constructs a square matrix with the given diagonal, storing every diagonal entry
+    /// explicitly.
+    #[must_use]
+    pub fn from_diagonal(diagonal: Vec<T>) -> Self {
+        let n = diagonal.len();
+        let offsets: Vec<_> = (0..=n).collect();
+        let indices: Vec<_> = (0..n).collect();
+        let pattern = SparsityPattern::try_from_offsets_and_indices(n, n, offsets, indices)
+            .expect("Internal error: Diagonal pattern must always be valid.");
+        Self::from_pattern_and_values(pattern, diagonal)
+    }
+
+    /// Computes `self + sigma * I`, inserting the diagonal entries that are not explicitly
+    /// stored.
+    ///
+    /// For a non-square matrix, `sigma` is added to the entries `(i, i)` for
+    /// `i < min(major_dim, minor_dim)`.
+    #[must_use]
+    pub fn shift_diagonal(&self, sigma: T) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        let (major_dim, minor_dim) = (self.pattern().major_dim(), self.pattern().minor_dim());
+        let diagonal_len = major_dim.min(minor_dim);
+
+        let mut offsets = Vec::with_capacity(major_dim + 1);
+        let mut indices = Vec::with_capacity(self.pattern().nnz() + diagonal_len);
+        let mut values = Vec::with_capacity(self.pattern().nnz() + diagonal_len);
+        offsets.push(0);
+        for (i, lane) in self.lane_iter().enumerate() {
+            let entries = lane
+                .minor_indices()
+                .iter()
+                .copied()
+                .zip(lane.values().iter().cloned());
+            let shift = Some((i, sigma.clone())).filter(|_| i < diagonal_len);
+            push_merged_lane(&mut indices, &mut values, entries, shift.into_iter());
+            offsets.push(indices.len());
+        }
+
+        let pattern =
+            SparsityPattern::try_from_offsets_and_indices(major_dim, minor_dim, offsets, indices)
+                .expect("Internal error: Shifted pattern must always be valid.");
+        Self::from_pattern_and_values(pattern, values)
+    }
+}
+
+/// Pushes the union of two lanes, given as sequences of entries sorted by their minor index,
+/// adding the values of the entries that are present in both.
+fn push_merged_lane<T: ClosedAdd>(
+    indices: &mut Vec<usize>,
+    values: &mut Vec<T>,
+    lhs: impl Iterator<Item = (usize, T)>,
+    rhs: impl Iterator<Item = (usize, T)>,
+) {
+    let mut lhs = lhs.peekable();
+    let mut rhs = rhs.peekable();
+    loop {
+        let (j, v) = match (lhs.peek(), rhs.peek()) {
+            (Some(&(j_lhs, _)), Some(&(j_rhs, _))) if j_lhs == j_rhs => {
+                let (j, a) = lhs.next().unwrap();
+                let (_, b) = rhs.next().unwrap();
+                (j, a + b)
+            }
+            (Some(&(j_lhs, _)), Some(&(j_rhs, _))) if j_lhs < j_rhs => lhs.next().unwrap(),
+            (_, Some(_)) => rhs.next().unwrap(),
+            (Some(_), None) => lhs.next().unwrap(),
+            (None, None) => break,
+        };
+        indices.push(j);
+        values.push(v);
+    }
+}
+
+/// Returns the position of the given global minor index among the minor indices of a lane.
+fn find_minor_index<I: SparseIndex>(
+    minor_indices: &[I],
+    global_minor_index: usize,
+) -> Option<usize> {
+    // An index that can not be represented by the index type can not be explicitly stored
+    let global_minor_index = I::try_from_index(global_minor_index)?;
+    minor_indices.binary_search(&global_minor_index).ok()
+}
+
+fn get_entry_from_slices<'a, T, I: SparseIndex>(
     minor_dim: usize,
-    minor_indices: &'a [usize],
+    minor_indices: &'a [I],
     values: &'a [T],
     global_minor_index: usize,
 ) -> Option<SparseEntry<'a, T>> {
-    let local_index = minor_indices.binary_search(&global_minor_index);
-    if let Ok(local_index) = local_index {
+    let local_index = find_minor_index(minor_indices, global_minor_index);
+    if let Some(local_index) = local_index {
         Some(SparseEntry::NonZero(&values[local_index]))
     } else if global_minor_index < minor_dim {
         Some(SparseEntry::Zero)
@@ -256,14 +802,14 @@ fn get_entry_from_slices<'a, T>(
     }
 }
 
-fn get_mut_entry_from_slices<'a, T>(
+fn get_mut_entry_from_slices<'a, T, I: SparseIndex>(
     minor_dim: usize,
-    minor_indices: &'a [usize],
+    minor_indices: &'a [I],
     values: &'a mut [T],
     global_minor_indices: usize,
 ) -> Option<SparseEntryMut<'a, T>> {
-    let local_index = minor_indices.binary_search(&global_minor_indices);
-    if let Ok(local_index) = local_index {
+    let local_index = find_minor_index(minor_indices, global_minor_indices);
+    if let Some(local_index) = local_index {
         Some(SparseEntryMut::NonZero(&mut values[local_index]))
     } else if global_minor_indices < minor_dim {
         Some(SparseEntryMut::Zero)
@@ -273,106 +819,68 @@ fn get_mut_entry_from_slices<'a, T>(
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CsLane<'a, T> {
+pub struct CsLane<'a, T, I = usize> {
     minor_dim: usize,
-    minor_indices: &'a [usize],
+    minor_indices: &'a [I],
     values: &'a [T],
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct CsLaneMut<'a, T> {
+pub struct CsLaneMut<'a, T, I = usize> {
     minor_dim: usize,
-    minor_indices: &'a [usize],
+    minor_indices: &'a [I],
     values: &'a mut [T],
 }
 
-pub struct CsLaneIter<'a, T> {
+pub struct CsLaneIter<'a, T, I = usize> {
     // The index of the lane that will be returned on the next iteration
     current_lane_idx: usize,
-    pattern: &'a SparsityPattern,
-    remaining_values: &'a [T],
+    view: CsMatrixView<'a, T, I>,
 }
 
-impl<'a, T> CsLaneIter<'a, T> {
-    pub fn new(pattern: &'a SparsityPattern, values: &'a [T]) -> Self {
-        Self {
-            current_lane_idx: 0,
-            pattern,
-            remaining_values: values,
-        }
-    }
-}
-
-impl<'a, T> Iterator for CsLaneIter<'a, T>
+impl<'a, T, I: SparseIndex> Iterator for CsLaneIter<'a, T, I>
 where
     T: 'a,
 {
-    type Item = CsLane<'a, T>;
+    type Item = CsLane<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let lane = self.pattern.get_lane(self.current_lane_idx);
-        let minor_dim = self.pattern.minor_dim();
-
-        if let Some(minor_indices) = lane {
-            let count = minor_indices.len();
-            let values_in_lane = &self.remaining_values[..count];
-            self.remaining_values = &self.remaining_values[count..];
-            self.current_lane_idx += 1;
-
-            Some(CsLane {
-                minor_dim,
-                minor_indices,
-                values: values_in_lane,
-            })
-        } else {
-            None
-        }
+        let lane = self.view.get_lane(self.current_lane_idx)?;
+        self.current_lane_idx += 1;
+        Some(lane)
     }
 }
 
-pub struct CsLaneIterMut<'a, T> {
+pub struct CsLaneIterMut<'a, T, I = usize> {
     // The index of the lane that will be returned on the next iteration
     current_lane_idx: usize,
-    pattern: &'a SparsityPattern,
+    major_offsets: &'a [I],
+    minor_indices: &'a [I],
+    minor_dim: usize,
     remaining_values: &'a mut [T],
 }
 
-impl<'a, T> CsLaneIterMut<'a, T> {
-    pub fn new(pattern: &'a SparsityPattern, values: &'a mut [T]) -> Self {
-        Self {
-            current_lane_idx: 0,
-            pattern,
-            remaining_values: values,
-        }
-    }
-}
-
-impl<'a, T> Iterator for CsLaneIterMut<'a, T>
+impl<'a, T, I: SparseIndex> Iterator for CsLaneIterMut<'a, T, I>
 where
     T: 'a,
 {
-    type Item = CsLaneMut<'a, T>;
+    type Item = CsLaneMut<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let lane = self.pattern.get_lane(self.current_lane_idx);
-        let minor_dim = self.pattern.minor_dim();
-
-        if let Some(minor_indices) = lane {
-            let count = minor_indices.len();
-
-            let remaining = replace(&mut self.remaining_values, &mut []);
-            let (values_in_lane, remaining) = remaining.split_at_mut(count);
-            self.remaining_values = remaining;
-            self.current_lane_idx += 1;
-
-            Some(CsLaneMut {
-                minor_dim,
-                minor_indices,
-                values: values_in_lane,
-            })
-        } else {
-            None
-        }
+        let begin = self.major_offsets.get(self.current_lane_idx)?.index();
+        let end = self.major_offsets.get(self.current_lane_idx + 1)?.index();
+        let minor_indices = &self.minor_indices[begin..end];
+
+        let remaining = replace(&mut self.remaining_values, &mut []);
+        let (values_in_lane, remaining) = remaining.split_at_mut(end - begin);
+        self.remaining_values = remaining;
+        self.current_lane_idx += 1;
+
+        Some(CsLaneMut {
+            minor_dim: self.minor_dim,
+            minor_indices,
+            values: values_in_lane,
+        })
     }
 }
 
@@ -380,7 +888,7 @@ where
 /// methods delegated here by CsrMatrix and CscMatrix members for more information.
 macro_rules! impl_cs_lane_common_methods {
     ($name:ty) => {
-        impl<'a, T> $name {
+        impl<'a, T, I: SparseIndex> $name {
             #[inline]
             #[must_use]
             pub fn minor_dim(&self) -> usize {
@@ -395,7 +903,7 @@ macro_rules! impl_cs_lane_common_methods {
 
             #[inline]
             #[must_use]
-            pub fn minor_indices(&self) -> &[usize] {
+            pub fn minor_indices(&self) -> &[I] {
                 self.minor_indices
             }
 
@@ -419,15 +927,27 @@ macro_rules! impl_cs_lane_common_methods {
     };
 }
 
-impl_cs_lane_common_methods!(CsLane<'a, T>);
-impl_cs_lane_common_methods!(CsLaneMut<'a, T>);
+impl_cs_lane_common_methods!(CsLane<'a, T, I>);
+impl_cs_lane_common_methods!(CsLaneMut<'a, T, I>);
+
+impl<'a, T, I: SparseIndex> CsLane<'a, T, I> {
+    #[must_use]
+    pub fn to_sparse_vector(&self) -> SparseVector<T>
+    where
+        T: Clone,
+    {
+        let indices = self.minor_indices.iter().map(|i| i.index()).collect();
+        SparseVector::try_from_indices_and_values(self.minor_dim, indices, self.values.to_vec())
+            .expect("Internal error: Lane must be a valid sparse vector")
+    }
+}
 
-impl<'a, T> CsLaneMut<'a, T> {
+impl<'a, T, I: SparseIndex> CsLaneMut<'a, T, I> {
     pub fn values_mut(&mut self) -> &mut [T] {
         self.values
     }
 
-    pub fn indices_and_values_mut(&mut self) -> (&[usize], &mut [T]) {
+    pub fn indices_and_values_mut(&mut self) -> (&[I], &mut [T]) {
         (self.minor_indices, self.values)
     }
 
@@ -480,15 +1000,16 @@ impl<T> UninitVec<T> {
 ///
 /// This means that major and minor roles are switched. This is used for converting between CSR
 /// and CSC formats.
-pub fn transpose_cs<T>(
+pub fn transpose_cs<T, I>(
     major_dim: usize,
     minor_dim: usize,
-    source_major_offsets: &[usize],
-    source_minor_indices: &[usize],
+    source_major_offsets: &[I],
+    source_minor_indices: &[I],
     values: &[T],
-) -> (Vec<usize>, Vec<usize>, Vec<T>)
+) -> (Vec<I>, Vec<I>, Vec<T>)
 where
     T: Scalar,
+    I: SparseIndex,
 {
     assert_eq!(source_major_offsets.len(), major_dim + 1);
     assert_eq!(source_minor_indices.len(), values.len());
@@ -497,12 +1018,12 @@ where
     // Count the number of occurences of each minor index
     let mut minor_counts = vec![0; minor_dim];
     for minor_idx in source_minor_indices {
-        minor_counts[*minor_idx] += 1;
+        minor_counts[minor_idx.index()] += 1;
     }
     convert_counts_to_offsets(&mut minor_counts);
     let mut target_offsets = minor_counts;
     target_offsets.push(nnz);
-    let mut target_indices = vec![usize::MAX; nnz];
+    let mut target_indices = vec![I::from_index(0); nnz];
 
     // We have to use uninitialized storage, because we don't have any kind of "default" value
     // available for `T`. Unfortunately this necessitates some small amount of unsafe code
@@ -512,16 +1033,17 @@ where
     let mut current_target_major_counts = vec![0; minor_dim];
 
     for source_major_idx in 0..major_dim {
-        let source_lane_begin = source_major_offsets[source_major_idx];
-        let source_lane_end = source_major_offsets[source_major_idx + 1];
+        let source_lane_begin = source_major_offsets[source_major_idx].index();
+        let source_lane_end = source_major_offsets[source_major_idx + 1].index();
         let source_lane_indices = &source_minor_indices[source_lane_begin..source_lane_end];
         let source_lane_values = &values[source_lane_begin..source_lane_end];
 
         for (&source_minor_idx, val) in source_lane_indices.iter().zip(source_lane_values) {
             // Compute the offset in the target data for this particular source entry
+            let source_minor_idx = source_minor_idx.index();
             let target_lane_count = &mut current_target_major_counts[source_minor_idx];
             let entry_offset = target_offsets[source_minor_idx] + *target_lane_count;
-            target_indices[entry_offset] = source_major_idx;
+            target_indices[entry_offset] = I::from_index(source_major_idx);
             unsafe {
                 target_values.set(entry_offset, val.clone());
             }
@@ -532,6 +1054,7 @@ where
     // At this point, we should have written to each element in target_values exactly once,
     // so initialization should be sound
     let target_values = unsafe { target_values.assume_init() };
+    let target_offsets = target_offsets.into_iter().map(I::from_index).collect();
     (target_offsets, target_indices, target_values)
 }
 
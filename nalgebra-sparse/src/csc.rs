@@ -7,13 +7,20 @@
 mod csc_serde;
 
 use crate::cs;
-use crate::cs::{CsLane, CsLaneIter, CsLaneIterMut, CsLaneMut, CsMatrix};
+use crate::cs::{
+    CsLane, CsLaneIter, CsLaneIterMut, CsLaneMut, CsMatrix, CsMatrixView, CsMatrixViewMut,
+};
 use crate::csr::CsrMatrix;
-use crate::pattern::{SparsityPattern, SparsityPatternFormatError, SparsityPatternIter};
+use crate::pattern::{
+    validate_offsets_and_indices, SparseIndex, SparsityPattern, SparsityPatternFormatError,
+    SparsityPatternIter,
+};
+use crate::vector::SparseVector;
 use crate::{SparseEntry, SparseEntryMut, SparseFormatError, SparseFormatErrorKind};
 
-use nalgebra::Scalar;
-use num_traits::One;
+use nalgebra::storage::RawStorage;
+use nalgebra::{ClosedAdd, ClosedMul, ComplexField, Dim, Scalar, Vector};
+use num_traits::{One, Zero};
 use std::slice::{Iter, IterMut};
 
 /// A CSC representation of a sparse matrix.
@@ -127,9 +134,9 @@ use std::slice::{Iter, IterMut};
 ///
 /// [Wikipedia article]: https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_column_(CSC_or_CCS)
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CscMatrix<T> {
+pub struct CscMatrix<T, I = usize> {
     // Cols are major, rows are minor in the sparsity pattern
-    pub(crate) cs: CsMatrix<T>,
+    pub(crate) cs: CsMatrix<T, I>,
 }
 
 impl<T> CscMatrix<T> {
@@ -166,14 +173,7 @@ impl<T> CscMatrix<T> {
         row_indices: Vec<usize>,
         values: Vec<T>,
     ) -> Result<Self, SparseFormatError> {
-        let pattern = SparsityPattern::try_from_offsets_and_indices(
-            num_cols,
-            num_rows,
-            col_offsets,
-            row_indices,
-        )
-        .map_err(pattern_format_error_to_csc_error)?;
-        Self::try_from_pattern_and_values(pattern, values)
+        Self::try_from_csc_data_generic(num_rows, num_cols, col_offsets, row_indices, values)
     }
 
     /// Try to construct a CSC matrix from raw CSC data with unsorted row indices.
@@ -219,13 +219,37 @@ impl<T> CscMatrix<T> {
             Err(err) => Err(err),
         }
     }
+}
+
+impl<T, I: SparseIndex> CscMatrix<T, I> {
+    /// Try to construct a CSC matrix with an arbitrary index type from raw CSC data.
+    ///
+    /// This is the same as [`try_from_csc_data`](CscMatrix::try_from_csc_data), except that the
+    /// index type is not restricted to `usize`. In addition to the format requirements, an
+    /// error is returned if the dimensions can not be represented by the index type.
+    pub fn try_from_csc_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        col_offsets: Vec<I>,
+        row_indices: Vec<I>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        let pattern = SparsityPattern::try_from_offsets_and_indices_generic(
+            num_cols,
+            num_rows,
+            col_offsets,
+            row_indices,
+        )
+        .map_err(pattern_format_error_to_csc_error)?;
+        Self::try_from_pattern_and_values(pattern, values)
+    }
 
     /// Try to construct a CSC matrix from a sparsity pattern and associated non-zero values.
     ///
     /// Returns an error if the number of values does not match the number of minor indices
     /// in the pattern.
     pub fn try_from_pattern_and_values(
-        pattern: SparsityPattern,
+        pattern: SparsityPattern<I>,
         values: Vec<T>,
     ) -> Result<Self, SparseFormatError> {
         if pattern.nnz() == values.len() {
@@ -265,17 +289,36 @@ impl<T> CscMatrix<T> {
         self.pattern().nnz()
     }
 
+    /// Returns a view of the matrix, which borrows its data.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CscMatrixView<'_, T, I> {
+        CscMatrixView {
+            cs: self.cs.as_view(),
+        }
+    }
+
+    /// Returns a view of the matrix that allows its values, but not its sparsity pattern, to be
+    /// modified.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CscMatrixViewMut<'_, T, I> {
+        CscMatrixViewMut {
+            cs: self.cs.as_view_mut(),
+        }
+    }
+
     /// The column offsets defining part of the CSC format.
     #[inline]
     #[must_use]
-    pub fn col_offsets(&self) -> &[usize] {
+    pub fn col_offsets(&self) -> &[I] {
         self.pattern().major_offsets()
     }
 
     /// The row indices defining part of the CSC format.
     #[inline]
     #[must_use]
-    pub fn row_indices(&self) -> &[usize] {
+    pub fn row_indices(&self) -> &[I] {
         self.pattern().minor_indices()
     }
 
@@ -310,7 +353,7 @@ impl<T> CscMatrix<T> {
     /// let triplets: Vec<_> = csc.triplet_iter().map(|(i, j, v)| (i, j, *v)).collect();
     /// assert_eq!(triplets, vec![(0, 0, 1), (2, 0, 3), (1, 1, 2), (0, 2, 4)]);
     /// ```
-    pub fn triplet_iter(&self) -> CscTripletIter<'_, T> {
+    pub fn triplet_iter(&self) -> CscTripletIter<'_, T, I> {
         CscTripletIter {
             pattern_iter: self.pattern().entries(),
             values_iter: self.values().iter(),
@@ -340,7 +383,7 @@ impl<T> CscMatrix<T> {
     /// let triplets: Vec<_> = csc.triplet_iter().map(|(i, j, v)| (i, j, *v)).collect();
     /// assert_eq!(triplets, vec![(0, 0, 1), (2, 0, 0), (1, 1, 2), (0, 2, 4)]);
     /// ```
-    pub fn triplet_iter_mut(&mut self) -> CscTripletIterMut<'_, T> {
+    pub fn triplet_iter_mut(&mut self) -> CscTripletIterMut<'_, T, I> {
         let (pattern, values) = self.cs.pattern_and_values_mut();
         CscTripletIterMut {
             pattern_iter: pattern.entries(),
@@ -355,17 +398,30 @@ impl<T> CscMatrix<T> {
     /// Panics if column index is out of bounds.
     #[inline]
     #[must_use]
-    pub fn col(&self, index: usize) -> CscCol<'_, T> {
+    pub fn col(&self, index: usize) -> CscCol<'_, T, I> {
         self.get_col(index).expect("Row index must be in bounds")
     }
 
+    /// Return the column at the given column index as a sparse vector.
+    ///
+    /// Panics
+    /// ------
+    /// Panics if column index is out of bounds.
+    #[must_use]
+    pub fn col_as_vector(&self, index: usize) -> SparseVector<T>
+    where
+        T: Clone,
+    {
+        self.col(index).lane.to_sparse_vector()
+    }
+
     /// Mutable column access for the given column index.
     ///
     /// Panics
     /// ------
     /// Panics if column index is out of bounds.
     #[inline]
-    pub fn col_mut(&mut self, index: usize) -> CscColMut<'_, T> {
+    pub fn col_mut(&mut self, index: usize) -> CscColMut<'_, T, I> {
         self.get_col_mut(index)
             .expect("Row index must be in bounds")
     }
@@ -373,29 +429,28 @@ impl<T> CscMatrix<T> {
     /// Return the column at the given column index, or `None` if out of bounds.
     #[inline]
     #[must_use]
-    pub fn get_col(&self, index: usize) -> Option<CscCol<'_, T>> {
+    pub fn get_col(&self, index: usize) -> Option<CscCol<'_, T, I>> {
         self.cs.get_lane(index).map(|lane| CscCol { lane })
     }
 
     /// Mutable column access for the given column index, or `None` if out of bounds.
     #[inline]
     #[must_use]
-    pub fn get_col_mut(&mut self, index: usize) -> Option<CscColMut<'_, T>> {
+    pub fn get_col_mut(&mut self, index: usize) -> Option<CscColMut<'_, T, I>> {
         self.cs.get_lane_mut(index).map(|lane| CscColMut { lane })
     }
 
     /// An iterator over columns in the matrix.
-    pub fn col_iter(&self) -> CscColIter<'_, T> {
+    pub fn col_iter(&self) -> CscColIter<'_, T, I> {
         CscColIter {
-            lane_iter: CsLaneIter::new(self.pattern(), self.values()),
+            lane_iter: self.cs.lane_iter(),
         }
     }
 
     /// A mutable iterator over columns in the matrix.
-    pub fn col_iter_mut(&mut self) -> CscColIterMut<'_, T> {
-        let (pattern, values) = self.cs.pattern_and_values_mut();
+    pub fn col_iter_mut(&mut self) -> CscColIterMut<'_, T, I> {
         CscColIterMut {
-            lane_iter: CsLaneIterMut::new(pattern, values),
+            lane_iter: self.cs.lane_iter_mut(),
         }
     }
 
@@ -424,31 +479,31 @@ impl<T> CscMatrix<T> {
     /// assert_eq!(row_indices2, row_indices);
     /// assert_eq!(values2, values);
     /// ```
-    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    pub fn disassemble(self) -> (Vec<I>, Vec<I>, Vec<T>) {
         self.cs.disassemble()
     }
 
     /// Returns the sparsity pattern and values associated with this matrix.
-    pub fn into_pattern_and_values(self) -> (SparsityPattern, Vec<T>) {
+    pub fn into_pattern_and_values(self) -> (SparsityPattern<I>, Vec<T>) {
         self.cs.into_pattern_and_values()
     }
 
     /// Returns a reference to the sparsity pattern and a mutable reference to the values.
     #[inline]
-    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern, &mut [T]) {
+    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern<I>, &mut [T]) {
         self.cs.pattern_and_values_mut()
     }
 
     /// Returns a reference to the underlying sparsity pattern.
     #[must_use]
-    pub fn pattern(&self) -> &SparsityPattern {
+    pub fn pattern(&self) -> &SparsityPattern<I> {
         self.cs.pattern()
     }
 
     /// Reinterprets the CSC matrix as its transpose represented by a CSR matrix.
     ///
     /// This operation does not touch the CSC data, and is effectively a no-op.
-    pub fn transpose_as_csr(self) -> CsrMatrix<T> {
+    pub fn transpose_as_csr(self) -> CsrMatrix<T, I> {
         let (pattern, values) = self.cs.take_pattern_and_values();
         CsrMatrix::try_from_pattern_and_values(pattern, values).unwrap()
     }
@@ -504,16 +559,32 @@ impl<T> CscMatrix<T> {
 
     /// Returns a triplet of slices `(col_offsets, row_indices, values)` that make up the CSC data.
     #[must_use]
-    pub fn csc_data(&self) -> (&[usize], &[usize], &[T]) {
+    pub fn csc_data(&self) -> (&[I], &[I], &[T]) {
         self.cs.cs_data()
     }
 
     /// Returns a triplet of slices `(col_offsets, row_indices, values)` that make up the CSC data,
     /// where the `values` array is mutable.
-    pub fn csc_data_mut(&mut self) -> (&[usize], &[usize], &mut [T]) {
+    pub fn csc_data_mut(&mut self) -> (&[I], &[I], &mut [T]) {
         self.cs.cs_data_mut()
     }
 
+    /// Converts the offsets and indices of the matrix to a different index type.
+    ///
+    /// Returns an error if the dimensions or the number of non-zeros of the matrix can not be
+    /// represented by the new index type.
+    pub fn try_cast_indices<J: SparseIndex>(self) -> Result<CscMatrix<T, J>, SparseFormatError> {
+        let (pattern, values) = self.cs.take_pattern_and_values();
+        let pattern = pattern
+            .try_cast_indices()
+            .map_err(pattern_format_error_to_csc_error)?;
+        Ok(CscMatrix {
+            cs: CsMatrix::from_pattern_and_values(pattern, values),
+        })
+    }
+}
+
+impl<T> CscMatrix<T> {
     /// Creates a sparse matrix that contains only the explicit entries decided by the
     /// given predicate.
     #[must_use]
@@ -531,6 +602,56 @@ impl<T> CscMatrix<T> {
         }
     }
 
+    /// Removes the explicit entries for which the given predicate returns `false`, in place.
+    ///
+    /// This is the in-place counterpart of [`filter`](Self::filter), and does not allocate.
+    /// Returns the number of removed entries.
+    pub fn retain<P>(&mut self, mut predicate: P) -> usize
+    where
+        P: FnMut(usize, usize, &T) -> bool,
+    {
+        // Note: Predicate uses (row, col, value), so we have to switch around since
+        // cs uses (major, minor, value)
+        self.cs
+            .retain(|col_idx, row_idx, v| predicate(row_idx, col_idx, v))
+    }
+
+    /// Removes all explicitly stored zeros from the matrix, in place.
+    ///
+    /// Returns the number of removed entries.
+    pub fn eliminate_zeros(&mut self) -> usize
+    where
+        T: Zero,
+    {
+        self.retain(|_, _, v| !v.is_zero())
+    }
+
+    /// Removes all explicitly stored entries whose absolute value is at most `tol`, in place.
+    ///
+    /// With `tol` equal to zero, this removes the explicitly stored zeros of the matrix. Returns
+    /// the number of removed entries.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::CscMatrix;
+    /// # use nalgebra::DMatrix;
+    /// let dense = DMatrix::from_row_slice(2, 2, &[1.0, 1e-14, 0.0, -2.0]);
+    /// let mut csc = CscMatrix::from(&dense);
+    /// assert_eq!(csc.nnz(), 3);
+    ///
+    /// let num_removed = csc.prune(1e-12);
+    /// assert_eq!(num_removed, 1);
+    /// assert_eq!(csc.nnz(), 2);
+    /// ```
+    pub fn prune(&mut self, tol: T::RealField) -> usize
+    where
+        T: ComplexField,
+    {
+        self.retain(|_, _, v| v.clone().modulus() > tol)
+    }
+
     /// Returns a new matrix representing the upper triangular part of this matrix.
     ///
     /// The result includes the diagonal of the matrix.
@@ -564,14 +685,517 @@ impl<T> CscMatrix<T> {
         }
     }
 
+    /// Computes the Kronecker product of two CSC matrices.
+    ///
+    /// The sparsity pattern of the result is computed directly from the patterns of the factors,
+    /// and the result contains `self.nnz() * rhs.nnz()` explicitly stored entries.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::csc::CscMatrix;
+    /// # use nalgebra::DMatrix;
+    /// let a = DMatrix::from_row_slice(2, 2, &[1, 2, 0, 3]);
+    /// let b = DMatrix::from_row_slice(1, 2, &[1, -1]);
+    /// let kron = CscMatrix::from(&a).kronecker(&CscMatrix::from(&b));
+    /// assert_eq!(DMatrix::from(&kron), a.kronecker(&b));
+    /// ```
+    #[must_use]
+    pub fn kronecker(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedMul,
+    {
+        Self {
+            cs: self.cs.kronecker(&rhs.cs),
+        }
+    }
+
+    /// Computes the Kronecker sum `A ⊗ I + I ⊗ B` of the square matrices `A` (`self`) and `B`.
+    ///
+    /// The Kronecker sum of one-dimensional discrete Laplacians is the Laplacian on the
+    /// corresponding tensor product grid.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if either matrix is not square.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::csc::CscMatrix;
+    /// # use nalgebra::DMatrix;
+    /// // The 1D Laplacian on three nodes
+    /// let laplace_1d = CscMatrix::from(&DMatrix::from_row_slice(3, 3, &[
+    ///     2, -1, 0,
+    ///     -1, 2, -1,
+    ///     0, -1, 2,
+    /// ]));
+    /// // The 2D Laplacian on a 3 x 3 grid
+    /// let laplace_2d = laplace_1d.kronecker_sum(&laplace_1d);
+    /// assert_eq!(laplace_2d.nrows(), 9);
+    /// assert_eq!(laplace_2d.get_entry(4, 4).unwrap().into_value(), 4);
+    /// ```
+    #[must_use]
+    pub fn kronecker_sum(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        Self {
+            cs: self.cs.kronecker_sum(&rhs.cs),
+        }
+    }
+
+    /// Constructs a square CSC matrix with the given diagonal.
+    ///
+    /// Every diagonal entry is explicitly stored, including zeros.
+    #[must_use]
+    pub fn from_diagonal<D, S>(diagonal: &Vector<T, D, S>) -> Self
+    where
+        T: Scalar,
+        D: Dim,
+        S: RawStorage<T, D>,
+    {
+        Self {
+            cs: CsMatrix::from_diagonal(diagonal.iter().cloned().collect()),
+        }
+    }
+
+    /// Computes `self + sigma * I`.
+    ///
+    /// Diagonal entries that are not explicitly stored are inserted into the pattern of the
+    /// result. For a non-square matrix, `sigma` is added to the entries `(i, i)` with
+    /// `i < min(nrows, ncols)`.
+    #[must_use]
+    pub fn shift_diagonal(&self, sigma: T) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        Self {
+            cs: self.cs.shift_diagonal(sigma),
+        }
+    }
+}
+
+impl<T, I: SparseIndex> CscMatrix<T, I> {
     /// Compute the transpose of the matrix.
     #[must_use]
-    pub fn transpose(&self) -> CscMatrix<T>
+    pub fn transpose(&self) -> CscMatrix<T, I>
     where
         T: Scalar,
     {
         CsrMatrix::from(self).transpose_as_csc()
     }
+
+    /// Compute the adjoint, i.e. the conjugate transpose, of the matrix.
+    ///
+    /// For real matrices, this is the same as [`transpose`](CscMatrix::transpose).
+    #[must_use]
+    pub fn adjoint(&self) -> CscMatrix<T, I>
+    where
+        T: ComplexField,
+    {
+        let mut adjoint = self.transpose();
+        for v in adjoint.values_mut() {
+            *v = v.clone().conjugate();
+        }
+        adjoint
+    }
+}
+
+/// A CSC matrix that borrows its data.
+///
+/// A view references column offsets, row indices and values that are stored elsewhere, for
+/// example in memory-mapped buffers or in the data structures of another library. The data is
+/// validated once when the view is constructed, after which it can be used with the routines in
+/// [`ops::serial`](crate::ops::serial) and with the multiplication operators without being
+/// copied. A view of an owned [`CscMatrix`] is obtained with [`CscMatrix::as_view`], or by converting
+/// a reference to the matrix.
+///
+/// Examples
+/// --------
+///
+/// ```
+/// use nalgebra_sparse::csc::CscMatrixView;
+/// use nalgebra_sparse::ops::{serial::spmm_csc_dense, Op};
+/// use nalgebra::DVector;
+///
+/// // The data could just as well live in a memory-mapped file
+/// let col_offsets = [0, 1, 2, 3];
+/// let row_indices = [0, 1, 0];
+/// let values = [1.0, 2.0, 3.0];
+/// let a = CscMatrixView::try_from_csc_data(2, 3, &col_offsets, &row_indices, &values).unwrap();
+///
+/// let x = DVector::from_column_slice(&[1.0, 1.0, 1.0]);
+/// let mut y = DVector::zeros(2);
+/// spmm_csc_dense(0.0, &mut y, 1.0, Op::NoOp(a), Op::NoOp(&x));
+/// assert_eq!(y.as_slice(), &[4.0, 2.0]);
+/// assert_eq!(a * &x, y);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct CscMatrixView<'a, T, I = usize> {
+    pub(crate) cs: CsMatrixView<'a, T, I>,
+}
+
+// Manual implementations, since the derives would require `T: Copy` and `I: Copy`
+impl<'a, T, I> Clone for CscMatrixView<'a, T, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, I> Copy for CscMatrixView<'a, T, I> {}
+
+/// A CSC matrix that borrows its data and allows its values to be modified.
+///
+/// This is the mutable counterpart of [`CscMatrixView`]. Only the values can be modified, the sparsity
+/// pattern is immutable.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CscMatrixViewMut<'a, T, I = usize> {
+    pub(crate) cs: CsMatrixViewMut<'a, T, I>,
+}
+
+impl<'a, T> CscMatrixView<'a, T> {
+    /// Try to construct a CSC matrix view from borrowed raw CSC data.
+    ///
+    /// The data must satisfy the same requirements as for [`CscMatrix::try_from_csc_data`], and is
+    /// validated before the view is constructed. The data is not copied.
+    pub fn try_from_csc_data(
+        num_rows: usize,
+        num_cols: usize,
+        col_offsets: &'a [usize],
+        row_indices: &'a [usize],
+        values: &'a [T],
+    ) -> Result<Self, SparseFormatError> {
+        Self::try_from_csc_data_generic(num_rows, num_cols, col_offsets, row_indices, values)
+    }
+}
+
+impl<'a, T, I: SparseIndex> CscMatrixView<'a, T, I> {
+    /// Try to construct a CSC matrix view with an arbitrary index type from borrowed raw CSC data.
+    ///
+    /// This is the same as [`try_from_csc_data`](CscMatrixView::try_from_csc_data), except that the
+    /// index type is not restricted to `usize`.
+    pub fn try_from_csc_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        col_offsets: &'a [I],
+        row_indices: &'a [I],
+        values: &'a [T],
+    ) -> Result<Self, SparseFormatError> {
+        validate_csc_view_data(num_rows, num_cols, col_offsets, row_indices, values.len())?;
+        Ok(Self {
+            cs: CsMatrixView::from_cs_data_unchecked(num_rows, col_offsets, row_indices, values),
+        })
+    }
+
+    /// Returns a copy of the view.
+    ///
+    /// This only exists for symmetry with [`CscMatrixViewMut::as_view`], since views are `Copy`.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CscMatrixView<'_, T, I> {
+        *self
+    }
+}
+
+impl<'a, T> CscMatrixViewMut<'a, T> {
+    /// Try to construct a mutable CSC matrix view from borrowed raw CSC data.
+    ///
+    /// The data must satisfy the same requirements as for [`CscMatrix::try_from_csc_data`], and is
+    /// validated before the view is constructed. The data is not copied.
+    pub fn try_from_csc_data(
+        num_rows: usize,
+        num_cols: usize,
+        col_offsets: &'a [usize],
+        row_indices: &'a [usize],
+        values: &'a mut [T],
+    ) -> Result<Self, SparseFormatError> {
+        Self::try_from_csc_data_generic(num_rows, num_cols, col_offsets, row_indices, values)
+    }
+}
+
+impl<'a, T, I: SparseIndex> CscMatrixViewMut<'a, T, I> {
+    /// Try to construct a mutable CSC matrix view with an arbitrary index type from borrowed raw
+    /// CSC data.
+    ///
+    /// This is the same as [`try_from_csc_data`](CscMatrixViewMut::try_from_csc_data), except that the
+    /// index type is not restricted to `usize`.
+    pub fn try_from_csc_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        col_offsets: &'a [I],
+        row_indices: &'a [I],
+        values: &'a mut [T],
+    ) -> Result<Self, SparseFormatError> {
+        validate_csc_view_data(num_rows, num_cols, col_offsets, row_indices, values.len())?;
+        Ok(Self {
+            cs: CsMatrixViewMut::from_cs_data_unchecked(num_rows, col_offsets, row_indices, values),
+        })
+    }
+
+    /// Reborrows the data as an immutable view.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CscMatrixView<'_, T, I> {
+        CscMatrixView {
+            cs: self.cs.as_view(),
+        }
+    }
+
+    /// Reborrows the data as a mutable view.
+    ///
+    /// This makes it possible to pass the view to several routines that consume a mutable view.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CscMatrixViewMut<'_, T, I> {
+        CscMatrixViewMut {
+            cs: self.cs.as_view_mut(),
+        }
+    }
+
+    /// Mutable access to the non-zero values.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        self.cs.values_mut()
+    }
+
+    /// A mutable iterator over non-zero triplets (i, j, v).
+    pub fn triplet_iter_mut(&mut self) -> CscTripletIterMut<'_, T, I> {
+        let (offsets, indices, values) = self.cs.as_view_mut().into_cs_data_mut();
+        CscTripletIterMut {
+            pattern_iter: SparsityPatternIter::from_offsets_and_indices(offsets, indices),
+            values_mut_iter: values.iter_mut(),
+        }
+    }
+
+    /// Mutable column access for the given column index.
+    ///
+    /// Panics
+    /// ------
+    /// Panics if column index is out of bounds.
+    #[inline]
+    pub fn col_mut(&mut self, index: usize) -> CscColMut<'_, T, I> {
+        self.get_col_mut(index)
+            .expect("Column index must be in bounds")
+    }
+
+    /// Mutable column access for the given column index, or `None` if out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get_col_mut(&mut self, index: usize) -> Option<CscColMut<'_, T, I>> {
+        self.cs.get_lane_mut(index).map(|lane| CscColMut { lane })
+    }
+
+    /// A mutable iterator over columns in the matrix.
+    pub fn col_iter_mut(&mut self) -> CscColIterMut<'_, T, I> {
+        CscColIterMut {
+            lane_iter: self.cs.lane_iter_mut(),
+        }
+    }
+
+    /// Returns a mutable entry for the given row/col indices, or `None` if the indices are out
+    /// of bounds.
+    pub fn get_entry_mut(
+        &mut self,
+        row_index: usize,
+        col_index: usize,
+    ) -> Option<SparseEntryMut<'_, T>> {
+        self.cs.get_entry_mut(col_index, row_index)
+    }
+}
+
+/// Implement the methods common to both CscMatrixView and CscMatrixViewMut
+macro_rules! impl_csc_view_common_methods {
+    ($name:ty) => {
+        impl<'a, T, I: SparseIndex> $name {
+            /// The number of rows in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn nrows(&self) -> usize {
+                self.as_view().cs.minor_dim()
+            }
+
+            /// The number of columns in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn ncols(&self) -> usize {
+                self.as_view().cs.major_dim()
+            }
+
+            /// The number of non-zeros in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn nnz(&self) -> usize {
+                self.as_view().cs.nnz()
+            }
+
+            /// The column offsets defining part of the CSC format.
+            #[inline]
+            #[must_use]
+            pub fn col_offsets(&self) -> &[I] {
+                let (offsets, _, _) = self.as_view().cs.cs_data();
+                offsets
+            }
+
+            /// The row indices defining part of the CSC format.
+            #[inline]
+            #[must_use]
+            pub fn row_indices(&self) -> &[I] {
+                let (_, indices, _) = self.as_view().cs.cs_data();
+                indices
+            }
+
+            /// The non-zero values defining part of the CSC format.
+            #[inline]
+            #[must_use]
+            pub fn values(&self) -> &[T] {
+                self.as_view().cs.values()
+            }
+
+            /// An iterator over non-zero triplets (i, j, v).
+            pub fn triplet_iter(&self) -> CscTripletIter<'_, T, I> {
+                let cs = self.as_view().cs;
+                CscTripletIter {
+                    pattern_iter: cs.entries(),
+                    values_iter: cs.values().iter(),
+                }
+            }
+
+            /// Return the column at the given column index.
+            ///
+            /// Panics
+            /// ------
+            /// Panics if column index is out of bounds.
+            #[inline]
+            #[must_use]
+            pub fn col(&self, index: usize) -> CscCol<'_, T, I> {
+                self.get_col(index).expect("Column index must be in bounds")
+            }
+
+            /// Return the column at the given column index as a sparse vector.
+            ///
+            /// Panics
+            /// ------
+            /// Panics if column index is out of bounds.
+            #[must_use]
+            pub fn col_as_vector(&self, index: usize) -> SparseVector<T>
+            where
+                T: Clone,
+            {
+                self.col(index).lane.to_sparse_vector()
+            }
+
+            /// Return the column at the given column index, or `None` if out of bounds.
+            #[inline]
+            #[must_use]
+            pub fn get_col(&self, index: usize) -> Option<CscCol<'_, T, I>> {
+                self.as_view()
+                    .cs
+                    .get_lane(index)
+                    .map(|lane| CscCol { lane })
+            }
+
+            /// An iterator over columns in the matrix.
+            pub fn col_iter(&self) -> CscColIter<'_, T, I> {
+                CscColIter {
+                    lane_iter: self.as_view().cs.lane_iter(),
+                }
+            }
+
+            /// Returns an entry for the given row/col indices, or `None` if the indices are out
+            /// of bounds.
+            #[must_use]
+            pub fn get_entry(
+                &self,
+                row_index: usize,
+                col_index: usize,
+            ) -> Option<SparseEntry<'_, T>> {
+                self.as_view().cs.get_entry(col_index, row_index)
+            }
+
+            /// Computes the transpose of the matrix, which is stored in a new matrix.
+            #[must_use]
+            pub fn transpose(&self) -> CscMatrix<T, I>
+            where
+                T: Scalar,
+            {
+                CscMatrix {
+                    cs: self.as_view().cs.transpose(),
+                }
+            }
+
+            /// Computes the adjoint, i.e. the conjugate transpose, of the matrix, which is
+            /// stored in a new matrix.
+            #[must_use]
+            pub fn adjoint(&self) -> CscMatrix<T, I>
+            where
+                T: ComplexField,
+            {
+                let mut adjoint = self.transpose();
+                for v in adjoint.values_mut() {
+                    *v = v.clone().conjugate();
+                }
+                adjoint
+            }
+
+            /// Copies the data into an owned matrix.
+            #[must_use]
+            pub fn clone_owned(&self) -> CscMatrix<T, I>
+            where
+                T: Clone,
+            {
+                CscMatrix {
+                    cs: self.as_view().cs.into_owned(),
+                }
+            }
+        }
+    };
+}
+
+impl_csc_view_common_methods!(CscMatrixView<'a, T, I>);
+impl_csc_view_common_methods!(CscMatrixViewMut<'a, T, I>);
+
+impl<'a, T, I: SparseIndex> From<&'a CscMatrix<T, I>> for CscMatrixView<'a, T, I> {
+    fn from(matrix: &'a CscMatrix<T, I>) -> Self {
+        matrix.as_view()
+    }
+}
+
+impl<'a, T, I: SparseIndex> From<&'a mut CscMatrix<T, I>> for CscMatrixViewMut<'a, T, I> {
+    fn from(matrix: &'a mut CscMatrix<T, I>) -> Self {
+        matrix.as_view_mut()
+    }
+}
+
+impl<'a, T, I: SparseIndex> From<CscMatrixViewMut<'a, T, I>> for CscMatrixView<'a, T, I> {
+    fn from(view: CscMatrixViewMut<'a, T, I>) -> Self {
+        CscMatrixView {
+            cs: view.cs.into_view(),
+        }
+    }
+}
+
+/// Validates borrowed CSC data, mapping errors in the same way as for owned matrices.
+fn validate_csc_view_data<I: SparseIndex>(
+    num_rows: usize,
+    num_cols: usize,
+    col_offsets: &[I],
+    row_indices: &[I],
+    num_values: usize,
+) -> Result<(), SparseFormatError> {
+    validate_offsets_and_indices(num_cols, num_rows, col_offsets, row_indices)
+        .map_err(pattern_format_error_to_csc_error)?;
+    if row_indices.len() == num_values {
+        Ok(())
+    } else {
+        Err(SparseFormatError::from_kind_and_msg(
+            SparseFormatErrorKind::InvalidStructure,
+            "Number of values and row indices must be the same",
+        ))
+    }
 }
 
 /// Convert pattern format errors into more meaningful CSC-specific errors.
@@ -607,17 +1231,21 @@ fn pattern_format_error_to_csc_error(err: SparsityPatternFormatError) -> SparseF
         PatternDuplicateEntry => {
             E::from_kind_and_msg(K::DuplicateEntry, "Matrix data contains duplicate entries.")
         }
+        IndexOverflow => E::from_kind_and_msg(
+            K::IndexOutOfBounds,
+            "Matrix dimensions or number of non-zeros can not be represented by the index type.",
+        ),
     }
 }
 
 /// Iterator type for iterating over triplets in a CSC matrix.
 #[derive(Debug)]
-pub struct CscTripletIter<'a, T> {
-    pattern_iter: SparsityPatternIter<'a>,
+pub struct CscTripletIter<'a, T, I = usize> {
+    pattern_iter: SparsityPatternIter<'a, I>,
     values_iter: Iter<'a, T>,
 }
 
-impl<'a, T: Clone> CscTripletIter<'a, T> {
+impl<'a, T: Clone, I: SparseIndex> CscTripletIter<'a, T, I> {
     /// Adapts the triplet iterator to return owned values.
     ///
     /// The triplet iterator returns references to the values. This method adapts the iterator
@@ -628,7 +1256,7 @@ impl<'a, T: Clone> CscTripletIter<'a, T> {
     }
 }
 
-impl<'a, T> Iterator for CscTripletIter<'a, T> {
+impl<'a, T, I: SparseIndex> Iterator for CscTripletIter<'a, T, I> {
     type Item = (usize, usize, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -644,12 +1272,12 @@ impl<'a, T> Iterator for CscTripletIter<'a, T> {
 
 /// Iterator type for mutably iterating over triplets in a CSC matrix.
 #[derive(Debug)]
-pub struct CscTripletIterMut<'a, T> {
-    pattern_iter: SparsityPatternIter<'a>,
+pub struct CscTripletIterMut<'a, T, I = usize> {
+    pattern_iter: SparsityPatternIter<'a, I>,
     values_mut_iter: IterMut<'a, T>,
 }
 
-impl<'a, T> Iterator for CscTripletIterMut<'a, T> {
+impl<'a, T, I: SparseIndex> Iterator for CscTripletIterMut<'a, T, I> {
     type Item = (usize, usize, &'a mut T);
 
     #[inline]
@@ -666,8 +1294,8 @@ impl<'a, T> Iterator for CscTripletIterMut<'a, T> {
 
 /// An immutable representation of a column in a CSC matrix.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CscCol<'a, T> {
-    lane: CsLane<'a, T>,
+pub struct CscCol<'a, T, I = usize> {
+    lane: CsLane<'a, T, I>,
 }
 
 /// A mutable representation of a column in a CSC matrix.
@@ -675,14 +1303,14 @@ pub struct CscCol<'a, T> {
 /// Note that only explicitly stored entries can be mutated. The sparsity pattern belonging
 /// to the column cannot be modified.
 #[derive(Debug, PartialEq, Eq)]
-pub struct CscColMut<'a, T> {
-    lane: CsLaneMut<'a, T>,
+pub struct CscColMut<'a, T, I = usize> {
+    lane: CsLaneMut<'a, T, I>,
 }
 
 /// Implement the methods common to both CscCol and CscColMut
 macro_rules! impl_csc_col_common_methods {
     ($name:ty) => {
-        impl<'a, T> $name {
+        impl<'a, T, I: SparseIndex> $name {
             /// The number of global rows in the column.
             #[inline]
             #[must_use]
@@ -700,7 +1328,7 @@ macro_rules! impl_csc_col_common_methods {
             /// The row indices corresponding to explicitly stored entries in this column.
             #[inline]
             #[must_use]
-            pub fn row_indices(&self) -> &[usize] {
+            pub fn row_indices(&self) -> &[I] {
                 self.lane.minor_indices()
             }
 
@@ -723,10 +1351,10 @@ macro_rules! impl_csc_col_common_methods {
     };
 }
 
-impl_csc_col_common_methods!(CscCol<'a, T>);
-impl_csc_col_common_methods!(CscColMut<'a, T>);
+impl_csc_col_common_methods!(CscCol<'a, T, I>);
+impl_csc_col_common_methods!(CscColMut<'a, T, I>);
 
-impl<'a, T> CscColMut<'a, T> {
+impl<'a, T, I: SparseIndex> CscColMut<'a, T, I> {
     /// Mutable access to the values corresponding to explicitly stored entries in this column.
     pub fn values_mut(&mut self) -> &mut [T] {
         self.lane.values_mut()
@@ -737,7 +1365,7 @@ impl<'a, T> CscColMut<'a, T> {
     ///
     /// This method primarily facilitates low-level access for methods that process data stored
     /// in CSC format directly.
-    pub fn rows_and_values_mut(&mut self) -> (&[usize], &mut [T]) {
+    pub fn rows_and_values_mut(&mut self) -> (&[I], &mut [T]) {
         self.lane.indices_and_values_mut()
     }
 
@@ -749,12 +1377,12 @@ impl<'a, T> CscColMut<'a, T> {
 }
 
 /// Column iterator for [CscMatrix](struct.CscMatrix.html).
-pub struct CscColIter<'a, T> {
-    lane_iter: CsLaneIter<'a, T>,
+pub struct CscColIter<'a, T, I = usize> {
+    lane_iter: CsLaneIter<'a, T, I>,
 }
 
-impl<'a, T> Iterator for CscColIter<'a, T> {
-    type Item = CscCol<'a, T>;
+impl<'a, T, I: SparseIndex> Iterator for CscColIter<'a, T, I> {
+    type Item = CscCol<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.lane_iter.next().map(|lane| CscCol { lane })
@@ -762,15 +1390,15 @@ impl<'a, T> Iterator for CscColIter<'a, T> {
 }
 
 /// Mutable column iterator for [CscMatrix](struct.CscMatrix.html).
-pub struct CscColIterMut<'a, T> {
-    lane_iter: CsLaneIterMut<'a, T>,
+pub struct CscColIterMut<'a, T, I = usize> {
+    lane_iter: CsLaneIterMut<'a, T, I>,
 }
 
-impl<'a, T> Iterator for CscColIterMut<'a, T>
+impl<'a, T, I: SparseIndex> Iterator for CscColIterMut<'a, T, I>
 where
     T: 'a,
 {
-    type Item = CscColMut<'a, T>;
+    type Item = CscColMut<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.lane_iter.next().map(|lane| CscColMut { lane })
@@ -7,13 +7,20 @@
 mod csr_serde;
 
 use crate::cs;
-use crate::cs::{CsLane, CsLaneIter, CsLaneIterMut, CsLaneMut, CsMatrix};
+use crate::cs::{
+    CsLane, CsLaneIter, CsLaneIterMut, CsLaneMut, CsMatrix, CsMatrixView, CsMatrixViewMut,
+};
 use crate::csc::CscMatrix;
-use crate::pattern::{SparsityPattern, SparsityPatternFormatError, SparsityPatternIter};
+use crate::pattern::{
+    validate_offsets_and_indices, SparseIndex, SparsityPattern, SparsityPatternFormatError,
+    SparsityPatternIter,
+};
+use crate::vector::SparseVector;
 use crate::{SparseEntry, SparseEntryMut, SparseFormatError, SparseFormatErrorKind};
 
-use nalgebra::Scalar;
-use num_traits::One;
+use nalgebra::storage::RawStorage;
+use nalgebra::{ClosedAdd, ClosedMul, ComplexField, Dim, Scalar, Vector};
+use num_traits::{One, Zero};
 
 use std::slice::{Iter, IterMut};
 
@@ -128,9 +135,9 @@ use std::slice::{Iter, IterMut};
 ///
 /// [Wikipedia article]: https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_row_(CSR,_CRS_or_Yale_format)
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CsrMatrix<T> {
+pub struct CsrMatrix<T, I = usize> {
     // Rows are major, cols are minor in the sparsity pattern
-    pub(crate) cs: CsMatrix<T>,
+    pub(crate) cs: CsMatrix<T, I>,
 }
 
 impl<T> CsrMatrix<T> {
@@ -167,14 +174,7 @@ impl<T> CsrMatrix<T> {
         col_indices: Vec<usize>,
         values: Vec<T>,
     ) -> Result<Self, SparseFormatError> {
-        let pattern = SparsityPattern::try_from_offsets_and_indices(
-            num_rows,
-            num_cols,
-            row_offsets,
-            col_indices,
-        )
-        .map_err(pattern_format_error_to_csr_error)?;
-        Self::try_from_pattern_and_values(pattern, values)
+        Self::try_from_csr_data_generic(num_rows, num_cols, row_offsets, col_indices, values)
     }
 
     /// Try to construct a CSR matrix from raw CSR data with unsorted column indices.
@@ -220,13 +220,37 @@ impl<T> CsrMatrix<T> {
             Err(err) => Err(err),
         }
     }
+}
+
+impl<T, I: SparseIndex> CsrMatrix<T, I> {
+    /// Try to construct a CSR matrix with an arbitrary index type from raw CSR data.
+    ///
+    /// This is the same as [`try_from_csr_data`](CsrMatrix::try_from_csr_data), except that the
+    /// index type is not restricted to `usize`. In addition to the format requirements, an
+    /// error is returned if the dimensions can not be represented by the index type.
+    pub fn try_from_csr_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        row_offsets: Vec<I>,
+        col_indices: Vec<I>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        let pattern = SparsityPattern::try_from_offsets_and_indices_generic(
+            num_rows,
+            num_cols,
+            row_offsets,
+            col_indices,
+        )
+        .map_err(pattern_format_error_to_csr_error)?;
+        Self::try_from_pattern_and_values(pattern, values)
+    }
 
     /// Try to construct a CSR matrix from a sparsity pattern and associated non-zero values.
     ///
     /// Returns an error if the number of values does not match the number of minor indices
     /// in the pattern.
     pub fn try_from_pattern_and_values(
-        pattern: SparsityPattern,
+        pattern: SparsityPattern<I>,
         values: Vec<T>,
     ) -> Result<Self, SparseFormatError> {
         if pattern.nnz() == values.len() {
@@ -266,10 +290,29 @@ impl<T> CsrMatrix<T> {
         self.cs.pattern().nnz()
     }
 
+    /// Returns a view of the matrix, which borrows its data.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CsrMatrixView<'_, T, I> {
+        CsrMatrixView {
+            cs: self.cs.as_view(),
+        }
+    }
+
+    /// Returns a view of the matrix that allows its values, but not its sparsity pattern, to be
+    /// modified.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CsrMatrixViewMut<'_, T, I> {
+        CsrMatrixViewMut {
+            cs: self.cs.as_view_mut(),
+        }
+    }
+
     /// The row offsets defining part of the CSR format.
     #[inline]
     #[must_use]
-    pub fn row_offsets(&self) -> &[usize] {
+    pub fn row_offsets(&self) -> &[I] {
         let (offsets, _, _) = self.cs.cs_data();
         offsets
     }
@@ -277,7 +320,7 @@ impl<T> CsrMatrix<T> {
     /// The column indices defining part of the CSR format.
     #[inline]
     #[must_use]
-    pub fn col_indices(&self) -> &[usize] {
+    pub fn col_indices(&self) -> &[I] {
         let (_, indices, _) = self.cs.cs_data();
         indices
     }
@@ -313,7 +356,7 @@ impl<T> CsrMatrix<T> {
     /// let triplets: Vec<_> = csr.triplet_iter().map(|(i, j, v)| (i, j, *v)).collect();
     /// assert_eq!(triplets, vec![(0, 0, 1), (0, 2, 2), (1, 1, 3), (2, 0, 4)]);
     /// ```
-    pub fn triplet_iter(&self) -> CsrTripletIter<'_, T> {
+    pub fn triplet_iter(&self) -> CsrTripletIter<'_, T, I> {
         CsrTripletIter {
             pattern_iter: self.pattern().entries(),
             values_iter: self.values().iter(),
@@ -343,7 +386,7 @@ impl<T> CsrMatrix<T> {
     /// let triplets: Vec<_> = csr.triplet_iter().map(|(i, j, v)| (i, j, *v)).collect();
     /// assert_eq!(triplets, vec![(0, 0, 1), (0, 2, 2), (1, 1, 3), (2, 0, 0)]);
     /// ```
-    pub fn triplet_iter_mut(&mut self) -> CsrTripletIterMut<'_, T> {
+    pub fn triplet_iter_mut(&mut self) -> CsrTripletIterMut<'_, T, I> {
         let (pattern, values) = self.cs.pattern_and_values_mut();
         CsrTripletIterMut {
             pattern_iter: pattern.entries(),
@@ -358,17 +401,30 @@ impl<T> CsrMatrix<T> {
     /// Panics if row index is out of bounds.
     #[inline]
     #[must_use]
-    pub fn row(&self, index: usize) -> CsrRow<'_, T> {
+    pub fn row(&self, index: usize) -> CsrRow<'_, T, I> {
         self.get_row(index).expect("Row index must be in bounds")
     }
 
+    /// Return the row at the given row index as a sparse vector.
+    ///
+    /// Panics
+    /// ------
+    /// Panics if row index is out of bounds.
+    #[must_use]
+    pub fn row_as_vector(&self, index: usize) -> SparseVector<T>
+    where
+        T: Clone,
+    {
+        self.row(index).lane.to_sparse_vector()
+    }
+
     /// Mutable row access for the given row index.
     ///
     /// Panics
     /// ------
     /// Panics if row index is out of bounds.
     #[inline]
-    pub fn row_mut(&mut self, index: usize) -> CsrRowMut<'_, T> {
+    pub fn row_mut(&mut self, index: usize) -> CsrRowMut<'_, T, I> {
         self.get_row_mut(index)
             .expect("Row index must be in bounds")
     }
@@ -376,29 +432,28 @@ impl<T> CsrMatrix<T> {
     /// Return the row at the given row index, or `None` if out of bounds.
     #[inline]
     #[must_use]
-    pub fn get_row(&self, index: usize) -> Option<CsrRow<'_, T>> {
+    pub fn get_row(&self, index: usize) -> Option<CsrRow<'_, T, I>> {
         self.cs.get_lane(index).map(|lane| CsrRow { lane })
     }
 
     /// Mutable row access for the given row index, or `None` if out of bounds.
     #[inline]
     #[must_use]
-    pub fn get_row_mut(&mut self, index: usize) -> Option<CsrRowMut<'_, T>> {
+    pub fn get_row_mut(&mut self, index: usize) -> Option<CsrRowMut<'_, T, I>> {
         self.cs.get_lane_mut(index).map(|lane| CsrRowMut { lane })
     }
 
     /// An iterator over rows in the matrix.
-    pub fn row_iter(&self) -> CsrRowIter<'_, T> {
+    pub fn row_iter(&self) -> CsrRowIter<'_, T, I> {
         CsrRowIter {
-            lane_iter: CsLaneIter::new(self.pattern(), self.values()),
+            lane_iter: self.cs.lane_iter(),
         }
     }
 
     /// A mutable iterator over rows in the matrix.
-    pub fn row_iter_mut(&mut self) -> CsrRowIterMut<'_, T> {
-        let (pattern, values) = self.cs.pattern_and_values_mut();
+    pub fn row_iter_mut(&mut self) -> CsrRowIterMut<'_, T, I> {
         CsrRowIterMut {
-            lane_iter: CsLaneIterMut::new(pattern, values),
+            lane_iter: self.cs.lane_iter_mut(),
         }
     }
 
@@ -427,31 +482,31 @@ impl<T> CsrMatrix<T> {
     /// assert_eq!(col_indices2, col_indices);
     /// assert_eq!(values2, values);
     /// ```
-    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    pub fn disassemble(self) -> (Vec<I>, Vec<I>, Vec<T>) {
         self.cs.disassemble()
     }
 
     /// Returns the sparsity pattern and values associated with this matrix.
-    pub fn into_pattern_and_values(self) -> (SparsityPattern, Vec<T>) {
+    pub fn into_pattern_and_values(self) -> (SparsityPattern<I>, Vec<T>) {
         self.cs.into_pattern_and_values()
     }
 
     /// Returns a reference to the sparsity pattern and a mutable reference to the values.
     #[inline]
-    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern, &mut [T]) {
+    pub fn pattern_and_values_mut(&mut self) -> (&SparsityPattern<I>, &mut [T]) {
         self.cs.pattern_and_values_mut()
     }
 
     /// Returns a reference to the underlying sparsity pattern.
     #[must_use]
-    pub fn pattern(&self) -> &SparsityPattern {
+    pub fn pattern(&self) -> &SparsityPattern<I> {
         self.cs.pattern()
     }
 
     /// Reinterprets the CSR matrix as its transpose represented by a CSC matrix.
     ///
     /// This operation does not touch the CSR data, and is effectively a no-op.
-    pub fn transpose_as_csc(self) -> CscMatrix<T> {
+    pub fn transpose_as_csc(self) -> CscMatrix<T, I> {
         let (pattern, values) = self.cs.take_pattern_and_values();
         CscMatrix::try_from_pattern_and_values(pattern, values).unwrap()
     }
@@ -507,16 +562,32 @@ impl<T> CsrMatrix<T> {
 
     /// Returns a triplet of slices `(row_offsets, col_indices, values)` that make up the CSR data.
     #[must_use]
-    pub fn csr_data(&self) -> (&[usize], &[usize], &[T]) {
+    pub fn csr_data(&self) -> (&[I], &[I], &[T]) {
         self.cs.cs_data()
     }
 
     /// Returns a triplet of slices `(row_offsets, col_indices, values)` that make up the CSR data,
     /// where the `values` array is mutable.
-    pub fn csr_data_mut(&mut self) -> (&[usize], &[usize], &mut [T]) {
+    pub fn csr_data_mut(&mut self) -> (&[I], &[I], &mut [T]) {
         self.cs.cs_data_mut()
     }
 
+    /// Converts the offsets and indices of the matrix to a different index type.
+    ///
+    /// Returns an error if the dimensions or the number of non-zeros of the matrix can not be
+    /// represented by the new index type.
+    pub fn try_cast_indices<J: SparseIndex>(self) -> Result<CsrMatrix<T, J>, SparseFormatError> {
+        let (pattern, values) = self.cs.take_pattern_and_values();
+        let pattern = pattern
+            .try_cast_indices()
+            .map_err(pattern_format_error_to_csr_error)?;
+        Ok(CsrMatrix {
+            cs: CsMatrix::from_pattern_and_values(pattern, values),
+        })
+    }
+}
+
+impl<T> CsrMatrix<T> {
     /// Creates a sparse matrix that contains only the explicit entries decided by the
     /// given predicate.
     #[must_use]
@@ -532,6 +603,53 @@ impl<T> CsrMatrix<T> {
         }
     }
 
+    /// Removes the explicit entries for which the given predicate returns `false`, in place.
+    ///
+    /// This is the in-place counterpart of [`filter`](Self::filter), and does not allocate.
+    /// Returns the number of removed entries.
+    pub fn retain<P>(&mut self, predicate: P) -> usize
+    where
+        P: FnMut(usize, usize, &T) -> bool,
+    {
+        self.cs.retain(predicate)
+    }
+
+    /// Removes all explicitly stored zeros from the matrix, in place.
+    ///
+    /// Returns the number of removed entries.
+    pub fn eliminate_zeros(&mut self) -> usize
+    where
+        T: Zero,
+    {
+        self.retain(|_, _, v| !v.is_zero())
+    }
+
+    /// Removes all explicitly stored entries whose absolute value is at most `tol`, in place.
+    ///
+    /// With `tol` equal to zero, this removes the explicitly stored zeros of the matrix. Returns
+    /// the number of removed entries.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::CsrMatrix;
+    /// # use nalgebra::DMatrix;
+    /// let dense = DMatrix::from_row_slice(2, 2, &[1.0, 1e-14, 0.0, -2.0]);
+    /// let mut csr = CsrMatrix::from(&dense);
+    /// assert_eq!(csr.nnz(), 3);
+    ///
+    /// let num_removed = csr.prune(1e-12);
+    /// assert_eq!(num_removed, 1);
+    /// assert_eq!(csr.nnz(), 2);
+    /// ```
+    pub fn prune(&mut self, tol: T::RealField) -> usize
+    where
+        T: ComplexField,
+    {
+        self.retain(|_, _, v| v.clone().modulus() > tol)
+    }
+
     /// Returns a new matrix representing the upper triangular part of this matrix.
     ///
     /// The result includes the diagonal of the matrix.
@@ -565,14 +683,517 @@ impl<T> CsrMatrix<T> {
         }
     }
 
+    /// Computes the Kronecker product of two CSR matrices.
+    ///
+    /// The sparsity pattern of the result is computed directly from the patterns of the factors,
+    /// and the result contains `self.nnz() * rhs.nnz()` explicitly stored entries.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::csr::CsrMatrix;
+    /// # use nalgebra::DMatrix;
+    /// let a = DMatrix::from_row_slice(2, 2, &[1, 2, 0, 3]);
+    /// let b = DMatrix::from_row_slice(1, 2, &[1, -1]);
+    /// let kron = CsrMatrix::from(&a).kronecker(&CsrMatrix::from(&b));
+    /// assert_eq!(DMatrix::from(&kron), a.kronecker(&b));
+    /// ```
+    #[must_use]
+    pub fn kronecker(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedMul,
+    {
+        Self {
+            cs: self.cs.kronecker(&rhs.cs),
+        }
+    }
+
+    /// Computes the Kronecker sum `A ⊗ I + I ⊗ B` of the square matrices `A` (`self`) and `B`.
+    ///
+    /// The Kronecker sum of one-dimensional discrete Laplacians is the Laplacian on the
+    /// corresponding tensor product grid.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if either matrix is not square.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::csr::CsrMatrix;
+    /// # use nalgebra::DMatrix;
+    /// // The 1D Laplacian on three nodes
+    /// let laplace_1d = CsrMatrix::from(&DMatrix::from_row_slice(3, 3, &[
+    ///     2, -1, 0,
+    ///     -1, 2, -1,
+    ///     0, -1, 2,
+    /// ]));
+    /// // The 2D Laplacian on a 3 x 3 grid
+    /// let laplace_2d = laplace_1d.kronecker_sum(&laplace_1d);
+    /// assert_eq!(laplace_2d.nrows(), 9);
+    /// assert_eq!(laplace_2d.get_entry(4, 4).unwrap().into_value(), 4);
+    /// ```
+    #[must_use]
+    pub fn kronecker_sum(&self, rhs: &Self) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        Self {
+            cs: self.cs.kronecker_sum(&rhs.cs),
+        }
+    }
+
+    /// Constructs a square CSR matrix with the given diagonal.
+    ///
+    /// Every diagonal entry is explicitly stored, including zeros.
+    #[must_use]
+    pub fn from_diagonal<D, S>(diagonal: &Vector<T, D, S>) -> Self
+    where
+        T: Scalar,
+        D: Dim,
+        S: RawStorage<T, D>,
+    {
+        Self {
+            cs: CsMatrix::from_diagonal(diagonal.iter().cloned().collect()),
+        }
+    }
+
+    /// Computes `self + sigma * I`.
+    ///
+    /// Diagonal entries that are not explicitly stored are inserted into the pattern of the
+    /// result. For a non-square matrix, `sigma` is added to the entries `(i, i)` with
+    /// `i < min(nrows, ncols)`.
+    #[must_use]
+    pub fn shift_diagonal(&self, sigma: T) -> Self
+    where
+        T: Scalar + ClosedAdd,
+    {
+        Self {
+            cs: self.cs.shift_diagonal(sigma),
+        }
+    }
+}
+
+impl<T, I: SparseIndex> CsrMatrix<T, I> {
     /// Compute the transpose of the matrix.
     #[must_use]
-    pub fn transpose(&self) -> CsrMatrix<T>
+    pub fn transpose(&self) -> CsrMatrix<T, I>
     where
         T: Scalar,
     {
         CscMatrix::from(self).transpose_as_csr()
     }
+
+    /// Compute the adjoint, i.e. the conjugate transpose, of the matrix.
+    ///
+    /// For real matrices, this is the same as [`transpose`](CsrMatrix::transpose).
+    #[must_use]
+    pub fn adjoint(&self) -> CsrMatrix<T, I>
+    where
+        T: ComplexField,
+    {
+        let mut adjoint = self.transpose();
+        for v in adjoint.values_mut() {
+            *v = v.clone().conjugate();
+        }
+        adjoint
+    }
+}
+
+/// A CSR matrix that borrows its data.
+///
+/// A view references row offsets, column indices and values that are stored elsewhere, for
+/// example in memory-mapped buffers or in the data structures of another library. The data is
+/// validated once when the view is constructed, after which it can be used with the routines in
+/// [`ops::serial`](crate::ops::serial) and with the multiplication operators without being
+/// copied. A view of an owned [`CsrMatrix`] is obtained with [`CsrMatrix::as_view`], or by converting
+/// a reference to the matrix.
+///
+/// Examples
+/// --------
+///
+/// ```
+/// use nalgebra_sparse::csr::CsrMatrixView;
+/// use nalgebra_sparse::ops::{serial::spmm_csr_dense, Op};
+/// use nalgebra::DVector;
+///
+/// // The data could just as well live in a memory-mapped file
+/// let row_offsets = [0, 2, 3];
+/// let col_indices = [0, 2, 1];
+/// let values = [1.0, 2.0, 3.0];
+/// let a = CsrMatrixView::try_from_csr_data(2, 3, &row_offsets, &col_indices, &values).unwrap();
+///
+/// let x = DVector::from_column_slice(&[1.0, 1.0, 1.0]);
+/// let mut y = DVector::zeros(2);
+/// spmm_csr_dense(0.0, &mut y, 1.0, Op::NoOp(a), Op::NoOp(&x));
+/// assert_eq!(y.as_slice(), &[3.0, 3.0]);
+/// assert_eq!(a * &x, y);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsrMatrixView<'a, T, I = usize> {
+    pub(crate) cs: CsMatrixView<'a, T, I>,
+}
+
+// Manual implementations, since the derives would require `T: Copy` and `I: Copy`
+impl<'a, T, I> Clone for CsrMatrixView<'a, T, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, I> Copy for CsrMatrixView<'a, T, I> {}
+
+/// A CSR matrix that borrows its data and allows its values to be modified.
+///
+/// This is the mutable counterpart of [`CsrMatrixView`]. Only the values can be modified, the sparsity
+/// pattern is immutable.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsrMatrixViewMut<'a, T, I = usize> {
+    pub(crate) cs: CsMatrixViewMut<'a, T, I>,
+}
+
+impl<'a, T> CsrMatrixView<'a, T> {
+    /// Try to construct a CSR matrix view from borrowed raw CSR data.
+    ///
+    /// The data must satisfy the same requirements as for [`CsrMatrix::try_from_csr_data`], and is
+    /// validated before the view is constructed. The data is not copied.
+    pub fn try_from_csr_data(
+        num_rows: usize,
+        num_cols: usize,
+        row_offsets: &'a [usize],
+        col_indices: &'a [usize],
+        values: &'a [T],
+    ) -> Result<Self, SparseFormatError> {
+        Self::try_from_csr_data_generic(num_rows, num_cols, row_offsets, col_indices, values)
+    }
+}
+
+impl<'a, T, I: SparseIndex> CsrMatrixView<'a, T, I> {
+    /// Try to construct a CSR matrix view with an arbitrary index type from borrowed raw CSR data.
+    ///
+    /// This is the same as [`try_from_csr_data`](CsrMatrixView::try_from_csr_data), except that the
+    /// index type is not restricted to `usize`.
+    pub fn try_from_csr_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        row_offsets: &'a [I],
+        col_indices: &'a [I],
+        values: &'a [T],
+    ) -> Result<Self, SparseFormatError> {
+        validate_csr_view_data(num_rows, num_cols, row_offsets, col_indices, values.len())?;
+        Ok(Self {
+            cs: CsMatrixView::from_cs_data_unchecked(num_cols, row_offsets, col_indices, values),
+        })
+    }
+
+    /// Returns a copy of the view.
+    ///
+    /// This only exists for symmetry with [`CsrMatrixViewMut::as_view`], since views are `Copy`.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CsrMatrixView<'_, T, I> {
+        *self
+    }
+}
+
+impl<'a, T> CsrMatrixViewMut<'a, T> {
+    /// Try to construct a mutable CSR matrix view from borrowed raw CSR data.
+    ///
+    /// The data must satisfy the same requirements as for [`CsrMatrix::try_from_csr_data`], and is
+    /// validated before the view is constructed. The data is not copied.
+    pub fn try_from_csr_data(
+        num_rows: usize,
+        num_cols: usize,
+        row_offsets: &'a [usize],
+        col_indices: &'a [usize],
+        values: &'a mut [T],
+    ) -> Result<Self, SparseFormatError> {
+        Self::try_from_csr_data_generic(num_rows, num_cols, row_offsets, col_indices, values)
+    }
+}
+
+impl<'a, T, I: SparseIndex> CsrMatrixViewMut<'a, T, I> {
+    /// Try to construct a mutable CSR matrix view with an arbitrary index type from borrowed raw
+    /// CSR data.
+    ///
+    /// This is the same as [`try_from_csr_data`](CsrMatrixViewMut::try_from_csr_data), except that the
+    /// index type is not restricted to `usize`.
+    pub fn try_from_csr_data_generic(
+        num_rows: usize,
+        num_cols: usize,
+        row_offsets: &'a [I],
+        col_indices: &'a [I],
+        values: &'a mut [T],
+    ) -> Result<Self, SparseFormatError> {
+        validate_csr_view_data(num_rows, num_cols, row_offsets, col_indices, values.len())?;
+        Ok(Self {
+            cs: CsMatrixViewMut::from_cs_data_unchecked(num_cols, row_offsets, col_indices, values),
+        })
+    }
+
+    /// Reborrows the data as an immutable view.
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> CsrMatrixView<'_, T, I> {
+        CsrMatrixView {
+            cs: self.cs.as_view(),
+        }
+    }
+
+    /// Reborrows the data as a mutable view.
+    ///
+    /// This makes it possible to pass the view to several routines that consume a mutable view.
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> CsrMatrixViewMut<'_, T, I> {
+        CsrMatrixViewMut {
+            cs: self.cs.as_view_mut(),
+        }
+    }
+
+    /// Mutable access to the non-zero values.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        self.cs.values_mut()
+    }
+
+    /// A mutable iterator over non-zero triplets (i, j, v).
+    pub fn triplet_iter_mut(&mut self) -> CsrTripletIterMut<'_, T, I> {
+        let (offsets, indices, values) = self.cs.as_view_mut().into_cs_data_mut();
+        CsrTripletIterMut {
+            pattern_iter: SparsityPatternIter::from_offsets_and_indices(offsets, indices),
+            values_mut_iter: values.iter_mut(),
+        }
+    }
+
+    /// Mutable row access for the given row index.
+    ///
+    /// Panics
+    /// ------
+    /// Panics if row index is out of bounds.
+    #[inline]
+    pub fn row_mut(&mut self, index: usize) -> CsrRowMut<'_, T, I> {
+        self.get_row_mut(index)
+            .expect("Row index must be in bounds")
+    }
+
+    /// Mutable row access for the given row index, or `None` if out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get_row_mut(&mut self, index: usize) -> Option<CsrRowMut<'_, T, I>> {
+        self.cs.get_lane_mut(index).map(|lane| CsrRowMut { lane })
+    }
+
+    /// A mutable iterator over rows in the matrix.
+    pub fn row_iter_mut(&mut self) -> CsrRowIterMut<'_, T, I> {
+        CsrRowIterMut {
+            lane_iter: self.cs.lane_iter_mut(),
+        }
+    }
+
+    /// Returns a mutable entry for the given row/col indices, or `None` if the indices are out
+    /// of bounds.
+    pub fn get_entry_mut(
+        &mut self,
+        row_index: usize,
+        col_index: usize,
+    ) -> Option<SparseEntryMut<'_, T>> {
+        self.cs.get_entry_mut(row_index, col_index)
+    }
+}
+
+/// Implement the methods common to both CsrMatrixView and CsrMatrixViewMut
+macro_rules! impl_csr_view_common_methods {
+    ($name:ty) => {
+        impl<'a, T, I: SparseIndex> $name {
+            /// The number of rows in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn nrows(&self) -> usize {
+                self.as_view().cs.major_dim()
+            }
+
+            /// The number of columns in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn ncols(&self) -> usize {
+                self.as_view().cs.minor_dim()
+            }
+
+            /// The number of non-zeros in the matrix.
+            #[inline]
+            #[must_use]
+            pub fn nnz(&self) -> usize {
+                self.as_view().cs.nnz()
+            }
+
+            /// The row offsets defining part of the CSR format.
+            #[inline]
+            #[must_use]
+            pub fn row_offsets(&self) -> &[I] {
+                let (offsets, _, _) = self.as_view().cs.cs_data();
+                offsets
+            }
+
+            /// The column indices defining part of the CSR format.
+            #[inline]
+            #[must_use]
+            pub fn col_indices(&self) -> &[I] {
+                let (_, indices, _) = self.as_view().cs.cs_data();
+                indices
+            }
+
+            /// The non-zero values defining part of the CSR format.
+            #[inline]
+            #[must_use]
+            pub fn values(&self) -> &[T] {
+                self.as_view().cs.values()
+            }
+
+            /// An iterator over non-zero triplets (i, j, v).
+            pub fn triplet_iter(&self) -> CsrTripletIter<'_, T, I> {
+                let cs = self.as_view().cs;
+                CsrTripletIter {
+                    pattern_iter: cs.entries(),
+                    values_iter: cs.values().iter(),
+                }
+            }
+
+            /// Return the row at the given row index.
+            ///
+            /// Panics
+            /// ------
+            /// Panics if row index is out of bounds.
+            #[inline]
+            #[must_use]
+            pub fn row(&self, index: usize) -> CsrRow<'_, T, I> {
+                self.get_row(index).expect("Row index must be in bounds")
+            }
+
+            /// Return the row at the given row index as a sparse vector.
+            ///
+            /// Panics
+            /// ------
+            /// Panics if row index is out of bounds.
+            #[must_use]
+            pub fn row_as_vector(&self, index: usize) -> SparseVector<T>
+            where
+                T: Clone,
+            {
+                self.row(index).lane.to_sparse_vector()
+            }
+
+            /// Return the row at the given row index, or `None` if out of bounds.
+            #[inline]
+            #[must_use]
+            pub fn get_row(&self, index: usize) -> Option<CsrRow<'_, T, I>> {
+                self.as_view()
+                    .cs
+                    .get_lane(index)
+                    .map(|lane| CsrRow { lane })
+            }
+
+            /// An iterator over rows in the matrix.
+            pub fn row_iter(&self) -> CsrRowIter<'_, T, I> {
+                CsrRowIter {
+                    lane_iter: self.as_view().cs.lane_iter(),
+                }
+            }
+
+            /// Returns an entry for the given row/col indices, or `None` if the indices are out
+            /// of bounds.
+            #[must_use]
+            pub fn get_entry(
+                &self,
+                row_index: usize,
+                col_index: usize,
+            ) -> Option<SparseEntry<'_, T>> {
+                self.as_view().cs.get_entry(row_index, col_index)
+            }
+
+            /// Computes the transpose of the matrix, which is stored in a new matrix.
+            #[must_use]
+            pub fn transpose(&self) -> CsrMatrix<T, I>
+            where
+                T: Scalar,
+            {
+                CsrMatrix {
+                    cs: self.as_view().cs.transpose(),
+                }
+            }
+
+            /// Computes the adjoint, i.e. the conjugate transpose, of the matrix, which is
+            /// stored in a new matrix.
+            #[must_use]
+            pub fn adjoint(&self) -> CsrMatrix<T, I>
+            where
+                T: ComplexField,
+            {
+                let mut adjoint = self.transpose();
+                for v in adjoint.values_mut() {
+                    *v = v.clone().conjugate();
+                }
+                adjoint
+            }
+
+            /// Copies the data into an owned matrix.
+            #[must_use]
+            pub fn clone_owned(&self) -> CsrMatrix<T, I>
+            where
+                T: Clone,
+            {
+                CsrMatrix {
+                    cs: self.as_view().cs.into_owned(),
+                }
+            }
+        }
+    };
+}
+
+impl_csr_view_common_methods!(CsrMatrixView<'a, T, I>);
+impl_csr_view_common_methods!(CsrMatrixViewMut<'a, T, I>);
+
+impl<'a, T, I: SparseIndex> From<&'a CsrMatrix<T, I>> for CsrMatrixView<'a, T, I> {
+    fn from(matrix: &'a CsrMatrix<T, I>) -> Self {
+        matrix.as_view()
+    }
+}
+
+impl<'a, T, I: SparseIndex> From<&'a mut CsrMatrix<T, I>> for CsrMatrixViewMut<'a, T, I> {
+    fn from(matrix: &'a mut CsrMatrix<T, I>) -> Self {
+        matrix.as_view_mut()
+    }
+}
+
+impl<'a, T, I: SparseIndex> From<CsrMatrixViewMut<'a, T, I>> for CsrMatrixView<'a, T, I> {
+    fn from(view: CsrMatrixViewMut<'a, T, I>) -> Self {
+        CsrMatrixView {
+            cs: view.cs.into_view(),
+        }
+    }
+}
+
+/// Validates borrowed CSR data, mapping errors in the same way as for owned matrices.
+fn validate_csr_view_data<I: SparseIndex>(
+    num_rows: usize,
+    num_cols: usize,
+    row_offsets: &[I],
+    col_indices: &[I],
+    num_values: usize,
+) -> Result<(), SparseFormatError> {
+    validate_offsets_and_indices(num_rows, num_cols, row_offsets, col_indices)
+        .map_err(pattern_format_error_to_csr_error)?;
+    if col_indices.len() == num_values {
+        Ok(())
+    } else {
+        Err(SparseFormatError::from_kind_and_msg(
+            SparseFormatErrorKind::InvalidStructure,
+            "Number of values and column indices must be the same",
+        ))
+    }
 }
 
 /// Convert pattern format errors into more meaningful CSR-specific errors.
@@ -608,17 +1229,21 @@ fn pattern_format_error_to_csr_error(err: SparsityPatternFormatError) -> SparseF
         PatternDuplicateEntry => {
             E::from_kind_and_msg(K::DuplicateEntry, "Matrix data contains duplicate entries.")
         }
+        IndexOverflow => E::from_kind_and_msg(
+            K::IndexOutOfBounds,
+            "Matrix dimensions or number of non-zeros can not be represented by the index type.",
+        ),
     }
 }
 
 /// Iterator type for iterating over triplets in a CSR matrix.
 #[derive(Debug)]
-pub struct CsrTripletIter<'a, T> {
-    pattern_iter: SparsityPatternIter<'a>,
+pub struct CsrTripletIter<'a, T, I = usize> {
+    pattern_iter: SparsityPatternIter<'a, I>,
     values_iter: Iter<'a, T>,
 }
 
-impl<'a, T: Clone> CsrTripletIter<'a, T> {
+impl<'a, T: Clone, I: SparseIndex> CsrTripletIter<'a, T, I> {
     /// Adapts the triplet iterator to return owned values.
     ///
     /// The triplet iterator returns references to the values. This method adapts the iterator
@@ -629,7 +1254,7 @@ impl<'a, T: Clone> CsrTripletIter<'a, T> {
     }
 }
 
-impl<'a, T> Iterator for CsrTripletIter<'a, T> {
+impl<'a, T, I: SparseIndex> Iterator for CsrTripletIter<'a, T, I> {
     type Item = (usize, usize, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -645,12 +1270,12 @@ impl<'a, T> Iterator for CsrTripletIter<'a, T> {
 
 /// Iterator type for mutably iterating over triplets in a CSR matrix.
 #[derive(Debug)]
-pub struct CsrTripletIterMut<'a, T> {
-    pattern_iter: SparsityPatternIter<'a>,
+pub struct CsrTripletIterMut<'a, T, I = usize> {
+    pattern_iter: SparsityPatternIter<'a, I>,
     values_mut_iter: IterMut<'a, T>,
 }
 
-impl<'a, T> Iterator for CsrTripletIterMut<'a, T> {
+impl<'a, T, I: SparseIndex> Iterator for CsrTripletIterMut<'a, T, I> {
     type Item = (usize, usize, &'a mut T);
 
     #[inline]
@@ -667,8 +1292,8 @@ impl<'a, T> Iterator for CsrTripletIterMut<'a, T> {
 
 /// An immutable representation of a row in a CSR matrix.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CsrRow<'a, T> {
-    lane: CsLane<'a, T>,
+pub struct CsrRow<'a, T, I = usize> {
+    lane: CsLane<'a, T, I>,
 }
 
 /// A mutable representation of a row in a CSR matrix.
@@ -676,14 +1301,14 @@ pub struct CsrRow<'a, T> {
 /// Note that only explicitly stored entries can be mutated. The sparsity pattern belonging
 /// to the row cannot be modified.
 #[derive(Debug, PartialEq, Eq)]
-pub struct CsrRowMut<'a, T> {
-    lane: CsLaneMut<'a, T>,
+pub struct CsrRowMut<'a, T, I = usize> {
+    lane: CsLaneMut<'a, T, I>,
 }
 
 /// Implement the methods common to both CsrRow and CsrRowMut
 macro_rules! impl_csr_row_common_methods {
     ($name:ty) => {
-        impl<'a, T> $name {
+        impl<'a, T, I: SparseIndex> $name {
             /// The number of global columns in the row.
             #[inline]
             #[must_use]
@@ -701,7 +1326,7 @@ macro_rules! impl_csr_row_common_methods {
             /// The column indices corresponding to explicitly stored entries in this row.
             #[inline]
             #[must_use]
-            pub fn col_indices(&self) -> &[usize] {
+            pub fn col_indices(&self) -> &[I] {
                 self.lane.minor_indices()
             }
 
@@ -725,10 +1350,10 @@ macro_rules! impl_csr_row_common_methods {
     };
 }
 
-impl_csr_row_common_methods!(CsrRow<'a, T>);
-impl_csr_row_common_methods!(CsrRowMut<'a, T>);
+impl_csr_row_common_methods!(CsrRow<'a, T, I>);
+impl_csr_row_common_methods!(CsrRowMut<'a, T, I>);
 
-impl<'a, T> CsrRowMut<'a, T> {
+impl<'a, T, I: SparseIndex> CsrRowMut<'a, T, I> {
     /// Mutable access to the values corresponding to explicitly stored entries in this row.
     #[inline]
     pub fn values_mut(&mut self) -> &mut [T] {
@@ -741,7 +1366,7 @@ impl<'a, T> CsrRowMut<'a, T> {
     /// This method primarily facilitates low-level access for methods that process data stored
     /// in CSR format directly.
     #[inline]
-    pub fn cols_and_values_mut(&mut self) -> (&[usize], &mut [T]) {
+    pub fn cols_and_values_mut(&mut self) -> (&[I], &mut [T]) {
         self.lane.indices_and_values_mut()
     }
 
@@ -754,12 +1379,12 @@ impl<'a, T> CsrRowMut<'a, T> {
 }
 
 /// Row iterator for [CsrMatrix](struct.CsrMatrix.html).
-pub struct CsrRowIter<'a, T> {
-    lane_iter: CsLaneIter<'a, T>,
+pub struct CsrRowIter<'a, T, I = usize> {
+    lane_iter: CsLaneIter<'a, T, I>,
 }
 
-impl<'a, T> Iterator for CsrRowIter<'a, T> {
-    type Item = CsrRow<'a, T>;
+impl<'a, T, I: SparseIndex> Iterator for CsrRowIter<'a, T, I> {
+    type Item = CsrRow<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.lane_iter.next().map(|lane| CsrRow { lane })
@@ -767,15 +1392,15 @@ impl<'a, T> Iterator for CsrRowIter<'a, T> {
 }
 
 /// Mutable row iterator for [CsrMatrix](struct.CsrMatrix.html).
-pub struct CsrRowIterMut<'a, T> {
-    lane_iter: CsLaneIterMut<'a, T>,
+pub struct CsrRowIterMut<'a, T, I = usize> {
+    lane_iter: CsLaneIterMut<'a, T, I>,
 }
 
-impl<'a, T> Iterator for CsrRowIterMut<'a, T>
+impl<'a, T, I: SparseIndex> Iterator for CsrRowIterMut<'a, T, I>
 where
     T: 'a,
 {
-    type Item = CsrRowMut<'a, T>;
+    type Item = CsrRowMut<'a, T, I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.lane_iter.next().map(|lane| CsrRowMut { lane })
@@ -0,0 +1,308 @@
+//! An implementation of the DIA (diagonal) sparse matrix format.
+//!
+//! This is the module-level documentation. See [`DiaMatrix`] for the main documentation of the
+//! DIA implementation.
+
+use crate::{SparseFormatError, SparseFormatErrorKind};
+
+use nalgebra::Scalar;
+
+/// A DIA representation of a sparse matrix.
+///
+/// The diagonal (DIA) format stores a sparse matrix as a collection of dense diagonals. It is
+/// well-suited for banded matrices, such as those arising from finite difference
+/// discretizations on structured grids, for which it requires no index data per entry.
+/// Matrices whose non-zeros are scattered across many diagonals are however stored very
+/// inefficiently.
+///
+/// # Usage
+///
+/// ```
+/// use nalgebra_sparse::dia::DiaMatrix;
+/// use nalgebra::DMatrix;
+/// use matrixcompare::assert_matrix_eq;
+///
+/// // A 3x4 matrix with the main diagonal, the first superdiagonal and the first subdiagonal
+/// let diagonal_offsets = vec![-1, 0, 1];
+/// let values = vec![
+///     // Subdiagonal: entries (1, 0), (2, 1)
+///     1.0, 2.0,
+///     // Main diagonal: entries (0, 0), (1, 1), (2, 2)
+///     3.0, 4.0, 5.0,
+///     // Superdiagonal: entries (0, 1), (1, 2), (2, 3)
+///     6.0, 7.0, 8.0,
+/// ];
+/// let dia = DiaMatrix::try_from_diagonals(3, 4, diagonal_offsets, values)
+///     .expect("DIA data must conform to format specifications");
+///
+/// let dense = DMatrix::from_row_slice(3, 4, &[
+///     3.0, 6.0, 0.0, 0.0,
+///     1.0, 4.0, 7.0, 0.0,
+///     0.0, 2.0, 5.0, 8.0,
+/// ]);
+/// assert_matrix_eq!(dia, dense);
+/// assert_eq!(dia.get_diagonal(1), Some(&[6.0, 7.0, 8.0][..]));
+/// ```
+///
+/// # Format
+///
+/// The diagonal with offset `k` consists of the entries `(i, j)` with `j - i == k`. Positive
+/// offsets denote superdiagonals and negative offsets denote subdiagonals. An `m x n` matrix
+/// with `d` stored diagonals is represented by the following two arrays:
+///
+/// - `diagonal_offsets`, an array of `d` signed integers.
+/// - `values`, an array holding the entries of the stored diagonals, one diagonal after the
+///   other.
+///
+/// Each diagonal is stored in full and without padding, starting at the entry in the top-left
+/// corner: the diagonal with offset `k >= 0` starts at entry `(0, k)`, and the diagonal with
+/// offset `k < 0` starts at entry `(-k, 0)`. The following invariants must be upheld and are
+/// enforced by the data structure:
+///
+/// - `-m < k < n` for every diagonal offset `k`.
+/// - `diagonal_offsets` is strictly increasing, i.e. the offsets are sorted and unique.
+/// - The length of `values` is the sum of the lengths of the stored diagonals.
+///
+/// Every entry of a stored diagonal is considered to be explicitly stored, even if it is zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaMatrix<T> {
+    nrows: usize,
+    ncols: usize,
+    diagonal_offsets: Vec<isize>,
+    // Offsets into `values` for each diagonal, with length `diagonal_offsets.len() + 1`
+    value_offsets: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> DiaMatrix<T> {
+    /// Create a zero DIA matrix with no explicitly stored diagonals.
+    pub fn zeros(nrows: usize, ncols: usize) -> Self {
+        Self {
+            nrows,
+            ncols,
+            diagonal_offsets: Vec::new(),
+            value_offsets: vec![0],
+            values: Vec::new(),
+        }
+    }
+
+    /// Try to construct a DIA matrix from diagonal offsets and the concatenated values of the
+    /// diagonals.
+    ///
+    /// An error is returned if the data given does not conform to the DIA storage format.
+    /// See the documentation for [DiaMatrix](struct.DiaMatrix.html) for more information.
+    pub fn try_from_diagonals(
+        nrows: usize,
+        ncols: usize,
+        diagonal_offsets: Vec<isize>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        use SparseFormatError as E;
+        use SparseFormatErrorKind as K;
+
+        let mut value_offsets = Vec::with_capacity(diagonal_offsets.len() + 1);
+        value_offsets.push(0);
+        let mut prev_offset = None;
+        for &k in &diagonal_offsets {
+            if !diagonal_in_bounds(nrows, ncols, k) {
+                return Err(E::from_kind_and_msg(
+                    K::IndexOutOfBounds,
+                    "Diagonal offsets are out of bounds.",
+                ));
+            }
+            match prev_offset {
+                Some(prev) if prev == k => {
+                    return Err(E::from_kind_and_msg(
+                        K::DuplicateEntry,
+                        "Matrix data contains duplicate diagonals.",
+                    ));
+                }
+                Some(prev) if prev > k => {
+                    return Err(E::from_kind_and_msg(
+                        K::InvalidStructure,
+                        "Diagonal offsets are not monotonically increasing (sorted).",
+                    ));
+                }
+                _ => {}
+            }
+            prev_offset = Some(k);
+            let last = *value_offsets.last().unwrap();
+            value_offsets.push(last + diagonal_len(nrows, ncols, k));
+        }
+
+        if *value_offsets.last().unwrap() != values.len() {
+            return Err(E::from_kind_and_msg(
+                K::InvalidStructure,
+                "Number of values must be equal to the total length of the stored diagonals",
+            ));
+        }
+
+        Ok(Self {
+            nrows,
+            ncols,
+            diagonal_offsets,
+            value_offsets,
+            values,
+        })
+    }
+
+    /// The number of rows in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// The number of columns in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The number of explicitly stored entries in the matrix.
+    ///
+    /// This is the total length of the stored diagonals, since every entry of a stored diagonal
+    /// is explicitly stored, even if it is zero.
+    #[inline]
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The number of stored diagonals.
+    #[inline]
+    #[must_use]
+    pub fn num_diagonals(&self) -> usize {
+        self.diagonal_offsets.len()
+    }
+
+    /// The offsets of the stored diagonals, in increasing order.
+    #[inline]
+    #[must_use]
+    pub fn diagonal_offsets(&self) -> &[isize] {
+        &self.diagonal_offsets
+    }
+
+    /// The values of all stored diagonals, one diagonal after the other.
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Mutable access to the values of all stored diagonals.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Returns the values of the `index`-th stored diagonal, along with its offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not smaller than the number of stored diagonals.
+    #[must_use]
+    pub fn diagonal(&self, index: usize) -> (isize, &[T]) {
+        let range = self.value_offsets[index]..self.value_offsets[index + 1];
+        (self.diagonal_offsets[index], &self.values[range])
+    }
+
+    /// Returns the values of the diagonal with the given offset, if it is stored.
+    #[must_use]
+    pub fn get_diagonal(&self, offset: isize) -> Option<&[T]> {
+        let index = self.diagonal_offsets.binary_search(&offset).ok()?;
+        Some(self.diagonal(index).1)
+    }
+
+    /// Returns the mutable values of the diagonal with the given offset, if it is stored.
+    pub fn get_diagonal_mut(&mut self, offset: isize) -> Option<&mut [T]> {
+        let index = self.diagonal_offsets.binary_search(&offset).ok()?;
+        let range = self.value_offsets[index]..self.value_offsets[index + 1];
+        Some(&mut self.values[range])
+    }
+
+    /// An iterator over the stored diagonals.
+    ///
+    /// The iterator yields pairs `(offset, values)` in order of increasing offset.
+    pub fn diagonal_iter(&self) -> impl Iterator<Item = (isize, &[T])> {
+        (0..self.num_diagonals()).map(move |index| self.diagonal(index))
+    }
+
+    /// An iterator over all explicitly stored entries of the matrix.
+    ///
+    /// The iterator yields triplets `(i, j, v)` for every entry of every stored diagonal,
+    /// including entries that are zero. The entries are visited diagonal by diagonal, in order of
+    /// increasing diagonal offset.
+    pub fn triplet_iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.diagonal_iter().flat_map(|(k, diagonal)| {
+            let (i0, j0) = diagonal_start(k);
+            diagonal
+                .iter()
+                .enumerate()
+                .map(move |(p, v)| (i0 + p, j0 + p, v))
+        })
+    }
+
+    /// Disassembles the DIA matrix into its diagonal offsets and values.
+    pub fn disassemble(self) -> (Vec<isize>, Vec<T>) {
+        (self.diagonal_offsets, self.values)
+    }
+}
+
+impl<T: Scalar> DiaMatrix<T> {
+    /// Returns the transpose of this matrix.
+    ///
+    /// The diagonal with offset `k` becomes the diagonal with offset `-k`.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let mut diagonal_offsets = Vec::with_capacity(self.num_diagonals());
+        let mut value_offsets = Vec::with_capacity(self.num_diagonals() + 1);
+        let mut values = Vec::with_capacity(self.nnz());
+        value_offsets.push(0);
+        for (k, diagonal) in (0..self.num_diagonals())
+            .rev()
+            .map(|index| self.diagonal(index))
+        {
+            diagonal_offsets.push(-k);
+            values.extend_from_slice(diagonal);
+            value_offsets.push(values.len());
+        }
+        Self {
+            nrows: self.ncols,
+            ncols: self.nrows,
+            diagonal_offsets,
+            value_offsets,
+            values,
+        }
+    }
+}
+
+/// Determines whether the diagonal with offset `k` is contained in an `nrows x ncols` matrix.
+pub(crate) fn diagonal_in_bounds(nrows: usize, ncols: usize, k: isize) -> bool {
+    if k >= 0 {
+        (k as usize) < ncols
+    } else {
+        k.unsigned_abs() < nrows
+    }
+}
+
+/// The number of entries on the diagonal with offset `k` of an `nrows x ncols` matrix.
+///
+/// The offset must be in bounds.
+pub(crate) fn diagonal_len(nrows: usize, ncols: usize, k: isize) -> usize {
+    if k >= 0 {
+        nrows.min(ncols - k as usize)
+    } else {
+        ncols.min(nrows - k.unsigned_abs())
+    }
+}
+
+/// The row and column index of the first entry on the diagonal with offset `k`.
+pub(crate) fn diagonal_start(k: isize) -> (usize, usize) {
+    if k >= 0 {
+        (0, k as usize)
+    } else {
+        (k.unsigned_abs(), 0)
+    }
+}
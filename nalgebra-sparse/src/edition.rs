@@ -0,0 +1,755 @@
+//! Slicing, stacking and block assembly of sparse matrices.
+//!
+//! This module is the sparse counterpart of the edition functionality of dense `nalgebra`
+//! matrices. [CsrMatrix] and [CscMatrix] can extract submatrices by arbitrary sets of row and
+//! column indices with `select_rows`, `select_columns` and `submatrix`, can be stacked with
+//! `hstack` and `vstack`, and can be placed along a diagonal with `block_diag`.
+//! [SparsityPattern] offers the slicing and stacking functionality in terms of major and minor
+//! lanes. Larger block matrices are assembled with [BlockBuilder].
+//!
+//! Index sets can be given as anything that can be iterated over to produce indices, such as
+//! slices, vectors or ranges. Indices may appear in any order and may be repeated, in which case
+//! the corresponding rows or columns appear in that order and are repeated in the result.
+//!
+//! Examples
+//! --------
+//!
+//! ```
+//! use nalgebra_sparse::{CooMatrix, CsrMatrix};
+//! use nalgebra::DMatrix;
+//!
+//! let dense = DMatrix::from_row_slice(3, 3, &[1.0, 0.0, 2.0,
+//!                                             0.0, 3.0, 0.0,
+//!                                             4.0, 0.0, 5.0]);
+//! let csr = CsrMatrix::from(&CooMatrix::from(&dense));
+//!
+//! // Select the corners of the matrix
+//! let corners = csr.submatrix([0, 2], [0, 2]);
+//! assert_eq!(DMatrix::from(&corners), DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 4.0, 5.0]));
+//!
+//! // Rows can be selected by a range, and indices may be repeated
+//! let rows = csr.select_rows(1..3);
+//! let cols = csr.select_columns([1, 1]);
+//! assert_eq!(rows.nrows(), 2);
+//! assert_eq!(cols.ncols(), 2);
+//!
+//! // Place the matrix next to itself
+//! let stacked = CsrMatrix::hstack(&[&csr, &csr]);
+//! assert_eq!(stacked.ncols(), 6);
+//! ```
+
+use crate::cs::CsMatrix;
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::pattern::SparsityPattern;
+
+use std::borrow::Borrow;
+
+/// Collects the given indices, asserting that each of them is smaller than `dim`.
+fn collect_indices<I>(indices: I, dim: usize, description: &str) -> Vec<usize>
+where
+    I: IntoIterator,
+    I::Item: Borrow<usize>,
+{
+    let indices: Vec<usize> = indices.into_iter().map(|i| *i.borrow()).collect();
+    assert!(
+        indices.iter().all(|&i| i < dim),
+        "{} index out of bounds.",
+        description
+    );
+    indices
+}
+
+/// Selects the given major lanes and minor indices of a sparsity pattern.
+///
+/// `None` selects every lane or minor index in order. Returns the new pattern along with the
+/// index of the source entry of every entry in the new pattern.
+pub(crate) fn select_pattern(
+    pattern: &SparsityPattern,
+    major: Option<&[usize]>,
+    minor: Option<&[usize]>,
+) -> (SparsityPattern, Vec<usize>) {
+    let all_lanes: Vec<usize>;
+    let major = match major {
+        Some(major) => major,
+        None => {
+            all_lanes = (0..pattern.major_dim()).collect();
+            &all_lanes
+        }
+    };
+
+    let mut new_offsets = Vec::with_capacity(major.len() + 1);
+    let mut new_indices = Vec::new();
+    let mut sources = Vec::new();
+    new_offsets.push(0);
+
+    match minor {
+        None => {
+            for &lane in major {
+                let begin = pattern.major_offsets()[lane];
+                let end = pattern.major_offsets()[lane + 1];
+                new_indices.extend_from_slice(&pattern.minor_indices()[begin..end]);
+                sources.extend(begin..end);
+                new_offsets.push(new_indices.len());
+            }
+        }
+        Some(minor) => {
+            // For every old minor index, the (ascending) list of new minor indices it maps to
+            let mut target_offsets = vec![0; pattern.minor_dim() + 1];
+            for &j in minor {
+                target_offsets[j + 1] += 1;
+            }
+            for j in 0..pattern.minor_dim() {
+                target_offsets[j + 1] += target_offsets[j];
+            }
+            let mut targets = vec![0; minor.len()];
+            let mut next = target_offsets[..pattern.minor_dim()].to_vec();
+            for (k, &j) in minor.iter().enumerate() {
+                targets[next[j]] = k;
+                next[j] += 1;
+            }
+
+            let mut lane_entries = Vec::new();
+            for &lane in major {
+                let begin = pattern.major_offsets()[lane];
+                let end = pattern.major_offsets()[lane + 1];
+                lane_entries.clear();
+                for (source, &j) in (begin..end).zip(&pattern.minor_indices()[begin..end]) {
+                    let lane_targets = &targets[target_offsets[j]..target_offsets[j + 1]];
+                    lane_entries.extend(lane_targets.iter().map(|&k| (k, source)));
+                }
+                lane_entries.sort_unstable();
+                for &(k, source) in &lane_entries {
+                    new_indices.push(k);
+                    sources.push(source);
+                }
+                new_offsets.push(new_indices.len());
+            }
+        }
+    }
+
+    let minor_dim = minor.map_or(pattern.minor_dim(), |minor| minor.len());
+    // TODO: Avoid checks here
+    let new_pattern = SparsityPattern::try_from_offsets_and_indices(
+        major.len(),
+        minor_dim,
+        new_offsets,
+        new_indices,
+    )
+    .expect("Internal error: Sparsity pattern must always be valid.");
+    (new_pattern, sources)
+}
+
+/// Selects the given major lanes and minor indices of a matrix.
+pub(crate) fn select_cs<T: Clone>(
+    cs: &CsMatrix<T>,
+    major: Option<&[usize]>,
+    minor: Option<&[usize]>,
+) -> CsMatrix<T> {
+    let (pattern, sources) = select_pattern(cs.pattern(), major, minor);
+    let values = sources.iter().map(|&k| cs.values()[k].clone()).collect();
+    CsMatrix::from_pattern_and_values(pattern, values)
+}
+
+/// Assembles a pattern from a grid of blocks, stored major block by major block.
+///
+/// Returns the new pattern along with the block index and the index of the source entry
+/// of every entry in the new pattern.
+fn assemble_pattern(
+    major_sizes: &[usize],
+    minor_sizes: &[usize],
+    blocks: &[Option<&SparsityPattern>],
+) -> (SparsityPattern, Vec<(usize, usize)>) {
+    debug_assert_eq!(blocks.len(), major_sizes.len() * minor_sizes.len());
+
+    let mut minor_offsets = Vec::with_capacity(minor_sizes.len());
+    let mut minor_dim = 0;
+    for &size in minor_sizes {
+        minor_offsets.push(minor_dim);
+        minor_dim += size;
+    }
+    let major_dim = major_sizes.iter().sum();
+    let nnz = blocks.iter().flatten().map(|block| block.nnz()).sum();
+
+    let mut new_offsets = Vec::with_capacity(major_dim + 1);
+    let mut new_indices = Vec::with_capacity(nnz);
+    let mut sources = Vec::with_capacity(nnz);
+    new_offsets.push(0);
+
+    for (i, &major_size) in major_sizes.iter().enumerate() {
+        let block_row = &blocks[i * minor_sizes.len()..(i + 1) * minor_sizes.len()];
+        for lane in 0..major_size {
+            for (j, block) in block_row.iter().enumerate() {
+                if let Some(block) = block {
+                    let begin = block.major_offsets()[lane];
+                    let end = block.major_offsets()[lane + 1];
+                    let block_index = i * minor_sizes.len() + j;
+                    for (source, &k) in (begin..end).zip(&block.minor_indices()[begin..end]) {
+                        new_indices.push(minor_offsets[j] + k);
+                        sources.push((block_index, source));
+                    }
+                }
+            }
+            new_offsets.push(new_indices.len());
+        }
+    }
+
+    // TODO: Avoid checks here
+    let new_pattern = SparsityPattern::try_from_offsets_and_indices(
+        major_dim,
+        minor_dim,
+        new_offsets,
+        new_indices,
+    )
+    .expect("Internal error: Sparsity pattern must always be valid.");
+    (new_pattern, sources)
+}
+
+/// Assembles a matrix from a grid of blocks, stored major block by major block.
+fn assemble_cs<T: Clone>(
+    major_sizes: &[usize],
+    minor_sizes: &[usize],
+    blocks: &[Option<&CsMatrix<T>>],
+) -> CsMatrix<T> {
+    let patterns: Vec<_> = blocks
+        .iter()
+        .map(|block| block.map(CsMatrix::pattern))
+        .collect();
+    let (pattern, sources) = assemble_pattern(major_sizes, minor_sizes, &patterns);
+    let values = sources
+        .iter()
+        .map(|&(block, k)| blocks[block].unwrap().values()[k].clone())
+        .collect();
+    CsMatrix::from_pattern_and_values(pattern, values)
+}
+
+impl<T: Clone> CsrMatrix<T> {
+    /// Creates a new matrix from the given rows of this matrix, in the given order.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any row index is out of bounds.
+    #[must_use]
+    pub fn select_rows<I>(&self, rows: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let rows = collect_indices(rows, self.nrows(), "Row");
+        Self {
+            cs: select_cs(&self.cs, Some(&rows), None),
+        }
+    }
+
+    /// Creates a new matrix from the given columns of this matrix, in the given order.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any column index is out of bounds.
+    #[must_use]
+    pub fn select_columns<I>(&self, cols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let cols = collect_indices(cols, self.ncols(), "Column");
+        Self {
+            cs: select_cs(&self.cs, None, Some(&cols)),
+        }
+    }
+
+    /// Creates a new matrix from the entries of this matrix at the intersection of the given rows
+    /// and columns.
+    ///
+    /// This is equivalent to, but faster than, `self.select_rows(rows).select_columns(cols)`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any row or column index is out of bounds.
+    #[must_use]
+    pub fn submatrix<I, J>(&self, rows: I, cols: J) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+        J: IntoIterator,
+        J::Item: Borrow<usize>,
+    {
+        let rows = collect_indices(rows, self.nrows(), "Row");
+        let cols = collect_indices(cols, self.ncols(), "Column");
+        Self {
+            cs: select_cs(&self.cs, Some(&rows), Some(&cols)),
+        }
+    }
+
+    /// Places the given matrices next to each other, from left to right.
+    ///
+    /// Stacking no matrices at all gives a `0x0` matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrices do not all have the same number of rows.
+    #[must_use]
+    pub fn hstack(matrices: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(1, matrices.len());
+        for (j, &matrix) in matrices.iter().enumerate() {
+            builder.set_block(0, j, matrix);
+        }
+        builder.build()
+    }
+
+    /// Places the given matrices on top of each other, from top to bottom.
+    ///
+    /// Stacking no matrices at all gives a `0x0` matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrices do not all have the same number of columns.
+    #[must_use]
+    pub fn vstack(matrices: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(matrices.len(), 1);
+        for (i, &matrix) in matrices.iter().enumerate() {
+            builder.set_block(i, 0, matrix);
+        }
+        builder.build()
+    }
+
+    /// Places the given matrices along the diagonal of a block diagonal matrix.
+    ///
+    /// The matrices need not be square. The pattern of the result is computed directly from the
+    /// patterns of the blocks. Stacking no matrices at all gives a `0x0` matrix.
+    #[must_use]
+    pub fn block_diag(matrices: &[&Self]) -> Self {
+        let blocks: Vec<_> = matrices.iter().map(|matrix| &matrix.cs).collect();
+        Self {
+            cs: CsMatrix::block_diag(&blocks),
+        }
+    }
+}
+
+impl<T: Clone> CscMatrix<T> {
+    /// Creates a new matrix from the given rows of this matrix, in the given order.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any row index is out of bounds.
+    #[must_use]
+    pub fn select_rows<I>(&self, rows: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let rows = collect_indices(rows, self.nrows(), "Row");
+        Self {
+            cs: select_cs(&self.cs, None, Some(&rows)),
+        }
+    }
+
+    /// Creates a new matrix from the given columns of this matrix, in the given order.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any column index is out of bounds.
+    #[must_use]
+    pub fn select_columns<I>(&self, cols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let cols = collect_indices(cols, self.ncols(), "Column");
+        Self {
+            cs: select_cs(&self.cs, Some(&cols), None),
+        }
+    }
+
+    /// Creates a new matrix from the entries of this matrix at the intersection of the given rows
+    /// and columns.
+    ///
+    /// This is equivalent to, but faster than, `self.select_rows(rows).select_columns(cols)`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any row or column index is out of bounds.
+    #[must_use]
+    pub fn submatrix<I, J>(&self, rows: I, cols: J) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+        J: IntoIterator,
+        J::Item: Borrow<usize>,
+    {
+        let rows = collect_indices(rows, self.nrows(), "Row");
+        let cols = collect_indices(cols, self.ncols(), "Column");
+        Self {
+            cs: select_cs(&self.cs, Some(&cols), Some(&rows)),
+        }
+    }
+
+    /// Places the given matrices next to each other, from left to right.
+    ///
+    /// Stacking no matrices at all gives a `0x0` matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrices do not all have the same number of rows.
+    #[must_use]
+    pub fn hstack(matrices: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(1, matrices.len());
+        for (j, &matrix) in matrices.iter().enumerate() {
+            builder.set_block(0, j, matrix);
+        }
+        builder.build()
+    }
+
+    /// Places the given matrices on top of each other, from top to bottom.
+    ///
+    /// Stacking no matrices at all gives a `0x0` matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrices do not all have the same number of columns.
+    #[must_use]
+    pub fn vstack(matrices: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(matrices.len(), 1);
+        for (i, &matrix) in matrices.iter().enumerate() {
+            builder.set_block(i, 0, matrix);
+        }
+        builder.build()
+    }
+
+    /// Places the given matrices along the diagonal of a block diagonal matrix.
+    ///
+    /// The matrices need not be square. The pattern of the result is computed directly from the
+    /// patterns of the blocks. Stacking no matrices at all gives a `0x0` matrix.
+    #[must_use]
+    pub fn block_diag(matrices: &[&Self]) -> Self {
+        let blocks: Vec<_> = matrices.iter().map(|matrix| &matrix.cs).collect();
+        Self {
+            cs: CsMatrix::block_diag(&blocks),
+        }
+    }
+}
+
+impl SparsityPattern {
+    /// Creates a new pattern from the given major lanes of this pattern, in the given order.
+    ///
+    /// For the pattern of a CSR matrix this selects rows, for the pattern of a CSC matrix this
+    /// selects columns.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any major index is out of bounds.
+    #[must_use]
+    pub fn select_major_lanes<I>(&self, major_indices: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let major_indices = collect_indices(major_indices, self.major_dim(), "Major");
+        select_pattern(self, Some(&major_indices), None).0
+    }
+
+    /// Creates a new pattern from the given minor lanes of this pattern, in the given order.
+    ///
+    /// For the pattern of a CSR matrix this selects columns, for the pattern of a CSC matrix this
+    /// selects rows.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any minor index is out of bounds.
+    #[must_use]
+    pub fn select_minor_lanes<I>(&self, minor_indices: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let minor_indices = collect_indices(minor_indices, self.minor_dim(), "Minor");
+        select_pattern(self, None, Some(&minor_indices)).0
+    }
+
+    /// Creates a new pattern from the entries of this pattern at the intersection of the given
+    /// major and minor lanes.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if any major or minor index is out of bounds.
+    #[must_use]
+    pub fn subpattern<I, J>(&self, major_indices: I, minor_indices: J) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+        J: IntoIterator,
+        J::Item: Borrow<usize>,
+    {
+        let major_indices = collect_indices(major_indices, self.major_dim(), "Major");
+        let minor_indices = collect_indices(minor_indices, self.minor_dim(), "Minor");
+        select_pattern(self, Some(&major_indices), Some(&minor_indices)).0
+    }
+
+    /// Concatenates the major lanes of the given patterns.
+    ///
+    /// This corresponds to [CsrMatrix::vstack] for the patterns of CSR matrices, and to
+    /// [CscMatrix::hstack] for the patterns of CSC matrices. Stacking no patterns at all gives
+    /// a `0x0` pattern.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the patterns do not all have the same minor dimension.
+    #[must_use]
+    pub fn stack_major(patterns: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(patterns.len(), 1);
+        for (i, &pattern) in patterns.iter().enumerate() {
+            builder.set_block(i, 0, pattern);
+        }
+        builder.build()
+    }
+
+    /// Places the given patterns next to each other along the minor dimension.
+    ///
+    /// This corresponds to [CsrMatrix::hstack] for the patterns of CSR matrices, and to
+    /// [CscMatrix::vstack] for the patterns of CSC matrices. Stacking no patterns at all gives
+    /// a `0x0` pattern.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the patterns do not all have the same major dimension.
+    #[must_use]
+    pub fn stack_minor(patterns: &[&Self]) -> Self {
+        let mut builder = BlockBuilder::new(1, patterns.len());
+        for (j, &pattern) in patterns.iter().enumerate() {
+            builder.set_block(0, j, pattern);
+        }
+        builder.build()
+    }
+}
+
+mod internal {
+    pub trait SupportedBlock {
+        /// The number of rows and columns of the block, or the major and minor dimension for
+        /// sparsity patterns.
+        fn block_dims(&self) -> (usize, usize);
+    }
+}
+
+/// A marker trait for the block types supported by [BlockBuilder].
+///
+/// This is a sealed trait; it cannot be implemented by external crates.
+pub trait Block: internal::SupportedBlock {}
+
+impl<T> internal::SupportedBlock for CsrMatrix<T> {
+    fn block_dims(&self) -> (usize, usize) {
+        (self.nrows(), self.ncols())
+    }
+}
+
+impl<T> internal::SupportedBlock for CscMatrix<T> {
+    fn block_dims(&self) -> (usize, usize) {
+        (self.nrows(), self.ncols())
+    }
+}
+
+impl internal::SupportedBlock for SparsityPattern {
+    fn block_dims(&self) -> (usize, usize) {
+        (self.major_dim(), self.minor_dim())
+    }
+}
+
+impl<T> Block for CsrMatrix<T> {}
+impl<T> Block for CscMatrix<T> {}
+impl Block for SparsityPattern {}
+
+/// A builder for assembling a block matrix from sparse blocks.
+///
+/// The builder describes a grid of `block_rows x block_cols` blocks, each of which is either a
+/// sparse matrix or absent, in which case the block is zero. All blocks in the same block row
+/// must have the same number of rows, and all blocks in the same block column must have the same
+/// number of columns. The size of a block row or block column without any blocks is zero, unless
+/// it is explicitly set with [set_row_height](Self::set_row_height) or
+/// [set_col_width](Self::set_col_width).
+///
+/// The builder can assemble [CsrMatrix], [CscMatrix] and [SparsityPattern] blocks. For sparsity
+/// patterns, block rows and block columns correspond to the major and minor dimension.
+///
+/// Examples
+/// --------
+///
+/// ```
+/// use nalgebra_sparse::edition::BlockBuilder;
+/// use nalgebra_sparse::CsrMatrix;
+/// use nalgebra::DMatrix;
+///
+/// let a = CsrMatrix::<f64>::identity(2);
+/// let b = CsrMatrix::<f64>::identity(1);
+///
+/// // Assemble the block matrix [ a 0 ]
+/// //                           [ 0 b ]
+/// //                           [ 0 0 ], where the last block row has a single row
+/// let matrix = BlockBuilder::new(3, 2)
+///     .set_block(0, 0, &a)
+///     .set_block(1, 1, &b)
+///     .set_row_height(2, 1)
+///     .build();
+///
+/// assert_eq!(matrix.nrows(), 4);
+/// assert_eq!(matrix.ncols(), 3);
+/// assert_eq!(DMatrix::from(&matrix), DMatrix::identity(4, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockBuilder<'a, M> {
+    row_heights: Vec<Option<usize>>,
+    col_widths: Vec<Option<usize>>,
+    // The blocks, stored block row by block row
+    blocks: Vec<Option<&'a M>>,
+}
+
+impl<'a, M> BlockBuilder<'a, M> {
+    /// Creates a builder for a grid of `block_rows x block_cols` blocks, all of which are absent.
+    pub fn new(block_rows: usize, block_cols: usize) -> Self {
+        Self {
+            row_heights: vec![None; block_rows],
+            col_widths: vec![None; block_cols],
+            blocks: vec![None; block_rows * block_cols],
+        }
+    }
+
+    /// The number of block rows.
+    #[must_use]
+    pub fn block_rows(&self) -> usize {
+        self.row_heights.len()
+    }
+
+    /// The number of block columns.
+    #[must_use]
+    pub fn block_cols(&self) -> usize {
+        self.col_widths.len()
+    }
+
+    /// Sets the number of rows of the given block row.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if `block_row` is out of bounds, or if the block row already has a different height.
+    pub fn set_row_height(&mut self, block_row: usize, height: usize) -> &mut Self {
+        assert!(block_row < self.block_rows(), "Block row out of bounds.");
+        set_size(
+            &mut self.row_heights[block_row],
+            height,
+            "Blocks in the same block row must have the same number of rows.",
+        );
+        self
+    }
+
+    /// Sets the number of columns of the given block column.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if `block_col` is out of bounds, or if the block column already has a different
+    /// width.
+    pub fn set_col_width(&mut self, block_col: usize, width: usize) -> &mut Self {
+        assert!(block_col < self.block_cols(), "Block column out of bounds.");
+        set_size(
+            &mut self.col_widths[block_col],
+            width,
+            "Blocks in the same block column must have the same number of columns.",
+        );
+        self
+    }
+
+    /// Places a block at the given position in the grid of blocks.
+    ///
+    /// For sparsity patterns, block rows correspond to the major dimension and block columns to
+    /// the minor dimension.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the position is out of bounds, or if the dimensions of the block are not
+    /// consistent with the other blocks in the same block row or block column.
+    pub fn set_block(&mut self, block_row: usize, block_col: usize, block: &'a M) -> &mut Self
+    where
+        M: Block,
+    {
+        let (nrows, ncols) = block.block_dims();
+        self.set_row_height(block_row, nrows);
+        self.set_col_width(block_col, ncols);
+        let block_cols = self.block_cols();
+        self.blocks[block_row * block_cols + block_col] = Some(block);
+        self
+    }
+
+    fn sizes(&self) -> (Vec<usize>, Vec<usize>) {
+        let heights = self.row_heights.iter().map(|h| h.unwrap_or(0)).collect();
+        let widths = self.col_widths.iter().map(|w| w.unwrap_or(0)).collect();
+        (heights, widths)
+    }
+
+    /// The blocks, stored block column by block column.
+    fn transposed_blocks(&self) -> Vec<Option<&'a M>> {
+        let (block_rows, block_cols) = (self.block_rows(), self.block_cols());
+        (0..block_cols)
+            .flat_map(|j| (0..block_rows).map(move |i| (i, j)))
+            .map(|(i, j)| self.blocks[i * block_cols + j])
+            .collect()
+    }
+}
+
+/// Sets the size of a block row or block column, asserting that it matches any previous size.
+pub(crate) fn set_size(size: &mut Option<usize>, new_size: usize, message: &str) {
+    match *size {
+        Some(size) => assert_eq!(size, new_size, "{}", message),
+        None => *size = Some(new_size),
+    }
+}
+
+impl<'a, T: Clone> BlockBuilder<'a, CsrMatrix<T>> {
+    /// Assembles the block matrix.
+    #[must_use]
+    pub fn build(&self) -> CsrMatrix<T> {
+        let (heights, widths) = self.sizes();
+        let blocks: Vec<_> = self.blocks.iter().map(|b| b.map(|b| &b.cs)).collect();
+        CsrMatrix {
+            cs: assemble_cs(&heights, &widths, &blocks),
+        }
+    }
+}
+
+impl<'a, T: Clone> BlockBuilder<'a, CscMatrix<T>> {
+    /// Assembles the block matrix.
+    #[must_use]
+    pub fn build(&self) -> CscMatrix<T> {
+        let (heights, widths) = self.sizes();
+        let blocks: Vec<_> = self
+            .transposed_blocks()
+            .iter()
+            .map(|b| b.map(|b| &b.cs))
+            .collect();
+        CscMatrix {
+            cs: assemble_cs(&widths, &heights, &blocks),
+        }
+    }
+}
+
+impl<'a> BlockBuilder<'a, SparsityPattern> {
+    /// Assembles the block pattern.
+    #[must_use]
+    pub fn build(&self) -> SparsityPattern {
+        let (heights, widths) = self.sizes();
+        assemble_pattern(&heights, &widths, &self.blocks).0
+    }
+}
@@ -0,0 +1,322 @@
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::eigen::{
+    b_orthogonalize, pseudo_random_vector, residual_norm, ritz_decomposition, EigenOptions,
+    PartialEigen, Which,
+};
+use crate::factorization::{CholeskyError, CscCholesky};
+use crate::operator::{FnOperator, LinearOperator};
+use nalgebra::{convert, DMatrix, DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Computes a few eigenpairs of the symmetric matrix `A` with the thick-restart Lanczos method.
+///
+/// Returns the `num_eigenpairs` eigenpairs at the end of the spectrum selected by `which`.
+/// The method builds an orthonormal basis of a Krylov subspace of dimension
+/// `subspace_dimension`, computes Ritz approximations in this subspace, and then restarts
+/// with the most relevant Ritz vectors. Larger subspaces typically lead to fewer restarts, at
+/// the cost of storing `subspace_dimension + 1` vectors of length `n`. A subspace dimension of
+/// about twice the number of requested eigenpairs is a reasonable starting point.
+///
+/// Convergence is fastest for eigenvalues that are well separated from the rest of the
+/// spectrum. To compute eigenvalues in the interior of the spectrum or at the lower end of the
+/// spectrum of a stiff problem, [`lanczos_shift_invert`] is usually much faster.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, if `num_eigenpairs` exceeds the dimension of `A`, or if
+/// `subspace_dimension` does not exceed `num_eigenpairs`.
+pub fn lanczos<T, A>(
+    a: &A,
+    num_eigenpairs: usize,
+    which: Which,
+    subspace_dimension: usize,
+    options: &EigenOptions<T>,
+) -> PartialEigen<T>
+where
+    T: RealField,
+    A: LinearOperator<T>,
+{
+    assert_eq!(a.nrows(), a.ncols(), "A must be square.");
+    let problem = LanczosProblem {
+        operator: a,
+        inner_product: None,
+        k: a,
+        m: None,
+        which,
+        to_eigenvalue: &|theta| theta,
+    };
+    thick_restart_lanczos(&problem, num_eigenpairs, subspace_dimension, options)
+}
+
+/// Computes a few eigenpairs of the generalized problem `K x = λ M x` with the thick-restart
+/// Lanczos method.
+///
+/// `K` must be symmetric and `M` must be symmetric positive definite. The Lanczos method is
+/// applied to the operator `M^{-1} K`, which is symmetric with respect to the `M`-inner
+/// product. This requires a sparse Cholesky factorization of `M`, and each iteration performs
+/// one product with `K` and one solve with the factorization.
+///
+/// See [`lanczos`] for the meaning of the remaining parameters.
+///
+/// # Errors
+///
+/// Returns an error if the Cholesky factorization of `M` fails, i.e. if `M` is not positive
+/// definite.
+///
+/// # Panics
+///
+/// Panics if `K` and `M` are not square matrices of the same dimensions, if `num_eigenpairs`
+/// exceeds their dimension, or if `subspace_dimension` does not exceed `num_eigenpairs`.
+pub fn lanczos_generalized<T: RealField>(
+    k: &CsrMatrix<T>,
+    m: &CsrMatrix<T>,
+    num_eigenpairs: usize,
+    which: Which,
+    subspace_dimension: usize,
+    options: &EigenOptions<T>,
+) -> Result<PartialEigen<T>, CholeskyError> {
+    assert_compatible_pencil_dims(k, Some(m));
+    let n = k.nrows();
+    let cholesky = CscCholesky::factor(&CscMatrix::from(m))?;
+    // x -> M^{-1} K x
+    let operator = FnOperator::new(
+        n,
+        n,
+        |x: DVectorView<'_, T>, mut y: DVectorViewMut<'_, T>| {
+            let mut kx = DVector::zeros(n);
+            k.apply(T::zero(), (&mut kx).into(), T::one(), x);
+            cholesky.solve_mut(&mut kx);
+            y.copy_from(&kx);
+        },
+    );
+    let problem = LanczosProblem {
+        operator: &operator,
+        inner_product: Some(m),
+        k,
+        m: Some(m),
+        which,
+        to_eigenvalue: &|theta| theta,
+    };
+    Ok(thick_restart_lanczos(
+        &problem,
+        num_eigenpairs,
+        subspace_dimension,
+        options,
+    ))
+}
+
+/// Computes the eigenpairs of `K x = λ M x` closest to the shift `σ` with the shift-invert
+/// Lanczos method.
+///
+/// `K` must be symmetric and `M` must be symmetric positive definite. If `m` is `None`, the
+/// standard problem `K x = λ x` is solved. The Lanczos method is applied to
+/// the operator `(K - σ M)^{-1} M`, whose eigenvalues are `1 / (λ - σ)`, so that the
+/// eigenvalues closest to `σ` become the dominant ones. This typically reduces the number of
+/// iterations dramatically, at the cost of a sparse Cholesky factorization of `K - σ M`.
+///
+/// Since [`CscCholesky`] requires a positive definite matrix, the shift must be chosen below
+/// the smallest eigenvalue. The returned eigenpairs are then the `num_eigenpairs` smallest
+/// ones. For modal analysis with a positive definite stiffness matrix, `σ = 0` is a natural
+/// choice. See [`lanczos`] for the meaning of the remaining parameters.
+///
+/// # Errors
+///
+/// Returns an error if the Cholesky factorization of `K - σ M` fails, i.e. if `σ` is not
+/// strictly below the smallest eigenvalue.
+///
+/// # Panics
+///
+/// Panics if `K` and `M` are not square matrices of the same dimensions, if `num_eigenpairs`
+/// exceeds their dimension, or if `subspace_dimension` does not exceed `num_eigenpairs`.
+pub fn lanczos_shift_invert<T: RealField>(
+    k: &CsrMatrix<T>,
+    m: Option<&CsrMatrix<T>>,
+    sigma: T,
+    num_eigenpairs: usize,
+    subspace_dimension: usize,
+    options: &EigenOptions<T>,
+) -> Result<PartialEigen<T>, CholeskyError> {
+    assert_compatible_pencil_dims(k, m);
+    let n = k.nrows();
+    let shifted = match m {
+        Some(m) => k - &(m * sigma.clone()),
+        None => k - &(CsrMatrix::identity(n) * sigma.clone()),
+    };
+    let cholesky = CscCholesky::factor(&CscMatrix::from(&shifted))?;
+
+    // x -> (K - σ M)^{-1} M x
+    let operator = FnOperator::new(
+        n,
+        n,
+        |x: DVectorView<'_, T>, mut y: DVectorViewMut<'_, T>| {
+            let mut mx = x.clone_owned();
+            if let Some(m) = m {
+                m.apply(T::zero(), (&mut mx).into(), T::one(), x);
+            }
+            cholesky.solve_mut(&mut mx);
+            y.copy_from(&mx);
+        },
+    );
+    let m_operator = m.map(|m| m as &dyn LinearOperator<T>);
+    let problem = LanczosProblem {
+        operator: &operator,
+        inner_product: m_operator,
+        k,
+        m: m_operator,
+        which: Which::Largest,
+        to_eigenvalue: &|theta| sigma.clone() + T::one() / theta,
+    };
+    Ok(thick_restart_lanczos(
+        &problem,
+        num_eigenpairs,
+        subspace_dimension,
+        options,
+    ))
+}
+
+fn assert_compatible_pencil_dims<T>(k: &CsrMatrix<T>, m: Option<&CsrMatrix<T>>) {
+    assert_eq!(k.nrows(), k.ncols(), "K must be square.");
+    if let Some(m) = m {
+        assert_eq!(m.nrows(), m.ncols(), "M must be square.");
+        assert_eq!(
+            k.nrows(),
+            m.nrows(),
+            "K and M must have the same dimensions."
+        );
+    }
+}
+
+/// A (possibly spectrally transformed) symmetric eigenvalue problem.
+struct LanczosProblem<'a, T> {
+    /// The operator `C` that the Lanczos method is applied to.
+    operator: &'a dyn LinearOperator<T>,
+    /// The matrix `B` that defines the inner product with respect to which `C` is symmetric.
+    inner_product: Option<&'a dyn LinearOperator<T>>,
+    /// The original problem `K x = λ M x`, used for computing residuals.
+    k: &'a dyn LinearOperator<T>,
+    m: Option<&'a dyn LinearOperator<T>>,
+    /// Which eigenvalues `θ` of `C` are wanted.
+    which: Which,
+    /// Maps an eigenvalue `θ` of `C` to the corresponding eigenvalue `λ` of the original problem.
+    to_eigenvalue: &'a dyn Fn(T) -> T,
+}
+
+fn thick_restart_lanczos<T: RealField>(
+    problem: &LanczosProblem<'_, T>,
+    nev: usize,
+    subspace_dimension: usize,
+    options: &EigenOptions<T>,
+) -> PartialEigen<T> {
+    let n = problem.operator.nrows();
+    let b = problem.inner_product;
+    assert!(
+        nev <= n,
+        "The number of eigenpairs must not exceed the dimension of the problem."
+    );
+    assert!(
+        subspace_dimension > nev,
+        "The subspace dimension must exceed the number of eigenpairs."
+    );
+    let m = subspace_dimension.min(n);
+
+    if nev == 0 {
+        return PartialEigen::new(Vec::new(), n, 0, options);
+    }
+
+    // Lanczos basis, stored column by column, and the projection of the operator onto the
+    // basis. After a restart, the projection has an arrowhead structure.
+    let mut v = DMatrix::zeros(n, m + 1);
+    let mut t = DMatrix::zeros(m, m);
+    let mut seed = 0;
+
+    let mut w = pseudo_random_vector(n, seed);
+    let (_, norm) = b_orthogonalize(&mut w, v.columns(0, 0), b);
+    v.column_mut(0).copy_from(&(w / norm));
+
+    // The number of Ritz vectors that were retained in the last restart
+    let mut k = 0;
+    let mut iterations = 0;
+
+    loop {
+        // Extend the Lanczos factorization C V_m = V_m T_m + beta_m v_{m+1} e_m^T
+        let mut beta_m = T::zero();
+        for j in k..m {
+            let mut w = DVector::zeros(n);
+            problem
+                .operator
+                .apply(T::zero(), (&mut w).into(), T::one(), v.column(j));
+            // Full reorthogonalization. The coefficients for the previous basis vectors are
+            // already stored in T, so we only need the diagonal entry
+            let (h, mut beta) = b_orthogonalize(&mut w, v.columns(0, j + 1), b);
+            t[(j, j)] = h[j].clone();
+
+            // If the Krylov subspace is invariant, continue with an arbitrary vector that is
+            // orthogonal to the basis
+            let w_norm = (h.norm_squared() + beta.clone() * beta.clone()).sqrt();
+            let breakdown_tolerance: T = convert(1e3);
+            if beta <= breakdown_tolerance * T::default_epsilon() * w_norm || beta.is_zero() {
+                // If the basis already spans the whole space, there is no such vector
+                if j + 1 == n {
+                    w.fill(T::zero());
+                } else {
+                    seed += 1;
+                    w = pseudo_random_vector(n, seed);
+                    let (_, norm) = b_orthogonalize(&mut w, v.columns(0, j + 1), b);
+                    w /= norm;
+                }
+                beta = T::zero();
+            } else {
+                w /= beta.clone();
+            }
+
+            v.column_mut(j + 1).copy_from(&w);
+            if j + 1 < m {
+                t[(j, j + 1)] = beta.clone();
+                t[(j + 1, j)] = beta;
+            } else {
+                beta_m = beta;
+            }
+        }
+        iterations += 1;
+
+        // Rayleigh-Ritz step
+        let (theta, y) = ritz_decomposition(t.clone(), problem.which);
+
+        let basis = v.columns(0, m);
+        let pairs: Vec<_> = theta[..nev]
+            .iter()
+            .zip(y.column_iter())
+            .map(|(theta_i, y_i)| {
+                let lambda = (problem.to_eigenvalue)(theta_i.clone());
+                let x = &basis * y_i;
+                let residual = residual_norm(problem.k, problem.m, lambda.clone(), &x);
+                (lambda, x, residual)
+            })
+            .collect();
+
+        let converged = pairs
+            .iter()
+            .all(|(lambda, _, residual)| *residual <= options.threshold(lambda.clone()));
+        // If the basis spans the whole space, the Ritz pairs are exact up to rounding errors.
+        // Otherwise, restarting requires room for at least one new basis vector
+        let retained = (nev + (m - nev) / 2).min(m - 1);
+        if converged || m == n || iterations >= options.max_iterations || retained < nev {
+            return PartialEigen::new(pairs, n, iterations, options);
+        }
+
+        // Thick restart: retain the most relevant Ritz vectors along with the last basis vector
+        let ritz_vectors = &basis * y.columns(0, retained);
+        let last = v.column(m).clone_owned();
+        v.columns_mut(0, retained).copy_from(&ritz_vectors);
+        v.column_mut(retained).copy_from(&last);
+
+        t.fill(T::zero());
+        for c in 0..retained {
+            t[(c, c)] = theta[c].clone();
+            let coupling = beta_m.clone() * y[(m - 1, c)].clone();
+            t[(c, retained)] = coupling.clone();
+            t[(retained, c)] = coupling;
+        }
+        k = retained;
+    }
+}
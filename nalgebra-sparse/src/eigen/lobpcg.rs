@@ -0,0 +1,256 @@
+use crate::eigen::{
+    b_product, residual_norm, ritz_decomposition, EigenOptions, PartialEigen, Which,
+};
+use crate::operator::LinearOperator;
+use crate::solvers::Preconditioner;
+use nalgebra::{DMatrix, DVector, RealField};
+
+/// Computes a few eigenpairs of the symmetric matrix `A` with the locally optimal block
+/// preconditioned conjugate gradient (LOBPCG) method.
+///
+/// The number of computed eigenpairs is given by the number of columns of the initial block
+/// `x`, whose columns must be linearly independent. A random initial block is usually a good
+/// choice if no better approximations are available. The eigenpairs at the end of the spectrum
+/// selected by `which` are computed.
+///
+/// The preconditioner should approximate the inverse of `A` (or of `A - σ I` for a shift `σ`
+/// close to the wanted eigenvalues). Use
+/// [`IdentityPreconditioner`](crate::solvers::IdentityPreconditioner) to run the method without
+/// preconditioning. Converged eigenpairs are kept in the block, but their residuals no longer
+/// contribute to the search space.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, if the dimensions of `A` and `x` are not compatible, or if the
+/// columns of `x` are linearly dependent.
+pub fn lobpcg<T, A, P>(
+    a: &A,
+    x: &DMatrix<T>,
+    preconditioner: &P,
+    which: Which,
+    options: &EigenOptions<T>,
+) -> PartialEigen<T>
+where
+    T: RealField,
+    A: LinearOperator<T>,
+    P: Preconditioner<T> + ?Sized,
+{
+    assert_eq!(a.nrows(), a.ncols(), "A must be square.");
+    assert_eq!(a.nrows(), x.nrows(), "A.nrows() != x.nrows()");
+    lobpcg_impl(a, None, x, preconditioner, which, options)
+}
+
+/// Computes a few eigenpairs of the generalized problem `K x = λ M x` with the LOBPCG method.
+///
+/// `K` must be symmetric and `M` must be symmetric positive definite. The method only requires
+/// products with `K` and `M`, and therefore does not need to factor either matrix. The
+/// preconditioner should approximate the inverse of `K` (or of `K - σ M` for a shift `σ` close
+/// to the wanted eigenvalues). See [`lobpcg`] for the meaning of the remaining parameters.
+///
+/// # Panics
+///
+/// Panics if `K` and `M` are not square matrices of the same dimensions, if the dimensions of
+/// `K` and `x` are not compatible, or if the columns of `x` are linearly dependent.
+pub fn lobpcg_generalized<T, K, M, P>(
+    k: &K,
+    m: &M,
+    x: &DMatrix<T>,
+    preconditioner: &P,
+    which: Which,
+    options: &EigenOptions<T>,
+) -> PartialEigen<T>
+where
+    T: RealField,
+    K: LinearOperator<T>,
+    M: LinearOperator<T>,
+    P: Preconditioner<T> + ?Sized,
+{
+    assert_eq!(k.nrows(), k.ncols(), "K must be square.");
+    assert_eq!(m.nrows(), m.ncols(), "M must be square.");
+    assert_eq!(
+        k.nrows(),
+        m.nrows(),
+        "K and M must have the same dimensions."
+    );
+    assert_eq!(k.nrows(), x.nrows(), "K.nrows() != x.nrows()");
+    lobpcg_impl(k, Some(m), x, preconditioner, which, options)
+}
+
+fn lobpcg_impl<T, P>(
+    a: &dyn LinearOperator<T>,
+    b: Option<&dyn LinearOperator<T>>,
+    x0: &DMatrix<T>,
+    preconditioner: &P,
+    which: Which,
+    options: &EigenOptions<T>,
+) -> PartialEigen<T>
+where
+    T: RealField,
+    P: Preconditioner<T> + ?Sized,
+{
+    let (n, nev) = x0.shape();
+    if nev == 0 {
+        return PartialEigen::new(Vec::new(), n, 0, options);
+    }
+
+    let mut basis = Basis::new(a, b);
+    for column in x0.column_iter() {
+        assert!(
+            basis.push(column.clone_owned()),
+            "The columns of the initial block must be linearly independent."
+        );
+    }
+    let (mut theta, mut x, mut p) = basis.rayleigh_ritz(nev, which);
+    let mut iterations = 0;
+
+    loop {
+        // Compute residuals R = A X - B X Θ
+        let mut residuals = Vec::with_capacity(nev);
+        for (i, (_, ax_i, bx_i)) in x.iter().enumerate() {
+            let r_i = ax_i - bx_i * theta[i].clone();
+            residuals.push(r_i);
+        }
+        let active: Vec<usize> = (0..nev)
+            .filter(|&i| residuals[i].norm() > options.threshold(theta[i].clone()))
+            .collect();
+
+        if active.is_empty() || iterations >= options.max_iterations {
+            let pairs = x
+                .into_iter()
+                .zip(theta)
+                .map(|((x_i, _, _), lambda)| {
+                    // Use an explicitly computed residual, to avoid reporting the accumulated
+                    // rounding errors of the recurrences
+                    let residual = residual_norm(a, b, lambda.clone(), &x_i);
+                    (lambda, x_i, residual)
+                })
+                .collect();
+            return PartialEigen::new(pairs, n, iterations, options);
+        }
+        iterations += 1;
+
+        // Build a B-orthonormal basis of the search space spanned by [X, W, P]
+        let mut basis = Basis::new(a, b);
+        for (x_i, ax_i, bx_i) in &x {
+            basis.push_orthonormal(x_i.clone(), ax_i.clone(), bx_i.clone());
+        }
+        for &i in &active {
+            let mut w = DVector::zeros(n);
+            preconditioner.apply((&residuals[i]).into(), (&mut w).into());
+            basis.push(w);
+        }
+        for p_i in p {
+            basis.push(p_i);
+        }
+
+        let (theta_new, x_new, p_new) = basis.rayleigh_ritz(nev, which);
+        theta = theta_new;
+        x = x_new;
+        p = p_new;
+    }
+}
+
+/// A `B`-orthonormal basis `S` of a search space, along with the products `A S` and `B S`.
+struct Basis<'a, T> {
+    a: &'a dyn LinearOperator<T>,
+    b: Option<&'a dyn LinearOperator<T>>,
+    s: Vec<DVector<T>>,
+    a_s: Vec<DVector<T>>,
+    b_s: Vec<DVector<T>>,
+}
+
+/// Ritz values, Ritz vectors along with their products with `A` and `B`, and the components
+/// of the Ritz vectors orthogonal to the previous block, which are the new search directions.
+type RitzPairs<T> = (
+    Vec<T>,
+    Vec<(DVector<T>, DVector<T>, DVector<T>)>,
+    Vec<DVector<T>>,
+);
+
+impl<'a, T: RealField> Basis<'a, T> {
+    fn new(a: &'a dyn LinearOperator<T>, b: Option<&'a dyn LinearOperator<T>>) -> Self {
+        Self {
+            a,
+            b,
+            s: Vec::new(),
+            a_s: Vec::new(),
+            b_s: Vec::new(),
+        }
+    }
+
+    /// Appends a vector, together with its precomputed products with `A` and `B`, that is
+    /// already `B`-orthonormal to the basis.
+    fn push_orthonormal(&mut self, s: DVector<T>, a_s: DVector<T>, b_s: DVector<T>) {
+        self.s.push(s);
+        self.a_s.push(a_s);
+        self.b_s.push(b_s);
+    }
+
+    /// Orthogonalizes `w` against the basis and appends the normalized result.
+    ///
+    /// Returns `false` and leaves the basis unchanged if `w` is numerically contained in the
+    /// span of the basis.
+    fn push(&mut self, mut w: DVector<T>) -> bool {
+        let mut b_w = b_product(self.b, &w);
+        let initial_norm = w.dot(&b_w).max(T::zero()).sqrt();
+        for _ in 0..2 {
+            for (s_i, b_s_i) in self.s.iter().zip(&self.b_s) {
+                let h = b_s_i.dot(&w);
+                w.axpy(-h, s_i, T::one());
+            }
+            b_w = b_product(self.b, &w);
+        }
+        let norm = w.dot(&b_w).max(T::zero()).sqrt();
+
+        // Dropping nearly dependent directions keeps the Gram matrix well-conditioned
+        if norm.is_zero() || norm <= T::default_epsilon().sqrt() * initial_norm {
+            return false;
+        }
+        w /= norm.clone();
+        b_w /= norm;
+        let mut a_w = DVector::zeros(w.nrows());
+        self.a
+            .apply(T::zero(), (&mut a_w).into(), T::one(), (&w).into());
+        self.push_orthonormal(w, a_w, b_w);
+        true
+    }
+
+    /// Performs the Rayleigh-Ritz procedure on the basis and returns the `nev` wanted Ritz
+    /// pairs.
+    ///
+    /// The first `nev` basis vectors are assumed to be the previous block of Ritz vectors.
+    fn rayleigh_ritz(&self, nev: usize, which: Which) -> RitzPairs<T> {
+        let dim = self.s.len();
+        let gram = DMatrix::from_fn(dim, dim, |i, j| {
+            let half: T = nalgebra::convert(0.5);
+            half * (self.s[i].dot(&self.a_s[j]) + self.s[j].dot(&self.a_s[i]))
+        });
+        let (eigenvalues, eigenvectors) = ritz_decomposition(gram, which);
+
+        let combine = |vectors: &[DVector<T>], coefficients: &[T]| {
+            let mut result = DVector::zeros(vectors[0].nrows());
+            for (v, c) in vectors.iter().zip(coefficients) {
+                result.axpy(c.clone(), v, T::one());
+            }
+            result
+        };
+
+        let mut theta = Vec::with_capacity(nev);
+        let mut x = Vec::with_capacity(nev);
+        let mut p = Vec::new();
+        for (i, lambda) in eigenvalues.into_iter().take(nev).enumerate() {
+            let c = eigenvectors.column(i);
+            let c = c.as_slice();
+            theta.push(lambda);
+            x.push((
+                combine(&self.s, c),
+                combine(&self.a_s, c),
+                combine(&self.b_s, c),
+            ));
+            if dim > nev {
+                p.push(combine(&self.s[nev..], &c[nev..]));
+            }
+        }
+        (theta, x, p)
+    }
+}
@@ -0,0 +1,341 @@
+//! Iterative eigensolvers for large, sparse symmetric eigenvalue problems.
+//!
+//! The solvers in this module compute a few eigenpairs at either end of the spectrum of a
+//! symmetric matrix `A`, i.e. solutions of `A x = λ x`, or of a symmetric-definite pencil
+//! `(K, M)`, i.e. solutions of the generalized problem `K x = λ M x` with symmetric `K` and
+//! symmetric positive definite `M`. The latter typically arises in modal analysis, where `K`
+//! and `M` are the stiffness and mass matrices.
+//!
+//! The following solvers are available:
+//!
+//! | Solver                     | Problem          | Notes                                       |
+//! | -------------------------- | ---------------- | ------------------------------------------- |
+//! | [`lanczos`]                | `A x = λ x`      | Only requires products with `A`.            |
+//! | [`lanczos_generalized`]    | `K x = λ M x`    | Factors `M` with [`CscCholesky`](crate::factorization::CscCholesky). |
+//! | [`lanczos_shift_invert`]   | `K x = λ M x`    | Factors `K - σ M`. Eigenvalues closest to `σ`. |
+//! | [`lobpcg`]                 | `A x = λ x`      | Block method, benefits from a preconditioner. |
+//! | [`lobpcg_generalized`]     | `K x = λ M x`    | Only requires products with `K` and `M`.    |
+//!
+//! The Lanczos solvers use the thick-restart Lanczos method, which keeps the memory usage
+//! bounded by restarting with the most relevant Ritz vectors. LOBPCG iterates on a block of
+//! vectors and can take advantage of any [`Preconditioner`](crate::solvers::Preconditioner),
+//! such as an [incomplete Cholesky factorization](crate::solvers::Ic0) of `K`. In both cases,
+//! the Rayleigh–Ritz step is carried out with nalgebra's dense
+//! [`SymmetricEigen`](nalgebra::linalg::SymmetricEigen) decomposition.
+//!
+//! All solvers return a [`PartialEigen`], which holds the computed eigenpairs together with
+//! their residual norms. Like the [iterative linear solvers](crate::solvers), the eigensolvers
+//! do not fail when the iteration does not converge. Use [`PartialEigen::converged`] to check
+//! whether all eigenpairs satisfy the requested tolerance.
+//!
+//! # Example
+//!
+//! ```
+//! use nalgebra_sparse::coo::CooMatrix;
+//! use nalgebra_sparse::csr::CsrMatrix;
+//! use nalgebra_sparse::eigen::{lanczos, EigenOptions, Which};
+//!
+//! // Assemble the 1D Laplacian, whose eigenvalues are 2 - 2 cos(k π / (n + 1))
+//! let n = 50;
+//! let mut coo = CooMatrix::new(n, n);
+//! for i in 0..n {
+//!     coo.push(i, i, 2.0);
+//!     if i > 0 { coo.push(i, i - 1, -1.0); }
+//!     if i + 1 < n { coo.push(i, i + 1, -1.0); }
+//! }
+//! let a = CsrMatrix::from(&coo);
+//!
+//! // Compute the three largest eigenvalues with a Lanczos basis of dimension 20
+//! let eigen = lanczos(&a, 3, Which::Largest, 20, &EigenOptions::default());
+//! assert!(eigen.converged());
+//!
+//! let pi = std::f64::consts::PI;
+//! for (i, &lambda) in eigen.eigenvalues().iter().enumerate() {
+//!     let k = (n - 2 + i) as f64;
+//!     assert!((lambda - (2.0 - 2.0 * (k * pi / (n + 1) as f64).cos())).abs() < 1e-8);
+//! }
+//! ```
+
+mod lanczos;
+mod lobpcg;
+
+pub use lanczos::*;
+pub use lobpcg::*;
+
+use crate::operator::LinearOperator;
+use nalgebra::{convert, DMatrix, DMatrixView, DVector, RealField, SymmetricEigen};
+use std::cmp::Ordering;
+
+/// Which end of the spectrum to compute eigenpairs for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Which {
+    /// The algebraically smallest eigenvalues.
+    Smallest,
+    /// The algebraically largest eigenvalues.
+    Largest,
+}
+
+/// Parameters that control the termination of an iterative eigensolver.
+///
+/// An eigenpair `(λ, x)`, with `x` normalized such that `x^T M x = 1`, is considered converged
+/// once its residual satisfies `||K x - λ M x|| <= max(relative_tolerance * |λ|,
+/// absolute_tolerance)`. For the standard eigenvalue problem, `M = I`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EigenOptions<T> {
+    /// The maximum number of iterations.
+    ///
+    /// For the Lanczos solvers, this is the maximum number of restarts.
+    pub max_iterations: usize,
+    /// The tolerance for the residual norm relative to the magnitude of the eigenvalue.
+    pub relative_tolerance: T,
+    /// The tolerance for the absolute residual norm.
+    pub absolute_tolerance: T,
+}
+
+impl<T: RealField> Default for EigenOptions<T> {
+    /// At most 1000 iterations, a relative tolerance of `1e-8` and an absolute tolerance of zero.
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            relative_tolerance: convert(1e-8),
+            absolute_tolerance: T::zero(),
+        }
+    }
+}
+
+impl<T: RealField> EigenOptions<T> {
+    /// The residual norm below which an eigenpair with the given eigenvalue is considered
+    /// converged.
+    fn threshold(&self, eigenvalue: T) -> T {
+        let relative = self.relative_tolerance.clone() * eigenvalue.abs();
+        relative.max(self.absolute_tolerance.clone())
+    }
+}
+
+/// A subset of the eigenpairs of a symmetric eigenvalue problem.
+///
+/// The eigenvalues are sorted in ascending order, and the `i`-th column of the eigenvector
+/// matrix is the eigenvector associated with the `i`-th eigenvalue. For the generalized
+/// problem `K x = λ M x`, the eigenvectors are `M`-orthonormal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialEigen<T: RealField> {
+    eigenvalues: DVector<T>,
+    eigenvectors: DMatrix<T>,
+    residual_norms: DVector<T>,
+    iterations: usize,
+    converged: bool,
+}
+
+impl<T: RealField> PartialEigen<T> {
+    fn new(
+        mut pairs: Vec<(T, DVector<T>, T)>,
+        n: usize,
+        iterations: usize,
+        options: &EigenOptions<T>,
+    ) -> Self {
+        pairs.sort_by(|(a, _, _), (b, _, _)| compare_wanted(a, b, Which::Smallest));
+        let converged = pairs
+            .iter()
+            .all(|(lambda, _, residual)| *residual <= options.threshold(lambda.clone()));
+        let eigenvalues = DVector::from_iterator(pairs.len(), pairs.iter().map(|p| p.0.clone()));
+        let residual_norms = DVector::from_iterator(pairs.len(), pairs.iter().map(|p| p.2.clone()));
+        let mut eigenvectors = DMatrix::zeros(n, pairs.len());
+        for (mut column, (_, x, _)) in eigenvectors.column_iter_mut().zip(&pairs) {
+            column.copy_from(x);
+        }
+        Self {
+            eigenvalues,
+            eigenvectors,
+            residual_norms,
+            iterations,
+            converged,
+        }
+    }
+
+    /// The computed eigenvalues, in ascending order.
+    #[must_use]
+    pub fn eigenvalues(&self) -> &DVector<T> {
+        &self.eigenvalues
+    }
+
+    /// The computed eigenvectors, stored column by column.
+    #[must_use]
+    pub fn eigenvectors(&self) -> &DMatrix<T> {
+        &self.eigenvectors
+    }
+
+    /// The Euclidean norms of the residuals `K x - λ M x` of the computed eigenpairs.
+    #[must_use]
+    pub fn residual_norms(&self) -> &DVector<T> {
+        &self.residual_norms
+    }
+
+    /// The number of iterations that were performed.
+    ///
+    /// For the Lanczos solvers, this is the number of restart cycles.
+    #[must_use]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether all computed eigenpairs satisfy the tolerances given in the [`EigenOptions`].
+    #[must_use]
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Consumes the decomposition and returns the eigenvalues and eigenvectors.
+    pub fn into_parts(self) -> (DVector<T>, DMatrix<T>) {
+        (self.eigenvalues, self.eigenvectors)
+    }
+}
+
+/// Orders `a` before `b` if it is more wanted at the end of the spectrum given by `which`.
+///
+/// NaN values, which may arise from an operator that produces NaN, are ordered after all other
+/// values instead of causing a panic.
+fn compare_wanted<T: RealField>(a: &T, b: &T, which: Which) -> Ordering {
+    // NaN is the only value that is not equal to itself
+    #[allow(clippy::eq_op)]
+    match (a != a, b != b) {
+        (false, false) => {
+            let ordering = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+            match which {
+                Which::Smallest => ordering,
+                Which::Largest => ordering.reverse(),
+            }
+        }
+        (a_is_nan, b_is_nan) => a_is_nan.cmp(&b_is_nan),
+    }
+}
+
+/// Computes the eigendecomposition of the small, dense symmetric matrix `h` for the
+/// Rayleigh-Ritz step, with the eigenpairs ordered from the most to the least wanted.
+fn ritz_decomposition<T: RealField>(h: DMatrix<T>, which: Which) -> (Vec<T>, DMatrix<T>) {
+    let mut eigenvectors = SymmetricEigen::new(h.clone()).eigenvectors;
+    let mut d = eigenvectors.tr_mul(&h) * &eigenvectors;
+    jacobi_polish(&mut d, &mut eigenvectors);
+
+    let mut order: Vec<usize> = (0..d.nrows()).collect();
+    order.sort_by(|&i, &j| compare_wanted(&d[(i, i)], &d[(j, j)], which));
+    let eigenvalues = order.iter().map(|&i| d[(i, i)].clone()).collect();
+    (eigenvalues, eigenvectors.select_columns(&order))
+}
+
+/// Diagonalizes the nearly diagonal symmetric matrix `d = Y^T H Y` with cyclic Jacobi
+/// sweeps, accumulating the rotations into `y`.
+///
+/// `SymmetricEigen` may lose the eigenvector components that are induced by tiny off-diagonal
+/// entries of nearly decoupled 2x2 blocks. Once a Ritz pair is accurate to about the square
+/// root of the machine epsilon, these components are exactly what drives further convergence,
+/// so the iteration would stagnate without this correction. Since Jacobi's method converges
+/// quadratically for nearly diagonal matrices, one or two sweeps usually suffice.
+fn jacobi_polish<T: RealField>(d: &mut DMatrix<T>, y: &mut DMatrix<T>) {
+    const MAX_SWEEPS: usize = 10;
+    let n = d.nrows();
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal = (0..n)
+            .flat_map(|j| (0..j).map(move |i| (i, j)))
+            .fold(T::zero(), |acc, (i, j)| acc + d[(i, j)].clone().powi(2))
+            .sqrt();
+        if off_diagonal <= T::default_epsilon() * d.norm() {
+            break;
+        }
+
+        for q in 0..n {
+            for p in 0..q {
+                let d_pq = d[(p, q)].clone();
+                if d_pq.is_zero() {
+                    continue;
+                }
+                // The rotation [c s; -s c], where t = s / c is the smaller root of
+                // t^2 + 2 ζ t - 1 = 0, annihilates the (p, q) entry
+                let zeta = (d[(q, q)].clone() - d[(p, p)].clone()) / (d_pq.clone() + d_pq);
+                let t = zeta.clone().signum()
+                    / (zeta.clone().abs() + (zeta.clone() * zeta + T::one()).sqrt());
+                let c = T::one() / (t.clone() * t.clone() + T::one()).sqrt();
+                let s = t * c.clone();
+                rotate_columns(d, p, q, &c, &s);
+                for k in 0..n {
+                    let (a, b) = (d[(p, k)].clone(), d[(q, k)].clone());
+                    d[(p, k)] = c.clone() * a.clone() - s.clone() * b.clone();
+                    d[(q, k)] = s.clone() * a + c.clone() * b;
+                }
+                rotate_columns(y, p, q, &c, &s);
+                d[(p, q)] = T::zero();
+                d[(q, p)] = T::zero();
+            }
+        }
+    }
+}
+
+/// Replaces the columns `p` and `q` of `m` by `c m_p - s m_q` and `s m_p + c m_q`.
+fn rotate_columns<T: RealField>(m: &mut DMatrix<T>, p: usize, q: usize, c: &T, s: &T) {
+    for k in 0..m.nrows() {
+        let (a, b) = (m[(k, p)].clone(), m[(k, q)].clone());
+        m[(k, p)] = c.clone() * a.clone() - s.clone() * b.clone();
+        m[(k, q)] = s.clone() * a + c.clone() * b;
+    }
+}
+
+/// Computes `B w`, where `B = I` if `b` is `None`.
+fn b_product<T: RealField>(b: Option<&dyn LinearOperator<T>>, w: &DVector<T>) -> DVector<T> {
+    match b {
+        Some(b) => {
+            let mut bw = DVector::zeros(w.nrows());
+            b.apply(T::zero(), (&mut bw).into(), T::one(), w.into());
+            bw
+        }
+        None => w.clone(),
+    }
+}
+
+/// Makes `w` orthogonal to the `B`-orthonormal columns of `basis` with respect to the
+/// `B`-inner product, and returns the `B`-norm of the result.
+///
+/// Classical Gram-Schmidt is applied twice, which is sufficient to obtain orthogonality to
+/// working precision. Returns the accumulated projection coefficients alongside the norm.
+fn b_orthogonalize<T: RealField>(
+    w: &mut DVector<T>,
+    basis: DMatrixView<'_, T>,
+    b: Option<&dyn LinearOperator<T>>,
+) -> (DVector<T>, T) {
+    let mut coefficients = DVector::zeros(basis.ncols());
+    let mut bw = b_product(b, w);
+    for _ in 0..2 {
+        let h = basis.tr_mul(&bw);
+        w.gemv(-T::one(), &basis, &h, T::one());
+        coefficients += h;
+        bw = b_product(b, w);
+    }
+    let norm = w.dot(&bw).max(T::zero()).sqrt();
+    (coefficients, norm)
+}
+
+/// Computes the Euclidean norm of `K x - λ M x`, where `M = I` if `m` is `None`.
+fn residual_norm<T: RealField>(
+    k: &dyn LinearOperator<T>,
+    m: Option<&dyn LinearOperator<T>>,
+    lambda: T,
+    x: &DVector<T>,
+) -> T {
+    let mut r = b_product(m, x);
+    k.apply(-lambda, (&mut r).into(), T::one(), x.into());
+    r.norm()
+}
+
+/// Returns a deterministic vector with pseudo-random entries in `[-1, 1]`.
+///
+/// Unlike simple choices such as a constant vector, such a vector is very unlikely to be
+/// (nearly) orthogonal to any eigenvector.
+fn pseudo_random_vector<T: RealField>(n: usize, seed: u64) -> DVector<T> {
+    // A linear congruential generator with the constants used by MMIX
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15).wrapping_add(1);
+    DVector::from_fn(n, |_, _| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let unit = (state >> 11) as f64 / (1u64 << 53) as f64;
+        convert(2.0 * unit - 1.0)
+    })
+}
@@ -0,0 +1,229 @@
+//! An implementation of the ELL (ELLPACK) sparse matrix format.
+//!
+//! This is the module-level documentation. See [`EllMatrix`] for the main documentation of the
+//! ELL implementation.
+
+use crate::{SparseFormatError, SparseFormatErrorKind};
+
+/// An ELL representation of a sparse matrix.
+///
+/// The ELLPACK (ELL) format pads every row to the same number of entries, the *width* of the
+/// matrix, and stores the padded rows as two dense `nrows x width` arrays of column indices and
+/// values. Since the arrays are stored in column-major order, consecutive rows are contiguous in
+/// memory, which makes the format well-suited for vectorized and GPU sparse matrix-vector
+/// products. If the number of non-zeros per row varies strongly, the padding overhead can be
+/// reduced by using [`SellMatrix`](crate::sell::SellMatrix) instead.
+///
+/// # Usage
+///
+/// ```
+/// use nalgebra_sparse::csr::CsrMatrix;
+/// use nalgebra_sparse::ell::EllMatrix;
+/// use nalgebra::DMatrix;
+/// use matrixcompare::assert_matrix_eq;
+///
+/// let dense = DMatrix::from_row_slice(3, 3, &[
+///     1.0, 0.0, 2.0,
+///     0.0, 0.0, 0.0,
+///     0.0, 3.0, 0.0,
+/// ]);
+/// let ell = EllMatrix::from(&CsrMatrix::from(&dense));
+/// assert_eq!(ell.width(), 2);
+/// assert_eq!(ell.row_lengths(), &[2, 0, 1]);
+/// assert_matrix_eq!(ell, dense);
+/// ```
+///
+/// # Format
+///
+/// An `m x n` sparse matrix with width `w` in ELL format is represented by the following three
+/// arrays:
+///
+/// - `row_lengths`, an array of integers with length `m`.
+/// - `col_indices`, an array of integers with length `m * w`.
+/// - `values`, an array of values with length `m * w`.
+///
+/// The `k`-th entry of row `i` is stored at index `k * m + i` of `col_indices` and `values`.
+/// Only the first `row_lengths[i]` entries of row `i` are part of the matrix, the remaining
+/// entries are padding and their contents are ignored. The following invariants must be upheld
+/// and are enforced by the data structure:
+///
+/// - `row_lengths[i] <= w` for all `i < m`.
+/// - The column indices of the entries of each row are in bounds and strictly increasing, i.e.
+///   sorted and unique.
+///
+/// Conversions to ELL format pad the rows with zero values and column index `0`.
+#[derive(Debug, Clone)]
+pub struct EllMatrix<T> {
+    nrows: usize,
+    ncols: usize,
+    width: usize,
+    row_lengths: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> EllMatrix<T> {
+    /// Create a zero ELL matrix with width zero.
+    pub fn zeros(nrows: usize, ncols: usize) -> Self {
+        Self {
+            nrows,
+            ncols,
+            width: 0,
+            row_lengths: vec![0; nrows],
+            col_indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Try to construct an ELL matrix from raw ELL data.
+    ///
+    /// An error is returned if the data given does not conform to the ELL storage format.
+    /// See the documentation for [EllMatrix](struct.EllMatrix.html) for more information.
+    pub fn try_from_ell_data(
+        nrows: usize,
+        ncols: usize,
+        width: usize,
+        row_lengths: Vec<usize>,
+        col_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        use SparseFormatError as E;
+        use SparseFormatErrorKind as K;
+
+        if row_lengths.len() != nrows {
+            return Err(E::from_kind_and_msg(
+                K::InvalidStructure,
+                "Length of row length array is not equal to nrows.",
+            ));
+        }
+        let storage_len = nrows.checked_mul(width).ok_or_else(|| {
+            E::from_kind_and_msg(K::InvalidStructure, "Storage size nrows * width overflows.")
+        })?;
+        if col_indices.len() != storage_len || values.len() != storage_len {
+            return Err(E::from_kind_and_msg(
+                K::InvalidStructure,
+                "Length of column index and value arrays must be equal to nrows * width.",
+            ));
+        }
+
+        for (i, &len) in row_lengths.iter().enumerate() {
+            if len > width {
+                return Err(E::from_kind_and_msg(
+                    K::InvalidStructure,
+                    "Row lengths must not exceed the width of the matrix.",
+                ));
+            }
+            let mut prev = None;
+            for k in 0..len {
+                let j = col_indices[k * nrows + i];
+                if j >= ncols {
+                    return Err(E::from_kind_and_msg(
+                        K::IndexOutOfBounds,
+                        "Column indices are out of bounds.",
+                    ));
+                }
+                match prev {
+                    Some(prev) if prev == j => {
+                        return Err(E::from_kind_and_msg(
+                            K::DuplicateEntry,
+                            "Matrix data contains duplicate entries.",
+                        ));
+                    }
+                    Some(prev) if prev > j => {
+                        return Err(E::from_kind_and_msg(
+                            K::InvalidStructure,
+                            "Column indices are not monotonically increasing (sorted) within \
+                             each row.",
+                        ));
+                    }
+                    _ => {}
+                }
+                prev = Some(j);
+            }
+        }
+
+        Ok(Self {
+            nrows,
+            ncols,
+            width,
+            row_lengths,
+            col_indices,
+            values,
+        })
+    }
+
+    /// The number of rows in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// The number of columns in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The width of the matrix, i.e. the number of (possibly padded) entries stored per row.
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of explicitly stored entries in the matrix, excluding padding.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.row_lengths.iter().sum()
+    }
+
+    /// The number of entries of each row, excluding padding.
+    #[inline]
+    #[must_use]
+    pub fn row_lengths(&self) -> &[usize] {
+        &self.row_lengths
+    }
+
+    /// The column indices of the padded entries, in column-major order.
+    #[inline]
+    #[must_use]
+    pub fn col_indices(&self) -> &[usize] {
+        &self.col_indices
+    }
+
+    /// The values of the padded entries, in column-major order.
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Mutable access to the values of the padded entries.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// An iterator over all explicitly stored entries of the matrix, excluding padding.
+    ///
+    /// The iterator yields triplets `(i, j, v)` in row-major order.
+    pub fn triplet_iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        let nrows = self.nrows;
+        self.row_lengths
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, &len)| {
+                (0..len).map(move |k| {
+                    let idx = k * nrows + i;
+                    (i, self.col_indices[idx], &self.values[idx])
+                })
+            })
+    }
+
+    /// Disassembles the ELL matrix into its width, row lengths, column indices and values.
+    pub fn disassemble(self) -> (usize, Vec<usize>, Vec<usize>, Vec<T>) {
+        (self.width, self.row_lengths, self.col_indices, self.values)
+    }
+}
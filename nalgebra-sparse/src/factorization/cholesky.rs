@@ -1,9 +1,12 @@
 use crate::csc::CscMatrix;
-use crate::ops::serial::spsolve_csc_lower_triangular;
+use crate::ops::serial::{spsolve_csc_lower_triangular, spsolve_csc_lower_triangular_adjoint};
 use crate::ops::Op;
+use crate::pattern::graph::elimination_tree;
 use crate::pattern::SparsityPattern;
-use core::{iter, mem};
-use nalgebra::{DMatrix, DMatrixView, DMatrixViewMut, RealField};
+use crate::vector::SparseVector;
+use core::mem;
+use nalgebra::{ComplexField, DMatrix, DMatrixView, DMatrixViewMut};
+use num_traits::{One, Zero};
 use std::fmt::{Display, Formatter};
 
 /// A symbolic sparse Cholesky factorization of a CSC matrix.
@@ -16,6 +19,7 @@ pub struct CscSymbolicCholesky {
     l_pattern: SparsityPattern,
     // u in this context is L^T, so that M = L L^T
     u_pattern: SparsityPattern,
+    etree: Vec<Option<usize>>,
 }
 
 impl CscSymbolicCholesky {
@@ -33,11 +37,13 @@ impl CscSymbolicCholesky {
             pattern.minor_dim(),
             "Major and minor dimensions must be the same (square matrix)."
         );
-        let (l_pattern, u_pattern) = nonzero_pattern(&pattern);
+        let etree = elimination_tree(&pattern);
+        let (l_pattern, u_pattern) = nonzero_pattern(&pattern, &etree);
         Self {
             m_pattern: pattern,
             l_pattern,
             u_pattern,
+            etree,
         }
     }
 
@@ -46,12 +52,25 @@ impl CscSymbolicCholesky {
     pub fn l_pattern(&self) -> &SparsityPattern {
         &self.l_pattern
     }
+
+    /// The elimination tree of the matrix, given as the parent of each column.
+    ///
+    /// The parent of column `j` is the row of the first off-diagonal entry in column `j` of `L`.
+    #[must_use]
+    pub fn elimination_tree(&self) -> &[Option<usize>] {
+        &self.etree
+    }
 }
 
-/// A sparse Cholesky factorization `A = L L^T` of a [`CscMatrix`].
+/// A sparse Cholesky factorization `A = L L^H` of a [`CscMatrix`].
+///
+/// The factor `L` is a sparse, lower-triangular matrix with a real and positive diagonal. For
+/// real matrices, `L^H = L^T`, while complex matrices must be Hermitian. See the article on
+/// [Wikipedia] for more information.
 ///
-/// The factor `L` is a sparse, lower-triangular matrix. See the article on [Wikipedia] for
-/// more information.
+/// An existing factorization can be modified without refactoring the whole matrix through
+/// rank-one and rank-k updates and downdates, and by deleting and adding rows and columns, as
+/// long as the modification fits within the sparsity pattern of `L`.
 ///
 /// The implementation is a port of the `CsCholesky` implementation in `nalgebra`. It is similar
 /// to Tim Davis' [`CSparse`]. The current implementation performs no fill-in reduction, and can
@@ -68,6 +87,7 @@ pub struct CscCholesky<T> {
     m_pattern: SparsityPattern,
     l_factor: CscMatrix<T>,
     u_pattern: SparsityPattern,
+    etree: Vec<Option<usize>>,
     work_x: Vec<T>,
     work_c: Vec<usize>,
 }
@@ -78,17 +98,25 @@ pub struct CscCholesky<T> {
 pub enum CholeskyError {
     /// The matrix is not positive definite.
     NotPositiveDefinite,
+    /// The sparsity pattern of a modification does not fit within the pattern of the factor.
+    IncompatiblePattern,
 }
 
 impl Display for CholeskyError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Matrix is not positive definite")
+        match self {
+            Self::NotPositiveDefinite => write!(f, "Matrix is not positive definite"),
+            Self::IncompatiblePattern => write!(
+                f,
+                "Sparsity pattern of the modification is not contained in the pattern of the factor"
+            ),
+        }
     }
 }
 
 impl std::error::Error for CholeskyError {}
 
-impl<T: RealField> CscCholesky<T> {
+impl<T: ComplexField> CscCholesky<T> {
     /// Computes the numerical Cholesky factorization associated with the given
     /// symbolic factorization and the provided values.
     ///
@@ -125,6 +153,7 @@ impl<T: RealField> CscCholesky<T> {
             m_pattern: symbolic.m_pattern,
             l_factor,
             u_pattern: symbolic.u_pattern,
+            etree: symbolic.etree,
             work_x: vec![T::zero(); nrows],
             // Fill with MAX so that things hopefully totally fail if values are not
             // overwritten. Might be easier to debug this way
@@ -137,8 +166,9 @@ impl<T: RealField> CscCholesky<T> {
 
     /// Computes the Cholesky factorization of the provided matrix.
     ///
-    /// The matrix must be symmetric positive definite. Symmetry is not checked, and it is up
-    /// to the user to enforce this property.
+    /// The matrix must be symmetric (Hermitian for complex matrices) positive definite. Symmetry
+    /// is not checked, and it is up to the user to enforce this property. Only the lower
+    /// triangular part of the matrix is read.
     ///
     /// # Errors
     ///
@@ -182,6 +212,292 @@ impl<T: RealField> CscCholesky<T> {
         self.l_factor
     }
 
+    /// Updates the factorization of `A` to a factorization of `A + w w^H`.
+    ///
+    /// Only the columns of `L` on the path from the first entry of `w` to the root of the
+    /// elimination tree are modified, which is typically much cheaper than a full
+    /// refactorization. The sparsity pattern of `L` is never changed, and so the pattern of `w`
+    /// must be contained in the pattern of the column of `L` corresponding to the first entry of
+    /// `w`. Modifications whose patterns are known in advance can be accommodated by including
+    /// them as explicit zeros in the matrix that is initially factored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CholeskyError::IncompatiblePattern`] if the pattern of `w` does not fit within
+    /// the pattern of `L`, in which case the factorization is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `w` does not match the dimensions of the matrix.
+    pub fn rank_one_update(&mut self, w: &SparseVector<T>) -> Result<(), CholeskyError> {
+        assert_eq!(w.len(), self.l_factor.nrows(), "Dimension mismatch.");
+        self.update_downdate(&[(w.indices(), w.values())], true)
+    }
+
+    /// Updates the factorization of `A` to a factorization of `A - w w^H`.
+    ///
+    /// See [`rank_one_update`](Self::rank_one_update) for the requirements on the pattern of
+    /// `w`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CholeskyError::NotPositiveDefinite`] if `A - w w^H` is not positive definite,
+    /// and [`CholeskyError::IncompatiblePattern`] if the pattern of `w` does not fit within the
+    /// pattern of `L`. In both cases, the factorization is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `w` does not match the dimensions of the matrix.
+    pub fn rank_one_downdate(&mut self, w: &SparseVector<T>) -> Result<(), CholeskyError> {
+        assert_eq!(w.len(), self.l_factor.nrows(), "Dimension mismatch.");
+        self.update_downdate(&[(w.indices(), w.values())], false)
+    }
+
+    /// Updates the factorization of `A` to a factorization of `A + C C^H`.
+    ///
+    /// This is equivalent to a rank-one update with each column of `C`, and each column must
+    /// satisfy the requirements of [`rank_one_update`](Self::rank_one_update).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CholeskyError::IncompatiblePattern`] if the pattern of a column of `C` does not
+    /// fit within the pattern of `L`, in which case the factorization is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of rows of `C` does not match the dimensions of the matrix.
+    pub fn rank_k_update(&mut self, c: &CscMatrix<T>) -> Result<(), CholeskyError> {
+        assert_eq!(c.nrows(), self.l_factor.nrows(), "Dimension mismatch.");
+        let columns: Vec<_> = c
+            .col_offsets()
+            .windows(2)
+            .map(|w| (&c.row_indices()[w[0]..w[1]], &c.values()[w[0]..w[1]]))
+            .collect();
+        self.update_downdate(&columns, true)
+    }
+
+    /// Updates the factorization of `A` to a factorization of `A - C C^H`.
+    ///
+    /// This is equivalent to a rank-one downdate with each column of `C`, and each column must
+    /// satisfy the requirements of [`rank_one_update`](Self::rank_one_update).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CholeskyError::NotPositiveDefinite`] if `A - C C^H` is not positive definite,
+    /// and [`CholeskyError::IncompatiblePattern`] if the pattern of a column of `C` does not fit
+    /// within the pattern of `L`. In both cases, the factorization is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of rows of `C` does not match the dimensions of the matrix.
+    pub fn rank_k_downdate(&mut self, c: &CscMatrix<T>) -> Result<(), CholeskyError> {
+        assert_eq!(c.nrows(), self.l_factor.nrows(), "Dimension mismatch.");
+        let columns: Vec<_> = c
+            .col_offsets()
+            .windows(2)
+            .map(|w| (&c.row_indices()[w[0]..w[1]], &c.values()[w[0]..w[1]]))
+            .collect();
+        self.update_downdate(&columns, false)
+    }
+
+    /// Replaces row and column `k` of the factored matrix by those of the identity matrix.
+    ///
+    /// This corresponds to removing the `k`-th unknown from the system, for example when a
+    /// constraint is deactivated. The row and column can later be restored with
+    /// [`add_row`](Self::add_row). Only row and column `k` of `L` and the columns on the path
+    /// from `k` to the root of the elimination tree are modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is out of bounds.
+    pub fn delete_row(&mut self, k: usize) {
+        assert!(k < self.l_factor.ncols(), "Row index out of bounds.");
+
+        let (offsets, rows, values) = self.l_factor.csc_data_mut();
+        // Zero out the off-diagonal entries in row k of L
+        for &j in self.u_pattern.lane(k).iter().filter(|&&j| j < k) {
+            let range = offsets[j]..offsets[j + 1];
+            let p = rows[range.clone()]
+                .binary_search(&k)
+                .expect("Internal error: Row pattern of L must be consistent with L");
+            values[range.start + p] = T::zero();
+        }
+
+        // Replace column k by the unit vector e_k. The contribution of the removed off-diagonal
+        // entries to the trailing part of the matrix is then restored by a rank-one update.
+        let (diag, below) = (offsets[k], offsets[k] + 1..offsets[k + 1]);
+        let w_indices = rows[below.clone()].to_vec();
+        let w_values: Vec<T> = values[below]
+            .iter_mut()
+            .map(|v| mem::replace(v, T::zero()))
+            .collect();
+        values[diag] = T::one();
+
+        self.update_downdate(&[(&w_indices, &w_values)], true)
+            .expect("Internal error: Off-diagonal pattern of a column of L must fit its parent");
+    }
+
+    /// Sets row and column `k` of the factored matrix to `a` (and `a^H`, respectively).
+    ///
+    /// Row and column `k` must previously have been removed with
+    /// [`delete_row`](Self::delete_row). The vector `a` is the full new column `k` of the
+    /// matrix, including the diagonal entry and the entries above it. Its pattern must be
+    /// contained in the pattern of column `k` of the matrix that was initially factored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CholeskyError::NotPositiveDefinite`] if the resulting matrix is not positive
+    /// definite, and [`CholeskyError::IncompatiblePattern`] if the pattern of `a` does not fit
+    /// within the pattern of `L`. In both cases, the factorization is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is out of bounds, if the length of `a` does not match the dimensions of the
+    /// matrix, or if row and column `k` have not been deleted.
+    pub fn add_row(&mut self, k: usize, a: &SparseVector<T>) -> Result<(), CholeskyError> {
+        let n = self.l_factor.ncols();
+        assert!(k < n, "Row index out of bounds.");
+        assert_eq!(a.len(), n, "Dimension mismatch.");
+
+        let col_k = self.l_factor.col(k);
+        let row_pattern: Vec<usize> = self
+            .u_pattern
+            .lane(k)
+            .iter()
+            .copied()
+            .filter(|&j| j < k)
+            .collect();
+        let is_deleted = col_k.values()[0].is_one()
+            && col_k.values()[1..].iter().all(T::is_zero)
+            && row_pattern
+                .iter()
+                .all(|&j| self.l_factor.index_entry(k, j).into_value().is_zero());
+        assert!(
+            is_deleted,
+            "Row and column k must be deleted before they can be added."
+        );
+
+        let col_pattern = col_k.row_indices();
+        let fits = a.indices().iter().all(|i| {
+            if *i < k {
+                row_pattern.binary_search(i).is_ok()
+            } else {
+                col_pattern.binary_search(i).is_ok()
+            }
+        });
+        if !fits {
+            return Err(CholeskyError::IncompatiblePattern);
+        }
+
+        // Solve L_11 x = a_12 for the conjugate x of the new row of L, and simultaneously
+        // accumulate a_32 - L_31 x in the entries below the diagonal.
+        let mut x = vec![T::zero(); n];
+        for (i, v) in a.iter() {
+            x[i] = v.clone();
+        }
+        let mut diag = x[k].clone().real();
+        for &j in &row_pattern {
+            let col_j = self.l_factor.col(j);
+            let x_j = x[j].clone() / col_j.values()[0].clone();
+            diag -= x_j.clone().modulus_squared();
+            for (&i, l_ij) in col_j.row_indices().iter().zip(col_j.values()).skip(1) {
+                x[i] -= l_ij.clone() * x_j.clone();
+            }
+            x[j] = x_j;
+        }
+
+        if diag <= T::RealField::zero() {
+            return Err(CholeskyError::NotPositiveDefinite);
+        }
+        let l_kk = T::from_real(diag.sqrt());
+        let w_indices = &col_pattern[1..];
+        let w_values: Vec<T> = w_indices
+            .iter()
+            .map(|&i| x[i].clone() / l_kk.clone())
+            .collect();
+
+        // The new column takes over part of the trailing matrix, which must be compensated for
+        // by a downdate. This only touches columns after k, and is the only step that can fail.
+        let w_indices = w_indices.to_vec();
+        self.update_downdate(&[(&w_indices, &w_values)], false)?;
+
+        let (offsets, rows, values) = self.l_factor.csc_data_mut();
+        for &j in &row_pattern {
+            let range = offsets[j]..offsets[j + 1];
+            let p = rows[range.clone()]
+                .binary_search(&k)
+                .expect("Internal error: Row pattern of L must be consistent with L");
+            values[range.start + p] = x[j].clone().conjugate();
+        }
+        values[offsets[k]] = l_kk;
+        values[offsets[k] + 1..offsets[k + 1]].clone_from_slice(&w_values);
+
+        Ok(())
+    }
+
+    /// Performs a sequence of rank-one updates (or downdates) with the given sparse columns.
+    ///
+    /// The factorization is left unchanged if an error occurs.
+    fn update_downdate(
+        &mut self,
+        columns: &[(&[usize], &[T])],
+        update: bool,
+    ) -> Result<(), CholeskyError> {
+        let n = self.l_factor.ncols();
+
+        // Every column must fit within the column of L of its first entry, since the
+        // modification then stays within the pattern of L. The modified columns of L are the
+        // ancestors of the first entries in the elimination tree.
+        let mut marks = vec![false; n];
+        let mut touched = Vec::new();
+        for &(indices, _) in columns {
+            if let Some(&first) = indices.first() {
+                let col_pattern = self.l_factor.pattern().lane(first);
+                if !indices.iter().all(|i| col_pattern.binary_search(i).is_ok()) {
+                    return Err(CholeskyError::IncompatiblePattern);
+                }
+                let mut curr = Some(first);
+                while let Some(j) = curr.filter(|&j| !marks[j]) {
+                    marks[j] = true;
+                    touched.push(j);
+                    curr = self.etree[j];
+                }
+            }
+        }
+
+        // A downdate may fail partway, in which case we need to restore the modified columns
+        let backup: Vec<T> = if update {
+            Vec::new()
+        } else {
+            touched
+                .iter()
+                .flat_map(|&j| self.l_factor.col(j).values().to_vec())
+                .collect()
+        };
+
+        let mut w = vec![T::zero(); n];
+        for &(indices, values) in columns {
+            if let Some(&first) = indices.first() {
+                for (&i, v) in indices.iter().zip(values) {
+                    w[i] = v.clone();
+                }
+                let result =
+                    rank_one_modify(&mut self.l_factor, &self.etree, &mut w, first, update);
+                if result.is_err() {
+                    let mut backup = backup.into_iter();
+                    for &j in &touched {
+                        for v in self.l_factor.col_mut(j).values_mut() {
+                            *v = backup.next().unwrap();
+                        }
+                    }
+                    return result;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Perform a numerical left-looking cholesky decomposition of a matrix with the same structure as the
     /// one used to initialize `self`, but with different non-zero values provided by `values`.
     fn decompose_left_looking(&mut self, values: &[T]) -> Result<(), CholeskyError> {
@@ -218,7 +534,8 @@ impl<T: RealField> CscCholesky<T> {
                         .l_factor
                         .values()
                         .get_unchecked(*self.work_c.get_unchecked(j))
-                        .clone();
+                        .clone()
+                        .conjugate();
                     *self.work_c.get_unchecked_mut(j) += 1;
 
                     if j < k {
@@ -232,10 +549,11 @@ impl<T: RealField> CscCholesky<T> {
                     }
                 }
 
-                let diag = self.work_x.get_unchecked(k).clone();
+                // The diagonal of a Hermitian matrix is real, so we discard any imaginary part
+                let diag = self.work_x.get_unchecked(k).clone().real();
 
-                if diag > T::zero() {
-                    let denom = diag.sqrt();
+                if diag > T::RealField::zero() {
+                    let denom = T::from_real(diag.sqrt());
 
                     {
                         let (offsets, _, values) = self.l_factor.csc_data_mut();
@@ -285,17 +603,173 @@ impl<T: RealField> CscCholesky<T> {
         let mut y = b.into();
         spsolve_csc_lower_triangular(Op::NoOp(self.l()), &mut y).expect(expect_msg);
 
-        // Solve L^T X = Y
+        // Solve L^H X = Y
         let mut x = y;
-        spsolve_csc_lower_triangular(Op::Transpose(self.l()), &mut x).expect(expect_msg);
+        spsolve_csc_lower_triangular_adjoint(self.l(), &mut x).expect(expect_msg);
     }
+
+    /// Computes the determinant of the decomposed matrix.
+    #[must_use]
+    pub fn determinant(&self) -> T::RealField {
+        let mut prod_diag = T::RealField::one();
+        for col in self.l_factor.col_iter() {
+            prod_diag *= col.values()[0].clone().real();
+        }
+        prod_diag.clone() * prod_diag
+    }
+
+    /// Computes the natural logarithm of determinant of the decomposed matrix.
+    ///
+    /// This method is more robust than `.determinant()` to very small or very
+    /// large determinants since it returns the natural logarithm of the
+    /// determinant rather than the determinant itself.
+    #[must_use]
+    pub fn ln_determinant(&self) -> T::RealField {
+        let mut sum_diag = T::RealField::zero();
+        for col in self.l_factor.col_iter() {
+            sum_diag += col.values()[0].clone().modulus_squared().ln();
+        }
+        sum_diag
+    }
+
+    /// Computes the entries of the inverse `A^-1` that lie in the sparsity pattern of `L`.
+    ///
+    /// The inverse of a sparse matrix is generally dense, but many applications only need a few
+    /// of its entries, such as the diagonal (marginal variances in statistics). Since `A^-1` is
+    /// Hermitian, the returned lower-triangular matrix determines `A^-1` on the pattern of
+    /// `L + L^H`, which includes the pattern of `A`.
+    ///
+    /// The entries are computed with the recurrence of Takahashi, Fagan and Chin, which proceeds
+    /// from the last column to the first and only ever refers to entries in the pattern of `L`.
+    /// The cost is comparable to that of the numerical factorization.
+    #[must_use]
+    pub fn selected_inverse(&self) -> CscMatrix<T> {
+        let l = &self.l_factor;
+        let mut z = l.clone();
+
+        for j in (0..l.ncols()).rev() {
+            let col_j = l.col(j);
+            let l_jj = col_j.values()[0].clone();
+            let (rows, l_values) = (&col_j.row_indices()[1..], &col_j.values()[1..]);
+
+            // Z(i, j) = - 1 / L(j, j) * sum_{k > j} Z(i, k) L(k, j) for i > j, where every
+            // Z(i, k) is in the pattern of L or its transpose, and has already been computed
+            let z_col_j: Vec<T> = rows
+                .iter()
+                .map(|&i| {
+                    let sum = rows
+                        .iter()
+                        .zip(l_values)
+                        .fold(T::zero(), |sum, (&k, l_kj)| {
+                            sum + hermitian_entry(&z, i, k) * l_kj.clone()
+                        });
+                    -sum / l_jj.clone()
+                })
+                .collect();
+
+            // Z(j, j) = (1 / L(j, j) - sum_{k > j} Z(j, k) L(k, j)) / L(j, j)
+            let sum = z_col_j
+                .iter()
+                .zip(l_values)
+                .fold(T::zero(), |sum, (z_kj, l_kj)| {
+                    sum + z_kj.clone().conjugate() * l_kj.clone()
+                });
+            let z_jj = (T::one() / l_jj.clone() - sum) / l_jj;
+
+            let mut z_col = z.col_mut(j);
+            let z_values = z_col.values_mut();
+            z_values[0] = T::from_real(z_jj.real());
+            z_values[1..].clone_from_slice(&z_col_j);
+        }
+
+        z
+    }
+}
+
+/// Returns the entry `(i, j)` of a Hermitian matrix of which only the lower triangle is stored.
+///
+/// The entry must be explicitly stored.
+fn hermitian_entry<T: ComplexField>(lower: &CscMatrix<T>, i: usize, j: usize) -> T {
+    let (i, j, transposed) = if i >= j { (i, j, false) } else { (j, i, true) };
+    let col = lower.col(j);
+    let p = col
+        .row_indices()
+        .binary_search(&i)
+        .expect("Internal error: Pattern of L must be closed under elimination");
+    let value = col.values()[p].clone();
+    if transposed {
+        value.conjugate()
+    } else {
+        value
+    }
+}
+
+/// Modifies `L` in-place so that `L L^H ± w w^H` is factored, where the dense workspace `w`
+/// holds the vector. The vector must be zero outside of the path from `first` to the root of the
+/// elimination tree, and is reset to zero on success.
+///
+/// This is the method of Gill, Golub, Murray and Saunders, as used in `cs_updown` in `CSparse`.
+fn rank_one_modify<T: ComplexField>(
+    l: &mut CscMatrix<T>,
+    etree: &[Option<usize>],
+    w: &mut [T],
+    first: usize,
+    update: bool,
+) -> Result<(), CholeskyError> {
+    let (offsets, rows, values) = l.csc_data_mut();
+    let sigma = if update {
+        T::RealField::one()
+    } else {
+        -T::RealField::one()
+    };
+
+    let mut beta = T::RealField::one();
+    let mut curr = Some(first);
+    while let Some(j) = curr {
+        let p = offsets[j];
+        // The diagonal is the first entry of each column of L, and it is real
+        let l_jj = values[p].clone().real();
+        let alpha = w[j].clone() / T::from_real(l_jj.clone());
+        let beta2 = beta.clone() * beta.clone() + sigma.clone() * alpha.clone().modulus_squared();
+        if beta2 <= T::RealField::zero() {
+            return Err(CholeskyError::NotPositiveDefinite);
+        }
+        let beta2 = beta2.sqrt();
+        let delta = if update {
+            beta.clone() / beta2.clone()
+        } else {
+            beta2.clone() / beta.clone()
+        };
+        let gamma =
+            alpha.clone().conjugate() * T::from_real(sigma.clone() / (beta2.clone() * beta));
+        let diag_increment = if update {
+            (gamma.clone() * w[j].clone()).real()
+        } else {
+            T::RealField::zero()
+        };
+        values[p] = T::from_real(delta.clone() * l_jj + diag_increment);
+        beta = beta2;
+
+        for q in p + 1..offsets[j + 1] {
+            let i = rows[q];
+            let w1 = w[i].clone();
+            let w2 = w1.clone() - alpha.clone() * values[q].clone();
+            let w_new = if update { w1 } else { w2.clone() };
+            values[q] = T::from_real(delta.clone()) * values[q].clone() + gamma.clone() * w_new;
+            w[i] = w2;
+        }
+        w[j] = T::zero();
+        curr = etree[j];
+    }
+
+    Ok(())
 }
 
 fn reach(
     pattern: &SparsityPattern,
     j: usize,
     max_j: usize,
-    tree: &[usize],
+    tree: &[Option<usize>],
     marks: &mut Vec<bool>,
     out: &mut Vec<usize>,
 ) {
@@ -307,11 +781,11 @@ fn reach(
     let mut res = Vec::new();
 
     for &irow in pattern.lane(j) {
-        let mut curr = irow;
-        while curr != usize::max_value() && curr <= max_j && !marks[curr] {
-            marks[curr] = true;
-            tmp.push(curr);
-            curr = tree[curr];
+        let mut curr = Some(irow);
+        while let Some(i) = curr.filter(|&i| i <= max_j && !marks[i]) {
+            marks[i] = true;
+            tmp.push(i);
+            curr = tree[i];
         }
 
         tmp.append(&mut res);
@@ -323,8 +797,10 @@ fn reach(
     out.append(&mut res);
 }
 
-fn nonzero_pattern(m: &SparsityPattern) -> (SparsityPattern, SparsityPattern) {
-    let etree = elimination_tree(m);
+fn nonzero_pattern(
+    m: &SparsityPattern,
+    etree: &[Option<usize>],
+) -> (SparsityPattern, SparsityPattern) {
     // Note: We assume CSC, therefore rows == minor and cols == major
     let (nrows, ncols) = (m.minor_dim(), m.major_dim());
     let mut rows = Vec::with_capacity(m.nnz());
@@ -335,7 +811,7 @@ fn nonzero_pattern(m: &SparsityPattern) -> (SparsityPattern, SparsityPattern) {
     // the transpose of l.
     col_offsets.push(0);
     for i in 0..nrows {
-        reach(m, i, i, &etree, &mut marks, &mut rows);
+        reach(m, i, i, etree, &mut marks, &mut rows);
         col_offsets.push(rows.len());
     }
 
@@ -347,31 +823,3 @@ fn nonzero_pattern(m: &SparsityPattern) -> (SparsityPattern, SparsityPattern) {
 
     (l_pattern, u_pattern)
 }
-
-fn elimination_tree(pattern: &SparsityPattern) -> Vec<usize> {
-    // Note: The pattern is assumed to of a CSC matrix, so the number of rows is
-    // given by the minor dimension
-    let nrows = pattern.minor_dim();
-    let mut forest: Vec<_> = iter::repeat(usize::max_value()).take(nrows).collect();
-    let mut ancestor: Vec<_> = iter::repeat(usize::max_value()).take(nrows).collect();
-
-    for k in 0..nrows {
-        for &irow in pattern.lane(k) {
-            let mut i = irow;
-
-            while i < k {
-                let i_ancestor = ancestor[i];
-                ancestor[i] = k;
-
-                if i_ancestor == usize::max_value() {
-                    forest[i] = k;
-                    break;
-                }
-
-                i = i_ancestor;
-            }
-        }
-    }
-
-    forest
-}
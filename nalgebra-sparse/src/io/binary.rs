@@ -0,0 +1,572 @@
+//! Implementation of the binary sparse matrix format.
+
+use crate::{CscMatrix, CsrMatrix, SparseFormatError, SparseFormatErrorKind};
+use nalgebra::Complex;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The magic bytes at the start of every binary file.
+const MAGIC: &[u8; 8] = b"NASPARSE";
+
+/// The current version of the binary format.
+const VERSION: u32 = 1;
+
+/// The number of elements that are encoded or decoded at once.
+const CHUNK_LEN: usize = 4096;
+
+/// A description of the error that occurred during importing a matrix from the binary format.
+#[derive(Debug)]
+pub struct BinaryFormatError {
+    error_kind: BinaryFormatErrorKind,
+    message: String,
+}
+
+/// Errors produced by functions that expect well-formed data in the binary format.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinaryFormatErrorKind {
+    /// Indicates that the data does not start with a valid header.
+    InvalidHeader,
+
+    /// Indicates that the data was written by a newer, unsupported version of the format.
+    UnsupportedVersion,
+
+    /// Indicates that the data holds a matrix in a different storage than requested, i.e. a CSC matrix
+    /// when loading a CSR matrix or vice versa.
+    StorageMismatch,
+
+    /// Indicates that the scalar type requested is not the scalar type stored in the data.
+    TypeMismatch,
+
+    /// Indicates [SparseFormatError] while creating the sparse matrix.
+    SparseFormatError(SparseFormatErrorKind),
+
+    /// Indicates that an IO error occurred while reading the data.
+    ///
+    /// Truncated data results in an error of kind [UnexpectedEof](std::io::ErrorKind::UnexpectedEof).
+    IOError(std::io::ErrorKind),
+}
+
+impl BinaryFormatError {
+    fn from_kind_and_message(error_type: BinaryFormatErrorKind, message: String) -> Self {
+        Self {
+            error_kind: error_type,
+            message,
+        }
+    }
+
+    /// The binary format error kind.
+    #[must_use]
+    pub fn kind(&self) -> BinaryFormatErrorKind {
+        self.error_kind
+    }
+
+    /// The underlying error message.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl fmt::Display for BinaryFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Binary format error: {:?}, message: {}",
+            self.kind(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for BinaryFormatError {}
+
+impl From<SparseFormatError> for BinaryFormatError {
+    fn from(err: SparseFormatError) -> Self {
+        Self::from_kind_and_message(
+            BinaryFormatErrorKind::SparseFormatError(*err.kind()),
+            format!("{}", &err),
+        )
+    }
+}
+
+impl From<std::io::Error> for BinaryFormatError {
+    fn from(err: std::io::Error) -> Self {
+        Self::from_kind_and_message(
+            BinaryFormatErrorKind::IOError(err.kind()),
+            format!("{}", &err),
+        )
+    }
+}
+
+/// Scalar types supported by the binary format.
+mod internal {
+    use na::Scalar;
+
+    pub trait SupportedBinaryScalar: Scalar {
+        /// The code identifying the scalar type in the header.
+        const TYPE_CODE: u8;
+        /// The number of bytes of a single value.
+        const SIZE: usize;
+        /// Append the little-endian bytes of the value to `bytes`.
+        fn write_le(&self, bytes: &mut Vec<u8>);
+        /// Read a value from exactly `SIZE` little-endian bytes.
+        fn read_le(bytes: &[u8]) -> Self;
+    }
+}
+
+/// A marker trait for scalars supported by the binary format.
+///
+/// Each scalar type is identified by a code in the header of the binary data, so that a matrix can only be
+/// loaded with the scalar type it was saved with:
+///
+/// | Code | Type  | Code | Type   | Code | Type           |
+/// |------|-------|------|--------|------|----------------|
+/// | 0    | `()`  | 6    | `u8`   | 11   | `f32`          |
+/// | 1    | `i8`  | 7    | `u16`  | 12   | `f64`          |
+/// | 2    | `i16` | 8    | `u32`  | 13   | `Complex<f32>` |
+/// | 3    | `i32` | 9    | `u64`  | 14   | `Complex<f64>` |
+/// | 4    | `i64` | 10   | `u128` |      |                |
+/// | 5    | `i128`|      |        |      |                |
+///
+/// Values of type `()` take up no space, so that a pattern can be stored without any values.
+///
+/// This is a sealed trait; it cannot be implemented by external crates. This is done in order to prevent leaking
+/// some of the implementation details we currently rely on. We may relax this restriction in the future.
+pub trait BinaryScalar: internal::SupportedBinaryScalar {}
+
+/// Implement BinaryScalar for primitive number types.
+macro_rules! binary_primitive_impl {
+    ($T:ty, $code:expr) => {
+        impl BinaryScalar for $T {}
+
+        impl internal::SupportedBinaryScalar for $T {
+            const TYPE_CODE: u8 = $code;
+            const SIZE: usize = std::mem::size_of::<$T>();
+            #[inline]
+            fn write_le(&self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.to_le_bytes());
+            }
+            #[inline]
+            fn read_le(bytes: &[u8]) -> Self {
+                <$T>::from_le_bytes(bytes.try_into().unwrap())
+            }
+        }
+    };
+}
+
+/// Implement BinaryScalar for complex types, stored as the real part followed by the imaginary part.
+macro_rules! binary_complex_impl {
+    ($T:ty, $code:expr) => {
+        impl BinaryScalar for Complex<$T> {}
+
+        impl internal::SupportedBinaryScalar for Complex<$T> {
+            const TYPE_CODE: u8 = $code;
+            const SIZE: usize = 2 * std::mem::size_of::<$T>();
+            #[inline]
+            fn write_le(&self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.re.to_le_bytes());
+                bytes.extend_from_slice(&self.im.to_le_bytes());
+            }
+            #[inline]
+            fn read_le(bytes: &[u8]) -> Self {
+                let (re, im) = bytes.split_at(std::mem::size_of::<$T>());
+                Complex::new(
+                    <$T>::from_le_bytes(re.try_into().unwrap()),
+                    <$T>::from_le_bytes(im.try_into().unwrap()),
+                )
+            }
+        }
+    };
+}
+
+binary_primitive_impl!(i8, 1);
+binary_primitive_impl!(i16, 2);
+binary_primitive_impl!(i32, 3);
+binary_primitive_impl!(i64, 4);
+binary_primitive_impl!(i128, 5);
+binary_primitive_impl!(u8, 6);
+binary_primitive_impl!(u16, 7);
+binary_primitive_impl!(u32, 8);
+binary_primitive_impl!(u64, 9);
+binary_primitive_impl!(u128, 10);
+binary_primitive_impl!(f32, 11);
+binary_primitive_impl!(f64, 12);
+
+binary_complex_impl!(f32, 13);
+binary_complex_impl!(f64, 14);
+
+impl BinaryScalar for () {}
+
+impl internal::SupportedBinaryScalar for () {
+    const TYPE_CODE: u8 = 0;
+    const SIZE: usize = 0;
+    #[inline]
+    fn write_le(&self, _bytes: &mut Vec<u8>) {}
+    #[inline]
+    fn read_le(_bytes: &[u8]) -> Self {}
+}
+
+/// The compressed storage of a matrix.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Storage {
+    Csr = 0,
+    Csc = 1,
+}
+
+/// Save a CSR matrix to a file in the binary format.
+///
+/// See [save_csr_to_binary] for more information.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use nalgebra_sparse::io::{load_csr_from_binary_file, save_csr_to_binary_file};
+/// use nalgebra_sparse::CsrMatrix;
+/// let matrix = CsrMatrix::<f64>::identity(3);
+/// save_csr_to_binary_file(&matrix, "path/to/matrix.bin")?;
+/// let loaded = load_csr_from_binary_file::<f64, _>("path/to/matrix.bin")?;
+/// assert_eq!(loaded, matrix);
+/// # Ok(()) }
+/// ```
+pub fn save_csr_to_binary_file<T, P>(matrix: &CsrMatrix<T>, path: P) -> Result<(), std::io::Error>
+where
+    T: BinaryScalar,
+    P: AsRef<Path>,
+{
+    let file = File::create(path)?;
+    let mut file = BufWriter::new(file);
+    save_csr_to_binary(&mut file, matrix)?;
+    file.flush()
+}
+
+/// Save a CSC matrix to a file in the binary format.
+///
+/// See [save_csc_to_binary] for more information.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written.
+pub fn save_csc_to_binary_file<T, P>(matrix: &CscMatrix<T>, path: P) -> Result<(), std::io::Error>
+where
+    T: BinaryScalar,
+    P: AsRef<Path>,
+{
+    let file = File::create(path)?;
+    let mut file = BufWriter::new(file);
+    save_csc_to_binary(&mut file, matrix)?;
+    file.flush()
+}
+
+/// Save a CSR matrix to an [std::io::Write] instance in the binary format.
+///
+/// The row offsets, column indices and values are stored exactly, so that loading the data with
+/// [load_csr_from_binary] gives back an identical matrix. See the [module-level documentation](crate::io#binary-format)
+/// for a description of the format.
+///
+/// Examples
+/// --------
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use nalgebra_sparse::io::{load_csr_from_binary, save_csr_to_binary};
+/// use nalgebra_sparse::CsrMatrix;
+/// use nalgebra::matrix;
+/// let matrix = CsrMatrix::from(&matrix![1.0, 0.0; 0.5, 2.0]);
+/// let mut bytes = Vec::new();
+/// save_csr_to_binary(&mut bytes, &matrix)?;
+/// let loaded = load_csr_from_binary::<f64, _>(bytes.as_slice())?;
+/// assert_eq!(loaded, matrix);
+/// # Ok(()) }
+/// ```
+pub fn save_csr_to_binary<T, W>(w: W, matrix: &CsrMatrix<T>) -> Result<(), std::io::Error>
+where
+    T: BinaryScalar,
+    W: Write,
+{
+    let (offsets, indices, values) = matrix.csr_data();
+    write_binary(
+        w,
+        Storage::Csr,
+        matrix.nrows(),
+        matrix.ncols(),
+        offsets,
+        indices,
+        values,
+    )
+}
+
+/// Save a CSC matrix to an [std::io::Write] instance in the binary format.
+///
+/// The column offsets, row indices and values are stored exactly, so that loading the data with
+/// [load_csc_from_binary] gives back an identical matrix. See the [module-level documentation](crate::io#binary-format)
+/// for a description of the format.
+pub fn save_csc_to_binary<T, W>(w: W, matrix: &CscMatrix<T>) -> Result<(), std::io::Error>
+where
+    T: BinaryScalar,
+    W: Write,
+{
+    let (offsets, indices, values) = matrix.csc_data();
+    write_binary(
+        w,
+        Storage::Csc,
+        matrix.nrows(),
+        matrix.ncols(),
+        offsets,
+        indices,
+        values,
+    )
+}
+
+/// Load a CSR matrix from a file in the binary format.
+///
+/// See [load_csr_from_binary] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [BinaryFormatErrorKind] for a list of possible error conditions.
+pub fn load_csr_from_binary_file<T, P>(path: P) -> Result<CsrMatrix<T>, BinaryFormatError>
+where
+    T: BinaryScalar,
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    load_csr_from_binary(BufReader::new(file))
+}
+
+/// Load a CSC matrix from a file in the binary format.
+///
+/// See [load_csc_from_binary] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [BinaryFormatErrorKind] for a list of possible error conditions.
+pub fn load_csc_from_binary_file<T, P>(path: P) -> Result<CscMatrix<T>, BinaryFormatError>
+where
+    T: BinaryScalar,
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    load_csc_from_binary(BufReader::new(file))
+}
+
+/// Load a CSR matrix from an [std::io::Read] instance in the binary format.
+///
+/// The data must have been saved with [save_csr_to_binary], using the same scalar type `T`. The data is
+/// validated, so that malformed data results in an error rather than an invalid matrix.
+///
+/// Errors
+/// --------
+///
+/// See [BinaryFormatErrorKind] for a list of possible error conditions.
+pub fn load_csr_from_binary<T, R>(r: R) -> Result<CsrMatrix<T>, BinaryFormatError>
+where
+    T: BinaryScalar,
+    R: Read,
+{
+    let (nrows, ncols, offsets, indices, values) = read_binary(r, Storage::Csr)?;
+    Ok(CsrMatrix::try_from_csr_data(
+        nrows, ncols, offsets, indices, values,
+    )?)
+}
+
+/// Load a CSC matrix from an [std::io::Read] instance in the binary format.
+///
+/// The data must have been saved with [save_csc_to_binary], using the same scalar type `T`. The data is
+/// validated, so that malformed data results in an error rather than an invalid matrix.
+///
+/// Errors
+/// --------
+///
+/// See [BinaryFormatErrorKind] for a list of possible error conditions.
+pub fn load_csc_from_binary<T, R>(r: R) -> Result<CscMatrix<T>, BinaryFormatError>
+where
+    T: BinaryScalar,
+    R: Read,
+{
+    let (nrows, ncols, offsets, indices, values) = read_binary(r, Storage::Csc)?;
+    Ok(CscMatrix::try_from_csc_data(
+        nrows, ncols, offsets, indices, values,
+    )?)
+}
+
+fn write_binary<T, W>(
+    mut w: W,
+    storage: Storage,
+    nrows: usize,
+    ncols: usize,
+    offsets: &[usize],
+    indices: &[usize],
+    values: &[T],
+) -> Result<(), std::io::Error>
+where
+    T: BinaryScalar,
+    W: Write,
+{
+    let mut bytes = Vec::with_capacity(CHUNK_LEN * 16);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.push(storage as u8);
+    bytes.push(T::TYPE_CODE);
+    bytes.extend_from_slice(&[0, 0]);
+    for &n in &[nrows, ncols, indices.len()] {
+        bytes.extend_from_slice(&(n as u64).to_le_bytes());
+    }
+    w.write_all(&bytes)?;
+
+    for chunk in offsets.chunks(CHUNK_LEN).chain(indices.chunks(CHUNK_LEN)) {
+        bytes.clear();
+        for &i in chunk {
+            bytes.extend_from_slice(&(i as u64).to_le_bytes());
+        }
+        w.write_all(&bytes)?;
+    }
+
+    if T::SIZE > 0 {
+        for chunk in values.chunks(CHUNK_LEN) {
+            bytes.clear();
+            for v in chunk {
+                v.write_le(&mut bytes);
+            }
+            w.write_all(&bytes)?;
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::type_complexity)]
+fn read_binary<T, R>(
+    mut r: R,
+    storage: Storage,
+) -> Result<(usize, usize, Vec<usize>, Vec<usize>, Vec<T>), BinaryFormatError>
+where
+    T: BinaryScalar,
+    R: Read,
+{
+    let mut header = [0u8; 40];
+    r.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::InvalidHeader,
+            String::from("The data does not start with the magic bytes of the binary format."),
+        ));
+    }
+    let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    if version != VERSION {
+        return Err(BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::UnsupportedVersion,
+            format!(
+                "Version {} of the binary format is not supported, only version {} is.",
+                version, VERSION
+            ),
+        ));
+    }
+    let stored = match header[12] {
+        0 => Storage::Csr,
+        1 => Storage::Csc,
+        code => {
+            return Err(BinaryFormatError::from_kind_and_message(
+                BinaryFormatErrorKind::InvalidHeader,
+                format!("Unknown storage code {}.", code),
+            ))
+        }
+    };
+    if stored != storage {
+        return Err(BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::StorageMismatch,
+            format!(
+                "The data holds a {:?} matrix, but a {:?} matrix was requested.",
+                stored, storage
+            ),
+        ));
+    }
+    if header[13] != T::TYPE_CODE {
+        return Err(BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::TypeMismatch,
+            format!(
+                "The data holds values of scalar type code {}, but scalar type code {} was requested.",
+                header[13],
+                T::TYPE_CODE
+            ),
+        ));
+    }
+    if header[14..16] != [0, 0] {
+        return Err(BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::InvalidHeader,
+            String::from("The reserved bytes of the header must be zero."),
+        ));
+    }
+
+    let mut dimensions = [0; 3];
+    for (k, dimension) in dimensions.iter_mut().enumerate() {
+        let bytes = &header[16 + 8 * k..24 + 8 * k];
+        *dimension = read_usize(bytes)?;
+    }
+    let [nrows, ncols, nnz] = dimensions;
+    let major_dim = match storage {
+        Storage::Csr => nrows,
+        Storage::Csc => ncols,
+    };
+    let offset_count = major_dim.checked_add(1).ok_or_else(|| {
+        BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::InvalidHeader,
+            String::from("The dimensions of the matrix are too large."),
+        )
+    })?;
+
+    let offsets = read_chunked(&mut r, offset_count, 8, read_usize)?;
+    let indices = read_chunked(&mut r, nnz, 8, read_usize)?;
+    let values = read_chunked(&mut r, nnz, T::SIZE, |bytes| Ok(T::read_le(bytes)))?;
+    Ok((nrows, ncols, offsets, indices, values))
+}
+
+fn read_usize(bytes: &[u8]) -> Result<usize, BinaryFormatError> {
+    let value = u64::from_le_bytes(bytes.try_into().unwrap());
+    usize::try_from(value).map_err(|_| {
+        BinaryFormatError::from_kind_and_message(
+            BinaryFormatErrorKind::InvalidHeader,
+            format!("The value {} does not fit into a usize.", value),
+        )
+    })
+}
+
+/// Reads `count` elements of `size` bytes each, decoding them with `decode`.
+fn read_chunked<R, V, F>(
+    r: &mut R,
+    count: usize,
+    size: usize,
+    mut decode: F,
+) -> Result<Vec<V>, BinaryFormatError>
+where
+    R: Read,
+    F: FnMut(&[u8]) -> Result<V, BinaryFormatError>,
+{
+    // The capacity is not taken from the header all at once, so that corrupt data does not cause huge
+    // allocations before running out of data
+    if size == 0 {
+        return (0..count).map(|_| decode(&[])).collect();
+    }
+    let mut elements = Vec::with_capacity(count.min(CHUNK_LEN));
+    let mut bytes = vec![0u8; CHUNK_LEN * size];
+    while elements.len() < count {
+        let chunk_len = (count - elements.len()).min(CHUNK_LEN);
+        let chunk = &mut bytes[..chunk_len * size];
+        r.read_exact(chunk)?;
+        for element_bytes in chunk.chunks_exact(size) {
+            elements.push(decode(element_bytes)?);
+        }
+    }
+    Ok(elements)
+}
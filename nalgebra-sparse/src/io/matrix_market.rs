@@ -4,16 +4,16 @@
 use crate::SparseFormatError;
 use crate::SparseFormatErrorKind;
 use crate::{CooMatrix, CscMatrix, CsrMatrix};
-use nalgebra::Complex;
-use pest::iterators::Pairs;
-use pest::Parser;
+use nalgebra::storage::RawStorage;
+use nalgebra::{ClosedAdd, Complex, DMatrix, Dim, Matrix};
+use num_traits::Zero;
 use std::cmp::PartialEq;
 use std::convert::Infallible;
 use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Formatter;
-use std::fs::{self, File};
-use std::io::{BufWriter, Write};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::num::ParseIntError;
 use std::num::TryFromIntError;
 use std::path::Path;
@@ -24,6 +24,7 @@ use std::str::FromStr;
 pub struct MatrixMarketError {
     error_kind: MatrixMarketErrorKind,
     message: String,
+    line_number: Option<usize>,
 }
 
 /// Errors produced by functions that expect well-formed matrix market format data.
@@ -241,9 +242,16 @@ impl MatrixMarketError {
         Self {
             error_kind: error_type,
             message,
+            line_number: None,
         }
     }
 
+    /// Attributes the error to the given line, unless it is already attributed to a line.
+    fn with_line_number(mut self, line_number: usize) -> Self {
+        self.line_number.get_or_insert(line_number);
+        self
+    }
+
     /// The matrix market error kind.
     #[must_use]
     pub fn kind(&self) -> MatrixMarketErrorKind {
@@ -255,6 +263,13 @@ impl MatrixMarketError {
     pub fn message(&self) -> &str {
         self.message.as_str()
     }
+
+    /// The (one-based) number of the line at which the error occurred, if the error can be attributed
+    /// to a single line of the input.
+    #[must_use]
+    pub fn line_number(&self) -> Option<usize> {
+        self.line_number
+    }
 }
 
 impl fmt::Display for MatrixMarketError {
@@ -292,24 +307,15 @@ impl fmt::Display for MatrixMarketError {
                 write!(f, "NonSquare,")?;
             }
         }
+        if let Some(line_number) = self.line_number {
+            write!(f, " line: {},", line_number)?;
+        }
         write!(f, " message: {}", self.message)
     }
 }
 
 impl std::error::Error for MatrixMarketError {}
 
-impl MatrixMarketError {
-    fn from_pest_error<T>(error: pest::error::Error<T>) -> Self
-    where
-        T: fmt::Debug + std::hash::Hash + std::marker::Copy + Ord,
-    {
-        Self::from_kind_and_message(
-            MatrixMarketErrorKind::ParsingError,
-            format!("Can't parse the data.\n Error: {}", error),
-        )
-    }
-}
-
 impl From<ParseIntError> for MatrixMarketError {
     fn from(err: ParseIntError) -> Self {
         Self::from_kind_and_message(
@@ -509,6 +515,10 @@ mod internal {
         fn conjugate(self) -> Result<Self, MatrixMarketError>;
         /// Returns the name of SupportedMatrixMarketScalar, used when write the matrix
         fn typename() -> &'static str;
+        /// Whether the type is complex, so that it supports the hermitian symmetry.
+        const IS_COMPLEX: bool;
+        /// Whether the type is the pattern type, whose entries have no values.
+        const IS_PATTERN: bool;
         /// Write the data self to w
         fn write_matrix_market<W: std::fmt::Write>(&self, w: W) -> Result<(), std::fmt::Error>;
     }
@@ -537,6 +547,9 @@ macro_rules! mm_int_impl {
         impl MatrixMarketScalar for $T {}
 
         impl internal::SupportedMatrixMarketScalar for $T {
+            const IS_COMPLEX: bool = false;
+            const IS_PATTERN: bool = false;
+
             #[inline]
             fn from_i128(i: i128) -> Result<Self, MatrixMarketError> {
                 Ok(Self::try_from(i)?)
@@ -593,6 +606,9 @@ macro_rules! mm_real_impl {
         impl MatrixMarketScalar for $T {}
 
         impl internal::SupportedMatrixMarketScalar for $T {
+            const IS_COMPLEX: bool = false;
+            const IS_PATTERN: bool = false;
+
             #[inline]
             fn from_i128(_i: i128) -> Result<Self, MatrixMarketError> {
                 Err(MatrixMarketError::from_kind_and_message(
@@ -650,6 +666,9 @@ macro_rules! mm_complex_impl {
         impl MatrixMarketScalar for Complex<$T> {}
 
         impl internal::SupportedMatrixMarketScalar for Complex<$T> {
+            const IS_COMPLEX: bool = true;
+            const IS_PATTERN: bool = false;
+
             #[inline]
             fn from_i128(_i: i128) -> Result<Self, MatrixMarketError> {
                 Err(MatrixMarketError::from_kind_and_message(
@@ -706,6 +725,9 @@ macro_rules! mm_pattern_impl {
         impl MatrixMarketScalar for $T {}
 
         impl internal::SupportedMatrixMarketScalar for $T {
+            const IS_COMPLEX: bool = false;
+            const IS_PATTERN: bool = true;
+
             #[inline]
             fn from_i128(_i: i128) -> Result<Self, MatrixMarketError> {
                 Err(MatrixMarketError::from_kind_and_message(
@@ -815,15 +837,13 @@ mm_matrix_impl!(CooMatrix<T>);
 mm_matrix_impl!(CsrMatrix<T>);
 mm_matrix_impl!(CscMatrix<T>);
 
-#[derive(Parser)]
-#[grammar = "io/matrix_market.pest"]
-struct MatrixMarketParser;
-
 /// Parses a Matrix Market file at the given path as a `CooMatrix`.
 ///
 /// The matrix market format specification does not clarify whether duplicate entries are allowed. Our importer
 /// assumes that this is permitted and produces a `CooMatrix` with possibly duplicate entries.
 ///
+/// The file is read line by line through a buffered reader, see [load_coo_from_matrix_market_reader].
+///
 /// **Note**: A current restriction of the importer is that you must use a compatible scalar type when importing.
 /// For example, in order to import a matrix stored as `integer` in the matrix market format, you must
 /// import it as an integer matrix, otherwise a [TypeMismatch](MatrixMarketErrorKind::TypeMismatch) error
@@ -848,8 +868,8 @@ pub fn load_coo_from_matrix_market_file<T, P: AsRef<Path>>(
 where
     T: MatrixMarketScalar,
 {
-    let file = fs::read_to_string(path)?;
-    load_coo_from_matrix_market_str(&file)
+    let file = File::open(path)?;
+    load_coo_from_matrix_market_reader(BufReader::new(file))
 }
 
 /// Parses a Matrix Market file described by the given string as a `CooMatrix`.
@@ -878,32 +898,115 @@ pub fn load_coo_from_matrix_market_str<T>(data: &str) -> Result<CooMatrix<T>, Ma
 where
     T: MatrixMarketScalar,
 {
-    // unwrap() in this function are guaranteed by parsing the data
-    let file = MatrixMarketParser::parse(Rule::Document, data)
-        .map_err(MatrixMarketError::from_pest_error)?
-        .next()
-        .unwrap();
-
-    let mut rows: Vec<usize> = Vec::new();
-    let mut cols: Vec<usize> = Vec::new();
-    let mut data: Vec<T> = Vec::new();
-    let mut lines = file.into_inner();
-
-    let header_line = lines.next().unwrap();
-    let header_type = parse_header(&mut header_line.into_inner());
-    typecode_precheck(&header_type)?;
-
-    let shape_line = lines.next().unwrap();
+    load_coo_from_matrix_market_reader(data.as_bytes())
+}
+
+/// Parses a Matrix Market file from a buffered reader as a `CooMatrix`.
+///
+/// The data is parsed line by line as it is read, so that the file never needs to be held in memory in its
+/// entirety. The entries are pushed directly into a `CooMatrix` whose storage is allocated up front,
+/// based on the number of entries given in the header.
+///
+/// See [load_coo_from_matrix_market_file] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [MatrixMarketErrorKind] for a list of possible error conditions. Errors that can be attributed to
+/// a specific line of the input report it through [MatrixMarketError::line_number].
+///
+/// Examples
+/// --------
+/// ```
+/// use nalgebra_sparse::io::load_coo_from_matrix_market_reader;
+/// use std::io::BufReader;
+/// let data = "%%matrixmarket matrix coordinate real general\n2 2 1\n2 1 3.5\n";
+/// // Any implementor of `BufRead` can be used, such as `BufReader<File>`
+/// let matrix = load_coo_from_matrix_market_reader::<f64, _>(BufReader::new(data.as_bytes())).unwrap();
+/// assert_eq!(matrix.nnz(), 1);
+/// ```
+pub fn load_coo_from_matrix_market_reader<T, R>(
+    reader: R,
+) -> Result<CooMatrix<T>, MatrixMarketError>
+where
+    T: MatrixMarketScalar,
+    R: BufRead,
+{
+    load_coo_from_matrix_market_reader_with_progress(reader, |_| {})
+}
+
+/// Parses a Matrix Market file from a buffered reader as a `CooMatrix`, reporting progress along the way.
+///
+/// The callback `progress` is called once the header has been parsed, after every
+/// 65536 data entries, and once all data entries have been read.
+///
+/// See [load_coo_from_matrix_market_reader] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [MatrixMarketErrorKind] for a list of possible error conditions.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// use nalgebra_sparse::io::load_coo_from_matrix_market_reader_with_progress;
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// let file = File::open("path/to/matrix.mtx").unwrap();
+/// let matrix = load_coo_from_matrix_market_reader_with_progress::<f64, _, _>(
+///     BufReader::new(file),
+///     |progress| {
+///         println!("Read {} of {} entries", progress.entries_read(), progress.total_entries())
+///     },
+/// )
+/// .unwrap();
+/// ```
+pub fn load_coo_from_matrix_market_reader_with_progress<T, R, F>(
+    reader: R,
+    mut progress: F,
+) -> Result<CooMatrix<T>, MatrixMarketError>
+where
+    T: MatrixMarketScalar,
+    R: BufRead,
+    F: FnMut(&MatrixMarketProgress),
+{
+    let mut lines = LineReader::new(reader);
+
+    let header_type = match lines.next_line(false)? {
+        Some(header_line) => parse_header(header_line),
+        None => Err(MatrixMarketError::from_kind_and_message(
+            MatrixMarketErrorKind::ParsingError,
+            String::from("The matrix market header is missing"),
+        )),
+    }
+    .and_then(|header_type| typecode_precheck(&header_type).map(|_| header_type))
+    .map_err(|err| err.with_line_number(lines.line_number))?;
+
     // shape here is number of rows, columns, non-zeros
-    let shape: (usize, usize, usize);
-    match header_type.sparsity {
-        Sparsity::Sparse => {
-            shape = parse_sparse_shape(&mut shape_line.into_inner(), &header_type.storagescheme)?;
-        }
-        Sparsity::Dense => {
-            shape = parse_dense_shape(&mut shape_line.into_inner(), &header_type.storagescheme)?;
-        }
+    let shape: (usize, usize, usize) = match lines.next_line(true)? {
+        Some(shape_line) => match header_type.sparsity {
+            Sparsity::Sparse => parse_sparse_shape(shape_line, &header_type.storagescheme),
+            Sparsity::Dense => parse_dense_shape(shape_line, &header_type.storagescheme),
+        },
+        None => Err(MatrixMarketError::from_kind_and_message(
+            MatrixMarketErrorKind::ParsingError,
+            String::from("The shape line of the matrix is missing"),
+        )),
     }
+    .map_err(|err| err.with_line_number(lines.line_number))?;
+
+    // Entries off the diagonal of (skew-)symmetric and hermitian matrices are stored twice.
+    // The header is not trusted, since a malformed file could otherwise make us allocate
+    // arbitrary amounts of memory. If the reservation fails, the storage grows as entries are
+    // parsed instead.
+    let capacity = match header_type.storagescheme {
+        StorageScheme::General => shape.2,
+        _ => shape.2.saturating_mul(2),
+    };
+    let mut coo = CooMatrix::new(shape.0, shape.1);
+    // Failing to reserve is not an error, so the result is deliberately ignored
+    let _ = coo.try_reserve(capacity.min(shape.0.saturating_mul(shape.1)));
 
     // used when constructing dense matrix.
     // If it's sparse matrix, it has no effect.
@@ -912,170 +1015,199 @@ where
         // for skew dense matrix, the first element starts from (1,0)
         current_dense_coordinate = (1, 0);
     }
-    // count how many entries in the matrix data
-    let count = lines.clone().count();
-    if count != shape.2 {
+
+    let mut entries_read = 0;
+    progress(&lines.progress(entries_read, shape.2));
+    while let Some(data_line) = lines.next_line(true)? {
+        let result = if entries_read == shape.2 {
+            Err(MatrixMarketError::from_kind_and_message(
+                MatrixMarketErrorKind::EntryMismatch,
+                format!(
+                    "{} entries required for the matrix, but more were provided",
+                    shape.2,
+                ),
+            ))
+        } else {
+            parse_entry::<T>(
+                data_line,
+                &header_type,
+                &mut current_dense_coordinate,
+                shape,
+            )
+            .and_then(|entry| push_entry(&mut coo, entry, &header_type.storagescheme))
+        };
+        result.map_err(|err| err.with_line_number(lines.line_number))?;
+
+        entries_read += 1;
+        if entries_read % PROGRESS_INTERVAL == 0 {
+            progress(&lines.progress(entries_read, shape.2));
+        }
+    }
+
+    if entries_read != shape.2 {
         return Err(MatrixMarketError::from_kind_and_message(
             MatrixMarketErrorKind::EntryMismatch,
             format!(
                 "{} entries required for the matrix, but {} was provided",
-                shape.2, count,
+                shape.2, entries_read,
             ),
         ));
     }
+    if entries_read % PROGRESS_INTERVAL != 0 {
+        progress(&lines.progress(entries_read, shape.2));
+    }
 
-    for data_line in lines {
-        let entry: (usize, usize, T);
-        match header_type {
-            Typecode {
-                sparsity: Sparsity::Sparse,
-                datatype: DataType::Real,
-                ..
-            } => {
-                entry = parse_sparse_real::<T>(&mut data_line.into_inner())?;
-            }
-            Typecode {
-                sparsity: Sparsity::Sparse,
-                datatype: DataType::Integer,
-                ..
-            } => {
-                entry = parse_sparse_int::<T>(&mut data_line.into_inner())?;
-            }
-            Typecode {
-                sparsity: Sparsity::Sparse,
-                datatype: DataType::Pattern,
-                ..
-            } => {
-                entry = parse_sparse_pattern::<T>(&mut data_line.into_inner())?;
-            }
-            Typecode {
-                sparsity: Sparsity::Sparse,
-                datatype: DataType::Complex,
-                ..
-            } => {
-                entry = parse_sparse_complex::<T>(&mut data_line.into_inner())?;
-            }
-            Typecode {
-                sparsity: Sparsity::Dense,
-                datatype: DataType::Complex,
-                ..
-            } => {
-                entry = (
-                    current_dense_coordinate.0,
-                    current_dense_coordinate.1,
-                    parse_dense_complex::<T>(&mut data_line.into_inner())?,
-                );
-                next_dense_coordinate(
-                    &mut current_dense_coordinate,
-                    shape,
-                    &header_type.storagescheme,
-                );
-            }
-            Typecode {
-                sparsity: Sparsity::Dense,
-                datatype: DataType::Real,
-                ..
-            } => {
-                entry = (
-                    current_dense_coordinate.0,
-                    current_dense_coordinate.1,
-                    parse_dense_real::<T>(&mut data_line.into_inner())?,
-                );
-
-                next_dense_coordinate(
-                    &mut current_dense_coordinate,
-                    shape,
-                    &header_type.storagescheme,
-                );
-            }
-            Typecode {
-                sparsity: Sparsity::Dense,
-                datatype: DataType::Integer,
-                ..
-            } => {
-                entry = (
-                    current_dense_coordinate.0,
-                    current_dense_coordinate.1,
-                    parse_dense_int::<T>(&mut data_line.into_inner())?,
-                );
-                next_dense_coordinate(
-                    &mut current_dense_coordinate,
-                    shape,
-                    &header_type.storagescheme,
-                );
+    Ok(coo)
+}
+
+/// The number of data entries between two progress reports.
+const PROGRESS_INTERVAL: usize = 1 << 16;
+
+/// The progress of loading a matrix market file.
+///
+/// See [load_coo_from_matrix_market_reader_with_progress].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MatrixMarketProgress {
+    line_number: usize,
+    bytes_read: usize,
+    entries_read: usize,
+    total_entries: usize,
+}
+
+impl MatrixMarketProgress {
+    /// The number of lines read so far.
+    #[must_use]
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The number of bytes read so far.
+    #[must_use]
+    pub fn bytes_read(&self) -> usize {
+        self.bytes_read
+    }
+
+    /// The number of data entries read so far.
+    #[must_use]
+    pub fn entries_read(&self) -> usize {
+        self.entries_read
+    }
+
+    /// The total number of data entries, as given in the header of the file.
+    #[must_use]
+    pub fn total_entries(&self) -> usize {
+        self.total_entries
+    }
+}
+
+/// Reads the lines of a matrix market file one by one, reusing a single line buffer.
+struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    bytes_read: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            bytes_read: 0,
+        }
+    }
+
+    /// Returns the next line that is not blank, trimmed of surrounding whitespace.
+    ///
+    /// Comment lines are skipped if `skip_comments` is set. Returns `None` at the end of the input.
+    fn next_line(&mut self, skip_comments: bool) -> Result<Option<&str>, MatrixMarketError> {
+        loop {
+            self.buffer.clear();
+            let bytes = self.reader.read_line(&mut self.buffer).map_err(|err| {
+                MatrixMarketError::from(err).with_line_number(self.line_number + 1)
+            })?;
+            if bytes == 0 {
+                return Ok(None);
             }
-            _ => {
-                // it shouldn't happen here, because dense matrix can't be pattern. And it will give InvalidHeader error beforehand.
-                entry = (1, 1, T::from_i128(1)?)
+            self.line_number += 1;
+            self.bytes_read += bytes;
+
+            let line = self.buffer.trim();
+            let skip = line.is_empty() || (skip_comments && line.starts_with('%'));
+            if !skip {
+                return Ok(Some(self.buffer.trim()));
             }
         }
+    }
 
-        let (r, c, d) = entry;
+    fn progress(&self, entries_read: usize, total_entries: usize) -> MatrixMarketProgress {
+        MatrixMarketProgress {
+            line_number: self.line_number,
+            bytes_read: self.bytes_read,
+            entries_read,
+            total_entries,
+        }
+    }
+}
 
-        match header_type.storagescheme {
-            StorageScheme::General => {
-                rows.push(r);
-                cols.push(c);
-                data.push(d);
-            }
-            StorageScheme::Symmetric => {
-                check_lower_triangle(r, c)?;
-                rows.push(r);
-                cols.push(c);
-                data.push(d.clone());
-                // don't need to add twice if the element in on diagonal
-                if r != c {
-                    rows.push(c);
-                    cols.push(r);
-                    data.push(d);
-                }
-            }
-            StorageScheme::Skew => {
-                check_lower_triangle(r, c)?;
-                rows.push(r);
-                cols.push(c);
-                data.push(d.clone());
-                // skew-symmetric matrix shouldn't have diagonal element
-                if r == c {
-                    return Err(MatrixMarketError::from_kind_and_message(
-                        MatrixMarketErrorKind::DiagonalError,
-                        format!(
-                            "There is a diagonal element in skew matrix, in row(and column) {}",
-                            r + 1
-                        ),
-                    ));
-                }
-                rows.push(c);
-                cols.push(r);
-                data.push(d.negative()?);
-            }
-            StorageScheme::Hermitian => {
-                check_lower_triangle(r, c)?;
-                rows.push(r);
-                cols.push(c);
-                data.push(d.clone());
-
-                if r == c && d != d.clone().conjugate()? {
-                    return Err(MatrixMarketError::from_kind_and_message(
-                        MatrixMarketErrorKind::DiagonalError,
-                        format!(
-                            "There is a diagonal element in hermitian matrix, which is not a real number, in row(and column) {}",
-                            r + 1
-                        ),
-                    ));
-                }
-                // don't need to add twice if the element in on diagonal
-                if r != c {
-                    rows.push(c);
-                    cols.push(r);
-                    data.push(d.conjugate()?);
-                }
-            }
+/// Adds an entry to the matrix, along with its mirrored entry if the matrix is not stored in full.
+fn push_entry<T>(
+    coo: &mut CooMatrix<T>,
+    entry: (usize, usize, T),
+    storagescheme: &StorageScheme,
+) -> Result<(), MatrixMarketError>
+where
+    T: MatrixMarketScalar,
+{
+    let (r, c, d) = entry;
+
+    if *storagescheme != StorageScheme::General {
+        check_lower_triangle(r, c)?;
+    }
+    match storagescheme {
+        // skew-symmetric matrix shouldn't have diagonal element
+        StorageScheme::Skew if r == c => {
+            return Err(MatrixMarketError::from_kind_and_message(
+                MatrixMarketErrorKind::DiagonalError,
+                format!(
+                    "There is a diagonal element in skew matrix, in row(and column) {}",
+                    r + 1
+                ),
+            ));
         }
+        StorageScheme::Hermitian if r == c && d != d.clone().conjugate()? => {
+            return Err(MatrixMarketError::from_kind_and_message(
+                MatrixMarketErrorKind::DiagonalError,
+                format!(
+                    "There is a diagonal element in hermitian matrix, which is not a real number, in row(and column) {}",
+                    r + 1
+                ),
+            ));
+        }
+        _ => {}
+    }
+    if r >= coo.nrows() || c >= coo.ncols() {
+        return Err(SparseFormatError::from_kind_and_msg(
+            SparseFormatErrorKind::IndexOutOfBounds,
+            "Row or column index out of bounds.",
+        )
+        .into());
     }
-    Ok(CooMatrix::try_from_triplets(
-        shape.0, shape.1, rows, cols, data,
-    )?)
+
+    // don't need to add twice if the element in on diagonal
+    let mirrored = match storagescheme {
+        StorageScheme::General => None,
+        _ if r == c => None,
+        StorageScheme::Symmetric => Some(d.clone()),
+        StorageScheme::Skew => Some(d.clone().negative()?),
+        StorageScheme::Hermitian => Some(d.clone().conjugate()?),
+    };
+    coo.push(r, c, d);
+    if let Some(mirrored) = mirrored {
+        coo.push(c, r, mirrored);
+    }
+    Ok(())
 }
 
 #[inline]
@@ -1093,55 +1225,69 @@ fn check_lower_triangle(r: usize, c: usize) -> Result<(), MatrixMarketError> {
     Ok(())
 }
 
-#[inline]
-/// Parse a pest structure to a Typecode of the matrix.
-fn parse_header(inner: &mut Pairs<'_, Rule>) -> Typecode {
-    // unwrap() in this function are guaranteed by parsing the data
-    Typecode {
-        sparsity: inner
-            .next()
-            .unwrap()
-            .as_str()
-            .to_ascii_lowercase()
-            .parse::<Sparsity>()
-            .unwrap(),
-        datatype: inner
-            .next()
-            .unwrap()
-            .as_str()
-            .to_ascii_lowercase()
-            .parse::<DataType>()
-            .unwrap(),
-        storagescheme: inner
+/// Parse the header line to a Typecode of the matrix.
+fn parse_header(line: &str) -> Result<Typecode, MatrixMarketError> {
+    let invalid_header = || {
+        MatrixMarketError::from_kind_and_message(
+            MatrixMarketErrorKind::ParsingError,
+            format!(
+                "The header should be `%%MatrixMarket matrix <format> <field> <symmetry>`, but line {} was provided.",
+                line
+            ),
+        )
+    };
+
+    let mut tokens = line.split_whitespace();
+    let is_banner = |token: Option<&str>, expected: &str| matches!(token, Some(token) if token.eq_ignore_ascii_case(expected));
+    if !is_banner(tokens.next(), "%%matrixmarket") || !is_banner(tokens.next(), "matrix") {
+        return Err(invalid_header());
+    }
+    let mut keyword = || {
+        tokens
             .next()
-            .unwrap()
-            .as_str()
-            .to_ascii_lowercase()
-            .parse::<StorageScheme>()
-            .unwrap(),
+            .map(str::to_ascii_lowercase)
+            .ok_or_else(invalid_header)
+    };
+    let typecode = Typecode {
+        sparsity: keyword()?.parse::<Sparsity>()?,
+        datatype: keyword()?.parse::<DataType>()?,
+        storagescheme: keyword()?.parse::<StorageScheme>()?,
+    };
+    if tokens.next().is_some() {
+        return Err(invalid_header());
+    }
+    Ok(typecode)
+}
+
+/// Splits a line into exactly `N` whitespace-separated tokens, if possible.
+fn split_tokens<const N: usize>(line: &str) -> Option<[&str; N]> {
+    let mut tokens = [""; N];
+    let mut split = line.split_whitespace();
+    for token in tokens.iter_mut() {
+        *token = split.next()?;
+    }
+    match split.next() {
+        Some(_) => None,
+        None => Some(tokens),
     }
 }
 
 // Parse shape starts here-------------------------------------------------
 
-/// Parse a pest structure to sparse shape information, including 3 int, which are number of rows, cols and non-zeros.
+/// Parse a line to sparse shape information, including 3 int, which are number of rows, cols and non-zeros.
 fn parse_sparse_shape(
-    inner: &mut Pairs<'_, Rule>,
+    line: &str,
     storagescheme: &StorageScheme,
 ) -> Result<(usize, usize, usize), MatrixMarketError> {
-    // unwrap() in this function are guaranteed by parsing the data
-    let shape_inner = inner.next().unwrap();
-    if shape_inner.as_rule() != Rule::SparseShape {
-        return Err(MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
+    let [r, c, nnz] = split_tokens(line).ok_or_else(|| {
+        MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
         Shape shape line requires 3 int numbers as number of rows, columns and non-zeros, but line {} was provided here.
-        ",shape_inner.as_str())));
-    }
-
-    let mut inner = shape_inner.into_inner();
+        ",line))
+    })?;
 
-    let r = inner.next().unwrap().as_str().parse::<usize>().unwrap();
-    let c = inner.next().unwrap().as_str().parse::<usize>().unwrap();
-    let nnz = inner.next().unwrap().as_str().parse::<usize>().unwrap();
+    let r = parse_dimension(r)?;
+    let c = parse_dimension(c)?;
+    let nnz = parse_dimension(nnz)?;
 
     // check for square matrix, when it's not a general matrix
     if *storagescheme != StorageScheme::General && r != c {
@@ -1151,22 +1297,19 @@ fn parse_sparse_shape(
     Ok((r, c, nnz))
 }
 
-/// Parse a pest structure to dense shape information, including 2 int, which are number of rows, cols.
+/// Parse a line to dense shape information, including 2 int, which are number of rows, cols.
 fn parse_dense_shape(
-    inner: &mut Pairs<'_, Rule>,
+    line: &str,
     storagescheme: &StorageScheme,
 ) -> Result<(usize, usize, usize), MatrixMarketError> {
-    // unwrap() in this function are guaranteed by parsing the data
-    let shape_inner = inner.next().unwrap();
-    if shape_inner.as_rule() != Rule::DenseShape {
-        return Err(MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
+    let [r, c] = split_tokens(line).ok_or_else(|| {
+        MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
         Shape shape line requires 2 int numbers as number of rows, columns, but line {} was provided here.
-        ",shape_inner.as_str())));
-    }
+        ",line))
+    })?;
 
-    let mut inner = shape_inner.into_inner();
-    let r = inner.next().unwrap().as_str().parse::<usize>().unwrap();
-    let c = inner.next().unwrap().as_str().parse::<usize>().unwrap();
+    let r = parse_dimension(r)?;
+    let c = parse_dimension(c)?;
 
     // check for square matrix, when it's not a general matrix
     if *storagescheme != StorageScheme::General && r != c {
@@ -1188,7 +1331,7 @@ fn parse_dense_shape(
         StorageScheme::Skew => {
             // it must be square matrix, so r==c is true here
             // Skew-Symmetric should contain 1+2...+r-1  = r*(r-1)/2 entries
-            n = r * (r - 1) / 2;
+            n = r * r.saturating_sub(1) / 2;
         }
     }
 
@@ -1199,185 +1342,126 @@ fn parse_dense_shape(
 
 // Parse entry starts here-------------------------------------------------
 
-/// Parse a pest structure to sparse real entry, including 2 int, which are number of rows, cols, and a real number as data
-fn parse_sparse_real<T>(inner: &mut Pairs<'_, Rule>) -> Result<(usize, usize, T), MatrixMarketError>
-where
-    T: MatrixMarketScalar,
-{
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    if entry_inner.as_rule() != Rule::SparseReal {
-        return Err(MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
-        Spare real matrix requires 2 int number as coordinates and 1 real number as data, but line {} was provided.  
-        ",entry_inner.as_str() )));
-    }
-
-    let mut inner = entry_inner.into_inner();
-    let (r, c) = parse_sparse_coordinate(&mut inner)?;
-    let d = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    Ok((r, c, T::from_f64(d)?))
-}
-
-/// Parse a pest structure to sparse integer entry, including 2 int, which are number of rows, cols, and a int number as data
-fn parse_sparse_int<T>(inner: &mut Pairs<'_, Rule>) -> Result<(usize, usize, T), MatrixMarketError>
+/// Parse a data line to an entry of the matrix, with zero-based coordinates.
+///
+/// For dense matrices, the coordinates of the entry are given by `current_dense_coordinate`, which is
+/// advanced to the next entry.
+fn parse_entry<T>(
+    line: &str,
+    header_type: &Typecode,
+    current_dense_coordinate: &mut (usize, usize),
+    shape: (usize, usize, usize),
+) -> Result<(usize, usize, T), MatrixMarketError>
 where
     T: MatrixMarketScalar,
 {
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    // Because integer numbers can also be parsed as float numbers, it will be checked again in `parse::<i128>()?`
-    if entry_inner.as_rule() != Rule::SparseReal {
-        return Err(MatrixMarketError::from_kind_and_message(
-            MatrixMarketErrorKind::ParsingError,
-            format!(
-                "
-        Spare real matrix requires 3 int number as coordinates and data, but line {} was provided.  
-        ",
-                entry_inner.as_str()
-            ),
-        ));
+    match header_type.sparsity {
+        Sparsity::Sparse => {
+            let (r, c, d) = match header_type.datatype {
+                DataType::Real | DataType::Integer => {
+                    let [r, c, d] = split_tokens(line).ok_or_else(|| {
+                        entry_error(line, "2 int number as coordinates and 1 number as data")
+                    })?;
+                    (r, c, parse_scalar(&header_type.datatype, d, "")?)
+                }
+                DataType::Complex => {
+                    let [r, c, re, im] = split_tokens(line).ok_or_else(|| {
+                        entry_error(
+                            line,
+                            "2 int number as coordinates and 2 real number as complex data",
+                        )
+                    })?;
+                    (r, c, parse_scalar(&header_type.datatype, re, im)?)
+                }
+                DataType::Pattern => {
+                    let [r, c] = split_tokens(line)
+                        .ok_or_else(|| entry_error(line, "2 int number as coordinates"))?;
+                    (r, c, T::from_pattern(())?)
+                }
+            };
+            let (r, c) = parse_sparse_coordinate(r, c)?;
+            Ok((r, c, d))
+        }
+        Sparsity::Dense => {
+            let d = match header_type.datatype {
+                DataType::Complex => {
+                    let [re, im] = split_tokens(line)
+                        .ok_or_else(|| entry_error(line, "2 real number as complex data"))?;
+                    parse_scalar(&header_type.datatype, re, im)?
+                }
+                _ => {
+                    let [d] =
+                        split_tokens(line).ok_or_else(|| entry_error(line, "1 number as data"))?;
+                    parse_scalar(&header_type.datatype, d, "")?
+                }
+            };
+            let (r, c) = *current_dense_coordinate;
+            next_dense_coordinate(current_dense_coordinate, shape, &header_type.storagescheme);
+            Ok((r, c, d))
+        }
     }
-    let mut inner = entry_inner.into_inner();
-    let (r, c) = parse_sparse_coordinate(&mut inner)?;
-    // Here to guarantee it is an integer number
-    let d = inner.next().unwrap().as_str().parse::<i128>()?;
-    Ok((r, c, T::from_i128(d)?))
 }
 
-/// Parse a pest structure to sparse pattern entry, including 2 int, which are number of rows, cols
-fn parse_sparse_pattern<T>(
-    inner: &mut Pairs<'_, Rule>,
-) -> Result<(usize, usize, T), MatrixMarketError>
-where
-    T: MatrixMarketScalar,
-{
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    if entry_inner.as_rule() != Rule::SparsePattern {
-        return Err(MatrixMarketError::from_kind_and_message(
-            MatrixMarketErrorKind::ParsingError,
-            format!(
-                "
-        Spare real matrix requires 2 int number as coordinates, but line {} was provided.  
-        ",
-                entry_inner.as_str()
-            ),
-        ));
-    }
-    let mut inner = entry_inner.into_inner();
-    let (r, c) = parse_sparse_coordinate(&mut inner)?;
-    Ok((r, c, T::from_pattern(())?))
+fn entry_error(line: &str, expected: &str) -> MatrixMarketError {
+    MatrixMarketError::from_kind_and_message(
+        MatrixMarketErrorKind::ParsingError,
+        format!(
+            "The matrix requires {} in each entry, but line {} was provided.",
+            expected, line
+        ),
+    )
 }
 
-/// Parse a pest structure to sparse complex entry, including 2 int, which are number of rows, cols, and 2 real number as complex data
-fn parse_sparse_complex<T>(
-    inner: &mut Pairs<'_, Rule>,
-) -> Result<(usize, usize, T), MatrixMarketError>
+/// Parse the data of an entry to a scalar of the given data type.
+///
+/// The imaginary part `im` is only used for complex data.
+fn parse_scalar<T>(datatype: &DataType, re: &str, im: &str) -> Result<T, MatrixMarketError>
 where
     T: MatrixMarketScalar,
 {
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    if entry_inner.as_rule() != Rule::SparseComplex {
-        return Err(MatrixMarketError::from_kind_and_message(MatrixMarketErrorKind::ParsingError,format!("
-        Spare real matrix requires 2 int number as coordinates and 2 real number as complex data, but line {} was provided.  
-        ",entry_inner.as_str() )));
+    match datatype {
+        DataType::Real => T::from_f64(parse_real(re)?),
+        // Here to guarantee it is an integer number
+        DataType::Integer => T::from_i128(re.parse::<i128>()?),
+        DataType::Complex => T::from_c64(Complex::new(parse_real(re)?, parse_real(im)?)),
+        DataType::Pattern => T::from_pattern(()),
     }
-    let mut inner = entry_inner.into_inner();
-    let (r, c) = parse_sparse_coordinate(&mut inner)?;
-    let real = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    let imag = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    let complex = Complex::<f64>::new(real, imag);
-    Ok((r, c, T::from_c64(complex)?))
 }
 
-/// Parse a pest structure to dense real entry, including a real number as data
-fn parse_dense_real<T>(inner: &mut Pairs<'_, Rule>) -> Result<T, MatrixMarketError>
-where
-    T: MatrixMarketScalar,
-{
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    if entry_inner.as_rule() != Rule::DenseReal {
-        return Err(MatrixMarketError::from_kind_and_message(
+fn parse_real(token: &str) -> Result<f64, MatrixMarketError> {
+    token.parse::<f64>().map_err(|_| {
+        MatrixMarketError::from_kind_and_message(
             MatrixMarketErrorKind::ParsingError,
-            format!(
-                "
-        Dense real matrix requires 1 real number as data, but line {} was provided.  
-        ",
-                entry_inner.as_str()
-            ),
-        ));
-    }
-    let mut inner = entry_inner.into_inner();
-    let d = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    Ok(T::from_f64(d)?)
+            format!("Can't parse {} as a real number.", token),
+        )
+    })
 }
 
-/// Parse a pest structure to dense integer entry, including a integer number as data
-fn parse_dense_int<T>(inner: &mut Pairs<'_, Rule>) -> Result<T, MatrixMarketError>
-where
-    T: MatrixMarketScalar,
-{
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    // Because integer numbers can also be parsed as float numbers, it will be checked again in `parse::<i128>()?`
-    if entry_inner.as_rule() != Rule::DenseReal {
+fn parse_dimension(token: &str) -> Result<usize, MatrixMarketError> {
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
         return Err(MatrixMarketError::from_kind_and_message(
             MatrixMarketErrorKind::ParsingError,
-            format!(
-                "
-        Dense real matrix requires 1 int number as data, but line {} was provided.  
-        ",
-                entry_inner.as_str()
-            ),
+            format!("Can't parse {} as a non-negative integer.", token),
         ));
     }
-    let mut inner = entry_inner.into_inner();
-    // Here to guarantee it is an integer number
-    let d = inner.next().unwrap().as_str().parse::<i128>()?;
-    Ok(T::from_i128(d)?)
-}
-
-/// Parse a pest structure to dense complex entry, including 2 real number as complex data
-fn parse_dense_complex<T>(inner: &mut Pairs<'_, Rule>) -> Result<T, MatrixMarketError>
-where
-    T: MatrixMarketScalar,
-{
-    // unwrap() in this function are guaranteed by parsing the data
-    let entry_inner = inner.next().unwrap();
-    // Note: theoretically, 2 positive integers could also become the complex number,
-    // but it would be parsed as SparsePattern, because SparsePattern has higher priority.
-    // But DenseComplex can't have higher priority,
-    // because, it's more often to deal with "normal" SparsePattern, rather than "unnormal" DenseComplex
-    if entry_inner.as_rule() != Rule::DenseComplex && entry_inner.as_rule() != Rule::SparsePattern {
-        return Err(MatrixMarketError::from_kind_and_message(
+    token.parse::<usize>().map_err(|err| {
+        MatrixMarketError::from_kind_and_message(
             MatrixMarketErrorKind::ParsingError,
             format!(
-                "
-        Dense real matrix requires 2 real number as complex data, but line {} was provided.  
-        ",
-                entry_inner.as_str()
+                "Can't parse {} as a non-negative integer.\n Error: {}",
+                token, err
             ),
-        ));
-    }
-    let mut inner = entry_inner.into_inner();
-    let real = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    let imag = inner.next().unwrap().as_str().parse::<f64>().unwrap();
-    let complex = Complex::<f64>::new(real, imag);
-    Ok(T::from_c64(complex)?)
+        )
+    })
 }
 
 // Parse entry ends here-------------------------------------------------
 
 /// Parse the coordinates information used for sparse matrix
-fn parse_sparse_coordinate(
-    inner: &mut Pairs<'_, Rule>,
-) -> Result<(usize, usize), MatrixMarketError> {
-    // unwrap() in this function are guaranteed by parsing the data
-    let r = inner.next().unwrap().as_str().parse::<usize>().unwrap();
-    let c = inner.next().unwrap().as_str().parse::<usize>().unwrap();
-    if r * c == 0 {
+fn parse_sparse_coordinate(r: &str, c: &str) -> Result<(usize, usize), MatrixMarketError> {
+    let r = parse_dimension(r)?;
+    let c = parse_dimension(c)?;
+    if r == 0 || c == 0 {
         return Err(MatrixMarketError::from_kind_and_message(
             MatrixMarketErrorKind::ZeroError,
             String::from("The data has to be one-indexed"),
@@ -1430,6 +1514,166 @@ fn next_dense_coordinate(
     }
 }
 
+/// Parses a Matrix Market file at the given path as a dense `DMatrix`.
+///
+/// Both the `array` and the `coordinate` layouts are accepted. Entries of a `coordinate` file that are not
+/// stored are zero, and duplicate entries are summed. Symmetric, skew-symmetric and Hermitian files are
+/// expanded into the full matrix.
+///
+/// See [load_coo_from_matrix_market_file] for more information on the supported scalar types.
+///
+/// Errors
+/// --------
+///
+/// See [MatrixMarketErrorKind] for a list of possible error conditions.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// use nalgebra_sparse::io::load_dmatrix_from_matrix_market_file;
+/// let matrix = load_dmatrix_from_matrix_market_file::<f64, _>("path/to/matrix.mtx").unwrap();
+/// ```
+pub fn load_dmatrix_from_matrix_market_file<T, P: AsRef<Path>>(
+    path: P,
+) -> Result<DMatrix<T>, MatrixMarketError>
+where
+    T: MatrixMarketScalar + Zero + ClosedAdd,
+{
+    let coo = load_coo_from_matrix_market_file(path)?;
+    Ok(DMatrix::from(&coo))
+}
+
+/// Parses a Matrix Market file described by the given string as a dense `DMatrix`.
+///
+/// See [load_dmatrix_from_matrix_market_file] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [MatrixMarketErrorKind] for a list of possible error conditions.
+///
+/// Examples
+/// --------
+/// ```
+/// use nalgebra_sparse::io::load_dmatrix_from_matrix_market_str;
+/// use nalgebra::matrix;
+/// let str = r#"
+/// %%matrixmarket matrix array real symmetric
+/// 2 2
+/// 4.0
+/// 1.0
+/// 3.0
+/// "#;
+/// let matrix = load_dmatrix_from_matrix_market_str::<f64>(str).unwrap();
+/// assert_eq!(matrix, matrix![4.0, 1.0; 1.0, 3.0]);
+/// ```
+pub fn load_dmatrix_from_matrix_market_str<T>(data: &str) -> Result<DMatrix<T>, MatrixMarketError>
+where
+    T: MatrixMarketScalar + Zero + ClosedAdd,
+{
+    let coo = load_coo_from_matrix_market_str(data)?;
+    Ok(DMatrix::from(&coo))
+}
+
+/// The symmetry structure with which a matrix is exported to the matrix market format.
+///
+/// With any structure other than [General](MatrixMarketSymmetry::General), only the lower triangle of the
+/// matrix is written to the file, which roughly halves its size. The strictly upper triangle is reconstructed
+/// from the lower triangle when the file is loaded again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MatrixMarketSymmetry {
+    /// The matrix has no particular structure, and all of its entries are written.
+    General,
+    /// The matrix is symmetric, `A = A^T`.
+    ///
+    /// Only the lower triangle, including the diagonal, is written.
+    Symmetric,
+    /// The matrix is skew-symmetric, `A = -A^T`.
+    ///
+    /// Only the strictly lower triangle is written, since the diagonal of a skew-symmetric matrix is zero.
+    /// Not supported for the `pattern` type.
+    SkewSymmetric,
+    /// The matrix is Hermitian, `A = A^H`.
+    ///
+    /// Only the lower triangle, including the diagonal, is written. Only supported for the `complex` type.
+    Hermitian,
+}
+
+impl MatrixMarketSymmetry {
+    fn keyword(self) -> &'static str {
+        match self {
+            MatrixMarketSymmetry::General => "general",
+            MatrixMarketSymmetry::Symmetric => "symmetric",
+            MatrixMarketSymmetry::SkewSymmetric => "skew-symmetric",
+            MatrixMarketSymmetry::Hermitian => "hermitian",
+        }
+    }
+
+    /// Whether the entry `(r, c)` is written to the file.
+    fn is_stored(self, r: usize, c: usize) -> bool {
+        match self {
+            MatrixMarketSymmetry::General => true,
+            MatrixMarketSymmetry::Symmetric | MatrixMarketSymmetry::Hermitian => r >= c,
+            MatrixMarketSymmetry::SkewSymmetric => r > c,
+        }
+    }
+
+    /// Maps the entry `(r, c)` to the entry `(c, r)` that it implies.
+    fn mirror<T: MatrixMarketScalar>(self, value: T) -> T {
+        match self {
+            MatrixMarketSymmetry::General | MatrixMarketSymmetry::Symmetric => Ok(value),
+            MatrixMarketSymmetry::SkewSymmetric => value.negative(),
+            MatrixMarketSymmetry::Hermitian => value.conjugate(),
+        }
+        .expect("The symmetry structure must be supported by the scalar type")
+    }
+}
+
+fn invalid_input(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+/// Checks that a matrix of the given shape and scalar type can be exported with the given symmetry structure.
+fn check_export_symmetry<T: MatrixMarketScalar>(
+    symmetry: MatrixMarketSymmetry,
+    nrows: usize,
+    ncols: usize,
+) -> Result<(), std::io::Error> {
+    if symmetry == MatrixMarketSymmetry::General {
+        return Ok(());
+    }
+    if nrows != ncols {
+        return Err(invalid_input(format!(
+            "A {} x {} matrix can not be exported as {}, since it is not square",
+            nrows,
+            ncols,
+            symmetry.keyword()
+        )));
+    }
+    let supported = match symmetry {
+        MatrixMarketSymmetry::General | MatrixMarketSymmetry::Symmetric => true,
+        MatrixMarketSymmetry::SkewSymmetric => !T::IS_PATTERN,
+        MatrixMarketSymmetry::Hermitian => T::IS_COMPLEX,
+    };
+    if !supported {
+        return Err(invalid_input(format!(
+            "A {} matrix can not be exported as {}",
+            T::typename(),
+            symmetry.keyword()
+        )));
+    }
+    Ok(())
+}
+
+fn symmetry_mismatch(symmetry: MatrixMarketSymmetry, r: usize, c: usize) -> std::io::Error {
+    invalid_input(format!(
+        "The matrix is not {}: entry at row {} col {} does not match its mirrored entry",
+        symmetry.keyword(),
+        r + 1,
+        c + 1
+    ))
+}
+
 /// Save a sparse matrix as a Matrix Market format string.
 ///
 /// The exporter only writes the matrix into `coordinate` and `general` format.
@@ -1456,6 +1700,48 @@ fn next_dense_coordinate(
 /// # Ok(()) }
 /// ```
 pub fn save_to_matrix_market_str<T, S>(sparse_matrix: &S) -> String
+where
+    T: MatrixMarketScalar,
+    S: MatrixMarketExport<T>,
+{
+    // A general export of a sparse matrix can not fail
+    save_to_matrix_market_str_with_symmetry(sparse_matrix, MatrixMarketSymmetry::General).unwrap()
+}
+
+/// Save a sparse matrix as a Matrix Market format string, using the given symmetry structure.
+///
+/// See [save_to_matrix_market_with_symmetry] for more information.
+///
+/// Errors
+/// --------
+///
+/// An error of kind [InvalidInput](std::io::ErrorKind::InvalidInput) is returned if the matrix can not be
+/// exported with the given symmetry structure.
+///
+/// Examples
+/// --------
+/// ```
+/// # use nalgebra_sparse::CsrMatrix;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use nalgebra_sparse::io::{save_to_matrix_market_str_with_symmetry, MatrixMarketSymmetry};
+/// use nalgebra::matrix;
+/// let expected_str = r#"%%matrixmarket matrix coordinate real symmetric
+/// % matrixmarket file generated by nalgebra-sparse.
+/// 2 2 3
+/// 1 1 4
+/// 2 1 1
+/// 2 2 3
+/// "#;
+/// let matrix = CsrMatrix::from(&matrix![4.0, 1.0; 1.0, 3.0]);
+/// let generated_matrixmarket_str =
+///     save_to_matrix_market_str_with_symmetry(&matrix, MatrixMarketSymmetry::Symmetric)?;
+/// assert_eq!(expected_str, generated_matrixmarket_str);
+/// # Ok(()) }
+/// ```
+pub fn save_to_matrix_market_str_with_symmetry<T, S>(
+    sparse_matrix: &S,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<String, std::io::Error>
 where
     T: MatrixMarketScalar,
     S: MatrixMarketExport<T>,
@@ -1463,11 +1749,11 @@ where
     let mut bytes = Vec::<u8>::new();
     // This will call impl<A: Allocator> Write for Vec<u8, A>
     // The vector will grow as needed.
-    // So, unwrap here won't cause any issue.
-    save_to_matrix_market(&mut bytes, sparse_matrix).unwrap();
+    // So, the only errors here stem from an unsupported symmetry structure.
+    save_to_matrix_market_with_symmetry(&mut bytes, sparse_matrix, symmetry)?;
 
-    String::from_utf8(bytes)
-        .expect("Unexpected non UTF-8 data was generated when export to matrix market string")
+    Ok(String::from_utf8(bytes)
+        .expect("Unexpected non UTF-8 data was generated when export to matrix market string"))
 }
 
 /// Save a sparse matrix to a Matrix Market format file.
@@ -1500,13 +1786,36 @@ where
     S: MatrixMarketExport<T>,
     P: AsRef<Path>,
 {
+    save_to_matrix_market_file_with_symmetry(sparse_matrix, path, MatrixMarketSymmetry::General)
+}
+
+/// Save a sparse matrix to a Matrix Market format file, using the given symmetry structure.
+///
+/// See [save_to_matrix_market_with_symmetry] for more information.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if the matrix can not be exported with the given
+/// symmetry structure. In the latter case, the file is not created.
+pub fn save_to_matrix_market_file_with_symmetry<T, S, P>(
+    sparse_matrix: &S,
+    path: P,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    S: MatrixMarketExport<T>,
+    P: AsRef<Path>,
+{
+    check_sparse_export(sparse_matrix, symmetry)?;
     let file = File::create(path)?;
     let mut file = BufWriter::new(file);
-    save_to_matrix_market(&mut file, sparse_matrix)?;
+    write_coordinate(&mut file, sparse_matrix, symmetry)?;
     // Quote from BufWriter doc.
     // > It is critical to call flush before BufWriter<W> is dropped. Though dropping will attempt to flush the contents of the buffer, any errors that happen in the process of dropping will be ignored. Calling flush ensures that the buffer is empty and thus dropping will not even attempt file operations.
-    file.flush()
-        .expect("Unexpected error when flushing the buffer data to File");
+    file.flush()?;
     Ok(())
 }
 
@@ -1514,7 +1823,53 @@ where
 ///
 /// This is the most general save functionality. See [save_to_matrix_market_file] and
 /// [save_to_matrix_market_str] for higher-level functionality.
-pub fn save_to_matrix_market<T, S, W>(mut w: W, sparse_matrix: &S) -> Result<(), std::io::Error>
+pub fn save_to_matrix_market<T, S, W>(w: W, sparse_matrix: &S) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    S: MatrixMarketExport<T>,
+    W: Write,
+{
+    save_to_matrix_market_with_symmetry(w, sparse_matrix, MatrixMarketSymmetry::General)
+}
+
+/// Save a sparse matrix to an [std::io::Write] instance, using the given symmetry structure.
+///
+/// The matrix is written in the `coordinate` format. Unless `symmetry` is
+/// [General](MatrixMarketSymmetry::General), only the entries in the lower triangle of the matrix are written.
+/// Before anything is written, the matrix is checked to actually have the given symmetry structure: every
+/// stored entry in the strictly upper triangle must have a matching stored entry in the strictly lower
+/// triangle, and vice versa. Since the check is performed on the explicitly stored entries, duplicate or
+/// explicitly stored zero entries must appear in both triangles.
+///
+/// Errors
+/// --------
+///
+/// Returns any error produced by the writer, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if
+///
+/// - the matrix is not square and `symmetry` is not [General](MatrixMarketSymmetry::General),
+/// - the scalar type does not support the symmetry structure, see [MatrixMarketSymmetry], or
+/// - the matrix does not have the given symmetry structure.
+pub fn save_to_matrix_market_with_symmetry<T, S, W>(
+    w: W,
+    sparse_matrix: &S,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    S: MatrixMarketExport<T>,
+    W: Write,
+{
+    check_sparse_export(sparse_matrix, symmetry)?;
+    write_coordinate(w, sparse_matrix, symmetry)
+}
+
+/// Writes a sparse matrix in the `coordinate` format, assuming that it has the given symmetry structure.
+fn write_coordinate<T, S, W>(
+    mut w: W,
+    sparse_matrix: &S,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
 where
     T: MatrixMarketScalar,
     S: MatrixMarketExport<T>,
@@ -1523,25 +1878,36 @@ where
     // write header
     writeln!(
         w,
-        "%%matrixmarket matrix coordinate {} general",
-        T::typename()
+        "%%matrixmarket matrix coordinate {} {}",
+        T::typename(),
+        symmetry.keyword()
     )?;
 
     //write comment
     writeln!(w, "% matrixmarket file generated by nalgebra-sparse.")?;
 
     // write shape information
+    let nnz = match symmetry {
+        MatrixMarketSymmetry::General => sparse_matrix.nnz(),
+        _ => sparse_matrix
+            .triplet_iter()
+            .filter(|&(r, c, _)| symmetry.is_stored(r, c))
+            .count(),
+    };
     writeln!(
         w,
         "{} {} {}",
         sparse_matrix.nrows(),
         sparse_matrix.ncols(),
-        sparse_matrix.nnz()
+        nnz
     )?;
 
     //write triplets
     let mut buffer = String::new();
     for (r, c, d) in sparse_matrix.triplet_iter() {
+        if !symmetry.is_stored(r, c) {
+            continue;
+        }
         buffer.clear();
         d.write_matrix_market(&mut buffer)
             .expect("Unexpected format error was generated when write to String");
@@ -1549,3 +1915,307 @@ where
     }
     Ok(())
 }
+
+/// Checks that a sparse matrix can be exported with the given symmetry structure.
+fn check_sparse_export<T, S>(
+    sparse_matrix: &S,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    S: MatrixMarketExport<T>,
+{
+    check_export_symmetry::<T>(symmetry, sparse_matrix.nrows(), sparse_matrix.ncols())?;
+    if symmetry == MatrixMarketSymmetry::General {
+        return Ok(());
+    }
+
+    // Collect the strictly lower triangle, and the mirror image of the strictly upper triangle
+    let mut lower = Vec::new();
+    let mut mirrored_upper = Vec::new();
+    for (r, c, d) in sparse_matrix.triplet_iter() {
+        if r > c {
+            lower.push((r, c, d.clone()));
+        } else if r < c {
+            mirrored_upper.push((c, r, symmetry.mirror(d.clone())));
+        } else if *d != symmetry.mirror(d.clone()) {
+            return Err(symmetry_mismatch(symmetry, r, c));
+        }
+    }
+
+    lower.sort_by_key(|&(r, c, _)| (r, c));
+    mirrored_upper.sort_by_key(|&(r, c, _)| (r, c));
+    if lower.len() != mirrored_upper.len() {
+        return Err(invalid_input(format!(
+            "The matrix is not {}: the upper and lower triangle have a different number of entries",
+            symmetry.keyword()
+        )));
+    }
+    for (l, u) in lower.iter().zip(mirrored_upper.iter()) {
+        if l != u {
+            let (r, c, _) = if (l.0, l.1) <= (u.0, u.1) { l } else { u };
+            return Err(symmetry_mismatch(symmetry, *r, *c));
+        }
+    }
+    Ok(())
+}
+
+/// Save a dense matrix as a Matrix Market format string.
+///
+/// The exporter writes the matrix in the `array` and `general` format.
+///
+/// Panics
+/// --------
+///
+/// Panics if `T` is the `pattern` type `()`, which can not be stored in the `array` format.
+///
+/// Examples
+/// --------
+/// ```
+/// use nalgebra_sparse::io::save_dmatrix_to_matrix_market_str;
+/// use nalgebra::matrix;
+/// let expected_str = r#"%%matrixmarket matrix array integer general
+/// % matrixmarket file generated by nalgebra-sparse.
+/// 2 3
+/// 1
+/// 4
+/// 2
+/// 5
+/// 3
+/// 6
+/// "#;
+/// let matrix = matrix![1, 2, 3; 4, 5, 6];
+/// let generated_matrixmarket_str = save_dmatrix_to_matrix_market_str(&matrix);
+/// assert_eq!(expected_str, generated_matrixmarket_str);
+/// ```
+pub fn save_dmatrix_to_matrix_market_str<T, R, C, S>(matrix: &Matrix<T, R, C, S>) -> String
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+{
+    save_dmatrix_to_matrix_market_str_with_symmetry(matrix, MatrixMarketSymmetry::General)
+        .expect("Dense matrices of the pattern type can not be exported")
+}
+
+/// Save a dense matrix as a Matrix Market format string, using the given symmetry structure.
+///
+/// See [save_dmatrix_to_matrix_market_with_symmetry] for more information.
+///
+/// Errors
+/// --------
+///
+/// An error of kind [InvalidInput](std::io::ErrorKind::InvalidInput) is returned if the matrix can not be
+/// exported with the given symmetry structure.
+pub fn save_dmatrix_to_matrix_market_str_with_symmetry<T, R, C, S>(
+    matrix: &Matrix<T, R, C, S>,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<String, std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+{
+    let mut bytes = Vec::<u8>::new();
+    save_dmatrix_to_matrix_market_with_symmetry(&mut bytes, matrix, symmetry)?;
+
+    Ok(String::from_utf8(bytes)
+        .expect("Unexpected non UTF-8 data was generated when export to matrix market string"))
+}
+
+/// Save a dense matrix to a Matrix Market format file.
+///
+/// The exporter writes the matrix in the `array` and `general` format.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if `T` is the `pattern` type `()`.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use nalgebra_sparse::io::save_dmatrix_to_matrix_market_file;
+/// use nalgebra::DMatrix;
+/// let matrix = DMatrix::<f64>::identity(3, 3);
+/// save_dmatrix_to_matrix_market_file(&matrix, "path/to/matrix.mtx")?;
+/// # Ok(()) }
+/// ```
+pub fn save_dmatrix_to_matrix_market_file<T, R, C, S, P>(
+    matrix: &Matrix<T, R, C, S>,
+    path: P,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+    P: AsRef<Path>,
+{
+    save_dmatrix_to_matrix_market_file_with_symmetry(matrix, path, MatrixMarketSymmetry::General)
+}
+
+/// Save a dense matrix to a Matrix Market format file, using the given symmetry structure.
+///
+/// See [save_dmatrix_to_matrix_market_with_symmetry] for more information.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if the matrix can not be exported with the given
+/// symmetry structure. In the latter case, the file is not created.
+pub fn save_dmatrix_to_matrix_market_file_with_symmetry<T, R, C, S, P>(
+    matrix: &Matrix<T, R, C, S>,
+    path: P,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+    P: AsRef<Path>,
+{
+    check_dense_export(matrix, symmetry)?;
+    let file = File::create(path)?;
+    let mut file = BufWriter::new(file);
+    write_array(&mut file, matrix, symmetry)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Save a dense matrix to an [std::io::Write] instance.
+///
+/// This is the most general save functionality for dense matrices. See
+/// [save_dmatrix_to_matrix_market_file] and [save_dmatrix_to_matrix_market_str] for higher-level
+/// functionality.
+///
+/// Errors
+/// --------
+///
+/// Returns any error produced by the writer, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if `T` is the `pattern` type `()`.
+pub fn save_dmatrix_to_matrix_market<T, R, C, S, W>(
+    w: W,
+    matrix: &Matrix<T, R, C, S>,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+    W: Write,
+{
+    save_dmatrix_to_matrix_market_with_symmetry(w, matrix, MatrixMarketSymmetry::General)
+}
+
+/// Save a dense matrix to an [std::io::Write] instance, using the given symmetry structure.
+///
+/// The matrix is written in the `array` format, in column-major order. Unless `symmetry` is
+/// [General](MatrixMarketSymmetry::General), only the entries in the lower triangle of the matrix are written,
+/// and the matrix is checked to actually have the given symmetry structure before anything is written.
+///
+/// Errors
+/// --------
+///
+/// Returns any error produced by the writer, or an error of kind
+/// [InvalidInput](std::io::ErrorKind::InvalidInput) if
+///
+/// - `T` is the `pattern` type `()`, which can not be stored in the `array` format,
+/// - the matrix is not square and `symmetry` is not [General](MatrixMarketSymmetry::General),
+/// - the scalar type does not support the symmetry structure, see [MatrixMarketSymmetry], or
+/// - the matrix does not have the given symmetry structure.
+pub fn save_dmatrix_to_matrix_market_with_symmetry<T, R, C, S, W>(
+    w: W,
+    matrix: &Matrix<T, R, C, S>,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+    W: Write,
+{
+    check_dense_export(matrix, symmetry)?;
+    write_array(w, matrix, symmetry)
+}
+
+/// Writes a dense matrix in the `array` format, assuming that it has the given symmetry structure.
+fn write_array<T, R, C, S, W>(
+    mut w: W,
+    matrix: &Matrix<T, R, C, S>,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+    W: Write,
+{
+    // write header
+    writeln!(
+        w,
+        "%%matrixmarket matrix array {} {}",
+        T::typename(),
+        symmetry.keyword()
+    )?;
+
+    //write comment
+    writeln!(w, "% matrixmarket file generated by nalgebra-sparse.")?;
+
+    // write shape information
+    writeln!(w, "{} {}", matrix.nrows(), matrix.ncols())?;
+
+    // write entries in column-major order
+    let mut buffer = String::new();
+    for c in 0..matrix.ncols() {
+        for r in 0..matrix.nrows() {
+            if !symmetry.is_stored(r, c) {
+                continue;
+            }
+            buffer.clear();
+            matrix[(r, c)]
+                .write_matrix_market(&mut buffer)
+                .expect("Unexpected format error was generated when write to String");
+            writeln!(w, "{}", buffer)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a dense matrix can be exported with the given symmetry structure.
+fn check_dense_export<T, R, C, S>(
+    matrix: &Matrix<T, R, C, S>,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), std::io::Error>
+where
+    T: MatrixMarketScalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<T, R, C>,
+{
+    if T::IS_PATTERN {
+        return Err(invalid_input(
+            "A pattern matrix can not be exported in the array format".to_string(),
+        ));
+    }
+    check_export_symmetry::<T>(symmetry, matrix.nrows(), matrix.ncols())?;
+    if symmetry == MatrixMarketSymmetry::General {
+        return Ok(());
+    }
+    for c in 0..matrix.ncols() {
+        for r in c..matrix.nrows() {
+            if matrix[(c, r)] != symmetry.mirror(matrix[(r, c)].clone()) {
+                return Err(symmetry_mismatch(symmetry, c, r));
+            }
+        }
+    }
+    Ok(())
+}
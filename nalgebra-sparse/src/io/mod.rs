@@ -7,6 +7,8 @@
 //! | Format                                          |  Import    |   Export   |
 //! | ------------------------------------------------|------------|------------|
 //! | [Matrix market](#matrix-market-format)          |  Yes       |    Yes     |
+//! | [Rutherford-Boeing](#rutherford-boeing-format)  |  Yes       |    Yes     |
+//! | [Binary](#binary-format)                        |  Yes       |    Yes     |
 //!
 //! [Matrix market]: https://math.nist.gov/MatrixMarket/formats.html
 //!
@@ -26,16 +28,91 @@
 //! [load_coo_from_matrix_market_str], or similarly write to a string with
 //! [save_to_matrix_market_str].
 //!
+//! Files are parsed line by line as they are read, so that large files never need to be held in memory in
+//! their entirety. Any [BufRead](std::io::BufRead) instance can be parsed directly with
+//! [load_coo_from_matrix_market_reader], and [load_coo_from_matrix_market_reader_with_progress]
+//! additionally reports the progress of the parser, which is useful for very large files.
+//!
+//! Dense matrices are supported through [load_dmatrix_from_matrix_market_file] and
+//! [save_dmatrix_to_matrix_market_file], which use the `array` layout of the format, and their
+//! string-based counterparts. Symmetric, skew-symmetric and Hermitian matrices may be exported with only
+//! one triangle stored by passing the appropriate [MatrixMarketSymmetry] to the `*_with_symmetry`
+//! variants of the save functions, such as [save_to_matrix_market_file_with_symmetry].
+//!
 //! Our implementation is based on the [format description](https://math.nist.gov/MatrixMarket/formats.html)
 //! on the Matrix Market website and the
 //! [following NIST whitepaper](https://math.nist.gov/MatrixMarket/reports/MMformat.ps):
 //!
 //! > Boisvert, Ronald F., Roldan Pozo, and Karin A. Remington.<br/>
 //! > "*The Matrix Market Exchange Formats: Initial Design.*" (1996).
+//!
+//! ## Rutherford-Boeing format
+//!
+//! The [Rutherford-Boeing format](https://www.cise.ufl.edu/research/sparse/matrices/DOC/rb.pdf) is a
+//! Fortran-oriented ASCII format for sparse matrices in compressed column form, and the successor of the
+//! Harwell-Boeing format. Files in either format can be imported as a [CscMatrix](crate::CscMatrix) with
+//! [load_csc_from_rutherford_boeing_file], as long as they hold an assembled matrix. A
+//! [CscMatrix](crate::CscMatrix) can be written with [save_to_rutherford_boeing_file].
+//!
+//! ## Binary format
+//!
+//! For fast checkpointing, [CsrMatrix](crate::CsrMatrix) and [CscMatrix](crate::CscMatrix) can be saved to
+//! a simple, versioned little-endian binary format with [save_csr_to_binary_file] and
+//! [save_csc_to_binary_file]. The offsets, indices and values are stored exactly, and are loaded again with
+//! [load_csr_from_binary_file] and [load_csc_from_binary_file].
+//!
+//! A binary file consists of a fixed-size header followed by the offsets, the indices and the values of the
+//! matrix. All numbers are stored in little-endian byte order.
+//!
+//! | Bytes   | Content                                                                  |
+//! |---------|--------------------------------------------------------------------------|
+//! | 8       | The magic bytes `NASPARSE`                                               |
+//! | 4       | The format version as `u32`, currently 1                                 |
+//! | 1       | The storage as `u8`: 0 for CSR, 1 for CSC                                |
+//! | 1       | The scalar type code as `u8`, see [BinaryScalar]                         |
+//! | 2       | Reserved, must be zero                                                   |
+//! | 3 × 8   | The number of rows, columns and non-zeros as `u64`                       |
+//! | 8 × ... | The offsets as `u64`, one more than the number of rows (columns for CSC) |
+//! | 8 × nnz | The minor indices as `u64`                                               |
+//! | ...     | The values                                                               |
+//!
+//! ## Spy plots and pattern statistics
+//!
+//! When debugging orderings or assembly, it often helps to look at the sparsity pattern itself.
+//! A [SparsityPattern](crate::pattern::SparsityPattern) can be drawn as a text spy plot in ASCII or
+//! Unicode with [save_spy_plot] and [save_spy_plot_str], or written as a PBM bitmap with
+//! [save_spy_pbm] or as an SVG image with [save_spy_svg]. Large patterns are downsampled to fit
+//! the requested size in text plots and bitmaps. [PatternStats] summarizes a pattern or a CSR
+//! matrix with the number of non-zeros per row, the bandwidth, the structural symmetry, the
+//! diagonal dominance and the number of empty rows.
 
+pub use self::binary::{
+    load_csc_from_binary, load_csc_from_binary_file, load_csr_from_binary,
+    load_csr_from_binary_file, save_csc_to_binary, save_csc_to_binary_file, save_csr_to_binary,
+    save_csr_to_binary_file, BinaryFormatError, BinaryFormatErrorKind, BinaryScalar,
+};
+mod binary;
 pub use self::matrix_market::{
-    load_coo_from_matrix_market_file, load_coo_from_matrix_market_str, save_to_matrix_market,
-    save_to_matrix_market_file, save_to_matrix_market_str, MatrixMarketError,
-    MatrixMarketErrorKind, MatrixMarketExport, MatrixMarketScalar,
+    load_coo_from_matrix_market_file, load_coo_from_matrix_market_reader,
+    load_coo_from_matrix_market_reader_with_progress, load_coo_from_matrix_market_str,
+    load_dmatrix_from_matrix_market_file, load_dmatrix_from_matrix_market_str,
+    save_dmatrix_to_matrix_market, save_dmatrix_to_matrix_market_file,
+    save_dmatrix_to_matrix_market_file_with_symmetry, save_dmatrix_to_matrix_market_str,
+    save_dmatrix_to_matrix_market_str_with_symmetry, save_dmatrix_to_matrix_market_with_symmetry,
+    save_to_matrix_market, save_to_matrix_market_file, save_to_matrix_market_file_with_symmetry,
+    save_to_matrix_market_str, save_to_matrix_market_str_with_symmetry,
+    save_to_matrix_market_with_symmetry, MatrixMarketError, MatrixMarketErrorKind,
+    MatrixMarketExport, MatrixMarketProgress, MatrixMarketScalar, MatrixMarketSymmetry,
 };
 mod matrix_market;
+pub use self::rutherford_boeing::{
+    load_csc_from_rutherford_boeing_file, load_csc_from_rutherford_boeing_reader,
+    load_csc_from_rutherford_boeing_str, save_to_rutherford_boeing, save_to_rutherford_boeing_file,
+    save_to_rutherford_boeing_str, RutherfordBoeingError, RutherfordBoeingErrorKind,
+    RutherfordBoeingScalar,
+};
+mod rutherford_boeing;
+pub use self::spy::{
+    save_spy_pbm, save_spy_plot, save_spy_plot_str, save_spy_svg, PatternStats, SpyCharset,
+};
+mod spy;
@@ -0,0 +1,1057 @@
+//! Implementation of Rutherford-Boeing io code.
+//!
+//! See the [format description](https://www.cise.ufl.edu/research/sparse/matrices/DOC/rb.pdf) for more
+//! details about the Rutherford-Boeing format.
+use self::internal::Component;
+use crate::{CscMatrix, SparseFormatError, SparseFormatErrorKind};
+use nalgebra::Complex;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// A description of the error that occurred during importing a matrix from a Rutherford-Boeing file.
+#[derive(Debug)]
+pub struct RutherfordBoeingError {
+    error_kind: RutherfordBoeingErrorKind,
+    message: String,
+    line_number: Option<usize>,
+}
+
+/// Errors produced by functions that expect well-formed Rutherford-Boeing data.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RutherfordBoeingErrorKind {
+    /// Parsing failure.
+    ///
+    /// Indicates that a number or a Fortran format descriptor could not be parsed.
+    ParsingError,
+
+    /// Indicates that the header is invalid, or that it describes a matrix type which is not supported.
+    ///
+    /// Only assembled matrices are supported, elemental matrices are not.
+    InvalidHeader,
+
+    /// Indicates that the file ended before all the data announced in the header was read.
+    EntryMismatch,
+
+    /// Indicates that the scalar type requested is not compatible with the scalar type stored
+    /// in the file.
+    TypeMismatch,
+
+    /// Indicates that an entry of a symmetric, skew-symmetric or Hermitian matrix lies in the
+    /// upper triangle.
+    NotLowerTriangle,
+
+    /// Indicates that a skew-symmetric matrix stores an entry on the diagonal.
+    DiagonalError,
+
+    /// Indicates [SparseFormatError] while creating the sparse matrix.
+    SparseFormatError(SparseFormatErrorKind),
+
+    /// Indicates that an IO error occurred while reading the data.
+    IOError(std::io::ErrorKind),
+}
+
+impl RutherfordBoeingError {
+    fn from_kind_and_message(error_type: RutherfordBoeingErrorKind, message: String) -> Self {
+        Self {
+            error_kind: error_type,
+            message,
+            line_number: None,
+        }
+    }
+
+    /// Attributes the error to the given line, unless it is already attributed to a line.
+    fn with_line_number(mut self, line_number: usize) -> Self {
+        self.line_number.get_or_insert(line_number);
+        self
+    }
+
+    /// The Rutherford-Boeing error kind.
+    #[must_use]
+    pub fn kind(&self) -> RutherfordBoeingErrorKind {
+        self.error_kind
+    }
+
+    /// The underlying error message.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    /// The (one-based) number of the line at which the error occurred, if the error can be attributed
+    /// to a single line of the input.
+    #[must_use]
+    pub fn line_number(&self) -> Option<usize> {
+        self.line_number
+    }
+}
+
+impl fmt::Display for RutherfordBoeingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Rutherford-Boeing error: {:?},", self.kind())?;
+        if let Some(line_number) = self.line_number {
+            write!(f, " line: {},", line_number)?;
+        }
+        write!(f, " message: {}", self.message)
+    }
+}
+
+impl std::error::Error for RutherfordBoeingError {}
+
+impl From<SparseFormatError> for RutherfordBoeingError {
+    fn from(err: SparseFormatError) -> Self {
+        Self::from_kind_and_message(
+            RutherfordBoeingErrorKind::SparseFormatError(*err.kind()),
+            format!("{}", &err),
+        )
+    }
+}
+
+impl From<std::io::Error> for RutherfordBoeingError {
+    fn from(err: std::io::Error) -> Self {
+        Self::from_kind_and_message(
+            RutherfordBoeingErrorKind::IOError(err.kind()),
+            format!("{}", &err),
+        )
+    }
+}
+
+/// Scalar types supported by the Rutherford-Boeing parser.
+mod internal {
+    use super::RutherfordBoeingError;
+    use na::Scalar;
+
+    /// A single component of a stored value, i.e. a real number or an integer.
+    #[derive(Debug, Copy, Clone)]
+    pub enum Component {
+        Real(f64),
+        Integer(i128),
+    }
+
+    pub trait SupportedRutherfordBoeingScalar: Scalar {
+        /// The (lower case) value type in the matrix type code: `r`, `c`, `i` or `p`.
+        const VALUE_TYPE: char;
+        /// The number of numbers that make up a single value.
+        const COMPONENTS: usize;
+        /// Parse a value from exactly `COMPONENTS` fields.
+        fn parse_components(fields: &[&str]) -> Result<Self, RutherfordBoeingError>;
+        /// Pass each component of the value to `f`.
+        fn for_each_component<F: FnMut(Component)>(&self, f: F);
+        /// Negate the value, used for skew-symmetric matrices.
+        fn negative(self) -> Self;
+        /// Conjugate the value, used for Hermitian matrices.
+        fn conjugate(self) -> Self;
+    }
+}
+
+/// A marker trait for scalars supported by the Rutherford-Boeing reader and writer.
+///
+/// This is a sealed trait; it cannot be implemented by external crates. This is done in order to prevent leaking
+/// some of the implementation details we currently rely on. We may relax this restriction in the future.
+pub trait RutherfordBoeingScalar: internal::SupportedRutherfordBoeingScalar {}
+
+/// Implement RutherfordBoeingScalar for primitive integer types.
+macro_rules! rb_int_impl {
+    ($T:ty) => {
+        impl RutherfordBoeingScalar for $T {}
+
+        impl internal::SupportedRutherfordBoeingScalar for $T {
+            const VALUE_TYPE: char = 'i';
+            const COMPONENTS: usize = 1;
+            #[inline]
+            fn parse_components(fields: &[&str]) -> Result<Self, RutherfordBoeingError> {
+                let value = parse_integer(fields[0])?;
+                <$T>::try_from(value).map_err(|err| {
+                    RutherfordBoeingError::from_kind_and_message(
+                        RutherfordBoeingErrorKind::TypeMismatch,
+                        format!(
+                            "Please consider using a larger integer type. Error message: {}",
+                            err
+                        ),
+                    )
+                })
+            }
+            #[inline]
+            fn for_each_component<F: FnMut(Component)>(&self, mut f: F) {
+                f(Component::Integer(*self as i128))
+            }
+            #[inline]
+            fn negative(self) -> Self {
+                -self
+            }
+            #[inline]
+            fn conjugate(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+/// Implement RutherfordBoeingScalar for primitive real types.
+macro_rules! rb_real_impl {
+    ($T:ty) => {
+        impl RutherfordBoeingScalar for $T {}
+
+        impl internal::SupportedRutherfordBoeingScalar for $T {
+            const VALUE_TYPE: char = 'r';
+            const COMPONENTS: usize = 1;
+            #[inline]
+            fn parse_components(fields: &[&str]) -> Result<Self, RutherfordBoeingError> {
+                Ok(parse_real(fields[0])? as $T)
+            }
+            #[inline]
+            fn for_each_component<F: FnMut(Component)>(&self, mut f: F) {
+                f(Component::Real(*self as f64))
+            }
+            #[inline]
+            fn negative(self) -> Self {
+                -self
+            }
+            #[inline]
+            fn conjugate(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+/// Implement RutherfordBoeingScalar for primitive complex types.
+macro_rules! rb_complex_impl {
+    ($T:ty) => {
+        impl RutherfordBoeingScalar for Complex<$T> {}
+
+        impl internal::SupportedRutherfordBoeingScalar for Complex<$T> {
+            const VALUE_TYPE: char = 'c';
+            const COMPONENTS: usize = 2;
+            #[inline]
+            fn parse_components(fields: &[&str]) -> Result<Self, RutherfordBoeingError> {
+                Ok(Complex::new(
+                    parse_real(fields[0])? as $T,
+                    parse_real(fields[1])? as $T,
+                ))
+            }
+            #[inline]
+            fn for_each_component<F: FnMut(Component)>(&self, mut f: F) {
+                f(Component::Real(self.re as f64));
+                f(Component::Real(self.im as f64));
+            }
+            #[inline]
+            fn negative(self) -> Self {
+                -self
+            }
+            #[inline]
+            fn conjugate(self) -> Self {
+                self.conj()
+            }
+        }
+    };
+}
+
+rb_int_impl!(i8);
+rb_int_impl!(i16);
+rb_int_impl!(i32);
+rb_int_impl!(i64);
+rb_int_impl!(i128);
+
+rb_real_impl!(f32);
+rb_real_impl!(f64);
+
+rb_complex_impl!(f32);
+rb_complex_impl!(f64);
+
+impl RutherfordBoeingScalar for () {}
+
+impl internal::SupportedRutherfordBoeingScalar for () {
+    const VALUE_TYPE: char = 'p';
+    const COMPONENTS: usize = 0;
+    #[inline]
+    fn parse_components(_fields: &[&str]) -> Result<Self, RutherfordBoeingError> {
+        Ok(())
+    }
+    #[inline]
+    fn for_each_component<F: FnMut(Component)>(&self, _f: F) {}
+    #[inline]
+    fn negative(self) -> Self {}
+    #[inline]
+    fn conjugate(self) -> Self {}
+}
+
+/// The symmetry structure of a matrix, as given by the second character of the matrix type code.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Structure {
+    /// Symmetric (`s`).
+    Symmetric,
+    /// Unsymmetric (`u`) or rectangular (`r`).
+    General,
+    /// Hermitian (`h`).
+    Hermitian,
+    /// Skew-symmetric (`z`).
+    Skew,
+}
+
+/// Parses a Rutherford-Boeing (or Harwell-Boeing) file at the given path as a `CscMatrix`.
+///
+/// Both the Rutherford-Boeing format and its predecessor, the Harwell-Boeing format, are supported, as long as
+/// the file holds an assembled matrix. Symmetric, skew-symmetric and Hermitian matrices, of which only the
+/// lower triangle is stored in the file, are expanded into the full matrix. Right-hand sides stored in
+/// Harwell-Boeing files are ignored.
+///
+/// Like for the matrix market format, you must use a compatible scalar type when importing: `real`
+/// matrices must be imported as `f32` or `f64`, `integer` matrices as an integer type, `complex`
+/// matrices as `Complex<f32>` or `Complex<f64>`, and `pattern` matrices as `()`. Otherwise, a
+/// [TypeMismatch](RutherfordBoeingErrorKind::TypeMismatch) error is returned.
+///
+/// Errors
+/// --------
+///
+/// See [RutherfordBoeingErrorKind] for a list of possible error conditions.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// use nalgebra_sparse::io::load_csc_from_rutherford_boeing_file;
+/// let matrix = load_csc_from_rutherford_boeing_file::<f64, _>("path/to/matrix.rb").unwrap();
+/// ```
+pub fn load_csc_from_rutherford_boeing_file<T, P>(
+    path: P,
+) -> Result<CscMatrix<T>, RutherfordBoeingError>
+where
+    T: RutherfordBoeingScalar,
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    load_csc_from_rutherford_boeing_reader(BufReader::new(file))
+}
+
+/// Parses a Rutherford-Boeing (or Harwell-Boeing) file described by the given string as a `CscMatrix`.
+///
+/// See [load_csc_from_rutherford_boeing_file] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [RutherfordBoeingErrorKind] for a list of possible error conditions.
+///
+/// Examples
+/// --------
+/// ```
+/// use nalgebra_sparse::io::load_csc_from_rutherford_boeing_str;
+/// use nalgebra::DMatrix;
+/// let str = "\
+/// Symmetric 2x2 example                                                   example
+///              4             1             1             2
+/// rsa                        2             2             2             0
+/// (3I3)           (2I3)           (2E26.16)
+///   1  3  3
+///   1  2
+///     4.0000000000000000E+00    1.0000000000000000E+00
+/// ";
+/// let matrix = load_csc_from_rutherford_boeing_str::<f64>(str).unwrap();
+/// assert_eq!(DMatrix::from(&matrix), DMatrix::from_row_slice(2, 2, &[4.0, 1.0, 1.0, 0.0]));
+/// ```
+pub fn load_csc_from_rutherford_boeing_str<T>(
+    data: &str,
+) -> Result<CscMatrix<T>, RutherfordBoeingError>
+where
+    T: RutherfordBoeingScalar,
+{
+    load_csc_from_rutherford_boeing_reader(data.as_bytes())
+}
+
+/// Parses a Rutherford-Boeing (or Harwell-Boeing) file from a buffered reader as a `CscMatrix`.
+///
+/// See [load_csc_from_rutherford_boeing_file] for more information.
+///
+/// Errors
+/// --------
+///
+/// See [RutherfordBoeingErrorKind] for a list of possible error conditions.
+pub fn load_csc_from_rutherford_boeing_reader<T, R>(
+    reader: R,
+) -> Result<CscMatrix<T>, RutherfordBoeingError>
+where
+    T: RutherfordBoeingScalar,
+    R: BufRead,
+{
+    let mut lines = LineReader {
+        reader,
+        buffer: String::new(),
+        line_number: 0,
+    };
+
+    // The first line holds the title and the key, neither of which we need
+    lines.next_line()?;
+
+    // The second line holds the number of lines of each section. The Harwell-Boeing format has an additional
+    // fifth entry, the number of lines of right-hand sides.
+    let (line_number, line) = lines.next_line()?;
+    let card_counts = line
+        .split_whitespace()
+        .map(parse_integer)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.with_line_number(line_number))?;
+    let rhs_lines = match card_counts.len() {
+        4 => 0,
+        5 => card_counts[4],
+        _ => {
+            return Err(invalid_header(
+                "The second line must hold 4 (or 5 for Harwell-Boeing) line counts.".to_string(),
+            )
+            .with_line_number(line_number))
+        }
+    };
+
+    let (line_number, line) = lines.next_line()?;
+    let (structure, nrows, ncols, nnz) =
+        parse_type_line::<T>(line).map_err(|err| err.with_line_number(line_number))?;
+
+    let (line_number, line) = lines.next_line()?;
+    let (ptr_format, ind_format, val_format) =
+        parse_format_line::<T>(line).map_err(|err| err.with_line_number(line_number))?;
+
+    // Harwell-Boeing files with right-hand sides have a fifth header line describing them
+    if rhs_lines > 0 {
+        lines.next_line()?;
+    }
+
+    let offsets_len = ncols
+        .checked_add(1)
+        .ok_or_else(|| invalid_header(String::from("The number of columns is too large.")))?;
+    let offsets = read_fields(&mut lines, ptr_format, offsets_len, 1, |fields| {
+        parse_index(fields[0])
+    })?;
+    let indices = read_fields(&mut lines, ind_format, nnz, 1, |fields| {
+        parse_index(fields[0])
+    })?;
+    let values = match val_format {
+        Some(format) => read_fields(&mut lines, format, nnz, T::COMPONENTS, T::parse_components)?,
+        None => (0..nnz)
+            .map(|_| T::parse_components(&[]))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let csc = CscMatrix::try_from_unsorted_csc_data(nrows, ncols, offsets, indices, values)?;
+    match structure {
+        Structure::General => Ok(csc),
+        _ => expand_lower_triangle(csc, structure),
+    }
+}
+
+/// Reads the lines of a file one by one, reusing a single line buffer.
+struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    /// Returns the next line without its line terminator, along with its line number.
+    ///
+    /// Fails at the end of the input.
+    fn next_line(&mut self) -> Result<(usize, &str), RutherfordBoeingError> {
+        self.buffer.clear();
+        let bytes = self.reader.read_line(&mut self.buffer).map_err(|err| {
+            RutherfordBoeingError::from(err).with_line_number(self.line_number + 1)
+        })?;
+        if bytes == 0 {
+            return Err(RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::EntryMismatch,
+                String::from("The file ended before all data announced in the header was read."),
+            ));
+        }
+        self.line_number += 1;
+        Ok((
+            self.line_number,
+            self.buffer.trim_end_matches(&['\n', '\r'][..]),
+        ))
+    }
+}
+
+fn invalid_header(message: String) -> RutherfordBoeingError {
+    RutherfordBoeingError::from_kind_and_message(RutherfordBoeingErrorKind::InvalidHeader, message)
+}
+
+/// Parses the third header line, holding the matrix type and the dimensions of the matrix.
+fn parse_type_line<T: RutherfordBoeingScalar>(
+    line: &str,
+) -> Result<(Structure, usize, usize, usize), RutherfordBoeingError> {
+    let mxtype = line.get(..3).unwrap_or(line).to_ascii_lowercase();
+    let mut type_code = mxtype.chars();
+    let (value_type, structure, assembled) =
+        match (type_code.next(), type_code.next(), type_code.next()) {
+            (Some(v), Some(s), Some(a)) => (v, s, a),
+            _ => return Err(invalid_header(format!("Invalid matrix type {}.", mxtype))),
+        };
+
+    let structure = match structure {
+        's' => Structure::Symmetric,
+        'u' | 'r' => Structure::General,
+        'h' => Structure::Hermitian,
+        'z' => Structure::Skew,
+        _ => return Err(invalid_header(format!("Invalid matrix type {}.", mxtype))),
+    };
+    if assembled != 'a' {
+        return Err(invalid_header(format!(
+            "Matrix type {} is not supported, only assembled matrices can be read.",
+            mxtype
+        )));
+    }
+    if !matches!(value_type, 'r' | 'c' | 'i' | 'p') {
+        return Err(invalid_header(format!(
+            "Matrix type {} is not supported.",
+            mxtype
+        )));
+    }
+    if (structure == Structure::Hermitian && value_type != 'c')
+        || (structure == Structure::Skew && value_type == 'p')
+    {
+        return Err(invalid_header(format!(
+            "Matrix type {} is not a valid matrix type.",
+            mxtype
+        )));
+    }
+    if value_type != T::VALUE_TYPE {
+        return Err(RutherfordBoeingError::from_kind_and_message(
+            RutherfordBoeingErrorKind::TypeMismatch,
+            format!(
+                "The file holds a matrix of type {}, which can not be read into the requested scalar type.",
+                mxtype
+            ),
+        ));
+    }
+
+    let dimensions = line
+        .get(3..)
+        .unwrap_or("")
+        .split_whitespace()
+        .take(3)
+        .map(parse_count)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (nrows, ncols, nnz) = match dimensions[..] {
+        [nrows, ncols, nnz] => (nrows, ncols, nnz),
+        _ => {
+            return Err(invalid_header(
+                "The third line must hold the number of rows, columns and entries.".to_string(),
+            ))
+        }
+    };
+    if structure != Structure::General && nrows != ncols {
+        return Err(invalid_header(format!(
+            "(Skew-)Symmetric or hermitian matrix should be square matrix, but it has dimension {} and {}",
+            nrows, ncols
+        )));
+    }
+
+    Ok((structure, nrows, ncols, nnz))
+}
+
+/// Parses the fourth header line, holding the Fortran formats of the pointers, indices and values.
+///
+/// The format of the values is `None` for pattern matrices.
+#[allow(clippy::type_complexity)]
+fn parse_format_line<T: RutherfordBoeingScalar>(
+    line: &str,
+) -> Result<(FortranFormat, FortranFormat, Option<FortranFormat>), RutherfordBoeingError> {
+    if !line.is_ascii() {
+        return Err(invalid_header(
+            "The format line must only contain ASCII characters.".to_string(),
+        ));
+    }
+    let column = |start: usize, end: usize| line.get(start..end.min(line.len())).unwrap_or("");
+    let ptr_format = FortranFormat::parse(column(0, 16))?;
+    let ind_format = FortranFormat::parse(column(16, 32))?;
+    let val_format = if T::COMPONENTS == 0 {
+        None
+    } else {
+        Some(FortranFormat::parse(column(32, 52))?)
+    };
+    Ok((ptr_format, ind_format, val_format))
+}
+
+/// The layout of the fields of a Fortran format descriptor such as `(10I8)` or `(1P,4E20.12)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct FortranFormat {
+    /// The number of fields on each line.
+    per_line: usize,
+    /// The width of each field.
+    width: usize,
+}
+
+impl FortranFormat {
+    fn parse(descriptor: &str) -> Result<Self, RutherfordBoeingError> {
+        let invalid_format = || {
+            RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::ParsingError,
+                format!("Can't parse {} as a Fortran format.", descriptor.trim()),
+            )
+        };
+
+        let descriptor: String = descriptor
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        let mut inner = descriptor
+            .strip_prefix('(')
+            .and_then(|d| d.strip_suffix(')'))
+            .ok_or_else(invalid_format)?;
+
+        // Skip an optional scale factor, such as `1P` or `1P,`
+        if let Some(position) = inner.find('P') {
+            inner = inner[position + 1..].trim_start_matches(',');
+        }
+
+        let letter = inner
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(invalid_format)?;
+        let per_line = match &inner[..letter] {
+            "" => 1,
+            repeat => repeat.parse::<usize>().map_err(|_| invalid_format())?,
+        };
+        if !matches!(&inner[letter..letter + 1], "I" | "E" | "D" | "F" | "G") {
+            return Err(invalid_format());
+        }
+        let rest = &inner[letter + 1..];
+        let width_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let width = rest[..width_end]
+            .parse::<usize>()
+            .map_err(|_| invalid_format())?;
+        if per_line == 0 || width == 0 {
+            return Err(invalid_format());
+        }
+        Ok(Self { per_line, width })
+    }
+
+    fn descriptor(&self, letter: char, precision: Option<usize>) -> String {
+        match precision {
+            Some(precision) => format!("({}{}{}.{})", self.per_line, letter, self.width, precision),
+            None => format!("({}{}{})", self.per_line, letter, self.width),
+        }
+    }
+}
+
+/// The largest number of values that are preallocated before they are read, as the counts in the
+/// header are not trusted.
+const CHUNK_LEN: usize = 4096;
+
+/// Reads `count` values made up of `components` fixed-width fields each.
+fn read_fields<R, V, F>(
+    lines: &mut LineReader<R>,
+    format: FortranFormat,
+    count: usize,
+    components: usize,
+    mut parse: F,
+) -> Result<Vec<V>, RutherfordBoeingError>
+where
+    R: BufRead,
+    F: FnMut(&[&str]) -> Result<V, RutherfordBoeingError>,
+{
+    let mut values = Vec::with_capacity(count.min(CHUNK_LEN));
+    // The fields of a single value may be split across lines
+    let mut fields: Vec<String> = Vec::with_capacity(components);
+    while values.len() < count {
+        let (line_number, line) = lines.next_line()?;
+        if !line.is_ascii() {
+            return Err(RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::ParsingError,
+                String::from("The data must only contain ASCII characters."),
+            )
+            .with_line_number(line_number));
+        }
+        let line = line.trim_end();
+
+        for k in 0..format.per_line {
+            let start = k * format.width;
+            if start >= line.len() || values.len() == count {
+                break;
+            }
+            let field = line[start..(start + format.width).min(line.len())].trim();
+            fields.push(field.to_string());
+            if fields.len() == components {
+                let field_refs: Vec<&str> = fields.iter().map(String::as_str).collect();
+                let value = parse(&field_refs).map_err(|err| err.with_line_number(line_number))?;
+                values.push(value);
+                fields.clear();
+            }
+        }
+    }
+    Ok(values)
+}
+
+fn parse_integer(field: &str) -> Result<i128, RutherfordBoeingError> {
+    field.parse::<i128>().map_err(|err| {
+        RutherfordBoeingError::from_kind_and_message(
+            RutherfordBoeingErrorKind::ParsingError,
+            format!("Can't parse {} as an integer.\n Error: {}", field, err),
+        )
+    })
+}
+
+/// Parses a non-negative count, such as a dimension or the number of entries.
+fn parse_count(field: &str) -> Result<usize, RutherfordBoeingError> {
+    let count = parse_integer(field)?;
+    usize::try_from(count).map_err(|_| {
+        RutherfordBoeingError::from_kind_and_message(
+            RutherfordBoeingErrorKind::ParsingError,
+            format!("Counts must be non-negative, but {} was provided.", field),
+        )
+    })
+}
+
+/// Parses a one-based index or offset, returning it zero-based.
+fn parse_index(field: &str) -> Result<usize, RutherfordBoeingError> {
+    let index = parse_integer(field)?;
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .ok_or_else(|| {
+            RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::ParsingError,
+                format!(
+                    "Indices and pointers must be positive and one-based, but {} was provided.",
+                    field
+                ),
+            )
+        })
+}
+
+/// Parses a real number in any of the forms accepted by Fortran, such as `1.5E+02`, `1.5D+02` or `1.5+002`.
+fn parse_real(field: &str) -> Result<f64, RutherfordBoeingError> {
+    let normalized = field.replace(&['D', 'd'][..], "E");
+    if let Ok(value) = normalized.parse::<f64>() {
+        return Ok(value);
+    }
+    // Fortran omits the exponent letter for exponents with three digits, as in `1.5-100`
+    let exponent_sign = normalized
+        .char_indices()
+        .skip(1)
+        .filter(|&(_, c)| c == '+' || c == '-')
+        .map(|(position, _)| position)
+        .last();
+    if let Some(position) = exponent_sign {
+        let with_letter = format!("{}E{}", &normalized[..position], &normalized[position..]);
+        if let Ok(value) = with_letter.parse::<f64>() {
+            return Ok(value);
+        }
+    }
+    Err(RutherfordBoeingError::from_kind_and_message(
+        RutherfordBoeingErrorKind::ParsingError,
+        format!("Can't parse {} as a real number.", field),
+    ))
+}
+
+/// Expands a matrix of which only the lower triangle is stored into the full matrix.
+fn expand_lower_triangle<T: RutherfordBoeingScalar>(
+    lower: CscMatrix<T>,
+    structure: Structure,
+) -> Result<CscMatrix<T>, RutherfordBoeingError> {
+    let n = lower.ncols();
+    let mut counts = vec![0; n];
+    for (i, j, _) in lower.triplet_iter() {
+        if i < j {
+            return Err(RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::NotLowerTriangle,
+                format!(
+                    "Entry: row {} col {} should be put into lower triangle",
+                    i + 1,
+                    j + 1
+                ),
+            ));
+        }
+        if i == j && structure == Structure::Skew {
+            return Err(RutherfordBoeingError::from_kind_and_message(
+                RutherfordBoeingErrorKind::DiagonalError,
+                format!(
+                    "There is a diagonal element in skew matrix, in row(and column) {}",
+                    i + 1
+                ),
+            ));
+        }
+        counts[j] += 1;
+        if i != j {
+            counts[i] += 1;
+        }
+    }
+
+    let mut offsets = Vec::with_capacity(n + 1);
+    offsets.push(0);
+    for count in counts {
+        offsets.push(offsets.last().unwrap() + count);
+    }
+    let nnz = *offsets.last().unwrap();
+
+    let mut next = offsets[..n].to_vec();
+    let mut indices = vec![0; nnz];
+    let mut values: Vec<Option<T>> = vec![None; nnz];
+    for (i, j, v) in lower.triplet_iter() {
+        indices[next[j]] = i;
+        values[next[j]] = Some(v.clone());
+        next[j] += 1;
+        if i != j {
+            let mirrored = match structure {
+                Structure::Skew => v.clone().negative(),
+                Structure::Hermitian => v.clone().conjugate(),
+                _ => v.clone(),
+            };
+            indices[next[i]] = j;
+            values[next[i]] = Some(mirrored);
+            next[i] += 1;
+        }
+    }
+    let values = values.into_iter().map(Option::unwrap).collect();
+
+    Ok(CscMatrix::try_from_unsorted_csc_data(
+        n, n, offsets, indices, values,
+    )?)
+}
+
+/// Save a sparse matrix as a Rutherford-Boeing format string.
+///
+/// See [save_to_rutherford_boeing] for more information.
+///
+/// Examples
+/// --------
+/// ```
+/// use nalgebra_sparse::io::{load_csc_from_rutherford_boeing_str, save_to_rutherford_boeing_str};
+/// use nalgebra_sparse::CscMatrix;
+/// use nalgebra::matrix;
+/// let matrix = CscMatrix::from(&matrix![1, 0, 2; 0, 3, 0]);
+/// let generated_rb_str = save_to_rutherford_boeing_str(&matrix);
+/// assert!(generated_rb_str.lines().nth(2).unwrap().starts_with("ira"));
+/// assert_eq!(load_csc_from_rutherford_boeing_str::<i32>(&generated_rb_str).unwrap(), matrix);
+/// ```
+pub fn save_to_rutherford_boeing_str<T>(sparse_matrix: &CscMatrix<T>) -> String
+where
+    T: RutherfordBoeingScalar,
+{
+    let mut bytes = Vec::<u8>::new();
+    // Writing to a Vec<u8> can not fail
+    save_to_rutherford_boeing(&mut bytes, sparse_matrix).unwrap();
+
+    String::from_utf8(bytes)
+        .expect("Unexpected non UTF-8 data was generated when export to Rutherford-Boeing string")
+}
+
+/// Save a sparse matrix to a Rutherford-Boeing format file.
+///
+/// See [save_to_rutherford_boeing] for more information.
+///
+/// Errors
+/// --------
+///
+/// Returns an error if the file can not be written.
+///
+/// Examples
+/// --------
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use nalgebra_sparse::io::save_to_rutherford_boeing_file;
+/// use nalgebra_sparse::CscMatrix;
+/// let matrix = CscMatrix::<f64>::identity(3);
+/// save_to_rutherford_boeing_file(&matrix, "path/to/matrix.rb")?;
+/// # Ok(()) }
+/// ```
+pub fn save_to_rutherford_boeing_file<T, P>(
+    sparse_matrix: &CscMatrix<T>,
+    path: P,
+) -> Result<(), std::io::Error>
+where
+    T: RutherfordBoeingScalar,
+    P: AsRef<Path>,
+{
+    let file = File::create(path)?;
+    let mut file = BufWriter::new(file);
+    save_to_rutherford_boeing(&mut file, sparse_matrix)?;
+    file.flush()
+}
+
+/// Save a sparse matrix to an [std::io::Write] instance in the Rutherford-Boeing format.
+///
+/// The matrix is written as an assembled, unsymmetric (or rectangular) matrix. Real values are written with 17
+/// significant digits, so that they are read back exactly.
+///
+/// This is the most general save functionality. See [save_to_rutherford_boeing_file] and
+/// [save_to_rutherford_boeing_str] for higher-level functionality.
+pub fn save_to_rutherford_boeing<T, W>(
+    mut w: W,
+    sparse_matrix: &CscMatrix<T>,
+) -> Result<(), std::io::Error>
+where
+    T: RutherfordBoeingScalar,
+    W: Write,
+{
+    let (offsets, indices, values) = sparse_matrix.csc_data();
+    let nrows = sparse_matrix.nrows();
+    let ncols = sparse_matrix.ncols();
+    let nnz = sparse_matrix.nnz();
+
+    let ptr_format = integer_format(decimal_width(nnz as i128 + 1));
+    let ind_format = integer_format(decimal_width(nrows as i128));
+
+    let val_format = match T::VALUE_TYPE {
+        _ if T::COMPONENTS == 0 => None,
+        'i' => {
+            let mut digits = 1;
+            for v in values {
+                v.for_each_component(|component| {
+                    if let Component::Integer(i) = component {
+                        digits = digits.max(decimal_width(i));
+                    }
+                });
+            }
+            Some(integer_format(digits))
+        }
+        _ => Some(REAL_FORMAT),
+    };
+    let val_descriptor = match val_format {
+        Some(format) if T::VALUE_TYPE == 'i' => format.descriptor('I', None),
+        Some(format) => format.descriptor('E', Some(16)),
+        None => String::new(),
+    };
+
+    let line_count = |format: Option<FortranFormat>, count: usize| match format {
+        Some(format) => count.div_ceil(format.per_line),
+        None => 0,
+    };
+    let ptr_lines = line_count(Some(ptr_format), ncols + 1);
+    let ind_lines = line_count(Some(ind_format), nnz);
+    let val_lines = line_count(val_format, nnz * T::COMPONENTS);
+
+    // write header
+    let mxtype = format!(
+        "{}{}a",
+        T::VALUE_TYPE,
+        if nrows == ncols { 'u' } else { 'r' }
+    );
+    writeln!(
+        w,
+        "{:<72}{:<8}",
+        "Rutherford-Boeing file generated by nalgebra-sparse", "nalgebra"
+    )?;
+    writeln!(
+        w,
+        "{:>14}{:>14}{:>14}{:>14}",
+        ptr_lines + ind_lines + val_lines,
+        ptr_lines,
+        ind_lines,
+        val_lines
+    )?;
+    writeln!(
+        w,
+        "{:<3}{:11}{:>14}{:>14}{:>14}{:>14}",
+        mxtype, "", nrows, ncols, nnz, 0
+    )?;
+    let header = format!(
+        "{:<16}{:<16}{:<20}",
+        ptr_format.descriptor('I', None),
+        ind_format.descriptor('I', None),
+        val_descriptor
+    );
+    writeln!(w, "{}", header.trim_end())?;
+
+    // write data
+    let mut fields = FieldWriter::new(&mut w, ptr_format);
+    for p in offsets {
+        fields.write_field(&(p + 1).to_string())?;
+    }
+    fields.finish()?;
+
+    let mut fields = FieldWriter::new(&mut w, ind_format);
+    for i in indices {
+        fields.write_field(&(i + 1).to_string())?;
+    }
+    fields.finish()?;
+
+    if let Some(format) = val_format {
+        let mut fields = FieldWriter::new(&mut w, format);
+        for v in values {
+            let mut result = Ok(());
+            v.for_each_component(|component| {
+                if result.is_ok() {
+                    result = match component {
+                        Component::Integer(i) => fields.write_field(&i.to_string()),
+                        Component::Real(x) => fields.write_field(&format_real(x)),
+                    };
+                }
+            });
+            result?;
+        }
+        fields.finish()?;
+    }
+    Ok(())
+}
+
+/// The format used for real values, which fits 17 significant digits and a three-digit exponent.
+const REAL_FORMAT: FortranFormat = FortranFormat {
+    per_line: 3,
+    width: 26,
+};
+
+/// The format for integers with at most `digits` characters, including the sign.
+///
+/// A blank is left in front of each field, and as many fields as possible are put on 80 character lines.
+fn integer_format(digits: usize) -> FortranFormat {
+    let width = digits + 1;
+    FortranFormat {
+        per_line: (80 / width).max(1),
+        width,
+    }
+}
+
+/// The number of characters in the decimal representation of `i`, including the sign.
+fn decimal_width(i: i128) -> usize {
+    i.to_string().len()
+}
+
+/// Formats a real number in the Fortran `E` format, with 17 significant digits.
+fn format_real(x: f64) -> String {
+    if !x.is_finite() {
+        return x.to_string();
+    }
+    let formatted = format!("{:.16E}", x);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('E').unwrap());
+    let exponent: i32 = exponent[1..].parse().unwrap();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{}E{}{:02}", mantissa, sign, exponent.abs())
+}
+
+/// Writes fields right-aligned in a given format, starting a new line whenever a line is full.
+struct FieldWriter<W> {
+    w: W,
+    format: FortranFormat,
+    on_line: usize,
+}
+
+impl<W: Write> FieldWriter<W> {
+    fn new(w: W, format: FortranFormat) -> Self {
+        Self {
+            w,
+            format,
+            on_line: 0,
+        }
+    }
+
+    fn write_field(&mut self, field: &str) -> Result<(), std::io::Error> {
+        write!(self.w, "{:>width$}", field, width = self.format.width)?;
+        self.on_line += 1;
+        if self.on_line == self.format.per_line {
+            writeln!(self.w)?;
+            self.on_line = 0;
+        }
+        Ok(())
+    }
+
+    /// Terminates the last, partially filled line.
+    fn finish(mut self) -> Result<(), std::io::Error> {
+        if self.on_line > 0 {
+            writeln!(self.w)?;
+        }
+        Ok(())
+    }
+}
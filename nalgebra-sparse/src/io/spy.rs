@@ -0,0 +1,416 @@
+//! Spy plots and statistics for sparsity patterns.
+
+use crate::csr::CsrMatrix;
+use crate::pattern::graph::bandwidth;
+use crate::pattern::SparsityPattern;
+use nalgebra::ComplexField;
+use num_traits::Zero;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::Write;
+
+/// The characters used to draw a text spy plot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpyCharset {
+    /// One character per cell, `*` for cells with at least one entry and `.` for empty cells.
+    Ascii,
+    /// Unicode Braille patterns, which pack `2 x 4` cells into every character and therefore
+    /// give eight times the resolution of [`SpyCharset::Ascii`].
+    Unicode,
+}
+
+/// A boolean image of a sparsity pattern, where every pixel covers a block of the matrix.
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Raster {
+    /// Downsamples the pattern into at most `max_width x max_height` pixels.
+    ///
+    /// Every pixel covers the same number of rows and the same number of columns, and is set if
+    /// any entry of the pattern lies in the block it covers.
+    fn new(pattern: &SparsityPattern, max_width: usize, max_height: usize) -> Self {
+        assert!(
+            max_width > 0 && max_height > 0,
+            "The maximum width and height must be positive."
+        );
+        let (nrows, ncols) = (pattern.major_dim(), pattern.minor_dim());
+        let rows_per_pixel = nrows.div_ceil(max_height).max(1);
+        let cols_per_pixel = ncols.div_ceil(max_width).max(1);
+        let (width, height) = (
+            ncols.div_ceil(cols_per_pixel),
+            nrows.div_ceil(rows_per_pixel),
+        );
+
+        let mut pixels = vec![false; width * height];
+        for (i, j) in pattern.entries() {
+            pixels[(i / rows_per_pixel) * width + j / cols_per_pixel] = true;
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn get(&self, row: usize, col: usize) -> bool {
+        row < self.height && col < self.width && self.pixels[row * self.width + col]
+    }
+}
+
+/// Draws a spy plot of the sparsity pattern as text to an [std::io::Write] instance.
+///
+/// The rows of the plot correspond to the major dimension of the pattern, i.e. to the rows of a
+/// CSR matrix. To plot the pattern of a CSC matrix in its natural orientation, plot the
+/// transpose of its pattern. Every line of the plot ends with a newline.
+///
+/// Large patterns are downsampled so that the plot is at most `max_width` characters wide and
+/// `max_height` lines high, in which case a cell of the plot is marked if any entry of the
+/// pattern falls into the block of the matrix that it covers.
+///
+/// # Panics
+///
+/// Panics if `max_width` or `max_height` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use nalgebra_sparse::io::{save_spy_plot_str, SpyCharset};
+/// use nalgebra_sparse::CsrMatrix;
+///
+/// let matrix = CsrMatrix::<f64>::identity(3);
+/// let plot = save_spy_plot_str(matrix.pattern(), SpyCharset::Ascii, 80, 40);
+/// assert_eq!(plot, "*..\n.*.\n..*\n");
+/// ```
+pub fn save_spy_plot<W: Write>(
+    mut w: W,
+    pattern: &SparsityPattern,
+    charset: SpyCharset,
+    max_width: usize,
+    max_height: usize,
+) -> Result<(), std::io::Error> {
+    match charset {
+        SpyCharset::Ascii => {
+            let raster = Raster::new(pattern, max_width, max_height);
+            for row in 0..raster.height {
+                let line: String = (0..raster.width)
+                    .map(|col| if raster.get(row, col) { '*' } else { '.' })
+                    .collect();
+                writeln!(w, "{}", line)?;
+            }
+        }
+        SpyCharset::Unicode => {
+            // The bit of each dot in a Braille pattern, indexed by the row and column of the dot
+            const DOT_BITS: [[u32; 2]; 4] =
+                [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+            let raster = Raster::new(pattern, 2 * max_width, 4 * max_height);
+            for row in 0..raster.height.div_ceil(4) {
+                let line: String = (0..raster.width.div_ceil(2))
+                    .map(|col| {
+                        let mut code = 0x2800;
+                        for (r, bits) in DOT_BITS.iter().enumerate() {
+                            for (c, bit) in bits.iter().enumerate() {
+                                if raster.get(4 * row + r, 2 * col + c) {
+                                    code |= bit;
+                                }
+                            }
+                        }
+                        std::char::from_u32(code).expect("Braille patterns are valid characters")
+                    })
+                    .collect();
+                writeln!(w, "{}", line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Draws a spy plot of the sparsity pattern as text to a `String`.
+///
+/// See [save_spy_plot] for more information.
+///
+/// # Panics
+///
+/// Panics if `max_width` or `max_height` is zero.
+#[must_use]
+pub fn save_spy_plot_str(
+    pattern: &SparsityPattern,
+    charset: SpyCharset,
+    max_width: usize,
+    max_height: usize,
+) -> String {
+    let mut bytes = Vec::new();
+    save_spy_plot(&mut bytes, pattern, charset, max_width, max_height)
+        .expect("Writing to a vector cannot fail");
+    String::from_utf8(bytes).expect("Spy plots are valid UTF-8")
+}
+
+/// Writes a spy plot of the sparsity pattern as a binary PBM (portable bitmap) image.
+///
+/// Pixels that cover at least one entry are black, and all other pixels are white. The image
+/// has one pixel per entry of the matrix, unless it would be larger than
+/// `max_width x max_height`, in which case it is downsampled as in [save_spy_plot]. The rows of
+/// the image correspond to the major dimension of the pattern.
+///
+/// # Panics
+///
+/// Panics if `max_width` or `max_height` is zero.
+pub fn save_spy_pbm<W: Write>(
+    mut w: W,
+    pattern: &SparsityPattern,
+    max_width: usize,
+    max_height: usize,
+) -> Result<(), std::io::Error> {
+    let raster = Raster::new(pattern, max_width, max_height);
+    write!(w, "P4\n{} {}\n", raster.width, raster.height)?;
+    let mut line = vec![0u8; raster.width.div_ceil(8)];
+    for row in 0..raster.height {
+        line.iter_mut().for_each(|byte| *byte = 0);
+        for col in 0..raster.width {
+            if raster.get(row, col) {
+                line[col / 8] |= 0x80 >> (col % 8);
+            }
+        }
+        w.write_all(&line)?;
+    }
+    Ok(())
+}
+
+/// Writes a spy plot of the sparsity pattern as an SVG image.
+///
+/// Every entry of the pattern is drawn as a black unit square on a white background, with
+/// consecutive entries in a row merged into a single rectangle. The image is never
+/// downsampled, and the rows of the image correspond to the major dimension of the pattern.
+pub fn save_spy_svg<W: Write>(mut w: W, pattern: &SparsityPattern) -> Result<(), std::io::Error> {
+    let (nrows, ncols) = (pattern.major_dim(), pattern.minor_dim());
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+        ncols, nrows
+    )?;
+    writeln!(
+        w,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        ncols, nrows
+    )?;
+    for i in 0..nrows {
+        let lane = pattern.lane(i);
+        let mut start = 0;
+        while start < lane.len() {
+            // Find the run of consecutive columns starting at `start`
+            let mut end = start + 1;
+            while end < lane.len() && lane[end] == lane[end - 1] + 1 {
+                end += 1;
+            }
+            writeln!(
+                w,
+                r#"<rect x="{}" y="{}" width="{}" height="1"/>"#,
+                lane[start],
+                i,
+                end - start
+            )?;
+            start = end;
+        }
+    }
+    writeln!(w, "</svg>")
+}
+
+/// Summary statistics of a sparsity pattern.
+///
+/// The statistics are computed with respect to the major dimension of the pattern, which
+/// corresponds to the rows of a CSR matrix. The [Display](std::fmt::Display) implementation
+/// gives a short human-readable report.
+///
+/// # Examples
+///
+/// ```
+/// use nalgebra_sparse::io::PatternStats;
+/// use nalgebra_sparse::CsrMatrix;
+///
+/// let matrix = CsrMatrix::<f64>::identity(4);
+/// let stats = PatternStats::from_csr(&matrix);
+/// assert_eq!(stats.max_row_nnz(), 1);
+/// assert_eq!(stats.bandwidth(), 0);
+/// assert_eq!(stats.diagonal_dominance(), Some(1.0));
+/// assert_eq!(
+///     stats.to_string().lines().next(),
+///     Some("4 x 4 pattern with 4 non-zeros")
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternStats {
+    nrows: usize,
+    ncols: usize,
+    nnz: usize,
+    min_row_nnz: usize,
+    max_row_nnz: usize,
+    empty_rows: usize,
+    bandwidth: usize,
+    symmetry_ratio: f64,
+    diagonal_dominance: Option<f64>,
+}
+
+impl PatternStats {
+    /// Computes the statistics of a sparsity pattern.
+    ///
+    /// Since a pattern has no values, the [diagonal dominance](Self::diagonal_dominance) is not
+    /// available.
+    #[must_use]
+    pub fn from_pattern(pattern: &SparsityPattern) -> Self {
+        let (nrows, ncols) = (pattern.major_dim(), pattern.minor_dim());
+        let row_nnz = (0..nrows).map(|i| pattern.lane(i).len());
+
+        let mut off_diagonal = 0;
+        let mut mirrored = 0;
+        for (i, j) in pattern.entries().filter(|(i, j)| i != j) {
+            off_diagonal += 1;
+            if j < nrows && pattern.lane(j).binary_search(&i).is_ok() {
+                mirrored += 1;
+            }
+        }
+
+        Self {
+            nrows,
+            ncols,
+            nnz: pattern.nnz(),
+            min_row_nnz: row_nnz.clone().min().unwrap_or(0),
+            max_row_nnz: row_nnz.clone().max().unwrap_or(0),
+            empty_rows: row_nnz.filter(|&nnz| nnz == 0).count(),
+            bandwidth: bandwidth(pattern),
+            symmetry_ratio: ratio(mirrored, off_diagonal),
+            diagonal_dominance: None,
+        }
+    }
+
+    /// Computes the statistics of a CSR matrix, including its diagonal dominance.
+    #[must_use]
+    pub fn from_csr<T: ComplexField>(matrix: &CsrMatrix<T>) -> Self {
+        let dominant_rows = matrix
+            .row_iter()
+            .enumerate()
+            .filter(|(i, row)| {
+                let mut diagonal = T::RealField::zero();
+                let mut off_diagonal = T::RealField::zero();
+                for (&j, value) in row.col_indices().iter().zip(row.values()) {
+                    if j == *i {
+                        diagonal += value.clone().modulus();
+                    } else {
+                        off_diagonal += value.clone().modulus();
+                    }
+                }
+                diagonal >= off_diagonal
+            })
+            .count();
+
+        Self {
+            diagonal_dominance: Some(ratio(dominant_rows, matrix.nrows())),
+            ..Self::from_pattern(matrix.pattern())
+        }
+    }
+
+    /// The number of rows (the major dimension of the pattern).
+    #[must_use]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// The number of columns (the minor dimension of the pattern).
+    #[must_use]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The number of explicitly stored entries.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.nnz
+    }
+
+    /// The smallest number of entries in a row, or zero if there are no rows.
+    #[must_use]
+    pub fn min_row_nnz(&self) -> usize {
+        self.min_row_nnz
+    }
+
+    /// The largest number of entries in a row, or zero if there are no rows.
+    #[must_use]
+    pub fn max_row_nnz(&self) -> usize {
+        self.max_row_nnz
+    }
+
+    /// The average number of entries per row, or zero if there are no rows.
+    #[must_use]
+    pub fn mean_row_nnz(&self) -> f64 {
+        if self.nrows == 0 {
+            0.0
+        } else {
+            self.nnz as f64 / self.nrows as f64
+        }
+    }
+
+    /// The number of rows without any entries.
+    #[must_use]
+    pub fn empty_rows(&self) -> usize {
+        self.empty_rows
+    }
+
+    /// The bandwidth, i.e. the largest distance `|i - j|` of an entry `(i, j)` from the diagonal.
+    #[must_use]
+    pub fn bandwidth(&self) -> usize {
+        self.bandwidth
+    }
+
+    /// The fraction of off-diagonal entries `(i, j)` for which `(j, i)` is also an entry.
+    ///
+    /// This is one for structurally symmetric patterns, including patterns without off-diagonal
+    /// entries.
+    #[must_use]
+    pub fn symmetry_ratio(&self) -> f64 {
+        self.symmetry_ratio
+    }
+
+    /// The fraction of rows that are (weakly) diagonally dominant, i.e. for which the magnitude
+    /// of the diagonal entry is at least the sum of the magnitudes of the other entries.
+    ///
+    /// Returns `None` if the statistics were computed from a pattern without values. A matrix
+    /// without rows counts as diagonally dominant.
+    #[must_use]
+    pub fn diagonal_dominance(&self) -> Option<f64> {
+        self.diagonal_dominance
+    }
+}
+
+/// The ratio `count / total`, which is taken to be one if `total` is zero.
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+impl fmt::Display for PatternStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} x {} pattern with {} non-zeros",
+            self.nrows, self.ncols, self.nnz
+        )?;
+        writeln!(
+            f,
+            "  non-zeros per row:  min {}, mean {:.2}, max {}",
+            self.min_row_nnz,
+            self.mean_row_nnz(),
+            self.max_row_nnz
+        )?;
+        writeln!(f, "  empty rows:         {}", self.empty_rows)?;
+        writeln!(f, "  bandwidth:          {}", self.bandwidth)?;
+        write!(f, "  symmetry ratio:     {:.3}", self.symmetry_ratio)?;
+        if let Some(dominance) = self.diagonal_dominance {
+            write!(f, "\n  diagonal dominance: {:.3}", dominance)?;
+        }
+        Ok(())
+    }
+}
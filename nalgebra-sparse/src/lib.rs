@@ -15,11 +15,31 @@
 //!
 //! - [CSR](csr::CsrMatrix), [CSC](csc::CscMatrix) and [COO](coo::CooMatrix) formats, and
 //!   [conversions](`convert`) between them.
+//! - Specialized [BSR](bsr::BsrMatrix), [DIA](dia::DiaMatrix), [ELL](ell::EllMatrix) and
+//!   [SELL-C-σ](sell::SellMatrix) formats for block-structured, banded and vectorized
+//!   matrix-vector products.
 //! - Common arithmetic operations are implemented. See the [`ops`] module.
+//! - [Sparse vectors](vector::SparseVector) and sparse matrix-sparse vector products.
+//! - [Finite element style assembly](`assembly`) into CSR matrices with a fixed sparsity pattern,
+//!   optionally in parallel.
+//! - [Slicing, stacking and block assembly](`edition`) and [permutations](`permutation`) of CSR
+//!   and CSC matrices.
+//! - Kronecker products and Kronecker sums, diagonal matrices and diagonal shifts of CSR and CSC
+//!   matrices, computed directly in sparse form.
+//! - Parallel versions of the arithmetic kernels in `ops::parallel` when the `rayon` feature is
+//!   enabled.
+//! - [Iterative solvers](`solvers`) for large linear systems, generic over
+//!   [linear operators](`operator`) that can be composed without forming a matrix.
+//! - [Eigensolvers](`eigen`) for a few eigenpairs of large symmetric (generalized) eigenvalue
+//!   problems.
 //! - Sparsity patterns in CSR and CSC matrices are explicitly represented by the
 //!   [SparsityPattern](pattern::SparsityPattern) type, which encodes the invariants of the
-//!   associated index data structures.
+//!   associated index data structures. [Graph algorithms](pattern::graph) such as connected
+//!   components, elimination trees and colorings operate directly on sparsity patterns.
 //! - [Matrix market format support](`io`) when the `io` feature is enabled.
+//! - Spy plots and summary statistics of sparsity patterns in the [`io`] module.
+//! - Conversions from and to the legacy `nalgebra::sparse` matrices when the `legacy-sparse`
+//!   feature is enabled.
 //! - [proptest strategies](`proptest`) for sparse matrices when the feature
 //!   `proptest-support` is enabled.
 //! - [matrixcompare support](https://crates.io/crates/matrixcompare) for effortless
@@ -36,9 +56,10 @@
 //! Current limitations:
 //!
 //! - Limited or no availability of sparse system solvers.
-//! - Limited support for complex numbers. Currently only arithmetic operations that do not
-//!   rely on particular properties of complex numbers, such as e.g. conjugation, are
-//!   supported.
+//! - Limited support for complex numbers. Conjugate transposition is available through
+//!   [`adjoint`](csr::CsrMatrix::adjoint) and the `_adjoint` routines in [`ops::serial`], and the
+//!   [Cholesky factorization](factorization::CscCholesky) supports Hermitian matrices, but the
+//!   iterative solvers and eigensolvers only support real matrices.
 //! - No integration with external libraries.
 //!
 //! # Usage
@@ -57,6 +78,10 @@
 //! | [COO](`coo::CooMatrix`) | Well-suited for matrix construction. <br /> Ill-suited for algebraic operations. |
 //! | [CSR](`csr::CsrMatrix`) | Immutable sparsity pattern, suitable for algebraic operations. <br /> Fast row access. |
 //! | [CSC](`csc::CscMatrix`) | Immutable sparsity pattern, suitable for algebraic operations. <br /> Fast column access. |
+//! | [BSR](`bsr::BsrMatrix`) | Sparse arrangement of dense square blocks of static size. <br /> Suitable for matrices with several unknowns per node. |
+//! | [DIA](`dia::DiaMatrix`) | Dense diagonals. <br /> Suitable for banded matrices. |
+//! | [ELL](`ell::EllMatrix`) | Rows padded to equal length. <br /> Suitable for vectorized matrix-vector products. |
+//! | [SELL-C-σ](`sell::SellMatrix`) | Sorted slices of rows in ELL format. <br /> Suitable for vectorized matrix-vector products with less padding than ELL. |
 //!
 //! What format is best to use depends on the application. The most common use case for sparse
 //! matrices in science is the solution of sparse linear systems. Here we can differentiate between
@@ -143,19 +168,27 @@
 )]
 
 pub extern crate nalgebra as na;
-#[macro_use]
-#[cfg(feature = "io")]
-extern crate pest_derive;
 
+pub mod assembly;
+pub mod bsr;
 pub mod convert;
 pub mod coo;
 pub mod csc;
 pub mod csr;
+pub mod dia;
+pub mod edition;
+pub mod eigen;
+pub mod ell;
 pub mod factorization;
 #[cfg(feature = "io")]
 pub mod io;
+pub mod operator;
 pub mod ops;
 pub mod pattern;
+pub mod permutation;
+pub mod sell;
+pub mod solvers;
+pub mod vector;
 
 pub(crate) mod cs;
 pub(crate) mod utils;
@@ -1,11 +1,15 @@
 //! Implements core traits for use with `matrixcompare`.
+use crate::bsr::BsrMatrix;
 use crate::coo::CooMatrix;
 use crate::csc::CscMatrix;
 use crate::csr::CsrMatrix;
+use crate::dia::DiaMatrix;
+use crate::ell::EllMatrix;
+use crate::sell::SellMatrix;
 use matrixcompare_core;
 use matrixcompare_core::{Access, SparseAccess};
 
-macro_rules! impl_matrix_for_csr_csc {
+macro_rules! impl_matrix_for_sparse_format {
     ($MatrixType:ident) => {
         impl<T: Clone> SparseAccess<T> for $MatrixType<T> {
             fn nnz(&self) -> usize {
@@ -35,8 +39,11 @@ macro_rules! impl_matrix_for_csr_csc {
     };
 }
 
-impl_matrix_for_csr_csc!(CsrMatrix);
-impl_matrix_for_csr_csc!(CscMatrix);
+impl_matrix_for_sparse_format!(CsrMatrix);
+impl_matrix_for_sparse_format!(CscMatrix);
+impl_matrix_for_sparse_format!(DiaMatrix);
+impl_matrix_for_sparse_format!(EllMatrix);
+impl_matrix_for_sparse_format!(SellMatrix);
 
 impl<T: Clone> SparseAccess<T> for CooMatrix<T> {
     fn nnz(&self) -> usize {
@@ -63,3 +70,29 @@ impl<T: Clone> matrixcompare_core::Matrix<T> for CooMatrix<T> {
         Access::Sparse(self)
     }
 }
+
+impl<T: Clone, const B: usize> SparseAccess<T> for BsrMatrix<T, B> {
+    fn nnz(&self) -> usize {
+        BsrMatrix::nnz(self)
+    }
+
+    fn fetch_triplets(&self) -> Vec<(usize, usize, T)> {
+        self.triplet_iter()
+            .map(|(i, j, v)| (i, j, v.clone()))
+            .collect()
+    }
+}
+
+impl<T: Clone, const B: usize> matrixcompare_core::Matrix<T> for BsrMatrix<T, B> {
+    fn rows(&self) -> usize {
+        self.nrows()
+    }
+
+    fn cols(&self) -> usize {
+        self.ncols()
+    }
+
+    fn access(&self) -> Access<'_, T> {
+        Access::Sparse(self)
+    }
+}
@@ -0,0 +1,744 @@
+//! Linear operators.
+//!
+//! Many algorithms, such as the iterative solvers in [`solvers`](crate::solvers), do not need
+//! access to the entries of a matrix. They only need to be able to compute matrix-vector products
+//! `y <- beta * y + alpha * A * x`. The [`LinearOperator`] trait captures this requirement, and
+//! lets the same algorithm work with sparse matrices, dense matrices and user-defined
+//! matrix-free operators.
+//!
+//! ```
+//! use nalgebra_sparse::csr::CsrMatrix;
+//! use nalgebra_sparse::operator::{FnOperator, LinearOperator};
+//! use nalgebra::{DVector, DVectorView, DVectorViewMut};
+//!
+//! let x = DVector::from_column_slice(&[1.0, 2.0, 3.0]);
+//!
+//! // Sparse matrices are linear operators
+//! let a = CsrMatrix::<f64>::identity(3);
+//! let mut y = DVector::zeros(3);
+//! a.apply(0.0, (&mut y).into(), 2.0, (&x).into());
+//! assert_eq!(y, 2.0 * &x);
+//!
+//! // ... and so are closures wrapped in a `FnOperator`
+//! let op = FnOperator::new(3, 3, |x: DVectorView<f64>, mut y: DVectorViewMut<f64>| {
+//!     y.copy_from(&x);
+//!     y[0] = 0.0;
+//! });
+//! op.apply(0.0, (&mut y).into(), 1.0, (&x).into());
+//! assert_eq!(y, DVector::from_column_slice(&[0.0, 2.0, 3.0]));
+//! ```
+//!
+//! Operators can be combined without forming the resulting matrix. [`SumOperator`],
+//! [`ProductOperator`], [`ScaledOperator`] and [`TransposeOperator`] provide sums, products,
+//! scaling and transposition, while [`BlockOperator`] composes operators into a block operator.
+//! Transposition requires the [`TransposableOperator`] trait, and matrices wrapped in an [`Op`]
+//! are also linear operators. For example, the operator `A^T A + λ I` of a regularized least
+//! squares problem can be applied as follows:
+//!
+//! ```
+//! use nalgebra_sparse::csr::CsrMatrix;
+//! use nalgebra_sparse::operator::{LinearOperator, ProductOperator, ScaledOperator, SumOperator};
+//! use nalgebra_sparse::ops::Op;
+//! use nalgebra::{DMatrix, DVector};
+//!
+//! let a = CsrMatrix::from(&DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 0.0, 2.0, 1.0, 1.0]));
+//! let identity = CsrMatrix::identity(2);
+//! let lambda = 0.5;
+//! let op = SumOperator::new(
+//!     ProductOperator::new(Op::Transpose(&a), &a),
+//!     ScaledOperator::new(lambda, &identity),
+//! );
+//!
+//! let x = DVector::from_column_slice(&[1.0, -1.0]);
+//! let mut y = DVector::zeros(2);
+//! op.apply(0.0, (&mut y).into(), 1.0, (&x).into());
+//! let a_dense = DMatrix::from(&a);
+//! assert_eq!(y, a_dense.transpose() * &a_dense * &x + &x * lambda);
+//! ```
+
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::edition::set_size;
+use crate::ops::serial::{spmm_csc_dense, spmm_csr_dense};
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrix, DVector, DVectorView, DVectorViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// An abstract linear operator `A`.
+///
+/// The only required capability is the computation of the (generalized) matrix-vector product
+/// `y <- beta * y + alpha * A * x`.
+pub trait LinearOperator<T: Scalar> {
+    /// The number of rows of the operator, i.e. the dimension of its range.
+    fn nrows(&self) -> usize;
+
+    /// The number of columns of the operator, i.e. the dimension of its domain.
+    fn ncols(&self) -> usize;
+
+    /// Computes `y <- beta * y + alpha * A * x`.
+    ///
+    /// If `beta` is zero, implementations must not read the initial contents of `y`.
+    ///
+    /// # Panics
+    ///
+    /// Implementations are expected to panic if the dimensions of `x` and `y` are not compatible
+    /// with the dimensions of the operator.
+    fn apply(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>);
+}
+
+impl<T, A> LinearOperator<T> for &A
+where
+    T: Scalar,
+    A: LinearOperator<T> + ?Sized,
+{
+    fn nrows(&self) -> usize {
+        A::nrows(self)
+    }
+
+    fn ncols(&self) -> usize {
+        A::ncols(self)
+    }
+
+    fn apply(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        A::apply(self, beta, y, alpha, x)
+    }
+}
+
+/// Zeroes out `y` if `beta` is zero.
+///
+/// The sparse kernels always compute `beta * y`, so we must ensure that e.g. `NaN`s in
+/// uninitialized output vectors do not leak into the result.
+fn prepare_output<T>(beta: &T, y: &mut DVectorViewMut<'_, T>)
+where
+    T: Scalar + Zero,
+{
+    if beta.is_zero() {
+        y.fill(T::zero());
+    }
+}
+
+impl<T> LinearOperator<T> for CsrMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        CsrMatrix::nrows(self)
+    }
+
+    fn ncols(&self) -> usize {
+        CsrMatrix::ncols(self)
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        prepare_output(&beta, &mut y);
+        spmm_csr_dense(beta, &mut y, alpha, Op::NoOp(self), Op::NoOp(&x));
+    }
+}
+
+impl<T> LinearOperator<T> for CscMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        CscMatrix::nrows(self)
+    }
+
+    fn ncols(&self) -> usize {
+        CscMatrix::ncols(self)
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        prepare_output(&beta, &mut y);
+        spmm_csc_dense(beta, &mut y, alpha, Op::NoOp(self), Op::NoOp(&x));
+    }
+}
+
+impl<T> LinearOperator<T> for DMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        self.shape().0
+    }
+
+    fn ncols(&self) -> usize {
+        self.shape().1
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        y.gemv(alpha, self, &x, beta);
+    }
+}
+
+/// A linear operator defined by a closure.
+///
+/// The closure is called as `f(x, y)` and must overwrite `y` with the product `A * x`.
+/// The initial contents of `y` are unspecified.
+///
+/// See the [module-level documentation](crate::operator) for an example.
+#[derive(Debug, Clone, Copy)]
+pub struct FnOperator<F> {
+    nrows: usize,
+    ncols: usize,
+    f: F,
+}
+
+impl<F> FnOperator<F> {
+    /// Constructs an `nrows x ncols` operator whose action is given by the closure `f`.
+    pub fn new(nrows: usize, ncols: usize, f: F) -> Self {
+        Self { nrows, ncols, f }
+    }
+}
+
+impl<T, F> LinearOperator<T> for FnOperator<F>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    F: Fn(DVectorView<'_, T>, DVectorViewMut<'_, T>),
+{
+    fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        assert_eq!(x.nrows(), self.ncols, "x.nrows() != A.ncols()");
+        assert_eq!(y.nrows(), self.nrows, "y.nrows() != A.nrows()");
+        if beta.is_zero() && alpha.is_one() {
+            (self.f)(x, y);
+        } else {
+            let mut ax = DVector::zeros(self.nrows);
+            (self.f)(x, (&mut ax).into());
+            y.axpy(alpha, &ax, beta);
+        }
+    }
+}
+
+impl<T> LinearOperator<T> for Op<&CsrMatrix<T>>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.nrows(),
+            Op::Transpose(a) => a.ncols(),
+        }
+    }
+
+    fn ncols(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.ncols(),
+            Op::Transpose(a) => a.nrows(),
+        }
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        prepare_output(&beta, &mut y);
+        spmm_csr_dense(beta, &mut y, alpha, *self, Op::NoOp(&x));
+    }
+}
+
+impl<T> LinearOperator<T> for Op<&CscMatrix<T>>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.nrows(),
+            Op::Transpose(a) => a.ncols(),
+        }
+    }
+
+    fn ncols(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.ncols(),
+            Op::Transpose(a) => a.nrows(),
+        }
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        prepare_output(&beta, &mut y);
+        spmm_csc_dense(beta, &mut y, alpha, *self, Op::NoOp(&x));
+    }
+}
+
+impl<T> LinearOperator<T> for Op<&DMatrix<T>>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.nrows(),
+            Op::Transpose(a) => a.ncols(),
+        }
+    }
+
+    fn ncols(&self) -> usize {
+        match self {
+            Op::NoOp(a) => a.ncols(),
+            Op::Transpose(a) => a.nrows(),
+        }
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        match self {
+            Op::NoOp(a) => y.gemv(alpha, *a, &x, beta),
+            Op::Transpose(a) => {
+                prepare_output(&beta, &mut y);
+                y.gemv_tr(alpha, *a, &x, beta)
+            }
+        }
+    }
+}
+
+/// A linear operator that can also be applied in transposed form.
+///
+/// This is required by [`TransposeOperator`], and is implemented by the sparse and dense matrix
+/// types as well as by the combinators in this module whose components are transposable.
+pub trait TransposableOperator<T: Scalar>: LinearOperator<T> {
+    /// Computes `y <- beta * y + alpha * A^T * x`.
+    ///
+    /// If `beta` is zero, implementations must not read the initial contents of `y`.
+    ///
+    /// # Panics
+    ///
+    /// Implementations are expected to panic if the dimensions of `x` and `y` are not compatible
+    /// with the dimensions of the transposed operator.
+    fn apply_transpose(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>);
+}
+
+impl<T, A> TransposableOperator<T> for &A
+where
+    T: Scalar,
+    A: TransposableOperator<T> + ?Sized,
+{
+    fn apply_transpose(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        A::apply_transpose(self, beta, y, alpha, x)
+    }
+}
+
+impl<T> TransposableOperator<T> for CsrMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn apply_transpose(
+        &self,
+        beta: T,
+        mut y: DVectorViewMut<'_, T>,
+        alpha: T,
+        x: DVectorView<'_, T>,
+    ) {
+        prepare_output(&beta, &mut y);
+        spmm_csr_dense(beta, &mut y, alpha, Op::Transpose(self), Op::NoOp(&x));
+    }
+}
+
+impl<T> TransposableOperator<T> for CscMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn apply_transpose(
+        &self,
+        beta: T,
+        mut y: DVectorViewMut<'_, T>,
+        alpha: T,
+        x: DVectorView<'_, T>,
+    ) {
+        prepare_output(&beta, &mut y);
+        spmm_csc_dense(beta, &mut y, alpha, Op::Transpose(self), Op::NoOp(&x));
+    }
+}
+
+impl<T> TransposableOperator<T> for DMatrix<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    fn apply_transpose(
+        &self,
+        beta: T,
+        mut y: DVectorViewMut<'_, T>,
+        alpha: T,
+        x: DVectorView<'_, T>,
+    ) {
+        prepare_output(&beta, &mut y);
+        y.gemv_tr(alpha, self, &x, beta);
+    }
+}
+
+/// The sum `A + B` of two linear operators with the same dimensions.
+#[derive(Debug, Clone, Copy)]
+pub struct SumOperator<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> SumOperator<A, B> {
+    /// Constructs the sum `A + B`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions of the operators are not the same.
+    pub fn new<T: Scalar>(a: A, b: B) -> Self
+    where
+        A: LinearOperator<T>,
+        B: LinearOperator<T>,
+    {
+        assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+        assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+        Self { a, b }
+    }
+}
+
+impl<T, A, B> LinearOperator<T> for SumOperator<A, B>
+where
+    T: Scalar + One,
+    A: LinearOperator<T>,
+    B: LinearOperator<T>,
+{
+    fn nrows(&self) -> usize {
+        self.a.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.a.ncols()
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        self.a
+            .apply(beta, (&mut y).into(), alpha.clone(), x.clone());
+        self.b.apply(T::one(), y, alpha, x);
+    }
+}
+
+impl<T, A, B> TransposableOperator<T> for SumOperator<A, B>
+where
+    T: Scalar + One,
+    A: TransposableOperator<T>,
+    B: TransposableOperator<T>,
+{
+    fn apply_transpose(
+        &self,
+        beta: T,
+        mut y: DVectorViewMut<'_, T>,
+        alpha: T,
+        x: DVectorView<'_, T>,
+    ) {
+        self.a
+            .apply_transpose(beta, (&mut y).into(), alpha.clone(), x.clone());
+        self.b.apply_transpose(T::one(), y, alpha, x);
+    }
+}
+
+/// The product `A B` of two linear operators.
+///
+/// The product is never formed. Each application applies `B` to a temporary vector, and then
+/// applies `A` to the result.
+#[derive(Debug, Clone, Copy)]
+pub struct ProductOperator<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> ProductOperator<A, B> {
+    /// Constructs the product `A B`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of columns of `A` does not match the number of rows of `B`.
+    pub fn new<T: Scalar>(a: A, b: B) -> Self
+    where
+        A: LinearOperator<T>,
+        B: LinearOperator<T>,
+    {
+        assert_eq!(a.ncols(), b.nrows(), "A.ncols() != B.nrows()");
+        Self { a, b }
+    }
+}
+
+impl<T, A, B> LinearOperator<T> for ProductOperator<A, B>
+where
+    T: Scalar + Zero + One,
+    A: LinearOperator<T>,
+    B: LinearOperator<T>,
+{
+    fn nrows(&self) -> usize {
+        self.a.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.b.ncols()
+    }
+
+    fn apply(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        let mut bx = DVector::zeros(self.b.nrows());
+        self.b.apply(T::zero(), (&mut bx).into(), T::one(), x);
+        self.a.apply(beta, y, alpha, (&bx).into());
+    }
+}
+
+impl<T, A, B> TransposableOperator<T> for ProductOperator<A, B>
+where
+    T: Scalar + Zero + One,
+    A: TransposableOperator<T>,
+    B: TransposableOperator<T>,
+{
+    fn apply_transpose(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        // (A B)^T = B^T A^T
+        let mut atx = DVector::zeros(self.a.ncols());
+        self.a
+            .apply_transpose(T::zero(), (&mut atx).into(), T::one(), x);
+        self.b.apply_transpose(beta, y, alpha, (&atx).into());
+    }
+}
+
+/// The operator `s A` for a scalar `s`.
+#[derive(Debug, Clone, Copy)]
+pub struct ScaledOperator<T, A> {
+    scale: T,
+    a: A,
+}
+
+impl<T, A> ScaledOperator<T, A> {
+    /// Constructs the scaled operator `s A`.
+    pub fn new(scale: T, a: A) -> Self {
+        Self { scale, a }
+    }
+}
+
+impl<T, A> LinearOperator<T> for ScaledOperator<T, A>
+where
+    T: Scalar + ClosedMul,
+    A: LinearOperator<T>,
+{
+    fn nrows(&self) -> usize {
+        self.a.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.a.ncols()
+    }
+
+    fn apply(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        self.a.apply(beta, y, alpha * self.scale.clone(), x);
+    }
+}
+
+impl<T, A> TransposableOperator<T> for ScaledOperator<T, A>
+where
+    T: Scalar + ClosedMul,
+    A: TransposableOperator<T>,
+{
+    fn apply_transpose(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        self.a
+            .apply_transpose(beta, y, alpha * self.scale.clone(), x);
+    }
+}
+
+/// The transpose `A^T` of a [transposable](TransposableOperator) linear operator.
+///
+/// To transpose a sparse or dense matrix directly, an [`Op`] such as `Op::Transpose(&matrix)`
+/// can also be used as a linear operator.
+#[derive(Debug, Clone, Copy)]
+pub struct TransposeOperator<A> {
+    a: A,
+}
+
+impl<A> TransposeOperator<A> {
+    /// Constructs the transposed operator `A^T`.
+    pub fn new(a: A) -> Self {
+        Self { a }
+    }
+}
+
+impl<T, A> LinearOperator<T> for TransposeOperator<A>
+where
+    T: Scalar,
+    A: TransposableOperator<T>,
+{
+    fn nrows(&self) -> usize {
+        self.a.ncols()
+    }
+
+    fn ncols(&self) -> usize {
+        self.a.nrows()
+    }
+
+    fn apply(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        self.a.apply_transpose(beta, y, alpha, x);
+    }
+}
+
+impl<T, A> TransposableOperator<T> for TransposeOperator<A>
+where
+    T: Scalar,
+    A: TransposableOperator<T>,
+{
+    fn apply_transpose(&self, beta: T, y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        self.a.apply(beta, y, alpha, x);
+    }
+}
+
+/// A linear operator composed of a grid of linear operators, such as the saddle point operator
+/// `[A B^T; B 0]`.
+///
+/// The operator describes a grid of `block_rows x block_cols` blocks, each of which is either a
+/// linear operator or absent, in which case the block is zero. The blocks may be of different
+/// types. As with [`BlockBuilder`](crate::edition::BlockBuilder), all blocks in the same block
+/// row must have the same number of rows, all blocks in the same block column must have the same
+/// number of columns, and the size of a block row or block column without any blocks is zero
+/// unless it is explicitly set.
+///
+/// # Examples
+///
+/// ```
+/// use nalgebra_sparse::csr::CsrMatrix;
+/// use nalgebra_sparse::operator::{BlockOperator, LinearOperator};
+/// use nalgebra_sparse::ops::Op;
+/// use nalgebra::{DMatrix, DVector};
+///
+/// let a = CsrMatrix::<f64>::identity(2);
+/// let b = CsrMatrix::from(&DMatrix::from_row_slice(1, 2, &[1.0, 2.0]));
+///
+/// // The saddle point operator [ a b^T ]
+/// //                           [ b  0  ]
+/// let mut op = BlockOperator::new(2, 2);
+/// op.set_block(0, 0, &a)
+///     .set_block(0, 1, Op::Transpose(&b))
+///     .set_block(1, 0, &b);
+///
+/// let x = DVector::from_column_slice(&[1.0, 1.0, 1.0]);
+/// let mut y = DVector::zeros(3);
+/// op.apply(0.0, (&mut y).into(), 1.0, (&x).into());
+/// assert_eq!(y, DVector::from_column_slice(&[2.0, 3.0, 3.0]));
+/// ```
+pub struct BlockOperator<'a, T> {
+    row_heights: Vec<Option<usize>>,
+    col_widths: Vec<Option<usize>>,
+    // The blocks, stored block row by block row
+    blocks: Vec<Option<Box<dyn LinearOperator<T> + 'a>>>,
+}
+
+impl<'a, T: Scalar> BlockOperator<'a, T> {
+    /// Creates an operator for a grid of `block_rows x block_cols` blocks, all of which are
+    /// absent.
+    pub fn new(block_rows: usize, block_cols: usize) -> Self {
+        Self {
+            row_heights: vec![None; block_rows],
+            col_widths: vec![None; block_cols],
+            blocks: (0..block_rows * block_cols).map(|_| None).collect(),
+        }
+    }
+
+    /// The number of block rows.
+    #[must_use]
+    pub fn block_rows(&self) -> usize {
+        self.row_heights.len()
+    }
+
+    /// The number of block columns.
+    #[must_use]
+    pub fn block_cols(&self) -> usize {
+        self.col_widths.len()
+    }
+
+    /// Sets the number of rows of the given block row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_row` is out of bounds, or if the block row already has a different height.
+    pub fn set_row_height(&mut self, block_row: usize, height: usize) -> &mut Self {
+        assert!(block_row < self.block_rows(), "Block row out of bounds.");
+        set_size(
+            &mut self.row_heights[block_row],
+            height,
+            "Blocks in the same block row must have the same number of rows.",
+        );
+        self
+    }
+
+    /// Sets the number of columns of the given block column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_col` is out of bounds, or if the block column already has a different
+    /// width.
+    pub fn set_col_width(&mut self, block_col: usize, width: usize) -> &mut Self {
+        assert!(block_col < self.block_cols(), "Block column out of bounds.");
+        set_size(
+            &mut self.col_widths[block_col],
+            width,
+            "Blocks in the same block column must have the same number of columns.",
+        );
+        self
+    }
+
+    /// Sets the block at the given position, replacing any previous block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is out of bounds, or if the dimensions of the block are not
+    /// consistent with the other blocks in the same block row or block column.
+    pub fn set_block(
+        &mut self,
+        block_row: usize,
+        block_col: usize,
+        block: impl LinearOperator<T> + 'a,
+    ) -> &mut Self {
+        self.set_row_height(block_row, block.nrows());
+        self.set_col_width(block_col, block.ncols());
+        let block_cols = self.block_cols();
+        self.blocks[block_row * block_cols + block_col] = Some(Box::new(block));
+        self
+    }
+}
+
+/// Computes the offsets of consecutive blocks with the given sizes, where absent sizes are zero.
+fn block_offsets(sizes: &[Option<usize>]) -> Vec<usize> {
+    let mut offsets = vec![0];
+    for size in sizes {
+        offsets.push(offsets.last().unwrap() + size.unwrap_or(0));
+    }
+    offsets
+}
+
+impl<'a, T> LinearOperator<T> for BlockOperator<'a, T>
+where
+    T: Scalar + ClosedMul + Zero + One,
+{
+    fn nrows(&self) -> usize {
+        self.row_heights.iter().map(|h| h.unwrap_or(0)).sum()
+    }
+
+    fn ncols(&self) -> usize {
+        self.col_widths.iter().map(|w| w.unwrap_or(0)).sum()
+    }
+
+    fn apply(&self, beta: T, mut y: DVectorViewMut<'_, T>, alpha: T, x: DVectorView<'_, T>) {
+        assert_eq!(x.nrows(), self.ncols(), "x.nrows() != A.ncols()");
+        assert_eq!(y.nrows(), self.nrows(), "y.nrows() != A.nrows()");
+        let row_offsets = block_offsets(&self.row_heights);
+        let col_offsets = block_offsets(&self.col_widths);
+
+        let block_cols = self.block_cols();
+        for i in 0..self.block_rows() {
+            let row_blocks = &self.blocks[i * block_cols..(i + 1) * block_cols];
+            let rows = row_offsets[i]..row_offsets[i + 1];
+            let mut y_i = y.rows_mut(rows.start, rows.len());
+            // Every block in the row accumulates into y_i, so scale y_i once up front
+            if beta.is_zero() {
+                y_i.fill(T::zero());
+            } else {
+                y_i *= beta.clone();
+            }
+            for (j, block) in row_blocks.iter().enumerate() {
+                if let Some(block) = block {
+                    let cols = col_offsets[j]..col_offsets[j + 1];
+                    let x_j = x.rows(cols.start, cols.len());
+                    block.apply(T::one(), (&mut y_i).into(), alpha.clone(), x_j);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Component-wise operations on CSR and CSC matrices.
+
+use crate::cs::CsMatrix;
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::ops::serial::{
+    elementwise_intersection_csc, elementwise_intersection_csr, elementwise_union_csc,
+    elementwise_union_csr,
+};
+use nalgebra::{ClosedDiv, ClosedMul, Scalar};
+use num_traits::Zero;
+
+macro_rules! impl_componentwise {
+    ($matrix_type:ident, $union_fn:ident, $intersection_fn:ident) => {
+        impl<T> $matrix_type<T> {
+            /// Returns a matrix with the same sparsity pattern, where `f` has been applied to
+            /// each explicitly stored value.
+            ///
+            /// Note that `f` is not applied to the implicit zeros of the matrix.
+            #[must_use]
+            pub fn map<U, F>(&self, mut f: F) -> $matrix_type<U>
+            where
+                T: Clone,
+                F: FnMut(T) -> U,
+            {
+                let values = self.values().iter().map(|v| f(v.clone())).collect();
+                $matrix_type {
+                    cs: CsMatrix::from_pattern_and_values(self.pattern().clone(), values),
+                }
+            }
+
+            /// Replaces each explicitly stored value `v` by `f(v)`, in place.
+            ///
+            /// Note that `f` is not applied to the implicit zeros of the matrix.
+            pub fn apply<F>(&mut self, mut f: F)
+            where
+                F: FnMut(&mut T),
+            {
+                self.values_mut().iter_mut().for_each(|v| f(v));
+            }
+
+            /// Component-wise multiplication of two matrices, also known as the Hadamard product.
+            ///
+            /// The result has the intersection of the sparsity patterns of both matrices.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the matrices do not have the same dimensions.
+            #[must_use]
+            pub fn component_mul(&self, other: &Self) -> Self
+            where
+                T: Scalar + ClosedMul,
+            {
+                $intersection_fn(self, other, |a, b| a * b)
+            }
+
+            /// Component-wise division of two matrices.
+            ///
+            /// The division is computed for the union of the sparsity patterns of both matrices,
+            /// where entries that are stored in only one of the matrices are divided by, or
+            /// divide, zero. Entries that are stored in neither matrix are left implicitly zero,
+            /// even though the dense division `0 / 0` is undefined.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the matrices do not have the same dimensions, or if the scalar type
+            /// panics on division by zero and `other` does not explicitly store all entries of
+            /// `self`.
+            #[must_use]
+            pub fn component_div(&self, other: &Self) -> Self
+            where
+                T: Scalar + Zero + ClosedDiv,
+            {
+                $union_fn(self, other, |a, b| a / b)
+            }
+
+            /// Component-wise minimum of two matrices.
+            ///
+            /// The result has the union of the sparsity patterns of both matrices, where
+            /// entries that are stored in only one of the matrices are compared with zero.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the matrices do not have the same dimensions.
+            #[must_use]
+            pub fn inf(&self, other: &Self) -> Self
+            where
+                T: Scalar + Zero + PartialOrd,
+            {
+                $union_fn(self, other, |a, b| if b < a { b } else { a })
+            }
+
+            /// Component-wise maximum of two matrices.
+            ///
+            /// The result has the union of the sparsity patterns of both matrices, where
+            /// entries that are stored in only one of the matrices are compared with zero.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the matrices do not have the same dimensions.
+            #[must_use]
+            pub fn sup(&self, other: &Self) -> Self
+            where
+                T: Scalar + Zero + PartialOrd,
+            {
+                $union_fn(self, other, |a, b| if b > a { b } else { a })
+            }
+        }
+    };
+}
+
+impl_componentwise!(
+    CsrMatrix,
+    elementwise_union_csr,
+    elementwise_intersection_csr
+);
+impl_componentwise!(
+    CscMatrix,
+    elementwise_union_csc,
+    elementwise_intersection_csc
+);
@@ -1,9 +1,15 @@
-use crate::csc::CscMatrix;
-use crate::csr::CsrMatrix;
+use crate::csc::{CscMatrix, CscMatrixView};
+use crate::csr::{CsrMatrix, CsrMatrixView};
+use crate::dia::DiaMatrix;
+use crate::ell::EllMatrix;
+use crate::sell::SellMatrix;
+use crate::vector::SparseVector;
 
+use crate::ops::serial::pattern::spmm_pattern_from_lanes;
 use crate::ops::serial::{
     spadd_csc_prealloc, spadd_csr_prealloc, spadd_pattern, spmm_csc_dense, spmm_csc_pattern,
     spmm_csc_prealloc_unchecked, spmm_csr_dense, spmm_csr_pattern, spmm_csr_prealloc_unchecked,
+    spmm_dia_dense, spmm_ell_dense, spmm_sell_dense, spmv_csc_sparse, spmv_csr_sparse,
 };
 use crate::ops::Op;
 use nalgebra::allocator::Allocator;
@@ -58,8 +64,8 @@ macro_rules! impl_sp_plus_minus {
             // We are giving data that is valid by definition, so it is safe to unwrap below
             let mut result = $matrix_type::try_from_pattern_and_values(pattern, values)
                 .unwrap();
-            $spadd_fn(T::zero(), &mut result, T::one(), Op::NoOp(&a)).unwrap();
-            $spadd_fn(T::one(), &mut result, $factor * T::one(), Op::NoOp(&b)).unwrap();
+            $spadd_fn(T::zero(), &mut result, T::one(), Op::NoOp(a)).unwrap();
+            $spadd_fn(T::one(), &mut result, $factor * T::one(), Op::NoOp(b)).unwrap();
             result
         });
 
@@ -116,6 +122,70 @@ impl_spmm!(CsrMatrix, spmm_csr_pattern, spmm_csr_prealloc_unchecked);
 // Need to switch order of operations for CSC pattern
 impl_spmm!(CscMatrix, spmm_csc_pattern, spmm_csc_prealloc_unchecked);
 
+/// Implements a * b for matrix views, which produces an owned matrix.
+macro_rules! impl_spmm_view {
+    ($view_type:ident, $matrix_type:ident, $spmm_fn:expr, |$a:ident, $b:ident| $pattern:expr) => {
+        impl_mul!(<'a, 'b, T>($a: $view_type<'a, T>, $b: $view_type<'b, T>) -> $matrix_type<T> {
+            assert_eq!($a.ncols(), $b.nrows(), "a and b must have compatible dimensions");
+            let pattern = $pattern;
+            let values = vec![T::zero(); pattern.nnz()];
+            let mut result = $matrix_type::try_from_pattern_and_values(pattern, values)
+                .unwrap();
+            $spmm_fn(T::zero(),
+                     &mut result,
+                     T::one(),
+                     Op::NoOp($a),
+                     Op::NoOp($b))
+                .expect("Internal error: spmm failed (please debug).");
+            result
+        });
+    }
+}
+
+impl_spmm_view!(
+    CsrMatrixView,
+    CsrMatrix,
+    spmm_csr_prealloc_unchecked,
+    |a, b| {
+        spmm_pattern_from_lanes(
+            a.nrows(),
+            b.ncols(),
+            |i| a.cs.lane_indices(i),
+            |k| b.cs.lane_indices(k),
+        )
+    }
+);
+// The CSC pattern of A * B is the CSR pattern of B^T * A^T
+impl_spmm_view!(
+    CscMatrixView,
+    CscMatrix,
+    spmm_csc_prealloc_unchecked,
+    |a, b| {
+        spmm_pattern_from_lanes(
+            b.ncols(),
+            a.nrows(),
+            |j| b.cs.lane_indices(j),
+            |k| a.cs.lane_indices(k),
+        )
+    }
+);
+
+/// Implements a * x for sparse matrices and sparse vectors, for all combinations of reference
+/// and owned operands.
+macro_rules! impl_spmv_sparse {
+    ($matrix_type:ident, $spmv_fn:expr) => {
+        impl_mul!(<'a, T>(a: &'a $matrix_type<T>, x: &'a SparseVector<T>) -> SparseVector<T> {
+            $spmv_fn(Op::NoOp(a), x)
+        });
+        impl_mul!(<'a, T>(a: &'a $matrix_type<T>, x: SparseVector<T>) -> SparseVector<T> { a * &x });
+        impl_mul!(<'a, T>(a: $matrix_type<T>, x: &'a SparseVector<T>) -> SparseVector<T> { &a * x });
+        impl_mul!(<T>(a: $matrix_type<T>, x: SparseVector<T>) -> SparseVector<T> { &a * &x });
+    }
+}
+
+impl_spmv_sparse!(CsrMatrix, spmv_csr_sparse);
+impl_spmv_sparse!(CscMatrix, spmv_csc_sparse);
+
 /// Implements Scalar * Matrix operations for *concrete* scalar types. The reason this is necessary
 /// is that we are not able to implement Mul<Matrix<T>> for all T generically due to orphan rules.
 macro_rules! impl_concrete_scalar_matrix_mul {
@@ -269,6 +339,20 @@ impl_div!(CsrMatrix);
 impl_div!(CscMatrix);
 
 macro_rules! impl_spmm_cs_dense {
+    (view $view_type_name:ident, $spmm_fn:ident) => {
+        // Views are `Copy`, so they are only implemented by value
+        impl_spmm_cs_dense!($view_type_name<'a, T>, &'a Matrix<T, R, C, S>, $spmm_fn, |lhs, rhs| {
+            let (_, ncols) = rhs.shape_generic();
+            let nrows = Dyn(lhs.nrows());
+            let mut result = OMatrix::<T, Dyn, C>::zeros_generic(nrows, ncols);
+            $spmm_fn(T::zero(), &mut result, T::one(), Op::NoOp(lhs), Op::NoOp(rhs));
+            result
+        });
+        impl_spmm_cs_dense!($view_type_name<'a, T>, Matrix<T, R, C, S>, $spmm_fn, |lhs, rhs| {
+            lhs * &rhs
+        });
+    };
+
     ($matrix_type_name:ident, $spmm_fn:ident) => {
         // Implement ref-ref
         impl_spmm_cs_dense!(&'a $matrix_type_name<T>, &'a Matrix<T, R, C, S>, $spmm_fn, |lhs, rhs| {
@@ -329,3 +413,8 @@ macro_rules! impl_spmm_cs_dense {
 
 impl_spmm_cs_dense!(CsrMatrix, spmm_csr_dense);
 impl_spmm_cs_dense!(CscMatrix, spmm_csc_dense);
+impl_spmm_cs_dense!(view CsrMatrixView, spmm_csr_dense);
+impl_spmm_cs_dense!(view CscMatrixView, spmm_csc_dense);
+impl_spmm_cs_dense!(DiaMatrix, spmm_dia_dense);
+impl_spmm_cs_dense!(EllMatrix, spmm_ell_dense);
+impl_spmm_cs_dense!(SellMatrix, spmm_sell_dense);
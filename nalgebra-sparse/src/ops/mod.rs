@@ -6,10 +6,10 @@
 //! offer more control over allocation, and allow fusing some low-level operations for higher
 //! performance.
 //!
-//! The available operations are organized by backend. Currently, only the [`serial`] backend
-//! is available. In the future, backends that expose parallel operations may become available.
-//! All `std::ops` implementations will remain single-threaded and powered by the
-//! `serial` backend.
+//! The available operations are organized by backend. The [`serial`] backend is always
+//! available. If the `rayon` feature is enabled, the `parallel` backend provides multi-threaded
+//! versions of the same operations with identical signatures. All `std::ops` implementations
+//! will remain single-threaded and powered by the `serial` backend.
 //!
 //! Many routines are able to implicitly transpose matrices involved in the operation.
 //! For example, the routine [`spadd_csr_prealloc`](serial::spadd_csr_prealloc) performs the
@@ -17,6 +17,11 @@
 //! either be used as-is or transposed. The notation `op(A)` is represented in code by the
 //! [`Op`] enum.
 //!
+//! For complex matrices, the routines with the suffix `_adjoint`, such as
+//! [`spadd_csr_prealloc_adjoint`](serial::spadd_csr_prealloc_adjoint), use the adjoint, i.e. the
+//! conjugate transpose, of `A` instead. Since they need to conjugate the entries of `A`, they
+//! require the scalar type to implement `ComplexField`.
+//!
 //! # Available `std::ops` implementations
 //!
 //! ## Binary operators
@@ -66,6 +71,14 @@
 //!
 //! As can be seen from the table, only `CSR * Dense` and `CSC * Dense` are supported.
 //! The other way around, i.e. `Dense * CSR` and `Dense * CSC` are not implemented.
+//! Furthermore, the [DIA](`crate::dia::DiaMatrix`), [ELL](`crate::ell::EllMatrix`) and
+//! [SELL-C-σ](`crate::sell::SellMatrix`) formats support `Matrix * Dense`. Products of
+//! [BSR](`crate::bsr::BsrMatrix`) matrices with dense matrices are available through
+//! [`spmm_bsr_dense`](serial::spmm_bsr_dense).
+//!
+//! CSR and CSC matrices also support `Matrix * SparseVector` with
+//! [sparse vectors](`crate::vector::SparseVector`), which produces a sparse vector. See
+//! [`spmv_csr_sparse`](serial::spmv_csr_sparse) for the variant that supports transposition.
 //!
 //! Additionally, [CsrMatrix](`crate::csr::CsrMatrix`) and [CscMatrix](`crate::csc::CscMatrix`)
 //! support multiplication with scalars, in addition to division by a scalar.
@@ -124,8 +137,56 @@
 //! directly calling kernels may sometimes lead to better performance. However, this should
 //! always be verified by performance profiling!
 
+macro_rules! assert_compatible_spmm_dims {
+    ($c:expr, $a:expr, $b:expr) => {{
+        use crate::ops::Op::{NoOp, Transpose};
+        match (&$a, &$b) {
+            (NoOp(ref a), NoOp(ref b)) => {
+                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
+                assert_eq!($c.ncols(), b.ncols(), "C.ncols() != B.ncols()");
+                assert_eq!(a.ncols(), b.nrows(), "A.ncols() != B.nrows()");
+            }
+            (Transpose(ref a), NoOp(ref b)) => {
+                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
+                assert_eq!($c.ncols(), b.ncols(), "C.ncols() != B.ncols()");
+                assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+            }
+            (NoOp(ref a), Transpose(ref b)) => {
+                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
+                assert_eq!($c.ncols(), b.nrows(), "C.ncols() != B.nrows()");
+                assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+            }
+            (Transpose(ref a), Transpose(ref b)) => {
+                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
+                assert_eq!($c.ncols(), b.nrows(), "C.ncols() != B.nrows()");
+                assert_eq!(a.nrows(), b.ncols(), "A.nrows() != B.ncols()");
+            }
+        }
+    }};
+}
+
+macro_rules! assert_compatible_spadd_dims {
+    ($c:expr, $a:expr) => {
+        use crate::ops::Op;
+        match $a {
+            Op::NoOp(a) => {
+                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
+                assert_eq!($c.ncols(), a.ncols(), "C.ncols() != A.ncols()");
+            }
+            Op::Transpose(a) => {
+                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
+                assert_eq!($c.ncols(), a.nrows(), "C.ncols() != A.nrows()");
+            }
+        }
+    };
+}
+
+mod componentwise;
 mod impl_std_ops;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod serial;
+mod statistics;
 
 /// Determines whether a matrix should be transposed in a given operation.
 ///
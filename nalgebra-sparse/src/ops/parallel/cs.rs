@@ -0,0 +1,137 @@
+use crate::cs::{CsLane, CsLaneMut, CsMatrix};
+use crate::ops::serial::cs::{
+    spadd_cs_lane_prealloc, spmm_cs_dense_rows, spmm_cs_lane_prealloc,
+    spmm_cs_lane_prealloc_unchecked,
+};
+use crate::ops::serial::OperationError;
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+use rayon::prelude::*;
+use std::convert::identity;
+
+/// The approximate number of multiply-add operations below which a block of rows in a
+/// sparse-dense product is not split any further.
+const SEQUENTIAL_WORK_THRESHOLD: usize = 4096;
+
+/// Pairs up the lanes of `c` and `a`, so that they can be distributed across threads.
+fn zip_lanes<'a, T>(
+    c: &'a mut CsMatrix<T>,
+    a: &'a CsMatrix<T>,
+) -> Vec<(CsLaneMut<'a, T>, CsLane<'a, T>)> {
+    c.lane_iter_mut().zip(a.lane_iter()).collect()
+}
+
+/// Parallel counterpart of the serial `spmm_cs_prealloc_unchecked`.
+pub fn spmm_cs_prealloc_unchecked<T>(
+    beta: T,
+    c: &mut CsMatrix<T>,
+    alpha: T,
+    a: &CsMatrix<T>,
+    b: &CsMatrix<T>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_eq!(c.pattern().major_dim(), a.pattern().major_dim());
+    assert_eq!(c.pattern().minor_dim(), b.pattern().minor_dim());
+    let minor_dim = b.pattern().minor_dim();
+    zip_lanes(c, a).into_par_iter().for_each_init(
+        || vec![T::zero(); minor_dim],
+        |scratchpad_values, (c_lane_i, a_lane_i)| {
+            spmm_cs_lane_prealloc_unchecked(
+                beta.clone(),
+                c_lane_i,
+                alpha.clone(),
+                a_lane_i,
+                b.as_view(),
+                scratchpad_values,
+            )
+        },
+    );
+    Ok(())
+}
+
+/// Parallel counterpart of the serial `spmm_cs_prealloc`.
+pub fn spmm_cs_prealloc<T>(
+    beta: T,
+    c: &mut CsMatrix<T>,
+    alpha: T,
+    a: &CsMatrix<T>,
+    b: &CsMatrix<T>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    zip_lanes(c, a)
+        .into_par_iter()
+        .try_for_each(|(c_lane_i, a_lane_i)| {
+            spmm_cs_lane_prealloc(beta.clone(), c_lane_i, alpha.clone(), a_lane_i, b.as_view())
+        })
+}
+
+/// Parallel counterpart of the serial `spadd_cs_prealloc` for a non-transposed `A`.
+pub fn spadd_cs_prealloc<T>(
+    beta: T,
+    c: &mut CsMatrix<T>,
+    alpha: T,
+    a: &CsMatrix<T>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    zip_lanes(c, a)
+        .into_par_iter()
+        .try_for_each(|(c_lane_i, a_lane_i)| {
+            spadd_cs_lane_prealloc(beta.clone(), c_lane_i, alpha.clone(), a_lane_i)
+        })
+}
+
+/// Computes `C <- beta * C + alpha * A * op(B)`, where `a` is interpreted as a CSR matrix.
+///
+/// The rows of `C` are recursively split into blocks until the work associated with a block
+/// is small enough to be computed sequentially.
+pub fn spmm_cs_dense<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: &CsMatrix<T>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    spmm_cs_dense_split(&beta, c, &alpha, a, 0, &b);
+}
+
+fn spmm_cs_dense_split<T>(
+    beta: &T,
+    mut c: DMatrixViewMut<'_, T>,
+    alpha: &T,
+    a: &CsMatrix<T>,
+    first_row: usize,
+    b: &Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    let offsets = a.pattern().major_offsets();
+    let nnz = offsets[first_row + c.nrows()] - offsets[first_row];
+    let work = (nnz + c.nrows()) * c.ncols();
+    if c.nrows() <= 1 || work <= SEQUENTIAL_WORK_THRESHOLD {
+        spmm_cs_dense_rows(
+            beta.clone(),
+            c,
+            alpha.clone(),
+            a.as_view(),
+            &identity,
+            first_row,
+            b.clone(),
+        );
+    } else {
+        let mid = c.nrows() / 2;
+        let (top, bottom) = c.rows_range_pair_mut(..mid, mid..);
+        rayon::join(
+            || spmm_cs_dense_split(beta, top, alpha, a, first_row, b),
+            || spmm_cs_dense_split(beta, bottom, alpha, a, first_row + mid, b),
+        );
+    }
+}
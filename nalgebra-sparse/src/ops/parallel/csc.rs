@@ -0,0 +1,167 @@
+use crate::csc::CscMatrix;
+use crate::ops::parallel::cs::{
+    spadd_cs_prealloc, spmm_cs_dense, spmm_cs_prealloc, spmm_cs_prealloc_unchecked,
+};
+use crate::ops::serial::{materialize_csc_op, OperationError};
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Parallel version of [`serial::spmm_csc_dense`](crate::ops::serial::spmm_csc_dense).
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csc_dense<'a, T>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    let b = b.convert();
+    spmm_csc_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_csc_dense_<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    // The dense kernel iterates over the rows of op(A), i.e. the lanes of op(A)^T in CSC format
+    match a {
+        Op::NoOp(a) => spmm_cs_dense(beta, c, alpha, &a.transpose().cs, b),
+        Op::Transpose(a) => spmm_cs_dense(beta, c, alpha, &a.cs, b),
+    }
+}
+
+/// Sparse matrix addition `C <- beta * C + alpha * op(A)`.
+///
+/// Parallel version of [`serial::spadd_csc_prealloc`](crate::ops::serial::spadd_csc_prealloc).
+///
+/// # Errors
+///
+/// If the pattern of `c` does not accommodate all the non-zero entries in `a`, an error is
+/// returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spadd_csc_prealloc<T>(
+    beta: T,
+    c: &mut CscMatrix<T>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spadd_dims!(c, a);
+    match a {
+        Op::NoOp(a) => spadd_cs_prealloc(beta, &mut c.cs, alpha, &a.cs),
+        Op::Transpose(a) => spadd_cs_prealloc(beta, &mut c.cs, alpha, &a.transpose().cs),
+    }
+}
+
+/// Sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Parallel version of [`serial::spmm_csc_prealloc`](crate::ops::serial::spmm_csc_prealloc).
+///
+/// # Errors
+///
+/// If the pattern of `C` is not able to hold the result of the operation, an error is returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csc_prealloc<T>(
+    beta: T,
+    c: &mut CscMatrix<T>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+    b: Op<&CscMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+
+    use Op::NoOp;
+
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => {
+            // Note: We have to reverse the order for CSC matrices
+            spmm_cs_prealloc(beta, &mut c.cs, alpha, &b.cs, &a.cs)
+        }
+        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc),
+    }
+}
+
+/// Faster sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
+/// This will not return an error even if the patterns don't match.
+/// Should be used for situations where pattern creation immediately precedes multiplication.
+///
+/// Parallel version of
+/// [`serial::spmm_csc_prealloc_unchecked`](crate::ops::serial::spmm_csc_prealloc_unchecked).
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csc_prealloc_unchecked<T>(
+    beta: T,
+    c: &mut CscMatrix<T>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+    b: Op<&CscMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+
+    use Op::NoOp;
+
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => {
+            // Note: We have to reverse the order for CSC matrices
+            spmm_cs_prealloc_unchecked(beta, &mut c.cs, alpha, &b.cs, &a.cs)
+        }
+        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc_unchecked),
+    }
+}
+
+fn spmm_csc_transposed<T, F>(
+    beta: T,
+    c: &mut CscMatrix<T>,
+    alpha: T,
+    a: Op<&CscMatrix<T>>,
+    b: Op<&CscMatrix<T>>,
+    spmm_kernel: F,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+    F: Fn(
+        T,
+        &mut CscMatrix<T>,
+        T,
+        Op<&CscMatrix<T>>,
+        Op<&CscMatrix<T>>,
+    ) -> Result<(), OperationError>,
+{
+    // As in the serial backend, transposition is handled by explicitly precomputing the
+    // transposed matrices and calling the operation again without transposition
+    let a_owned = materialize_csc_op(a.map_same_op(CscMatrix::as_view));
+    let b_owned = materialize_csc_op(b.map_same_op(CscMatrix::as_view));
+    let a = a_owned.as_ref().unwrap_or_else(|| a.into_inner());
+    let b = b_owned.as_ref().unwrap_or_else(|| b.into_inner());
+    spmm_kernel(beta, c, alpha, Op::NoOp(a), Op::NoOp(b))
+}
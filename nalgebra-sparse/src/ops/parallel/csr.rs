@@ -0,0 +1,160 @@
+use crate::csr::CsrMatrix;
+use crate::ops::parallel::cs::{
+    spadd_cs_prealloc, spmm_cs_dense, spmm_cs_prealloc, spmm_cs_prealloc_unchecked,
+};
+use crate::ops::serial::{materialize_csr_op, OperationError};
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Parallel version of [`serial::spmm_csr_dense`](crate::ops::serial::spmm_csr_dense).
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csr_dense<'a, T>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    let b = b.convert();
+    spmm_csr_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_csr_dense_<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    match a {
+        Op::NoOp(a) => spmm_cs_dense(beta, c, alpha, &a.cs, b),
+        Op::Transpose(a) => spmm_cs_dense(beta, c, alpha, &a.transpose().cs, b),
+    }
+}
+
+/// Sparse matrix addition `C <- beta * C + alpha * op(A)`.
+///
+/// Parallel version of [`serial::spadd_csr_prealloc`](crate::ops::serial::spadd_csr_prealloc).
+///
+/// # Errors
+///
+/// If the pattern of `c` does not accommodate all the non-zero entries in `a`, an error is
+/// returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spadd_csr_prealloc<T>(
+    beta: T,
+    c: &mut CsrMatrix<T>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spadd_dims!(c, a);
+    match a {
+        Op::NoOp(a) => spadd_cs_prealloc(beta, &mut c.cs, alpha, &a.cs),
+        Op::Transpose(a) => spadd_cs_prealloc(beta, &mut c.cs, alpha, &a.transpose().cs),
+    }
+}
+
+/// Sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Parallel version of [`serial::spmm_csr_prealloc`](crate::ops::serial::spmm_csr_prealloc).
+///
+/// # Errors
+///
+/// If the pattern of `C` is not able to hold the result of the operation, an error is returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csr_prealloc<T>(
+    beta: T,
+    c: &mut CsrMatrix<T>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+    b: Op<&CsrMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+
+    use Op::NoOp;
+
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => spmm_cs_prealloc(beta, &mut c.cs, alpha, &a.cs, &b.cs),
+        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc),
+    }
+}
+
+/// Faster sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
+/// This will not return an error even if the patterns don't match.
+/// Should be used for situations where pattern creation immediately precedes multiplication.
+///
+/// Parallel version of
+/// [`serial::spmm_csr_prealloc_unchecked`](crate::ops::serial::spmm_csr_prealloc_unchecked).
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csr_prealloc_unchecked<T>(
+    beta: T,
+    c: &mut CsrMatrix<T>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+    b: Op<&CsrMatrix<T>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+
+    use Op::NoOp;
+
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => spmm_cs_prealloc_unchecked(beta, &mut c.cs, alpha, &a.cs, &b.cs),
+        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc_unchecked),
+    }
+}
+
+fn spmm_csr_transposed<T, F>(
+    beta: T,
+    c: &mut CsrMatrix<T>,
+    alpha: T,
+    a: Op<&CsrMatrix<T>>,
+    b: Op<&CsrMatrix<T>>,
+    spmm_kernel: F,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One + Send + Sync,
+    F: Fn(
+        T,
+        &mut CsrMatrix<T>,
+        T,
+        Op<&CsrMatrix<T>>,
+        Op<&CsrMatrix<T>>,
+    ) -> Result<(), OperationError>,
+{
+    // As in the serial backend, transposition is handled by explicitly precomputing the
+    // transposed matrices and calling the operation again without transposition
+    let a_owned = materialize_csr_op(a.map_same_op(CsrMatrix::as_view));
+    let b_owned = materialize_csr_op(b.map_same_op(CsrMatrix::as_view));
+    let a = a_owned.as_ref().unwrap_or_else(|| a.into_inner());
+    let b = b_owned.as_ref().unwrap_or_else(|| b.into_inner());
+    spmm_kernel(beta, c, alpha, Op::NoOp(a), Op::NoOp(b))
+}
@@ -0,0 +1,53 @@
+//! Parallel sparse matrix arithmetic routines.
+//!
+//! *Only available if compiled with the feature `rayon`.*
+//!
+//! The routines in this module mirror the routines in the [`serial`](crate::ops::serial)
+//! backend. They have the same names, signatures and semantics, but distribute the work across
+//! the threads of the current [rayon](https://docs.rs/rayon) thread pool. Switching between the
+//! backends is therefore a matter of changing an import. For matrices with few non-zeros, the
+//! overhead of distributing the work may outweigh its benefits, so it is worth measuring which
+//! backend is faster for the problem at hand.
+//!
+//! Sparse-sparse operations are parallelized over the major lanes of the output, i.e. over rows
+//! for CSR matrices and over columns for CSC matrices. Sparse-dense products are parallelized
+//! over the rows of the output, which means that sparse matrix-vector products are parallel as
+//! well. Operations that are not naturally expressed in terms of the lanes of the storage
+//! format, such as `op(A) = A^T` for CSR matrices, explicitly transpose the operand first.
+//!
+//! The results are identical to the results of the serial routines, except that sparse-dense
+//! products with transposed CSR (non-transposed CSC) operands may differ by rounding errors.
+//! Errors are reported with the same [`OperationError`](crate::ops::serial::OperationError)
+//! type as the serial routines.
+//!
+//! Triangular solves are inherently sequential and therefore only available in the serial
+//! backend.
+//!
+//! # Example
+//!
+//! ```
+//! use nalgebra_sparse::csr::CsrMatrix;
+//! use nalgebra_sparse::ops::{parallel, serial, Op};
+//! use nalgebra::DVector;
+//!
+//! let a = CsrMatrix::<f64>::identity(1000) * 2.0;
+//! let x = DVector::repeat(1000, 1.0);
+//!
+//! // y <- 2 * A * x, computed in parallel
+//! let mut y = DVector::zeros(1000);
+//! parallel::spmm_csr_dense(0.0, &mut y, 2.0, Op::NoOp(&a), Op::NoOp(&x));
+//! assert_eq!(y, DVector::repeat(1000, 4.0));
+//!
+//! // The sparsity pattern of A * A, computed in parallel
+//! let pattern = parallel::spmm_csr_pattern(a.pattern(), a.pattern());
+//! assert_eq!(pattern, serial::spmm_csr_pattern(a.pattern(), a.pattern()));
+//! ```
+
+mod cs;
+mod csc;
+mod csr;
+mod pattern;
+
+pub use csc::*;
+pub use csr::*;
+pub use pattern::*;
@@ -0,0 +1,89 @@
+use crate::ops::serial::pattern::{iterate_union, spmm_pattern_lane};
+use crate::pattern::SparsityPattern;
+use rayon::prelude::*;
+
+/// Sparse matrix addition pattern construction, `C <- A + B`.
+///
+/// Parallel version of [`serial::spadd_pattern`](crate::ops::serial::spadd_pattern).
+///
+/// # Panics
+///
+/// Panics if the patterns do not have the same major and minor dimensions.
+pub fn spadd_pattern(a: &SparsityPattern, b: &SparsityPattern) -> SparsityPattern {
+    assert_eq!(
+        a.major_dim(),
+        b.major_dim(),
+        "Patterns must have identical major dimensions."
+    );
+    assert_eq!(
+        a.minor_dim(),
+        b.minor_dim(),
+        "Patterns must have identical minor dimensions."
+    );
+
+    let lanes = (0..a.major_dim())
+        .into_par_iter()
+        .map(|lane_idx| iterate_union(a.lane(lane_idx), b.lane(lane_idx)).collect())
+        .collect();
+    pattern_from_lanes(a.major_dim(), a.minor_dim(), lanes)
+}
+
+/// Sparse matrix multiplication pattern construction, `C <- A * B`.
+///
+/// Parallel version of [`serial::spmm_csc_pattern`](crate::ops::serial::spmm_csc_pattern).
+///
+/// # Panics
+///
+/// Panics if the patterns, when interpreted as CSC patterns, are not compatible for
+/// matrix multiplication.
+pub fn spmm_csc_pattern(a: &SparsityPattern, b: &SparsityPattern) -> SparsityPattern {
+    // See the serial version for why this works
+    spmm_csr_pattern(b, a)
+}
+
+/// Sparse matrix multiplication pattern construction, `C <- A * B`.
+///
+/// Parallel version of [`serial::spmm_csr_pattern`](crate::ops::serial::spmm_csr_pattern).
+///
+/// # Panics
+///
+/// Panics if the patterns, when interpreted as CSR patterns, are not compatible for
+/// matrix multiplication.
+pub fn spmm_csr_pattern(a: &SparsityPattern, b: &SparsityPattern) -> SparsityPattern {
+    assert_eq!(
+        a.minor_dim(),
+        b.major_dim(),
+        "a and b must have compatible dimensions"
+    );
+
+    // Each thread keeps its own record of visited minor indices
+    let lanes = (0..a.major_dim())
+        .into_par_iter()
+        .map_init(
+            || vec![false; b.minor_dim()],
+            |visited, i| {
+                let mut indices = Vec::new();
+                spmm_pattern_lane(a.lane(i), &|k| b.lane(k), visited, &mut indices);
+                indices
+            },
+        )
+        .collect();
+    pattern_from_lanes(a.major_dim(), b.minor_dim(), lanes)
+}
+
+/// Assembles a sparsity pattern from the (sorted) minor indices of each major lane.
+fn pattern_from_lanes(
+    major_dim: usize,
+    minor_dim: usize,
+    lanes: Vec<Vec<usize>>,
+) -> SparsityPattern {
+    let mut offsets = Vec::with_capacity(major_dim + 1);
+    offsets.push(0);
+    for lane in &lanes {
+        offsets.push(offsets.last().unwrap() + lane.len());
+    }
+    let indices = lanes.concat();
+
+    SparsityPattern::try_from_offsets_and_indices(major_dim, minor_dim, offsets, indices)
+        .expect("Internal error: Pattern must be valid by definition")
+}
@@ -0,0 +1,53 @@
+use crate::bsr::BsrMatrix;
+use crate::ops::serial::spmm_dense_columnwise;
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Each stored block of `A` is multiplied with the corresponding segment of a column of `op(B)`
+/// as a dense `B x B` matrix-vector product. With a single column, this is a sparse
+/// matrix-vector product.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_bsr_dense<'a, T, const B: usize>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&BsrMatrix<T, B>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    let b = b.convert();
+    spmm_bsr_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_bsr_dense_<T, const B: usize>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&BsrMatrix<T, B>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    match a {
+        Op::NoOp(a) => spmm_dense_columnwise(beta, c, alpha, b, |x, y| {
+            for (bi, bj, block) in a.block_iter() {
+                let mut y_i = y.fixed_rows_mut::<B>(bi * B);
+                y_i += block * x.fixed_rows::<B>(bj * B);
+            }
+        }),
+        Op::Transpose(a) => spmm_dense_columnwise(beta, c, alpha, b, |x, y| {
+            for (bi, bj, block) in a.block_iter() {
+                let mut y_j = y.fixed_rows_mut::<B>(bj * B);
+                y_j += block.tr_mul(&x.fixed_rows::<B>(bi * B));
+            }
+        }),
+    }
+}
@@ -1,9 +1,14 @@
-use crate::cs::CsMatrix;
+use crate::cs::{CsLane, CsLaneMut, CsMatrix, CsMatrixView, CsMatrixViewMut};
 use crate::ops::serial::{OperationError, OperationErrorKind};
 use crate::ops::Op;
+use crate::pattern::{SparseIndex, SparsityPattern};
+use crate::vector::SparseVector;
 use crate::SparseEntryMut;
-use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use nalgebra::{
+    ClosedAdd, ClosedDiv, ClosedMul, ClosedSub, ComplexField, DMatrixView, DMatrixViewMut, Scalar,
+};
 use num_traits::{One, Zero};
+use std::mem::replace;
 
 fn spmm_cs_unexpected_entry() -> OperationError {
     OperationError::from_kind_and_message(
@@ -20,84 +25,128 @@ fn spmm_cs_unexpected_entry() -> OperationError {
 /// reversed (since transpose(AB) = transpose(B) * transpose(A) and CSC(A) = transpose(CSR(A)).
 ///
 /// We assume here that the matrices have already been verified to be dimensionally compatible.
-pub fn spmm_cs_prealloc_unchecked<T>(
+pub fn spmm_cs_prealloc_unchecked<T, I>(
     beta: T,
-    c: &mut CsMatrix<T>,
+    mut c: CsMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: &CsMatrix<T>,
-    b: &CsMatrix<T>,
+    a: CsMatrixView<'_, T, I>,
+    b: CsMatrixView<'_, T, I>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
-    assert_eq!(c.pattern().major_dim(), a.pattern().major_dim());
-    assert_eq!(c.pattern().minor_dim(), b.pattern().minor_dim());
+    assert_eq!(c.major_dim(), a.major_dim());
+    assert_eq!(c.minor_dim(), b.minor_dim());
     let some_val = Zero::zero();
-    let mut scratchpad_values: Vec<T> = vec![some_val; b.pattern().minor_dim()];
-    for i in 0..c.pattern().major_dim() {
+    let mut scratchpad_values: Vec<T> = vec![some_val; b.minor_dim()];
+    for i in 0..c.major_dim() {
         let a_lane_i = a.get_lane(i).unwrap();
+        let c_lane_i = c.get_lane_mut(i).unwrap();
+        spmm_cs_lane_prealloc_unchecked(
+            beta.clone(),
+            c_lane_i,
+            alpha.clone(),
+            a_lane_i,
+            b,
+            &mut scratchpad_values,
+        );
+    }
 
-        let mut c_lane_i = c.get_lane_mut(i).unwrap();
+    Ok(())
+}
 
-        for (&k, a_ik) in a_lane_i.minor_indices().iter().zip(a_lane_i.values()) {
-            let b_lane_k = b.get_lane(k).unwrap();
-            let alpha_aik = alpha.clone() * a_ik.clone();
-            for (j, b_kj) in b_lane_k.minor_indices().iter().zip(b_lane_k.values()) {
-                // use a dense scatter vector to accumulate non-zeros quickly
-                unsafe {
-                    *scratchpad_values.get_unchecked_mut(*j) += alpha_aik.clone() * b_kj.clone();
-                }
+/// Computes a single lane of `C <- beta * C + alpha * A * B` in CSR-centric terms, without
+/// checking that the lane of `C` is able to hold the result.
+///
+/// The scratchpad must have length `b.minor_dim()` and contain only zeros. This is
+/// also the case when the function returns.
+pub(in crate::ops) fn spmm_cs_lane_prealloc_unchecked<T, I>(
+    beta: T,
+    mut c_lane_i: CsLaneMut<'_, T, I>,
+    alpha: T,
+    a_lane_i: CsLane<'_, T, I>,
+    b: CsMatrixView<'_, T, I>,
+    scratchpad_values: &mut [T],
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    for (&k, a_ik) in a_lane_i.minor_indices().iter().zip(a_lane_i.values()) {
+        let b_lane_k = b.get_lane(k.index()).unwrap();
+        let alpha_aik = alpha.clone() * a_ik.clone();
+        for (j, b_kj) in b_lane_k.minor_indices().iter().zip(b_lane_k.values()) {
+            // use a dense scatter vector to accumulate non-zeros quickly
+            unsafe {
+                *scratchpad_values.get_unchecked_mut(j.index()) += alpha_aik.clone() * b_kj.clone();
             }
         }
+    }
+
+    //Get indices from C pattern and gather from the dense scratchpad_values
+    let (indices, values) = c_lane_i.indices_and_values_mut();
+    values
+        .iter_mut()
+        .zip(indices)
+        .for_each(|(output_ref, index)| unsafe {
+            let index = index.index();
+            *output_ref =
+                beta.clone() * output_ref.clone() + scratchpad_values.get_unchecked(index).clone();
+            *scratchpad_values.get_unchecked_mut(index) = Zero::zero();
+        });
+}
 
-        //Get indices from C pattern and gather from the dense scratchpad_values
-        let (indices, values) = c_lane_i.indices_and_values_mut();
-        values
-            .iter_mut()
-            .zip(indices)
-            .for_each(|(output_ref, index)| unsafe {
-                *output_ref = beta.clone() * output_ref.clone()
-                    + scratchpad_values.get_unchecked(*index).clone();
-                *scratchpad_values.get_unchecked_mut(*index) = Zero::zero();
-            });
+pub fn spmm_cs_prealloc<T, I>(
+    beta: T,
+    mut c: CsMatrixViewMut<'_, T, I>,
+    alpha: T,
+    a: CsMatrixView<'_, T, I>,
+    b: CsMatrixView<'_, T, I>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    for i in 0..c.major_dim() {
+        let a_lane_i = a.get_lane(i).unwrap();
+        let c_lane_i = c.get_lane_mut(i).unwrap();
+        spmm_cs_lane_prealloc(beta.clone(), c_lane_i, alpha.clone(), a_lane_i, b)?;
     }
 
     Ok(())
 }
 
-pub fn spmm_cs_prealloc<T>(
+/// Computes a single lane of `C <- beta * C + alpha * A * B` in CSR-centric terms.
+pub(in crate::ops) fn spmm_cs_lane_prealloc<T, I>(
     beta: T,
-    c: &mut CsMatrix<T>,
+    mut c_lane_i: CsLaneMut<'_, T, I>,
     alpha: T,
-    a: &CsMatrix<T>,
-    b: &CsMatrix<T>,
+    a_lane_i: CsLane<'_, T, I>,
+    b: CsMatrixView<'_, T, I>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
-    for i in 0..c.pattern().major_dim() {
-        let a_lane_i = a.get_lane(i).unwrap();
-        let mut c_lane_i = c.get_lane_mut(i).unwrap();
-        for c_ij in c_lane_i.values_mut() {
-            *c_ij = beta.clone() * c_ij.clone();
-        }
+    for c_ij in c_lane_i.values_mut() {
+        *c_ij = beta.clone() * c_ij.clone();
+    }
 
-        for (&k, a_ik) in a_lane_i.minor_indices().iter().zip(a_lane_i.values()) {
-            let b_lane_k = b.get_lane(k).unwrap();
-            let (mut c_lane_i_cols, mut c_lane_i_values) = c_lane_i.indices_and_values_mut();
-            let alpha_aik = alpha.clone() * a_ik.clone();
-            for (j, b_kj) in b_lane_k.minor_indices().iter().zip(b_lane_k.values()) {
-                // Determine the location in C to append the value
-                let (c_local_idx, _) = c_lane_i_cols
-                    .iter()
-                    .enumerate()
-                    .find(|(_, c_col)| *c_col == j)
-                    .ok_or_else(spmm_cs_unexpected_entry)?;
+    for (&k, a_ik) in a_lane_i.minor_indices().iter().zip(a_lane_i.values()) {
+        let b_lane_k = b.get_lane(k.index()).unwrap();
+        let (mut c_lane_i_cols, mut c_lane_i_values) = c_lane_i.indices_and_values_mut();
+        let alpha_aik = alpha.clone() * a_ik.clone();
+        for (j, b_kj) in b_lane_k.minor_indices().iter().zip(b_lane_k.values()) {
+            // Determine the location in C to append the value
+            let (c_local_idx, _) = c_lane_i_cols
+                .iter()
+                .enumerate()
+                .find(|(_, c_col)| *c_col == j)
+                .ok_or_else(spmm_cs_unexpected_entry)?;
 
-                c_lane_i_values[c_local_idx] += alpha_aik.clone() * b_kj.clone();
-                c_lane_i_cols = &c_lane_i_cols[c_local_idx..];
-                c_lane_i_values = &mut c_lane_i_values[c_local_idx..];
-            }
+            c_lane_i_values[c_local_idx] += alpha_aik.clone() * b_kj.clone();
+            c_lane_i_cols = &c_lane_i_cols[c_local_idx..];
+            c_lane_i_values = &mut c_lane_i_values[c_local_idx..];
         }
     }
 
@@ -112,40 +161,25 @@ fn spadd_cs_unexpected_entry() -> OperationError {
 }
 
 /// Helper functionality for implementing CSR/CSC SPADD.
-pub fn spadd_cs_prealloc<T>(
+///
+/// The entries of `A` are passed through `map_a` when transposed, which lets the same kernel
+/// compute the sum with the adjoint of `A`.
+pub fn spadd_cs_prealloc<T, I, F>(
     beta: T,
-    c: &mut CsMatrix<T>,
+    mut c: CsMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: Op<&CsMatrix<T>>,
+    a: Op<CsMatrixView<'_, T, I>>,
+    map_a: F,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+    F: Fn(T) -> T,
 {
     match a {
         Op::NoOp(a) => {
-            for (mut c_lane_i, a_lane_i) in c.lane_iter_mut().zip(a.lane_iter()) {
-                if beta != T::one() {
-                    for c_ij in c_lane_i.values_mut() {
-                        *c_ij *= beta.clone();
-                    }
-                }
-
-                let (mut c_minors, mut c_vals) = c_lane_i.indices_and_values_mut();
-                let (a_minors, a_vals) = (a_lane_i.minor_indices(), a_lane_i.values());
-
-                for (a_col, a_val) in a_minors.iter().zip(a_vals) {
-                    // TODO: Use exponential search instead of linear search.
-                    // If C has substantially more entries in the row than A, then a line search
-                    // will needlessly visit many entries in C.
-                    let (c_idx, _) = c_minors
-                        .iter()
-                        .enumerate()
-                        .find(|(_, c_col)| *c_col == a_col)
-                        .ok_or_else(spadd_cs_unexpected_entry)?;
-                    c_vals[c_idx] += alpha.clone() * a_val.clone();
-                    c_minors = &c_minors[c_idx..];
-                    c_vals = &mut c_vals[c_idx..];
-                }
+            for (c_lane_i, a_lane_i) in c.lane_iter_mut().zip(a.lane_iter()) {
+                spadd_cs_lane_prealloc(beta.clone(), c_lane_i, alpha.clone(), a_lane_i)?;
             }
         }
         Op::Transpose(a) => {
@@ -157,9 +191,9 @@ where
 
             for (i, a_lane_i) in a.lane_iter().enumerate() {
                 for (&j, a_val) in a_lane_i.minor_indices().iter().zip(a_lane_i.values()) {
-                    let a_val = a_val.clone();
+                    let a_val = map_a(a_val.clone());
                     let alpha = alpha.clone();
-                    match c.get_entry_mut(j, i).unwrap() {
+                    match c.get_entry_mut(j.index(), i).unwrap() {
                         SparseEntryMut::NonZero(c_ji) => *c_ji += alpha * a_val,
                         SparseEntryMut::Zero => return Err(spadd_cs_unexpected_entry()),
                     }
@@ -170,61 +204,502 @@ where
     Ok(())
 }
 
+/// Computes a single lane of `C <- beta * C + alpha * A`.
+pub(in crate::ops) fn spadd_cs_lane_prealloc<T, I>(
+    beta: T,
+    mut c_lane_i: CsLaneMut<'_, T, I>,
+    alpha: T,
+    a_lane_i: CsLane<'_, T, I>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    if beta != T::one() {
+        for c_ij in c_lane_i.values_mut() {
+            *c_ij *= beta.clone();
+        }
+    }
+
+    let (mut c_minors, mut c_vals) = c_lane_i.indices_and_values_mut();
+    let (a_minors, a_vals) = (a_lane_i.minor_indices(), a_lane_i.values());
+
+    for (a_col, a_val) in a_minors.iter().zip(a_vals) {
+        // TODO: Use exponential search instead of linear search.
+        // If C has substantially more entries in the row than A, then a line search
+        // will needlessly visit many entries in C.
+        let (c_idx, _) = c_minors
+            .iter()
+            .enumerate()
+            .find(|(_, c_col)| *c_col == a_col)
+            .ok_or_else(spadd_cs_unexpected_entry)?;
+        c_vals[c_idx] += alpha.clone() * a_val.clone();
+        c_minors = &c_minors[c_idx..];
+        c_vals = &mut c_vals[c_idx..];
+    }
+
+    Ok(())
+}
+
 /// Helper functionality for implementing CSR/CSC SPMM.
 ///
-/// The implementation essentially assumes that `a` is a CSR matrix. To use it with CSC matrices,
-/// the transposed operation must be specified for the CSC matrix.
-pub fn spmm_cs_dense<T>(
+/// The implementation essentially assumes that `a` is a CSR matrix, which is transposed if
+/// `transpose_a` is set and whose entries are passed through `map_a`, e.g. to conjugate them. To
+/// use it with CSC matrices, the transposition must be inverted for the CSC matrix.
+pub fn spmm_cs_dense<T, I, F>(
     beta: T,
     mut c: DMatrixViewMut<'_, T>,
     alpha: T,
-    a: Op<&CsMatrix<T>>,
+    a: CsMatrixView<'_, T, I>,
+    transpose_a: bool,
+    map_a: F,
     b: Op<DMatrixView<'_, T>>,
 ) where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+    F: Fn(T) -> T,
 {
-    match a {
-        Op::NoOp(a) => {
-            for j in 0..c.ncols() {
-                let mut c_col_j = c.column_mut(j);
-                for (c_ij, a_row_i) in c_col_j.iter_mut().zip(a.lane_iter()) {
-                    let mut dot_ij = T::zero();
-                    for (&k, a_ik) in a_row_i.minor_indices().iter().zip(a_row_i.values()) {
-                        let b_contrib = match b {
-                            Op::NoOp(ref b) => b.index((k, j)),
-                            Op::Transpose(ref b) => b.index((j, k)),
-                        };
-                        dot_ij += a_ik.clone() * b_contrib.clone();
+    if !transpose_a {
+        return spmm_cs_dense_rows(beta, c, alpha, a, &map_a, 0, b);
+    }
+
+    // In this case, we have to pre-multiply C by beta
+    c *= beta;
+
+    for k in 0..a.major_dim() {
+        let a_row_k = a.get_lane(k).unwrap();
+        for (&i, a_ki) in a_row_k.minor_indices().iter().zip(a_row_k.values()) {
+            let gamma_ki = alpha.clone() * map_a(a_ki.clone());
+            let mut c_row_i = c.row_mut(i.index());
+            match b {
+                Op::NoOp(ref b) => {
+                    let b_row_k = b.row(k);
+                    for (c_ij, b_kj) in c_row_i.iter_mut().zip(b_row_k.iter()) {
+                        *c_ij += gamma_ki.clone() * b_kj.clone();
+                    }
+                }
+                Op::Transpose(ref b) => {
+                    let b_col_k = b.column(k);
+                    for (c_ij, b_jk) in c_row_i.iter_mut().zip(b_col_k.iter()) {
+                        *c_ij += gamma_ki.clone() * b_jk.clone();
                     }
-                    *c_ij = beta.clone() * c_ij.clone() + alpha.clone() * dot_ij;
                 }
             }
         }
-        Op::Transpose(a) => {
-            // In this case, we have to pre-multiply C by beta
-            c *= beta;
-
-            for k in 0..a.pattern().major_dim() {
-                let a_row_k = a.get_lane(k).unwrap();
-                for (&i, a_ki) in a_row_k.minor_indices().iter().zip(a_row_k.values()) {
-                    let gamma_ki = alpha.clone() * a_ki.clone();
-                    let mut c_row_i = c.row_mut(i);
-                    match b {
-                        Op::NoOp(ref b) => {
-                            let b_row_k = b.row(k);
-                            for (c_ij, b_kj) in c_row_i.iter_mut().zip(b_row_k.iter()) {
-                                *c_ij += gamma_ki.clone() * b_kj.clone();
-                            }
-                        }
-                        Op::Transpose(ref b) => {
-                            let b_col_k = b.column(k);
-                            for (c_ij, b_jk) in c_row_i.iter_mut().zip(b_col_k.iter()) {
-                                *c_ij += gamma_ki.clone() * b_jk.clone();
-                            }
-                        }
+    }
+}
+
+/// Computes the rows `first_row..first_row + c.nrows()` of `C <- beta * C + alpha * A * op(B)`,
+/// where `c` holds only these rows of `C` and `a` is interpreted as a CSR matrix, whose entries
+/// are passed through `map_a`.
+pub(in crate::ops) fn spmm_cs_dense_rows<T, I, F>(
+    beta: T,
+    mut c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: CsMatrixView<'_, T, I>,
+    map_a: &F,
+    first_row: usize,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+    F: Fn(T) -> T,
+{
+    for j in 0..c.ncols() {
+        let mut c_col_j = c.column_mut(j);
+        for (i, c_ij) in c_col_j.iter_mut().enumerate() {
+            let a_row_i = a.get_lane(first_row + i).unwrap();
+            let mut dot_ij = T::zero();
+            for (&k, a_ik) in a_row_i.minor_indices().iter().zip(a_row_i.values()) {
+                let k = k.index();
+                let b_contrib = match b {
+                    Op::NoOp(ref b) => b.index((k, j)),
+                    Op::Transpose(ref b) => b.index((j, k)),
+                };
+                dot_ij += map_a(a_ik.clone()) * b_contrib.clone();
+            }
+            *c_ij = beta.clone() * c_ij.clone() + alpha.clone() * dot_ij;
+        }
+    }
+}
+
+/// Helper functionality for implementing CSR/CSC sparse matrix-sparse vector products.
+///
+/// Computes `y = op(A) * x`, where `a` is interpreted as a CSR matrix, which is transposed if
+/// `transpose_a` is set and whose entries are passed through `map_a`. The result has an explicit
+/// entry for every index that is structurally reachable from the pattern of `x`.
+pub fn spmv_cs_sparse<T, I, F>(
+    a: CsMatrixView<'_, T, I>,
+    transpose_a: bool,
+    map_a: F,
+    x: &SparseVector<T>,
+) -> SparseVector<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero,
+    I: SparseIndex,
+    F: Fn(T) -> T,
+{
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+
+    if !transpose_a {
+        // Every entry of the result is the sparse dot product of a row of A with x
+        for i in 0..a.major_dim() {
+            let a_row_i = a.get_lane(i).unwrap();
+            let (a_indices, a_values) = (a_row_i.minor_indices(), a_row_i.values());
+            let (x_indices, x_values) = (x.indices(), x.values());
+            let mut dot = None;
+            let (mut p, mut q) = (0, 0);
+            while p < a_indices.len() && q < x_indices.len() {
+                let k = a_indices[p].index();
+                if k < x_indices[q] {
+                    p += 1;
+                } else if k > x_indices[q] {
+                    q += 1;
+                } else {
+                    let product = map_a(a_values[p].clone()) * x_values[q].clone();
+                    dot = Some(match dot {
+                        Some(dot) => dot + product,
+                        None => product,
+                    });
+                    p += 1;
+                    q += 1;
+                }
+            }
+            if let Some(dot) = dot {
+                indices.push(i);
+                values.push(dot);
+            }
+        }
+        return SparseVector::try_from_indices_and_values(a.major_dim(), indices, values)
+            .expect("Internal error: Invalid sparse vector data in sparse matrix-vector product");
+    }
+
+    // The result is a linear combination of the rows of A selected by x, which we accumulate
+    // in a dense workspace while recording the touched indices
+    let mut workspace = vec![T::zero(); a.minor_dim()];
+    let mut touched = vec![false; a.minor_dim()];
+    for (k, x_k) in x.iter() {
+        let a_row_k = a.get_lane(k).unwrap();
+        for (&j, a_kj) in a_row_k.minor_indices().iter().zip(a_row_k.values()) {
+            let j = j.index();
+            workspace[j] += map_a(a_kj.clone()) * x_k.clone();
+            if !touched[j] {
+                touched[j] = true;
+                indices.push(j);
+            }
+        }
+    }
+    indices.sort_unstable();
+    values.extend(indices.iter().map(|&j| workspace[j].clone()));
+    SparseVector::try_from_indices_and_values(a.minor_dim(), indices, values)
+        .expect("Internal error: Invalid sparse vector data in sparse matrix-vector product")
+}
+
+/// Helper functionality for implementing element-wise CSR/CSC operations on the union of the
+/// sparsity patterns of `A` and `B`.
+///
+/// Entries that are explicitly stored in only one of the matrices are combined with zero.
+pub fn elementwise_union_cs<T, U, F>(
+    a: CsMatrixView<'_, T>,
+    b: CsMatrixView<'_, T>,
+    mut f: F,
+) -> CsMatrix<U>
+where
+    T: Scalar + Zero,
+    F: FnMut(T, T) -> U,
+{
+    let mut offsets = Vec::with_capacity(a.major_dim() + 1);
+    let mut indices = Vec::with_capacity(a.nnz().max(b.nnz()));
+    let mut values = Vec::with_capacity(indices.capacity());
+    offsets.push(0);
+
+    for (a_lane, b_lane) in a.lane_iter().zip(b.lane_iter()) {
+        let (a_minors, a_vals) = (a_lane.minor_indices(), a_lane.values());
+        let (b_minors, b_vals) = (b_lane.minor_indices(), b_lane.values());
+        let (mut ka, mut kb) = (0, 0);
+        while ka < a_minors.len() || kb < b_minors.len() {
+            let ja = a_minors.get(ka).copied().unwrap_or(usize::MAX);
+            let jb = b_minors.get(kb).copied().unwrap_or(usize::MAX);
+            let (j, value) = if ja < jb {
+                ka += 1;
+                (ja, f(a_vals[ka - 1].clone(), T::zero()))
+            } else if jb < ja {
+                kb += 1;
+                (jb, f(T::zero(), b_vals[kb - 1].clone()))
+            } else {
+                ka += 1;
+                kb += 1;
+                (ja, f(a_vals[ka - 1].clone(), b_vals[kb - 1].clone()))
+            };
+            indices.push(j);
+            values.push(value);
+        }
+        offsets.push(indices.len());
+    }
+
+    elementwise_result(a, offsets, indices, values)
+}
+
+/// Helper functionality for implementing element-wise CSR/CSC operations on the intersection of
+/// the sparsity patterns of `A` and `B`.
+pub fn elementwise_intersection_cs<T, U, F>(
+    a: CsMatrixView<'_, T>,
+    b: CsMatrixView<'_, T>,
+    mut f: F,
+) -> CsMatrix<U>
+where
+    T: Scalar,
+    F: FnMut(T, T) -> U,
+{
+    let mut offsets = Vec::with_capacity(a.major_dim() + 1);
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+    offsets.push(0);
+
+    for (a_lane, b_lane) in a.lane_iter().zip(b.lane_iter()) {
+        let (a_minors, a_vals) = (a_lane.minor_indices(), a_lane.values());
+        let (b_minors, b_vals) = (b_lane.minor_indices(), b_lane.values());
+        let (mut ka, mut kb) = (0, 0);
+        while ka < a_minors.len() && kb < b_minors.len() {
+            let (ja, jb) = (a_minors[ka], b_minors[kb]);
+            if ja < jb {
+                ka += 1;
+            } else if jb < ja {
+                kb += 1;
+            } else {
+                indices.push(ja);
+                values.push(f(a_vals[ka].clone(), b_vals[kb].clone()));
+                ka += 1;
+                kb += 1;
+            }
+        }
+        offsets.push(indices.len());
+    }
+
+    elementwise_result(a, offsets, indices, values)
+}
+
+fn elementwise_result<T, U>(
+    a: CsMatrixView<'_, T>,
+    offsets: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<U>,
+) -> CsMatrix<U> {
+    let (major_dim, minor_dim) = (a.major_dim(), a.minor_dim());
+    // TODO: Avoid checks here
+    let pattern =
+        SparsityPattern::try_from_offsets_and_indices(major_dim, minor_dim, offsets, indices)
+            .expect("Internal error: Element-wise operations must always produce valid patterns.");
+    CsMatrix::from_pattern_and_values(pattern, values)
+}
+
+pub(in crate::ops) fn spsolve_encountered_zero_diagonal<T>() -> Result<T, OperationError> {
+    let message = "Matrix contains at least one diagonal entry that is zero.";
+    Err(OperationError::from_kind_and_message(
+        OperationErrorKind::Singular,
+        String::from(message),
+    ))
+}
+
+/// Returns the diagonal entry of the given lane, or an error if it is missing or zero.
+fn triangular_lane_diagonal<T>(lane: &CsLane<'_, T>, index: usize) -> Result<T, OperationError>
+where
+    T: Scalar + Zero,
+{
+    let diag = lane
+        .minor_indices()
+        .iter()
+        .position(|&j| j == index)
+        .map(|k| lane.values()[k].clone());
+    match diag {
+        Some(diag) if diag != T::zero() => Ok(diag),
+        _ => spsolve_encountered_zero_diagonal(),
+    }
+}
+
+/// Solves the triangular system `op(S) X = B` in place, where `S` is the matrix whose rows are
+/// the lanes of `a`, and `op(S)` is `S` or its transpose, with conjugated entries if
+/// `conjugate` is set.
+///
+/// Only the lower (`lower == true`) or upper triangular part of `S` is read. If `unit_diagonal`
+/// is set, the diagonal entries are not read and assumed to be one.
+pub(in crate::ops) fn spsolve_cs_triangular<T>(
+    a: CsMatrixView<'_, T>,
+    lower: bool,
+    transpose: bool,
+    conjugate: bool,
+    unit_diagonal: bool,
+    mut b: DMatrixViewMut<'_, T>,
+) -> Result<(), OperationError>
+where
+    T: ComplexField,
+{
+    let n = a.major_dim();
+    let in_triangle = |i: usize, j: usize| if lower { j < i } else { j > i };
+    let op = |s: &T| {
+        if conjugate {
+            s.clone().conjugate()
+        } else {
+            s.clone()
+        }
+    };
+
+    for c in 0..b.ncols() {
+        let mut x = b.column_mut(c);
+        if !transpose {
+            // The lanes are the rows of the system, so each unknown is computed from the
+            // previously computed ones. Lower triangular systems are solved front to back.
+            for step in 0..n {
+                let i = if lower { step } else { n - 1 - step };
+                let lane = a.get_lane(i).unwrap();
+                let mut x_i = x[i].clone();
+                for (&j, s_ij) in lane.minor_indices().iter().zip(lane.values()) {
+                    if in_triangle(i, j) {
+                        x_i -= op(s_ij) * x[j].clone();
+                    }
+                }
+                if !unit_diagonal {
+                    x_i /= op(&triangular_lane_diagonal(&lane, i)?);
+                }
+                x[i] = x_i;
+            }
+        } else {
+            // The lanes are the columns of the system, so each computed unknown is eliminated
+            // from the remaining ones. The transpose of a lower triangular matrix is upper
+            // triangular, so it is solved back to front.
+            for step in 0..n {
+                let k = if lower { n - 1 - step } else { step };
+                let lane = a.get_lane(k).unwrap();
+                if !unit_diagonal {
+                    let diag = triangular_lane_diagonal(&lane, k)?;
+                    x[k] /= op(&diag);
+                }
+                let x_k = x[k].clone();
+                for (&j, s_kj) in lane.minor_indices().iter().zip(lane.values()) {
+                    if in_triangle(k, j) {
+                        x[j] -= op(s_kj) * x_k.clone();
                     }
                 }
             }
         }
     }
+
+    Ok(())
+}
+
+/// Solves the triangular system `S X = B` with sparse `B`, where `S` is the matrix whose
+/// columns are the lanes of `a`, and the columns of `B` and `X` are the lanes of `b` and the
+/// result.
+///
+/// This is the algorithm of Gilbert and Peierls: the sparsity pattern of each column of `X` is
+/// the set of nodes reachable from the pattern of the corresponding column of `B` in the graph
+/// of `S`, and a depth-first search gives these nodes in topological order. The cost is
+/// therefore proportional to the number of floating-point operations, and independent of the
+/// dimensions of the system.
+///
+/// Only the lower (`lower == true`) or upper triangular part of `S` is read. If `unit_diagonal`
+/// is set, the diagonal entries are not read and assumed to be one.
+pub(in crate::ops) fn spsolve_cs_triangular_sparse<T>(
+    a: CsMatrixView<'_, T>,
+    lower: bool,
+    unit_diagonal: bool,
+    b: CsMatrixView<'_, T>,
+) -> Result<CsMatrix<T>, OperationError>
+where
+    T: Scalar + Zero + ClosedSub + ClosedMul + ClosedDiv,
+{
+    let n = a.major_dim();
+    let in_triangle = |k: usize, j: usize| if lower { j > k } else { j < k };
+
+    // Workspaces, which are reset after each column
+    let mut x = vec![T::zero(); n];
+    let mut marks = vec![false; n];
+    let mut stack = Vec::new();
+    let mut reach = Vec::new();
+
+    let mut offsets = Vec::with_capacity(b.major_dim() + 1);
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+    offsets.push(0);
+
+    for b_lane in b.lane_iter() {
+        for &start in b_lane.minor_indices() {
+            if !marks[start] {
+                triangular_reach(a, start, &in_triangle, &mut marks, &mut stack, &mut reach);
+            }
+        }
+        for (&i, b_i) in b_lane.minor_indices().iter().zip(b_lane.values()) {
+            x[i] = b_i.clone();
+        }
+
+        // The reverse post-order of the search is a topological order
+        for &k in reach.iter().rev() {
+            let lane = a.get_lane(k).unwrap();
+            if !unit_diagonal {
+                let diag = triangular_lane_diagonal(&lane, k)?;
+                x[k] /= diag;
+            }
+            let x_k = x[k].clone();
+            for (&j, s_jk) in lane.minor_indices().iter().zip(lane.values()) {
+                if in_triangle(k, j) {
+                    x[j] -= s_jk.clone() * x_k.clone();
+                }
+            }
+        }
+
+        reach.sort_unstable();
+        for &i in &reach {
+            indices.push(i);
+            values.push(replace(&mut x[i], T::zero()));
+            marks[i] = false;
+        }
+        reach.clear();
+        offsets.push(indices.len());
+    }
+
+    // TODO: Avoid checks here
+    let pattern = SparsityPattern::try_from_offsets_and_indices(b.major_dim(), n, offsets, indices)
+        .expect("Internal error: Triangular solves must always produce valid patterns.");
+    Ok(CsMatrix::from_pattern_and_values(pattern, values))
+}
+
+/// Appends the unmarked nodes that are reachable from `start` to `reach` in post-order, using
+/// an iterative depth-first search over the edges `k -> j` of the lanes `k` of `a` for
+/// which `in_triangle(k, j)` holds.
+fn triangular_reach<T>(
+    a: CsMatrixView<'_, T>,
+    start: usize,
+    in_triangle: &impl Fn(usize, usize) -> bool,
+    marks: &mut [bool],
+    stack: &mut Vec<(usize, usize)>,
+    reach: &mut Vec<usize>,
+) {
+    marks[start] = true;
+    stack.push((start, 0));
+    while let Some(&(k, mut pos)) = stack.last() {
+        let lane = a.lane_indices(k);
+        let mut child = None;
+        while pos < lane.len() {
+            let j = lane[pos];
+            pos += 1;
+            if in_triangle(k, j) && !marks[j] {
+                child = Some(j);
+                break;
+            }
+        }
+
+        let top = stack.len() - 1;
+        stack[top].1 = pos;
+        match child {
+            Some(j) => {
+                marks[j] = true;
+                stack.push((j, 0));
+            }
+            None => {
+                stack.pop();
+                reach.push(k);
+            }
+        }
+    }
 }
@@ -1,45 +1,126 @@
-use crate::csc::CscMatrix;
+use crate::csc::{CscMatrix, CscMatrixView, CscMatrixViewMut};
 use crate::ops::serial::cs::{
-    spadd_cs_prealloc, spmm_cs_dense, spmm_cs_prealloc, spmm_cs_prealloc_unchecked,
+    elementwise_intersection_cs, elementwise_union_cs, spadd_cs_prealloc, spmm_cs_dense,
+    spmm_cs_prealloc, spmm_cs_prealloc_unchecked, spmv_cs_sparse, spsolve_cs_triangular,
+    spsolve_cs_triangular_sparse,
 };
-use crate::ops::serial::{OperationError, OperationErrorKind};
+use crate::ops::serial::OperationError;
 use crate::ops::Op;
-use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, RealField, Scalar};
+use crate::pattern::SparseIndex;
+use crate::vector::SparseVector;
+use nalgebra::{ClosedAdd, ClosedMul, ComplexField, DMatrixView, DMatrixViewMut, Scalar};
 use num_traits::{One, Zero};
-
-use std::borrow::Cow;
+use std::convert::identity;
 
 /// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
 ///
 /// # Panics
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spmm_csc_dense<'a, T>(
+pub fn spmm_csc_dense<'a, T, I>(
     beta: T,
     c: impl Into<DMatrixViewMut<'a, T>>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
+    a: Op<impl Into<CscMatrixView<'a, T, I>>>,
     b: Op<impl Into<DMatrixView<'a, T>>>,
 ) where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     let b = b.convert();
-    spmm_csc_dense_(beta, c.into(), alpha, a, b)
+    spmm_csc_dense_(beta, c.into(), alpha, a.convert(), b)
 }
 
-fn spmm_csc_dense_<T>(
+fn spmm_csc_dense_<T, I>(
     beta: T,
     c: DMatrixViewMut<'_, T>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
+    a: Op<CscMatrixView<'_, T, I>>,
     b: Op<DMatrixView<'_, T>>,
 ) where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
     // Need to interpret matrix as transposed since the spmm_cs_dense function assumes CSR layout
-    let a = a.transposed().map_same_op(|a| &a.cs);
-    spmm_cs_dense(beta, c, alpha, a, b)
+    let transpose = matches!(a, Op::NoOp(_));
+    spmm_cs_dense(beta, c, alpha, a.into_inner().cs, transpose, identity, b)
+}
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * A^H * op(B)`, where `A^H`
+/// is the adjoint, i.e. the conjugate transpose, of `A`.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csc_dense_adjoint<'a, T, I>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: impl Into<CscMatrixView<'a, T, I>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: ComplexField,
+    I: SparseIndex,
+{
+    let (c, a, b) = (c.into(), Op::Transpose(a.into()), b.convert());
+    assert_compatible_spmm_dims!(c, a, b);
+    // The lanes of A^H in CSR layout are the conjugated lanes of A in CSC layout
+    spmm_cs_dense(beta, c, alpha, a.into_inner().cs, false, T::conjugate, b)
+}
+
+/// Sparse matrix-sparse vector multiplication `y = op(A) * x`.
+///
+/// The result has an explicit entry for every index `i` for which row `i` of `op(A)` and `x`
+/// have an explicitly stored entry in common, even if the computed value is zero.
+///
+/// # Panics
+///
+/// Panics if the number of columns of `op(A)` is not equal to the length of `x`.
+pub fn spmv_csc_sparse<'a, T, I>(
+    a: Op<impl Into<CscMatrixView<'a, T, I>>>,
+    x: &SparseVector<T>,
+) -> SparseVector<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero,
+    I: SparseIndex,
+{
+    let a = a.convert();
+    let transpose = matches!(a, Op::Transpose(_));
+    let a = a.into_inner();
+    let ncols = if transpose { a.nrows() } else { a.ncols() };
+    assert_eq!(
+        ncols,
+        x.len(),
+        "Number of columns of op(A) must be equal to the length of x."
+    );
+    // Need to interpret matrix as transposed since the spmv_cs_sparse function assumes CSR layout
+    spmv_cs_sparse(a.cs, !transpose, identity, x)
+}
+
+/// Sparse matrix-sparse vector multiplication `y = A^H * x`, where `A^H` is the adjoint, i.e. the
+/// conjugate transpose, of `A`.
+///
+/// The pattern of the result is determined as in [`spmv_csc_sparse`].
+///
+/// # Panics
+///
+/// Panics if the number of rows of `A` is not equal to the length of `x`.
+pub fn spmv_csc_sparse_adjoint<'a, T, I>(
+    a: impl Into<CscMatrixView<'a, T, I>>,
+    x: &SparseVector<T>,
+) -> SparseVector<T>
+where
+    T: ComplexField,
+    I: SparseIndex,
+{
+    let a = a.into();
+    assert_eq!(
+        a.nrows(),
+        x.len(),
+        "Number of rows of A must be equal to the length of x."
+    );
+    spmv_cs_sparse(a.cs, false, T::conjugate, x)
 }
 
 /// Sparse matrix addition `C <- beta * C + alpha * op(A)`.
@@ -50,17 +131,44 @@ fn spmm_csc_dense_<T>(
 /// # Panics
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spadd_csc_prealloc<T>(
+pub fn spadd_csc_prealloc<'a, T, I>(
     beta: T,
-    c: &mut CscMatrix<T>,
+    c: impl Into<CscMatrixViewMut<'a, T, I>>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
+    a: Op<impl Into<CscMatrixView<'a, T, I>>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    let (c, a): (CscMatrixViewMut<'_, T, I>, Op<CscMatrixView<'_, T, I>>) = (c.into(), a.convert());
+    assert_compatible_spadd_dims!(c, a);
+    spadd_cs_prealloc(beta, c.cs, alpha, a.map_same_op(|a| a.cs), identity)
+}
+
+/// Sparse matrix addition `C <- beta * C + alpha * A^H`, where `A^H` is the adjoint, i.e. the
+/// conjugate transpose, of `A`.
+///
+/// If the pattern of `c` does not accommodate all the non-zero entries in `A^H`, an error is
+/// returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spadd_csc_prealloc_adjoint<'a, T, I>(
+    beta: T,
+    c: impl Into<CscMatrixViewMut<'a, T, I>>,
+    alpha: T,
+    a: impl Into<CscMatrixView<'a, T, I>>,
+) -> Result<(), OperationError>
+where
+    T: ComplexField,
+    I: SparseIndex,
 {
+    let (c, a): (CscMatrixViewMut<'_, T, I>, Op<CscMatrixView<'_, T, I>>) =
+        (c.into(), Op::Transpose(a.into()));
     assert_compatible_spadd_dims!(c, a);
-    spadd_cs_prealloc(beta, &mut c.cs, alpha, a.map_same_op(|a| &a.cs))
+    spadd_cs_prealloc(beta, c.cs, alpha, a.map_same_op(|a| a.cs), T::conjugate)
 }
 
 /// Sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
@@ -73,26 +181,41 @@ where
 /// # Panics
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spmm_csc_prealloc<T>(
+pub fn spmm_csc_prealloc<'a, T, I>(
     beta: T,
-    c: &mut CscMatrix<T>,
+    c: impl Into<CscMatrixViewMut<'a, T, I>>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
-    b: Op<&CscMatrix<T>>,
+    a: Op<impl Into<CscMatrixView<'a, T, I>>>,
+    b: Op<impl Into<CscMatrixView<'a, T, I>>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    spmm_csc_prealloc_(beta, c.into(), alpha, a.convert(), b.convert())
+}
+
+fn spmm_csc_prealloc_<T, I>(
+    beta: T,
+    c: CscMatrixViewMut<'_, T, I>,
+    alpha: T,
+    a: Op<CscMatrixView<'_, T, I>>,
+    b: Op<CscMatrixView<'_, T, I>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
 
     use Op::NoOp;
 
-    match (&a, &b) {
-        (NoOp(ref a), NoOp(ref b)) => {
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => {
             // Note: We have to reverse the order for CSC matrices
-            spmm_cs_prealloc(beta, &mut c.cs, alpha, &b.cs, &a.cs)
+            spmm_cs_prealloc(beta, c.cs, alpha, b.cs, a.cs)
         }
-        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc),
+        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc_),
     }
 }
 
@@ -101,63 +224,83 @@ where
 /// Should be used for situations where pattern creation immediately preceeds multiplication.
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spmm_csc_prealloc_unchecked<T>(
+pub fn spmm_csc_prealloc_unchecked<'a, T, I>(
+    beta: T,
+    c: impl Into<CscMatrixViewMut<'a, T, I>>,
+    alpha: T,
+    a: Op<impl Into<CscMatrixView<'a, T, I>>>,
+    b: Op<impl Into<CscMatrixView<'a, T, I>>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    spmm_csc_prealloc_unchecked_(beta, c.into(), alpha, a.convert(), b.convert())
+}
+
+fn spmm_csc_prealloc_unchecked_<T, I>(
     beta: T,
-    c: &mut CscMatrix<T>,
+    c: CscMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
-    b: Op<&CscMatrix<T>>,
+    a: Op<CscMatrixView<'_, T, I>>,
+    b: Op<CscMatrixView<'_, T, I>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
 
     use Op::NoOp;
 
-    match (&a, &b) {
-        (NoOp(ref a), NoOp(ref b)) => {
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => {
             // Note: We have to reverse the order for CSC matrices
-            spmm_cs_prealloc_unchecked(beta, &mut c.cs, alpha, &b.cs, &a.cs)
+            spmm_cs_prealloc_unchecked(beta, c.cs, alpha, b.cs, a.cs)
         }
-        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc_unchecked),
+        _ => spmm_csc_transposed(beta, c, alpha, a, b, spmm_csc_prealloc_unchecked_),
     }
 }
 
-fn spmm_csc_transposed<T, F>(
+fn spmm_csc_transposed<T, I, F>(
     beta: T,
-    c: &mut CscMatrix<T>,
+    c: CscMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: Op<&CscMatrix<T>>,
-    b: Op<&CscMatrix<T>>,
+    a: Op<CscMatrixView<'_, T, I>>,
+    b: Op<CscMatrixView<'_, T, I>>,
     spmm_kernel: F,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
     F: Fn(
         T,
-        &mut CscMatrix<T>,
+        CscMatrixViewMut<'_, T, I>,
         T,
-        Op<&CscMatrix<T>>,
-        Op<&CscMatrix<T>>,
+        Op<CscMatrixView<'_, T, I>>,
+        Op<CscMatrixView<'_, T, I>>,
     ) -> Result<(), OperationError>,
 {
-    use Op::{NoOp, Transpose};
-
     // Currently we handle transposition by explicitly precomputing transposed matrices
     // and calling the operation again without transposition
-    let a_ref: &CscMatrix<T> = a.inner_ref();
-    let b_ref: &CscMatrix<T> = b.inner_ref();
-    let (a, b) = {
-        use Cow::*;
-        match (&a, &b) {
-            (NoOp(_), NoOp(_)) => unreachable!(),
-            (Transpose(ref a), NoOp(_)) => (Owned(a.transpose()), Borrowed(b_ref)),
-            (NoOp(_), Transpose(ref b)) => (Borrowed(a_ref), Owned(b.transpose())),
-            (Transpose(ref a), Transpose(ref b)) => (Owned(a.transpose()), Owned(b.transpose())),
-        }
-    };
-    spmm_kernel(beta, c, alpha, NoOp(a.as_ref()), NoOp(b.as_ref()))
+    let (a_owned, b_owned) = (materialize_csc_op(a), materialize_csc_op(b));
+    let a = a_owned.as_ref().map_or(a.into_inner(), CscMatrix::as_view);
+    let b = b_owned.as_ref().map_or(b.into_inner(), CscMatrix::as_view);
+    spmm_kernel(beta, c, alpha, Op::NoOp(a), Op::NoOp(b))
+}
+
+/// Returns `op(A)` as an owned matrix, or `None` if `op` is a no-op and no copy is necessary.
+pub(in crate::ops) fn materialize_csc_op<T, I>(
+    a: Op<CscMatrixView<'_, T, I>>,
+) -> Option<CscMatrix<T, I>>
+where
+    T: Scalar,
+    I: SparseIndex,
+{
+    match a {
+        Op::NoOp(_) => None,
+        Op::Transpose(a) => Some(a.transpose()),
+    }
 }
 
 /// Solve the lower triangular system `op(L) X = B`.
@@ -171,133 +314,271 @@ where
 /// # Panics
 ///
 /// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
-pub fn spsolve_csc_lower_triangular<'a, T: RealField>(
-    l: Op<&CscMatrix<T>>,
+pub fn spsolve_csc_lower_triangular<'a, T: ComplexField>(
+    l: Op<impl Into<CscMatrixView<'a, T>>>,
     b: impl Into<DMatrixViewMut<'a, T>>,
 ) -> Result<(), OperationError> {
-    let b = b.into();
-    let l_matrix = l.into_inner();
-    assert_eq!(
-        l_matrix.nrows(),
-        l_matrix.ncols(),
-        "Matrix must be square for triangular solve."
-    );
-    assert_eq!(
-        l_matrix.nrows(),
-        b.nrows(),
-        "Dimension mismatch in sparse lower triangular solver."
-    );
-    match l {
-        Op::NoOp(a) => spsolve_csc_lower_triangular_no_transpose(a, b),
-        Op::Transpose(a) => spsolve_csc_lower_triangular_transpose(a, b),
-    }
+    spsolve_csc_triangular(l.convert(), true, false, false, b.into())
 }
 
-fn spsolve_csc_lower_triangular_no_transpose<T: RealField>(
-    l: &CscMatrix<T>,
-    b: DMatrixViewMut<'_, T>,
+/// Solve the triangular system `L^H X = B`, where `L^H` is the adjoint, i.e. the conjugate
+/// transpose, of the lower triangular matrix `L`.
+///
+/// Only the lower triangular part of L is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_lower_triangular_adjoint<'a, T: ComplexField>(
+    l: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
 ) -> Result<(), OperationError> {
-    let mut x = b;
-
-    // Solve column-by-column
-    for j in 0..x.ncols() {
-        let mut x_col_j = x.column_mut(j);
-
-        for k in 0..l.ncols() {
-            let l_col_k = l.col(k);
-
-            // Skip entries above the diagonal
-            // TODO: Can use exponential search here to quickly skip entries
-            // (we'd like to avoid using binary search as it's very cache unfriendly
-            // and the matrix might actually *be* lower triangular, which would induce
-            // a severe penalty)
-            let diag_csc_index = l_col_k.row_indices().iter().position(|&i| i == k);
-            if let Some(diag_csc_index) = diag_csc_index {
-                let l_kk = l_col_k.values()[diag_csc_index].clone();
-
-                if l_kk != T::zero() {
-                    // Update entry associated with diagonal
-                    x_col_j[k] /= l_kk;
-                    // Copy value after updating (so we don't run into the borrow checker)
-                    let x_kj = x_col_j[k].clone();
-
-                    let row_indices = &l_col_k.row_indices()[(diag_csc_index + 1)..];
-                    let l_values = &l_col_k.values()[(diag_csc_index + 1)..];
+    spsolve_csc_triangular(Op::Transpose(l.into()), true, false, true, b.into())
+}
 
-                    // Note: The remaining entries are below the diagonal
-                    for (&i, l_ik) in row_indices.iter().zip(l_values) {
-                        let x_ij = &mut x_col_j[i];
-                        *x_ij -= l_ik.clone() * x_kj.clone();
-                    }
+/// Solve the upper triangular system `op(U) X = B`.
+///
+/// Only the upper triangular part of U is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_upper_triangular<'a, T: ComplexField>(
+    u: Op<impl Into<CscMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csc_triangular(u.convert(), false, false, false, b.into())
+}
 
-                    x_col_j[k] = x_kj;
-                } else {
-                    return spsolve_encountered_zero_diagonal();
-                }
-            } else {
-                return spsolve_encountered_zero_diagonal();
-            }
-        }
-    }
+/// Solve the triangular system `U^H X = B`, where `U^H` is the adjoint, i.e. the conjugate
+/// transpose, of the upper triangular matrix `U`.
+///
+/// Only the upper triangular part of U is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_upper_triangular_adjoint<'a, T: ComplexField>(
+    u: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csc_triangular(Op::Transpose(u.into()), false, false, true, b.into())
+}
 
-    Ok(())
+/// Solve the lower triangular system `op(L) X = B`, where `L` has a unit diagonal.
+///
+/// Only the strictly lower triangular part of L is read, and the diagonal entries are assumed
+/// to be one. The result is stored in B.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_unit_lower_triangular<'a, T: ComplexField>(
+    l: Op<impl Into<CscMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) {
+    spsolve_csc_triangular(l.convert(), true, true, false, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
 }
 
-fn spsolve_encountered_zero_diagonal() -> Result<(), OperationError> {
-    let message = "Matrix contains at least one diagonal entry that is zero.";
-    Err(OperationError::from_kind_and_message(
-        OperationErrorKind::Singular,
-        String::from(message),
-    ))
+/// Solve the upper triangular system `op(U) X = B`, where `U` has a unit diagonal.
+///
+/// Only the strictly upper triangular part of U is read, and the diagonal entries are assumed
+/// to be one. The result is stored in B.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_unit_upper_triangular<'a, T: ComplexField>(
+    u: Op<impl Into<CscMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) {
+    spsolve_csc_triangular(u.convert(), false, true, false, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
 }
 
-fn spsolve_csc_lower_triangular_transpose<T: RealField>(
-    l: &CscMatrix<T>,
+fn spsolve_csc_triangular<T: ComplexField>(
+    a: Op<CscMatrixView<'_, T>>,
+    lower: bool,
+    unit_diagonal: bool,
+    conjugate: bool,
     b: DMatrixViewMut<'_, T>,
 ) -> Result<(), OperationError> {
-    let mut x = b;
-
-    // Solve column-by-column
-    for j in 0..x.ncols() {
-        let mut x_col_j = x.column_mut(j);
-
-        // Due to the transposition, we're essentially solving an upper triangular system,
-        // and the columns in our matrix become rows
-
-        for i in (0..l.ncols()).rev() {
-            let l_col_i = l.col(i);
+    let a_matrix = a.inner_ref();
+    assert_eq!(
+        a_matrix.nrows(),
+        a_matrix.ncols(),
+        "Matrix must be square for triangular solve."
+    );
+    assert_eq!(
+        a_matrix.nrows(),
+        b.nrows(),
+        "Dimension mismatch in sparse triangular solver."
+    );
+    // The lanes of a CSC matrix are its columns, i.e. the rows of its transpose
+    let transpose = matches!(a, Op::NoOp(_));
+    spsolve_cs_triangular(a_matrix.cs, !lower, transpose, conjugate, unit_diagonal, b)
+}
 
-            // Skip entries above the diagonal
-            // TODO: Can use exponential search here to quickly skip entries
-            let diag_csc_index = l_col_i.row_indices().iter().position(|&k| i == k);
-            if let Some(diag_csc_index) = diag_csc_index {
-                let l_ii = l_col_i.values()[diag_csc_index].clone();
+/// Solve the lower triangular system `L X = B` for a sparse right-hand side `B`.
+///
+/// Only the lower triangular part of L is read. The solution is computed with the algorithm of
+/// Gilbert and Peierls, whose cost is proportional to the number of floating-point operations
+/// rather than to the dimensions of the system. This makes it well-suited for computing
+/// individual sparse columns of a factorization.
+///
+/// The sparsity pattern of the solution contains every entry that is structurally non-zero, so
+/// it may contain explicit zeros due to cancellation.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_lower_triangular_sparse<'a, T: ComplexField>(
+    l: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+) -> Result<CscMatrix<T>, OperationError> {
+    spsolve_csc_triangular_sparse(l.into(), true, false, b.into())
+}
 
-                if l_ii != T::zero() {
-                    // // Update entry associated with diagonal
-                    // x_col_j[k] /= a_kk;
+/// Solve the upper triangular system `U X = B` for a sparse right-hand side `B`.
+///
+/// Only the upper triangular part of U is read. See
+/// [`spsolve_csc_lower_triangular_sparse`] for details.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csc_upper_triangular_sparse<'a, T: ComplexField>(
+    u: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+) -> Result<CscMatrix<T>, OperationError> {
+    spsolve_csc_triangular_sparse(u.into(), false, false, b.into())
+}
 
-                    // Copy value after updating (so we don't run into the borrow checker)
-                    let mut x_ii = x_col_j[i].clone();
+/// Solve the lower triangular system `L X = B` for a sparse right-hand side `B`, where `L` has
+/// a unit diagonal.
+///
+/// Only the strictly lower triangular part of L is read, and the diagonal entries are assumed
+/// to be one. See [`spsolve_csc_lower_triangular_sparse`] for details.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+#[must_use]
+pub fn spsolve_csc_unit_lower_triangular_sparse<'a, T: ComplexField>(
+    l: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+) -> CscMatrix<T> {
+    spsolve_csc_triangular_sparse(l.into(), true, true, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
+}
 
-                    let row_indices = &l_col_i.row_indices()[(diag_csc_index + 1)..];
-                    let a_values = &l_col_i.values()[(diag_csc_index + 1)..];
+/// Solve the upper triangular system `U X = B` for a sparse right-hand side `B`, where `U` has
+/// a unit diagonal.
+///
+/// Only the strictly upper triangular part of U is read, and the diagonal entries are assumed
+/// to be one. See [`spsolve_csc_lower_triangular_sparse`] for details.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+#[must_use]
+pub fn spsolve_csc_unit_upper_triangular_sparse<'a, T: ComplexField>(
+    u: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+) -> CscMatrix<T> {
+    spsolve_csc_triangular_sparse(u.into(), false, true, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
+}
 
-                    // Note: The remaining entries are below the diagonal
-                    for (k, l_ki) in row_indices.iter().zip(a_values) {
-                        let x_kj = x_col_j[*k].clone();
-                        x_ii -= l_ki.clone() * x_kj;
-                    }
+fn spsolve_csc_triangular_sparse<T: ComplexField>(
+    a: CscMatrixView<'_, T>,
+    lower: bool,
+    unit_diagonal: bool,
+    b: CscMatrixView<'_, T>,
+) -> Result<CscMatrix<T>, OperationError> {
+    assert_eq!(
+        a.nrows(),
+        a.ncols(),
+        "Matrix must be square for triangular solve."
+    );
+    assert_eq!(
+        a.nrows(),
+        b.nrows(),
+        "Dimension mismatch in sparse triangular solver."
+    );
+    Ok(CscMatrix {
+        cs: spsolve_cs_triangular_sparse(a.cs, lower, unit_diagonal, b.cs)?,
+    })
+}
 
-                    x_col_j[i] = x_ii / l_ii;
-                } else {
-                    return spsolve_encountered_zero_diagonal();
-                }
-            } else {
-                return spsolve_encountered_zero_diagonal();
-            }
-        }
+/// Element-wise operation on the union of the sparsity patterns, `C_ij <- f(A_ij, B_ij)`.
+///
+/// The result has the union of the sparsity patterns of `A` and `B`. Entries that are explicitly
+/// stored in only one of the matrices are combined with zero, while `f` is never called for
+/// entries that are stored in neither matrix.
+///
+/// # Panics
+///
+/// Panics if `A` and `B` do not have the same dimensions.
+pub fn elementwise_union_csc<'a, T, U, F>(
+    a: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+    f: F,
+) -> CscMatrix<U>
+where
+    T: Scalar + Zero,
+    F: FnMut(T, T) -> U,
+{
+    let (a, b) = (a.into(), b.into());
+    assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+    assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+    CscMatrix {
+        cs: elementwise_union_cs(a.cs, b.cs, f),
     }
+}
 
-    Ok(())
+/// Element-wise operation on the intersection of the sparsity patterns, `C_ij <- f(A_ij, B_ij)`.
+///
+/// The result has the intersection of the sparsity patterns of `A` and `B`, i.e. `f` is only
+/// called for entries that are explicitly stored in both matrices.
+///
+/// # Panics
+///
+/// Panics if `A` and `B` do not have the same dimensions.
+pub fn elementwise_intersection_csc<'a, T, U, F>(
+    a: impl Into<CscMatrixView<'a, T>>,
+    b: impl Into<CscMatrixView<'a, T>>,
+    f: F,
+) -> CscMatrix<U>
+where
+    T: Scalar,
+    F: FnMut(T, T) -> U,
+{
+    let (a, b) = (a.into(), b.into());
+    assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+    assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+    CscMatrix {
+        cs: elementwise_intersection_cs(a.cs, b.cs, f),
+    }
 }
@@ -1,38 +1,118 @@
-use crate::csr::CsrMatrix;
+use crate::csr::{CsrMatrix, CsrMatrixView, CsrMatrixViewMut};
 use crate::ops::serial::cs::{
-    spadd_cs_prealloc, spmm_cs_dense, spmm_cs_prealloc, spmm_cs_prealloc_unchecked,
+    elementwise_intersection_cs, elementwise_union_cs, spadd_cs_prealloc, spmm_cs_dense,
+    spmm_cs_prealloc, spmm_cs_prealloc_unchecked, spmv_cs_sparse, spsolve_cs_triangular,
 };
 use crate::ops::serial::OperationError;
 use crate::ops::Op;
-use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use crate::pattern::SparseIndex;
+use crate::vector::SparseVector;
+use nalgebra::{ClosedAdd, ClosedMul, ComplexField, DMatrixView, DMatrixViewMut, Scalar};
 use num_traits::{One, Zero};
-use std::borrow::Cow;
+use std::convert::identity;
 
 /// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
-pub fn spmm_csr_dense<'a, T>(
+pub fn spmm_csr_dense<'a, T, I>(
     beta: T,
     c: impl Into<DMatrixViewMut<'a, T>>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
+    a: Op<impl Into<CsrMatrixView<'a, T, I>>>,
     b: Op<impl Into<DMatrixView<'a, T>>>,
 ) where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     let b = b.convert();
-    spmm_csr_dense_(beta, c.into(), alpha, a, b)
+    spmm_csr_dense_(beta, c.into(), alpha, a.convert(), b)
 }
 
-fn spmm_csr_dense_<T>(
+fn spmm_csr_dense_<T, I>(
     beta: T,
     c: DMatrixViewMut<'_, T>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
+    a: Op<CsrMatrixView<'_, T, I>>,
     b: Op<DMatrixView<'_, T>>,
 ) where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
-    spmm_cs_dense(beta, c, alpha, a.map_same_op(|a| &a.cs), b)
+    let transpose = matches!(a, Op::Transpose(_));
+    spmm_cs_dense(beta, c, alpha, a.into_inner().cs, transpose, identity, b)
+}
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * A^H * op(B)`, where `A^H`
+/// is the adjoint, i.e. the conjugate transpose, of `A`.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_csr_dense_adjoint<'a, T, I>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: impl Into<CsrMatrixView<'a, T, I>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: ComplexField,
+    I: SparseIndex,
+{
+    let (c, a, b) = (c.into(), Op::Transpose(a.into()), b.convert());
+    assert_compatible_spmm_dims!(c, a, b);
+    spmm_cs_dense(beta, c, alpha, a.into_inner().cs, true, T::conjugate, b)
+}
+
+/// Sparse matrix-sparse vector multiplication `y = op(A) * x`.
+///
+/// The result has an explicit entry for every index `i` for which row `i` of `op(A)` and `x`
+/// have an explicitly stored entry in common, even if the computed value is zero.
+///
+/// # Panics
+///
+/// Panics if the number of columns of `op(A)` is not equal to the length of `x`.
+pub fn spmv_csr_sparse<'a, T, I>(
+    a: Op<impl Into<CsrMatrixView<'a, T, I>>>,
+    x: &SparseVector<T>,
+) -> SparseVector<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero,
+    I: SparseIndex,
+{
+    let a = a.convert();
+    let transpose = matches!(a, Op::Transpose(_));
+    let a = a.into_inner();
+    let ncols = if transpose { a.nrows() } else { a.ncols() };
+    assert_eq!(
+        ncols,
+        x.len(),
+        "Number of columns of op(A) must be equal to the length of x."
+    );
+    spmv_cs_sparse(a.cs, transpose, identity, x)
+}
+
+/// Sparse matrix-sparse vector multiplication `y = A^H * x`, where `A^H` is the adjoint, i.e. the
+/// conjugate transpose, of `A`.
+///
+/// The pattern of the result is determined as in [`spmv_csr_sparse`].
+///
+/// # Panics
+///
+/// Panics if the number of rows of `A` is not equal to the length of `x`.
+pub fn spmv_csr_sparse_adjoint<'a, T, I>(
+    a: impl Into<CsrMatrixView<'a, T, I>>,
+    x: &SparseVector<T>,
+) -> SparseVector<T>
+where
+    T: ComplexField,
+    I: SparseIndex,
+{
+    let a = a.into();
+    assert_eq!(
+        a.nrows(),
+        x.len(),
+        "Number of rows of A must be equal to the length of x."
+    );
+    spmv_cs_sparse(a.cs, true, T::conjugate, x)
 }
 
 /// Sparse matrix addition `C <- beta * C + alpha * op(A)`.
@@ -45,17 +125,46 @@ fn spmm_csr_dense_<T>(
 /// # Panics
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spadd_csr_prealloc<T>(
+pub fn spadd_csr_prealloc<'a, T, I>(
     beta: T,
-    c: &mut CsrMatrix<T>,
+    c: impl Into<CsrMatrixViewMut<'a, T, I>>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
+    a: Op<impl Into<CsrMatrixView<'a, T, I>>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    let (c, a): (CsrMatrixViewMut<'_, T, I>, Op<CsrMatrixView<'_, T, I>>) = (c.into(), a.convert());
+    assert_compatible_spadd_dims!(c, a);
+    spadd_cs_prealloc(beta, c.cs, alpha, a.map_same_op(|a| a.cs), identity)
+}
+
+/// Sparse matrix addition `C <- beta * C + alpha * A^H`, where `A^H` is the adjoint, i.e. the
+/// conjugate transpose, of `A`.
+///
+/// # Errors
+///
+/// If the pattern of `c` does not accommodate all the non-zero entries in `A^H`, an error is
+/// returned.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spadd_csr_prealloc_adjoint<'a, T, I>(
+    beta: T,
+    c: impl Into<CsrMatrixViewMut<'a, T, I>>,
+    alpha: T,
+    a: impl Into<CsrMatrixView<'a, T, I>>,
+) -> Result<(), OperationError>
+where
+    T: ComplexField,
+    I: SparseIndex,
 {
+    let (c, a): (CsrMatrixViewMut<'_, T, I>, Op<CsrMatrixView<'_, T, I>>) =
+        (c.into(), Op::Transpose(a.into()));
     assert_compatible_spadd_dims!(c, a);
-    spadd_cs_prealloc(beta, &mut c.cs, alpha, a.map_same_op(|a| &a.cs))
+    spadd_cs_prealloc(beta, c.cs, alpha, a.map_same_op(|a| a.cs), T::conjugate)
 }
 
 /// Sparse-sparse matrix multiplication, `C <- beta * C + alpha * op(A) * op(B)`.
@@ -67,23 +176,38 @@ where
 /// # Panics
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spmm_csr_prealloc<T>(
+pub fn spmm_csr_prealloc<'a, T, I>(
+    beta: T,
+    c: impl Into<CsrMatrixViewMut<'a, T, I>>,
+    alpha: T,
+    a: Op<impl Into<CsrMatrixView<'a, T, I>>>,
+    b: Op<impl Into<CsrMatrixView<'a, T, I>>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    spmm_csr_prealloc_(beta, c.into(), alpha, a.convert(), b.convert())
+}
+
+fn spmm_csr_prealloc_<T, I>(
     beta: T,
-    c: &mut CsrMatrix<T>,
+    c: CsrMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
-    b: Op<&CsrMatrix<T>>,
+    a: Op<CsrMatrixView<'_, T, I>>,
+    b: Op<CsrMatrixView<'_, T, I>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
 
     use Op::NoOp;
 
-    match (&a, &b) {
-        (NoOp(ref a), NoOp(ref b)) => spmm_cs_prealloc(beta, &mut c.cs, alpha, &a.cs, &b.cs),
-        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc),
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => spmm_cs_prealloc(beta, c.cs, alpha, a.cs, b.cs),
+        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc_),
     }
 }
 
@@ -92,60 +216,257 @@ where
 /// Should be used for situations where pattern creation immediately preceeds multiplication.
 ///
 /// Panics if the dimensions of the matrices involved are not compatible with the expression.
-pub fn spmm_csr_prealloc_unchecked<T>(
+pub fn spmm_csr_prealloc_unchecked<'a, T, I>(
     beta: T,
-    c: &mut CsrMatrix<T>,
+    c: impl Into<CsrMatrixViewMut<'a, T, I>>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
-    b: Op<&CsrMatrix<T>>,
+    a: Op<impl Into<CsrMatrixView<'a, T, I>>>,
+    b: Op<impl Into<CsrMatrixView<'a, T, I>>>,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
+{
+    spmm_csr_prealloc_unchecked_(beta, c.into(), alpha, a.convert(), b.convert())
+}
+
+fn spmm_csr_prealloc_unchecked_<T, I>(
+    beta: T,
+    c: CsrMatrixViewMut<'_, T, I>,
+    alpha: T,
+    a: Op<CsrMatrixView<'_, T, I>>,
+    b: Op<CsrMatrixView<'_, T, I>>,
+) -> Result<(), OperationError>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
 {
     assert_compatible_spmm_dims!(c, a, b);
 
     use Op::NoOp;
 
-    match (&a, &b) {
-        (NoOp(ref a), NoOp(ref b)) => {
-            spmm_cs_prealloc_unchecked(beta, &mut c.cs, alpha, &a.cs, &b.cs)
-        }
-        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc_unchecked),
+    match (a, b) {
+        (NoOp(a), NoOp(b)) => spmm_cs_prealloc_unchecked(beta, c.cs, alpha, a.cs, b.cs),
+        _ => spmm_csr_transposed(beta, c, alpha, a, b, spmm_csr_prealloc_unchecked_),
     }
 }
 
-fn spmm_csr_transposed<T, F>(
+fn spmm_csr_transposed<T, I, F>(
     beta: T,
-    c: &mut CsrMatrix<T>,
+    c: CsrMatrixViewMut<'_, T, I>,
     alpha: T,
-    a: Op<&CsrMatrix<T>>,
-    b: Op<&CsrMatrix<T>>,
+    a: Op<CsrMatrixView<'_, T, I>>,
+    b: Op<CsrMatrixView<'_, T, I>>,
     spmm_kernel: F,
 ) -> Result<(), OperationError>
 where
     T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    I: SparseIndex,
     F: Fn(
         T,
-        &mut CsrMatrix<T>,
+        CsrMatrixViewMut<'_, T, I>,
         T,
-        Op<&CsrMatrix<T>>,
-        Op<&CsrMatrix<T>>,
+        Op<CsrMatrixView<'_, T, I>>,
+        Op<CsrMatrixView<'_, T, I>>,
     ) -> Result<(), OperationError>,
 {
-    use Op::{NoOp, Transpose};
-
     // Currently we handle transposition by explicitly precomputing transposed matrices
     // and calling the operation again without transposition
-    let a_ref: &CsrMatrix<T> = a.inner_ref();
-    let b_ref: &CsrMatrix<T> = b.inner_ref();
-    let (a, b) = {
-        use Cow::*;
-        match (&a, &b) {
-            (NoOp(_), NoOp(_)) => unreachable!(),
-            (Transpose(ref a), NoOp(_)) => (Owned(a.transpose()), Borrowed(b_ref)),
-            (NoOp(_), Transpose(ref b)) => (Borrowed(a_ref), Owned(b.transpose())),
-            (Transpose(ref a), Transpose(ref b)) => (Owned(a.transpose()), Owned(b.transpose())),
-        }
-    };
-    spmm_kernel(beta, c, alpha, NoOp(a.as_ref()), NoOp(b.as_ref()))
+    let (a_owned, b_owned) = (materialize_csr_op(a), materialize_csr_op(b));
+    let a = a_owned.as_ref().map_or(a.into_inner(), CsrMatrix::as_view);
+    let b = b_owned.as_ref().map_or(b.into_inner(), CsrMatrix::as_view);
+    spmm_kernel(beta, c, alpha, Op::NoOp(a), Op::NoOp(b))
+}
+
+/// Returns `op(A)` as an owned matrix, or `None` if `op` is a no-op and no copy is necessary.
+pub(in crate::ops) fn materialize_csr_op<T, I>(
+    a: Op<CsrMatrixView<'_, T, I>>,
+) -> Option<CsrMatrix<T, I>>
+where
+    T: Scalar,
+    I: SparseIndex,
+{
+    match a {
+        Op::NoOp(_) => None,
+        Op::Transpose(a) => Some(a.transpose()),
+    }
+}
+
+/// Solve the lower triangular system `op(L) X = B`.
+///
+/// Only the lower triangular part of L is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_lower_triangular<'a, T: ComplexField>(
+    l: Op<impl Into<CsrMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csr_triangular(l.convert(), true, false, false, b.into())
+}
+
+/// Solve the triangular system `L^H X = B`, where `L^H` is the adjoint, i.e. the conjugate
+/// transpose, of the lower triangular matrix `L`.
+///
+/// Only the lower triangular part of L is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_lower_triangular_adjoint<'a, T: ComplexField>(
+    l: impl Into<CsrMatrixView<'a, T>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csr_triangular(Op::Transpose(l.into()), true, false, true, b.into())
+}
+
+/// Solve the upper triangular system `op(U) X = B`.
+///
+/// Only the upper triangular part of U is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_upper_triangular<'a, T: ComplexField>(
+    u: Op<impl Into<CsrMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csr_triangular(u.convert(), false, false, false, b.into())
+}
+
+/// Solve the triangular system `U^H X = B`, where `U^H` is the adjoint, i.e. the conjugate
+/// transpose, of the upper triangular matrix `U`.
+///
+/// Only the upper triangular part of U is read, and the result is stored in B.
+///
+/// # Errors
+///
+/// An error is returned if the system can not be solved due to the matrix being singular.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_upper_triangular_adjoint<'a, T: ComplexField>(
+    u: impl Into<CsrMatrixView<'a, T>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) -> Result<(), OperationError> {
+    spsolve_csr_triangular(Op::Transpose(u.into()), false, false, true, b.into())
+}
+
+/// Solve the lower triangular system `op(L) X = B`, where `L` has a unit diagonal.
+///
+/// Only the strictly lower triangular part of L is read, and the diagonal entries are assumed
+/// to be one. The result is stored in B.
+///
+/// # Panics
+///
+/// Panics if `L` is not square, or if `L` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_unit_lower_triangular<'a, T: ComplexField>(
+    l: Op<impl Into<CsrMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) {
+    spsolve_csr_triangular(l.convert(), true, true, false, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
+}
+
+/// Solve the upper triangular system `op(U) X = B`, where `U` has a unit diagonal.
+///
+/// Only the strictly upper triangular part of U is read, and the diagonal entries are assumed
+/// to be one. The result is stored in B.
+///
+/// # Panics
+///
+/// Panics if `U` is not square, or if `U` and `B` are not dimensionally compatible.
+pub fn spsolve_csr_unit_upper_triangular<'a, T: ComplexField>(
+    u: Op<impl Into<CsrMatrixView<'a, T>>>,
+    b: impl Into<DMatrixViewMut<'a, T>>,
+) {
+    spsolve_csr_triangular(u.convert(), false, true, false, b.into())
+        .expect("Internal error: Unit triangular solves cannot fail.")
+}
+
+fn spsolve_csr_triangular<T: ComplexField>(
+    a: Op<CsrMatrixView<'_, T>>,
+    lower: bool,
+    unit_diagonal: bool,
+    conjugate: bool,
+    b: DMatrixViewMut<'_, T>,
+) -> Result<(), OperationError> {
+    let a_matrix = a.inner_ref();
+    assert_eq!(
+        a_matrix.nrows(),
+        a_matrix.ncols(),
+        "Matrix must be square for triangular solve."
+    );
+    assert_eq!(
+        a_matrix.nrows(),
+        b.nrows(),
+        "Dimension mismatch in sparse triangular solver."
+    );
+    let transpose = matches!(a, Op::Transpose(_));
+    spsolve_cs_triangular(a_matrix.cs, lower, transpose, conjugate, unit_diagonal, b)
+}
+
+/// Element-wise operation on the union of the sparsity patterns, `C_ij <- f(A_ij, B_ij)`.
+///
+/// The result has the union of the sparsity patterns of `A` and `B`. Entries that are explicitly
+/// stored in only one of the matrices are combined with zero, while `f` is never called for
+/// entries that are stored in neither matrix.
+///
+/// # Panics
+///
+/// Panics if `A` and `B` do not have the same dimensions.
+pub fn elementwise_union_csr<'a, T, U, F>(
+    a: impl Into<CsrMatrixView<'a, T>>,
+    b: impl Into<CsrMatrixView<'a, T>>,
+    f: F,
+) -> CsrMatrix<U>
+where
+    T: Scalar + Zero,
+    F: FnMut(T, T) -> U,
+{
+    let (a, b) = (a.into(), b.into());
+    assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+    assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+    CsrMatrix {
+        cs: elementwise_union_cs(a.cs, b.cs, f),
+    }
+}
+
+/// Element-wise operation on the intersection of the sparsity patterns, `C_ij <- f(A_ij, B_ij)`.
+///
+/// The result has the intersection of the sparsity patterns of `A` and `B`, i.e. `f` is only
+/// called for entries that are explicitly stored in both matrices.
+///
+/// # Panics
+///
+/// Panics if `A` and `B` do not have the same dimensions.
+pub fn elementwise_intersection_csr<'a, T, U, F>(
+    a: impl Into<CsrMatrixView<'a, T>>,
+    b: impl Into<CsrMatrixView<'a, T>>,
+    f: F,
+) -> CsrMatrix<U>
+where
+    T: Scalar,
+    F: FnMut(T, T) -> U,
+{
+    let (a, b) = (a.into(), b.into());
+    assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
+    assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
+    CsrMatrix {
+        cs: elementwise_intersection_cs(a.cs, b.cs, f),
+    }
 }
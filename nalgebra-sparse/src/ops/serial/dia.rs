@@ -0,0 +1,55 @@
+use crate::dia::{diagonal_start, DiaMatrix};
+use crate::ops::serial::spmm_dense_columnwise;
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// The product is computed one diagonal of `A` at a time, so that the innermost loop is a
+/// contiguous element-wise multiply-add. With a single column, this is a sparse matrix-vector
+/// product.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_dia_dense<'a, T>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&DiaMatrix<T>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    let b = b.convert();
+    spmm_dia_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_dia_dense_<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&DiaMatrix<T>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    let (a, transposed) = match a {
+        Op::NoOp(a) => (a, false),
+        Op::Transpose(a) => (a, true),
+    };
+    spmm_dense_columnwise(beta, c, alpha, b, |x, y| {
+        for (k, diagonal) in a.diagonal_iter() {
+            let (i0, j0) = diagonal_start(k);
+            let (y0, x0) = if transposed { (j0, i0) } else { (i0, j0) };
+            let n = diagonal.len();
+            let mut y_segment = y.rows_mut(y0, n);
+            let x_segment = x.rows(x0, n);
+            for ((y_p, x_p), d_p) in y_segment.iter_mut().zip(x_segment.iter()).zip(diagonal) {
+                *y_p += d_p.clone() * x_p.clone();
+            }
+        }
+    });
+}
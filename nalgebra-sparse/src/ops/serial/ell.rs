@@ -0,0 +1,118 @@
+use crate::ell::EllMatrix;
+use crate::ops::serial::spmm_dense_columnwise;
+use crate::ops::Op;
+use crate::sell::SellMatrix;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, Scalar};
+use num_traits::{One, Zero};
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// The padded entries of `A` are traversed in storage order, i.e. the `k`-th entry of all rows
+/// is processed before the `(k + 1)`-th entry of any row. With a single column, this is a sparse
+/// matrix-vector product.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_ell_dense<'a, T>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&EllMatrix<T>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    let b = b.convert();
+    spmm_ell_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_ell_dense_<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&EllMatrix<T>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    let (a, transposed) = match a {
+        Op::NoOp(a) => (a, false),
+        Op::Transpose(a) => (a, true),
+    };
+    let nrows = a.nrows();
+    spmm_dense_columnwise(beta, c, alpha, b, |x, y| {
+        for k in 0..a.width() {
+            for (i, &len) in a.row_lengths().iter().enumerate() {
+                if k < len {
+                    let idx = k * nrows + i;
+                    let (j, v) = (a.col_indices()[idx], &a.values()[idx]);
+                    if transposed {
+                        y[j] += v.clone() * x[i].clone();
+                    } else {
+                        y[i] += v.clone() * x[j].clone();
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Sparse-dense matrix-matrix multiplication `C <- beta * C + alpha * op(A) * op(B)`.
+///
+/// Each slice of `A` is processed like an [ELL](crate::ell::EllMatrix) matrix. With a single
+/// column, this is a sparse matrix-vector product.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the matrices involved are not compatible with the expression.
+pub fn spmm_sell_dense<'a, T>(
+    beta: T,
+    c: impl Into<DMatrixViewMut<'a, T>>,
+    alpha: T,
+    a: Op<&SellMatrix<T>>,
+    b: Op<impl Into<DMatrixView<'a, T>>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    let b = b.convert();
+    spmm_sell_dense_(beta, c.into(), alpha, a, b)
+}
+
+fn spmm_sell_dense_<T>(
+    beta: T,
+    c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    a: Op<&SellMatrix<T>>,
+    b: Op<DMatrixView<'_, T>>,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+{
+    assert_compatible_spmm_dims!(c, a, b);
+    let (a, transposed) = match a {
+        Op::NoOp(a) => (a, false),
+        Op::Transpose(a) => (a, true),
+    };
+    spmm_dense_columnwise(beta, c, alpha, b, |x, y| {
+        for s in 0..a.num_slices() {
+            let slice = a.slice(s);
+            let stored_rows = slice.first_row..slice.first_row + slice.height;
+            let row_lengths = &a.row_lengths()[stored_rows.clone()];
+            let rows = &a.row_permutation()[stored_rows];
+            for k in 0..slice.width {
+                for (r, (&i, &len)) in rows.iter().zip(row_lengths).enumerate() {
+                    if k < len {
+                        let idx = slice.offset + k * slice.height + r;
+                        let (j, v) = (a.col_indices()[idx], &a.values()[idx]);
+                        if transposed {
+                            y[j] += v.clone() * x[i].clone();
+                        } else {
+                            y[i] += v.clone() * x[j].clone();
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
@@ -8,58 +8,24 @@
 //! some operations which will be able to dynamically adapt the output pattern to fit the
 //! result, but these have yet to be implemented.
 
-macro_rules! assert_compatible_spmm_dims {
-    ($c:expr, $a:expr, $b:expr) => {{
-        use crate::ops::Op::{NoOp, Transpose};
-        match (&$a, &$b) {
-            (NoOp(ref a), NoOp(ref b)) => {
-                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
-                assert_eq!($c.ncols(), b.ncols(), "C.ncols() != B.ncols()");
-                assert_eq!(a.ncols(), b.nrows(), "A.ncols() != B.nrows()");
-            }
-            (Transpose(ref a), NoOp(ref b)) => {
-                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
-                assert_eq!($c.ncols(), b.ncols(), "C.ncols() != B.ncols()");
-                assert_eq!(a.nrows(), b.nrows(), "A.nrows() != B.nrows()");
-            }
-            (NoOp(ref a), Transpose(ref b)) => {
-                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
-                assert_eq!($c.ncols(), b.nrows(), "C.ncols() != B.nrows()");
-                assert_eq!(a.ncols(), b.ncols(), "A.ncols() != B.ncols()");
-            }
-            (Transpose(ref a), Transpose(ref b)) => {
-                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
-                assert_eq!($c.ncols(), b.nrows(), "C.ncols() != B.nrows()");
-                assert_eq!(a.nrows(), b.ncols(), "A.nrows() != B.ncols()");
-            }
-        }
-    }};
-}
-
-macro_rules! assert_compatible_spadd_dims {
-    ($c:expr, $a:expr) => {
-        use crate::ops::Op;
-        match $a {
-            Op::NoOp(a) => {
-                assert_eq!($c.nrows(), a.nrows(), "C.nrows() != A.nrows()");
-                assert_eq!($c.ncols(), a.ncols(), "C.ncols() != A.ncols()");
-            }
-            Op::Transpose(a) => {
-                assert_eq!($c.nrows(), a.ncols(), "C.nrows() != A.ncols()");
-                assert_eq!($c.ncols(), a.nrows(), "C.ncols() != A.nrows()");
-            }
-        }
-    };
-}
-
-mod cs;
+mod bsr;
+pub(super) mod cs;
 mod csc;
 mod csr;
-mod pattern;
+mod dia;
+mod ell;
+pub(super) mod pattern;
 
+pub use bsr::*;
 pub use csc::*;
 pub use csr::*;
+pub use dia::*;
+pub use ell::*;
 pub use pattern::*;
+
+use crate::ops::Op;
+use nalgebra::{ClosedAdd, ClosedMul, DMatrixView, DMatrixViewMut, DVector, Scalar};
+use num_traits::{One, Zero};
 use std::fmt;
 use std::fmt::Formatter;
 
@@ -86,7 +52,7 @@ pub enum OperationErrorKind {
 }
 
 impl OperationError {
-    fn from_kind_and_message(error_type: OperationErrorKind, message: String) -> Self {
+    pub(crate) fn from_kind_and_message(error_type: OperationErrorKind, message: String) -> Self {
         Self {
             error_kind: error_type,
             message,
@@ -122,3 +88,31 @@ impl fmt::Display for OperationError {
 }
 
 impl std::error::Error for OperationError {}
+
+/// Computes `C <- beta * C + alpha * op(A) * op(B)` one column at a time.
+///
+/// For each column `x` of `op(B)`, `apply(x, y)` must accumulate `op(A) * x` into `y`, which is
+/// zero-initialized and has `C.nrows()` entries.
+fn spmm_dense_columnwise<T, F>(
+    beta: T,
+    mut c: DMatrixViewMut<'_, T>,
+    alpha: T,
+    b: Op<DMatrixView<'_, T>>,
+    mut apply: F,
+) where
+    T: Scalar + ClosedAdd + ClosedMul + Zero + One,
+    F: FnMut(&DVector<T>, &mut DVector<T>),
+{
+    let mut y = DVector::zeros(c.nrows());
+    for j in 0..c.ncols() {
+        let x = match b {
+            Op::NoOp(ref b) => b.column(j).clone_owned(),
+            Op::Transpose(ref b) => b.row(j).transpose(),
+        };
+        y.fill(T::zero());
+        apply(&x, &mut y);
+        for (c_ij, y_i) in c.column_mut(j).iter_mut().zip(y.iter()) {
+            *c_ij = beta.clone() * c_ij.clone() + alpha.clone() * y_i.clone();
+        }
+    }
+}
@@ -76,7 +76,20 @@ pub fn spmm_csr_pattern(a: &SparsityPattern, b: &SparsityPattern) -> SparsityPat
         b.major_dim(),
         "a and b must have compatible dimensions"
     );
+    spmm_pattern_from_lanes(a.major_dim(), b.minor_dim(), |i| a.lane(i), |k| b.lane(k))
+}
 
+/// Sparse matrix multiplication pattern construction in CSR-centric terms, where `a_lane` and
+/// `b_lane` give the minor indices of the lanes of `A` and `B`.
+///
+/// This makes it possible to compute the pattern without an owned [`SparsityPattern`], e.g. for
+/// matrix views.
+pub(in crate::ops) fn spmm_pattern_from_lanes<'a>(
+    a_major_dim: usize,
+    b_minor_dim: usize,
+    a_lane: impl Fn(usize) -> &'a [usize],
+    b_lane: impl Fn(usize) -> &'a [usize],
+) -> SparsityPattern {
     let mut offsets = Vec::new();
     let mut indices = Vec::new();
     offsets.push(0);
@@ -85,41 +98,53 @@ pub fn spmm_csr_pattern(a: &SparsityPattern, b: &SparsityPattern) -> SparsityPat
     // on a major lane
     // TODO: Consider using a bitvec or similar here to reduce pressure on memory
     // (would cut memory use to 1/8, which might help reduce cache misses)
-    let mut visited = vec![false; b.minor_dim()];
-
-    for i in 0..a.major_dim() {
-        let a_lane_i = a.lane(i);
-        let c_lane_i_offset = *offsets.last().unwrap();
-        for &k in a_lane_i {
-            let b_lane_k = b.lane(k);
-
-            for &j in b_lane_k {
-                let have_visited_j = &mut visited[j];
-                if !*have_visited_j {
-                    indices.push(j);
-                    *have_visited_j = true;
-                }
-            }
-        }
-
-        let c_lane_i = &mut indices[c_lane_i_offset..];
-        c_lane_i.sort_unstable();
-
-        // Reset visits so that visited[j] == false for all j for the next major lane
-        for j in c_lane_i {
-            visited[*j] = false;
-        }
+    let mut visited = vec![false; b_minor_dim];
 
+    for i in 0..a_major_dim {
+        spmm_pattern_lane(a_lane(i), &b_lane, &mut visited, &mut indices);
         offsets.push(indices.len());
     }
 
-    SparsityPattern::try_from_offsets_and_indices(a.major_dim(), b.minor_dim(), offsets, indices)
+    SparsityPattern::try_from_offsets_and_indices(a_major_dim, b_minor_dim, offsets, indices)
         .expect("Internal error: Invalid pattern during matrix multiplication pattern construction")
 }
 
+/// Appends the sorted minor indices of the lane of `C = A * B` associated with the given lane
+/// of `A` to `indices`, where `b_lane` gives the minor indices of the lanes of `B`.
+///
+/// `visited` must have length equal to the minor dimension of `B` and contain only `false`.
+/// This is also the case when the function returns.
+pub(in crate::ops) fn spmm_pattern_lane<'a>(
+    a_lane_i: &[usize],
+    b_lane: &impl Fn(usize) -> &'a [usize],
+    visited: &mut [bool],
+    indices: &mut Vec<usize>,
+) {
+    let c_lane_i_offset = indices.len();
+    for &k in a_lane_i {
+        let b_lane_k = b_lane(k);
+
+        for &j in b_lane_k {
+            let have_visited_j = &mut visited[j];
+            if !*have_visited_j {
+                indices.push(j);
+                *have_visited_j = true;
+            }
+        }
+    }
+
+    let c_lane_i = &mut indices[c_lane_i_offset..];
+    c_lane_i.sort_unstable();
+
+    // Reset visits so that visited[j] == false for all j for the next major lane
+    for j in c_lane_i {
+        visited[*j] = false;
+    }
+}
+
 /// Iterate over the union of the two sets represented by sorted slices
 /// (with unique elements)
-fn iterate_union<'a>(
+pub(in crate::ops) fn iterate_union<'a>(
     mut sorted_a: &'a [usize],
     mut sorted_b: &'a [usize],
 ) -> impl Iterator<Item = usize> + 'a {
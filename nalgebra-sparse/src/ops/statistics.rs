@@ -0,0 +1,170 @@
+//! Reductions and statistics of CSR and CSC matrices.
+//!
+//! The method names follow those of the dense matrices in `nalgebra`, so that e.g. `row_sum`
+//! computes the sum of each column, returned as a row vector.
+
+use crate::cs::CsMatrix;
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use nalgebra::{ClosedAdd, ComplexField, DVector, RowDVector, Scalar};
+use num_traits::{Signed, Zero};
+
+impl<T> CsMatrix<T> {
+    /// The sum of the explicitly stored values of each major lane.
+    fn major_sums(&self) -> Vec<T>
+    where
+        T: Scalar + Zero + ClosedAdd,
+    {
+        self.lane_iter()
+            .map(|lane| {
+                lane.values()
+                    .iter()
+                    .fold(T::zero(), |sum, v| sum + v.clone())
+            })
+            .collect()
+    }
+
+    /// The sum of the explicitly stored values of each minor lane.
+    fn minor_sums(&self) -> Vec<T>
+    where
+        T: Scalar + Zero + ClosedAdd,
+    {
+        let mut sums = vec![T::zero(); self.pattern().minor_dim()];
+        for lane in self.lane_iter() {
+            for (&j, v) in lane.minor_indices().iter().zip(lane.values()) {
+                sums[j] += v.clone();
+            }
+        }
+        sums
+    }
+
+    /// The main diagonal, which is the same for a matrix and its transpose.
+    fn diagonal(&self) -> DVector<T>
+    where
+        T: Scalar + Zero,
+    {
+        let n = self.pattern().major_dim().min(self.pattern().minor_dim());
+        DVector::from_fn(n, |i, _| {
+            self.get_entry(i, i)
+                .expect("Internal error: Diagonal index must be in bounds.")
+                .into_value()
+        })
+    }
+}
+
+macro_rules! impl_statistics {
+    ($matrix_type:ident, $row_sums:ident, $column_sums:ident) => {
+        impl<T> $matrix_type<T> {
+            /// The sum of all the entries of the matrix.
+            #[must_use]
+            pub fn sum(&self) -> T
+            where
+                T: Scalar + Zero + ClosedAdd,
+            {
+                self.values()
+                    .iter()
+                    .fold(T::zero(), |sum, v| sum + v.clone())
+            }
+
+            /// The row vector of the sums of each column, i.e. the sum of all the rows.
+            #[must_use]
+            pub fn row_sum(&self) -> RowDVector<T>
+            where
+                T: Scalar + Zero + ClosedAdd,
+            {
+                RowDVector::from_vec(self.cs.$column_sums())
+            }
+
+            /// The column vector of the sums of each column, i.e. the transpose of
+            /// [`row_sum`](Self::row_sum).
+            #[must_use]
+            pub fn row_sum_tr(&self) -> DVector<T>
+            where
+                T: Scalar + Zero + ClosedAdd,
+            {
+                DVector::from_vec(self.cs.$column_sums())
+            }
+
+            /// The column vector of the sums of each row, i.e. the sum of all the columns.
+            #[must_use]
+            pub fn column_sum(&self) -> DVector<T>
+            where
+                T: Scalar + Zero + ClosedAdd,
+            {
+                DVector::from_vec(self.cs.$row_sums())
+            }
+
+            /// The squared Frobenius norm of the matrix.
+            #[must_use]
+            pub fn norm_squared(&self) -> T::RealField
+            where
+                T: ComplexField,
+            {
+                self.values().iter().fold(T::RealField::zero(), |sum, v| {
+                    sum + v.clone().modulus_squared()
+                })
+            }
+
+            /// The Frobenius norm of the matrix.
+            #[must_use]
+            pub fn norm(&self) -> T::RealField
+            where
+                T: ComplexField,
+            {
+                self.norm_squared().sqrt()
+            }
+
+            /// The largest absolute value of the entries of the matrix.
+            ///
+            /// Returns zero if the matrix has no explicitly stored entries.
+            #[must_use]
+            pub fn amax(&self) -> T
+            where
+                T: Scalar + Zero + Signed + PartialOrd,
+            {
+                self.values().iter().fold(T::zero(), |max, v| {
+                    let abs = v.abs();
+                    if abs > max {
+                        abs
+                    } else {
+                        max
+                    }
+                })
+            }
+
+            /// The largest 1-norm of the complex entries of the matrix, i.e. the largest value of
+            /// `|re| + |im|`.
+            ///
+            /// Returns zero if the matrix has no explicitly stored entries.
+            #[must_use]
+            pub fn camax(&self) -> T::RealField
+            where
+                T: ComplexField,
+            {
+                self.values().iter().fold(T::RealField::zero(), |max, v| {
+                    let norm = v.clone().norm1();
+                    if norm > max {
+                        norm
+                    } else {
+                        max
+                    }
+                })
+            }
+
+            /// The main diagonal of the matrix, including its implicit zeros.
+            ///
+            /// Unlike for dense matrices, the matrix does not need to be square. The length of
+            /// the diagonal is the smallest of the two dimensions of the matrix.
+            #[must_use]
+            pub fn diagonal(&self) -> DVector<T>
+            where
+                T: Scalar + Zero,
+            {
+                self.cs.diagonal()
+            }
+        }
+    };
+}
+
+impl_statistics!(CsrMatrix, major_sums, minor_sums);
+impl_statistics!(CscMatrix, minor_sums, major_sums);
@@ -1,13 +1,84 @@
 //! Sparsity patterns for CSR and CSC matrices.
 
+pub mod graph;
+
 #[cfg(feature = "serde-serialize")]
 mod pattern_serde;
 
 use crate::cs::transpose_cs;
 use crate::SparseFormatError;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 
+mod private {
+    pub trait Sealed {}
+}
+
+/// An integer type that can be used for the offsets and indices of sparse matrices.
+///
+/// The sparse matrix formats store their offsets and indices as `usize` by default. Using a
+/// narrower type such as `u32` or `i32` reduces the memory footprint (and hence the memory
+/// bandwidth requirements) of large matrices, and allows exchanging data with libraries that
+/// use a different index type without copying.
+///
+/// Every offset and index stored in a sparse matrix is non-negative and representable by
+/// `usize`, which is checked upon construction.
+///
+/// This trait is sealed, and is implemented for `usize`, `u32`, `u64`, `i32` and `i64`.
+pub trait SparseIndex: private::Sealed + Copy + Ord + fmt::Debug + Send + Sync + 'static {
+    /// Converts the index to `usize`.
+    ///
+    /// The result is only meaningful if the index is representable by `usize`, which is always
+    /// the case for offsets and indices stored in a sparse matrix.
+    fn index(self) -> usize;
+
+    /// Converts the index to `usize`, or returns `None` if it is not representable by `usize`.
+    fn try_index(self) -> Option<usize>;
+
+    /// Converts a `usize` to the index type, or returns `None` if it is not representable by
+    /// the index type.
+    fn try_from_index(index: usize) -> Option<Self>;
+
+    /// Converts a `usize` to the index type.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if `index` is not representable by the index type.
+    #[inline]
+    fn from_index(index: usize) -> Self {
+        Self::try_from_index(index).expect("Index must be representable by the index type.")
+    }
+}
+
+macro_rules! impl_sparse_index {
+    ($($t:ty),*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl SparseIndex for $t {
+                #[inline]
+                fn index(self) -> usize {
+                    self as usize
+                }
+
+                #[inline]
+                fn try_index(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                #[inline]
+                fn try_from_index(index: usize) -> Option<Self> {
+                    <$t>::try_from(index).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_sparse_index!(usize, u32, u64, i32, i64);
+
 /// A representation of the sparsity pattern of a CSR or CSC matrix.
 ///
 /// CSR and CSC matrices store matrices in a very similar fashion. In fact, in a certain sense,
@@ -43,12 +114,19 @@ use std::fmt;
 /// The invariants and relationship between `major_offsets` and `minor_indices` remain the same
 /// as for `row_offsets` and `col_indices` in the [CSR](`crate::csr::CsrMatrix`) format
 /// specification.
+///
+/// # Index type
+///
+/// The offsets and indices are stored as `usize` by default. Any other [`SparseIndex`] type
+/// can be used by constructing the pattern with
+/// [`try_from_offsets_and_indices_generic`](Self::try_from_offsets_and_indices_generic),
+/// or by converting an existing pattern with [`try_cast_indices`](Self::try_cast_indices).
+/// In addition to the invariants above, the dimensions of the pattern must then be
+/// representable by the index type.
 #[derive(Debug, Clone, PartialEq, Eq)]
-// TODO: Make SparsityPattern parametrized by index type
-// (need a solid abstraction for index types though)
-pub struct SparsityPattern {
-    major_offsets: Vec<usize>,
-    minor_indices: Vec<usize>,
+pub struct SparsityPattern<I = usize> {
+    major_offsets: Vec<I>,
+    minor_indices: Vec<I>,
     minor_dim: usize,
 }
 
@@ -62,17 +140,66 @@ impl SparsityPattern {
         }
     }
 
+    /// Try to construct a sparsity pattern from the given dimensions, major offsets
+    /// and minor indices.
+    ///
+    /// Returns an error if the data does not conform to the requirements.
+    pub fn try_from_offsets_and_indices(
+        major_dim: usize,
+        minor_dim: usize,
+        major_offsets: Vec<usize>,
+        minor_indices: Vec<usize>,
+    ) -> Result<Self, SparsityPatternFormatError> {
+        Self::try_from_offsets_and_indices_generic(
+            major_dim,
+            minor_dim,
+            major_offsets,
+            minor_indices,
+        )
+    }
+
+    /// Try to construct a sparsity pattern from the given dimensions, major offsets
+    /// and minor indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of major offsets is not exactly one greater than the major dimension
+    /// or if major offsets do not start with 0 and end with the number of minor indices.
+    pub unsafe fn from_offset_and_indices_unchecked(
+        major_dim: usize,
+        minor_dim: usize,
+        major_offsets: Vec<usize>,
+        minor_indices: Vec<usize>,
+    ) -> Self {
+        assert_eq!(major_offsets.len(), major_dim + 1);
+
+        // Check that the first and last offsets conform to the specification
+        {
+            let first_offset_ok = *major_offsets.first().unwrap() == 0;
+            let last_offset_ok = *major_offsets.last().unwrap() == minor_indices.len();
+            assert!(first_offset_ok && last_offset_ok);
+        }
+
+        Self {
+            major_offsets,
+            minor_indices,
+            minor_dim,
+        }
+    }
+}
+
+impl<I: SparseIndex> SparsityPattern<I> {
     /// The offsets for the major dimension.
     #[inline]
     #[must_use]
-    pub fn major_offsets(&self) -> &[usize] {
+    pub fn major_offsets(&self) -> &[I] {
         &self.major_offsets
     }
 
     /// The indices for the minor dimension.
     #[inline]
     #[must_use]
-    pub fn minor_indices(&self) -> &[usize] {
+    pub fn minor_indices(&self) -> &[I] {
         &self.minor_indices
     }
 
@@ -106,80 +233,49 @@ impl SparsityPattern {
     /// Panics if `major_index` is out of bounds.
     #[inline]
     #[must_use]
-    pub fn lane(&self, major_index: usize) -> &[usize] {
+    pub fn lane(&self, major_index: usize) -> &[I] {
         self.get_lane(major_index).unwrap()
     }
 
     /// Get the lane at the given index, or `None` if out of bounds.
     #[inline]
     #[must_use]
-    pub fn get_lane(&self, major_index: usize) -> Option<&[usize]> {
-        let offset_begin = *self.major_offsets().get(major_index)?;
-        let offset_end = *self.major_offsets().get(major_index + 1)?;
+    pub fn get_lane(&self, major_index: usize) -> Option<&[I]> {
+        let offset_begin = self.major_offsets().get(major_index)?.index();
+        let offset_end = self.major_offsets().get(major_index + 1)?.index();
         Some(&self.minor_indices()[offset_begin..offset_end])
     }
 
-    /// Try to construct a sparsity pattern from the given dimensions, major offsets
-    /// and minor indices.
+    /// Try to construct a sparsity pattern with an arbitrary index type from the given
+    /// dimensions, major offsets and minor indices.
     ///
-    /// Returns an error if the data does not conform to the requirements.
-    pub fn try_from_offsets_and_indices(
+    /// This is the same as
+    /// [`try_from_offsets_and_indices`](SparsityPattern::try_from_offsets_and_indices), except
+    /// that the index type is not restricted to `usize`. In addition to the format requirements,
+    /// an error is returned if the dimensions can not be represented by the index type.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::pattern::SparsityPattern;
+    /// let offsets: Vec<i32> = vec![0, 2, 3, 4];
+    /// let minor_indices: Vec<i32> = vec![0, 2, 1, 0];
+    /// let pattern =
+    ///     SparsityPattern::try_from_offsets_and_indices_generic(3, 4, offsets, minor_indices)
+    ///         .unwrap();
+    ///
+    /// assert_eq!(pattern.lane(0), &[0, 2]);
+    /// let entries: Vec<_> = pattern.entries().collect();
+    /// assert_eq!(entries, vec![(0, 0), (0, 2), (1, 1), (2, 0)]);
+    /// ```
+    pub fn try_from_offsets_and_indices_generic(
         major_dim: usize,
         minor_dim: usize,
-        major_offsets: Vec<usize>,
-        minor_indices: Vec<usize>,
+        major_offsets: Vec<I>,
+        minor_indices: Vec<I>,
     ) -> Result<Self, SparsityPatternFormatError> {
-        use SparsityPatternFormatError::*;
-
-        if major_offsets.len() != major_dim + 1 {
-            return Err(InvalidOffsetArrayLength);
-        }
-
-        // Check that the first and last offsets conform to the specification
-        {
-            let first_offset_ok = *major_offsets.first().unwrap() == 0;
-            let last_offset_ok = *major_offsets.last().unwrap() == minor_indices.len();
-            if !first_offset_ok || !last_offset_ok {
-                return Err(InvalidOffsetFirstLast);
-            }
-        }
-
-        // Test that each lane has strictly monotonically increasing minor indices, i.e.
-        // minor indices within a lane are sorted, unique. In addition, each minor index
-        // must be in bounds with respect to the minor dimension.
-        {
-            for lane_idx in 0..major_dim {
-                let range_start = major_offsets[lane_idx];
-                let range_end = major_offsets[lane_idx + 1];
-
-                // Test that major offsets are monotonically increasing
-                if range_start > range_end {
-                    return Err(NonmonotonicOffsets);
-                }
-
-                let minor_indices = &minor_indices[range_start..range_end];
-
-                // We test for in-bounds, uniqueness and monotonicity at the same time
-                // to ensure that we only visit each minor index once
-                let mut iter = minor_indices.iter();
-                let mut prev = None;
-
-                while let Some(next) = iter.next().copied() {
-                    if next >= minor_dim {
-                        return Err(MinorIndexOutOfBounds);
-                    }
-
-                    if let Some(prev) = prev {
-                        if prev > next {
-                            return Err(NonmonotonicMinorIndices);
-                        } else if prev == next {
-                            return Err(DuplicateEntry);
-                        }
-                    }
-                    prev = Some(next);
-                }
-            }
-        }
+        validate_offsets_and_indices(major_dim, minor_dim, &major_offsets, &minor_indices)?;
 
         Ok(Self {
             major_offsets,
@@ -188,35 +284,6 @@ impl SparsityPattern {
         })
     }
 
-    /// Try to construct a sparsity pattern from the given dimensions, major offsets
-    /// and minor indices.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of major offsets is not exactly one greater than the major dimension
-    /// or if major offsets do not start with 0 and end with the number of minor indices.
-    pub unsafe fn from_offset_and_indices_unchecked(
-        major_dim: usize,
-        minor_dim: usize,
-        major_offsets: Vec<usize>,
-        minor_indices: Vec<usize>,
-    ) -> Self {
-        assert_eq!(major_offsets.len(), major_dim + 1);
-
-        // Check that the first and last offsets conform to the specification
-        {
-            let first_offset_ok = *major_offsets.first().unwrap() == 0;
-            let last_offset_ok = *major_offsets.last().unwrap() == minor_indices.len();
-            assert!(first_offset_ok && last_offset_ok);
-        }
-
-        Self {
-            major_offsets,
-            minor_indices,
-            minor_dim,
-        }
-    }
-
     /// An iterator over the explicitly stored "non-zero" entries (i, j).
     ///
     /// The iteration happens in a lane-major fashion, meaning that the lane index i
@@ -238,8 +305,8 @@ impl SparsityPattern {
     /// ```
     ///
     #[must_use]
-    pub fn entries(&self) -> SparsityPatternIter<'_> {
-        SparsityPatternIter::from_pattern(self)
+    pub fn entries(&self) -> SparsityPatternIter<'_, I> {
+        SparsityPatternIter::from_offsets_and_indices(self.major_offsets(), self.minor_indices())
     }
 
     /// Returns the raw offset and index data for the sparsity pattern.
@@ -261,7 +328,7 @@ impl SparsityPattern {
     /// assert_eq!(offsets2, offsets);
     /// assert_eq!(minor_indices2, minor_indices);
     /// ```
-    pub fn disassemble(self) -> (Vec<usize>, Vec<usize>) {
+    pub fn disassemble(self) -> (Vec<I>, Vec<I>) {
         (self.major_offsets, self.minor_indices)
     }
 
@@ -281,7 +348,7 @@ impl SparsityPattern {
             &values,
         );
         // TODO: Skip checks
-        Self::try_from_offsets_and_indices(
+        Self::try_from_offsets_and_indices_generic(
             self.minor_dim(),
             self.major_dim(),
             new_offsets,
@@ -289,6 +356,131 @@ impl SparsityPattern {
         )
         .expect("Internal error: Transpose should never fail.")
     }
+
+    /// Converts the offsets and indices of the pattern to a different index type.
+    ///
+    /// Returns an error if the dimensions or the number of entries of the pattern can not be
+    /// represented by the new index type.
+    ///
+    /// Examples
+    /// --------
+    ///
+    /// ```
+    /// # use nalgebra_sparse::pattern::{SparsityPattern, SparsityPatternFormatError};
+    /// let pattern = SparsityPattern::try_from_offsets_and_indices(2, 3, vec![0, 1, 2], vec![2, 0])
+    ///     .unwrap();
+    /// let narrow = pattern.clone().try_cast_indices::<u32>().unwrap();
+    /// assert_eq!(narrow.minor_indices(), &[2u32, 0]);
+    /// assert_eq!(narrow.try_cast_indices::<usize>().unwrap(), pattern);
+    ///
+    /// let wide = SparsityPattern::zeros(1, 1 << 32);
+    /// let error = wide.try_cast_indices::<i32>().unwrap_err();
+    /// assert_eq!(error, SparsityPatternFormatError::IndexOverflow);
+    /// ```
+    pub fn try_cast_indices<J: SparseIndex>(
+        self,
+    ) -> Result<SparsityPattern<J>, SparsityPatternFormatError> {
+        // All offsets and indices are bounded by the dimensions and the number of entries
+        let (major_dim, minor_dim, nnz) = (self.major_dim(), self.minor_dim(), self.nnz());
+        if [major_dim, minor_dim, nnz]
+            .iter()
+            .any(|&bound| J::try_from_index(bound).is_none())
+        {
+            return Err(SparsityPatternFormatError::IndexOverflow);
+        }
+
+        let cast = |indices: Vec<I>| -> Vec<J> {
+            indices
+                .into_iter()
+                .map(|index| J::from_index(index.index()))
+                .collect()
+        };
+        Ok(SparsityPattern {
+            major_offsets: cast(self.major_offsets),
+            minor_indices: cast(self.minor_indices),
+            minor_dim,
+        })
+    }
+}
+
+/// Checks that the given dimensions, major offsets and minor indices describe a valid sparsity
+/// pattern.
+///
+/// This is the validation performed by
+/// [`SparsityPattern::try_from_offsets_and_indices_generic`], which is also used for validating
+/// borrowed data that is never assembled into an owned pattern.
+pub(crate) fn validate_offsets_and_indices<I: SparseIndex>(
+    major_dim: usize,
+    minor_dim: usize,
+    major_offsets: &[I],
+    minor_indices: &[I],
+) -> Result<(), SparsityPatternFormatError> {
+    use SparsityPatternFormatError::*;
+
+    if major_offsets.len() != major_dim + 1 {
+        return Err(InvalidOffsetArrayLength);
+    }
+
+    if I::try_from_index(major_dim).is_none() || I::try_from_index(minor_dim).is_none() {
+        return Err(IndexOverflow);
+    }
+
+    // Check that the first and last offsets conform to the specification
+    {
+        let first_offset_ok = major_offsets.first().unwrap().try_index() == Some(0);
+        let last_offset_ok = major_offsets.last().unwrap().try_index() == Some(minor_indices.len());
+        if !first_offset_ok || !last_offset_ok {
+            return Err(InvalidOffsetFirstLast);
+        }
+    }
+
+    // Test that each lane has strictly monotonically increasing minor indices, i.e.
+    // minor indices within a lane are sorted, unique. In addition, each minor index
+    // must be in bounds with respect to the minor dimension.
+    {
+        for lane_idx in 0..major_dim {
+            // Since the first offset is zero and the last offset is representable by usize,
+            // an offset that is not representable by usize is necessarily non-monotonic
+            let range_start = major_offsets[lane_idx]
+                .try_index()
+                .ok_or(NonmonotonicOffsets)?;
+            let range_end = major_offsets[lane_idx + 1]
+                .try_index()
+                .ok_or(NonmonotonicOffsets)?;
+
+            // Test that major offsets are monotonically increasing
+            if range_start > range_end {
+                return Err(NonmonotonicOffsets);
+            }
+
+            let minor_indices = minor_indices
+                .get(range_start..range_end)
+                .ok_or(NonmonotonicOffsets)?;
+
+            // We test for in-bounds, uniqueness and monotonicity at the same time
+            // to ensure that we only visit each minor index once
+            let mut iter = minor_indices.iter();
+            let mut prev = None;
+
+            while let Some(next) = iter.next().copied() {
+                let next = next
+                    .try_index()
+                    .filter(|&next| next < minor_dim)
+                    .ok_or(MinorIndexOutOfBounds)?;
+
+                if let Some(prev) = prev {
+                    if prev > next {
+                        return Err(NonmonotonicMinorIndices);
+                    } else if prev == next {
+                        return Err(DuplicateEntry);
+                    }
+                }
+                prev = Some(next);
+            }
+        }
+    }
+
+    Ok(())
 }
 
 /// Error type for `SparsityPattern` format errors.
@@ -316,6 +508,9 @@ pub enum SparsityPatternFormatError {
     DuplicateEntry,
     /// Indicates that minor indices are not monotonically increasing within each lane.
     NonmonotonicMinorIndices,
+    /// Indicates that the dimensions or the number of entries can not be represented by the
+    /// index type.
+    IndexOverflow,
 }
 
 impl From<SparsityPatternFormatError> for SparseFormatError {
@@ -331,7 +526,7 @@ impl From<SparsityPatternFormatError> for SparseFormatError {
             | NonmonotonicMinorIndices => {
                 SparseFormatError::from_kind_and_error(InvalidStructure, Box::from(err))
             }
-            MinorIndexOutOfBounds => {
+            MinorIndexOutOfBounds | IndexOverflow => {
                 SparseFormatError::from_kind_and_error(IndexOutOfBounds, Box::from(err))
             }
             PatternDuplicateEntry => SparseFormatError::from_kind_and_error(
@@ -367,6 +562,12 @@ impl fmt::Display for SparsityPatternFormatError {
                     "Minor indices are not monotonically increasing within each lane."
                 )
             }
+            SparsityPatternFormatError::IndexOverflow => {
+                write!(
+                    f,
+                    "Dimensions or number of entries can not be represented by the index type."
+                )
+            }
         }
     }
 }
@@ -375,28 +576,28 @@ impl Error for SparsityPatternFormatError {}
 
 /// Iterator type for iterating over entries in a sparsity pattern.
 #[derive(Debug, Clone)]
-pub struct SparsityPatternIter<'a> {
+pub struct SparsityPatternIter<'a, I = usize> {
     // See implementation of Iterator::next for an explanation of how these members are used
-    major_offsets: &'a [usize],
-    minor_indices: &'a [usize],
+    major_offsets: &'a [I],
+    minor_indices: &'a [I],
     current_lane_idx: usize,
-    remaining_minors_in_lane: &'a [usize],
+    remaining_minors_in_lane: &'a [I],
 }
 
-impl<'a> SparsityPatternIter<'a> {
-    fn from_pattern(pattern: &'a SparsityPattern) -> Self {
-        let first_lane_end = pattern.major_offsets().get(1).unwrap_or(&0);
-        let minors_in_first_lane = &pattern.minor_indices()[0..*first_lane_end];
+impl<'a, I: SparseIndex> SparsityPatternIter<'a, I> {
+    pub(crate) fn from_offsets_and_indices(major_offsets: &'a [I], minor_indices: &'a [I]) -> Self {
+        let first_lane_end = major_offsets.get(1).map_or(0, |end| end.index());
+        let minors_in_first_lane = &minor_indices[0..first_lane_end];
         Self {
-            major_offsets: pattern.major_offsets(),
-            minor_indices: pattern.minor_indices(),
+            major_offsets,
+            minor_indices,
             current_lane_idx: 0,
             remaining_minors_in_lane: minors_in_first_lane,
         }
     }
 }
 
-impl<'a> Iterator for SparsityPatternIter<'a> {
+impl<'a, I: SparseIndex> Iterator for SparsityPatternIter<'a, I> {
     type Item = (usize, usize);
 
     #[inline]
@@ -409,7 +610,7 @@ impl<'a> Iterator for SparsityPatternIter<'a> {
         // This way we can avoid doing unnecessary bookkeeping on every iteration,
         // instead paying a small price whenever we jump to a new lane.
         if let Some(minor_idx) = self.remaining_minors_in_lane.first() {
-            let item = Some((self.current_lane_idx, *minor_idx));
+            let item = Some((self.current_lane_idx, minor_idx.index()));
             self.remaining_minors_in_lane = &self.remaining_minors_in_lane[1..];
             item
         } else {
@@ -422,11 +623,11 @@ impl<'a> Iterator for SparsityPatternIter<'a> {
                 } else {
                     // Bump lane index and check if the lane is non-empty
                     self.current_lane_idx += 1;
-                    let lower = self.major_offsets[self.current_lane_idx];
-                    let upper = self.major_offsets[self.current_lane_idx + 1];
+                    let lower = self.major_offsets[self.current_lane_idx].index();
+                    let upper = self.major_offsets[self.current_lane_idx + 1].index();
                     if upper > lower {
                         self.remaining_minors_in_lane = &self.minor_indices[(lower + 1)..upper];
-                        return Some((self.current_lane_idx, self.minor_indices[lower]));
+                        return Some((self.current_lane_idx, self.minor_indices[lower].index()));
                     }
                 }
             }
@@ -0,0 +1,369 @@
+//! Graph algorithms on sparsity patterns.
+//!
+//! A square [SparsityPattern] is the adjacency structure of a graph whose nodes are the indices
+//! `0 .. n`, with an edge from the major index `i` to the minor index `j` for every explicitly
+//! stored entry `(i, j)`. Many preprocessing steps for sparse matrices are graph algorithms on
+//! this structure, such as finding disconnected blocks, computing orderings that reduce the
+//! bandwidth, or coloring the nodes for parallel assembly and finite-difference Jacobians.
+//!
+//! Apart from [connected_components], the functions in this module assume that the pattern is
+//! structurally symmetric, i.e. that the entry `(j, i)` is stored whenever `(i, j)` is. A
+//! non-symmetric pattern can be symmetrized by adding its [transpose](SparsityPattern::transpose)
+//! with [spadd_pattern](crate::ops::serial::spadd_pattern).
+//!
+//! Examples
+//! --------
+//!
+//! ```
+//! use nalgebra_sparse::pattern::graph::{bandwidth, bfs_levels, connected_components};
+//! use nalgebra_sparse::CsrMatrix;
+//! use nalgebra::DMatrix;
+//!
+//! // Two disconnected paths 0 - 1 - 2 and 3 - 4
+//! let dense = DMatrix::from_row_slice(5, 5, &[
+//!     1, 1, 0, 0, 0,
+//!     1, 1, 1, 0, 0,
+//!     0, 1, 1, 0, 0,
+//!     0, 0, 0, 1, 1,
+//!     0, 0, 0, 1, 1,
+//! ]);
+//! let pattern = CsrMatrix::from(&dense).pattern().clone();
+//!
+//! let (num_components, labels) = connected_components(&pattern);
+//! assert_eq!(num_components, 2);
+//! assert_eq!(labels, vec![0, 0, 0, 1, 1]);
+//!
+//! assert_eq!(bfs_levels(&pattern, 0), vec![vec![0], vec![1], vec![2]]);
+//! assert_eq!(bandwidth(&pattern), 1);
+//! ```
+
+use crate::pattern::{SparseIndex, SparsityPattern};
+
+fn assert_square<I: SparseIndex>(pattern: &SparsityPattern<I>) {
+    assert_eq!(
+        pattern.major_dim(),
+        pattern.minor_dim(),
+        "Major and minor dimensions must be the same (square pattern)."
+    );
+}
+
+/// The neighbors of node `i`, i.e. the minor indices in the major lane `i`.
+fn neighbors<I: SparseIndex>(
+    pattern: &SparsityPattern<I>,
+    i: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    pattern.lane(i).iter().map(|j| j.index())
+}
+
+/// Computes the connected components of the graph of a square pattern.
+///
+/// Returns the number of components and the component of each node. The components are
+/// numbered in increasing order of their smallest node. Every stored entry `(i, j)` connects `i`
+/// and `j` regardless of direction, so that the weakly connected components are returned for
+/// non-symmetric patterns.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square.
+#[must_use]
+pub fn connected_components<I: SparseIndex>(pattern: &SparsityPattern<I>) -> (usize, Vec<usize>) {
+    assert_square(pattern);
+    let n = pattern.major_dim();
+
+    // Union-find with path halving, where each tree is rooted at its smallest node
+    let mut parent: Vec<_> = (0..n).collect();
+    let find = |parent: &mut [usize], mut i: usize| {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    };
+    for i in 0..n {
+        for j in neighbors(pattern, i) {
+            let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, j));
+            let (min, max) = (root_i.min(root_j), root_i.max(root_j));
+            parent[max] = min;
+        }
+    }
+
+    // Since roots are the smallest nodes of their trees, the root of node i has already been
+    // labeled when i is visited, unless i is a root itself
+    let mut labels = vec![0; n];
+    let mut num_components = 0;
+    for i in 0..n {
+        let root = find(&mut parent, i);
+        if root == i {
+            labels[i] = num_components;
+            num_components += 1;
+        } else {
+            labels[i] = labels[root];
+        }
+    }
+    (num_components, labels)
+}
+
+/// Computes the level structure of a breadth-first search starting in the given root node.
+///
+/// Level `k` contains the nodes at distance `k` from the root, in the order in which they are
+/// visited. Only the nodes that are reachable from the root are included.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square, or if the root is out of bounds.
+#[must_use]
+pub fn bfs_levels<I: SparseIndex>(pattern: &SparsityPattern<I>, root: usize) -> Vec<Vec<usize>> {
+    assert_square(pattern);
+    assert!(
+        root < pattern.major_dim(),
+        "Root node must be smaller than the number of nodes."
+    );
+
+    let mut visited = vec![false; pattern.major_dim()];
+    visited[root] = true;
+    let mut levels = vec![vec![root]];
+    loop {
+        let mut next_level = Vec::new();
+        for &i in levels.last().unwrap() {
+            for j in neighbors(pattern, i) {
+                if !visited[j] {
+                    visited[j] = true;
+                    next_level.push(j);
+                }
+            }
+        }
+        if next_level.is_empty() {
+            return levels;
+        }
+        levels.push(next_level);
+    }
+}
+
+/// Finds a pseudo-peripheral node in the connected component of the given start node.
+///
+/// A pseudo-peripheral node is a node whose breadth-first search level structure is
+/// approximately as deep as possible, which makes it a good starting node for bandwidth and
+/// profile reducing orderings such as reverse Cuthill-McKee. The node is found with the
+/// algorithm of Gibbs, Poole and Stockmeyer as modified by George and Liu, which repeatedly
+/// restarts the search from a node of minimum degree in the last level.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square, or if the start node is out of bounds.
+#[must_use]
+pub fn pseudo_peripheral_node<I: SparseIndex>(pattern: &SparsityPattern<I>, start: usize) -> usize {
+    let mut node = start;
+    let mut levels = bfs_levels(pattern, node);
+    loop {
+        let candidate = levels
+            .last()
+            .unwrap()
+            .iter()
+            .copied()
+            .min_by_key(|&i| pattern.lane(i).len())
+            .unwrap();
+        let candidate_levels = bfs_levels(pattern, candidate);
+        if candidate_levels.len() <= levels.len() {
+            return node;
+        }
+        node = candidate;
+        levels = candidate_levels;
+    }
+}
+
+/// Computes the bandwidth of a pattern, i.e. the largest distance `|i - j|` of a stored entry
+/// `(i, j)` from the diagonal.
+///
+/// The bandwidth of a pattern without explicitly stored entries is zero.
+#[must_use]
+pub fn bandwidth<I: SparseIndex>(pattern: &SparsityPattern<I>) -> usize {
+    pattern
+        .entries()
+        .map(|(i, j)| i.abs_diff(j))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Computes the profile, or envelope size, of a pattern.
+///
+/// The profile is the sum over all major lanes `i` of the distance `i - j` between the diagonal
+/// and the smallest stored minor index `j <= i` in the lane. Lanes without stored entries on or
+/// before the diagonal do not contribute. For a symmetric pattern, this is the number of entries
+/// strictly below the diagonal that a skyline (envelope) storage scheme would store.
+#[must_use]
+pub fn profile<I: SparseIndex>(pattern: &SparsityPattern<I>) -> usize {
+    (0..pattern.major_dim())
+        .filter_map(|i| {
+            // The minor indices are sorted, so the first is the smallest
+            let j = pattern.lane(i).first()?.index();
+            (j <= i).then(|| i - j)
+        })
+        .sum()
+}
+
+/// Computes the elimination tree of a square, symmetric pattern.
+///
+/// Entry `i` of the result is the parent of node `i` in the elimination tree, or `None` if `i`
+/// is a root. The elimination tree describes the dependencies between the columns of the
+/// Cholesky factor of a matrix with the given pattern. Only the entries `(k, i)` with `i < k`
+/// are used, so that it is sufficient for the pattern to store one triangle of the matrix:
+/// the lower triangle in CSR, or the upper triangle in CSC format.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square.
+#[must_use]
+pub fn elimination_tree<I: SparseIndex>(pattern: &SparsityPattern<I>) -> Vec<Option<usize>> {
+    assert_square(pattern);
+    let n = pattern.major_dim();
+    let mut parent = vec![None; n];
+    // Path compressed ancestors, which lead to the current root of each subtree
+    let mut ancestor: Vec<Option<usize>> = vec![None; n];
+
+    for k in 0..n {
+        for mut i in neighbors(pattern, k) {
+            while i < k {
+                let i_ancestor = ancestor[i].replace(k);
+                match i_ancestor {
+                    Some(a) => i = a,
+                    None => {
+                        parent[i] = Some(k);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    parent
+}
+
+/// Computes a postorder of a forest given by the parent of each node, such as an
+/// [elimination tree](elimination_tree).
+///
+/// In a postorder, every node appears after all of its descendants, and the nodes of every
+/// subtree are numbered consecutively. The children of a node, and the roots of the forest, are
+/// visited in increasing order.
+///
+/// # Panics
+///
+/// Panics if a parent is out of bounds, or if the parents do not form a forest.
+#[must_use]
+pub fn postorder(parent: &[Option<usize>]) -> Vec<usize> {
+    let n = parent.len();
+    // The children of every node as linked lists. Since the nodes are inserted in reverse order,
+    // every list is sorted increasingly.
+    let mut first_child = vec![None; n];
+    let mut next_sibling = vec![None; n];
+    let mut roots = Vec::new();
+    for i in (0..n).rev() {
+        match parent[i] {
+            Some(p) => {
+                assert!(p < n, "Parent must be smaller than the number of nodes.");
+                next_sibling[i] = first_child[p].replace(i);
+            }
+            None => roots.push(i),
+        }
+    }
+
+    let mut order = Vec::with_capacity(n);
+    let mut stack = Vec::new();
+    for &root in roots.iter().rev() {
+        stack.push(root);
+        while let Some(&i) = stack.last() {
+            // Descend into the next unvisited child, or emit the node when all children are done
+            match first_child[i].take() {
+                Some(child) => {
+                    first_child[i] = next_sibling[child];
+                    stack.push(child);
+                }
+                None => {
+                    stack.pop();
+                    order.push(i);
+                }
+            }
+        }
+    }
+
+    assert_eq!(order.len(), n, "Parents must form a forest.");
+    order
+}
+
+/// Colors the nodes of the graph of a square, symmetric pattern such that adjacent nodes have
+/// different colors.
+///
+/// Returns the number of colors and the color of each node. The nodes are colored greedily in
+/// increasing order, each with the smallest color that is not used by its neighbors. Diagonal
+/// entries are ignored.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square.
+#[must_use]
+pub fn greedy_coloring<I: SparseIndex>(pattern: &SparsityPattern<I>) -> (usize, Vec<usize>) {
+    greedy_coloring_with(pattern, |i, forbid| {
+        neighbors(pattern, i).for_each(&mut *forbid);
+    })
+}
+
+/// Colors the nodes of the graph of a square, symmetric pattern such that nodes at distance one
+/// or two have different colors.
+///
+/// Returns the number of colors and the color of each node. The nodes are colored greedily in
+/// increasing order.
+///
+/// With a distance-2 coloring, the columns of a matrix with the given pattern that share a color
+/// have no row in common. The Jacobian of a function with this sparsity pattern can therefore be
+/// estimated with one finite difference per color, perturbing all the variables of a color at
+/// once. Likewise, the rows of the same color can be processed in parallel when each row updates
+/// the entries of its neighbors.
+///
+/// # Panics
+///
+/// Panics if the pattern is not square.
+#[must_use]
+pub fn distance2_coloring<I: SparseIndex>(pattern: &SparsityPattern<I>) -> (usize, Vec<usize>) {
+    greedy_coloring_with(pattern, |i, forbid| {
+        for j in neighbors(pattern, i) {
+            forbid(j);
+            neighbors(pattern, j).for_each(&mut *forbid);
+        }
+    })
+}
+
+/// Greedily colors the nodes in increasing order, where `visit_conflicts(i, forbid)` calls
+/// `forbid(j)` for every node `j` that must have a different color than `i`.
+fn greedy_coloring_with<I, F>(
+    pattern: &SparsityPattern<I>,
+    mut visit_conflicts: F,
+) -> (usize, Vec<usize>)
+where
+    I: SparseIndex,
+    F: FnMut(usize, &mut dyn FnMut(usize)),
+{
+    assert_square(pattern);
+    let n = pattern.major_dim();
+    const UNCOLORED: usize = usize::MAX;
+    let mut colors = vec![UNCOLORED; n];
+    // The last node for which each color was forbidden, which avoids clearing the marks
+    let mut forbidden_for: Vec<usize> = Vec::new();
+
+    for i in 0..n {
+        visit_conflicts(i, &mut |j| {
+            let color = colors[j];
+            if j != i && color != UNCOLORED {
+                forbidden_for[color] = i;
+            }
+        });
+        let color = forbidden_for
+            .iter()
+            .position(|&node| node != i)
+            .unwrap_or_else(|| {
+                forbidden_for.push(UNCOLORED);
+                forbidden_for.len() - 1
+            });
+        colors[i] = color;
+    }
+
+    (forbidden_for.len(), colors)
+}
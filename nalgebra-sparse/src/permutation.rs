@@ -0,0 +1,349 @@
+//! Permutations of the rows and columns of sparse matrices.
+//!
+//! A [Permutation] of length `n` is a reordering of the indices `0 .. n`. It is stored as the list
+//! of old indices in their new order, i.e. permuting a sequence `x` by the permutation `p` gives
+//! the sequence `y` with `y[i] = x[p[i]]`. In terms of matrices, permuting the rows of `A` gives
+//! `P A`, where `P` is the permutation matrix with ones at `(i, p[i])`. Likewise, permuting the
+//! columns gives `A Pᵀ` and permuting symmetrically gives `P A Pᵀ`.
+//!
+//! The permutations are applied directly to the compressed index arrays of [CsrMatrix],
+//! [CscMatrix] and [SparsityPattern], without going through an intermediate format.
+//!
+//! Examples
+//! --------
+//!
+//! ```
+//! use nalgebra_sparse::permutation::Permutation;
+//! use nalgebra_sparse::CsrMatrix;
+//! use nalgebra::DMatrix;
+//!
+//! let dense = DMatrix::from_row_slice(3, 3, &[1.0, 2.0, 0.0,
+//!                                             0.0, 3.0, 0.0,
+//!                                             4.0, 0.0, 5.0]);
+//! let csr = CsrMatrix::from(&dense);
+//!
+//! // Reverse the order of the rows and columns
+//! let p = Permutation::try_from_vec(vec![2, 1, 0]).unwrap();
+//! let permuted = csr.permute_symmetric(&p);
+//! assert_eq!(DMatrix::from(&permuted), DMatrix::from_row_slice(3, 3, &[5.0, 0.0, 4.0,
+//!                                                                      0.0, 3.0, 0.0,
+//!                                                                      0.0, 2.0, 1.0]));
+//!
+//! // Permuting by the inverse permutation restores the original matrix
+//! assert_eq!(permuted.permute_symmetric(&p.inverse()), csr);
+//! ```
+
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::edition::{select_cs, select_pattern};
+use crate::pattern::SparsityPattern;
+use crate::utils::apply_permutation;
+use crate::{SparseFormatError, SparseFormatErrorKind};
+
+/// A permutation of the indices `0 .. n`.
+///
+/// See the [module-level documentation](self) for the conventions used.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    // The old index of each new index
+    perm: Vec<usize>,
+    // The new index of each old index
+    inverse: Vec<usize>,
+}
+
+impl Permutation {
+    /// The identity permutation of length `n`.
+    #[must_use]
+    pub fn identity(n: usize) -> Self {
+        Self {
+            perm: (0..n).collect(),
+            inverse: (0..n).collect(),
+        }
+    }
+
+    /// Tries to construct a permutation from the list of old indices in their new order.
+    ///
+    /// Returns an error if the list is not a permutation of `0 .. perm.len()`, i.e. if an index
+    /// is out of bounds or appears more than once.
+    pub fn try_from_vec(perm: Vec<usize>) -> Result<Self, SparseFormatError> {
+        let n = perm.len();
+        let mut inverse = vec![usize::MAX; n];
+        for (new_index, &old_index) in perm.iter().enumerate() {
+            if old_index >= n {
+                return Err(SparseFormatError::from_kind_and_msg(
+                    SparseFormatErrorKind::IndexOutOfBounds,
+                    "Permutation index is out of bounds.",
+                ));
+            }
+            if inverse[old_index] != usize::MAX {
+                return Err(SparseFormatError::from_kind_and_msg(
+                    SparseFormatErrorKind::DuplicateEntry,
+                    "Permutation index appears more than once.",
+                ));
+            }
+            inverse[old_index] = new_index;
+        }
+        Ok(Self { perm, inverse })
+    }
+
+    /// The length of the permutation.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.perm.len()
+    }
+
+    /// Whether the permutation has length zero.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.perm.is_empty()
+    }
+
+    /// The old index of each new index.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// The new index of each old index, i.e. the list representation of the inverse permutation.
+    #[inline]
+    #[must_use]
+    pub fn inverse_slice(&self) -> &[usize] {
+        &self.inverse
+    }
+
+    /// The inverse permutation, which undoes this permutation.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self {
+            perm: self.inverse.clone(),
+            inverse: self.perm.clone(),
+        }
+    }
+
+    /// The composition of this permutation with another permutation.
+    ///
+    /// Permuting by the result is the same as first permuting by `other`, and then by `self`.
+    /// In terms of permutation matrices, this is the product `P Q`, where `P` belongs to `self`
+    /// and `Q` belongs to `other`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the permutations do not have the same length.
+    #[must_use]
+    pub fn compose(&self, other: &Permutation) -> Self {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "Permutations must have the same length."
+        );
+        let perm = self.perm.iter().map(|&i| other.perm[i]).collect();
+        let inverse = other.inverse.iter().map(|&i| self.inverse[i]).collect();
+        Self { perm, inverse }
+    }
+
+    /// Permutes the given slice, returning `y` with `y[i] = x[p[i]]`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the slice does not have the same length as the permutation.
+    #[must_use]
+    pub fn permute_slice<T: Clone>(&self, x: &[T]) -> Vec<T> {
+        assert_eq!(
+            x.len(),
+            self.len(),
+            "Slice length must be equal to the permutation length."
+        );
+        let mut y = x.to_vec();
+        apply_permutation(&mut y, x, &self.perm);
+        y
+    }
+
+    /// Consumes the permutation, returning the list of old indices in their new order.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<usize> {
+        self.perm
+    }
+}
+
+impl<T: Clone> CsrMatrix<T> {
+    /// Permutes the rows of the matrix, computing `P A`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the number of rows.
+    #[must_use]
+    pub fn permute_rows(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.nrows(),
+            "Permutation length must match nrows."
+        );
+        Self {
+            cs: select_cs(&self.cs, Some(perm.as_slice()), None),
+        }
+    }
+
+    /// Permutes the columns of the matrix, computing `A Pᵀ`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the number of columns.
+    #[must_use]
+    pub fn permute_columns(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.ncols(),
+            "Permutation length must match ncols."
+        );
+        Self {
+            cs: select_cs(&self.cs, None, Some(perm.as_slice())),
+        }
+    }
+
+    /// Permutes the rows and columns of a square matrix, computing `P A Pᵀ`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrix is not square, or if the length of the permutation is not equal to
+    /// the number of rows.
+    #[must_use]
+    pub fn permute_symmetric(&self, perm: &Permutation) -> Self {
+        assert_eq!(self.nrows(), self.ncols(), "Matrix must be square.");
+        assert_eq!(
+            perm.len(),
+            self.nrows(),
+            "Permutation length must match nrows."
+        );
+        Self {
+            cs: select_cs(&self.cs, Some(perm.as_slice()), Some(perm.as_slice())),
+        }
+    }
+}
+
+impl<T: Clone> CscMatrix<T> {
+    /// Permutes the rows of the matrix, computing `P A`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the number of rows.
+    #[must_use]
+    pub fn permute_rows(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.nrows(),
+            "Permutation length must match nrows."
+        );
+        Self {
+            cs: select_cs(&self.cs, None, Some(perm.as_slice())),
+        }
+    }
+
+    /// Permutes the columns of the matrix, computing `A Pᵀ`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the number of columns.
+    #[must_use]
+    pub fn permute_columns(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.ncols(),
+            "Permutation length must match ncols."
+        );
+        Self {
+            cs: select_cs(&self.cs, Some(perm.as_slice()), None),
+        }
+    }
+
+    /// Permutes the rows and columns of a square matrix, computing `P A Pᵀ`.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the matrix is not square, or if the length of the permutation is not equal to
+    /// the number of rows.
+    #[must_use]
+    pub fn permute_symmetric(&self, perm: &Permutation) -> Self {
+        assert_eq!(self.nrows(), self.ncols(), "Matrix must be square.");
+        assert_eq!(
+            perm.len(),
+            self.nrows(),
+            "Permutation length must match nrows."
+        );
+        Self {
+            cs: select_cs(&self.cs, Some(perm.as_slice()), Some(perm.as_slice())),
+        }
+    }
+}
+
+impl SparsityPattern {
+    /// Permutes the major lanes of the pattern.
+    ///
+    /// This permutes the rows of the pattern of a CSR matrix, and the columns of the pattern of
+    /// a CSC matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the major dimension.
+    #[must_use]
+    pub fn permute_major(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.major_dim(),
+            "Permutation length must match the major dimension."
+        );
+        select_pattern(self, Some(perm.as_slice()), None).0
+    }
+
+    /// Permutes the minor lanes of the pattern.
+    ///
+    /// This permutes the columns of the pattern of a CSR matrix, and the rows of the pattern of
+    /// a CSC matrix.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the length of the permutation is not equal to the minor dimension.
+    #[must_use]
+    pub fn permute_minor(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            perm.len(),
+            self.minor_dim(),
+            "Permutation length must match the minor dimension."
+        );
+        select_pattern(self, None, Some(perm.as_slice())).0
+    }
+
+    /// Permutes both the major and minor lanes of a square pattern by the same permutation.
+    ///
+    /// Panics
+    /// ------
+    ///
+    /// Panics if the pattern is not square, or if the length of the permutation is not equal to
+    /// the major dimension.
+    #[must_use]
+    pub fn permute_symmetric(&self, perm: &Permutation) -> Self {
+        assert_eq!(
+            self.major_dim(),
+            self.minor_dim(),
+            "Pattern must be square."
+        );
+        assert_eq!(
+            perm.len(),
+            self.major_dim(),
+            "Permutation length must match the major dimension."
+        );
+        select_pattern(self, Some(perm.as_slice()), Some(perm.as_slice())).0
+    }
+}
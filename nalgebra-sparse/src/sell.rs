@@ -0,0 +1,232 @@
+//! An implementation of the SELL-C-σ (sliced ELLPACK) sparse matrix format.
+//!
+//! This is the module-level documentation. See [`SellMatrix`] for the main documentation of the
+//! SELL-C-σ implementation.
+
+/// A SELL-C-σ representation of a sparse matrix.
+///
+/// The sliced ELLPACK format with sorting, SELL-C-σ, splits the rows of the matrix into
+/// *slices* of `C` consecutive rows, and stores each slice in
+/// [ELL format](crate::ell::EllMatrix) with its own width. To reduce the amount of padding, the
+/// rows are first sorted by decreasing number of non-zeros within windows of `σ` consecutive
+/// rows, so that rows of similar length end up in the same slice. The format combines the
+/// vectorization opportunities of ELL with a storage overhead close to that of CSR.
+///
+/// A SELL-C-σ matrix is constructed by conversion from one of the other formats, see
+/// [`convert_csr_sell`](crate::convert::serial::convert_csr_sell).
+///
+/// # Usage
+///
+/// ```
+/// use nalgebra_sparse::convert::serial::{convert_csr_sell, convert_sell_csr};
+/// use nalgebra_sparse::csr::CsrMatrix;
+/// use nalgebra::DMatrix;
+/// use matrixcompare::assert_matrix_eq;
+///
+/// let dense = DMatrix::from_row_slice(4, 4, &[
+///     1.0, 0.0, 0.0, 0.0,
+///     2.0, 3.0, 4.0, 5.0,
+///     0.0, 0.0, 6.0, 0.0,
+///     0.0, 7.0, 0.0, 8.0,
+/// ]);
+/// let csr = CsrMatrix::from(&dense);
+///
+/// // Slices of 2 rows, rows are sorted within windows of 4 rows
+/// let sell = convert_csr_sell(&csr, 2, 4);
+/// assert_eq!(sell.num_slices(), 2);
+/// // The longest rows are stored first
+/// assert_eq!(sell.row_permutation(), &[1, 3, 0, 2]);
+/// assert_matrix_eq!(sell, dense);
+/// assert_eq!(convert_sell_csr(&sell), csr);
+/// ```
+///
+/// # Format
+///
+/// An `m x n` sparse matrix with slice height `C` in SELL-C-σ format has `ceil(m / C)` slices
+/// and is represented by the following arrays:
+///
+/// - `row_permutation`, an array of length `m`. The `r`-th stored row is row
+///   `row_permutation[r]` of the matrix. Only rows within the same window of `σ` rows are
+///   permuted amongst each other.
+/// - `slice_offsets`, an array of length `ceil(m / C) + 1`, holding the offset of each slice in
+///   the column index and value arrays.
+/// - `row_lengths`, an array of length `m`, holding the number of entries of each stored row.
+/// - `col_indices` and `values`, holding the padded entries of all slices.
+///
+/// Slice `s` holds the `h` stored rows `s * C .. s * C + h`, where `h = C` for all but possibly
+/// the last slice. The width of the slice is `w = (slice_offsets[s + 1] - slice_offsets[s]) / h`,
+/// and the `k`-th entry of the `r`-th row of the slice is stored at index
+/// `slice_offsets[s] + k * h + r`. Only the first `row_lengths` entries of each row are part of
+/// the matrix, and within each row, the column indices are sorted and unique.
+#[derive(Debug, Clone)]
+pub struct SellMatrix<T> {
+    nrows: usize,
+    ncols: usize,
+    slice_height: usize,
+    sigma: usize,
+    row_permutation: Vec<usize>,
+    slice_offsets: Vec<usize>,
+    row_lengths: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// The layout of a single slice of a [`SellMatrix`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct SellSlice {
+    /// The index of the first stored row of the slice.
+    pub first_row: usize,
+    /// The number of rows in the slice.
+    pub height: usize,
+    /// The offset of the slice in the column index and value arrays.
+    pub offset: usize,
+    /// The number of (possibly padded) entries per row in the slice.
+    pub width: usize,
+}
+
+impl<T> SellMatrix<T> {
+    /// Constructs a SELL-C-σ matrix from its raw parts without validating them.
+    ///
+    /// The caller must make sure that the data conforms to the format specification.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_parts_unchecked(
+        nrows: usize,
+        ncols: usize,
+        slice_height: usize,
+        sigma: usize,
+        row_permutation: Vec<usize>,
+        slice_offsets: Vec<usize>,
+        row_lengths: Vec<usize>,
+        col_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Self {
+        debug_assert_eq!(row_permutation.len(), nrows);
+        debug_assert_eq!(row_lengths.len(), nrows);
+        debug_assert_eq!(col_indices.len(), values.len());
+        debug_assert_eq!(slice_offsets.last(), Some(&values.len()));
+        Self {
+            nrows,
+            ncols,
+            slice_height,
+            sigma,
+            row_permutation,
+            slice_offsets,
+            row_lengths,
+            col_indices,
+            values,
+        }
+    }
+
+    /// The number of rows in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// The number of columns in the matrix.
+    #[inline]
+    #[must_use]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The slice height `C`.
+    #[inline]
+    #[must_use]
+    pub fn slice_height(&self) -> usize {
+        self.slice_height
+    }
+
+    /// The size `σ` of the windows within which rows are sorted by length.
+    #[inline]
+    #[must_use]
+    pub fn sigma(&self) -> usize {
+        self.sigma
+    }
+
+    /// The number of slices.
+    #[inline]
+    #[must_use]
+    pub fn num_slices(&self) -> usize {
+        self.slice_offsets.len() - 1
+    }
+
+    /// The number of explicitly stored entries in the matrix, excluding padding.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.row_lengths.iter().sum()
+    }
+
+    /// The original row index of each stored row.
+    #[inline]
+    #[must_use]
+    pub fn row_permutation(&self) -> &[usize] {
+        &self.row_permutation
+    }
+
+    /// The offsets of the slices in the column index and value arrays.
+    #[inline]
+    #[must_use]
+    pub fn slice_offsets(&self) -> &[usize] {
+        &self.slice_offsets
+    }
+
+    /// The number of entries of each stored row, excluding padding.
+    #[inline]
+    #[must_use]
+    pub fn row_lengths(&self) -> &[usize] {
+        &self.row_lengths
+    }
+
+    /// The column indices of the padded entries of all slices.
+    #[inline]
+    #[must_use]
+    pub fn col_indices(&self) -> &[usize] {
+        &self.col_indices
+    }
+
+    /// The values of the padded entries of all slices.
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Mutable access to the values of the padded entries of all slices.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    pub(crate) fn slice(&self, s: usize) -> SellSlice {
+        let first_row = s * self.slice_height;
+        let height = self.slice_height.min(self.nrows - first_row);
+        let offset = self.slice_offsets[s];
+        let width = (self.slice_offsets[s + 1] - offset) / height;
+        SellSlice {
+            first_row,
+            height,
+            offset,
+            width,
+        }
+    }
+
+    /// An iterator over all explicitly stored entries of the matrix, excluding padding.
+    ///
+    /// The iterator yields triplets `(i, j, v)` in the order of the stored rows, so the row
+    /// indices are generally not sorted.
+    pub fn triplet_iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        (0..self.num_slices()).flat_map(move |s| {
+            let slice = self.slice(s);
+            (0..slice.height).flat_map(move |r| {
+                let stored_row = slice.first_row + r;
+                let i = self.row_permutation[stored_row];
+                (0..self.row_lengths[stored_row]).map(move |k| {
+                    let idx = slice.offset + k * slice.height + r;
+                    (i, self.col_indices[idx], &self.values[idx])
+                })
+            })
+        })
+    }
+}
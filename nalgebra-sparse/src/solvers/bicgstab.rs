@@ -0,0 +1,114 @@
+use crate::operator::LinearOperator;
+use crate::solvers::{
+    compute_residual, Preconditioner, SolverOptions, SolverReport, TerminationReason,
+};
+use nalgebra::{DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Solves `A x = b` with the right-preconditioned BiCGSTAB method.
+///
+/// BiCGSTAB applies to general square (non-symmetric) systems. Each iteration requires two
+/// applications of the operator and two applications of the preconditioner.
+/// On entry, `x` holds the initial guess. On exit, it holds the final iterate.
+///
+/// The residual history contains the Euclidean norms of the recursively updated residuals.
+///
+/// The iteration terminates with [`TerminationReason::Breakdown`] if one of the scalar
+/// recurrence coefficients becomes zero before convergence.
+///
+/// # Panics
+///
+/// Panics if `A` is not square or if the dimensions of `A`, `b` and `x` are not compatible.
+pub fn bicgstab<'a, T, A, P>(
+    a: &A,
+    b: impl Into<DVectorView<'a, T>>,
+    x: impl Into<DVectorViewMut<'a, T>>,
+    preconditioner: &P,
+    options: &SolverOptions<T>,
+) -> SolverReport<T>
+where
+    T: RealField,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let (b, mut x) = (b.into(), x.into());
+    assert_compatible_solver_dims!(a, b, x);
+    let n = b.nrows();
+
+    let threshold = options.threshold(b.norm());
+    let mut r = compute_residual(a, &b, &x);
+    let mut history = vec![r.norm()];
+
+    if history[0] <= threshold {
+        return SolverReport::new(TerminationReason::Converged, history);
+    }
+
+    // The "shadow" residual, which remains fixed throughout the iteration
+    let r_hat = r.clone();
+    let mut p = DVector::zeros(n);
+    let mut v = DVector::zeros(n);
+    let mut p_hat = DVector::zeros(n);
+    let mut s_hat = DVector::zeros(n);
+    let mut t = DVector::zeros(n);
+    let (mut rho, mut alpha, mut omega) = (T::one(), T::one(), T::one());
+
+    for iteration in 0..options.max_iterations {
+        let rho_new = r_hat.dot(&r);
+        if rho_new.is_zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+
+        if iteration == 0 {
+            p.copy_from(&r);
+        } else {
+            // p <- r + beta * (p - omega * v)
+            let beta = (rho_new.clone() / rho) * (alpha.clone() / omega.clone());
+            p.axpy(-omega, &v, T::one());
+            p.axpy(T::one(), &r, beta);
+        }
+        rho = rho_new;
+
+        preconditioner.apply((&p).into(), (&mut p_hat).into());
+        a.apply(T::zero(), (&mut v).into(), T::one(), (&p_hat).into());
+        let r_hat_v = r_hat.dot(&v);
+        if r_hat_v.is_zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+        alpha = rho.clone() / r_hat_v;
+
+        // s <- r - alpha * v. We reuse the storage of r for s
+        let mut s = r;
+        s.axpy(-alpha.clone(), &v, T::one());
+        let s_norm = s.norm();
+        if s_norm <= threshold {
+            x.axpy(alpha, &p_hat, T::one());
+            history.push(s_norm);
+            return SolverReport::new(TerminationReason::Converged, history);
+        }
+
+        preconditioner.apply((&s).into(), (&mut s_hat).into());
+        a.apply(T::zero(), (&mut t).into(), T::one(), (&s_hat).into());
+        let t_t = t.dot(&t);
+        if t_t.is_zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+        omega = t.dot(&s) / t_t;
+
+        x.axpy(alpha.clone(), &p_hat, T::one());
+        x.axpy(omega.clone(), &s_hat, T::one());
+
+        // r <- s - omega * t
+        r = s;
+        r.axpy(-omega.clone(), &t, T::one());
+        let r_norm = r.norm();
+        history.push(r_norm.clone());
+
+        if r_norm <= threshold {
+            return SolverReport::new(TerminationReason::Converged, history);
+        }
+        if omega.is_zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+    }
+
+    SolverReport::new(TerminationReason::MaxIterationsReached, history)
+}
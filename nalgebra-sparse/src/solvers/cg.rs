@@ -0,0 +1,79 @@
+use crate::operator::LinearOperator;
+use crate::solvers::{
+    compute_residual, Preconditioner, SolverOptions, SolverReport, TerminationReason,
+};
+use nalgebra::{DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Solves `A x = b` with the preconditioned conjugate gradient (CG) method.
+///
+/// The operator `A` and the preconditioner must both be symmetric positive definite.
+/// On entry, `x` holds the initial guess. On exit, it holds the final iterate.
+///
+/// The residual history contains the Euclidean norms of the recursively updated residuals.
+///
+/// If the iteration encounters a search direction `p` with `p^T A p <= 0` or a residual with
+/// `r^T M^{-1} r <= 0`, which can only happen if `A` or the preconditioner is not positive
+/// definite, the iteration terminates with [`TerminationReason::Breakdown`].
+///
+/// # Panics
+///
+/// Panics if `A` is not square or if the dimensions of `A`, `b` and `x` are not compatible.
+pub fn cg<'a, T, A, P>(
+    a: &A,
+    b: impl Into<DVectorView<'a, T>>,
+    x: impl Into<DVectorViewMut<'a, T>>,
+    preconditioner: &P,
+    options: &SolverOptions<T>,
+) -> SolverReport<T>
+where
+    T: RealField,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let (b, mut x) = (b.into(), x.into());
+    assert_compatible_solver_dims!(a, b, x);
+    let n = b.nrows();
+
+    let threshold = options.threshold(b.norm());
+    let mut r = compute_residual(a, &b, &x);
+    let mut r_norm = r.norm();
+    let mut history = vec![r_norm.clone()];
+
+    if r_norm <= threshold {
+        return SolverReport::new(TerminationReason::Converged, history);
+    }
+
+    let mut z = DVector::zeros(n);
+    preconditioner.apply((&r).into(), (&mut z).into());
+    let mut p = z.clone();
+    let mut ap = DVector::zeros(n);
+    let mut rz = r.dot(&z);
+
+    for _ in 0..options.max_iterations {
+        a.apply(T::zero(), (&mut ap).into(), T::one(), (&p).into());
+        let pap = p.dot(&ap);
+        if rz <= T::zero() || pap <= T::zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+
+        let alpha = rz.clone() / pap;
+        x.axpy(alpha.clone(), &p, T::one());
+        r.axpy(-alpha, &ap, T::one());
+
+        r_norm = r.norm();
+        history.push(r_norm.clone());
+        if r_norm <= threshold {
+            return SolverReport::new(TerminationReason::Converged, history);
+        }
+
+        preconditioner.apply((&r).into(), (&mut z).into());
+        let rz_new = r.dot(&z);
+        let beta = rz_new.clone() / rz;
+        rz = rz_new;
+
+        // p <- z + beta * p
+        p.axpy(T::one(), &z, beta);
+    }
+
+    SolverReport::new(TerminationReason::MaxIterationsReached, history)
+}
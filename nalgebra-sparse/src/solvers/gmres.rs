@@ -0,0 +1,145 @@
+use crate::operator::LinearOperator;
+use crate::solvers::{
+    compute_residual, Preconditioner, SolverOptions, SolverReport, TerminationReason,
+};
+use nalgebra::{DMatrix, DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Solves `A x = b` with the right-preconditioned, restarted GMRES method.
+///
+/// GMRES applies to general square (non-symmetric) systems. The method builds an orthonormal
+/// basis for a Krylov subspace of dimension at most `restart`, after which the iteration is
+/// restarted from the current iterate. Larger values of `restart` typically lead to faster
+/// convergence at the cost of storing `restart + 1` vectors of length `n`.
+/// On entry, `x` holds the initial guess. On exit, it holds the final iterate.
+///
+/// Since the preconditioner is applied from the right, the residual norms in the history are
+/// (estimates of) the Euclidean norms of the true residuals `b - A x`. Within a cycle,
+/// the norms are obtained from the least-squares problem, and at every restart the residual is
+/// explicitly recomputed.
+///
+/// The iteration terminates with [`TerminationReason::Breakdown`] if the upper Hessenberg
+/// matrix becomes singular.
+///
+/// # Panics
+///
+/// Panics if `restart` is zero, if `A` is not square or if the dimensions of `A`, `b` and `x`
+/// are not compatible.
+pub fn gmres<'a, T, A, P>(
+    a: &A,
+    b: impl Into<DVectorView<'a, T>>,
+    x: impl Into<DVectorViewMut<'a, T>>,
+    preconditioner: &P,
+    restart: usize,
+    options: &SolverOptions<T>,
+) -> SolverReport<T>
+where
+    T: RealField,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let (b, mut x) = (b.into(), x.into());
+    assert_compatible_solver_dims!(a, b, x);
+    assert!(restart > 0, "The restart length must be positive.");
+    let n = b.nrows();
+    let m = restart;
+
+    let threshold = options.threshold(b.norm());
+
+    // Orthonormal basis of the Krylov subspace, stored column by column
+    let mut v = DMatrix::zeros(n, m + 1);
+    // Upper Hessenberg matrix, which is reduced to upper triangular form by Givens rotations
+    let mut h = DMatrix::zeros(m + 1, m);
+    let mut cs = vec![T::zero(); m];
+    let mut sn = vec![T::zero(); m];
+    let mut g = DVector::zeros(m + 1);
+    let mut z = DVector::zeros(n);
+    let mut w = DVector::zeros(n);
+
+    let mut history = Vec::new();
+    let mut iterations = 0;
+
+    loop {
+        let r = compute_residual(a, &b, &x);
+        let beta = r.norm();
+        // The explicitly computed residual replaces the estimate from the previous cycle
+        history.pop();
+        history.push(beta.clone());
+
+        if beta <= threshold {
+            return SolverReport::new(TerminationReason::Converged, history);
+        }
+        if iterations >= options.max_iterations {
+            return SolverReport::new(TerminationReason::MaxIterationsReached, history);
+        }
+
+        v.column_mut(0).copy_from(&(r / beta.clone()));
+        g.fill(T::zero());
+        g[0] = beta;
+
+        let mut k = 0;
+        let mut breakdown = false;
+        while k < m && iterations < options.max_iterations {
+            // w <- A M^{-1} v_k
+            preconditioner.apply(v.column(k), (&mut z).into());
+            a.apply(T::zero(), (&mut w).into(), T::one(), (&z).into());
+
+            // Modified Gram-Schmidt
+            for i in 0..=k {
+                let h_ik = w.dot(&v.column(i));
+                w.axpy(-h_ik.clone(), &v.column(i), T::one());
+                h[(i, k)] = h_ik;
+            }
+            let h_next = w.norm();
+            h[(k + 1, k)] = h_next.clone();
+
+            // Apply the previous rotations to the new column
+            for i in 0..k {
+                let h_ik = h[(i, k)].clone();
+                let h_i1k = h[(i + 1, k)].clone();
+                h[(i, k)] = cs[i].clone() * h_ik.clone() + sn[i].clone() * h_i1k.clone();
+                h[(i + 1, k)] = cs[i].clone() * h_i1k - sn[i].clone() * h_ik;
+            }
+
+            // Compute a new rotation that eliminates the subdiagonal entry
+            let h_kk = h[(k, k)].clone();
+            let h_k1k = h[(k + 1, k)].clone();
+            let denom = h_kk.clone().hypot(h_k1k.clone());
+            if denom.is_zero() {
+                breakdown = true;
+                break;
+            }
+            cs[k] = h_kk / denom.clone();
+            sn[k] = h_k1k / denom.clone();
+            h[(k, k)] = denom;
+            h[(k + 1, k)] = T::zero();
+            g[k + 1] = -sn[k].clone() * g[k].clone();
+            g[k] = cs[k].clone() * g[k].clone();
+
+            k += 1;
+            iterations += 1;
+            let estimate = g[k].clone().abs();
+            history.push(estimate.clone());
+
+            if estimate <= threshold || h_next.is_zero() {
+                break;
+            }
+            v.column_mut(k).copy_from(&(&w / h_next));
+        }
+
+        // Update the solution with the minimizer over the current Krylov subspace,
+        // x <- x + M^{-1} V y
+        if k > 0 {
+            let y = h
+                .view((0, 0), (k, k))
+                .solve_upper_triangular(&g.rows(0, k))
+                .expect("Internal error: The triangular factor must be invertible.");
+            let vy = v.columns(0, k) * y;
+            preconditioner.apply((&vy).into(), (&mut z).into());
+            x.axpy(T::one(), &z, T::one());
+        }
+
+        if breakdown {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::factorization::CholeskyError;
+use crate::ops::serial::OperationError;
+use crate::pattern::SparsityPattern;
+use crate::solvers::preconditioner::{find_diagonal_offsets, zero_pivot_error};
+use crate::solvers::Preconditioner;
+use nalgebra::{DVectorView, DVectorViewMut, RealField};
+use std::cmp::Ordering;
+
+/// An incomplete LU factorization without fill-in, ILU(0).
+///
+/// The factorization computes a unit lower triangular matrix `L` and an upper triangular matrix
+/// `U` such that `L U` agrees with `A` on the sparsity pattern of `A`. Both factors are
+/// restricted to the sparsity pattern of `A`, and are stored together in a single CSR matrix
+/// with the same pattern as `A`: the strictly lower triangular part holds `L` (whose unit
+/// diagonal is implied) and the upper triangular part holds `U`.
+///
+/// Every diagonal entry of `A` must be explicitly stored. ILU(0) is guaranteed to exist for
+/// M-matrices and diagonally dominant matrices, but may break down with a zero pivot otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ilu0<T> {
+    lu: CsrMatrix<T>,
+    diagonal_offsets: Vec<usize>,
+}
+
+impl<T: RealField> Ilu0<T> {
+    /// Computes the ILU(0) factorization of the given CSR matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`Singular`](crate::ops::serial::OperationErrorKind::Singular)
+    /// if a diagonal entry is not explicitly stored or if a zero pivot is encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<Self, OperationError> {
+        assert_eq!(matrix.nrows(), matrix.ncols(), "Matrix must be square.");
+        let diagonal_offsets = find_diagonal_offsets(matrix.pattern())?;
+        let mut factorization = Self {
+            lu: matrix.clone(),
+            diagonal_offsets,
+        };
+        factorization.decompose()?;
+        Ok(factorization)
+    }
+
+    /// Computes the ILU(0) factorization of the given CSC matrix.
+    ///
+    /// The matrix is converted to CSR format internally.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`Singular`](crate::ops::serial::OperationErrorKind::Singular)
+    /// if a diagonal entry is not explicitly stored or if a zero pivot is encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csc(matrix: &CscMatrix<T>) -> Result<Self, OperationError> {
+        Self::from_csr(&CsrMatrix::from(matrix))
+    }
+
+    /// Re-computes the factorization for a new set of non-zero values.
+    ///
+    /// The values correspond to the non-zeros of a CSR matrix with the same sparsity pattern
+    /// as the [combined factor](Self::lu). This is useful when the values of a matrix change,
+    /// but the sparsity pattern remains constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`Singular`](crate::ops::serial::OperationErrorKind::Singular)
+    /// if a zero pivot is encountered. In this case, the factorization is left in an
+    /// unspecified state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values does not match the number of non-zeros in the sparsity
+    /// pattern.
+    pub fn refactor(&mut self, values: &[T]) -> Result<(), OperationError> {
+        assert_eq!(
+            values.len(),
+            self.lu.nnz(),
+            "Number of values must match the number of non-zeros."
+        );
+        self.lu.values_mut().clone_from_slice(values);
+        self.decompose()
+    }
+
+    /// The combined factor, holding `L` in its strictly lower triangular part and `U` in its
+    /// upper triangular part.
+    #[must_use]
+    pub fn lu(&self) -> &CsrMatrix<T> {
+        &self.lu
+    }
+
+    fn decompose(&mut self) -> Result<(), OperationError> {
+        let diagonal_offsets = &self.diagonal_offsets;
+        let n = diagonal_offsets.len();
+        let (offsets, indices, values) = self.lu.csr_data_mut();
+
+        // Maps a column index to its offset in the current row, if present
+        let mut work = vec![usize::MAX; n];
+
+        for i in 0..n {
+            let row = offsets[i]..offsets[i + 1];
+            for p in row.clone() {
+                work[indices[p]] = p;
+            }
+
+            // Eliminate the entries in the lower triangular part of the row in increasing
+            // column order, discarding any fill-in outside the pattern
+            for p in offsets[i]..diagonal_offsets[i] {
+                let k = indices[p];
+                let l_ik = values[p].clone() / values[diagonal_offsets[k]].clone();
+                values[p] = l_ik.clone();
+                for q in diagonal_offsets[k] + 1..offsets[k + 1] {
+                    let target = work[indices[q]];
+                    if target != usize::MAX {
+                        let u_kj = values[q].clone();
+                        values[target] -= l_ik.clone() * u_kj;
+                    }
+                }
+            }
+
+            for p in row {
+                work[indices[p]] = usize::MAX;
+            }
+
+            if values[diagonal_offsets[i]].is_zero() {
+                return Err(zero_pivot_error(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: RealField> Preconditioner<T> for Ilu0<T> {
+    fn apply(&self, r: DVectorView<'_, T>, mut z: DVectorViewMut<'_, T>) {
+        let n = self.diagonal_offsets.len();
+        assert_compatible_preconditioner_dims!(n, r, z);
+        let (offsets, indices, values) = self.lu.csr_data();
+
+        // Solve L y = r
+        z.copy_from(&r);
+        for i in 0..n {
+            let mut z_i = z[i].clone();
+            for p in offsets[i]..self.diagonal_offsets[i] {
+                z_i -= values[p].clone() * z[indices[p]].clone();
+            }
+            z[i] = z_i;
+        }
+
+        // Solve U z = y
+        for i in (0..n).rev() {
+            let diag = self.diagonal_offsets[i];
+            let mut z_i = z[i].clone();
+            for p in diag + 1..offsets[i + 1] {
+                z_i -= values[p].clone() * z[indices[p]].clone();
+            }
+            z[i] = z_i / values[diag].clone();
+        }
+    }
+}
+
+/// An incomplete Cholesky factorization without fill-in, IC(0).
+///
+/// The factorization computes a lower triangular matrix `L` with the same sparsity pattern as
+/// the lower triangular part of the symmetric matrix `A`, such that `L L^T` agrees with `A` on
+/// this pattern. Only the lower triangular part of `A` is accessed.
+///
+/// Every diagonal entry of `A` must be explicitly stored. IC(0) is guaranteed to exist for
+/// symmetric M-matrices, but may break down for general symmetric positive definite matrices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ic0<T> {
+    l: CsrMatrix<T>,
+    matrix_pattern: SparsityPattern,
+}
+
+impl<T: RealField> Ic0<T> {
+    /// Computes the IC(0) factorization of the given CSR matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a diagonal entry is not explicitly stored or if a non-positive
+    /// pivot is encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<Self, CholeskyError> {
+        assert_eq!(matrix.nrows(), matrix.ncols(), "Matrix must be square.");
+        let pattern = matrix.pattern();
+        let n = pattern.major_dim();
+
+        // The lower triangular part of each row is a prefix of the row
+        let mut l_offsets = Vec::with_capacity(n + 1);
+        let mut l_indices = Vec::new();
+        l_offsets.push(0);
+        for i in 0..n {
+            let lane = pattern.lane(i);
+            let count = lane.partition_point(|&j| j <= i);
+            l_indices.extend_from_slice(&lane[..count]);
+            l_offsets.push(l_indices.len());
+        }
+        let l_pattern = SparsityPattern::try_from_offsets_and_indices(n, n, l_offsets, l_indices)
+            .expect("Internal error: The lower triangular pattern must be valid.");
+        let l_values = vec![T::zero(); l_pattern.nnz()];
+        let l = CsrMatrix::try_from_pattern_and_values(l_pattern, l_values).unwrap();
+
+        let mut factorization = Self {
+            l,
+            matrix_pattern: pattern.clone(),
+        };
+        factorization.refactor(matrix.values())?;
+        Ok(factorization)
+    }
+
+    /// Computes the IC(0) factorization of the given CSC matrix.
+    ///
+    /// The matrix is converted to CSR format internally.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a diagonal entry is not explicitly stored or if a non-positive
+    /// pivot is encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csc(matrix: &CscMatrix<T>) -> Result<Self, CholeskyError> {
+        Self::from_csr(&CsrMatrix::from(matrix))
+    }
+
+    /// Re-computes the factorization for a new set of non-zero values.
+    ///
+    /// The values correspond to the non-zeros of a CSR matrix with the same sparsity pattern
+    /// as the originally factored matrix. This is useful when the values of a matrix change,
+    /// but the sparsity pattern remains constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a diagonal entry is not explicitly stored or if a non-positive
+    /// pivot is encountered. In this case, the factorization is left in an unspecified state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values does not match the number of non-zeros in the sparsity
+    /// pattern.
+    pub fn refactor(&mut self, values: &[T]) -> Result<(), CholeskyError> {
+        assert_eq!(
+            values.len(),
+            self.matrix_pattern.nnz(),
+            "Number of values must match the number of non-zeros."
+        );
+
+        let matrix_offsets = self.matrix_pattern.major_offsets();
+        let (l_offsets, _, l_values) = self.l.csr_data_mut();
+        for i in 0..self.matrix_pattern.major_dim() {
+            let count = l_offsets[i + 1] - l_offsets[i];
+            let begin = matrix_offsets[i];
+            l_values[l_offsets[i]..l_offsets[i + 1]]
+                .clone_from_slice(&values[begin..begin + count]);
+        }
+
+        self.decompose()
+    }
+
+    /// The incomplete Cholesky factor `L`.
+    #[must_use]
+    pub fn l(&self) -> &CsrMatrix<T> {
+        &self.l
+    }
+
+    fn decompose(&mut self) -> Result<(), CholeskyError> {
+        let n = self.l.nrows();
+        let (offsets, indices, values) = self.l.csr_data_mut();
+
+        for i in 0..n {
+            let (begin, end) = (offsets[i], offsets[i + 1]);
+            if begin == end || indices[end - 1] != i {
+                return Err(CholeskyError::NotPositiveDefinite);
+            }
+
+            for p in begin..end {
+                let k = indices[p];
+                // The diagonal of row k is its last entry
+                let k_diag = offsets[k + 1] - 1;
+
+                // Compute the sum of L[i, j] * L[k, j] over the common columns j < k
+                let mut sum = T::zero();
+                let (mut a, mut b) = (begin, offsets[k]);
+                while a < p && b < k_diag {
+                    match indices[a].cmp(&indices[b]) {
+                        Ordering::Less => a += 1,
+                        Ordering::Greater => b += 1,
+                        Ordering::Equal => {
+                            sum += values[a].clone() * values[b].clone();
+                            a += 1;
+                            b += 1;
+                        }
+                    }
+                }
+
+                let value = values[p].clone() - sum;
+                if k < i {
+                    values[p] = value / values[k_diag].clone();
+                } else if value > T::zero() {
+                    values[p] = value.sqrt();
+                } else {
+                    return Err(CholeskyError::NotPositiveDefinite);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: RealField> Preconditioner<T> for Ic0<T> {
+    fn apply(&self, r: DVectorView<'_, T>, mut z: DVectorViewMut<'_, T>) {
+        let n = self.l.nrows();
+        assert_compatible_preconditioner_dims!(n, r, z);
+        let (offsets, indices, values) = self.l.csr_data();
+
+        // Solve L y = r
+        z.copy_from(&r);
+        for i in 0..n {
+            let diag = offsets[i + 1] - 1;
+            let mut z_i = z[i].clone();
+            for p in offsets[i]..diag {
+                z_i -= values[p].clone() * z[indices[p]].clone();
+            }
+            z[i] = z_i / values[diag].clone();
+        }
+
+        // Solve L^T z = y, traversing the rows of L as the columns of L^T
+        for i in (0..n).rev() {
+            let diag = offsets[i + 1] - 1;
+            let z_i = z[i].clone() / values[diag].clone();
+            for p in offsets[i]..diag {
+                z[indices[p]] -= values[p].clone() * z_i.clone();
+            }
+            z[i] = z_i;
+        }
+    }
+}
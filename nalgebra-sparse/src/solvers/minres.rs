@@ -0,0 +1,134 @@
+use crate::operator::LinearOperator;
+use crate::solvers::{
+    compute_residual, Preconditioner, SolverOptions, SolverReport, TerminationReason,
+};
+use nalgebra::{DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Solves `A x = b` with the preconditioned minimal residual (MINRES) method.
+///
+/// The operator `A` must be symmetric, but may be indefinite. The preconditioner must be
+/// symmetric positive definite. On entry, `x` holds the initial guess. On exit, it holds the
+/// final iterate.
+///
+/// The implementation follows the Lanczos-based formulation by Paige and Saunders. The residual
+/// history contains the residual norm estimates maintained by the recurrence. When a
+/// preconditioner `M` is used, these are estimates of the `M^{-1}`-norm `sqrt(r^T M^{-1} r)`
+/// of the residual, and the tolerances given in the [`SolverOptions`] are interpreted with
+/// respect to the same norm. Without preconditioning, this is simply the Euclidean norm.
+///
+/// The iteration terminates with [`TerminationReason::Breakdown`] if the preconditioner is
+/// detected to be indefinite.
+///
+/// # Panics
+///
+/// Panics if `A` is not square or if the dimensions of `A`, `b` and `x` are not compatible.
+pub fn minres<'a, T, A, P>(
+    a: &A,
+    b: impl Into<DVectorView<'a, T>>,
+    x: impl Into<DVectorViewMut<'a, T>>,
+    preconditioner: &P,
+    options: &SolverOptions<T>,
+) -> SolverReport<T>
+where
+    T: RealField,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let (b, mut x) = (b.into(), x.into());
+    assert_compatible_solver_dims!(a, b, x);
+    let n = b.nrows();
+
+    // The M^{-1}-norm of b, so that the relative tolerance is measured in the same norm
+    // as the residual estimates
+    let mut y = DVector::zeros(n);
+    preconditioner.apply(b.clone(), (&mut y).into());
+    let b_dot_y = b.dot(&y);
+    if b_dot_y < T::zero() {
+        return SolverReport::new(TerminationReason::Breakdown, vec![b.norm()]);
+    }
+    let threshold = options.threshold(b_dot_y.sqrt());
+
+    let mut r1 = compute_residual(a, &b, &x);
+    preconditioner.apply((&r1).into(), (&mut y).into());
+    let r1_dot_y = r1.dot(&y);
+    if r1_dot_y < T::zero() {
+        return SolverReport::new(TerminationReason::Breakdown, vec![r1.norm()]);
+    }
+    let beta1 = r1_dot_y.sqrt();
+    let mut history = vec![beta1.clone()];
+
+    if beta1 <= threshold {
+        return SolverReport::new(TerminationReason::Converged, history);
+    }
+
+    let mut r2 = r1.clone();
+    let mut v = DVector::zeros(n);
+    let mut w = DVector::zeros(n);
+    let mut w1 = DVector::zeros(n);
+    let mut w2 = DVector::zeros(n);
+
+    let mut old_beta = T::zero();
+    let mut beta = beta1.clone();
+    let mut d_bar = T::zero();
+    let mut epsilon = T::zero();
+    let mut phi_bar = beta1;
+    let mut cs = -T::one();
+    let mut sn = T::zero();
+
+    for iteration in 0..options.max_iterations {
+        // Lanczos step
+        v.copy_from(&(&y / beta.clone()));
+        a.apply(T::zero(), (&mut y).into(), T::one(), (&v).into());
+        if iteration > 0 {
+            y.axpy(-beta.clone() / old_beta.clone(), &r1, T::one());
+        }
+        let alpha = v.dot(&y);
+        y.axpy(-alpha.clone() / beta.clone(), &r2, T::one());
+        std::mem::swap(&mut r1, &mut r2);
+        r2.copy_from(&y);
+        preconditioner.apply((&r2).into(), (&mut y).into());
+        old_beta = beta;
+        let r2_dot_y = r2.dot(&y);
+        if r2_dot_y < T::zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+        beta = r2_dot_y.sqrt();
+
+        // Apply the previous rotation and compute the next one
+        let old_epsilon = epsilon;
+        let delta = cs.clone() * d_bar.clone() + sn.clone() * alpha.clone();
+        let g_bar = sn.clone() * d_bar - cs.clone() * alpha;
+        epsilon = sn.clone() * beta.clone();
+        d_bar = -cs * beta.clone();
+
+        let gamma = g_bar.clone().hypot(beta.clone());
+        if gamma.is_zero() {
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+        cs = g_bar / gamma.clone();
+        sn = beta.clone() / gamma.clone();
+        let phi = cs.clone() * phi_bar.clone();
+        phi_bar *= sn.clone();
+
+        // Update the search direction and the solution
+        std::mem::swap(&mut w1, &mut w2);
+        std::mem::swap(&mut w2, &mut w);
+        // w <- (v - old_epsilon * w1 - delta * w2) / gamma
+        w.copy_from(&v);
+        w.axpy(-old_epsilon, &w1, T::one());
+        w.axpy(-delta, &w2, T::one());
+        w /= gamma;
+        x.axpy(phi, &w, T::one());
+
+        history.push(phi_bar.clone().abs());
+        if phi_bar.clone().abs() <= threshold {
+            return SolverReport::new(TerminationReason::Converged, history);
+        }
+        if beta.is_zero() {
+            // The Krylov subspace is invariant, so no further progress is possible
+            return SolverReport::new(TerminationReason::Breakdown, history);
+        }
+    }
+
+    SolverReport::new(TerminationReason::MaxIterationsReached, history)
+}
@@ -0,0 +1,214 @@
+//! Iterative (Krylov subspace) solvers for linear systems.
+//!
+//! For systems that are too large to factor with a direct method such as
+//! [`CscCholesky`](crate::factorization::CscCholesky), iterative methods are often the only
+//! viable option. The solvers in this module only require the ability to compute
+//! matrix-vector products, and are therefore generic over the
+//! [`LinearOperator`](crate::operator::LinearOperator) trait. This means that they can be used
+//! with [CSR](crate::csr::CsrMatrix) and [CSC](crate::csc::CscMatrix) matrices, dense matrices
+//! and matrix-free operators alike.
+//!
+//! The following solvers are available:
+//!
+//! | Solver         | Requirements on `A`                      | Notes                           |
+//! | -------------- | ---------------------------------------- | ------------------------------- |
+//! | [`cg`]         | Symmetric positive definite              | Cheapest per iteration.         |
+//! | [`minres`]     | Symmetric, possibly indefinite           | Preconditioner must be SPD.     |
+//! | [`bicgstab`]   | Square                                   | Fixed memory, irregular convergence. |
+//! | [`gmres`]      | Square                                   | Memory grows with the restart length. |
+//!
+//! All solvers accept a [`Preconditioner`]. Use [`IdentityPreconditioner`] to solve without
+//! preconditioning. The following preconditioners can be computed from a
+//! [CSR](crate::csr::CsrMatrix) or [CSC](crate::csc::CscMatrix) matrix:
+//!
+//! | Preconditioner | Requirements on `A`                      | Notes                           |
+//! | -------------- | ---------------------------------------- | ------------------------------- |
+//! | [`Jacobi`]     | Non-zero diagonal                        | Cheapest to compute and apply.  |
+//! | [`Ssor`]       | Non-zero diagonal                        | SPD if `A` is SPD.              |
+//! | [`Ilu0`]       | Square, non-zero pivots                  | Factors restricted to the pattern of `A`. |
+//! | [`Ic0`]        | Symmetric, positive pivots               | Factor restricted to the pattern of `A`. |
+//!
+//! Since the preconditioners implement the [`Preconditioner`] trait, they can equally be used
+//! in custom iterative schemes.
+//!
+//! The solvers never fail in the sense of returning an error. Instead, they return a
+//! [`SolverReport`], which describes why the iteration terminated and how the residual norm
+//! evolved over the course of the iteration.
+//!
+//! # Example
+//!
+//! ```
+//! use nalgebra_sparse::csr::CsrMatrix;
+//! use nalgebra_sparse::coo::CooMatrix;
+//! use nalgebra_sparse::solvers::{cg, IdentityPreconditioner, SolverOptions, TerminationReason};
+//! use nalgebra::DVector;
+//!
+//! // Assemble the 1D Laplacian
+//! let n = 20;
+//! let mut coo = CooMatrix::new(n, n);
+//! for i in 0..n {
+//!     coo.push(i, i, 2.0);
+//!     if i > 0 { coo.push(i, i - 1, -1.0); }
+//!     if i + 1 < n { coo.push(i, i + 1, -1.0); }
+//! }
+//! let a = CsrMatrix::from(&coo);
+//! let b = DVector::repeat(n, 1.0);
+//!
+//! // Solve A x = b, starting from the initial guess x = 0
+//! let mut x = DVector::zeros(n);
+//! let options = SolverOptions { relative_tolerance: 1e-10, ..SolverOptions::default() };
+//! let report = cg(&a, &b, &mut x, &IdentityPreconditioner, &options);
+//!
+//! assert_eq!(report.termination_reason(), TerminationReason::Converged);
+//! assert!((&a * &x - &b).norm() <= 1e-10 * b.norm());
+//! ```
+
+macro_rules! assert_compatible_solver_dims {
+    ($a:expr, $b:expr, $x:expr) => {
+        assert_eq!($a.nrows(), $a.ncols(), "A must be square.");
+        assert_eq!($a.nrows(), $b.nrows(), "A.nrows() != b.nrows()");
+        assert_eq!($a.ncols(), $x.nrows(), "A.ncols() != x.nrows()");
+    };
+}
+
+macro_rules! assert_compatible_preconditioner_dims {
+    ($n:expr, $r:expr, $z:expr) => {
+        assert_eq!(
+            $n,
+            $r.nrows(),
+            "r.nrows() does not match the preconditioner dimension."
+        );
+        assert_eq!($r.nrows(), $z.nrows(), "r.nrows() != z.nrows()");
+    };
+}
+
+mod bicgstab;
+mod cg;
+mod gmres;
+mod incomplete;
+mod minres;
+mod preconditioner;
+
+pub use bicgstab::*;
+pub use cg::*;
+pub use gmres::*;
+pub use incomplete::*;
+pub use minres::*;
+pub use preconditioner::*;
+
+use crate::operator::LinearOperator;
+use nalgebra::{DVector, DVectorView, DVectorViewMut, RealField};
+
+/// Parameters that control the termination of an iterative solver.
+///
+/// The iteration is considered converged once the residual norm satisfies
+/// `||r|| <= max(relative_tolerance * ||b||, absolute_tolerance)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SolverOptions<T> {
+    /// The maximum number of iterations.
+    ///
+    /// For [`gmres`], this is the total number of inner iterations across all restarts.
+    pub max_iterations: usize,
+    /// The tolerance for the residual norm relative to the norm of the right-hand side.
+    pub relative_tolerance: T,
+    /// The tolerance for the absolute residual norm.
+    pub absolute_tolerance: T,
+}
+
+impl<T: RealField> Default for SolverOptions<T> {
+    /// At most 1000 iterations, a relative tolerance of `1e-8` and an absolute tolerance of zero.
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            relative_tolerance: nalgebra::convert(1e-8),
+            absolute_tolerance: T::zero(),
+        }
+    }
+}
+
+impl<T: RealField> SolverOptions<T> {
+    /// The residual norm below which the iteration is considered converged, given the norm
+    /// of the right-hand side.
+    fn threshold(&self, b_norm: T) -> T {
+        let relative = self.relative_tolerance.clone() * b_norm;
+        relative.max(self.absolute_tolerance.clone())
+    }
+}
+
+/// The reason why an iterative solver terminated.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TerminationReason {
+    /// The residual norm satisfies the tolerances given in the [`SolverOptions`].
+    Converged,
+    /// The maximum number of iterations was reached before the tolerances were satisfied.
+    MaxIterationsReached,
+    /// The iteration broke down before the tolerances were satisfied.
+    ///
+    /// This typically indicates that the operator or the preconditioner does not have the
+    /// properties required by the solver, e.g. an indefinite matrix passed to [`cg`].
+    Breakdown,
+}
+
+/// The outcome of an iterative solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverReport<T> {
+    termination_reason: TerminationReason,
+    residual_history: Vec<T>,
+}
+
+impl<T: RealField> SolverReport<T> {
+    fn new(termination_reason: TerminationReason, residual_history: Vec<T>) -> Self {
+        debug_assert!(!residual_history.is_empty());
+        Self {
+            termination_reason,
+            residual_history,
+        }
+    }
+
+    /// The reason why the iteration terminated.
+    #[must_use]
+    pub fn termination_reason(&self) -> TerminationReason {
+        self.termination_reason
+    }
+
+    /// Whether the iteration converged.
+    #[must_use]
+    pub fn converged(&self) -> bool {
+        self.termination_reason == TerminationReason::Converged
+    }
+
+    /// The number of iterations that were performed.
+    #[must_use]
+    pub fn iterations(&self) -> usize {
+        self.residual_history.len() - 1
+    }
+
+    /// The residual norm at termination.
+    #[must_use]
+    pub fn residual_norm(&self) -> T {
+        self.residual_history.last().unwrap().clone()
+    }
+
+    /// The residual norm before the first iteration, followed by the residual norm after each
+    /// iteration.
+    ///
+    /// Depending on the solver, the residual norms may be estimates obtained from recurrences
+    /// rather than explicitly computed norms. See the documentation of the individual solvers
+    /// for details.
+    #[must_use]
+    pub fn residual_history(&self) -> &[T] {
+        &self.residual_history
+    }
+}
+
+/// Computes `r <- b - A x`.
+fn compute_residual<T, A>(a: &A, b: &DVectorView<'_, T>, x: &DVectorViewMut<'_, T>) -> DVector<T>
+where
+    T: RealField,
+    A: LinearOperator<T> + ?Sized,
+{
+    let mut r = b.clone_owned();
+    a.apply(T::one(), (&mut r).into(), -T::one(), x.into());
+    r
+}
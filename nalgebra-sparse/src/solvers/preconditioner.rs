@@ -0,0 +1,256 @@
+use crate::csc::CscMatrix;
+use crate::csr::CsrMatrix;
+use crate::ops::serial::{OperationError, OperationErrorKind};
+use crate::pattern::SparsityPattern;
+use nalgebra::{DVector, DVectorView, DVectorViewMut, RealField, Scalar};
+
+/// A preconditioner for an iterative solver.
+///
+/// A preconditioner approximates the action of the inverse `M^{-1}` of a matrix `M` that is in
+/// some sense close to the system matrix `A`.
+pub trait Preconditioner<T: Scalar> {
+    /// Computes `z <- M^{-1} r`.
+    ///
+    /// The initial contents of `z` are unspecified and must not be read.
+    ///
+    /// # Panics
+    ///
+    /// Implementations are expected to panic if the dimensions of `r` and `z` are not
+    /// compatible with the preconditioner.
+    fn apply(&self, r: DVectorView<'_, T>, z: DVectorViewMut<'_, T>);
+}
+
+impl<T, P> Preconditioner<T> for &P
+where
+    T: Scalar,
+    P: Preconditioner<T> + ?Sized,
+{
+    fn apply(&self, r: DVectorView<'_, T>, z: DVectorViewMut<'_, T>) {
+        P::apply(self, r, z)
+    }
+}
+
+/// The trivial preconditioner `M = I`.
+///
+/// Use this preconditioner to run a solver without preconditioning.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct IdentityPreconditioner;
+
+impl<T: Scalar> Preconditioner<T> for IdentityPreconditioner {
+    fn apply(&self, r: DVectorView<'_, T>, mut z: DVectorViewMut<'_, T>) {
+        assert_eq!(r.nrows(), z.nrows(), "r.nrows() != z.nrows()");
+        z.copy_from(&r);
+    }
+}
+
+/// The Jacobi (diagonal) preconditioner `M = diag(A)`.
+#[derive(Debug, Clone)]
+pub struct Jacobi<T> {
+    inverse_diagonal: DVector<T>,
+}
+
+impl<T: RealField> Jacobi<T> {
+    /// Constructs the Jacobi preconditioner for the given CSR matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`OperationErrorKind::Singular`] if a diagonal entry of the
+    /// matrix is zero or not explicitly stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<Self, OperationError> {
+        assert_eq!(matrix.nrows(), matrix.ncols(), "Matrix must be square.");
+        Self::from_pattern_and_values(matrix.pattern(), matrix.values())
+    }
+
+    /// Constructs the Jacobi preconditioner for the given CSC matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`OperationErrorKind::Singular`] if a diagonal entry of the
+    /// matrix is zero or not explicitly stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn from_csc(matrix: &CscMatrix<T>) -> Result<Self, OperationError> {
+        assert_eq!(matrix.nrows(), matrix.ncols(), "Matrix must be square.");
+        Self::from_pattern_and_values(matrix.pattern(), matrix.values())
+    }
+
+    fn from_pattern_and_values(
+        pattern: &SparsityPattern,
+        values: &[T],
+    ) -> Result<Self, OperationError> {
+        let diagonal_offsets = find_diagonal_offsets(pattern)?;
+        let mut inverse_diagonal = DVector::zeros(diagonal_offsets.len());
+        for (i, &offset) in diagonal_offsets.iter().enumerate() {
+            let d = values[offset].clone();
+            if d.is_zero() {
+                return Err(zero_pivot_error(i));
+            }
+            inverse_diagonal[i] = T::one() / d;
+        }
+        Ok(Self { inverse_diagonal })
+    }
+
+    /// The reciprocals of the diagonal entries of the matrix.
+    #[must_use]
+    pub fn inverse_diagonal(&self) -> &DVector<T> {
+        &self.inverse_diagonal
+    }
+}
+
+impl<T: RealField> Preconditioner<T> for Jacobi<T> {
+    fn apply(&self, r: DVectorView<'_, T>, mut z: DVectorViewMut<'_, T>) {
+        assert_compatible_preconditioner_dims!(self.inverse_diagonal.nrows(), r, z);
+        z.zip_zip_apply(&r, &self.inverse_diagonal, |z_i, r_i, d_i| *z_i = r_i * d_i);
+    }
+}
+
+/// The symmetric successive over-relaxation (SSOR) preconditioner.
+///
+/// Writing `A = L + D + U` with strictly lower triangular `L`, diagonal `D` and strictly upper
+/// triangular `U`, the preconditioner is given by
+///
+/// ```text
+/// M = 1 / (ω (2 - ω)) (D + ω L) D^{-1} (D + ω U)
+/// ```
+///
+/// with relaxation parameter `0 < ω < 2`. For `ω = 1`, this is the symmetric Gauss-Seidel
+/// preconditioner. If `A` is symmetric positive definite, then so is `M`.
+///
+/// The preconditioner stores a copy of the matrix, and each application performs a forward and
+/// a backward sweep over its non-zeros.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ssor<T> {
+    matrix: CsrMatrix<T>,
+    diagonal_offsets: Vec<usize>,
+    omega: T,
+}
+
+impl<T: RealField> Ssor<T> {
+    /// Constructs the SSOR preconditioner for the given CSR matrix and relaxation parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`OperationErrorKind::Singular`] if a diagonal entry of the
+    /// matrix is zero or not explicitly stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square or if `omega` is not in the open interval `(0, 2)`.
+    pub fn from_csr(matrix: &CsrMatrix<T>, omega: T) -> Result<Self, OperationError> {
+        assert_eq!(matrix.nrows(), matrix.ncols(), "Matrix must be square.");
+        let two = T::one() + T::one();
+        assert!(
+            omega > T::zero() && omega < two,
+            "The relaxation parameter must be in the interval (0, 2)."
+        );
+
+        let diagonal_offsets = find_diagonal_offsets(matrix.pattern())?;
+        for (i, &offset) in diagonal_offsets.iter().enumerate() {
+            if matrix.values()[offset].is_zero() {
+                return Err(zero_pivot_error(i));
+            }
+        }
+
+        Ok(Self {
+            matrix: matrix.clone(),
+            diagonal_offsets,
+            omega,
+        })
+    }
+
+    /// Constructs the SSOR preconditioner for the given CSC matrix and relaxation parameter.
+    ///
+    /// The matrix is converted to CSR format internally.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`OperationErrorKind::Singular`] if a diagonal entry of the
+    /// matrix is zero or not explicitly stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square or if `omega` is not in the open interval `(0, 2)`.
+    pub fn from_csc(matrix: &CscMatrix<T>, omega: T) -> Result<Self, OperationError> {
+        Self::from_csr(&CsrMatrix::from(matrix), omega)
+    }
+
+    /// The relaxation parameter `ω`.
+    #[must_use]
+    pub fn omega(&self) -> T {
+        self.omega.clone()
+    }
+}
+
+impl<T: RealField> Preconditioner<T> for Ssor<T> {
+    fn apply(&self, r: DVectorView<'_, T>, mut z: DVectorViewMut<'_, T>) {
+        let n = self.diagonal_offsets.len();
+        assert_compatible_preconditioner_dims!(n, r, z);
+        let (offsets, indices, values) = self.matrix.csr_data();
+        let omega = self.omega.clone();
+
+        // Forward sweep: solve (D + ω L) y = r
+        z.copy_from(&r);
+        for i in 0..n {
+            let diag = self.diagonal_offsets[i];
+            let mut z_i = z[i].clone();
+            for p in offsets[i]..diag {
+                z_i -= omega.clone() * values[p].clone() * z[indices[p]].clone();
+            }
+            z[i] = z_i / values[diag].clone();
+        }
+
+        for (z_i, &diag) in z.iter_mut().zip(&self.diagonal_offsets) {
+            *z_i *= values[diag].clone();
+        }
+
+        // Backward sweep: solve (D + ω U) z = D y
+        for i in (0..n).rev() {
+            let diag = self.diagonal_offsets[i];
+            let mut z_i = z[i].clone();
+            for p in diag + 1..offsets[i + 1] {
+                z_i -= omega.clone() * values[p].clone() * z[indices[p]].clone();
+            }
+            z[i] = z_i / values[diag].clone();
+        }
+
+        let two = T::one() + T::one();
+        z *= omega.clone() * (two - omega);
+    }
+}
+
+/// Returns the offsets of the diagonal entries of a square pattern.
+///
+/// Returns an error of kind [`OperationErrorKind::Singular`] if a diagonal entry is not
+/// explicitly stored.
+pub(super) fn find_diagonal_offsets(
+    pattern: &SparsityPattern,
+) -> Result<Vec<usize>, OperationError> {
+    (0..pattern.major_dim())
+        .map(|i| {
+            let begin = pattern.major_offsets()[i];
+            pattern
+                .lane(i)
+                .binary_search(&i)
+                .map(|local| begin + local)
+                .map_err(|_| {
+                    OperationError::from_kind_and_message(
+                        OperationErrorKind::Singular,
+                        format!("Diagonal entry ({0}, {0}) is not explicitly stored.", i),
+                    )
+                })
+        })
+        .collect()
+}
+
+pub(super) fn zero_pivot_error(i: usize) -> OperationError {
+    OperationError::from_kind_and_message(
+        OperationErrorKind::Singular,
+        format!("Zero pivot encountered at diagonal entry ({0}, {0}).", i),
+    )
+}
@@ -12,7 +12,7 @@ pub fn apply_permutation<T: Clone>(out_slice: &mut [T], in_slice: &[T], permutat
 
 /// computes permutation by using provided indices as keys
 #[inline]
-pub fn compute_sort_permutation(permutation: &mut [usize], indices: &[usize]) {
+pub fn compute_sort_permutation<I: Ord + Copy>(permutation: &mut [usize], indices: &[I]) {
     assert_eq!(permutation.len(), indices.len());
     // Set permutation to identity
     for (i, p) in permutation.iter_mut().enumerate() {
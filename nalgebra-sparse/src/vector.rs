@@ -0,0 +1,434 @@
+//! An implementation of a sparse vector.
+//!
+//! This is the module-level documentation. See [`SparseVector`] for the main documentation of the
+//! sparse vector implementation.
+
+use crate::utils::{apply_permutation, compute_sort_permutation};
+use crate::{SparseEntry, SparseEntryMut, SparseFormatError, SparseFormatErrorKind};
+
+use nalgebra::{ClosedAdd, ClosedMul, ComplexField, DVectorView, DVectorViewMut, Scalar};
+use num_traits::{Signed, Zero};
+
+use std::cmp::Ordering;
+
+/// A sparse vector.
+///
+/// A sparse vector of length `n` stores its explicitly stored entries as a sorted array of
+/// indices and an array of the associated values. This is the one-dimensional counterpart of the
+/// rows of a [CSR matrix](crate::csr::CsrMatrix) and the columns of a
+/// [CSC matrix](crate::csc::CscMatrix), which can be extracted as sparse vectors with
+/// [`CsrMatrix::row_as_vector`](crate::csr::CsrMatrix::row_as_vector) and
+/// [`CscMatrix::col_as_vector`](crate::csc::CscMatrix::col_as_vector).
+///
+/// # Usage
+///
+/// ```
+/// use nalgebra_sparse::vector::SparseVector;
+/// use nalgebra_sparse::csr::CsrMatrix;
+/// use nalgebra::{DMatrix, DVector};
+///
+/// // The vector [1.0, 0.0, 0.0, 2.0]
+/// let x = SparseVector::try_from_indices_and_values(4, vec![0, 3], vec![1.0, 2.0])
+///     .expect("Vector data must conform to format specifications");
+/// assert_eq!(x.nnz(), 2);
+/// assert_eq!(DVector::from(&x), DVector::from_column_slice(&[1.0, 0.0, 0.0, 2.0]));
+///
+/// let y = SparseVector::try_from_indices_and_values(4, vec![1, 3], vec![5.0, 3.0]).unwrap();
+/// assert_eq!(x.dot(&y), 6.0);
+///
+/// // Sparse matrix-sparse vector products produce sparse vectors
+/// let a = CsrMatrix::from(&DMatrix::from_row_slice(2, 4, &[
+///     1.0, 0.0, 0.0, 0.0,
+///     0.0, 1.0, 1.0, 0.0,
+/// ]));
+/// let ax = &a * &x;
+/// assert_eq!(ax.indices(), &[0]);
+/// assert_eq!(ax.values(), &[1.0]);
+/// ```
+///
+/// # Format
+///
+/// A sparse vector of length `n` with `nnz` explicitly stored entries is represented by the
+/// following two arrays:
+///
+/// - `indices`, an array of `nnz` indices.
+/// - `values`, an array of `nnz` values, where `values[k]` is the value of the entry at index
+///   `indices[k]`.
+///
+/// The following invariants must be upheld and are enforced by the data structure:
+///
+/// - `indices` and `values` have the same length.
+/// - Every index is smaller than `n`.
+/// - `indices` is strictly increasing, i.e. the indices are sorted and unique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseVector<T> {
+    len: usize,
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> SparseVector<T> {
+    /// Create a zero sparse vector with no explicitly stored entries.
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            len,
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Try to construct a sparse vector from its sorted indices and the associated values.
+    ///
+    /// An error is returned if the data given does not conform to the sparse vector format.
+    /// See the documentation for [SparseVector](struct.SparseVector.html) for more information.
+    pub fn try_from_indices_and_values(
+        len: usize,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError> {
+        use SparseFormatError as E;
+        use SparseFormatErrorKind as K;
+
+        if indices.len() != values.len() {
+            return Err(E::from_kind_and_msg(
+                K::InvalidStructure,
+                "Number of values and indices must be the same.",
+            ));
+        }
+
+        let mut prev_index = None;
+        for &index in &indices {
+            if index >= len {
+                return Err(E::from_kind_and_msg(
+                    K::IndexOutOfBounds,
+                    "An index is out of bounds.",
+                ));
+            }
+            match prev_index {
+                Some(prev) if prev == index => {
+                    return Err(E::from_kind_and_msg(
+                        K::DuplicateEntry,
+                        "Vector data contains duplicate indices.",
+                    ));
+                }
+                Some(prev) if prev > index => {
+                    return Err(E::from_kind_and_msg(
+                        K::InvalidStructure,
+                        "Indices are not monotonically increasing (sorted).",
+                    ));
+                }
+                _ => {}
+            }
+            prev_index = Some(index);
+        }
+
+        Ok(Self {
+            len,
+            indices,
+            values,
+        })
+    }
+
+    /// Try to construct a sparse vector from unsorted indices and the associated values.
+    ///
+    /// The indices and values are sorted before the data is validated. An error is returned if
+    /// the data does not otherwise conform to the sparse vector format, in particular if it
+    /// contains duplicate indices.
+    pub fn try_from_unsorted_indices_and_values(
+        len: usize,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<Self, SparseFormatError>
+    where
+        T: Clone,
+    {
+        if indices.len() != values.len() {
+            return Err(SparseFormatError::from_kind_and_msg(
+                SparseFormatErrorKind::InvalidStructure,
+                "Number of values and indices must be the same.",
+            ));
+        }
+
+        let mut permutation = vec![0; indices.len()];
+        compute_sort_permutation(&mut permutation, &indices);
+        let mut sorted_indices = indices.clone();
+        let mut sorted_values = values.clone();
+        apply_permutation(&mut sorted_indices, &indices, &permutation);
+        apply_permutation(&mut sorted_values, &values, &permutation);
+
+        Self::try_from_indices_and_values(len, sorted_indices, sorted_values)
+    }
+
+    /// The length of the vector, i.e. its dimension.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the vector has length zero.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of explicitly stored entries in the vector.
+    #[inline]
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The indices of the explicitly stored entries, in increasing order.
+    #[inline]
+    #[must_use]
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The values of the explicitly stored entries.
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Mutable access to the values of the explicitly stored entries.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// An iterator over the explicitly stored entries of the vector.
+    ///
+    /// The iterator yields pairs `(i, v)` in order of increasing index `i`.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.indices.iter().copied().zip(self.values.iter())
+    }
+
+    /// A mutable iterator over the explicitly stored entries of the vector.
+    ///
+    /// The iterator yields pairs `(i, v)` in order of increasing index `i`.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.indices.iter().copied().zip(self.values.iter_mut())
+    }
+
+    /// Returns an entry for the given index, or `None` if the index is out of bounds.
+    ///
+    /// Each call to this function incurs the cost of a binary search among the explicitly
+    /// stored entries.
+    #[must_use]
+    pub fn get_entry(&self, index: usize) -> Option<SparseEntry<'_, T>> {
+        (index < self.len).then(|| match self.indices.binary_search(&index) {
+            Ok(k) => SparseEntry::NonZero(&self.values[k]),
+            Err(_) => SparseEntry::Zero,
+        })
+    }
+
+    /// Returns a mutable entry for the given index, or `None` if the index is out of bounds.
+    ///
+    /// Each call to this function incurs the cost of a binary search among the explicitly
+    /// stored entries.
+    pub fn get_entry_mut(&mut self, index: usize) -> Option<SparseEntryMut<'_, T>> {
+        if index >= self.len {
+            return None;
+        }
+        Some(match self.indices.binary_search(&index) {
+            Ok(k) => SparseEntryMut::NonZero(&mut self.values[k]),
+            Err(_) => SparseEntryMut::Zero,
+        })
+    }
+
+    /// Disassembles the sparse vector into its length, indices and values.
+    pub fn disassemble(self) -> (usize, Vec<usize>, Vec<T>) {
+        (self.len, self.indices, self.values)
+    }
+}
+
+impl<T> SparseVector<T>
+where
+    T: Scalar + ClosedAdd + ClosedMul + Zero,
+{
+    /// The dot product of two sparse vectors.
+    ///
+    /// Only entries that are explicitly stored in both vectors contribute to the result. As for
+    /// [`Matrix::dot`](nalgebra::Matrix::dot), the entries are not conjugated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors do not have the same length.
+    #[must_use]
+    pub fn dot(&self, rhs: &SparseVector<T>) -> T {
+        assert_eq!(self.len, rhs.len, "Vectors must have the same length.");
+        let mut result = T::zero();
+        let (mut p, mut q) = (0, 0);
+        while p < self.nnz() && q < rhs.nnz() {
+            match self.indices[p].cmp(&rhs.indices[q]) {
+                Ordering::Less => p += 1,
+                Ordering::Greater => q += 1,
+                Ordering::Equal => {
+                    result += self.values[p].clone() * rhs.values[q].clone();
+                    p += 1;
+                    q += 1;
+                }
+            }
+        }
+        result
+    }
+
+    /// The dot product of this sparse vector with a dense vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors do not have the same length.
+    #[must_use]
+    pub fn dot_dense<'a>(&self, rhs: impl Into<DVectorView<'a, T>>) -> T {
+        let rhs = rhs.into();
+        assert_eq!(self.len, rhs.len(), "Vectors must have the same length.");
+        self.iter()
+            .fold(T::zero(), |acc, (i, v)| acc + v.clone() * rhs[i].clone())
+    }
+
+    /// Computes `self = a * x + b * self`.
+    ///
+    /// The explicitly stored entries of the result are the union of the explicitly stored
+    /// entries of `x` and `self`, so that `self` is reallocated unless the pattern of `x` is
+    /// contained in the pattern of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors do not have the same length.
+    pub fn axpy(&mut self, a: T, x: &SparseVector<T>, b: T) {
+        assert_eq!(self.len, x.len, "Vectors must have the same length.");
+
+        let is_subset = x.nnz() <= self.nnz()
+            && x.indices
+                .iter()
+                .all(|i| self.indices.binary_search(i).is_ok());
+        if is_subset {
+            for v in &mut self.values {
+                *v = b.clone() * v.clone();
+            }
+            let mut k = 0;
+            for (i, x_i) in x.iter() {
+                while self.indices[k] < i {
+                    k += 1;
+                }
+                self.values[k] += a.clone() * x_i.clone();
+            }
+            return;
+        }
+
+        let capacity = self.nnz() + x.nnz();
+        let mut indices = Vec::with_capacity(capacity);
+        let mut values = Vec::with_capacity(capacity);
+        let (mut p, mut q) = (0, 0);
+        while p < self.nnz() || q < x.nnz() {
+            let ordering = match (self.indices.get(p), x.indices.get(q)) {
+                (Some(i), Some(j)) => i.cmp(j),
+                (Some(_), None) => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+            match ordering {
+                Ordering::Less => {
+                    indices.push(self.indices[p]);
+                    values.push(b.clone() * self.values[p].clone());
+                    p += 1;
+                }
+                Ordering::Greater => {
+                    indices.push(x.indices[q]);
+                    values.push(a.clone() * x.values[q].clone());
+                    q += 1;
+                }
+                Ordering::Equal => {
+                    indices.push(self.indices[p]);
+                    values
+                        .push(b.clone() * self.values[p].clone() + a.clone() * x.values[q].clone());
+                    p += 1;
+                    q += 1;
+                }
+            }
+        }
+        self.indices = indices;
+        self.values = values;
+    }
+
+    /// Computes `y = a * self + b * y` for a dense vector `y`.
+    ///
+    /// As for [`Matrix::axpy`](nalgebra::Matrix::axpy), `y` is never read from if `b` is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors do not have the same length.
+    pub fn axpy_to_dense<'a>(&self, a: T, y: impl Into<DVectorViewMut<'a, T>>, b: T) {
+        let mut y = y.into();
+        assert_eq!(self.len, y.len(), "Vectors must have the same length.");
+        if b.is_zero() {
+            y.fill(T::zero());
+        } else {
+            y *= b;
+        }
+        for (i, x_i) in self.iter() {
+            y[i] += a.clone() * x_i.clone();
+        }
+    }
+}
+
+impl<T: ComplexField> SparseVector<T> {
+    /// The squared Euclidean norm of the vector.
+    #[must_use]
+    pub fn norm_squared(&self) -> T::RealField {
+        self.values.iter().fold(T::RealField::zero(), |acc, v| {
+            acc + v.clone().modulus_squared()
+        })
+    }
+
+    /// The Euclidean norm of the vector.
+    #[must_use]
+    pub fn norm(&self) -> T::RealField {
+        self.norm_squared().sqrt()
+    }
+
+    /// The [Lp norm](https://en.wikipedia.org/wiki/Lp_space) of the vector.
+    ///
+    /// As for [`Matrix::lp_norm`](nalgebra::Matrix::lp_norm), `p` must be positive.
+    #[must_use]
+    pub fn lp_norm(&self, p: i32) -> T::RealField {
+        self.values
+            .iter()
+            .fold(T::RealField::zero(), |acc, v| {
+                acc + v.clone().modulus().powi(p)
+            })
+            .powf(nalgebra::convert(1.0 / p as f64))
+    }
+
+    /// The largest 1-norm of the explicitly stored complex entries, i.e. the largest value of
+    /// `|re| + |im|`, or zero if there are none.
+    #[must_use]
+    pub fn camax(&self) -> T::RealField {
+        self.values.iter().fold(T::RealField::zero(), |max, v| {
+            let norm = v.clone().norm1();
+            if norm > max {
+                norm
+            } else {
+                max
+            }
+        })
+    }
+}
+
+impl<T: Scalar + Zero + Signed + PartialOrd> SparseVector<T> {
+    /// The largest absolute value of the explicitly stored entries, or zero if there are none.
+    #[must_use]
+    pub fn amax(&self) -> T {
+        self.values.iter().fold(T::zero(), |max, v| {
+            let abs = v.abs();
+            if abs > max {
+                abs
+            } else {
+                max
+            }
+        })
+    }
+}
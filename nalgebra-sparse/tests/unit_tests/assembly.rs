@@ -0,0 +1,193 @@
+use crate::assert_panics;
+use nalgebra::{DMatrix, DMatrixViewMut};
+use nalgebra_sparse::assembly::{
+    add_element_matrix, color_elements, pattern_from_connectivity, CsrAssembler,
+};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::serial::OperationErrorKind;
+use nalgebra_sparse::pattern::SparsityPattern;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Generates the number of nodes and a list of elements with between one and four nodes each.
+///
+/// Nodes may be repeated within an element.
+fn mesh_strategy() -> impl Strategy<Value = (usize, Vec<Vec<usize>>)> {
+    (1..=8usize).prop_flat_map(|num_nodes| {
+        let element = vec(0..num_nodes, 1..=4);
+        (Just(num_nodes), vec(element, 0..=12))
+    })
+}
+
+/// A deterministic element matrix that differs between elements and entries.
+fn fill_element_matrix(element_index: usize, mut element_matrix: DMatrixViewMut<'_, i32>) {
+    let n = element_matrix.nrows();
+    for a in 0..n {
+        for b in 0..n {
+            element_matrix[(a, b)] = (element_index as i32 + 2 * a as i32 - 3 * b as i32) % 7;
+        }
+    }
+}
+
+/// Assembles the expected result through a COO matrix.
+fn assemble_with_coo(num_nodes: usize, elements: &[Vec<usize>]) -> DMatrix<i32> {
+    let mut coo = CooMatrix::new(num_nodes, num_nodes);
+    for (element_index, nodes) in elements.iter().enumerate() {
+        let mut element_matrix = DMatrix::zeros(nodes.len(), nodes.len());
+        fill_element_matrix(element_index, (&mut element_matrix).into());
+        for (a, &i) in nodes.iter().enumerate() {
+            for (b, &j) in nodes.iter().enumerate() {
+                coo.push(i, j, element_matrix[(a, b)]);
+            }
+        }
+    }
+    DMatrix::from(&coo)
+}
+
+fn zeros_with_pattern(pattern: SparsityPattern) -> CsrMatrix<i32> {
+    let nnz = pattern.nnz();
+    CsrMatrix::try_from_pattern_and_values(pattern, vec![0; nnz]).unwrap()
+}
+
+#[test]
+fn pattern_from_connectivity_couples_element_nodes() {
+    let elements = vec![vec![0, 2], vec![2, 3, 1]];
+    let pattern = pattern_from_connectivity(5, &elements);
+    assert_eq!(pattern.major_dim(), 5);
+    assert_eq!(pattern.minor_dim(), 5);
+    assert_eq!(pattern.lane(0), &[0, 2]);
+    assert_eq!(pattern.lane(1), &[1, 2, 3]);
+    assert_eq!(pattern.lane(2), &[0, 1, 2, 3]);
+    assert_eq!(pattern.lane(3), &[1, 2, 3]);
+    assert!(pattern.lane(4).is_empty());
+}
+
+#[test]
+fn add_element_matrix_into_fixed_pattern() {
+    let elements = vec![[0, 1], [1, 2]];
+    let mut matrix = zeros_with_pattern(pattern_from_connectivity(3, &elements));
+    let element_matrix = DMatrix::from_row_slice(2, 2, &[1, -1, -1, 1]);
+    for nodes in &elements {
+        add_element_matrix(&mut matrix, nodes, &element_matrix).unwrap();
+    }
+    assert_eq!(
+        DMatrix::from(&matrix),
+        DMatrix::from_row_slice(3, 3, &[1, -1, 0, -1, 2, -1, 0, -1, 1])
+    );
+
+    // The entry (0, 2) is not part of the pattern
+    let error = add_element_matrix(&mut matrix, &[0, 2], &element_matrix).unwrap_err();
+    assert!(matches!(error.kind(), OperationErrorKind::InvalidPattern));
+}
+
+#[test]
+fn csr_assembler_rejects_incompatible_pattern() {
+    let pattern = CsrMatrix::<i32>::identity(3).pattern().clone();
+    let error = CsrAssembler::try_from_pattern(&pattern, &[[0, 1]]).unwrap_err();
+    assert!(matches!(error.kind(), OperationErrorKind::InvalidPattern));
+}
+
+#[test]
+fn csr_assembler_accessors() {
+    let elements = vec![vec![0, 1], vec![1, 2], vec![2, 3], vec![3]];
+    let pattern = pattern_from_connectivity(4, &elements);
+    let assembler = CsrAssembler::try_from_pattern(&pattern, &elements).unwrap();
+    assert_eq!(assembler.num_elements(), 4);
+    assert_eq!(assembler.element_nodes(1), &[1, 2]);
+    assert_eq!(assembler.element_nodes(3), &[3]);
+    #[cfg(feature = "rayon")]
+    {
+        assert_eq!(assembler.num_colors(), 2);
+        assert_eq!(assembler.color_elements(0), &[0, 2]);
+        assert_eq!(assembler.color_elements(1), &[1, 3]);
+    }
+}
+
+#[test]
+fn csr_assembler_panics_on_incompatible_matrix() {
+    let elements = vec![[0, 1]];
+    let pattern = pattern_from_connectivity(2, &elements);
+    let assembler = CsrAssembler::try_from_pattern(&pattern, &elements).unwrap();
+    let matrix = CsrMatrix::<i32>::identity(2);
+    assert_panics!(assembler.assemble(&mut matrix.clone(), |_, _| {}));
+    assert_panics!(assembler.add_element_matrix(
+        &mut matrix.clone(),
+        0,
+        &DMatrix::from_element(2, 2, 1)
+    ));
+}
+
+#[test]
+fn csr_assembler_panics_on_matrix_with_same_nnz_but_different_pattern() {
+    let elements = vec![[0, 1]];
+    let pattern = pattern_from_connectivity(3, &elements);
+    let assembler = CsrAssembler::try_from_pattern(&pattern, &elements).unwrap();
+    let other_pattern = pattern_from_connectivity(3, &[[1, 2]]);
+    assert_eq!(other_pattern.nnz(), pattern.nnz());
+    let matrix = zeros_with_pattern(other_pattern);
+    assert_panics!(assembler.assemble(&mut matrix.clone(), |_, _| {}));
+    assert_panics!(assembler.add_element_matrix(
+        &mut matrix.clone(),
+        0,
+        &DMatrix::from_element(2, 2, 1)
+    ));
+}
+
+proptest! {
+    #[test]
+    fn color_elements_is_valid_coloring((num_nodes, elements) in mesh_strategy()) {
+        let colors = color_elements(num_nodes, &elements);
+
+        let mut all_elements: Vec<_> = colors.iter().flatten().copied().collect();
+        all_elements.sort_unstable();
+        prop_assert_eq!(all_elements, (0..elements.len()).collect::<Vec<_>>());
+
+        for color in &colors {
+            prop_assert!(!color.is_empty());
+            let mut used = vec![false; num_nodes];
+            for &element_index in color {
+                let mut nodes = elements[element_index].clone();
+                nodes.sort_unstable();
+                nodes.dedup();
+                for i in nodes {
+                    prop_assert!(!used[i]);
+                    used[i] = true;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn assembly_matches_coo((num_nodes, elements) in mesh_strategy()) {
+        let expected = assemble_with_coo(num_nodes, &elements);
+        let pattern = pattern_from_connectivity(num_nodes, &elements);
+
+        // Binary search
+        let mut matrix = zeros_with_pattern(pattern.clone());
+        for (element_index, nodes) in elements.iter().enumerate() {
+            let mut element_matrix = DMatrix::zeros(nodes.len(), nodes.len());
+            fill_element_matrix(element_index, (&mut element_matrix).into());
+            add_element_matrix(&mut matrix, nodes, &element_matrix).unwrap();
+        }
+        prop_assert_eq!(DMatrix::from(&matrix), expected.clone());
+
+        // Cached positions
+        let assembler = CsrAssembler::try_from_pattern(&pattern, &elements).unwrap();
+        let mut matrix = zeros_with_pattern(pattern.clone());
+        assembler.assemble(&mut matrix, fill_element_matrix);
+        prop_assert_eq!(DMatrix::from(&matrix), expected.clone());
+
+        // Assembly accumulates into the existing values
+        assembler.assemble(&mut matrix, fill_element_matrix);
+        prop_assert_eq!(DMatrix::from(&matrix), expected.clone() * 2);
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut matrix = zeros_with_pattern(pattern);
+            assembler.par_assemble(&mut matrix, fill_element_matrix);
+            prop_assert_eq!(DMatrix::from(&matrix), expected);
+        }
+    }
+}
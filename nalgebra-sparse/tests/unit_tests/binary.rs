@@ -0,0 +1,150 @@
+use nalgebra::Complex;
+use nalgebra_sparse::io::{
+    load_csc_from_binary, load_csc_from_binary_file, load_csr_from_binary,
+    load_csr_from_binary_file, save_csc_to_binary, save_csc_to_binary_file, save_csr_to_binary,
+    save_csr_to_binary_file, BinaryFormatErrorKind,
+};
+use nalgebra_sparse::proptest::{csc, csr};
+use nalgebra_sparse::{CscMatrix, CsrMatrix, SparseFormatErrorKind};
+use proptest::prelude::*;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn example_csr() -> CsrMatrix<f64> {
+    CsrMatrix::try_from_csr_data(
+        2,
+        3,
+        vec![0, 2, 3],
+        vec![0, 2, 1],
+        vec![1.0, -0.0, f64::MIN_POSITIVE],
+    )
+    .unwrap()
+}
+
+#[test]
+fn binary_header_layout() {
+    let mut bytes = Vec::new();
+    save_csr_to_binary(&mut bytes, &example_csr()).unwrap();
+
+    assert_eq!(&bytes[0..8], b"NASPARSE");
+    assert_eq!(&bytes[8..12], &1u32.to_le_bytes());
+    // CSR storage, f64 type code and the reserved bytes
+    assert_eq!(&bytes[12..16], &[0, 12, 0, 0]);
+    assert_eq!(&bytes[16..24], &2u64.to_le_bytes());
+    assert_eq!(&bytes[24..32], &3u64.to_le_bytes());
+    assert_eq!(&bytes[32..40], &3u64.to_le_bytes());
+    assert_eq!(bytes.len(), 40 + 3 * 8 + 3 * 8 + 3 * 8);
+}
+
+#[test]
+fn binary_roundtrip_is_bitwise_exact() {
+    let matrix = example_csr();
+    let mut bytes = Vec::new();
+    save_csr_to_binary(&mut bytes, &matrix).unwrap();
+    let loaded = load_csr_from_binary::<f64, _>(bytes.as_slice()).unwrap();
+
+    assert_eq!(loaded.row_offsets(), matrix.row_offsets());
+    assert_eq!(loaded.col_indices(), matrix.col_indices());
+    let to_bits = |values: &[f64]| values.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+    assert_eq!(to_bits(loaded.values()), to_bits(matrix.values()));
+}
+
+#[test]
+fn binary_roundtrip_complex_and_pattern() {
+    let complex = CscMatrix::try_from_csc_data(
+        2,
+        2,
+        vec![0, 1, 2],
+        vec![1, 0],
+        vec![Complex::new(1.5f32, -2.0), Complex::new(0.0, 3.25)],
+    )
+    .unwrap();
+    let mut bytes = Vec::new();
+    save_csc_to_binary(&mut bytes, &complex).unwrap();
+    let loaded = load_csc_from_binary::<Complex<f32>, _>(bytes.as_slice()).unwrap();
+    assert_eq!(loaded, complex);
+
+    let pattern =
+        CscMatrix::try_from_csc_data(2, 2, vec![0, 1, 2], vec![1, 0], vec![(), ()]).unwrap();
+    let mut bytes = Vec::new();
+    save_csc_to_binary(&mut bytes, &pattern).unwrap();
+    assert_eq!(bytes.len(), 40 + 3 * 8 + 2 * 8);
+    let loaded = load_csc_from_binary::<(), _>(bytes.as_slice()).unwrap();
+    assert_eq!(loaded, pattern);
+}
+
+#[test]
+fn binary_load_errors() {
+    let mut bytes = Vec::new();
+    save_csr_to_binary(&mut bytes, &example_csr()).unwrap();
+
+    let mut invalid_magic = bytes.clone();
+    invalid_magic[0] = b'X';
+    let err = load_csr_from_binary::<f64, _>(invalid_magic.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), BinaryFormatErrorKind::InvalidHeader);
+
+    let mut invalid_version = bytes.clone();
+    invalid_version[8..12].copy_from_slice(&2u32.to_le_bytes());
+    let err = load_csr_from_binary::<f64, _>(invalid_version.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), BinaryFormatErrorKind::UnsupportedVersion);
+
+    let mut invalid_reserved = bytes.clone();
+    invalid_reserved[14] = 1;
+    let err = load_csr_from_binary::<f64, _>(invalid_reserved.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), BinaryFormatErrorKind::InvalidHeader);
+
+    let err = load_csc_from_binary::<f64, _>(bytes.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), BinaryFormatErrorKind::StorageMismatch);
+
+    let err = load_csr_from_binary::<f32, _>(bytes.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), BinaryFormatErrorKind::TypeMismatch);
+
+    let err = load_csr_from_binary::<f64, _>(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        BinaryFormatErrorKind::IOError(ErrorKind::UnexpectedEof)
+    );
+
+    let err = load_csr_from_binary::<f64, _>(&bytes[..20]).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        BinaryFormatErrorKind::IOError(ErrorKind::UnexpectedEof)
+    );
+
+    // Column index 3 is out of bounds for a matrix with 3 columns
+    let mut invalid_index = bytes.clone();
+    let index_start = 40 + 3 * 8;
+    invalid_index[index_start..index_start + 8].copy_from_slice(&3u64.to_le_bytes());
+    let err = load_csr_from_binary::<f64, _>(invalid_index.as_slice()).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        BinaryFormatErrorKind::SparseFormatError(SparseFormatErrorKind::IndexOutOfBounds)
+    );
+}
+
+proptest! {
+    #[test]
+    fn csr_binary_roundtrip(matrix in csr(any::<i64>(), 0 ..= 10, 0 ..= 10, 100)) {
+        let mut bytes = Vec::new();
+        save_csr_to_binary(&mut bytes, &matrix).unwrap();
+        let loaded = load_csr_from_binary::<i64, _>(bytes.as_slice()).unwrap();
+        prop_assert_eq!(loaded, matrix);
+    }
+}
+
+proptest! {
+    #[test]
+    fn csc_binary_roundtrip_file(matrix in csc(any::<u16>(), 0 ..= 10, 0 ..= 10, 100)) {
+        let temp_dir = tempdir().expect("Unable to create temporary directory");
+        let file_path = temp_dir.path().join("temp.bin");
+        save_csc_to_binary_file(&matrix, &file_path).unwrap();
+        let loaded = load_csc_from_binary_file::<u16, _>(&file_path).unwrap();
+        prop_assert_eq!(&loaded, &matrix);
+
+        let csr = CsrMatrix::from(&matrix);
+        save_csr_to_binary_file(&csr, &file_path).unwrap();
+        let loaded = load_csr_from_binary_file::<u16, _>(&file_path).unwrap();
+        prop_assert_eq!(loaded, csr);
+        temp_dir.close().expect("Unable to delete temporary directory");
+    }
+}
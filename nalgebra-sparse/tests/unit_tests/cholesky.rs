@@ -1,9 +1,12 @@
 #![cfg_attr(rustfmt, rustfmt_skip)]
 use crate::common::{value_strategy, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ};
 use nalgebra_sparse::csc::CscMatrix;
-use nalgebra_sparse::factorization::{CscCholesky};
+use crate::assert_panics;
+use nalgebra_sparse::factorization::{CholeskyError, CscCholesky};
+use nalgebra_sparse::io::load_coo_from_matrix_market_str;
 use nalgebra_sparse::proptest::csc;
-use nalgebra::{Matrix5, Vector5, Cholesky, DMatrix};
+use nalgebra_sparse::vector::SparseVector;
+use nalgebra::{Complex, Matrix5, Vector5, Cholesky, DMatrix, DVector};
 use nalgebra::proptest::matrix;
 
 use proptest::prelude::*;
@@ -21,6 +24,16 @@ fn positive_definite() -> impl Strategy<Value=CscMatrix<f64>> {
         })
 }
 
+fn complex_value_strategy() -> impl Strategy<Value=Complex<f64>> + Clone {
+    (value_strategy::<f64>(), value_strategy::<f64>())
+        .prop_map(|(re, im)| Complex::new(re, im))
+}
+
+fn hermitian_positive_definite() -> impl Strategy<Value=CscMatrix<Complex<f64>>> {
+    csc(complex_value_strategy(), PROPTEST_MATRIX_DIM, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ)
+        .prop_map(|x| x.adjoint() * &x + CscMatrix::identity(x.ncols()))
+}
+
 proptest! {
     #[test]
     fn cholesky_correct_for_positive_definite_matrices(
@@ -60,6 +73,187 @@ proptest! {
         }
     }
 
+    #[test]
+    fn cholesky_correct_for_hermitian_positive_definite_matrices(
+        matrix in hermitian_positive_definite()
+    ) {
+        let cholesky = CscCholesky::factor(&matrix).unwrap();
+        let l = cholesky.take_l();
+        let matrix_reconstructed = &l * l.adjoint();
+
+        let error = DMatrix::from(&matrix_reconstructed) - DMatrix::from(&matrix);
+        prop_assert!(error.norm() <= 1e-8);
+
+        let is_lower_triangular = l.triplet_iter().all(|(i, j, _)| j <= i);
+        prop_assert!(is_lower_triangular);
+        let has_real_positive_diagonal = l.triplet_iter()
+            .filter(|(i, j, _)| i == j)
+            .all(|(_, _, v)| v.im == 0.0 && v.re > 0.0);
+        prop_assert!(has_real_positive_diagonal);
+    }
+
+    #[test]
+    fn cholesky_solve_hermitian_positive_definite(
+        (matrix, rhs) in hermitian_positive_definite()
+            .prop_flat_map(|csc| {
+                let rhs = matrix(complex_value_strategy(), csc.nrows(), PROPTEST_MATRIX_DIM);
+                (Just(csc), rhs)
+            })
+    ) {
+        let cholesky = CscCholesky::factor(&matrix).unwrap();
+        let x = cholesky.solve(&rhs);
+        prop_assert!((&matrix * &x - &rhs).norm() <= 1e-10 * rhs.norm().max(1.0));
+    }
+
+    #[test]
+    fn cholesky_determinant_and_selected_inverse(matrix in positive_definite()) {
+        let cholesky = CscCholesky::factor(&matrix).unwrap();
+        let dense = DMatrix::from(&matrix);
+        let dense_cholesky = Cholesky::new(dense.clone()).unwrap();
+
+        let ln_det = dense_cholesky.ln_determinant();
+        prop_assert!((cholesky.ln_determinant() - ln_det).abs() <= 1e-10 * ln_det.abs().max(1.0));
+        let det = dense_cholesky.determinant();
+        prop_assert!((cholesky.determinant() - det).abs() <= 1e-10 * det);
+
+        let inverse = dense_cholesky.inverse();
+        let selected = cholesky.selected_inverse();
+        prop_assert_eq!(selected.pattern(), cholesky.l().pattern());
+        for (i, j, &z) in selected.triplet_iter() {
+            prop_assert!((z - inverse[(i, j)]).abs() <= 1e-10 * inverse.norm());
+        }
+    }
+
+    #[test]
+    fn cholesky_selected_inverse_hermitian(matrix in hermitian_positive_definite()) {
+        let cholesky = CscCholesky::factor(&matrix).unwrap();
+        let inverse = Cholesky::new(DMatrix::from(&matrix)).unwrap().inverse();
+        let selected = cholesky.selected_inverse();
+        for (i, j, &z) in selected.triplet_iter() {
+            prop_assert!((z - inverse[(i, j)]).norm_sqr().sqrt() <= 1e-10 * inverse.norm());
+        }
+        prop_assert!(selected.diagonal_as_csc().values().iter().all(|z| z.im == 0.0));
+    }
+
+    #[test]
+    fn cholesky_rank_one_update_and_downdate(
+        (matrix, column, values) in modification_strategy(positive_definite(), value_strategy::<f64>())
+    ) {
+        let mut cholesky = CscCholesky::factor(&matrix).unwrap();
+        let w = vector_in_column(cholesky.l(), column, &values);
+        let w_dense = DVector::from(&w);
+
+        cholesky.rank_one_update(&w).unwrap();
+        let updated = DMatrix::from(&matrix) + &w_dense * w_dense.transpose();
+        prop_assert!(reconstruction_error(&cholesky, &updated) <= 1e-8);
+
+        cholesky.rank_one_downdate(&w).unwrap();
+        prop_assert!(reconstruction_error(&cholesky, &DMatrix::from(&matrix)) <= 1e-8);
+    }
+
+    #[test]
+    fn cholesky_rank_one_update_and_downdate_hermitian(
+        (matrix, column, values) in modification_strategy(hermitian_positive_definite(), complex_value_strategy())
+    ) {
+        let mut cholesky = CscCholesky::factor(&matrix).unwrap();
+        let w = vector_in_column(cholesky.l(), column, &values);
+        let w_dense = DVector::from(&w);
+
+        cholesky.rank_one_update(&w).unwrap();
+        let updated = DMatrix::from(&matrix) + &w_dense * w_dense.adjoint();
+        prop_assert!(reconstruction_error(&cholesky, &updated) <= 1e-8);
+        let has_real_positive_diagonal = cholesky.l().triplet_iter()
+            .filter(|(i, j, _)| i == j)
+            .all(|(_, _, v)| v.im == 0.0 && v.re > 0.0);
+        prop_assert!(has_real_positive_diagonal);
+
+        cholesky.rank_one_downdate(&w).unwrap();
+        prop_assert!(reconstruction_error(&cholesky, &DMatrix::from(&matrix)) <= 1e-8);
+    }
+
+    #[test]
+    fn cholesky_rank_k_update_and_downdate(
+        ((matrix, first, values), second) in modification_strategy(positive_definite(), value_strategy::<f64>())
+            .prop_flat_map(|(matrix, column, values)| {
+                let n = matrix.ncols();
+                ((Just(matrix), Just(column), Just(values)), 0..n)
+            })
+    ) {
+        let mut cholesky = CscCholesky::factor(&matrix).unwrap();
+        let n = matrix.ncols();
+        let mut offsets = vec![0];
+        let mut indices = Vec::new();
+        let mut c_values = Vec::new();
+        for &column in &[first, second] {
+            let w = vector_in_column(cholesky.l(), column, &values);
+            indices.extend_from_slice(w.indices());
+            c_values.extend_from_slice(w.values());
+            offsets.push(indices.len());
+        }
+        let c = CscMatrix::try_from_csc_data(n, 2, offsets, indices, c_values).unwrap();
+        let c_dense = DMatrix::from(&c);
+
+        cholesky.rank_k_update(&c).unwrap();
+        let updated = DMatrix::from(&matrix) + &c_dense * c_dense.transpose();
+        prop_assert!(reconstruction_error(&cholesky, &updated) <= 1e-8);
+
+        cholesky.rank_k_downdate(&c).unwrap();
+        prop_assert!(reconstruction_error(&cholesky, &DMatrix::from(&matrix)) <= 1e-8);
+    }
+
+    #[test]
+    fn cholesky_delete_and_add_row(
+        (matrix, k, _) in modification_strategy(positive_definite(), value_strategy::<f64>())
+    ) {
+        let mut cholesky = CscCholesky::factor(&matrix).unwrap();
+
+        cholesky.delete_row(k);
+        let mut deleted = DMatrix::from(&matrix);
+        deleted.row_mut(k).fill(0.0);
+        deleted.column_mut(k).fill(0.0);
+        deleted[(k, k)] = 1.0;
+        prop_assert!(reconstruction_error(&cholesky, &deleted) <= 1e-8);
+
+        cholesky.add_row(k, &matrix.col_as_vector(k)).unwrap();
+        prop_assert!(reconstruction_error(&cholesky, &DMatrix::from(&matrix)) <= 1e-8);
+    }
+
+    #[test]
+    fn cholesky_delete_and_add_row_hermitian(
+        (matrix, k, _) in modification_strategy(hermitian_positive_definite(), complex_value_strategy())
+    ) {
+        let mut cholesky = CscCholesky::factor(&matrix).unwrap();
+
+        cholesky.delete_row(k);
+        cholesky.add_row(k, &matrix.col_as_vector(k)).unwrap();
+        prop_assert!(reconstruction_error(&cholesky, &DMatrix::from(&matrix)) <= 1e-8);
+    }
+}
+
+/// Generates a non-empty matrix together with a column index and some values.
+fn modification_strategy<T: std::fmt::Debug + Clone>(
+    matrix: impl Strategy<Value=CscMatrix<T>>,
+    value: impl Strategy<Value=T> + Clone,
+) -> impl Strategy<Value=(CscMatrix<T>, usize, Vec<T>)> {
+    matrix
+        .prop_filter("matrix must be non-empty", |m| m.ncols() > 0)
+        .prop_flat_map(move |m| {
+            let n = m.ncols();
+            (Just(m), 0..n, proptest::collection::vec(value.clone(), 1..=4))
+        })
+}
+
+/// A vector whose pattern is that of the given column of `L`, which makes it a valid rank-one
+/// modification of the factorization.
+fn vector_in_column<T: Clone>(l: &CscMatrix<T>, column: usize, values: &[T]) -> SparseVector<T> {
+    let indices = l.col(column).row_indices().to_vec();
+    let values = values.iter().cycle().take(indices.len()).cloned().collect();
+    SparseVector::try_from_indices_and_values(l.nrows(), indices, values).unwrap()
+}
+
+fn reconstruction_error<T: nalgebra::ComplexField>(cholesky: &CscCholesky<T>, expected: &DMatrix<T>) -> T::RealField {
+    let l = DMatrix::from(cholesky.l());
+    (&l * l.adjoint() - expected).norm() / expected.norm()
 }
 
 // This is a test ported from nalgebra's "sparse" module, for the original CsCholesky impl
@@ -114,4 +308,78 @@ fn test_cholesky(a: Matrix5<f64>) {
     let l = DMatrix::from_iterator(l.nrows(), l.ncols(), l.iter().cloned());
     let cs_l_mat = DMatrix::from(&cs_l);
     assert_matrix_eq!(l, cs_l_mat, comp = abs, tol = 1e-12);
-}
\ No newline at end of file
+}
+
+#[test]
+fn complex_cholesky_agrees_with_dense_cholesky() {
+    let file_str = r#"
+%%MatrixMarket matrix coordinate complex hermitian
+    4 4 7
+    1 1  4.0  0.0
+    2 1  1.0  1.0
+    2 2  5.0  0.0
+    3 2  0.0 -2.0
+    3 3  6.0  0.0
+    4 1  1.0 -0.5
+    4 4  3.0  0.0
+"#;
+    let coo = load_coo_from_matrix_market_str::<Complex<f64>>(file_str).unwrap();
+    let csc = CscMatrix::from(&coo);
+
+    let dense_l = Cholesky::new(DMatrix::from(&csc)).unwrap().l();
+    let cholesky = CscCholesky::factor(&csc).unwrap();
+    assert!((DMatrix::from(cholesky.l()) - &dense_l).norm() <= 1e-12);
+
+    let b = DMatrix::from_fn(4, 2, |i, j| Complex::new(i as f64, j as f64 - 1.0));
+    let x = cholesky.solve(&b);
+    assert!((&csc * &x - &b).norm() <= 1e-12);
+}
+
+#[test]
+fn cholesky_downdate_preserves_factor_on_failure() {
+    let mut a = Matrix5::new(
+        4.0, 0.0, 0.0, 0.0, 0.0,
+        1.0, 4.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 4.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 4.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 4.0
+    );
+    a.fill_upper_triangle_with_lower_triangle();
+    let mut cholesky = CscCholesky::factor(&CscMatrix::from(&a)).unwrap();
+    let l = cholesky.l().clone();
+
+    // The downdate only fails at the last column on the path
+    let w = SparseVector::try_from_indices_and_values(5, vec![3, 4], vec![0.5, 3.0]).unwrap();
+    assert_eq!(cholesky.rank_one_downdate(&w), Err(CholeskyError::NotPositiveDefinite));
+    assert_eq!(cholesky.l(), &l);
+
+    // Column 0 of L has no entry in row 2
+    let w = SparseVector::try_from_indices_and_values(5, vec![0, 2], vec![1.0, 1.0]).unwrap();
+    assert_eq!(cholesky.rank_one_update(&w), Err(CholeskyError::IncompatiblePattern));
+    assert_eq!(cholesky.l(), &l);
+
+    let w = SparseVector::try_from_indices_and_values(5, vec![1, 2], vec![1.0, 1.0]).unwrap();
+    cholesky.rank_one_update(&w).unwrap();
+    cholesky.rank_one_downdate(&w).unwrap();
+    assert_matrix_eq!(DMatrix::from(cholesky.l()), DMatrix::from(&l), comp = abs, tol = 1e-12);
+
+    // A row must be deleted before it can be added, and the new row must fit the pattern
+    let row = SparseVector::try_from_indices_and_values(5, vec![1, 2], vec![1.0, 4.0]).unwrap();
+    assert_panics!(cholesky.clone().add_row(2, &row));
+    cholesky.delete_row(2);
+    let row = SparseVector::try_from_indices_and_values(5, vec![0, 2], vec![1.0, 4.0]).unwrap();
+    assert_eq!(cholesky.add_row(2, &row), Err(CholeskyError::IncompatiblePattern));
+    let row = SparseVector::try_from_indices_and_values(5, vec![1, 2], vec![1.0, -4.0]).unwrap();
+    assert_eq!(cholesky.add_row(2, &row), Err(CholeskyError::NotPositiveDefinite));
+    let row = SparseVector::try_from_indices_and_values(5, vec![1, 2, 3], vec![1.0, 4.0, 1.0]).unwrap();
+    cholesky.add_row(2, &row).unwrap();
+    assert_matrix_eq!(DMatrix::from(cholesky.l()), DMatrix::from(&l), comp = abs, tol = 1e-12);
+}
+
+#[test]
+fn cholesky_of_empty_matrix_has_unit_determinant() {
+    let cholesky = CscCholesky::<f64>::factor(&CscMatrix::zeros(0, 0)).unwrap();
+    assert_eq!(cholesky.determinant(), 1.0);
+    assert_eq!(cholesky.ln_determinant(), 0.0);
+    assert_eq!(cholesky.selected_inverse().nnz(), 0);
+}
@@ -0,0 +1,183 @@
+use crate::assert_panics;
+use crate::common::{
+    csc_strategy, csr_strategy, non_zero_i32_value_strategy, value_strategy, PROPTEST_MAX_NNZ,
+};
+use nalgebra::{DMatrix, DVector};
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::serial::{elementwise_intersection_csr, elementwise_union_csr};
+use nalgebra_sparse::proptest::csr;
+
+use proptest::prelude::*;
+
+/// The main diagonal of a possibly rectangular dense matrix.
+fn dense_diagonal<T: nalgebra::Scalar>(dense: &DMatrix<T>) -> DVector<T> {
+    let n = dense.nrows().min(dense.ncols());
+    DVector::from_fn(n, |i, _| dense[(i, i)].clone())
+}
+
+/// Generates two matrices with the same dimensions.
+fn csr_pair() -> impl Strategy<Value = (CsrMatrix<i32>, CsrMatrix<i32>)> {
+    csr_strategy().prop_flat_map(|a| {
+        let (nrows, ncols) = (a.nrows(), a.ncols());
+        let b = csr(value_strategy::<i32>(), nrows, ncols, PROPTEST_MAX_NNZ);
+        (Just(a), b)
+    })
+}
+
+#[test]
+fn csr_componentwise_example() {
+    #[rustfmt::skip]
+    let a = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 2,
+        0, -3, 0,
+    ]);
+    #[rustfmt::skip]
+    let b = DMatrix::from_row_slice(2, 3, &[
+        4, 5, 0,
+        0, 6, 0,
+    ]);
+    let (a, b) = (CsrMatrix::from(&a), CsrMatrix::from(&b));
+
+    let product = a.component_mul(&b);
+    assert_eq!(product.nnz(), 2);
+    assert_eq!(
+        DMatrix::from(&product),
+        DMatrix::from_row_slice(2, 3, &[4, 0, 0, 0, -18, 0])
+    );
+    let min = a.inf(&b);
+    assert_eq!(min.nnz(), 4);
+    assert_eq!(
+        DMatrix::from(&min),
+        DMatrix::from_row_slice(2, 3, &[1, 0, 0, 0, -3, 0])
+    );
+    assert_eq!(
+        DMatrix::from(&a.sup(&b)),
+        DMatrix::from_row_slice(2, 3, &[4, 5, 2, 0, 6, 0])
+    );
+
+    let union = elementwise_union_csr(&a, &b, |x, y| (x, y));
+    assert_eq!(union.pattern(), min.pattern());
+    assert_eq!(union.values(), &[(1, 4), (0, 5), (2, 0), (-3, 6)]);
+    let intersection = elementwise_intersection_csr(&a, &b, |x, y| x - y);
+    assert_eq!(intersection.pattern(), product.pattern());
+    assert_eq!(intersection.values(), &[-3, -9]);
+}
+
+#[test]
+fn csr_map_and_apply_preserve_pattern() {
+    let mut csr = CsrMatrix::from(&DMatrix::from_row_slice(2, 2, &[1, 0, -2, 3]));
+    let mapped = csr.map(|v| v as f64 * 0.5);
+    assert_eq!(mapped.pattern(), csr.pattern());
+    assert_eq!(mapped.values(), &[0.5, -1.0, 1.5]);
+
+    csr.apply(|v| *v = 0);
+    assert_eq!(csr.nnz(), 3);
+    assert_eq!(csr.values(), &[0, 0, 0]);
+}
+
+#[test]
+fn reductions_example() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(2, 3, &[
+        1.0, 0.0, -2.0,
+        0.0, 3.0, 0.0,
+    ]);
+    let csr = CsrMatrix::from(&dense);
+    let csc = CscMatrix::from(&dense);
+
+    assert_eq!(csr.sum(), 2.0);
+    assert_eq!(csr.row_sum(), dense.row_sum());
+    assert_eq!(csc.row_sum_tr(), dense.row_sum_tr());
+    assert_eq!(csc.column_sum(), dense.column_sum());
+    assert_eq!(csr.norm_squared(), 14.0);
+    assert_eq!(csc.norm(), dense.norm());
+    assert_eq!(csr.amax(), 3.0);
+    assert_eq!(csc.camax(), 3.0);
+    assert_eq!(csr.diagonal(), DVector::from_column_slice(&[1.0, 3.0]));
+    assert_eq!(csc.diagonal(), dense_diagonal(&dense));
+
+    let empty = CsrMatrix::<f64>::zeros(3, 0);
+    assert_eq!(empty.sum(), 0.0);
+    assert_eq!(empty.amax(), 0.0);
+    assert_eq!(empty.column_sum(), DVector::zeros(3));
+    assert_eq!(empty.diagonal().len(), 0);
+}
+
+#[test]
+fn componentwise_dimension_mismatch_panics() {
+    let a = CsrMatrix::<f64>::identity(2);
+    let b = CsrMatrix::<f64>::zeros(2, 3);
+    assert_panics!(a.component_mul(&b));
+    assert_panics!(a.component_div(&b));
+    assert_panics!(a.inf(&b));
+    assert_panics!(a.sup(&CsrMatrix::zeros(3, 2)));
+
+    let a = CscMatrix::<f64>::identity(2);
+    let b = CscMatrix::<f64>::zeros(3, 2);
+    assert_panics!(a.component_mul(&b));
+    assert_panics!(a.sup(&b));
+}
+
+proptest! {
+    #[test]
+    fn csr_componentwise_agrees_with_dense((a, b) in csr_pair()) {
+        let (dense_a, dense_b) = (DMatrix::from(&a), DMatrix::from(&b));
+        prop_assert_eq!(DMatrix::from(&a.component_mul(&b)), dense_a.component_mul(&dense_b));
+        prop_assert_eq!(DMatrix::from(&a.inf(&b)), dense_a.inf(&dense_b));
+        prop_assert_eq!(DMatrix::from(&a.sup(&b)), dense_a.sup(&dense_b));
+        prop_assert_eq!(DMatrix::from(&a.map(|v| 2 * v)), dense_a.map(|v| 2 * v));
+    }
+
+    #[test]
+    fn csc_componentwise_agrees_with_csr((a, b) in csr_pair()) {
+        let (csc_a, csc_b) = (CscMatrix::from(&a), CscMatrix::from(&b));
+        prop_assert_eq!(csc_a.component_mul(&csc_b), CscMatrix::from(&a.component_mul(&b)));
+        prop_assert_eq!(csc_a.inf(&csc_b), CscMatrix::from(&a.inf(&b)));
+        prop_assert_eq!(csc_a.sup(&csc_b), CscMatrix::from(&a.sup(&b)));
+    }
+
+    #[test]
+    fn component_div_by_dense_agrees_with_dense(
+        (a, b) in csr_strategy().prop_flat_map(|a| {
+            let (nrows, ncols) = (a.nrows(), a.ncols());
+            let b = proptest::collection::vec(non_zero_i32_value_strategy(), nrows * ncols)
+                .prop_map(move |values| DMatrix::from_vec(nrows, ncols, values));
+            (Just(a), b)
+        })
+    ) {
+        let dense_a = DMatrix::from(&a).map(|v| v as f64);
+        let dense_b = b.map(|v| v as f64);
+        let (a, b) = (CsrMatrix::from(&dense_a), CsrMatrix::from(&dense_b));
+        prop_assert_eq!(DMatrix::from(&a.component_div(&b)), dense_a.component_div(&dense_b));
+        let (a, b) = (CscMatrix::from(&a), CscMatrix::from(&b));
+        prop_assert_eq!(DMatrix::from(&a.component_div(&b)), dense_a.component_div(&dense_b));
+    }
+
+    #[test]
+    fn csr_reductions_agree_with_dense(csr in csr_strategy()) {
+        let dense = DMatrix::from(&csr);
+        prop_assert_eq!(csr.sum(), dense.sum());
+        prop_assert_eq!(csr.row_sum(), dense.row_sum());
+        prop_assert_eq!(csr.row_sum_tr(), dense.row_sum_tr());
+        prop_assert_eq!(csr.column_sum(), dense.column_sum());
+        prop_assert_eq!(csr.amax(), dense.amax());
+        prop_assert_eq!(csr.diagonal(), dense_diagonal(&dense));
+
+        let dense = dense.map(|v| v as f64);
+        let csr = CsrMatrix::from(&dense);
+        prop_assert_eq!(csr.norm_squared(), dense.norm_squared());
+        prop_assert_eq!(csr.camax(), dense.camax());
+    }
+
+    #[test]
+    fn csc_reductions_agree_with_dense(csc in csc_strategy()) {
+        let dense = DMatrix::from(&csc);
+        prop_assert_eq!(csc.sum(), dense.sum());
+        prop_assert_eq!(csc.row_sum(), dense.row_sum());
+        prop_assert_eq!(csc.row_sum_tr(), dense.row_sum_tr());
+        prop_assert_eq!(csc.column_sum(), dense.column_sum());
+        prop_assert_eq!(csc.amax(), dense.amax());
+        prop_assert_eq!(csc.diagonal(), dense_diagonal(&dense));
+    }
+}
@@ -283,6 +283,59 @@ fn coo_clear_triplets_valid_entries() {
     );
 }
 
+#[test]
+fn coo_sum_duplicates_preserves_order() {
+    let mut coo = CooMatrix::new(3, 3);
+    coo.push(2, 1, 1);
+    coo.push(0, 0, 2);
+    coo.push(2, 1, 3);
+    coo.push(1, 2, 4);
+    coo.push(0, 0, 5);
+    coo.push(2, 1, 6);
+    let dense = DMatrix::from(&coo);
+
+    assert_eq!(coo.sum_duplicates(), 3);
+    assert_eq!(
+        coo.triplet_iter().collect::<Vec<_>>(),
+        vec![(2, 1, &10), (0, 0, &7), (1, 2, &4)]
+    );
+    assert_eq!(DMatrix::from(&coo), dense);
+    assert_eq!(coo.sum_duplicates(), 0);
+}
+
+#[test]
+fn coo_compress_sums_duplicates_and_sorts() {
+    let mut coo = CooMatrix::new(3, 3);
+    coo.push(2, 1, 1);
+    coo.push(0, 2, 2);
+    coo.push(2, 1, 3);
+    coo.push(0, 0, 4);
+    coo.push(2, 0, 5);
+    let dense = DMatrix::from(&coo);
+
+    assert_eq!(coo.compress(), 1);
+    assert_eq!(coo.row_indices(), &[0, 0, 2, 2]);
+    assert_eq!(coo.col_indices(), &[0, 2, 0, 1]);
+    assert_eq!(coo.values(), &[4, 2, 5, 4]);
+    assert_eq!(DMatrix::from(&coo), dense);
+
+    // The sorting permutation consists of a 3-cycle and a 2-cycle
+    let mut coo = CooMatrix::new(2, 3);
+    coo.push(1, 0, 1);
+    coo.push(0, 0, 2);
+    coo.push(0, 1, 3);
+    coo.push(1, 2, 4);
+    coo.push(1, 1, 5);
+    assert_eq!(coo.compress(), 0);
+    assert_eq!(coo.row_indices(), &[0, 0, 1, 1, 1]);
+    assert_eq!(coo.col_indices(), &[0, 1, 0, 1, 2]);
+    assert_eq!(coo.values(), &[2, 3, 1, 5, 4]);
+
+    let mut empty = CooMatrix::<f64>::new(2, 2);
+    assert_eq!(empty.compress(), 0);
+    assert_eq!(empty.nnz(), 0);
+}
+
 #[test]
 fn coo_push_out_of_bounds_entries() {
     {
@@ -1,5 +1,6 @@
-use nalgebra::DMatrix;
+use nalgebra::{DMatrix, DVector};
 use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::proptest::csc;
 use nalgebra_sparse::{SparseEntry, SparseEntryMut, SparseFormatErrorKind};
 
 use proptest::prelude::*;
@@ -8,10 +9,15 @@ use proptest::sample::subsequence;
 use super::test_data_examples::{InvalidCsDataExamples, ValidCsDataExamples};
 
 use crate::assert_panics;
-use crate::common::csc_strategy;
+use crate::common::{csc_strategy, PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM};
 
 use std::collections::HashSet;
 
+/// Generates a square matrix of dimension at most 4, to keep Kronecker products small.
+fn square_csc_strategy() -> impl Strategy<Value = CscMatrix<i32>> {
+    (0..=4usize).prop_flat_map(|n| csc(PROPTEST_I32_VALUE_STRATEGY, n, n, 16))
+}
+
 #[test]
 fn csc_matrix_valid_data() {
     // Construct matrix from valid data and check that selected methods return results
@@ -646,6 +652,38 @@ fn csc_matrix_col_iter() {
     }
 }
 
+#[test]
+fn csc_prune_removes_small_entries() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(2, 3, &[
+        1.0, 1e-10, 0.0,
+        -1e-3, 0.0, -2.0,
+    ]);
+    let mut csc = CscMatrix::from(&dense);
+    assert_eq!(csc.nnz(), 4);
+
+    assert_eq!(csc.prune(0.0), 0);
+    assert_eq!(csc.prune(1e-8), 1);
+    assert_eq!(csc.nnz(), 3);
+    assert_eq!(csc.prune(1e-3), 1);
+    assert_eq!(
+        DMatrix::from(&csc),
+        DMatrix::from_row_slice(2, 3, &[1.0, 0.0, 0.0, 0.0, 0.0, -2.0])
+    );
+
+    csc.values_mut()[0] = 0.0;
+    assert_eq!(csc.eliminate_zeros(), 1);
+    assert_eq!(csc.triplet_iter().collect::<Vec<_>>(), vec![(1, 2, &-2.0)]);
+}
+
+#[test]
+fn csc_kronecker_sum_panics_on_non_square_matrices() {
+    let square = CscMatrix::<i32>::identity(2);
+    let non_square = CscMatrix::<i32>::zeros(2, 3);
+    assert_panics!(non_square.kronecker_sum(&square));
+    assert_panics!(square.kronecker_sum(&non_square));
+}
+
 proptest! {
     #[test]
     fn csc_double_transpose_is_identity(csc in csc_strategy()) {
@@ -687,6 +725,26 @@ proptest! {
         prop_assert_eq!(filtered_triplets, triplet_subset);
     }
 
+    #[test]
+    fn csc_retain_agrees_with_filter(csc in csc_strategy()) {
+        let predicate = |i: usize, j: usize, v: &i32| i != j + 1 && *v != 1;
+        let filtered = csc.filter(predicate);
+        let mut retained = csc.clone();
+        let num_removed = retained.retain(predicate);
+        prop_assert_eq!(num_removed, csc.nnz() - filtered.nnz());
+        prop_assert_eq!(retained, filtered);
+    }
+
+    #[test]
+    fn csc_eliminate_zeros_agrees_with_dense(csc in csc_strategy()) {
+        let mut pruned = csc.clone();
+        let num_zeros = csc.values().iter().filter(|&&v| v == 0).count();
+        prop_assert_eq!(pruned.eliminate_zeros(), num_zeros);
+        prop_assert_eq!(pruned.nnz(), csc.nnz() - num_zeros);
+        prop_assert!(pruned.values().iter().all(|&v| v != 0));
+        prop_assert_eq!(DMatrix::from(&pruned), DMatrix::from(&csc));
+    }
+
     #[test]
     fn csc_lower_triangle_agrees_with_dense(csc in csc_strategy()) {
         let csc_lower_triangle = csc.lower_triangle();
@@ -720,4 +778,62 @@ proptest! {
         prop_assert_eq!(csc.nnz(), n);
         prop_assert_eq!(DMatrix::from(&csc), DMatrix::identity(n, n));
     }
+
+    #[test]
+    fn csc_kronecker_agrees_with_dense(a in csc_strategy(), b in csc_strategy()) {
+        let kron = a.kronecker(&b);
+        prop_assert_eq!(kron.nnz(), a.nnz() * b.nnz());
+        prop_assert_eq!(DMatrix::from(&kron), DMatrix::from(&a).kronecker(&DMatrix::from(&b)));
+    }
+
+    #[test]
+    fn csc_kronecker_sum_agrees_with_dense(
+        a in square_csc_strategy(),
+        b in square_csc_strategy(),
+    ) {
+        let (n, m) = (a.nrows(), b.nrows());
+        let expected = DMatrix::from(&a).kronecker(&DMatrix::identity(m, m))
+            + DMatrix::identity(n, n).kronecker(&DMatrix::from(&b));
+        let sum = a.kronecker_sum(&b);
+        prop_assert_eq!(DMatrix::from(&sum), expected);
+
+        // The pattern is the union of the patterns of the two Kronecker products
+        let lhs = a.kronecker(&CscMatrix::identity(m));
+        let rhs = CscMatrix::identity(n).kronecker(&b);
+        let union: HashSet<_> = lhs
+            .triplet_iter()
+            .chain(rhs.triplet_iter())
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        let sum_pattern: HashSet<_> = sum.triplet_iter().map(|(i, j, _)| (i, j)).collect();
+        prop_assert_eq!(sum_pattern, union);
+    }
+
+    #[test]
+    fn csc_from_diagonal(diagonal in proptest::collection::vec(PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM)) {
+        let diagonal = DVector::from_vec(diagonal);
+        let csc = CscMatrix::from_diagonal(&diagonal);
+        prop_assert_eq!(csc.nnz(), diagonal.len());
+        prop_assert_eq!(DMatrix::from(&csc), DMatrix::from_diagonal(&diagonal));
+    }
+
+    #[test]
+    fn csc_shift_diagonal_agrees_with_dense(
+        csc in csc_strategy(),
+        sigma in PROPTEST_I32_VALUE_STRATEGY,
+    ) {
+        let shifted = csc.shift_diagonal(sigma);
+        let diagonal_len = csc.nrows().min(csc.ncols());
+        let mut expected = DMatrix::from(&csc);
+        for i in 0..diagonal_len {
+            expected[(i, i)] += sigma;
+        }
+        prop_assert_eq!(DMatrix::from(&shifted), expected);
+
+        // Every diagonal entry is explicitly stored after the shift
+        let missing = (0..diagonal_len)
+            .filter(|&i| matches!(csc.get_entry(i, i), Some(SparseEntry::Zero)))
+            .count();
+        prop_assert_eq!(shifted.nnz(), csc.nnz() + missing);
+    }
 }
@@ -1,5 +1,6 @@
-use nalgebra::DMatrix;
+use nalgebra::{DMatrix, DVector};
 use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::proptest::csr;
 use nalgebra_sparse::{SparseEntry, SparseEntryMut, SparseFormatErrorKind};
 
 use proptest::prelude::*;
@@ -8,10 +9,15 @@ use proptest::sample::subsequence;
 use super::test_data_examples::{InvalidCsDataExamples, ValidCsDataExamples};
 
 use crate::assert_panics;
-use crate::common::csr_strategy;
+use crate::common::{csr_strategy, PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM};
 
 use std::collections::HashSet;
 
+/// Generates a square matrix of dimension at most 4, to keep Kronecker products small.
+fn square_csr_strategy() -> impl Strategy<Value = CsrMatrix<i32>> {
+    (0..=4usize).prop_flat_map(|n| csr(PROPTEST_I32_VALUE_STRATEGY, n, n, 16))
+}
+
 #[test]
 fn csr_matrix_valid_data() {
     // Construct matrix from valid data and check that selected methods return results
@@ -640,6 +646,38 @@ fn csr_matrix_row_iter() {
     }
 }
 
+#[test]
+fn csr_prune_removes_small_entries() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(2, 3, &[
+        1.0, 1e-10, 0.0,
+        -1e-3, 0.0, -2.0,
+    ]);
+    let mut csr = CsrMatrix::from(&dense);
+    assert_eq!(csr.nnz(), 4);
+
+    assert_eq!(csr.prune(0.0), 0);
+    assert_eq!(csr.prune(1e-8), 1);
+    assert_eq!(csr.nnz(), 3);
+    assert_eq!(csr.prune(1e-3), 1);
+    assert_eq!(
+        DMatrix::from(&csr),
+        DMatrix::from_row_slice(2, 3, &[1.0, 0.0, 0.0, 0.0, 0.0, -2.0])
+    );
+
+    csr.values_mut()[0] = 0.0;
+    assert_eq!(csr.eliminate_zeros(), 1);
+    assert_eq!(csr.triplet_iter().collect::<Vec<_>>(), vec![(1, 2, &-2.0)]);
+}
+
+#[test]
+fn csr_kronecker_sum_panics_on_non_square_matrices() {
+    let square = CsrMatrix::<i32>::identity(2);
+    let non_square = CsrMatrix::<i32>::zeros(2, 3);
+    assert_panics!(non_square.kronecker_sum(&square));
+    assert_panics!(square.kronecker_sum(&non_square));
+}
+
 proptest! {
     #[test]
     fn csr_double_transpose_is_identity(csr in csr_strategy()) {
@@ -681,6 +719,26 @@ proptest! {
         prop_assert_eq!(filtered_triplets, triplet_subset);
     }
 
+    #[test]
+    fn csr_retain_agrees_with_filter(csr in csr_strategy()) {
+        let predicate = |i: usize, j: usize, v: &i32| i != j + 1 && *v != 1;
+        let filtered = csr.filter(predicate);
+        let mut retained = csr.clone();
+        let num_removed = retained.retain(predicate);
+        prop_assert_eq!(num_removed, csr.nnz() - filtered.nnz());
+        prop_assert_eq!(retained, filtered);
+    }
+
+    #[test]
+    fn csr_eliminate_zeros_agrees_with_dense(csr in csr_strategy()) {
+        let mut pruned = csr.clone();
+        let num_zeros = csr.values().iter().filter(|&&v| v == 0).count();
+        prop_assert_eq!(pruned.eliminate_zeros(), num_zeros);
+        prop_assert_eq!(pruned.nnz(), csr.nnz() - num_zeros);
+        prop_assert!(pruned.values().iter().all(|&v| v != 0));
+        prop_assert_eq!(DMatrix::from(&pruned), DMatrix::from(&csr));
+    }
+
     #[test]
     fn csr_lower_triangle_agrees_with_dense(csr in csr_strategy()) {
         let csr_lower_triangle = csr.lower_triangle();
@@ -714,4 +772,62 @@ proptest! {
         prop_assert_eq!(csr.nnz(), n);
         prop_assert_eq!(DMatrix::from(&csr), DMatrix::identity(n, n));
     }
+
+    #[test]
+    fn csr_kronecker_agrees_with_dense(a in csr_strategy(), b in csr_strategy()) {
+        let kron = a.kronecker(&b);
+        prop_assert_eq!(kron.nnz(), a.nnz() * b.nnz());
+        prop_assert_eq!(DMatrix::from(&kron), DMatrix::from(&a).kronecker(&DMatrix::from(&b)));
+    }
+
+    #[test]
+    fn csr_kronecker_sum_agrees_with_dense(
+        a in square_csr_strategy(),
+        b in square_csr_strategy(),
+    ) {
+        let (n, m) = (a.nrows(), b.nrows());
+        let expected = DMatrix::from(&a).kronecker(&DMatrix::identity(m, m))
+            + DMatrix::identity(n, n).kronecker(&DMatrix::from(&b));
+        let sum = a.kronecker_sum(&b);
+        prop_assert_eq!(DMatrix::from(&sum), expected);
+
+        // The pattern is the union of the patterns of the two Kronecker products
+        let lhs = a.kronecker(&CsrMatrix::identity(m));
+        let rhs = CsrMatrix::identity(n).kronecker(&b);
+        let union: HashSet<_> = lhs
+            .triplet_iter()
+            .chain(rhs.triplet_iter())
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        let sum_pattern: HashSet<_> = sum.triplet_iter().map(|(i, j, _)| (i, j)).collect();
+        prop_assert_eq!(sum_pattern, union);
+    }
+
+    #[test]
+    fn csr_from_diagonal(diagonal in proptest::collection::vec(PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM)) {
+        let diagonal = DVector::from_vec(diagonal);
+        let csr = CsrMatrix::from_diagonal(&diagonal);
+        prop_assert_eq!(csr.nnz(), diagonal.len());
+        prop_assert_eq!(DMatrix::from(&csr), DMatrix::from_diagonal(&diagonal));
+    }
+
+    #[test]
+    fn csr_shift_diagonal_agrees_with_dense(
+        csr in csr_strategy(),
+        sigma in PROPTEST_I32_VALUE_STRATEGY,
+    ) {
+        let shifted = csr.shift_diagonal(sigma);
+        let diagonal_len = csr.nrows().min(csr.ncols());
+        let mut expected = DMatrix::from(&csr);
+        for i in 0..diagonal_len {
+            expected[(i, i)] += sigma;
+        }
+        prop_assert_eq!(DMatrix::from(&shifted), expected);
+
+        // Every diagonal entry is explicitly stored after the shift
+        let missing = (0..diagonal_len)
+            .filter(|&i| matches!(csr.get_entry(i, i), Some(SparseEntry::Zero)))
+            .count();
+        prop_assert_eq!(shifted.nnz(), csr.nnz() + missing);
+    }
 }
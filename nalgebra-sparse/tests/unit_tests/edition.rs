@@ -0,0 +1,278 @@
+use crate::assert_panics;
+use crate::common::{csc_strategy, csr_strategy};
+use nalgebra::DMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::edition::BlockBuilder;
+use nalgebra_sparse::pattern::SparsityPattern;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Generates lists of up to 8 (possibly repeated) indices smaller than `dim`.
+fn indices(dim: usize) -> impl Strategy<Value = Vec<usize>> {
+    let max_len = if dim > 0 { 8 } else { 0 };
+    vec(0..dim.max(1), 0..=max_len)
+}
+
+fn csr_with_indices() -> impl Strategy<Value = (CsrMatrix<i32>, Vec<usize>, Vec<usize>)> {
+    csr_strategy().prop_flat_map(|csr| {
+        let (nrows, ncols) = (csr.nrows(), csr.ncols());
+        (Just(csr), indices(nrows), indices(ncols))
+    })
+}
+
+fn csc_with_indices() -> impl Strategy<Value = (CscMatrix<i32>, Vec<usize>, Vec<usize>)> {
+    csc_strategy().prop_flat_map(|csc| {
+        let (nrows, ncols) = (csc.nrows(), csc.ncols());
+        (Just(csc), indices(nrows), indices(ncols))
+    })
+}
+
+/// Generates two matrices with the same number of rows.
+fn csr_pair_same_rows() -> impl Strategy<Value = (CsrMatrix<i32>, CsrMatrix<i32>)> {
+    (csr_strategy(), csr_strategy()).prop_map(|(a, b)| {
+        let rows: Vec<usize> = (0..a.nrows()).map(|i| i % b.nrows().max(1)).collect();
+        if b.nrows() == 0 {
+            (a.select_rows(0..0), b)
+        } else {
+            (a, b.select_rows(&rows))
+        }
+    })
+}
+
+#[test]
+fn csr_select_rows_and_columns() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(3, 4, &[
+        1, 0, 2, 0,
+        0, 3, 0, 4,
+        5, 0, 0, 6,
+    ]);
+    let csr = CsrMatrix::from(&dense);
+
+    let rows = csr.select_rows([2, 0, 2]);
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(3, 4, &[
+        5, 0, 0, 6,
+        1, 0, 2, 0,
+        5, 0, 0, 6,
+    ]);
+    assert_eq!(DMatrix::from(&rows), expected);
+
+    let cols = csr.select_columns(vec![3, 0]);
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(3, 2, &[
+        0, 1,
+        4, 0,
+        6, 5,
+    ]);
+    assert_eq!(DMatrix::from(&cols), expected);
+
+    let sub = csr.submatrix(1..3, 1..4);
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(2, 3, &[
+        3, 0, 4,
+        0, 0, 6,
+    ]);
+    assert_eq!(DMatrix::from(&sub), expected);
+
+    let empty = csr.submatrix(0..0, [1, 2]);
+    assert_eq!(empty.nrows(), 0);
+    assert_eq!(empty.ncols(), 2);
+}
+
+#[test]
+fn select_out_of_bounds_panics() {
+    let csr = CsrMatrix::<f64>::identity(3);
+    let csc = CscMatrix::<f64>::identity(3);
+    let pattern = csr.pattern().clone();
+    assert_panics!(csr.select_rows([3]));
+    assert_panics!(csr.select_columns([0, 4]));
+    assert_panics!(csr.submatrix([0], [3]));
+    assert_panics!(csc.select_rows([3]));
+    assert_panics!(csc.select_columns([3]));
+    assert_panics!(pattern.select_major_lanes([3]));
+    assert_panics!(pattern.select_minor_lanes([3]));
+}
+
+#[test]
+fn stacking_dimension_mismatch_panics() {
+    let a = CsrMatrix::<f64>::identity(2);
+    let b = CsrMatrix::<f64>::identity(3);
+    assert_panics!(CsrMatrix::hstack(&[&a, &b]));
+    assert_panics!(CsrMatrix::vstack(&[&a, &b]));
+
+    let a = CscMatrix::<f64>::identity(2);
+    let b = CscMatrix::<f64>::identity(3);
+    assert_panics!(CscMatrix::hstack(&[&a, &b]));
+    assert_panics!(CscMatrix::vstack(&[&a, &b]));
+
+    let mut builder = BlockBuilder::new(2, 2);
+    builder.set_block(0, 0, &a);
+    assert_panics!({
+        builder.clone().set_block(0, 1, &b);
+    });
+    assert_panics!({
+        builder.clone().set_block(1, 0, &b);
+    });
+    assert_panics!({
+        builder.clone().set_row_height(0, 3);
+    });
+    assert_panics!({
+        builder.clone().set_block(2, 0, &a);
+    });
+}
+
+#[test]
+fn stacking_no_matrices_gives_empty_matrix() {
+    let csr = CsrMatrix::<f64>::hstack(&[]);
+    assert_eq!((csr.nrows(), csr.ncols()), (0, 0));
+    let csc = CscMatrix::<f64>::vstack(&[]);
+    assert_eq!((csc.nrows(), csc.ncols()), (0, 0));
+    let pattern = SparsityPattern::stack_major(&[]);
+    assert_eq!((pattern.major_dim(), pattern.minor_dim()), (0, 0));
+    let csr = CsrMatrix::<f64>::block_diag(&[]);
+    assert_eq!((csr.nrows(), csr.ncols()), (0, 0));
+}
+
+#[test]
+fn block_builder_assembles_block_matrix() {
+    #[rustfmt::skip]
+    let a = DMatrix::from_row_slice(2, 2, &[
+        1, 2,
+        0, 3,
+    ]);
+    let b = DMatrix::from_row_slice(2, 1, &[4, 5]);
+    let c = DMatrix::from_row_slice(1, 2, &[0, 6]);
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(4, 4, &[
+        1, 2, 0, 4,
+        0, 3, 0, 5,
+        0, 0, 0, 0,
+        0, 6, 0, 0,
+    ]);
+
+    // Block row 1 and block column 1 have no blocks, and need explicit sizes
+    let (a_csr, b_csr, c_csr) = (
+        CsrMatrix::from(&a),
+        CsrMatrix::from(&b),
+        CsrMatrix::from(&c),
+    );
+    let csr = BlockBuilder::new(3, 3)
+        .set_block(0, 0, &a_csr)
+        .set_block(0, 2, &b_csr)
+        .set_block(2, 0, &c_csr)
+        .set_row_height(1, 1)
+        .set_col_width(1, 1)
+        .build();
+    assert_eq!(DMatrix::from(&csr), expected);
+
+    let (a_csc, b_csc, c_csc) = (
+        CscMatrix::from(&a),
+        CscMatrix::from(&b),
+        CscMatrix::from(&c),
+    );
+    let csc = BlockBuilder::new(3, 3)
+        .set_block(0, 0, &a_csc)
+        .set_block(0, 2, &b_csc)
+        .set_block(2, 0, &c_csc)
+        .set_row_height(1, 1)
+        .set_col_width(1, 1)
+        .build();
+    assert_eq!(DMatrix::from(&csc), expected);
+
+    let pattern = BlockBuilder::new(3, 3)
+        .set_block(0, 0, a_csr.pattern())
+        .set_block(0, 2, b_csr.pattern())
+        .set_block(2, 0, c_csr.pattern())
+        .set_row_height(1, 1)
+        .set_col_width(1, 1)
+        .build();
+    assert_eq!(&pattern, csr.pattern());
+}
+
+proptest! {
+    #[test]
+    fn csr_submatrix_agrees_with_dense((csr, rows, cols) in csr_with_indices()) {
+        let dense = DMatrix::from(&csr);
+        let expected_rows = dense.select_rows(&rows);
+        let expected_cols = dense.select_columns(&cols);
+        let expected_sub = expected_rows.select_columns(&cols);
+
+        prop_assert_eq!(DMatrix::from(&csr.select_rows(&rows)), expected_rows);
+        prop_assert_eq!(DMatrix::from(&csr.select_columns(&cols)), expected_cols);
+        prop_assert_eq!(DMatrix::from(&csr.submatrix(&rows, &cols)), expected_sub);
+    }
+
+    #[test]
+    fn csc_submatrix_agrees_with_dense((csc, rows, cols) in csc_with_indices()) {
+        let dense = DMatrix::from(&csc);
+        let expected_rows = dense.select_rows(&rows);
+        let expected_cols = dense.select_columns(&cols);
+        let expected_sub = expected_rows.select_columns(&cols);
+
+        prop_assert_eq!(DMatrix::from(&csc.select_rows(&rows)), expected_rows);
+        prop_assert_eq!(DMatrix::from(&csc.select_columns(&cols)), expected_cols);
+        prop_assert_eq!(DMatrix::from(&csc.submatrix(&rows, &cols)), expected_sub);
+    }
+
+    #[test]
+    fn pattern_selection_agrees_with_csr((csr, rows, cols) in csr_with_indices()) {
+        let pattern = csr.pattern();
+        let (selected_rows, selected_cols) = (csr.select_rows(&rows), csr.select_columns(&cols));
+        let submatrix = csr.submatrix(&rows, &cols);
+        prop_assert_eq!(&pattern.select_major_lanes(&rows), selected_rows.pattern());
+        prop_assert_eq!(&pattern.select_minor_lanes(&cols), selected_cols.pattern());
+        prop_assert_eq!(&pattern.subpattern(&rows, &cols), submatrix.pattern());
+    }
+
+    #[test]
+    fn hstack_agrees_with_dense((a, b) in csr_pair_same_rows()) {
+        let (dense_a, dense_b) = (DMatrix::from(&a), DMatrix::from(&b));
+        let mut expected = DMatrix::zeros(a.nrows(), a.ncols() + b.ncols());
+        expected.columns_mut(0, a.ncols()).copy_from(&dense_a);
+        expected.columns_mut(a.ncols(), b.ncols()).copy_from(&dense_b);
+
+        let csr = CsrMatrix::hstack(&[&a, &b]);
+        prop_assert_eq!(DMatrix::from(&csr), expected.clone());
+        let csc = CscMatrix::hstack(&[&CscMatrix::from(&a), &CscMatrix::from(&b)]);
+        prop_assert_eq!(DMatrix::from(&csc), expected.clone());
+        let pattern = SparsityPattern::stack_minor(&[a.pattern(), b.pattern()]);
+        prop_assert_eq!(&pattern, csr.pattern());
+
+        // Vertical stacking is horizontal stacking of the transposes
+        let (at, bt) = (a.transpose(), b.transpose());
+        let csr = CsrMatrix::vstack(&[&at, &bt]);
+        prop_assert_eq!(DMatrix::from(&csr), expected.transpose());
+        let csc = CscMatrix::vstack(&[&CscMatrix::from(&at), &CscMatrix::from(&bt)]);
+        prop_assert_eq!(DMatrix::from(&csc), expected.transpose());
+        let pattern = SparsityPattern::stack_major(&[at.pattern(), bt.pattern()]);
+        prop_assert_eq!(&pattern, csr.pattern());
+    }
+
+    #[test]
+    fn block_diag_agrees_with_dense(blocks in vec(csr_strategy(), 0..=4)) {
+        let nrows = blocks.iter().map(|b| b.nrows()).sum();
+        let ncols = blocks.iter().map(|b| b.ncols()).sum();
+        let mut expected = DMatrix::zeros(nrows, ncols);
+        let (mut i, mut j) = (0, 0);
+        for block in &blocks {
+            expected
+                .view_mut((i, j), (block.nrows(), block.ncols()))
+                .copy_from(&DMatrix::from(block));
+            i += block.nrows();
+            j += block.ncols();
+        }
+
+        let csr_blocks: Vec<_> = blocks.iter().collect();
+        let csr = CsrMatrix::block_diag(&csr_blocks);
+        prop_assert_eq!(csr.nnz(), blocks.iter().map(|b| b.nnz()).sum::<usize>());
+        prop_assert_eq!(DMatrix::from(&csr), expected.clone());
+
+        let csc_blocks: Vec<_> = blocks.iter().map(CscMatrix::from).collect();
+        let csc = CscMatrix::block_diag(&csc_blocks.iter().collect::<Vec<_>>());
+        prop_assert_eq!(csc.nnz(), csr.nnz());
+        prop_assert_eq!(DMatrix::from(&csc), expected);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::assert_panics;
+use crate::common::{value_strategy, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::eigen::{
+    lanczos, lanczos_generalized, lanczos_shift_invert, lobpcg, lobpcg_generalized, EigenOptions,
+    PartialEigen, Which,
+};
+use nalgebra_sparse::factorization::CholeskyError;
+use nalgebra_sparse::proptest::csr;
+use nalgebra_sparse::solvers::{Ic0, IdentityPreconditioner};
+
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+
+use proptest::prelude::*;
+use std::f64::consts::PI;
+
+fn options() -> EigenOptions<f64> {
+    EigenOptions {
+        max_iterations: 500,
+        relative_tolerance: 1e-10,
+        absolute_tolerance: 1e-10,
+    }
+}
+
+/// Returns a matrix of the form `X^T X + I`.
+fn spd_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    csr(
+        value_strategy::<f64>(),
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MAX_NNZ,
+    )
+    .prop_map(|x| x.transpose() * &x + CsrMatrix::identity(x.ncols()))
+}
+
+/// Returns a symmetric, possibly indefinite matrix of the form `X + X^T`.
+fn symmetric_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    PROPTEST_MATRIX_DIM
+        .prop_flat_map(|n| csr(value_strategy::<f64>(), n..=n, n..=n, PROPTEST_MAX_NNZ))
+        .prop_map(|x| &x + x.transpose())
+}
+
+/// The 1D Laplacian of dimension `n`, scaled by `scale`.
+fn laplacian_1d(n: usize, scale: f64) -> CsrMatrix<f64> {
+    let mut coo = CooMatrix::new(n, n);
+    for i in 0..n {
+        coo.push(i, i, 2.0 * scale);
+        if i > 0 {
+            coo.push(i, i - 1, -scale);
+            coo.push(i - 1, i, -scale);
+        }
+    }
+    CsrMatrix::from(&coo)
+}
+
+/// The `k`-th smallest eigenvalue of the (unscaled) 1D Laplacian of dimension `n`.
+fn laplacian_1d_eigenvalue(n: usize, k: usize) -> f64 {
+    2.0 - 2.0 * ((k + 1) as f64 * PI / (n + 1) as f64).cos()
+}
+
+/// A diagonal mass matrix with varying entries.
+fn mass_matrix(n: usize) -> CsrMatrix<f64> {
+    let mut coo = CooMatrix::new(n, n);
+    for i in 0..n {
+        coo.push(i, i, 1.0 + (i % 3) as f64);
+    }
+    CsrMatrix::from(&coo)
+}
+
+/// The eigenvalues of the symmetric-definite pencil `(K, M)` in ascending order.
+fn dense_eigenvalues(k: &CsrMatrix<f64>, m: Option<&CsrMatrix<f64>>) -> Vec<f64> {
+    if k.nrows() == 0 {
+        return Vec::new();
+    }
+    let k = DMatrix::from(k);
+    let c = match m {
+        Some(m) => {
+            let l_inv = DMatrix::from(m)
+                .cholesky()
+                .unwrap()
+                .l()
+                .try_inverse()
+                .unwrap();
+            &l_inv * k * l_inv.transpose()
+        }
+        None => k,
+    };
+    let mut eigenvalues: Vec<f64> = SymmetricEigen::new(c).eigenvalues.iter().copied().collect();
+    eigenvalues.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    eigenvalues
+}
+
+fn pseudo_random_block(n: usize, ncols: usize) -> DMatrix<f64> {
+    DMatrix::from_fn(n, ncols, |i, j| (((i + 1) * (j + 3)) as f64).sin())
+}
+
+/// Checks the residuals, the ordering and the orthonormality of the eigenvectors.
+fn check_eigen(
+    eigen: &PartialEigen<f64>,
+    k: &CsrMatrix<f64>,
+    m: Option<&CsrMatrix<f64>>,
+    tol: f64,
+) {
+    let x = eigen.eigenvectors();
+    let lambda = eigen.eigenvalues();
+    assert_eq!(x.ncols(), lambda.len());
+    assert_eq!(eigen.residual_norms().len(), lambda.len());
+    assert!(lambda.as_slice().windows(2).all(|w| w[0] <= w[1]));
+
+    let mx = match m {
+        Some(m) => m * x,
+        None => x.clone(),
+    };
+    let gram = x.transpose() * &mx;
+    assert!((gram - DMatrix::identity(x.ncols(), x.ncols())).amax() <= tol);
+
+    let r = k * x - mx * DMatrix::from_diagonal(lambda);
+    for (j, r_j) in r.column_iter().enumerate() {
+        assert!((r_j.norm() - eigen.residual_norms()[j]).abs() <= tol);
+        assert!(r_j.norm() <= tol * lambda[j].abs().max(1.0));
+    }
+}
+
+fn assert_eigenvalues_eq(computed: &DVector<f64>, expected: &[f64], tol: f64) {
+    assert_eq!(computed.len(), expected.len());
+    for (a, b) in computed.iter().zip(expected) {
+        assert!((a - b).abs() <= tol * b.abs().max(1.0), "{} != {}", a, b);
+    }
+}
+
+proptest! {
+    #[test]
+    fn lanczos_with_full_subspace_is_exact(
+        (a, nev) in symmetric_strategy().prop_flat_map(|a| {
+            let n = a.nrows();
+            (Just(a), 0..=n)
+        })
+    ) {
+        let n = a.nrows();
+        let expected = dense_eigenvalues(&a, None);
+
+        let eigen = lanczos(&a, nev, Which::Smallest, n + 1, &options());
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &a, None, 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[..nev], 1e-8);
+
+        let eigen = lanczos(&a, nev, Which::Largest, n + 1, &options());
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &a, None, 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[n - nev..], 1e-8);
+    }
+
+    #[test]
+    fn lanczos_generalized_with_full_subspace_is_exact(
+        (k, nev) in spd_strategy().prop_flat_map(|k| {
+            let n = k.nrows();
+            (Just(k), 0..=n)
+        })
+    ) {
+        let n = k.nrows();
+        let m = mass_matrix(n);
+        let expected = dense_eigenvalues(&k, Some(&m));
+
+        let eigen = lanczos_generalized(&k, &m, nev, Which::Smallest, n + 1, &options()).unwrap();
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &k, Some(&m), 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[..nev], 1e-8);
+
+        let eigen = lanczos_shift_invert(&k, Some(&m), 0.0, nev, n + 1, &options()).unwrap();
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &k, Some(&m), 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[..nev], 1e-8);
+    }
+
+    #[test]
+    fn lobpcg_finds_extreme_eigenvalues(
+        (k, nev) in spd_strategy().prop_flat_map(|k| {
+            let n = k.nrows();
+            (Just(k), 0..=n)
+        })
+    ) {
+        let n = k.nrows();
+        let x0 = pseudo_random_block(n, nev);
+        let m = mass_matrix(n);
+
+        // LOBPCG converges in a single iteration if the search space spans the whole space,
+        // and otherwise within a modest number of iterations for these small problems
+        let expected = dense_eigenvalues(&k, None);
+        let eigen = lobpcg(&k, &x0, &IdentityPreconditioner, Which::Smallest, &options());
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &k, None, 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[..nev], 1e-8);
+
+        let expected = dense_eigenvalues(&k, Some(&m));
+        let eigen =
+            lobpcg_generalized(&k, &m, &x0, &IdentityPreconditioner, Which::Largest, &options());
+        prop_assert!(eigen.converged());
+        check_eigen(&eigen, &k, Some(&m), 1e-8);
+        assert_eigenvalues_eq(eigen.eigenvalues(), &expected[n - nev..], 1e-8);
+    }
+}
+
+#[test]
+fn lanczos_restarts_on_1d_laplacian() {
+    let n = 100;
+    let a = laplacian_1d(n, 1.0);
+    let nev = 4;
+
+    let eigen = lanczos(&a, nev, Which::Largest, 12, &options());
+    assert!(eigen.converged());
+    assert!(eigen.iterations() > 1);
+    check_eigen(&eigen, &a, None, 1e-8);
+    let expected: Vec<_> = (n - nev..n)
+        .map(|k| laplacian_1d_eigenvalue(n, k))
+        .collect();
+    assert_eigenvalues_eq(eigen.eigenvalues(), &expected, 1e-8);
+}
+
+#[test]
+fn shift_invert_lanczos_finds_lowest_modes() {
+    let n = 200;
+    let k = laplacian_1d(n, 1.0);
+    let nev = 5;
+    let expected: Vec<_> = (0..nev).map(|i| laplacian_1d_eigenvalue(n, i)).collect();
+
+    let eigen = lanczos_shift_invert(&k, None, 0.0, nev, 12, &options()).unwrap();
+    assert!(eigen.converged());
+    check_eigen(&eigen, &k, None, 1e-8);
+    assert_eigenvalues_eq(eigen.eigenvalues(), &expected, 1e-8);
+
+    // The lowest eigenvalues of the Laplacian are poorly separated relative to the width of
+    // the spectrum, so that plain Lanczos needs considerably more restarts
+    let plain = lanczos(&k, nev, Which::Smallest, 12, &options());
+    assert!(plain.iterations() > eigen.iterations());
+
+    // With a mass matrix, K x = λ M x with M = 2 I has the eigenvalues λ / 2
+    let m = CsrMatrix::identity(n) * 2.0;
+    let eigen = lanczos_shift_invert(&k, Some(&m), -1.0, nev, 12, &options()).unwrap();
+    assert!(eigen.converged());
+    check_eigen(&eigen, &k, Some(&m), 1e-8);
+    let halved: Vec<_> = expected.iter().map(|lambda| lambda / 2.0).collect();
+    assert_eigenvalues_eq(eigen.eigenvalues(), &halved, 1e-8);
+}
+
+#[test]
+fn lanczos_generalized_matches_dense_solution() {
+    let n = 60;
+    let k = laplacian_1d(n, 1.0);
+    let m = mass_matrix(n);
+    let expected = dense_eigenvalues(&k, Some(&m));
+
+    let eigen = lanczos_generalized(&k, &m, 3, Which::Largest, 20, &options()).unwrap();
+    assert!(eigen.converged());
+    check_eigen(&eigen, &k, Some(&m), 1e-8);
+    assert_eigenvalues_eq(eigen.eigenvalues(), &expected[n - 3..], 1e-8);
+}
+
+#[test]
+fn lobpcg_handles_multiple_eigenvalues() {
+    // The 2D Laplacian on a square grid has eigenvalues μ_i + μ_j, where μ_k are the
+    // eigenvalues of the 1D Laplacian. Hence, all eigenvalues with i != j are double.
+    let grid = 12;
+    let l1 = laplacian_1d(grid, 1.0);
+    let id = CsrMatrix::<f64>::identity(grid);
+    let kron = |a: &CsrMatrix<f64>, b: &CsrMatrix<f64>| {
+        let mut coo = CooMatrix::new(a.nrows() * b.nrows(), a.ncols() * b.ncols());
+        for (i, j, &a_ij) in a.triplet_iter() {
+            for (k, l, &b_kl) in b.triplet_iter() {
+                coo.push(i * b.nrows() + k, j * b.ncols() + l, a_ij * b_kl);
+            }
+        }
+        CsrMatrix::from(&coo)
+    };
+    let a = kron(&l1, &id) + kron(&id, &l1);
+    let n = a.nrows();
+
+    let mu = |k| laplacian_1d_eigenvalue(grid, k);
+    let expected = [mu(0) + mu(0), mu(0) + mu(1), mu(1) + mu(0), mu(1) + mu(1)];
+
+    let x0 = pseudo_random_block(n, 4);
+    let preconditioner = Ic0::from_csr(&a).unwrap();
+    let eigen = lobpcg(&a, &x0, &preconditioner, Which::Smallest, &options());
+    assert!(eigen.converged());
+    check_eigen(&eigen, &a, None, 1e-8);
+    assert_eigenvalues_eq(eigen.eigenvalues(), &expected, 1e-8);
+
+    // The preconditioner should reduce the number of iterations
+    let unpreconditioned = lobpcg(
+        &a,
+        &x0,
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options(),
+    );
+    assert!(unpreconditioned.converged());
+    assert!(eigen.iterations() < unpreconditioned.iterations());
+}
+
+#[test]
+fn eigensolvers_report_non_convergence() {
+    let n = 100;
+    let a = laplacian_1d(n, 1.0);
+    let options = EigenOptions {
+        max_iterations: 1,
+        ..options()
+    };
+
+    let eigen = lanczos(&a, 2, Which::Smallest, 5, &options);
+    assert!(!eigen.converged());
+    assert_eq!(eigen.iterations(), 1);
+
+    let eigen = lobpcg(
+        &a,
+        &pseudo_random_block(n, 2),
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options,
+    );
+    assert!(!eigen.converged());
+    assert_eq!(eigen.iterations(), 1);
+
+    let (eigenvalues, eigenvectors) = eigen.into_parts();
+    assert_eq!(eigenvalues.len(), 2);
+    assert_eq!(eigenvectors.shape(), (n, 2));
+}
+
+#[test]
+fn eigensolvers_do_not_panic_on_nan() {
+    let n = 10;
+    let mut a = laplacian_1d(n, 1.0);
+    a.values_mut()[0] = f64::NAN;
+    let options = EigenOptions {
+        max_iterations: 2,
+        ..options()
+    };
+
+    for which in [Which::Smallest, Which::Largest] {
+        let eigen = lanczos(&a, 2, which, 5, &options);
+        assert!(!eigen.converged());
+    }
+}
+
+#[test]
+fn shift_invert_lanczos_rejects_shift_above_smallest_eigenvalue() {
+    let k = laplacian_1d(10, 1.0);
+    let result = lanczos_shift_invert(&k, None, 1.0, 2, 5, &options());
+    assert_eq!(result.unwrap_err(), CholeskyError::NotPositiveDefinite);
+
+    let m = laplacian_1d(10, -1.0);
+    let result = lanczos_generalized(&k, &m, 2, Which::Smallest, 5, &options());
+    assert_eq!(result.unwrap_err(), CholeskyError::NotPositiveDefinite);
+}
+
+#[test]
+fn eigensolvers_panic_on_invalid_input() {
+    let a = laplacian_1d(5, 1.0);
+    let rectangular = CsrMatrix::<f64>::zeros(5, 4);
+    let m4 = CsrMatrix::<f64>::identity(4);
+
+    assert_panics!(lanczos(&rectangular, 1, Which::Smallest, 3, &options()));
+    assert_panics!(lanczos(&a, 6, Which::Smallest, 10, &options()));
+    assert_panics!(lanczos(&a, 2, Which::Smallest, 2, &options()));
+    assert_panics!(lanczos_generalized(
+        &a,
+        &m4,
+        1,
+        Which::Smallest,
+        3,
+        &options()
+    ));
+    assert_panics!(lanczos_shift_invert(&a, Some(&m4), 0.0, 1, 3, &options()));
+
+    let x0 = pseudo_random_block(5, 2);
+    assert_panics!(lobpcg(
+        &rectangular,
+        &x0,
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options()
+    ));
+    assert_panics!(lobpcg(
+        &a,
+        &pseudo_random_block(4, 2),
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options()
+    ));
+    assert_panics!(lobpcg_generalized(
+        &a,
+        &m4,
+        &x0,
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options()
+    ));
+
+    // Linearly dependent initial block
+    let dependent = DMatrix::from_fn(5, 2, |i, _| i as f64 + 1.0);
+    assert_panics!(lobpcg(
+        &a,
+        &dependent,
+        &IdentityPreconditioner,
+        Which::Smallest,
+        &options()
+    ));
+}
@@ -0,0 +1,445 @@
+use crate::common::{csr_strategy, value_strategy, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ};
+use nalgebra::proptest::matrix;
+use nalgebra::{DMatrix, DVector, Matrix2};
+use nalgebra_sparse::bsr::BsrMatrix;
+use nalgebra_sparse::convert::serial::{
+    convert_bsr_coo, convert_bsr_csr, convert_coo_bsr, convert_coo_sell, convert_csr_bsr,
+    convert_csr_sell, convert_sell_coo, convert_sell_csr,
+};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::dia::DiaMatrix;
+use nalgebra_sparse::ell::EllMatrix;
+use nalgebra_sparse::ops::serial::{
+    spmm_bsr_dense, spmm_dia_dense, spmm_ell_dense, spmm_sell_dense,
+};
+use nalgebra_sparse::ops::Op;
+use nalgebra_sparse::proptest::{coo_with_duplicates, csr};
+use nalgebra_sparse::SparseFormatErrorKind;
+
+use proptest::prelude::*;
+
+use matrixcompare::{assert_matrix_eq, prop_assert_matrix_eq};
+
+/// CSR matrices whose dimensions are multiples of 2, for conversion to BSR with 2x2 blocks.
+fn csr_block_strategy() -> impl Strategy<Value = CsrMatrix<i32>> {
+    (0..=3usize, 0..=3usize).prop_flat_map(|(block_rows, block_cols)| {
+        csr(
+            value_strategy::<i32>(),
+            2 * block_rows,
+            2 * block_cols,
+            PROPTEST_MAX_NNZ,
+        )
+    })
+}
+
+/// Returns `(C, beta, alpha, op(A), op(B))` with compatible dimensions for the expression
+/// `C <- beta * C + alpha * op(A) * op(B)`, where `A` is drawn from the given strategy.
+#[allow(clippy::type_complexity)]
+fn spmm_dense_args_strategy(
+    a_strategy: impl Strategy<Value = CsrMatrix<i32>>,
+) -> impl Strategy<Value = (DMatrix<i32>, i32, i32, Op<CsrMatrix<i32>>, Op<DMatrix<i32>>)> {
+    (
+        a_strategy,
+        any::<bool>(),
+        any::<bool>(),
+        PROPTEST_MATRIX_DIM,
+    )
+        .prop_flat_map(|(a, trans_a, trans_b, n)| {
+            let (m, k) = if trans_a {
+                (a.ncols(), a.nrows())
+            } else {
+                (a.nrows(), a.ncols())
+            };
+            let b_shape = if trans_b { (n, k) } else { (k, n) };
+            let a = if trans_a {
+                Op::Transpose(a)
+            } else {
+                Op::NoOp(a)
+            };
+            let b = matrix(value_strategy::<i32>(), b_shape.0, b_shape.1).prop_map(move |b| {
+                if trans_b {
+                    Op::Transpose(b)
+                } else {
+                    Op::NoOp(b)
+                }
+            });
+            (
+                matrix(value_strategy::<i32>(), m, n),
+                value_strategy::<i32>(),
+                value_strategy::<i32>(),
+                Just(a),
+                b,
+            )
+        })
+}
+
+fn dense_spmm_result(
+    c: &DMatrix<i32>,
+    beta: i32,
+    alpha: i32,
+    a: &Op<CsrMatrix<i32>>,
+    b: &Op<DMatrix<i32>>,
+) -> DMatrix<i32> {
+    let a = match a {
+        Op::NoOp(a) => DMatrix::from(a),
+        Op::Transpose(a) => DMatrix::from(a).transpose(),
+    };
+    let b = match b {
+        Op::NoOp(b) => b.clone(),
+        Op::Transpose(b) => b.transpose(),
+    };
+    c * beta + a * b * alpha
+}
+
+#[test]
+fn bsr_matrix_valid_data() {
+    let blocks = vec![
+        Matrix2::new(1, 2, 3, 4),
+        Matrix2::new(5, 0, 0, 6),
+        Matrix2::new(7, 8, 0, 9),
+    ];
+    let bsr = BsrMatrix::try_from_bsr_data(2, 3, vec![0, 2, 3], vec![0, 2, 1], blocks).unwrap();
+
+    assert_eq!(bsr.nrows(), 4);
+    assert_eq!(bsr.ncols(), 6);
+    assert_eq!(bsr.block_rows(), 2);
+    assert_eq!(bsr.block_cols(), 3);
+    assert_eq!(bsr.block_size(), 2);
+    assert_eq!(bsr.nnz_blocks(), 3);
+    assert_eq!(bsr.nnz(), 12);
+    assert_eq!(bsr.get_block(0, 2), Some(&Matrix2::new(5, 0, 0, 6)));
+    assert_eq!(bsr.get_block(1, 0), None);
+    assert_eq!(bsr.get_block(2, 0), None);
+
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(4, 6, &[
+        1, 2, 0, 0, 5, 0,
+        3, 4, 0, 0, 0, 6,
+        0, 0, 7, 8, 0, 0,
+        0, 0, 0, 9, 0, 0,
+    ]);
+    assert_matrix_eq!(bsr, expected);
+    assert_matrix_eq!(bsr.transpose(), expected.transpose());
+
+    let zeros = BsrMatrix::<i32, 3>::zeros(2, 1);
+    assert_eq!(zeros.nrows(), 6);
+    assert_eq!(zeros.ncols(), 3);
+    assert_eq!(zeros.nnz(), 0);
+}
+
+#[test]
+fn bsr_matrix_invalid_data() {
+    let block = Matrix2::new(1, 2, 3, 4);
+
+    // Wrong number of block row offsets
+    let result = BsrMatrix::try_from_bsr_data(2, 2, vec![0, 1], vec![0], vec![block]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+
+    // Block column index out of bounds
+    let result = BsrMatrix::try_from_bsr_data(1, 2, vec![0, 1], vec![2], vec![block]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+
+    // Duplicate blocks
+    let result = BsrMatrix::try_from_bsr_data(1, 2, vec![0, 2], vec![1, 1], vec![block; 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::DuplicateEntry
+    );
+
+    // Number of blocks does not match the pattern
+    let result = BsrMatrix::try_from_bsr_data(1, 2, vec![0, 1], vec![1], vec![block; 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn dia_matrix_valid_data() {
+    let dia = DiaMatrix::try_from_diagonals(3, 2, vec![-2, 0, 1], vec![1, 2, 3, 4]).unwrap();
+
+    assert_eq!(dia.nrows(), 3);
+    assert_eq!(dia.ncols(), 2);
+    assert_eq!(dia.nnz(), 4);
+    assert_eq!(dia.num_diagonals(), 3);
+    assert_eq!(dia.get_diagonal(0), Some(&[2, 3][..]));
+    assert_eq!(dia.get_diagonal(-1), None);
+    assert_eq!(dia.diagonal(0), (-2, &[1][..]));
+
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(3, 2, &[
+        2, 4,
+        0, 3,
+        1, 0,
+    ]);
+    assert_matrix_eq!(dia, expected);
+    assert_matrix_eq!(dia.transpose(), expected.transpose());
+    assert_eq!(dia.transpose().diagonal_offsets(), &[-1, 0, 2]);
+}
+
+#[test]
+fn dia_matrix_invalid_data() {
+    // Diagonal out of bounds
+    let result = DiaMatrix::try_from_diagonals(3, 2, vec![2], vec![0; 0]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+    let result = DiaMatrix::try_from_diagonals(3, 2, vec![-3], vec![0; 0]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+
+    // Duplicate diagonals
+    let result = DiaMatrix::try_from_diagonals(2, 2, vec![0, 0], vec![0; 4]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::DuplicateEntry
+    );
+
+    // Unsorted diagonals
+    let result = DiaMatrix::try_from_diagonals(2, 2, vec![1, 0], vec![0; 3]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+
+    // Wrong number of values
+    let result = DiaMatrix::try_from_diagonals(2, 2, vec![0, 1], vec![0; 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn ell_matrix_valid_data() {
+    // Rows: [(0, 1), (2, 2)], [], [(1, 3)], padded entries are arbitrary
+    let ell = EllMatrix::try_from_ell_data(
+        3,
+        3,
+        2,
+        vec![2, 0, 1],
+        vec![0, 9, 1, 2, 9, 9],
+        vec![1, 9, 3, 2, 9, 9],
+    )
+    .unwrap();
+
+    assert_eq!(ell.width(), 2);
+    assert_eq!(ell.nnz(), 3);
+
+    #[rustfmt::skip]
+    let expected = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 2,
+        0, 0, 0,
+        0, 3, 0,
+    ]);
+    assert_matrix_eq!(ell, expected);
+    assert_eq!(CsrMatrix::from(&ell), CsrMatrix::from(&expected));
+}
+
+#[test]
+fn ell_matrix_invalid_data() {
+    // Row longer than the width
+    let result = EllMatrix::try_from_ell_data(1, 3, 1, vec![2], vec![0], vec![1]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+
+    // Wrong array lengths
+    let result = EllMatrix::try_from_ell_data(2, 3, 1, vec![1, 1], vec![0], vec![1]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+
+    // Column index out of bounds
+    let result = EllMatrix::try_from_ell_data(1, 3, 1, vec![1], vec![3], vec![1]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+
+    // Duplicate and unsorted column indices
+    let result = EllMatrix::try_from_ell_data(1, 3, 2, vec![2], vec![1, 1], vec![1, 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::DuplicateEntry
+    );
+    let result = EllMatrix::try_from_ell_data(1, 3, 2, vec![2], vec![2, 1], vec![1, 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn sell_matrix_sorts_rows_within_windows() {
+    // Row i has i + 1 entries
+    let mut coo = CooMatrix::new(5, 5);
+    for i in 0..5 {
+        for j in 0..=i {
+            coo.push(i, j, 1);
+        }
+    }
+
+    let sell = convert_coo_sell(&coo, 2, 3);
+    assert_eq!(sell.row_permutation(), &[2, 1, 0, 4, 3]);
+    assert_eq!(sell.row_lengths(), &[3, 2, 1, 5, 4]);
+    assert_eq!(sell.num_slices(), 3);
+    // Slice widths 3, 5 and 4 for slice heights 2, 2 and 1
+    assert_eq!(sell.slice_offsets(), &[0, 6, 16, 20]);
+    assert_eq!(sell.nnz(), 15);
+    assert_matrix_eq!(sell, DMatrix::from(&coo));
+
+    // Without sorting, the row order is preserved
+    let sell = convert_coo_sell(&coo, 2, 1);
+    assert_eq!(sell.row_permutation(), &[0, 1, 2, 3, 4]);
+    assert_matrix_eq!(sell, DMatrix::from(&coo));
+}
+
+#[test]
+fn format_std_ops_matrix_vector_product() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(3, 3, &[
+        2, -1, 0,
+        -1, 2, -1,
+        0, -1, 2,
+    ]);
+    let csr = CsrMatrix::from(&dense);
+    let x = DVector::from_column_slice(&[1, 2, 3]);
+    let expected = &dense * &x;
+
+    assert_eq!(&DiaMatrix::from(&csr) * &x, expected);
+    assert_eq!(&EllMatrix::from(&csr) * &x, expected);
+    assert_eq!(&convert_csr_sell(&csr, 2, 2) * &x, expected);
+}
+
+proptest! {
+    #[test]
+    fn csr_bsr_roundtrip(csr in csr_block_strategy()) {
+        let bsr: BsrMatrix<i32, 2> = convert_csr_bsr(&csr);
+        prop_assert_matrix_eq!(bsr, csr);
+        prop_assert_eq!(bsr.nnz(), 4 * bsr.nnz_blocks());
+        prop_assert!(bsr.nnz() >= csr.nnz());
+
+        let roundtrip = convert_bsr_csr(&bsr);
+        prop_assert_matrix_eq!(roundtrip, csr);
+        prop_assert_eq!(&convert_csr_bsr::<_, 2>(&roundtrip), &bsr);
+        prop_assert_eq!(CsrMatrix::from(&bsr), roundtrip);
+
+        prop_assert_matrix_eq!(bsr.transpose(), csr.transpose());
+    }
+
+    #[test]
+    fn coo_bsr_roundtrip(csr in csr_block_strategy()) {
+        let coo = CooMatrix::from(&csr);
+        let bsr: BsrMatrix<i32, 2> = convert_coo_bsr(&coo);
+        prop_assert_matrix_eq!(bsr, csr);
+        prop_assert_matrix_eq!(convert_bsr_coo(&bsr), csr);
+        prop_assert_matrix_eq!(CooMatrix::from(&bsr), csr);
+    }
+
+    #[test]
+    fn csr_dia_roundtrip(csr in csr_strategy()) {
+        let dia = DiaMatrix::from(&csr);
+        prop_assert_matrix_eq!(dia, csr);
+        prop_assert!(dia.nnz() >= csr.nnz());
+
+        let roundtrip = CsrMatrix::from(&dia);
+        prop_assert_matrix_eq!(roundtrip, csr);
+        prop_assert_eq!(DiaMatrix::from(&roundtrip), dia.clone());
+
+        prop_assert_matrix_eq!(dia.transpose(), csr.transpose());
+    }
+
+    #[test]
+    fn coo_dia_roundtrip(coo in coo_with_duplicates(value_strategy::<i32>(), PROPTEST_MATRIX_DIM, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ, 2)) {
+        let dia = DiaMatrix::from(&coo);
+        prop_assert_matrix_eq!(dia, DMatrix::from(&coo));
+        prop_assert_matrix_eq!(CooMatrix::from(&dia), DMatrix::from(&coo));
+    }
+
+    #[test]
+    fn csr_ell_roundtrip(csr in csr_strategy()) {
+        let ell = EllMatrix::from(&csr);
+        prop_assert_matrix_eq!(ell, csr);
+        prop_assert_eq!(ell.nnz(), csr.nnz());
+        prop_assert_eq!(ell.values().len(), ell.nrows() * ell.width());
+        prop_assert_eq!(CsrMatrix::from(&ell), csr.clone());
+        prop_assert_matrix_eq!(CooMatrix::from(&ell), csr);
+        prop_assert_matrix_eq!(EllMatrix::from(&CooMatrix::from(&csr)), csr);
+    }
+
+    #[test]
+    fn csr_sell_roundtrip(csr in csr_strategy(), slice_height in 1..=4usize, sigma in 1..=8usize) {
+        let sell = convert_csr_sell(&csr, slice_height, sigma);
+        prop_assert_matrix_eq!(sell, csr);
+        prop_assert_eq!(sell.nnz(), csr.nnz());
+        prop_assert_eq!(convert_sell_csr(&sell), csr.clone());
+        prop_assert_matrix_eq!(convert_sell_coo(&sell), csr);
+
+        // Rows are only permuted within windows, and sorted by decreasing length in each window
+        for (window_index, window) in sell.row_permutation().chunks(sigma).enumerate() {
+            let first_row = window_index * sigma;
+            prop_assert!(window.iter().all(|&i| i >= first_row && i < first_row + window.len()));
+            let lengths: Vec<_> = window.iter().map(|&i| csr.row(i).nnz()).collect();
+            prop_assert!(lengths.windows(2).all(|w| w[0] >= w[1]));
+        }
+    }
+
+    #[test]
+    fn spmm_bsr_dense_agrees_with_dense_result(
+        (c, beta, alpha, a, b) in spmm_dense_args_strategy(csr_block_strategy())
+    ) {
+        let expected = dense_spmm_result(&c, beta, alpha, &a, &b);
+        let a = a.as_ref().map_same_op(convert_csr_bsr::<_, 2>);
+        let mut result = c;
+        spmm_bsr_dense(beta, &mut result, alpha, a.as_ref(), b.as_ref());
+        prop_assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn spmm_dia_dense_agrees_with_dense_result(
+        (c, beta, alpha, a, b) in spmm_dense_args_strategy(csr_strategy())
+    ) {
+        let expected = dense_spmm_result(&c, beta, alpha, &a, &b);
+        let a = a.as_ref().map_same_op(DiaMatrix::from);
+        let mut result = c;
+        spmm_dia_dense(beta, &mut result, alpha, a.as_ref(), b.as_ref());
+        prop_assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn spmm_ell_dense_agrees_with_dense_result(
+        (c, beta, alpha, a, b) in spmm_dense_args_strategy(csr_strategy())
+    ) {
+        let expected = dense_spmm_result(&c, beta, alpha, &a, &b);
+        let a = a.as_ref().map_same_op(EllMatrix::from);
+        let mut result = c;
+        spmm_ell_dense(beta, &mut result, alpha, a.as_ref(), b.as_ref());
+        prop_assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn spmm_sell_dense_agrees_with_dense_result(
+        (c, beta, alpha, a, b) in spmm_dense_args_strategy(csr_strategy()),
+        slice_height in 1..=4usize,
+        sigma in 1..=8usize,
+    ) {
+        let expected = dense_spmm_result(&c, beta, alpha, &a, &b);
+        let a = a.as_ref().map_same_op(|a| convert_csr_sell(a, slice_height, sigma));
+        let mut result = c;
+        spmm_sell_dense(beta, &mut result, alpha, a.as_ref(), b.as_ref());
+        prop_assert_eq!(result, expected);
+    }
+}
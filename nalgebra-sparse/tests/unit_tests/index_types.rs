@@ -0,0 +1,256 @@
+use crate::common::{csc_strategy, csr_strategy, PROPTEST_I32_VALUE_STRATEGY};
+use nalgebra::DMatrix;
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::serial::{
+    spadd_csr_prealloc, spmm_csc_dense, spmm_csr_dense, spmm_csr_prealloc,
+};
+use nalgebra_sparse::ops::Op;
+use nalgebra_sparse::pattern::{SparseIndex, SparsityPattern, SparsityPatternFormatError};
+use nalgebra_sparse::SparseFormatErrorKind;
+
+use proptest::prelude::*;
+
+fn to_usize<I: SparseIndex>(indices: &[I]) -> Vec<usize> {
+    indices.iter().map(|i| i.index()).collect()
+}
+
+#[test]
+fn sparse_index_conversions() {
+    assert_eq!(5u32.index(), 5);
+    assert_eq!((-1i32).try_index(), None);
+    assert_eq!(7i64.try_index(), Some(7));
+    assert_eq!(i32::try_from_index(1 << 31), None);
+    assert_eq!(u32::try_from_index(1 << 31), Some(1 << 31));
+    assert_eq!(<usize as SparseIndex>::from_index(3), 3);
+}
+
+#[test]
+fn sparsity_pattern_generic_valid_data() {
+    let offsets: Vec<i32> = vec![0, 2, 2, 5];
+    let indices: Vec<i32> = vec![0, 5, 1, 2, 3];
+    let pattern = SparsityPattern::try_from_offsets_and_indices_generic(
+        3,
+        6,
+        offsets.clone(),
+        indices.clone(),
+    )
+    .unwrap();
+
+    assert_eq!(pattern.major_dim(), 3);
+    assert_eq!(pattern.minor_dim(), 6);
+    assert_eq!(pattern.nnz(), 5);
+    assert_eq!(pattern.major_offsets(), offsets.as_slice());
+    assert_eq!(pattern.minor_indices(), indices.as_slice());
+    assert_eq!(pattern.lane(2), &[1, 2, 3]);
+
+    let entries: Vec<_> = pattern.entries().collect();
+    assert_eq!(entries, vec![(0, 0), (0, 5), (2, 1), (2, 2), (2, 3)]);
+
+    let expected_transpose = pattern
+        .clone()
+        .try_cast_indices::<usize>()
+        .unwrap()
+        .transpose();
+    let transpose = pattern.transpose();
+    assert_eq!(
+        transpose.try_cast_indices::<usize>().unwrap(),
+        expected_transpose
+    );
+}
+
+#[test]
+fn sparsity_pattern_generic_invalid_data() {
+    use SparsityPatternFormatError::*;
+    let try_pattern = |major_dim, minor_dim, offsets: Vec<i32>, indices: Vec<i32>| {
+        SparsityPattern::try_from_offsets_and_indices_generic(
+            major_dim, minor_dim, offsets, indices,
+        )
+    };
+
+    assert_eq!(
+        try_pattern(2, 2, vec![0, 1, 2], vec![0, -1]),
+        Err(MinorIndexOutOfBounds)
+    );
+    assert_eq!(
+        try_pattern(2, 2, vec![0, -1, 2], vec![0, 1]),
+        Err(NonmonotonicOffsets)
+    );
+    assert_eq!(
+        try_pattern(2, 2, vec![0, 3, 2], vec![0, 1]),
+        Err(NonmonotonicOffsets)
+    );
+    assert_eq!(
+        try_pattern(2, 2, vec![-1, 1, 2], vec![0, 1]),
+        Err(InvalidOffsetFirstLast)
+    );
+    assert_eq!(try_pattern(0, 1 << 31, vec![0], vec![]), Err(IndexOverflow));
+}
+
+#[test]
+fn index_overflow_is_reported_by_casts() {
+    let pattern = SparsityPattern::zeros(1, 1 << 32);
+    assert_eq!(
+        pattern.clone().try_cast_indices::<u32>(),
+        Err(SparsityPatternFormatError::IndexOverflow)
+    );
+    assert!(pattern.try_cast_indices::<i64>().is_ok());
+
+    let csr = CsrMatrix::<f64>::zeros(1, 1 << 31);
+    let error = csr.try_cast_indices::<i32>().unwrap_err();
+    assert_eq!(error.kind(), &SparseFormatErrorKind::IndexOutOfBounds);
+
+    let coo = CooMatrix::<f64>::new(1, 1 << 31);
+    let error = coo.try_cast_indices::<i32>().unwrap_err();
+    assert_eq!(error.kind(), &SparseFormatErrorKind::IndexOutOfBounds);
+}
+
+#[test]
+fn csr_generic_invalid_data() {
+    let result = CsrMatrix::try_from_csr_data_generic(
+        2,
+        2,
+        vec![0i32, 1, 2],
+        vec![1i32, -1],
+        vec![1.0, 2.0],
+    );
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+
+    let result =
+        CscMatrix::try_from_csc_data_generic(2, 2, vec![0u32, 2, 1], vec![0u32, 1], vec![1.0, 2.0]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn coo_generic_invalid_data() {
+    let result =
+        CooMatrix::try_from_triplets_generic(2, 2, vec![0i64, -1], vec![0i64, 1], vec![1, 2]);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+}
+
+#[test]
+fn coo_generic_push_and_convert() {
+    let mut coo =
+        CooMatrix::try_from_triplets_generic(3, 2, Vec::<u32>::new(), Vec::new(), Vec::new())
+            .unwrap();
+    coo.push(2, 1, 1.0);
+    coo.push(0, 0, 2.0);
+    coo.push(2, 1, 3.0);
+    assert_eq!(coo.row_indices(), &[2, 0, 2]);
+    assert_eq!(coo.col_indices(), &[1, 0, 1]);
+
+    let csr = CsrMatrix::from(&coo);
+    assert_eq!(csr.row_offsets(), &[0, 1, 1, 2]);
+    assert_eq!(csr.col_indices(), &[0, 1]);
+    assert_eq!(csr.values(), &[2.0, 4.0]);
+
+    let csc = CscMatrix::from(&coo);
+    assert_eq!(csc.col_offsets(), &[0, 1, 2]);
+    assert_eq!(csc.row_indices(), &[0, 2]);
+
+    let dense = DMatrix::from(&coo);
+    assert_eq!(dense, DMatrix::from(&csr));
+    assert_eq!(dense, DMatrix::from(&csc));
+}
+
+proptest! {
+    #[test]
+    fn csr_cast_indices_roundtrip(csr in csr_strategy()) {
+        let narrow = csr.clone().try_cast_indices::<u32>().unwrap();
+        prop_assert_eq!(narrow.nrows(), csr.nrows());
+        prop_assert_eq!(narrow.ncols(), csr.ncols());
+        prop_assert_eq!(to_usize(narrow.row_offsets()), csr.row_offsets());
+        prop_assert_eq!(to_usize(narrow.col_indices()), csr.col_indices());
+        prop_assert_eq!(narrow.values(), csr.values());
+        prop_assert_eq!(DMatrix::from(&narrow), DMatrix::from(&csr));
+
+        let triplets: Vec<_> = narrow.triplet_iter().collect();
+        prop_assert_eq!(triplets, csr.triplet_iter().collect::<Vec<_>>());
+        for i in 0 .. csr.nrows() {
+            for j in 0 .. csr.ncols() {
+                prop_assert_eq!(narrow.get_entry(i, j), csr.get_entry(i, j));
+            }
+        }
+
+        prop_assert_eq!(narrow.try_cast_indices::<usize>().unwrap(), csr);
+    }
+
+    #[test]
+    fn csc_cast_indices_roundtrip(csc in csc_strategy()) {
+        let narrow = csc.clone().try_cast_indices::<i32>().unwrap();
+        prop_assert_eq!(to_usize(narrow.col_offsets()), csc.col_offsets());
+        prop_assert_eq!(to_usize(narrow.row_indices()), csc.row_indices());
+        prop_assert_eq!(DMatrix::from(&narrow), DMatrix::from(&csc));
+        prop_assert_eq!(narrow.try_cast_indices::<usize>().unwrap(), csc);
+    }
+
+    #[test]
+    fn generic_conversions_agree_with_usize_conversions(csr in csr_strategy()) {
+        let narrow = csr.clone().try_cast_indices::<i64>().unwrap();
+
+        let csc = CscMatrix::from(&csr);
+        let narrow_csc = CscMatrix::from(&narrow);
+        prop_assert_eq!(narrow_csc.clone().try_cast_indices::<usize>().unwrap(), csc);
+        prop_assert_eq!(CsrMatrix::from(&narrow_csc), narrow.clone());
+
+        let coo = CooMatrix::from(&csr);
+        let narrow_coo = CooMatrix::from(&narrow);
+        prop_assert_eq!(narrow_coo.clone().try_cast_indices::<usize>().unwrap(), coo);
+        prop_assert_eq!(CsrMatrix::from(&narrow_coo), narrow.clone());
+        prop_assert_eq!(CscMatrix::from(&narrow_coo), narrow_csc);
+
+        prop_assert_eq!(narrow.transpose().try_cast_indices::<usize>().unwrap(), csr.transpose());
+    }
+
+    #[test]
+    fn spmm_dense_with_narrow_indices(
+        csr in csr_strategy(),
+        b_values in proptest::collection::vec(PROPTEST_I32_VALUE_STRATEGY, 18),
+    ) {
+        let a = csr.clone().try_cast_indices::<u32>().unwrap();
+        let a_dense = DMatrix::from(&csr);
+        let b = DMatrix::from_fn(6, 3, |i, j| b_values[3 * i + j]);
+        let b = b.rows(0, csr.ncols()).into_owned();
+        let bt = DMatrix::from_fn(6, 3, |i, j| b_values[3 * i + j]);
+        let bt = bt.rows(0, csr.nrows()).into_owned();
+
+        let mut c = DMatrix::zeros(csr.nrows(), 3);
+        spmm_csr_dense(0, &mut c, 1, Op::NoOp(&a), Op::NoOp(&b));
+        prop_assert_eq!(&c, &(&a_dense * &b));
+
+        let mut c = DMatrix::zeros(csr.ncols(), 3);
+        spmm_csr_dense(0, &mut c, 1, Op::Transpose(&a), Op::NoOp(&bt));
+        prop_assert_eq!(&c, &(a_dense.transpose() * &bt));
+
+        let a_csc = CscMatrix::from(&a);
+        let mut c = DMatrix::zeros(csr.nrows(), 3);
+        spmm_csc_dense(0, &mut c, 1, Op::NoOp(&a_csc), Op::NoOp(&b));
+        prop_assert_eq!(&c, &(&a_dense * &b));
+    }
+
+    #[test]
+    fn spadd_and_spmm_prealloc_with_narrow_indices(csr in csr_strategy()) {
+        let a = csr.clone().try_cast_indices::<u32>().unwrap();
+
+        // C <- 2 * C + 3 * A, where C has the pattern of A
+        let mut c = a.clone();
+        spadd_csr_prealloc(2, &mut c, 3, Op::NoOp(&a)).unwrap();
+        prop_assert_eq!(DMatrix::from(&c), DMatrix::from(&csr) * 5);
+
+        // C <- A^T * A, using the pattern of the usize product
+        let product = csr.transpose() * &csr;
+        let mut c = product.map(|_| 0).try_cast_indices::<u32>().unwrap();
+        spmm_csr_prealloc(0, &mut c, 1, Op::Transpose(&a), Op::NoOp(&a)).unwrap();
+        prop_assert_eq!(c.try_cast_indices::<usize>().unwrap(), product);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::common::{csc_strategy, PROPTEST_I32_VALUE_STRATEGY};
+use nalgebra::sparse::{CsMatrix as LegacyCsMatrix, CsVector as LegacyCsVector};
+use nalgebra::{DMatrix, DVector, Dyn, U1};
+use nalgebra_sparse::convert::serial::{
+    convert_csc_legacy, convert_legacy_csc, convert_legacy_sparse_vector,
+    convert_sparse_vector_legacy,
+};
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::proptest::csc;
+use nalgebra_sparse::vector::SparseVector;
+
+use proptest::prelude::*;
+use std::convert::TryFrom;
+
+#[test]
+fn legacy_triplets_convert_to_csc() {
+    let legacy = LegacyCsMatrix::from_triplet(3, 2, &[2, 0, 1], &[0, 0, 1], &[1, 2, 3]);
+    let csc = CscMatrix::try_from(&legacy).unwrap();
+    assert_eq!(csc.col_offsets(), &[0, 2, 3]);
+    assert_eq!(csc.row_indices(), &[0, 2, 1]);
+    assert_eq!(csc.values(), &[2, 1, 3]);
+
+    let legacy = LegacyCsVector::from_triplet_generic(Dyn(4), U1, &[3, 1], &[0, 0], &[5, 6]);
+    let vector = SparseVector::try_from(&legacy).unwrap();
+    assert_eq!(vector.len(), 4);
+    assert_eq!(vector.indices(), &[1, 3]);
+    assert_eq!(vector.values(), &[6, 5]);
+}
+
+proptest! {
+    #[test]
+    fn csc_legacy_roundtrip(csc in csc_strategy()) {
+        let legacy = LegacyCsMatrix::from(&csc);
+        prop_assert!(legacy.is_sorted());
+        prop_assert_eq!(legacy.shape(), (csc.nrows(), csc.ncols()));
+        prop_assert_eq!(DMatrix::from(legacy.clone()), DMatrix::from(&csc));
+
+        let roundtrip = convert_legacy_csc(&legacy).unwrap();
+        prop_assert_eq!(roundtrip, csc.clone());
+        prop_assert_eq!(convert_legacy_csc(&convert_csc_legacy(&csc)).unwrap(), csc);
+    }
+
+    #[test]
+    fn sparse_vector_legacy_roundtrip(
+        x in csc(PROPTEST_I32_VALUE_STRATEGY, 0..=6usize, 1, 6).prop_map(|x| x.col_as_vector(0))
+    ) {
+        let legacy = LegacyCsVector::from(&x);
+        prop_assert_eq!(DVector::from(legacy.clone()), DVector::from(&x));
+        prop_assert_eq!(SparseVector::try_from(&legacy).unwrap(), x.clone());
+        prop_assert_eq!(
+            convert_legacy_sparse_vector(&convert_sparse_vector_legacy(&x)).unwrap(),
+            x
+        );
+    }
+}
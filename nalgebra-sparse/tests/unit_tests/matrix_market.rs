@@ -1,13 +1,21 @@
 use matrixcompare::assert_matrix_eq;
 use nalgebra::matrix;
-use nalgebra::Complex;
+use nalgebra::proptest::matrix as dense_matrix;
+use nalgebra::{Complex, DMatrix};
 use nalgebra_sparse::io::{
-    load_coo_from_matrix_market_file, load_coo_from_matrix_market_str, save_to_matrix_market_file,
-    save_to_matrix_market_str,
+    load_coo_from_matrix_market_file, load_coo_from_matrix_market_reader,
+    load_coo_from_matrix_market_reader_with_progress, load_coo_from_matrix_market_str,
+    load_dmatrix_from_matrix_market_file, load_dmatrix_from_matrix_market_str,
+    save_dmatrix_to_matrix_market_file, save_dmatrix_to_matrix_market_file_with_symmetry,
+    save_dmatrix_to_matrix_market_str, save_dmatrix_to_matrix_market_str_with_symmetry,
+    save_to_matrix_market_file, save_to_matrix_market_file_with_symmetry,
+    save_to_matrix_market_str, save_to_matrix_market_str_with_symmetry, MatrixMarketErrorKind,
+    MatrixMarketSymmetry,
 };
 use nalgebra_sparse::proptest::coo_no_duplicates;
-use nalgebra_sparse::CooMatrix;
+use nalgebra_sparse::{CooMatrix, CsrMatrix, SparseFormatErrorKind};
 use proptest::prelude::*;
+use std::io::{BufReader, ErrorKind};
 use tempfile::tempdir;
 
 type C64 = Complex<f64>;
@@ -446,6 +454,341 @@ fn test_matrixmarket_write_complex() {
     assert_eq!(matrixmarket_str, expected);
 }
 
+#[test]
+fn test_matrixmarket_load_dmatrix() {
+    let file_str = r#"
+%%matrixmarket matrix array integer skew-symmetric
+3 3
+2
+3
+-1
+"#;
+    let dense = load_dmatrix_from_matrix_market_str::<i32>(file_str).unwrap();
+    let expected = matrix![
+        0, -2, -3;
+        2,  0,  1;
+        3, -1,  0;
+    ];
+    assert_eq!(dense, expected);
+
+    // Coordinate files are densified, with duplicates summed
+    let file_str = r#"
+%%matrixmarket matrix coordinate real general
+2 3 3
+1 1 1.5
+2 3 2.0
+1 1 1.0
+"#;
+    let dense = load_dmatrix_from_matrix_market_str::<f64>(file_str).unwrap();
+    assert_eq!(dense, matrix![2.5, 0.0, 0.0; 0.0, 0.0, 2.0]);
+}
+
+#[test]
+fn test_matrixmarket_write_dense_real() {
+    let dense = matrix![
+        1.5, 2.0, 3.0;
+        4.0, 0.0, -6.25;
+    ];
+    let expected = r#"%%matrixmarket matrix array real general
+% matrixmarket file generated by nalgebra-sparse.
+2 3
+1.5
+4
+2
+0
+3
+-6.25
+"#;
+    let matrixmarket_str = save_dmatrix_to_matrix_market_str(&dense);
+    assert_eq!(matrixmarket_str, expected);
+    let loaded = load_dmatrix_from_matrix_market_str::<f64>(&matrixmarket_str).unwrap();
+    assert_eq!(loaded, dense);
+}
+
+#[test]
+fn test_matrixmarket_write_dense_with_symmetry() {
+    let symmetric = matrix![
+        4.0, 1.0, 2.0;
+        1.0, 5.0, 0.0;
+        2.0, 0.0, 6.0;
+    ];
+    let expected = r#"%%matrixmarket matrix array real symmetric
+% matrixmarket file generated by nalgebra-sparse.
+3 3
+4
+1
+2
+5
+0
+6
+"#;
+    let matrixmarket_str = save_dmatrix_to_matrix_market_str_with_symmetry(
+        &symmetric,
+        MatrixMarketSymmetry::Symmetric,
+    )
+    .unwrap();
+    assert_eq!(matrixmarket_str, expected);
+    let loaded = load_dmatrix_from_matrix_market_str::<f64>(&matrixmarket_str).unwrap();
+    assert_eq!(loaded, symmetric);
+
+    let skew = matrix![
+        0, -2, -3;
+        2,  0,  1;
+        3, -1,  0;
+    ];
+    let expected = r#"%%matrixmarket matrix array integer skew-symmetric
+% matrixmarket file generated by nalgebra-sparse.
+3 3
+2
+3
+-1
+"#;
+    let matrixmarket_str =
+        save_dmatrix_to_matrix_market_str_with_symmetry(&skew, MatrixMarketSymmetry::SkewSymmetric)
+            .unwrap();
+    assert_eq!(matrixmarket_str, expected);
+    let loaded = load_dmatrix_from_matrix_market_str::<i32>(&matrixmarket_str).unwrap();
+    assert_eq!(loaded, skew);
+
+    let hermitian = matrix![
+        C64 { re: 1.0, im: 0.0 }, C64 { re: 2.0, im: -1.0 };
+        C64 { re: 2.0, im: 1.0 }, C64 { re: 3.0, im: 0.0 };
+    ];
+    let expected = r#"%%matrixmarket matrix array complex hermitian
+% matrixmarket file generated by nalgebra-sparse.
+2 2
+1 0
+2 1
+3 0
+"#;
+    let matrixmarket_str = save_dmatrix_to_matrix_market_str_with_symmetry(
+        &hermitian,
+        MatrixMarketSymmetry::Hermitian,
+    )
+    .unwrap();
+    assert_eq!(matrixmarket_str, expected);
+    let loaded = load_dmatrix_from_matrix_market_str::<C64>(&matrixmarket_str).unwrap();
+    assert_eq!(loaded, hermitian);
+}
+
+#[test]
+fn test_matrixmarket_write_sparse_with_symmetry() {
+    let dense = matrix![
+        4.0, 1.0, 0.0;
+        1.0, 0.0, 2.0;
+        0.0, 2.0, 6.0;
+    ];
+    let csr = CsrMatrix::from(&dense);
+    let expected = r#"%%matrixmarket matrix coordinate real symmetric
+% matrixmarket file generated by nalgebra-sparse.
+3 3 4
+1 1 4
+2 1 1
+3 2 2
+3 3 6
+"#;
+    let matrixmarket_str =
+        save_to_matrix_market_str_with_symmetry(&csr, MatrixMarketSymmetry::Symmetric).unwrap();
+    assert_eq!(matrixmarket_str, expected);
+    let loaded = load_coo_from_matrix_market_str::<f64>(&matrixmarket_str).unwrap();
+    assert_matrix_eq!(loaded, dense);
+
+    // The diagonal is not written for skew-symmetric matrices
+    let skew = CooMatrix::try_from_triplets(2, 2, vec![0, 1], vec![1, 0], vec![3, -3]).unwrap();
+    let expected = r#"%%matrixmarket matrix coordinate integer skew-symmetric
+% matrixmarket file generated by nalgebra-sparse.
+2 2 1
+2 1 -3
+"#;
+    let matrixmarket_str =
+        save_to_matrix_market_str_with_symmetry(&skew, MatrixMarketSymmetry::SkewSymmetric)
+            .unwrap();
+    assert_eq!(matrixmarket_str, expected);
+
+    let pattern =
+        CooMatrix::try_from_triplets(2, 2, vec![0, 1, 1], vec![1, 0, 1], vec![(), (), ()]).unwrap();
+    let matrixmarket_str =
+        save_to_matrix_market_str_with_symmetry(&pattern, MatrixMarketSymmetry::Symmetric).unwrap();
+    let loaded = load_coo_from_matrix_market_str::<()>(&matrixmarket_str).unwrap();
+    assert_eq!(loaded.nnz(), 3);
+}
+
+#[test]
+fn test_matrixmarket_write_with_invalid_symmetry() {
+    let symmetry_error = |result: Result<String, std::io::Error>| result.unwrap_err().kind();
+
+    // Not symmetric
+    let dense = matrix![1.0, 2.0; 3.0, 4.0];
+    let csr = CsrMatrix::from(&dense);
+    assert_eq!(
+        symmetry_error(save_dmatrix_to_matrix_market_str_with_symmetry(
+            &dense,
+            MatrixMarketSymmetry::Symmetric
+        )),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        symmetry_error(save_to_matrix_market_str_with_symmetry(
+            &csr,
+            MatrixMarketSymmetry::Symmetric
+        )),
+        ErrorKind::InvalidInput
+    );
+
+    // Entry missing from the upper triangle
+    let coo = CooMatrix::try_from_triplets(2, 2, vec![1], vec![0], vec![1.0]).unwrap();
+    assert_eq!(
+        symmetry_error(save_to_matrix_market_str_with_symmetry(
+            &coo,
+            MatrixMarketSymmetry::Symmetric
+        )),
+        ErrorKind::InvalidInput
+    );
+
+    // Mismatched entries are reported with 1-based indices
+    let coo = CooMatrix::try_from_triplets(2, 2, vec![0, 1], vec![1, 0], vec![1.0, 2.0]).unwrap();
+    let error =
+        save_to_matrix_market_str_with_symmetry(&coo, MatrixMarketSymmetry::Symmetric).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("row 2 col 1"), "{}", error);
+
+    // Non-zero diagonal of a skew-symmetric matrix
+    let dense = matrix![1, 2; -2, 0];
+    assert_eq!(
+        symmetry_error(save_dmatrix_to_matrix_market_str_with_symmetry(
+            &dense,
+            MatrixMarketSymmetry::SkewSymmetric
+        )),
+        ErrorKind::InvalidInput
+    );
+
+    // Non-square
+    let dense = DMatrix::<f64>::zeros(2, 3);
+    assert_eq!(
+        symmetry_error(save_dmatrix_to_matrix_market_str_with_symmetry(
+            &dense,
+            MatrixMarketSymmetry::Symmetric
+        )),
+        ErrorKind::InvalidInput
+    );
+
+    // Hermitian real matrix
+    let dense = DMatrix::<f64>::identity(2, 2);
+    assert_eq!(
+        symmetry_error(save_dmatrix_to_matrix_market_str_with_symmetry(
+            &dense,
+            MatrixMarketSymmetry::Hermitian
+        )),
+        ErrorKind::InvalidInput
+    );
+
+    // Dense pattern matrix
+    let dense = DMatrix::from_element(1, 1, ());
+    assert_eq!(
+        symmetry_error(save_dmatrix_to_matrix_market_str_with_symmetry(
+            &dense,
+            MatrixMarketSymmetry::General
+        )),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_matrixmarket_load_reader() {
+    // Blank lines, comments in the data section and CRLF line endings are accepted
+    let data = "\r\n%%MatrixMarket matrix coordinate integer symmetric\r\n% comment\r\n\r\n3 3 2\r\n2 1 4\r\n\r\n% another comment\r\n3 3 5\r\n";
+    let coo =
+        load_coo_from_matrix_market_reader::<i32, _>(BufReader::new(data.as_bytes())).unwrap();
+    let expected = matrix![
+        0, 4, 0;
+        4, 0, 0;
+        0, 0, 5;
+    ];
+    assert_matrix_eq!(coo, expected);
+}
+
+#[test]
+fn test_matrixmarket_load_reports_line_numbers() {
+    let load_error = |data: &str| load_coo_from_matrix_market_str::<f64>(data).unwrap_err();
+
+    let error = load_error(
+        "%%matrixmarket matrix coordinate real general\n% comment\n2 2 2\n1 1 1.0\n1 x 2.0\n",
+    );
+    assert_eq!(error.kind(), MatrixMarketErrorKind::ParsingError);
+    assert_eq!(error.line_number(), Some(5));
+    assert!(error.to_string().contains("line: 5"));
+
+    let error = load_error("\n%%matrixmarket matrix coordinate real unknown\n1 1 1\n1 1 1.0\n");
+    assert_eq!(error.kind(), MatrixMarketErrorKind::ParsingError);
+    assert_eq!(error.line_number(), Some(2));
+
+    let error = load_error("%%matrixmarket matrix coordinate real general\n1 1\n1 1 1.0\n");
+    assert_eq!(error.kind(), MatrixMarketErrorKind::ParsingError);
+    assert_eq!(error.line_number(), Some(2));
+
+    let error =
+        load_error("%%matrixmarket matrix coordinate real symmetric\n2 2 2\n2 1 1.0\n1 2 1.0\n");
+    assert_eq!(error.kind(), MatrixMarketErrorKind::NotLowerTriangle);
+    assert_eq!(error.line_number(), Some(4));
+
+    let error = load_error("%%matrixmarket matrix coordinate real general\n2 2 1\n3 1 1.0\n");
+    assert_eq!(
+        error.kind(),
+        MatrixMarketErrorKind::SparseFormatError(SparseFormatErrorKind::IndexOutOfBounds)
+    );
+    assert_eq!(error.line_number(), Some(3));
+
+    // Too many entries are reported at the first superfluous entry
+    let error = load_error("%%matrixmarket matrix array real general\n1 1\n1.0\n2.0\n");
+    assert_eq!(error.kind(), MatrixMarketErrorKind::EntryMismatch);
+    assert_eq!(error.line_number(), Some(4));
+
+    // Missing entries can't be attributed to a line
+    let error = load_error("%%matrixmarket matrix coordinate real general\n2 2 2\n1 1 1.0\n");
+    assert_eq!(error.kind(), MatrixMarketErrorKind::EntryMismatch);
+    assert_eq!(error.line_number(), None);
+
+    // A huge number of entries in the header must not abort when the reservation fails
+    let error = load_error(
+        "%%matrixmarket matrix coordinate real general\n1 1 18446744073709551615\n1 1 1.0\n",
+    );
+    assert_eq!(error.kind(), MatrixMarketErrorKind::EntryMismatch);
+    let error = load_error(&format!(
+        "%%matrixmarket matrix coordinate real symmetric\n{0} {0} 100000000000000000\n1 1 1.0\n",
+        usize::MAX
+    ));
+    assert_eq!(error.kind(), MatrixMarketErrorKind::EntryMismatch);
+}
+
+#[test]
+fn test_matrixmarket_load_reports_progress() {
+    let n = 100_000;
+    let mut data = format!(
+        "%%matrixmarket matrix coordinate pattern general\n{} {} {}\n",
+        n, n, n
+    );
+    for i in 1..=n {
+        data.push_str(&format!("{} {}\n", i, i));
+    }
+
+    let mut reports = Vec::new();
+    let coo =
+        load_coo_from_matrix_market_reader_with_progress::<(), _, _>(data.as_bytes(), |progress| {
+            reports.push(*progress)
+        })
+        .unwrap();
+    assert_eq!(coo.nnz(), n);
+
+    let entries_read: Vec<_> = reports.iter().map(|p| p.entries_read()).collect();
+    assert_eq!(entries_read, vec![0, 65536, n]);
+    assert!(reports.iter().all(|p| p.total_entries() == n));
+    assert_eq!(reports[0].line_number(), 2);
+    assert_eq!(reports[1].line_number(), 2 + 65536);
+    let last = reports.last().unwrap();
+    assert_eq!(last.line_number(), 2 + n);
+    assert_eq!(last.bytes_read(), data.len());
+}
+
 proptest! {
     #[test]
     fn coo_matrix_market_roundtrip_str(coo in coo_no_duplicates(-10 ..= 10, 0 ..= 10, 0..= 10, 100)) {
@@ -466,3 +809,47 @@ proptest! {
         temp_dir.close().expect("Unable to delete temporary directory");
     }
 }
+
+proptest! {
+    #[test]
+    fn dmatrix_matrix_market_roundtrip_str(dense in dense_matrix(-10 ..= 10, 0 ..= 6, 0 ..= 6)) {
+        let generated_matrixmarket_string = save_dmatrix_to_matrix_market_str(&dense);
+        let generated_matrix = load_dmatrix_from_matrix_market_str(&generated_matrixmarket_string).unwrap();
+        prop_assert_eq!(generated_matrix, dense);
+    }
+}
+
+proptest! {
+    #[test]
+    fn dmatrix_matrix_market_roundtrip_file(dense in dense_matrix(-10 ..= 10, 0 ..= 6, 0 ..= 6)) {
+        let temp_dir = tempdir().expect("Unable to create temporary directory");
+        let file_path = temp_dir.path().join("temp.mtx");
+        save_dmatrix_to_matrix_market_file(&dense, &file_path).unwrap();
+        let generated_matrix = load_dmatrix_from_matrix_market_file(file_path).unwrap();
+        prop_assert_eq!(generated_matrix, dense);
+        temp_dir.close().expect("Unable to delete temporary directory");
+    }
+}
+
+proptest! {
+    #[test]
+    fn symmetric_matrix_market_roundtrip_file(
+        coo in (0 ..= 10usize).prop_flat_map(|n| coo_no_duplicates(-10 ..= 10, n, n, 100))
+    ) {
+        let square = CsrMatrix::from(&coo);
+        let symmetric = &square + &square.transpose();
+        let skew = &square - &square.transpose();
+
+        let temp_dir = tempdir().expect("Unable to create temporary directory");
+        let file_path = temp_dir.path().join("temp.mtx");
+        save_to_matrix_market_file_with_symmetry(&symmetric, &file_path, MatrixMarketSymmetry::Symmetric).unwrap();
+        let generated_matrix = load_coo_from_matrix_market_file(&file_path).unwrap();
+        assert_matrix_eq!(generated_matrix, symmetric);
+
+        let dense_skew = DMatrix::from(&skew);
+        save_dmatrix_to_matrix_market_file_with_symmetry(&dense_skew, &file_path, MatrixMarketSymmetry::SkewSymmetric).unwrap();
+        let generated_matrix = load_dmatrix_from_matrix_market_file(&file_path).unwrap();
+        prop_assert_eq!(generated_matrix, dense_skew);
+        temp_dir.close().expect("Unable to delete temporary directory");
+    }
+}
@@ -1,10 +1,30 @@
+mod assembly;
+mod binary;
 mod cholesky;
+mod componentwise;
 mod convert_serial;
 mod coo;
 mod csc;
 mod csr;
+mod edition;
+mod eigen;
+mod formats;
+mod index_types;
+#[cfg(feature = "legacy-sparse")]
+mod legacy;
 mod matrix_market;
+mod operator;
 mod ops;
+#[cfg(feature = "rayon")]
+mod ops_parallel;
 mod pattern;
+mod pattern_graph;
+mod permutation;
+mod preconditioners;
 mod proptest;
+mod rutherford_boeing;
+mod solvers;
+mod spy;
 mod test_data_examples;
+mod vector;
+mod views;
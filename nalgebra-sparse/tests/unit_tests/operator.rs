@@ -0,0 +1,232 @@
+use crate::assert_panics;
+use crate::common::{csc_strategy, csr_strategy, value_strategy, PROPTEST_MATRIX_DIM};
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::operator::{
+    BlockOperator, FnOperator, LinearOperator, ProductOperator, ScaledOperator, SumOperator,
+    TransposableOperator, TransposeOperator,
+};
+use nalgebra_sparse::ops::Op;
+
+use nalgebra::proptest::vector;
+use nalgebra::{DMatrix, DVector, DVectorView, DVectorViewMut};
+
+use proptest::prelude::*;
+
+use matrixcompare::{assert_matrix_eq, prop_assert_matrix_eq};
+
+/// Computes `beta * y + alpha * A * x` by applying the operator to a copy of `y`.
+fn apply_to_copy<A: LinearOperator<i32>>(
+    a: &A,
+    beta: i32,
+    y: &DVector<i32>,
+    alpha: i32,
+    x: &DVector<i32>,
+) -> DVector<i32> {
+    let mut result = y.clone();
+    a.apply(beta, (&mut result).into(), alpha, x.into());
+    result
+}
+
+proptest! {
+    #[test]
+    fn csr_csc_dense_operators_agree(
+        (a, x, y) in csr_strategy().prop_flat_map(|a| {
+            let x = vector(value_strategy::<i32>(), a.ncols());
+            let y = vector(value_strategy::<i32>(), a.nrows());
+            (Just(a), x, y)
+        }),
+        beta in value_strategy::<i32>(),
+        alpha in value_strategy::<i32>(),
+    ) {
+        let dense = DMatrix::from(&a);
+        let csc = CscMatrix::from(&a);
+        let expected = &y * beta + &dense * &x * alpha;
+
+        prop_assert_eq!(LinearOperator::nrows(&a), a.nrows());
+        prop_assert_eq!(LinearOperator::ncols(&a), a.ncols());
+        prop_assert_eq!(apply_to_copy(&a, beta, &y, alpha, &x), expected.clone());
+        prop_assert_eq!(apply_to_copy(&csc, beta, &y, alpha, &x), expected.clone());
+        prop_assert_eq!(apply_to_copy(&dense, beta, &y, alpha, &x), expected.clone());
+        // References to operators are also operators
+        prop_assert_eq!(apply_to_copy(&&a, beta, &y, alpha, &x), expected);
+    }
+
+    #[test]
+    fn fn_operator_agrees_with_matrix(
+        (a, x, y) in csc_strategy().prop_flat_map(|a| {
+            let x = vector(value_strategy::<i32>(), a.ncols());
+            let y = vector(value_strategy::<i32>(), a.nrows());
+            (Just(a), x, y)
+        }),
+        beta in value_strategy::<i32>(),
+        alpha in value_strategy::<i32>(),
+    ) {
+        let op = FnOperator::new(a.nrows(), a.ncols(), |x: DVectorView<i32>, mut y: DVectorViewMut<i32>| {
+            y.copy_from(&(&a * x.clone_owned()));
+        });
+        let expected = apply_to_copy(&a, beta, &y, alpha, &x);
+        prop_assert_matrix_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected);
+    }
+
+    #[test]
+    fn op_operators_agree_with_dense(
+        (a, x, y) in csr_strategy().prop_flat_map(|a| {
+            let x = vector(value_strategy::<i32>(), a.nrows());
+            let y = vector(value_strategy::<i32>(), a.ncols());
+            (Just(a), x, y)
+        }),
+        beta in value_strategy::<i32>(),
+        alpha in value_strategy::<i32>(),
+    ) {
+        let dense = DMatrix::from(&a);
+        let csc = CscMatrix::from(&a);
+        let expected = &y * beta + dense.transpose() * &x * alpha;
+
+        for op in [Op::Transpose(&a)] {
+            prop_assert_eq!(op.nrows(), a.ncols());
+            prop_assert_eq!(op.ncols(), a.nrows());
+            prop_assert_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected.clone());
+        }
+        for op in [Op::Transpose(&csc)] {
+            prop_assert_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected.clone());
+        }
+        for op in [Op::Transpose(&dense)] {
+            prop_assert_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected.clone());
+        }
+        prop_assert_eq!(apply_to_copy(&TransposeOperator::new(&a), beta, &y, alpha, &x), expected.clone());
+        prop_assert_eq!(apply_to_copy(&TransposeOperator::new(&csc), beta, &y, alpha, &x), expected.clone());
+        prop_assert_eq!(apply_to_copy(&TransposeOperator::new(&dense), beta, &y, alpha, &x), expected);
+
+        let expected = &x * beta + &dense * &y * alpha;
+        prop_assert_eq!(apply_to_copy(&Op::NoOp(&a), beta, &x, alpha, &y), expected.clone());
+        prop_assert_eq!(apply_to_copy(&Op::NoOp(&dense), beta, &x, alpha, &y), expected.clone());
+        let transposed_twice = TransposeOperator::new(TransposeOperator::new(&csc));
+        prop_assert_eq!(apply_to_copy(&transposed_twice, beta, &x, alpha, &y), expected);
+    }
+
+    #[test]
+    fn combinators_agree_with_dense(
+        (a, b, x, y) in csr_strategy().prop_flat_map(|a| {
+            let b = nalgebra_sparse::proptest::csr(value_strategy::<i32>(), a.nrows(), a.ncols(), 10);
+            let x = vector(value_strategy::<i32>(), a.ncols());
+            let y = vector(value_strategy::<i32>(), a.ncols());
+            (Just(a), b, x, y)
+        }),
+        beta in value_strategy::<i32>(),
+        alpha in value_strategy::<i32>(),
+        scale in value_strategy::<i32>(),
+    ) {
+        let (a_dense, b_dense) = (DMatrix::from(&a), DMatrix::from(&b));
+
+        // A^T (s A + B) as a composition of operators
+        let op = ProductOperator::new(
+            TransposeOperator::new(&a),
+            SumOperator::new(ScaledOperator::new(scale, &a), &b),
+        );
+        let op_dense = a_dense.transpose() * (&a_dense * scale + &b_dense);
+        prop_assert_eq!(op.nrows(), op_dense.nrows());
+        prop_assert_eq!(op.ncols(), op_dense.ncols());
+        let expected = &y * beta + &op_dense * &x * alpha;
+        prop_assert_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected);
+
+        let mut result = x.clone();
+        op.apply_transpose(beta, (&mut result).into(), alpha, (&y).into());
+        prop_assert_eq!(result, &x * beta + op_dense.transpose() * &y * alpha);
+    }
+
+    #[test]
+    fn block_operator_agrees_with_dense(
+        (a, b, x, y) in PROPTEST_MATRIX_DIM.prop_flat_map(|n| {
+            nalgebra_sparse::proptest::csr(value_strategy::<i32>(), n, n, 20)
+        }).prop_flat_map(|a| {
+            let b = nalgebra_sparse::proptest::csr(value_strategy::<i32>(), PROPTEST_MATRIX_DIM, a.ncols(), 10);
+            (Just(a), b)
+        }).prop_flat_map(|(a, b)| {
+            let x = vector(value_strategy::<i32>(), a.ncols() + b.nrows());
+            let y = vector(value_strategy::<i32>(), a.nrows() + b.nrows());
+            (Just(a), Just(b), x, y)
+        }),
+        beta in value_strategy::<i32>(),
+        alpha in value_strategy::<i32>(),
+    ) {
+        // [ A  B^T ]
+        // [ B   0  ]
+        let (a_dense, b_dense) = (DMatrix::from(&a), DMatrix::from(&b));
+        let mut op = BlockOperator::new(2, 2);
+        op.set_block(0, 0, &a)
+            .set_block(0, 1, Op::Transpose(&b))
+            .set_block(1, 0, &b_dense);
+
+        let mut dense = DMatrix::zeros(a.nrows() + b.nrows(), a.ncols() + b.nrows());
+        dense.view_mut((0, 0), a_dense.shape()).copy_from(&a_dense);
+        dense.view_mut((0, a.ncols()), (b.ncols(), b.nrows())).copy_from(&b_dense.transpose());
+        dense.view_mut((a.nrows(), 0), b_dense.shape()).copy_from(&b_dense);
+
+        prop_assert_eq!(op.nrows(), dense.nrows());
+        prop_assert_eq!(op.ncols(), dense.ncols());
+        let expected = &y * beta + &dense * &x * alpha;
+        prop_assert_eq!(apply_to_copy(&op, beta, &y, alpha, &x), expected);
+    }
+
+    #[test]
+    fn zero_beta_ignores_nan_in_output(
+        a in nalgebra_sparse::proptest::csr(value_strategy::<f64>(), PROPTEST_MATRIX_DIM, PROPTEST_MATRIX_DIM, 40)
+    ) {
+        let x = DVector::repeat(a.ncols(), 1.0);
+        let mut y = DVector::repeat(a.nrows(), f64::NAN);
+        a.apply(0.0, (&mut y).into(), 1.0, (&x).into());
+        prop_assert_matrix_eq!(y, &a * &x);
+    }
+}
+
+#[test]
+fn fn_operator_reports_dimensions() {
+    let op = FnOperator::new(3, 2, |_: DVectorView<f64>, mut y: DVectorViewMut<f64>| {
+        y.fill(1.0)
+    });
+    assert_eq!(LinearOperator::<f64>::nrows(&op), 3);
+    assert_eq!(LinearOperator::<f64>::ncols(&op), 2);
+
+    let mut y = DVector::repeat(3, 2.0);
+    op.apply(3.0, (&mut y).into(), 2.0, (&DVector::zeros(2)).into());
+    assert_matrix_eq!(y, DVector::repeat(3, 8.0));
+}
+
+#[test]
+fn operator_apply_panics_on_dimension_mismatch() {
+    let a = CsrMatrix::<f64>::identity(3);
+    let op = FnOperator::new(3, 3, |_: DVectorView<f64>, _: DVectorViewMut<f64>| {});
+    assert_panics!({
+        let (x, mut y) = (DVector::zeros(4), DVector::zeros(3));
+        a.apply(0.0, (&mut y).into(), 1.0, (&x).into())
+    });
+    assert_panics!({
+        let (x, mut y) = (DVector::zeros(4), DVector::zeros(3));
+        op.apply(0.0, (&mut y).into(), 1.0, (&x).into())
+    });
+}
+
+#[test]
+fn block_operator_with_absent_blocks() {
+    let a = CsrMatrix::<f64>::identity(2);
+    let mut op = BlockOperator::new(2, 2);
+    op.set_block(1, 1, &a)
+        .set_row_height(0, 1)
+        .set_col_width(0, 3);
+    assert_eq!(op.nrows(), 3);
+    assert_eq!(op.ncols(), 5);
+
+    let x = DVector::from_column_slice(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+    let mut y = DVector::repeat(3, f64::NAN);
+    op.apply(0.0, (&mut y).into(), 2.0, (&x).into());
+    assert_eq!(y, DVector::from_column_slice(&[0.0, 8.0, 10.0]));
+
+    assert_panics!({
+        BlockOperator::new(1, 2)
+            .set_block(0, 0, &a)
+            .set_row_height(0, 3);
+    });
+    assert_panics!(SumOperator::new(&a, CsrMatrix::<f64>::identity(3)));
+    assert_panics!(ProductOperator::new(&a, CsrMatrix::<f64>::identity(3)));
+}
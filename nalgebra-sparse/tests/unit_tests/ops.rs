@@ -4,22 +4,34 @@ use crate::common::{
 };
 use nalgebra_sparse::csc::CscMatrix;
 use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::serial::OperationErrorKind;
 use nalgebra_sparse::ops::serial::{
-    spadd_csc_prealloc, spadd_csr_prealloc, spadd_pattern, spmm_csc_dense, spmm_csc_prealloc,
-    spmm_csc_prealloc_unchecked, spmm_csr_dense, spmm_csr_pattern, spmm_csr_prealloc,
-    spmm_csr_prealloc_unchecked, spsolve_csc_lower_triangular,
+    spadd_csc_prealloc, spadd_csc_prealloc_adjoint, spadd_csr_prealloc, spadd_csr_prealloc_adjoint,
+    spadd_pattern, spmm_csc_dense, spmm_csc_dense_adjoint, spmm_csc_prealloc,
+    spmm_csc_prealloc_unchecked, spmm_csr_dense, spmm_csr_dense_adjoint, spmm_csr_pattern,
+    spmm_csr_prealloc, spmm_csr_prealloc_unchecked, spmv_csc_sparse_adjoint,
+    spmv_csr_sparse_adjoint, spsolve_csc_lower_triangular, spsolve_csc_lower_triangular_adjoint,
+    spsolve_csc_lower_triangular_sparse, spsolve_csc_unit_lower_triangular,
+    spsolve_csc_unit_lower_triangular_sparse, spsolve_csc_unit_upper_triangular,
+    spsolve_csc_unit_upper_triangular_sparse, spsolve_csc_upper_triangular,
+    spsolve_csc_upper_triangular_adjoint, spsolve_csc_upper_triangular_sparse,
+    spsolve_csr_lower_triangular, spsolve_csr_lower_triangular_adjoint,
+    spsolve_csr_unit_lower_triangular, spsolve_csr_unit_upper_triangular,
+    spsolve_csr_upper_triangular, spsolve_csr_upper_triangular_adjoint,
 };
 use nalgebra_sparse::ops::Op;
 use nalgebra_sparse::pattern::SparsityPattern;
 use nalgebra_sparse::proptest::{csc, csr, sparsity_pattern};
+use nalgebra_sparse::vector::SparseVector;
 
 use nalgebra::proptest::{matrix, vector};
-use nalgebra::{DMatrix, DMatrixView, DMatrixViewMut, Scalar};
+use nalgebra::{Complex, DMatrix, DMatrixView, DMatrixViewMut, DVector, Scalar};
 
 use proptest::prelude::*;
 
 use matrixcompare::prop_assert_matrix_eq;
 
+use std::collections::HashSet;
 use std::panic::catch_unwind;
 
 /// Represents the sparsity pattern of a CSR matrix as a dense matrix with 0/1
@@ -330,6 +342,28 @@ fn csc_square_with_non_zero_diagonals() -> impl Strategy<Value = CscMatrix<f64>>
     })
 }
 
+/// Replaces the diagonal of a dense matrix by ones.
+fn with_unit_diagonal(matrix: &DMatrix<f64>) -> DMatrix<f64> {
+    let mut matrix = matrix.clone();
+    matrix.fill_diagonal(1.0);
+    matrix
+}
+
+/// Generates a square matrix with non-zero diagonals along with a sparse right-hand side.
+fn csc_square_with_non_zero_diagonals_and_sparse_rhs(
+) -> impl Strategy<Value = (CscMatrix<f64>, CscMatrix<f64>)> {
+    csc_square_with_non_zero_diagonals().prop_flat_map(|a| {
+        let n = a.nrows();
+        let b = csc(
+            value_strategy::<f64>(),
+            n,
+            PROPTEST_MATRIX_DIM,
+            PROPTEST_MAX_NNZ,
+        );
+        (Just(a), b)
+    })
+}
+
 /// Helper function to help us call dense GEMM with our `Op` type
 fn dense_gemm<'a>(
     beta: i32,
@@ -342,13 +376,16 @@ fn dense_gemm<'a>(
     let a = a.convert();
     let b = b.convert();
 
-    use Op::{NoOp, Transpose};
-    match (a, b) {
-        (NoOp(a), NoOp(b)) => c.gemm(alpha, &a, &b, beta),
-        (Transpose(a), NoOp(b)) => c.gemm(alpha, &a.transpose(), &b, beta),
-        (NoOp(a), Transpose(b)) => c.gemm(alpha, &a, &b.transpose(), beta),
-        (Transpose(a), Transpose(b)) => c.gemm(alpha, &a.transpose(), &b.transpose(), beta),
-    }
+    // Conjugation is the identity for integers
+    let a = match a {
+        Op::NoOp(a) => a.clone_owned(),
+        Op::Transpose(a) => a.transpose(),
+    };
+    let b = match b {
+        Op::NoOp(b) => b.clone_owned(),
+        Op::Transpose(b) => b.transpose(),
+    };
+    c.gemm(alpha, &a, &b, beta);
 }
 
 proptest! {
@@ -1279,4 +1316,246 @@ proptest! {
         prop_assert_matrix_eq!(&a_lower.transpose() * &x, &b, comp = abs, tol = 1e-4);
     }
 
+    #[test]
+    fn triangular_solves_agree_with_dense(
+        // A square matrix `a` and a dimensionally compatible dense matrix `b`
+        (a, b)
+            in csc_square_with_non_zero_diagonals()
+                .prop_flat_map(|a| {
+                    let nrows = a.nrows();
+                    (Just(a), matrix(value_strategy::<f64>(), nrows, PROPTEST_MATRIX_DIM))
+                }))
+    {
+        let csr = CsrMatrix::from(&a);
+        let lower = DMatrix::from(&a.lower_triangle());
+        let upper = DMatrix::from(&a.upper_triangle());
+        let (unit_lower, unit_upper) = (with_unit_diagonal(&lower), with_unit_diagonal(&upper));
+
+        for &transpose in &[false, true] {
+            let op = |m: &DMatrix<f64>| if transpose { m.transpose() } else { m.clone() };
+            let (csc_op, csr_op) = if transpose {
+                (Op::Transpose(&a), Op::Transpose(&csr))
+            } else {
+                (Op::NoOp(&a), Op::NoOp(&csr))
+            };
+
+            // We're using a high tolerance here because there are some "bad" inputs that can
+            // give severe loss of precision.
+            let mut x = b.clone();
+            spsolve_csc_upper_triangular(csc_op, &mut x).unwrap();
+            prop_assert_matrix_eq!(op(&upper) * &x, &b, comp = abs, tol = 1e-4);
+            let mut x = b.clone();
+            spsolve_csc_unit_lower_triangular(csc_op, &mut x);
+            prop_assert_matrix_eq!(op(&unit_lower) * &x, &b, comp = abs, tol = 1e-4);
+            let mut x = b.clone();
+            spsolve_csc_unit_upper_triangular(csc_op, &mut x);
+            prop_assert_matrix_eq!(op(&unit_upper) * &x, &b, comp = abs, tol = 1e-4);
+
+            let mut x = b.clone();
+            spsolve_csr_lower_triangular(csr_op, &mut x).unwrap();
+            prop_assert_matrix_eq!(op(&lower) * &x, &b, comp = abs, tol = 1e-4);
+            let mut x = b.clone();
+            spsolve_csr_upper_triangular(csr_op, &mut x).unwrap();
+            prop_assert_matrix_eq!(op(&upper) * &x, &b, comp = abs, tol = 1e-4);
+            let mut x = b.clone();
+            spsolve_csr_unit_lower_triangular(csr_op, &mut x);
+            prop_assert_matrix_eq!(op(&unit_lower) * &x, &b, comp = abs, tol = 1e-4);
+            let mut x = b.clone();
+            spsolve_csr_unit_upper_triangular(csr_op, &mut x);
+            prop_assert_matrix_eq!(op(&unit_upper) * &x, &b, comp = abs, tol = 1e-4);
+        }
+    }
+
+    #[test]
+    fn sparse_triangular_solves_agree_with_dense_solves(
+        (a, b) in csc_square_with_non_zero_diagonals_and_sparse_rhs()
+    ) {
+        let b_dense = DMatrix::from(&b);
+
+        let mut expected = b_dense.clone();
+        spsolve_csc_lower_triangular(Op::NoOp(&a), &mut expected).unwrap();
+        let x = spsolve_csc_lower_triangular_sparse(&a, &b).unwrap();
+        prop_assert_matrix_eq!(DMatrix::from(&x), expected, comp = abs, tol = 1e-6);
+
+        let mut expected = b_dense.clone();
+        spsolve_csc_upper_triangular(Op::NoOp(&a), &mut expected).unwrap();
+        let x = spsolve_csc_upper_triangular_sparse(&a, &b).unwrap();
+        prop_assert_matrix_eq!(DMatrix::from(&x), expected, comp = abs, tol = 1e-6);
+
+        let mut expected = b_dense.clone();
+        spsolve_csc_unit_lower_triangular(Op::NoOp(&a), &mut expected);
+        let x = spsolve_csc_unit_lower_triangular_sparse(&a, &b);
+        prop_assert_matrix_eq!(DMatrix::from(&x), expected, comp = abs, tol = 1e-6);
+
+        let mut expected = b_dense;
+        spsolve_csc_unit_upper_triangular(Op::NoOp(&a), &mut expected);
+        let x = spsolve_csc_unit_upper_triangular_sparse(&a, &b);
+        prop_assert_matrix_eq!(DMatrix::from(&x), expected, comp = abs, tol = 1e-6);
+
+        // The pattern of the solution contains the pattern of the right-hand side
+        let x_entries: HashSet<_> = x.triplet_iter().map(|(i, j, _)| (i, j)).collect();
+        prop_assert!(b.triplet_iter().all(|(i, j, _)| x_entries.contains(&(i, j))));
+    }
+
+}
+
+#[test]
+fn triangular_solves_report_zero_diagonal() {
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(3, 3, &[
+        2.0, 0.0, 0.0,
+        1.0, 0.0, 0.0,
+        0.0, 3.0, 1.0,
+    ]);
+    let csc = CscMatrix::from(&dense);
+    let csr = CsrMatrix::from(&dense);
+    let b = DMatrix::from_element(3, 1, 1.0);
+
+    let err = spsolve_csc_upper_triangular(Op::Transpose(&csc), &mut b.clone()).unwrap_err();
+    assert!(matches!(err.kind(), OperationErrorKind::Singular));
+    let err = spsolve_csr_lower_triangular(Op::NoOp(&csr), &mut b.clone()).unwrap_err();
+    assert!(matches!(err.kind(), OperationErrorKind::Singular));
+    let err = spsolve_csc_lower_triangular_sparse(&csc, &CscMatrix::from(&b)).unwrap_err();
+    assert!(matches!(err.kind(), OperationErrorKind::Singular));
+
+    // With a unit diagonal, the stored diagonal is ignored
+    let mut x = b.clone();
+    spsolve_csr_unit_lower_triangular(Op::NoOp(&csr), &mut x);
+    assert_eq!(x, DMatrix::from_column_slice(3, 1, &[1.0, 0.0, 1.0]));
+    let x = spsolve_csc_unit_lower_triangular_sparse(&csc, &CscMatrix::from(&b));
+    assert_eq!(
+        DMatrix::from(&x),
+        DMatrix::from_column_slice(3, 1, &[1.0, 0.0, 1.0])
+    );
+
+    // Only the entries that are reachable from the right-hand side are computed
+    let e2 = CscMatrix::from(&DMatrix::from_column_slice(3, 1, &[0.0, 0.0, 1.0]));
+    let x = spsolve_csc_unit_lower_triangular_sparse(&csc, &e2);
+    assert_eq!(x.nnz(), 1);
+}
+
+fn complex_value_strategy() -> impl Strategy<Value = Complex<f64>> + Clone {
+    (value_strategy::<f64>(), value_strategy::<f64>()).prop_map(|(re, im)| Complex::new(re, im))
+}
+
+/// Generates a complex CSR matrix and a complex dense matrix with the same number of rows.
+fn complex_csr_and_dense_with_same_nrows(
+) -> impl Strategy<Value = (CsrMatrix<Complex<f64>>, DMatrix<Complex<f64>>)> {
+    csr(
+        complex_value_strategy(),
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MAX_NNZ,
+    )
+    .prop_flat_map(|a| {
+        let b = matrix(complex_value_strategy(), a.nrows(), PROPTEST_MATRIX_DIM);
+        (Just(a), b)
+    })
+}
+
+fn assert_complex_approx_eq(actual: &DMatrix<Complex<f64>>, expected: &DMatrix<Complex<f64>>) {
+    assert_eq!(actual.shape(), expected.shape());
+    assert!((actual - expected).norm() <= 1e-10 * expected.norm().max(1.0));
+}
+
+proptest! {
+    #[test]
+    fn complex_adjoint_agrees_with_dense(
+        (a, _) in complex_csr_and_dense_with_same_nrows()
+    ) {
+        let dense = DMatrix::from(&a);
+        prop_assert_eq!(DMatrix::from(&a.adjoint()), dense.adjoint());
+        prop_assert_eq!(DMatrix::from(&CscMatrix::from(&a).adjoint()), dense.adjoint());
+    }
+
+    #[test]
+    fn complex_spmm_dense_adjoint_agrees_with_dense(
+        (a, b) in complex_csr_and_dense_with_same_nrows()
+    ) {
+        let expected = DMatrix::from(&a).adjoint() * &b;
+        let b_adjoint = b.adjoint();
+        let zero = Complex::new(0.0, 0.0);
+        let one = Complex::new(1.0, 0.0);
+        let mut c = DMatrix::zeros(a.ncols(), b.ncols());
+
+        spmm_csr_dense_adjoint(zero, &mut c, one, &a, Op::NoOp(&b));
+        assert_complex_approx_eq(&c, &expected);
+        // The transpose of the adjoint of B is the conjugate of B, which must not be conjugated
+        spmm_csr_dense_adjoint(zero, &mut c, one, &a, Op::Transpose(&b_adjoint.conjugate()));
+        assert_complex_approx_eq(&c, &expected);
+
+        let a_csc = CscMatrix::from(&a);
+        spmm_csc_dense_adjoint(zero, &mut c, one, &a_csc, Op::NoOp(&b));
+        assert_complex_approx_eq(&c, &expected);
+        spmm_csc_dense_adjoint(zero, &mut c, one, &a_csc, Op::Transpose(&b.transpose()));
+        assert_complex_approx_eq(&c, &expected);
+    }
+
+    #[test]
+    fn complex_sparse_ops_with_adjoint_agree_with_dense(
+        (a, b) in complex_csr_and_dense_with_same_nrows()
+    ) {
+        let dense = DMatrix::from(&a);
+        let zero = Complex::new(0.0, 0.0);
+        let one = Complex::new(1.0, 0.0);
+
+        // C <- C + A^H, where C has the pattern of A^H
+        let mut c = a.adjoint();
+        spadd_csr_prealloc_adjoint(one, &mut c, one, &a).unwrap();
+        assert_complex_approx_eq(&DMatrix::from(&c), &(dense.adjoint() * Complex::new(2.0, 0.0)));
+
+        let a_csc = CscMatrix::from(&a);
+        let mut c = a_csc.adjoint();
+        spadd_csc_prealloc_adjoint(zero, &mut c, one, &a_csc).unwrap();
+        assert_complex_approx_eq(&DMatrix::from(&c), &dense.adjoint());
+
+        // y = A^H x for a sparse vector x, given by the sum of the columns of B
+        let x_dense = b.column_sum();
+        let x = SparseVector::try_from_indices_and_values(
+            x_dense.len(),
+            (0..x_dense.len()).collect(),
+            x_dense.iter().cloned().collect(),
+        ).unwrap();
+        let as_matrix = |v: DVector<Complex<f64>>| DMatrix::from_column_slice(v.len(), 1, v.as_slice());
+        let expected = as_matrix(dense.adjoint() * &x_dense);
+        let y = as_matrix(DVector::from(&spmv_csr_sparse_adjoint(&a, &x)));
+        assert_complex_approx_eq(&y, &expected);
+        let y = as_matrix(DVector::from(&spmv_csc_sparse_adjoint(&a_csc, &x)));
+        assert_complex_approx_eq(&y, &expected);
+    }
+
+    #[test]
+    fn complex_triangular_solves_with_adjoint_agree_with_dense(
+        (l, b) in csc(complex_value_strategy(), PROPTEST_MATRIX_DIM, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ)
+            .prop_filter("Matrix must be square", |m| m.nrows() == m.ncols())
+            .prop_flat_map(|m| {
+                // Replace the diagonal so that the matrix is well-conditioned
+                let mut l = m.lower_triangle();
+                l.retain(|i, j, _| i != j);
+                let diagonal = CscMatrix::identity(m.nrows()) * Complex::new(10.0, 2.0);
+                let b = matrix(complex_value_strategy(), m.nrows(), PROPTEST_MATRIX_DIM);
+                (Just(l + diagonal), b)
+            })
+    ) {
+        let l_dense = DMatrix::from(&l);
+
+        let mut x = b.clone();
+        spsolve_csc_lower_triangular_adjoint(&l, &mut x).unwrap();
+        assert_complex_approx_eq(&(l_dense.adjoint() * &x), &b);
+
+        let u = l.transpose();
+        let mut x = b.clone();
+        spsolve_csc_upper_triangular_adjoint(&u, &mut x).unwrap();
+        assert_complex_approx_eq(&(l_dense.conjugate() * &x), &b);
+
+        let l_csr = CsrMatrix::from(&l);
+        let mut x = b.clone();
+        spsolve_csr_lower_triangular_adjoint(&l_csr, &mut x).unwrap();
+        assert_complex_approx_eq(&(l_dense.adjoint() * &x), &b);
+
+        let u_csr = CsrMatrix::from(&u);
+        let mut x = b.clone();
+        spsolve_csr_upper_triangular_adjoint(&u_csr, &mut x).unwrap();
+        assert_complex_approx_eq(&(l_dense.conjugate() * &x), &b);
+    }
 }
@@ -0,0 +1,322 @@
+use crate::common::{PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MAX_NNZ};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::{parallel, serial, Op};
+use nalgebra_sparse::pattern::SparsityPattern;
+use nalgebra_sparse::proptest::{csr, sparsity_pattern};
+
+use nalgebra::proptest::matrix;
+use nalgebra::DMatrix;
+
+use proptest::prelude::*;
+
+use std::ops::RangeInclusive;
+
+/// Somewhat larger than the dimensions used for the serial tests, so that the work is actually
+/// distributed across several threads.
+const MATRIX_DIM: RangeInclusive<usize> = 0..=20;
+
+fn op_from_bool<T>(is_transposed: bool, obj: T) -> Op<T> {
+    if is_transposed {
+        Op::Transpose(obj)
+    } else {
+        Op::NoOp(obj)
+    }
+}
+
+/// Returns a CSR matrix `A` such that `op(A)` has the given dimensions.
+fn op_csr_strategy(nrows: usize, ncols: usize) -> impl Strategy<Value = Op<CsrMatrix<i32>>> {
+    proptest::bool::ANY.prop_flat_map(move |is_transposed| {
+        let (r, c) = if is_transposed {
+            (ncols, nrows)
+        } else {
+            (nrows, ncols)
+        };
+        csr(PROPTEST_I32_VALUE_STRATEGY, r..=r, c..=c, PROPTEST_MAX_NNZ)
+            .prop_map(move |a| op_from_bool(is_transposed, a))
+    })
+}
+
+/// Returns a dense matrix `B` such that `op(B)` has the given dimensions.
+fn op_dense_strategy(nrows: usize, ncols: usize) -> impl Strategy<Value = Op<DMatrix<i32>>> {
+    proptest::bool::ANY.prop_flat_map(move |is_transposed| {
+        let (r, c) = if is_transposed {
+            (ncols, nrows)
+        } else {
+            (nrows, ncols)
+        };
+        matrix(PROPTEST_I32_VALUE_STRATEGY, r..=r, c..=c)
+            .prop_map(move |b| op_from_bool(is_transposed, b))
+    })
+}
+
+/// Returns `(C, beta, alpha, op(A), op(B))` with dimensions compatible with a sparse-sparse
+/// product.
+///
+/// The pattern of `C` is either the pattern of the product, such that the operation succeeds,
+/// or a random pattern, such that the operation most likely fails.
+#[allow(clippy::type_complexity)]
+fn spmm_csr_args_strategy() -> impl Strategy<
+    Value = (
+        CsrMatrix<i32>,
+        i32,
+        i32,
+        Op<CsrMatrix<i32>>,
+        Op<CsrMatrix<i32>>,
+    ),
+> {
+    (MATRIX_DIM, MATRIX_DIM, MATRIX_DIM)
+        .prop_flat_map(|(m, k, n)| (op_csr_strategy(m, k), op_csr_strategy(k, n)))
+        .prop_flat_map(|(a, b)| {
+            let product = owned_op_product(&a, &b);
+            let (m, n) = (product.nrows(), product.ncols());
+            let c_pattern = prop_oneof![
+                Just(product.pattern().clone()),
+                sparsity_pattern(m..=m, n..=n, PROPTEST_MAX_NNZ),
+            ];
+            let c = c_pattern.prop_flat_map(|pattern| {
+                let values = vec![PROPTEST_I32_VALUE_STRATEGY; pattern.nnz()];
+                values.prop_map(move |values| {
+                    CsrMatrix::try_from_pattern_and_values(pattern.clone(), values).unwrap()
+                })
+            });
+            let scalar = PROPTEST_I32_VALUE_STRATEGY;
+            (c, scalar.clone(), scalar, Just(a), Just(b))
+        })
+}
+
+fn owned_op_product(a: &Op<CsrMatrix<i32>>, b: &Op<CsrMatrix<i32>>) -> CsrMatrix<i32> {
+    let evaluate = |op: &Op<CsrMatrix<i32>>| match op {
+        Op::NoOp(x) => x.clone(),
+        Op::Transpose(x) => x.transpose(),
+    };
+    evaluate(a) * evaluate(b)
+}
+
+fn to_csc(op: &Op<CsrMatrix<i32>>) -> Op<CscMatrix<i32>> {
+    op.as_ref().map_same_op(CscMatrix::from)
+}
+
+/// A deterministic square matrix with a few non-zeros per row at pseudo-random locations.
+fn large_csr(n: usize) -> CsrMatrix<i64> {
+    let mut coo = CooMatrix::new(n, n);
+    for i in 0..n {
+        coo.push(i, i, 4);
+        for k in 1..5 {
+            let j = (i * 7919 + k * 104_729) % n;
+            coo.push(i, j, (i % 5) as i64 - k as i64);
+        }
+    }
+    CsrMatrix::from(&coo)
+}
+
+proptest! {
+    #[test]
+    fn spmm_csr_dense_matches_serial(
+        (c, beta, alpha, a, b) in (MATRIX_DIM, MATRIX_DIM, MATRIX_DIM)
+            .prop_flat_map(|(m, k, n)| (
+                matrix(PROPTEST_I32_VALUE_STRATEGY, m..=m, n..=n),
+                PROPTEST_I32_VALUE_STRATEGY,
+                PROPTEST_I32_VALUE_STRATEGY,
+                op_csr_strategy(m, k),
+                op_dense_strategy(k, n),
+            ))
+    ) {
+        let mut c_serial = c.clone();
+        let mut c_parallel = c;
+        let b = b.as_ref().map_same_op(|b| b.as_view());
+        serial::spmm_csr_dense(beta, &mut c_serial, alpha, a.as_ref(), b);
+        parallel::spmm_csr_dense(beta, &mut c_parallel, alpha, a.as_ref(), b);
+        prop_assert_eq!(c_parallel, c_serial);
+    }
+
+    #[test]
+    fn spmm_csc_dense_matches_serial(
+        (c, beta, alpha, a, b) in (MATRIX_DIM, MATRIX_DIM, MATRIX_DIM)
+            .prop_flat_map(|(m, k, n)| (
+                matrix(PROPTEST_I32_VALUE_STRATEGY, m..=m, n..=n),
+                PROPTEST_I32_VALUE_STRATEGY,
+                PROPTEST_I32_VALUE_STRATEGY,
+                op_csr_strategy(m, k),
+                op_dense_strategy(k, n),
+            ))
+    ) {
+        let a = to_csc(&a);
+        let mut c_serial = c.clone();
+        let mut c_parallel = c;
+        let b = b.as_ref().map_same_op(|b| b.as_view());
+        serial::spmm_csc_dense(beta, &mut c_serial, alpha, a.as_ref(), b);
+        parallel::spmm_csc_dense(beta, &mut c_parallel, alpha, a.as_ref(), b);
+        prop_assert_eq!(c_parallel, c_serial);
+    }
+
+    #[test]
+    fn spmm_csr_prealloc_matches_serial((c, beta, alpha, a, b) in spmm_csr_args_strategy()) {
+        let (a, b) = (a.as_ref(), b.as_ref());
+
+        let mut c_serial = c.clone();
+        let mut c_parallel = c.clone();
+        let result_serial = serial::spmm_csr_prealloc(beta, &mut c_serial, alpha, a, b);
+        let result_parallel = parallel::spmm_csr_prealloc(beta, &mut c_parallel, alpha, a, b);
+        prop_assert_eq!(result_parallel.is_ok(), result_serial.is_ok());
+        // On failure, the state of C is unspecified
+        if result_serial.is_ok() {
+            prop_assert_eq!(&c_parallel, &c_serial);
+
+            let mut c_unchecked = c;
+            parallel::spmm_csr_prealloc_unchecked(beta, &mut c_unchecked, alpha, a, b).unwrap();
+            prop_assert_eq!(c_unchecked, c_serial);
+        }
+    }
+
+    #[test]
+    fn spmm_csc_prealloc_matches_serial((c, beta, alpha, a, b) in spmm_csr_args_strategy()) {
+        let c = CscMatrix::from(&c);
+        let (a, b) = (to_csc(&a), to_csc(&b));
+        let (a, b) = (a.as_ref(), b.as_ref());
+
+        let mut c_serial = c.clone();
+        let mut c_parallel = c.clone();
+        let result_serial = serial::spmm_csc_prealloc(beta, &mut c_serial, alpha, a, b);
+        let result_parallel = parallel::spmm_csc_prealloc(beta, &mut c_parallel, alpha, a, b);
+        prop_assert_eq!(result_parallel.is_ok(), result_serial.is_ok());
+        if result_serial.is_ok() {
+            prop_assert_eq!(&c_parallel, &c_serial);
+
+            let mut c_unchecked = c;
+            parallel::spmm_csc_prealloc_unchecked(beta, &mut c_unchecked, alpha, a, b).unwrap();
+            prop_assert_eq!(c_unchecked, c_serial);
+        }
+    }
+
+    #[test]
+    fn spadd_prealloc_matches_serial(
+        (c, beta, alpha, a) in (MATRIX_DIM, MATRIX_DIM)
+            .prop_flat_map(|(m, n)| (
+                csr(PROPTEST_I32_VALUE_STRATEGY, m..=m, n..=n, PROPTEST_MAX_NNZ),
+                PROPTEST_I32_VALUE_STRATEGY,
+                PROPTEST_I32_VALUE_STRATEGY,
+                op_csr_strategy(m, n),
+                proptest::bool::ANY,
+            ))
+            .prop_map(|(c, beta, alpha, a, fits)| {
+                // Make room for A in C, unless we want to exercise the error path
+                let c = if fits {
+                    let a_dense = match a.as_ref() {
+                        Op::NoOp(a) => DMatrix::from(a),
+                        Op::Transpose(a) => DMatrix::from(a).transpose(),
+                    };
+                    let pattern = serial::spadd_pattern(
+                        c.pattern(),
+                        CsrMatrix::from(&a_dense.map(|_| 1)).pattern(),
+                    );
+                    let values = (0..pattern.nnz()).map(|i| i as i32 % 5 - 2).collect();
+                    CsrMatrix::try_from_pattern_and_values(pattern, values).unwrap()
+                } else {
+                    c
+                };
+                (c, beta, alpha, a)
+            })
+    ) {
+        let mut c_serial = c.clone();
+        let mut c_parallel = c.clone();
+        let result_serial = serial::spadd_csr_prealloc(beta, &mut c_serial, alpha, a.as_ref());
+        let result_parallel =
+            parallel::spadd_csr_prealloc(beta, &mut c_parallel, alpha, a.as_ref());
+        prop_assert_eq!(result_parallel.is_ok(), result_serial.is_ok());
+        if result_serial.is_ok() {
+            prop_assert_eq!(c_parallel, c_serial);
+        }
+
+        let c = CscMatrix::from(&c);
+        let a = to_csc(&a);
+        let mut c_serial = c.clone();
+        let mut c_parallel = c;
+        let result_serial = serial::spadd_csc_prealloc(beta, &mut c_serial, alpha, a.as_ref());
+        let result_parallel =
+            parallel::spadd_csc_prealloc(beta, &mut c_parallel, alpha, a.as_ref());
+        prop_assert_eq!(result_parallel.is_ok(), result_serial.is_ok());
+        if result_serial.is_ok() {
+            prop_assert_eq!(c_parallel, c_serial);
+        }
+    }
+
+    #[test]
+    fn spadd_pattern_matches_serial(
+        (a, b) in (MATRIX_DIM, MATRIX_DIM).prop_flat_map(|(m, n)| (
+            sparsity_pattern(m..=m, n..=n, PROPTEST_MAX_NNZ),
+            sparsity_pattern(m..=m, n..=n, PROPTEST_MAX_NNZ),
+        ))
+    ) {
+        prop_assert_eq!(parallel::spadd_pattern(&a, &b), serial::spadd_pattern(&a, &b));
+    }
+
+    #[test]
+    fn spmm_pattern_matches_serial(
+        (a, b) in (MATRIX_DIM, MATRIX_DIM, MATRIX_DIM).prop_flat_map(|(m, k, n)| (
+            sparsity_pattern(m..=m, k..=k, PROPTEST_MAX_NNZ),
+            sparsity_pattern(k..=k, n..=n, PROPTEST_MAX_NNZ),
+        ))
+    ) {
+        let expected: SparsityPattern = serial::spmm_csr_pattern(&a, &b);
+        prop_assert_eq!(parallel::spmm_csr_pattern(&a, &b), expected);
+        // Interpreted as CSC patterns, b and a represent B^T and A^T
+        prop_assert_eq!(parallel::spmm_csc_pattern(&b, &a), serial::spmm_csc_pattern(&b, &a));
+    }
+}
+
+#[test]
+fn parallel_kernels_match_serial_on_large_matrices() {
+    let n = 3000;
+    let a = large_csr(n);
+    let a_csc = CscMatrix::from(&a);
+
+    // SpMV and SpMM with a few columns, large enough to be split into many blocks
+    for ncols in [1, 3] {
+        let b = DMatrix::from_fn(n, ncols, |i, j| (i * (j + 2)) as i64 % 11 - 5);
+        let c = DMatrix::from_fn(n, ncols, |i, j| (i + j) as i64 % 3);
+        for a_op in [Op::NoOp(&a), Op::Transpose(&a)] {
+            let mut c_serial = c.clone();
+            let mut c_parallel = c.clone();
+            serial::spmm_csr_dense(2, &mut c_serial, 3, a_op, Op::NoOp(&b));
+            parallel::spmm_csr_dense(2, &mut c_parallel, 3, a_op, Op::NoOp(&b));
+            assert_eq!(c_parallel, c_serial);
+        }
+        for a_op in [Op::NoOp(&a_csc), Op::Transpose(&a_csc)] {
+            let mut c_serial = c.clone();
+            let mut c_parallel = c.clone();
+            serial::spmm_csc_dense(2, &mut c_serial, 3, a_op, Op::NoOp(&b));
+            parallel::spmm_csc_dense(2, &mut c_parallel, 3, a_op, Op::NoOp(&b));
+            assert_eq!(c_parallel, c_serial);
+        }
+    }
+
+    // Sparse-sparse product with a preallocated pattern
+    let pattern = parallel::spmm_csr_pattern(a.pattern(), a.pattern());
+    assert_eq!(pattern, serial::spmm_csr_pattern(a.pattern(), a.pattern()));
+    let nnz = pattern.nnz();
+    let c = CsrMatrix::try_from_pattern_and_values(pattern, vec![1; nnz]).unwrap();
+    let mut c_serial = c.clone();
+    let mut c_parallel = c;
+    serial::spmm_csr_prealloc(2, &mut c_serial, 1, Op::NoOp(&a), Op::NoOp(&a)).unwrap();
+    parallel::spmm_csr_prealloc(2, &mut c_parallel, 1, Op::NoOp(&a), Op::NoOp(&a)).unwrap();
+    assert_eq!(c_parallel, c_serial);
+
+    // Sparse addition with a transposed operand
+    let pattern = parallel::spadd_pattern(a.pattern(), a.transpose().pattern());
+    let nnz = pattern.nnz();
+    let c = CsrMatrix::try_from_pattern_and_values(pattern, vec![1; nnz]).unwrap();
+    let mut c_serial = c.clone();
+    let mut c_parallel = c;
+    serial::spadd_csr_prealloc(2, &mut c_serial, 1, Op::Transpose(&a)).unwrap();
+    parallel::spadd_csr_prealloc(2, &mut c_parallel, 1, Op::Transpose(&a)).unwrap();
+    assert_eq!(c_parallel, c_serial);
+}
+
+#[test]
+fn parallel_spmm_csr_prealloc_reports_missing_entries() {
+    let a = large_csr(500);
+    let mut c = CsrMatrix::identity(500);
+    assert!(parallel::spmm_csr_prealloc(0, &mut c, 1, Op::NoOp(&a), Op::NoOp(&a)).is_err());
+}
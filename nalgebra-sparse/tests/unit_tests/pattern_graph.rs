@@ -0,0 +1,225 @@
+use crate::assert_panics;
+use crate::common::PROPTEST_I32_VALUE_STRATEGY;
+use nalgebra_sparse::factorization::CscSymbolicCholesky;
+use nalgebra_sparse::ops::serial::spadd_pattern;
+use nalgebra_sparse::pattern::graph::{
+    bandwidth, bfs_levels, connected_components, distance2_coloring, elimination_tree,
+    greedy_coloring, postorder, profile, pseudo_peripheral_node,
+};
+use nalgebra_sparse::pattern::SparsityPattern;
+use nalgebra_sparse::proptest::csr;
+
+use proptest::prelude::*;
+
+/// Generates a square, structurally symmetric pattern.
+fn symmetric_pattern_strategy() -> impl Strategy<Value = SparsityPattern> {
+    (0..=8usize)
+        .prop_flat_map(|n| csr(PROPTEST_I32_VALUE_STRATEGY, n, n, 20))
+        .prop_map(|a| spadd_pattern(a.pattern(), &a.pattern().transpose()))
+}
+
+/// Generates a square, structurally symmetric pattern with at least one node, and a node.
+fn symmetric_pattern_and_node_strategy() -> impl Strategy<Value = (SparsityPattern, usize)> {
+    symmetric_pattern_strategy()
+        .prop_filter("pattern must have nodes", |p| p.major_dim() > 0)
+        .prop_flat_map(|p| {
+            let n = p.major_dim();
+            (Just(p), 0..n)
+        })
+}
+
+/// The pattern of the path graph `0 - 1 - ... - (n - 1)`, including the diagonal.
+fn path_pattern(n: usize) -> SparsityPattern {
+    let mut offsets = vec![0];
+    let mut indices = Vec::new();
+    for i in 0..n {
+        indices.extend((i.saturating_sub(1)..(i + 2).min(n)).collect::<Vec<_>>());
+        offsets.push(indices.len());
+    }
+    SparsityPattern::try_from_offsets_and_indices(n, n, offsets, indices).unwrap()
+}
+
+fn levels_of(pattern: &SparsityPattern, root: usize) -> Vec<Option<usize>> {
+    let mut level = vec![None; pattern.major_dim()];
+    for (k, nodes) in bfs_levels(pattern, root).iter().enumerate() {
+        for &i in nodes {
+            assert!(level[i].is_none());
+            level[i] = Some(k);
+        }
+    }
+    level
+}
+
+#[test]
+fn path_graph_properties() {
+    let pattern = path_pattern(5);
+    assert_eq!(connected_components(&pattern), (1, vec![0; 5]));
+    assert_eq!(
+        bfs_levels(&pattern, 2),
+        vec![vec![2], vec![1, 3], vec![0, 4]]
+    );
+    assert_eq!(pseudo_peripheral_node(&pattern, 2), 0);
+    assert_eq!(bandwidth(&pattern), 1);
+    assert_eq!(profile(&pattern), 4);
+    assert_eq!(
+        elimination_tree(&pattern),
+        vec![Some(1), Some(2), Some(3), Some(4), None]
+    );
+    assert_eq!(greedy_coloring(&pattern), (2, vec![0, 1, 0, 1, 0]));
+    assert_eq!(distance2_coloring(&pattern), (3, vec![0, 1, 2, 0, 1]));
+}
+
+#[test]
+fn graph_functions_on_empty_pattern() {
+    let pattern = SparsityPattern::zeros(0, 0);
+    assert_eq!(connected_components(&pattern), (0, vec![]));
+    assert_eq!(bandwidth(&pattern), 0);
+    assert_eq!(profile(&pattern), 0);
+    assert_eq!(elimination_tree(&pattern), vec![]);
+    assert_eq!(greedy_coloring(&pattern), (0, vec![]));
+    assert_eq!(postorder(&[]), Vec::<usize>::new());
+}
+
+#[test]
+fn postorder_of_forest() {
+    //      4       6
+    //    / | \     |
+    //   0  2  3    5
+    //      |
+    //      1
+    let parent = [Some(4), Some(2), Some(4), Some(4), None, Some(6), None];
+    assert_eq!(postorder(&parent), vec![0, 1, 2, 3, 4, 5, 6]);
+
+    let parent = [None, Some(0), Some(0), Some(1)];
+    assert_eq!(postorder(&parent), vec![3, 1, 2, 0]);
+}
+
+#[test]
+fn graph_functions_panic_on_invalid_input() {
+    let non_square = SparsityPattern::zeros(2, 3);
+    assert_panics!(connected_components(&non_square));
+    assert_panics!(elimination_tree(&non_square));
+    assert_panics!(greedy_coloring(&non_square));
+    assert_panics!(bfs_levels(&path_pattern(3), 3));
+    // A cycle is not a forest
+    assert_panics!(postorder(&[Some(1), Some(0)]));
+}
+
+proptest! {
+    #[test]
+    fn connected_components_agree_with_bfs(pattern in symmetric_pattern_strategy()) {
+        let (num_components, labels) = connected_components(&pattern);
+        prop_assert_eq!(labels.len(), pattern.major_dim());
+
+        // The components are numbered in increasing order of their smallest node
+        let mut next_label = 0;
+        for &label in &labels {
+            prop_assert!(label <= next_label);
+            if label == next_label {
+                next_label += 1;
+            }
+        }
+        prop_assert_eq!(next_label, num_components);
+
+        for root in 0..pattern.major_dim() {
+            let level = levels_of(&pattern, root);
+            for i in 0..pattern.major_dim() {
+                prop_assert_eq!(level[i].is_some(), labels[i] == labels[root]);
+            }
+        }
+    }
+
+    #[test]
+    fn bfs_levels_are_distances((pattern, root) in symmetric_pattern_and_node_strategy()) {
+        let level = levels_of(&pattern, root);
+        prop_assert_eq!(level[root], Some(0));
+        for (i, j) in pattern.entries() {
+            match (level[i], level[j]) {
+                (Some(a), Some(b)) => prop_assert!(a.max(b) - a.min(b) <= 1),
+                (a, b) => prop_assert!(a.is_none() && b.is_none()),
+            }
+        }
+        // Every node except the root has a neighbor in the previous level
+        for (i, &l) in level.iter().enumerate() {
+            if let Some(l) = l.filter(|&l| l > 0) {
+                prop_assert!(pattern.lane(i).iter().any(|&j| level[j] == Some(l - 1)));
+            }
+        }
+    }
+
+    #[test]
+    fn pseudo_peripheral_node_is_at_least_as_eccentric(
+        (pattern, start) in symmetric_pattern_and_node_strategy()
+    ) {
+        let node = pseudo_peripheral_node(&pattern, start);
+        let (_, labels) = connected_components(&pattern);
+        prop_assert_eq!(labels[node], labels[start]);
+        prop_assert!(bfs_levels(&pattern, node).len() >= bfs_levels(&pattern, start).len());
+    }
+
+    #[test]
+    fn bandwidth_and_profile_agree_with_entries(pattern in symmetric_pattern_strategy()) {
+        let expected_bandwidth = pattern
+            .entries()
+            .map(|(i, j)| i.abs_diff(j))
+            .max()
+            .unwrap_or(0);
+        prop_assert_eq!(bandwidth(&pattern), expected_bandwidth);
+
+        let expected_profile: usize = (0..pattern.major_dim())
+            .map(|i| {
+                pattern
+                    .entries()
+                    .filter(|&(r, c)| r == i && c <= i)
+                    .map(|(_, c)| i - c)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum();
+        prop_assert_eq!(profile(&pattern), expected_profile);
+    }
+
+    #[test]
+    fn elimination_tree_agrees_with_cholesky_pattern(pattern in symmetric_pattern_strategy()) {
+        let parent = elimination_tree(&pattern);
+        let symbolic = CscSymbolicCholesky::factor(pattern.clone());
+        let l_pattern = symbolic.l_pattern();
+        // The parent of i is the row of the first off-diagonal entry in column i of L
+        for (i, &p) in parent.iter().enumerate() {
+            let expected = l_pattern.lane(i).iter().copied().find(|&k| k > i);
+            prop_assert_eq!(p, expected);
+        }
+
+        let order = postorder(&parent);
+        let mut position = vec![0; order.len()];
+        for (p, &i) in order.iter().enumerate() {
+            position[i] = p;
+        }
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        prop_assert_eq!(sorted, (0..pattern.major_dim()).collect::<Vec<_>>());
+        for (i, &p) in parent.iter().enumerate() {
+            if let Some(p) = p {
+                prop_assert!(position[i] < position[p]);
+            }
+        }
+    }
+
+    #[test]
+    fn colorings_are_valid(pattern in symmetric_pattern_strategy()) {
+        let (num_colors, colors) = greedy_coloring(&pattern);
+        prop_assert!(colors.iter().all(|&c| c < num_colors));
+        for (i, j) in pattern.entries() {
+            prop_assert!(i == j || colors[i] != colors[j]);
+        }
+
+        let (num_colors, colors) = distance2_coloring(&pattern);
+        prop_assert!(colors.iter().all(|&c| c < num_colors));
+        for (i, j) in pattern.entries() {
+            prop_assert!(i == j || colors[i] != colors[j]);
+            for &k in pattern.lane(j) {
+                prop_assert!(i == k || colors[i] != colors[k]);
+            }
+        }
+    }
+}
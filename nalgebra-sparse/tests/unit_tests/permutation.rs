@@ -0,0 +1,136 @@
+use crate::assert_panics;
+use crate::common::{csc_strategy, csr_strategy};
+use nalgebra::DMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::permutation::Permutation;
+use nalgebra_sparse::SparseFormatErrorKind;
+
+use proptest::prelude::*;
+
+fn permutation(n: usize) -> impl Strategy<Value = Permutation> {
+    Just((0..n).collect::<Vec<_>>())
+        .prop_shuffle()
+        .prop_map(|perm| Permutation::try_from_vec(perm).unwrap())
+}
+
+/// Generates a matrix along with permutations of its rows and columns.
+fn csr_with_permutations() -> impl Strategy<Value = (CsrMatrix<i32>, Permutation, Permutation)> {
+    csr_strategy().prop_flat_map(|csr| {
+        let (nrows, ncols) = (csr.nrows(), csr.ncols());
+        (Just(csr), permutation(nrows), permutation(ncols))
+    })
+}
+
+/// Generates a square matrix along with a permutation of its rows and columns.
+fn square_csr_with_permutation() -> impl Strategy<Value = (CsrMatrix<i32>, Permutation)> {
+    csr_strategy().prop_flat_map(|csr| {
+        let n = csr.nrows().min(csr.ncols());
+        let square = csr.submatrix(0..n, 0..n);
+        (Just(square), permutation(n))
+    })
+}
+
+/// The permutation matrix `P` with ones at `(i, p[i])`.
+fn permutation_matrix(perm: &Permutation) -> DMatrix<i32> {
+    let n = perm.len();
+    DMatrix::from_fn(n, n, |i, j| (perm.as_slice()[i] == j) as i32)
+}
+
+#[test]
+fn permutation_try_from_vec() {
+    let perm = Permutation::try_from_vec(vec![2, 0, 1]).unwrap();
+    assert_eq!(perm.len(), 3);
+    assert_eq!(perm.as_slice(), &[2, 0, 1]);
+    assert_eq!(perm.inverse_slice(), &[1, 2, 0]);
+    assert_eq!(perm.permute_slice(&['a', 'b', 'c']), vec!['c', 'a', 'b']);
+    assert_eq!(perm.inverse().as_slice(), &[1, 2, 0]);
+    assert_eq!(perm.clone().into_vec(), vec![2, 0, 1]);
+
+    assert!(Permutation::try_from_vec(vec![]).unwrap().is_empty());
+
+    let err = Permutation::try_from_vec(vec![0, 3, 1]).unwrap_err();
+    assert_eq!(err.kind(), &SparseFormatErrorKind::IndexOutOfBounds);
+    let err = Permutation::try_from_vec(vec![0, 1, 1]).unwrap_err();
+    assert_eq!(err.kind(), &SparseFormatErrorKind::DuplicateEntry);
+}
+
+#[test]
+fn permutation_compose() {
+    let p = Permutation::try_from_vec(vec![1, 2, 0]).unwrap();
+    let q = Permutation::try_from_vec(vec![0, 2, 1]).unwrap();
+    let x = [10, 20, 30];
+
+    let pq = p.compose(&q);
+    assert_eq!(pq.permute_slice(&x), p.permute_slice(&q.permute_slice(&x)));
+    assert_eq!(pq.inverse(), q.inverse().compose(&p.inverse()));
+    assert_eq!(p.compose(&p.inverse()), Permutation::identity(3));
+    assert_panics!(p.compose(&Permutation::identity(2)));
+}
+
+#[test]
+fn permute_dimension_mismatch_panics() {
+    let csr = CsrMatrix::<f64>::zeros(2, 3);
+    let csc = CscMatrix::<f64>::zeros(2, 3);
+    let (p2, p3) = (Permutation::identity(2), Permutation::identity(3));
+    assert_panics!(csr.permute_rows(&p3));
+    assert_panics!(csr.permute_columns(&p2));
+    assert_panics!(csr.permute_symmetric(&p2));
+    assert_panics!(csc.permute_rows(&p3));
+    assert_panics!(csc.permute_columns(&p2));
+    assert_panics!(csc.permute_symmetric(&p2));
+    assert_panics!(csr.pattern().permute_major(&p3));
+    assert_panics!(csr.pattern().permute_minor(&p2));
+    assert_panics!(csr.pattern().permute_symmetric(&p2));
+}
+
+proptest! {
+    #[test]
+    fn csr_permutation_agrees_with_dense((csr, p, q) in csr_with_permutations()) {
+        let dense = DMatrix::from(&csr);
+        let (p_dense, q_dense) = (permutation_matrix(&p), permutation_matrix(&q));
+
+        prop_assert_eq!(DMatrix::from(&csr.permute_rows(&p)), &p_dense * &dense);
+        prop_assert_eq!(DMatrix::from(&csr.permute_columns(&q)), &dense * q_dense.transpose());
+        prop_assert_eq!(csr.permute_rows(&p).permute_rows(&p.inverse()), csr);
+    }
+
+    #[test]
+    fn csc_permutation_agrees_with_csr((csr, p, q) in csr_with_permutations()) {
+        let csc = CscMatrix::from(&csr);
+        prop_assert_eq!(csc.permute_rows(&p), CscMatrix::from(&csr.permute_rows(&p)));
+        prop_assert_eq!(csc.permute_columns(&q), CscMatrix::from(&csr.permute_columns(&q)));
+    }
+
+    #[test]
+    fn pattern_permutation_agrees_with_csr((csr, p, q) in csr_with_permutations()) {
+        let pattern = csr.pattern();
+        let (rows_permuted, cols_permuted) = (csr.permute_rows(&p), csr.permute_columns(&q));
+        prop_assert_eq!(&pattern.permute_major(&p), rows_permuted.pattern());
+        prop_assert_eq!(&pattern.permute_minor(&q), cols_permuted.pattern());
+    }
+
+    #[test]
+    fn symmetric_permutation_agrees_with_dense((csr, p) in square_csr_with_permutation()) {
+        let dense = DMatrix::from(&csr);
+        let p_dense = permutation_matrix(&p);
+        let expected = &p_dense * &dense * p_dense.transpose();
+
+        let permuted = csr.permute_symmetric(&p);
+        prop_assert_eq!(DMatrix::from(&permuted), expected.clone());
+        prop_assert_eq!(DMatrix::from(&CscMatrix::from(&csr).permute_symmetric(&p)), expected);
+        prop_assert_eq!(&csr.pattern().permute_symmetric(&p), permuted.pattern());
+    }
+
+    #[test]
+    fn csc_permutations_compose(csc in csc_strategy()) {
+        let n = csc.nrows();
+        let reversed = Permutation::try_from_vec((0..n).rev().collect()).unwrap();
+        let shifted = Permutation::try_from_vec((0..n).map(|i| (i + 1) % n).collect()).unwrap();
+        let composed = reversed.compose(&shifted);
+        prop_assert_eq!(
+            csc.permute_rows(&composed),
+            csc.permute_rows(&shifted).permute_rows(&reversed)
+        );
+    }
+}
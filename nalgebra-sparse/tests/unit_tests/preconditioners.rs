@@ -0,0 +1,307 @@
+use crate::assert_panics;
+use crate::common::{value_strategy, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::factorization::{CholeskyError, CscCholesky};
+use nalgebra_sparse::ops::serial::OperationErrorKind;
+use nalgebra_sparse::proptest::csr;
+use nalgebra_sparse::solvers::{Ic0, Ilu0, Jacobi, Preconditioner, Ssor};
+
+use nalgebra::proptest::{matrix, vector};
+use nalgebra::{DMatrix, DVector};
+
+use matrixcompare::{assert_matrix_eq, prop_assert_matrix_eq};
+use proptest::prelude::*;
+
+/// Applies the preconditioner to `r`, checking that the initial contents of `z` are not read.
+fn apply<P: Preconditioner<f64>>(preconditioner: &P, r: &DVector<f64>) -> DVector<f64> {
+    let mut z = DVector::repeat(r.nrows(), f64::NAN);
+    preconditioner.apply(r.into(), (&mut z).into());
+    z
+}
+
+/// Adds a diagonal to the matrix that makes it strictly diagonally dominant.
+fn add_dominant_diagonal(x: CsrMatrix<f64>) -> CsrMatrix<f64> {
+    let n = x.nrows();
+    let mut diagonal = CooMatrix::new(n, n);
+    for (i, row) in x.row_iter().enumerate() {
+        let abs_sum: f64 = row.values().iter().map(|v| v.abs()).sum();
+        diagonal.push(i, i, abs_sum + 1.0);
+    }
+    x + CsrMatrix::from(&diagonal)
+}
+
+fn square_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    PROPTEST_MATRIX_DIM
+        .prop_flat_map(|n| csr(value_strategy::<f64>(), n..=n, n..=n, PROPTEST_MAX_NNZ))
+}
+
+/// Returns a strictly diagonally dominant matrix, for which ILU(0) is guaranteed to exist.
+fn diagonally_dominant_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    square_strategy().prop_map(add_dominant_diagonal)
+}
+
+/// Returns a symmetric, strictly diagonally dominant matrix with positive diagonal, for which
+/// IC(0) is guaranteed to exist.
+fn symmetric_diagonally_dominant_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    square_strategy().prop_map(|x| add_dominant_diagonal(&x + x.transpose()))
+}
+
+/// Returns a CSR matrix that explicitly stores every entry of the given dense matrix.
+fn with_full_pattern(dense: &DMatrix<f64>) -> CsrMatrix<f64> {
+    let (nrows, ncols) = dense.shape();
+    let offsets = (0..=nrows).map(|i| i * ncols).collect();
+    let indices = (0..nrows).flat_map(|_| 0..ncols).collect();
+    let values = dense.transpose().as_slice().to_vec();
+    CsrMatrix::try_from_csr_data(nrows, ncols, offsets, indices, values).unwrap()
+}
+
+fn with_rhs(
+    matrix: impl Strategy<Value = CsrMatrix<f64>>,
+) -> impl Strategy<Value = (CsrMatrix<f64>, DVector<f64>)> {
+    matrix.prop_flat_map(|a| {
+        let b = vector(value_strategy::<f64>(), a.nrows());
+        (Just(a), b)
+    })
+}
+
+proptest! {
+    #[test]
+    fn jacobi_scales_by_inverse_diagonal((a, r) in with_rhs(diagonally_dominant_strategy())) {
+        let jacobi = Jacobi::from_csr(&a).unwrap();
+        let diagonal = DVector::from_fn(a.nrows(), |i, _| a.index_entry(i, i).into_value());
+        let z = apply(&jacobi, &r);
+        prop_assert_matrix_eq!(z.component_mul(&diagonal), r, comp = abs, tol = 1e-12);
+
+        let jacobi_csc = Jacobi::from_csc(&CscMatrix::from(&a)).unwrap();
+        prop_assert_eq!(jacobi_csc.inverse_diagonal(), jacobi.inverse_diagonal());
+    }
+
+    #[test]
+    fn ssor_applies_inverse_of_ssor_matrix(
+        (a, r) in with_rhs(diagonally_dominant_strategy()),
+        omega in 0.1..1.9f64
+    ) {
+        let ssor = Ssor::from_csr(&a, omega).unwrap();
+        prop_assert_eq!(ssor.omega(), omega);
+
+        let a_dense = DMatrix::from(&a);
+        let d = DMatrix::from_diagonal(&a_dense.diagonal());
+        let d_inv = DMatrix::from_diagonal(&a_dense.diagonal().map(|d_ii| 1.0 / d_ii));
+        let l = a_dense.lower_triangle() - &d;
+        let u = a_dense.upper_triangle() - &d;
+        let m = (&d + l * omega) * d_inv * (&d + u * omega) / (omega * (2.0 - omega));
+
+        let z = apply(&ssor, &r);
+        prop_assert_matrix_eq!(&m * &z, &r, comp = abs, tol = 1e-8);
+
+        let ssor_csc = Ssor::from_csc(&CscMatrix::from(&a), omega).unwrap();
+        prop_assert_matrix_eq!(apply(&ssor_csc, &r), z, comp = abs, tol = 1e-12);
+    }
+
+    #[test]
+    fn ilu0_agrees_with_matrix_on_pattern((a, r) in with_rhs(diagonally_dominant_strategy())) {
+        let ilu = Ilu0::from_csr(&a).unwrap();
+        prop_assert_eq!(ilu.lu().pattern(), a.pattern());
+
+        let lu = DMatrix::from(ilu.lu());
+        let mut l = lu.lower_triangle();
+        l.fill_diagonal(1.0);
+        let u = lu.upper_triangle();
+        let product = &l * &u;
+        for (i, j, &a_ij) in a.triplet_iter() {
+            prop_assert!((product[(i, j)] - a_ij).abs() <= 1e-10 * (1.0 + a_ij.abs()));
+        }
+
+        let z = apply(&ilu, &r);
+        prop_assert_matrix_eq!(&product * &z, &r, comp = abs, tol = 1e-8);
+
+        let ilu_csc = Ilu0::from_csc(&CscMatrix::from(&a)).unwrap();
+        prop_assert_eq!(ilu_csc.lu(), ilu.lu());
+    }
+
+    #[test]
+    fn ilu0_with_full_pattern_is_exact_lu(
+        (dense, r) in PROPTEST_MATRIX_DIM.prop_flat_map(|n| {
+            (matrix(value_strategy::<f64>(), n, n), vector(value_strategy::<f64>(), n))
+        })
+    ) {
+        let n = dense.nrows();
+        let a = with_full_pattern(&(dense + DMatrix::identity(n, n) * 100.0));
+        let ilu = Ilu0::from_csr(&a).unwrap();
+        let z = apply(&ilu, &r);
+        prop_assert_matrix_eq!(&a * &z, &r, comp = abs, tol = 1e-8);
+    }
+
+    #[test]
+    fn ic0_agrees_with_matrix_on_pattern(
+        (a, r) in with_rhs(symmetric_diagonally_dominant_strategy())
+    ) {
+        let ic = Ic0::from_csr(&a).unwrap();
+        let lower = a.lower_triangle();
+        prop_assert_eq!(ic.l().pattern(), lower.pattern());
+
+        let l = DMatrix::from(ic.l());
+        let product = &l * l.transpose();
+        for (i, j, &a_ij) in a.triplet_iter() {
+            prop_assert!((product[(i, j)] - a_ij).abs() <= 1e-10 * (1.0 + a_ij.abs()));
+        }
+
+        let z = apply(&ic, &r);
+        prop_assert_matrix_eq!(&product * &z, &r, comp = abs, tol = 1e-8);
+
+        let ic_csc = Ic0::from_csc(&CscMatrix::from(&a)).unwrap();
+        prop_assert_eq!(ic_csc.l(), ic.l());
+    }
+
+    #[test]
+    fn ic0_with_full_pattern_is_exact_cholesky(
+        x in PROPTEST_MATRIX_DIM.prop_flat_map(|n| matrix(value_strategy::<f64>(), n, n))
+    ) {
+        let n = x.nrows();
+        let a = with_full_pattern(&(x.transpose() * &x + DMatrix::identity(n, n)));
+        let ic = Ic0::from_csr(&a).unwrap();
+        let cholesky = CscCholesky::factor(&CscMatrix::from(&a)).unwrap();
+        prop_assert_matrix_eq!(ic.l(), cholesky.l(), comp = abs, tol = 1e-8);
+    }
+
+    #[test]
+    fn refactor_agrees_with_factor(a in symmetric_diagonally_dominant_strategy(), scale in 1.0..10.0f64) {
+        let scaled = &a * scale;
+
+        let mut ilu = Ilu0::from_csr(&a).unwrap();
+        ilu.refactor(scaled.values()).unwrap();
+        prop_assert_eq!(ilu, Ilu0::from_csr(&scaled).unwrap());
+
+        let mut ic = Ic0::from_csr(&a).unwrap();
+        ic.refactor(scaled.values()).unwrap();
+        prop_assert_eq!(ic, Ic0::from_csr(&scaled).unwrap());
+    }
+}
+
+#[test]
+fn incomplete_factorizations_discard_fill_in() {
+    // An arrow matrix whose exact factors are dense, but whose pattern is not
+    #[rustfmt::skip]
+    let dense = DMatrix::from_row_slice(4, 4, &[
+        4.0, 1.0, 1.0, 1.0,
+        1.0, 4.0, 0.0, 0.0,
+        1.0, 0.0, 4.0, 0.0,
+        1.0, 0.0, 0.0, 4.0,
+    ]);
+    let a = CsrMatrix::from(&dense);
+
+    let ilu = Ilu0::from_csr(&a).unwrap();
+    assert_eq!(ilu.lu().pattern(), a.pattern());
+    assert_eq!(ilu.lu().row(2).col_indices(), &[0, 2]);
+
+    let ic = Ic0::from_csr(&a).unwrap();
+    assert_eq!(ic.l().nnz(), 7);
+    assert_eq!(ic.l().row(2).col_indices(), &[0, 2]);
+
+    // The incomplete factors are only approximations of the inverse
+    let r = DVector::from_element(4, 1.0);
+    assert!((&a * apply(&ilu, &r) - &r).norm() > 1e-3);
+    assert!((&a * apply(&ic, &r) - &r).norm() > 1e-3);
+}
+
+#[test]
+fn incomplete_factorizations_of_tridiagonal_matrix_are_exact() {
+    // A tridiagonal matrix has no fill-in, so the incomplete factorizations are exact
+    let n = 10;
+    let mut coo = CooMatrix::new(n, n);
+    for i in 0..n {
+        coo.push(i, i, 2.0);
+        if i > 0 {
+            coo.push(i, i - 1, -1.0);
+            coo.push(i - 1, i, -1.0);
+        }
+    }
+    let a = CsrMatrix::from(&coo);
+    let r = DVector::from_fn(n, |i, _| i as f64);
+
+    let ic = Ic0::from_csr(&a).unwrap();
+    let cholesky = CscCholesky::factor(&CscMatrix::from(&a)).unwrap();
+    assert_matrix_eq!(ic.l(), cholesky.l(), comp = abs, tol = 1e-12);
+    assert_matrix_eq!(&a * apply(&ic, &r), r, comp = abs, tol = 1e-10);
+
+    let ilu = Ilu0::from_csr(&a).unwrap();
+    assert_matrix_eq!(&a * apply(&ilu, &r), r, comp = abs, tol = 1e-10);
+}
+
+#[test]
+fn preconditioners_handle_empty_matrix() {
+    let a = CsrMatrix::<f64>::zeros(0, 0);
+    let r = DVector::zeros(0);
+    assert_eq!(apply(&Jacobi::from_csr(&a).unwrap(), &r).nrows(), 0);
+    assert_eq!(apply(&Ssor::from_csr(&a, 1.0).unwrap(), &r).nrows(), 0);
+    assert_eq!(apply(&Ilu0::from_csr(&a).unwrap(), &r).nrows(), 0);
+    assert_eq!(apply(&Ic0::from_csr(&a).unwrap(), &r).nrows(), 0);
+}
+
+#[test]
+fn preconditioners_reject_missing_or_zero_diagonal() {
+    // The diagonal entry (1, 1) is not stored
+    let missing =
+        CsrMatrix::try_from_csr_data(2, 2, vec![0, 2, 3], vec![0, 1, 0], vec![1.0, 2.0, 3.0])
+            .unwrap();
+    // The diagonal entry (1, 1) is stored, but zero
+    let zero =
+        CsrMatrix::try_from_csr_data(2, 2, vec![0, 1, 2], vec![0, 1], vec![1.0, 0.0]).unwrap();
+
+    for a in [&missing, &zero] {
+        let is_singular = |kind: &OperationErrorKind| matches!(kind, OperationErrorKind::Singular);
+        assert!(is_singular(Jacobi::from_csr(a).unwrap_err().kind()));
+        assert!(is_singular(
+            Jacobi::from_csc(&CscMatrix::from(a)).unwrap_err().kind()
+        ));
+        assert!(is_singular(Ssor::from_csr(a, 1.0).unwrap_err().kind()));
+        assert!(is_singular(Ilu0::from_csr(a).unwrap_err().kind()));
+        assert_eq!(
+            Ic0::from_csr(a).unwrap_err(),
+            CholeskyError::NotPositiveDefinite
+        );
+    }
+}
+
+#[test]
+fn ilu0_reports_zero_pivot() {
+    // The leading 2x2 block is singular, so the second pivot is zero
+    let dense = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+    let a = CsrMatrix::from(&dense);
+    let error = Ilu0::from_csr(&a).unwrap_err();
+    assert!(matches!(error.kind(), OperationErrorKind::Singular));
+}
+
+#[test]
+fn ic0_reports_indefinite_matrix() {
+    let dense = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0]);
+    let a = CsrMatrix::from(&dense);
+    assert_eq!(
+        Ic0::from_csr(&a).unwrap_err(),
+        CholeskyError::NotPositiveDefinite
+    );
+}
+
+#[test]
+fn preconditioners_panic_on_invalid_input() {
+    let a = CsrMatrix::<f64>::identity(3);
+    let rectangular = CsrMatrix::<f64>::zeros(2, 3);
+
+    assert_panics!(Jacobi::from_csr(&rectangular));
+    assert_panics!(Ssor::from_csr(&rectangular, 1.0));
+    assert_panics!(Ilu0::from_csr(&rectangular));
+    assert_panics!(Ic0::from_csr(&rectangular));
+
+    assert_panics!(Ssor::from_csr(&a, 0.0));
+    assert_panics!(Ssor::from_csr(&a, 2.0));
+
+    assert_panics!(Ilu0::from_csr(&a).unwrap().refactor(&[1.0, 1.0]));
+    assert_panics!(Ic0::from_csr(&a).unwrap().refactor(&[1.0, 1.0]));
+
+    assert_panics!(apply(&Jacobi::from_csr(&a).unwrap(), &DVector::zeros(2)));
+    assert_panics!(apply(&Ssor::from_csr(&a, 1.0).unwrap(), &DVector::zeros(2)));
+    assert_panics!(apply(&Ilu0::from_csr(&a).unwrap(), &DVector::zeros(2)));
+    assert_panics!(apply(&Ic0::from_csr(&a).unwrap(), &DVector::zeros(2)));
+}
@@ -0,0 +1,370 @@
+use matrixcompare::assert_matrix_eq;
+use nalgebra::{Complex, DMatrix};
+use nalgebra_sparse::io::{
+    load_csc_from_rutherford_boeing_file, load_csc_from_rutherford_boeing_reader,
+    load_csc_from_rutherford_boeing_str, save_to_rutherford_boeing_file,
+    save_to_rutherford_boeing_str, RutherfordBoeingErrorKind,
+};
+use nalgebra_sparse::proptest::csc;
+use nalgebra_sparse::{CscMatrix, SparseFormatErrorKind};
+use proptest::prelude::*;
+use std::io::BufReader;
+use tempfile::tempdir;
+
+type C64 = Complex<f64>;
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_real_unsymmetric() {
+    let file_str = r#"
+Unsymmetric 3x3 example                                                 example
+             5             1             1             3
+rua                        3             3             5             0
+(4I3)           (5I3)           (2E20.12)
+  1  3  4  6
+  1  3  2  1  3
+  1.000000000000E+00  2.000000000000E+00
+ -3.000000000000D+00  4.000000000000E-01
+  5.000000000000E+01
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<f64>(file_str).unwrap();
+    let expected = DMatrix::from_row_slice(3, 3, &[
+        1.0,  0.0,  0.4,
+        0.0, -3.0,  0.0,
+        2.0,  0.0, 50.0,
+    ]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_unsorted_indices_and_scale_factor() {
+    // Fortran formats may carry a scale factor, and numbers may omit the exponent letter
+    let file_str = r#"
+Unsorted 2x2 example                                                    example
+             3             1             1             1
+rua                        2             2             3             0
+(3I3)           (3I3)           (1P,3E12.4)
+  1  3  4
+  2  1  2
+  1.0000+00   2.0000-01   3.0000E+00
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<f64>(file_str).unwrap();
+    let expected = DMatrix::from_row_slice(2, 2, &[
+        0.2, 0.0,
+        1.0, 3.0,
+    ]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_skew_symmetric_integer() {
+    let file_str = r#"
+Skew-symmetric 3x3 example                                              example
+             4             1             1             1
+iza                        3             3             2             0
+(4I3)           (2I3)           (2I4)
+  1  3  3  3
+  2  3
+   5  -7
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<i32>(file_str).unwrap();
+    let expected = DMatrix::from_row_slice(3, 3, &[
+        0, -5,  7,
+        5,  0,  0,
+       -7,  0,  0,
+    ]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_hermitian_complex() {
+    let file_str = r#"
+Hermitian 2x2 example                                                   example
+             5             1             1             3
+cha                        2             2             3             0
+(3I3)           (3I3)           (2E12.4)
+  1  3  4
+  1  2  2
+  2.0000E+00  0.0000E+00
+  1.0000E+00  3.0000E+00
+  4.0000E+00  0.0000E+00
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<C64>(file_str).unwrap();
+    let expected = DMatrix::from_row_slice(2, 2, &[
+        C64 { re: 2.0, im: 0.0 }, C64 { re: 1.0, im: -3.0 },
+        C64 { re: 1.0, im: 3.0 }, C64 { re: 4.0, im: 0.0 },
+    ]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_pattern_symmetric() {
+    let file_str = r#"
+Pattern 3x3 example                                                     example
+             2             1             1             0
+psa                        3             3             4
+(4I3)           (4I3)
+  1  3  4  5
+  1  3  2  3
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<()>(file_str).unwrap();
+    let expected_offsets = vec![0, 2, 3, 5];
+    let expected_indices = vec![0, 2, 1, 0, 2];
+    assert_eq!(sparse_mat.col_offsets(), expected_offsets.as_slice());
+    assert_eq!(sparse_mat.row_indices(), expected_indices.as_slice());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_load_harwell_boeing_with_right_hand_side() {
+    // The Harwell-Boeing format has a fifth line count, and an additional header line for right-hand sides
+    let file_str = r#"
+Harwell-Boeing 2x2 example                                              example
+             5             1             1             1             2
+RUA                        2             2             2             0
+(3I3)           (2I3)           (2E12.4)            (2E12.4)
+F                          1             0
+  1  2  3
+  2  1
+  1.0000E+00  2.0000E+00
+  5.0000E+00  6.0000E+00
+"#.trim_start();
+    let sparse_mat = load_csc_from_rutherford_boeing_str::<f64>(file_str).unwrap();
+    let expected = DMatrix::from_row_slice(2, 2, &[
+        0.0, 2.0,
+        1.0, 0.0,
+    ]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+fn test_rutherford_boeing_load_reader() {
+    let file_str = "\
+Reader example                                                          example
+             3             1             1             1
+rua                        1             2             1             0
+(3I3)           (1I3)           (1E12.4)
+  1  2  2
+  1
+  7.5000E+00
+";
+    let reader = BufReader::new(file_str.as_bytes());
+    let sparse_mat = load_csc_from_rutherford_boeing_reader::<f32, _>(reader).unwrap();
+    let expected = DMatrix::from_row_slice(1, 2, &[7.5f32, 0.0]);
+    assert_matrix_eq!(sparse_mat, expected);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_errors() {
+    let header = "Error example                                                           example\n";
+
+    // Type mismatch
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1             1             1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<i32>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::TypeMismatch);
+
+    // Elemental matrices are not supported
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rue                        1             1             1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::InvalidHeader);
+    assert_eq!(err.line_number(), Some(3));
+
+    // Non-square symmetric matrix
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rsa                        1             2             1             0
+(3I3)           (1I3)           (1E12.4)
+  1  2  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::InvalidHeader);
+
+    // Invalid format descriptor
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1             1             1             0
+(2X3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::ParsingError);
+    assert_eq!(err.line_number(), Some(4));
+
+    // Invalid number
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1             1             1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.00x0E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::ParsingError);
+    assert_eq!(err.line_number(), Some(7));
+
+    // Missing values
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1             1             1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::EntryMismatch);
+
+    // Entry in the upper triangle of a symmetric matrix
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rsa                        2             2             1             0
+(3I3)           (1I3)           (1E12.4)
+  1  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::NotLowerTriangle);
+
+    // Diagonal entry in a skew-symmetric matrix
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rza                        2             2             1             0
+(3I3)           (1I3)           (1E12.4)
+  1  2  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::DiagonalError);
+
+    // Row index out of bounds
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        2             1             1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  3
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        RutherfordBoeingErrorKind::SparseFormatError(SparseFormatErrorKind::IndexOutOfBounds)
+    );
+
+    // Number of columns too large to hold the column offsets
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1 18446744073709551615 1             0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::InvalidHeader);
+
+    // Huge number of entries in the header must not be preallocated
+    let file_str = format!("{}{}", header, r#"
+             3             1             1             1
+rua                        1             1 100000000000000000 0
+(2I3)           (1I3)           (1E12.4)
+  1  2
+  1
+  1.0000E+00
+"#.trim_start());
+    let err = load_csc_from_rutherford_boeing_str::<f64>(&file_str).unwrap_err();
+    assert_eq!(err.kind(), RutherfordBoeingErrorKind::EntryMismatch);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_rutherford_boeing_write_real_general() {
+    let sparse_mat = CscMatrix::try_from_csc_data(
+        3,
+        2,
+        vec![0, 2, 3],
+        vec![0, 2, 1],
+        vec![1.5, -2.0, 1e-300],
+    ).unwrap();
+    let rb_str = save_to_rutherford_boeing_str(&sparse_mat);
+    let lines: Vec<&str> = rb_str.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert!(lines[2].starts_with("rra"));
+    assert_eq!(lines[4], " 1 3 4");
+    assert_eq!(lines[5], " 1 3 2");
+    assert_eq!(lines[6], "    1.5000000000000000E+00   -2.0000000000000000E+00   1.0000000000000000E-300");
+
+    let loaded = load_csc_from_rutherford_boeing_str::<f64>(&rb_str).unwrap();
+    assert_eq!(loaded, sparse_mat);
+}
+
+#[test]
+fn test_rutherford_boeing_write_complex_and_pattern() {
+    let complex = CscMatrix::try_from_csc_data(
+        2,
+        2,
+        vec![0, 1, 2],
+        vec![1, 0],
+        vec![C64 { re: 1.0, im: -0.25 }, C64 { re: 0.0, im: 3.0 }],
+    )
+    .unwrap();
+    let rb_str = save_to_rutherford_boeing_str(&complex);
+    assert_eq!(rb_str.lines().nth(2).unwrap().get(..3), Some("cua"));
+    let loaded = load_csc_from_rutherford_boeing_str::<C64>(&rb_str).unwrap();
+    assert_eq!(loaded, complex);
+
+    let pattern =
+        CscMatrix::try_from_csc_data(2, 2, vec![0, 1, 2], vec![1, 0], vec![(), ()]).unwrap();
+    let rb_str = save_to_rutherford_boeing_str(&pattern);
+    assert_eq!(rb_str.lines().nth(2).unwrap().get(..3), Some("pua"));
+    let loaded = load_csc_from_rutherford_boeing_str::<()>(&rb_str).unwrap();
+    assert_eq!(loaded, pattern);
+}
+
+proptest! {
+    #[test]
+    fn csc_rutherford_boeing_roundtrip_str(matrix in csc(-1000 ..= 1000, 0 ..= 12, 0 ..= 12, 100)) {
+        let rb_str = save_to_rutherford_boeing_str(&matrix);
+        let loaded = load_csc_from_rutherford_boeing_str::<i32>(&rb_str).unwrap();
+        prop_assert_eq!(loaded, matrix);
+    }
+}
+
+proptest! {
+    #[test]
+    fn csc_rutherford_boeing_roundtrip_file(
+        matrix in csc(any::<f64>().prop_filter("finite", |x| x.is_finite()), 0 ..= 12, 0 ..= 12, 100)
+    ) {
+        let temp_dir = tempdir().expect("Unable to create temporary directory");
+        let file_path = temp_dir.path().join("temp.rb");
+        save_to_rutherford_boeing_file(&matrix, &file_path).unwrap();
+        let loaded = load_csc_from_rutherford_boeing_file::<f64, _>(&file_path).unwrap();
+        prop_assert_eq!(loaded, matrix);
+        temp_dir.close().expect("Unable to delete temporary directory");
+    }
+}
@@ -0,0 +1,390 @@
+use crate::assert_panics;
+use crate::common::{value_strategy, PROPTEST_MATRIX_DIM, PROPTEST_MAX_NNZ};
+use nalgebra_sparse::coo::CooMatrix;
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::operator::LinearOperator;
+use nalgebra_sparse::proptest::csr;
+use nalgebra_sparse::solvers::{
+    bicgstab, cg, gmres, minres, Ic0, IdentityPreconditioner, Ilu0, Jacobi, Preconditioner,
+    SolverOptions, SolverReport, Ssor, TerminationReason,
+};
+
+use nalgebra::proptest::vector;
+use nalgebra::{DMatrix, DVector};
+
+use proptest::prelude::*;
+
+fn options() -> SolverOptions<f64> {
+    SolverOptions {
+        max_iterations: 200,
+        relative_tolerance: 1e-12,
+        absolute_tolerance: 1e-14,
+    }
+}
+
+/// Checks that `x` solves `A x = b` to a reasonable accuracy.
+fn is_solution<A: LinearOperator<f64>>(a: &A, x: &DVector<f64>, b: &DVector<f64>) -> bool {
+    let mut r = b.clone();
+    a.apply(1.0, (&mut r).into(), -1.0, x.into());
+    r.norm() <= 1e-8 * b.norm().max(1.0)
+}
+
+fn report_is_consistent(report: &SolverReport<f64>) -> bool {
+    let history = report.residual_history();
+    history.len() == report.iterations() + 1
+        && *history.last().unwrap() == report.residual_norm()
+        && report.converged() == (report.termination_reason() == TerminationReason::Converged)
+}
+
+/// Returns a matrix of the form `X^T X + I`.
+fn spd_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    csr(
+        value_strategy::<f64>(),
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MAX_NNZ,
+    )
+    .prop_map(|x| x.transpose() * &x + CsrMatrix::identity(x.ncols()))
+}
+
+/// Returns a square matrix of the form `X + 100 I`, which is strictly diagonally dominant.
+fn nonsymmetric_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    PROPTEST_MATRIX_DIM
+        .prop_flat_map(|n| csr(value_strategy::<f64>(), n..=n, n..=n, PROPTEST_MAX_NNZ))
+        .prop_map(|x| {
+            let n = x.nrows();
+            x + CsrMatrix::identity(n) * 100.0
+        })
+}
+
+/// Returns a symmetric, indefinite and non-singular matrix of the form `X + X^T + D`,
+/// where `D` is diagonal with entries alternating between `100` and `-100`.
+fn symmetric_indefinite_strategy() -> impl Strategy<Value = CsrMatrix<f64>> {
+    PROPTEST_MATRIX_DIM
+        .prop_flat_map(|n| csr(value_strategy::<f64>(), n..=n, n..=n, PROPTEST_MAX_NNZ))
+        .prop_map(|x| {
+            let n = x.nrows();
+            let mut d = CsrMatrix::identity(n) * 100.0;
+            d.values_mut()
+                .iter_mut()
+                .step_by(2)
+                .for_each(|d_ii| *d_ii = -100.0);
+            &x + x.transpose() + d
+        })
+}
+
+fn with_rhs(
+    matrix: impl Strategy<Value = CsrMatrix<f64>>,
+) -> impl Strategy<Value = (CsrMatrix<f64>, DVector<f64>)> {
+    matrix.prop_flat_map(|a| {
+        let b = vector(value_strategy::<f64>(), a.nrows());
+        (Just(a), b)
+    })
+}
+
+/// The 2D Laplacian on an `m x m` grid.
+fn laplacian_2d(m: usize) -> CsrMatrix<f64> {
+    let n = m * m;
+    let mut coo = CooMatrix::new(n, n);
+    for i in 0..m {
+        for j in 0..m {
+            let idx = i * m + j;
+            coo.push(idx, idx, 4.0);
+            if i > 0 {
+                coo.push(idx, idx - m, -1.0);
+            }
+            if i + 1 < m {
+                coo.push(idx, idx + m, -1.0);
+            }
+            if j > 0 {
+                coo.push(idx, idx - 1, -1.0);
+            }
+            if j + 1 < m {
+                coo.push(idx, idx + 1, -1.0);
+            }
+        }
+    }
+    CsrMatrix::from(&coo)
+}
+
+proptest! {
+    #[test]
+    fn cg_solves_spd_systems((a, b) in with_rhs(spd_strategy())) {
+        let mut x = DVector::zeros(a.ncols());
+        let report = cg(&a, &b, &mut x, &IdentityPreconditioner, &options());
+        prop_assert!(report.converged());
+        prop_assert!(report_is_consistent(&report));
+        prop_assert!(is_solution(&a, &x, &b));
+
+        let mut x = DVector::zeros(a.ncols());
+        let jacobi = Jacobi::from_csr(&a).unwrap();
+        let report = cg(&a, &b, &mut x, &jacobi, &options());
+        prop_assert!(report.converged());
+        prop_assert!(is_solution(&a, &x, &b));
+    }
+
+    #[test]
+    fn minres_solves_symmetric_systems(
+        (a, b) in with_rhs(prop_oneof![spd_strategy(), symmetric_indefinite_strategy()])
+    ) {
+        let mut x = DVector::zeros(a.ncols());
+        let report = minres(&a, &b, &mut x, &IdentityPreconditioner, &options());
+        prop_assert!(report.converged());
+        prop_assert!(report_is_consistent(&report));
+        prop_assert!(is_solution(&a, &x, &b));
+
+        // MINRES minimizes the residual, so the residual norms must be non-increasing
+        let history = report.residual_history();
+        prop_assert!(history.windows(2).all(|w| w[1] <= w[0] * (1.0 + 1e-12)));
+    }
+
+    #[test]
+    fn bicgstab_solves_nonsymmetric_systems((a, b) in with_rhs(nonsymmetric_strategy())) {
+        let mut x = DVector::zeros(a.ncols());
+        let report = bicgstab(&a, &b, &mut x, &IdentityPreconditioner, &options());
+        prop_assert!(report.converged());
+        prop_assert!(report_is_consistent(&report));
+        prop_assert!(is_solution(&a, &x, &b));
+
+        let mut x = DVector::zeros(a.ncols());
+        let report = bicgstab(&a, &b, &mut x, &Jacobi::from_csr(&a).unwrap(), &options());
+        prop_assert!(report.converged());
+        prop_assert!(is_solution(&a, &x, &b));
+    }
+
+    #[test]
+    fn gmres_solves_nonsymmetric_systems(
+        (a, b) in with_rhs(nonsymmetric_strategy()),
+        restart in 1..=8usize
+    ) {
+        let mut x = DVector::zeros(a.ncols());
+        let report = gmres(&a, &b, &mut x, &IdentityPreconditioner, restart, &options());
+        prop_assert!(report.converged());
+        prop_assert!(report_is_consistent(&report));
+        prop_assert!(is_solution(&a, &x, &b));
+
+        let mut x = DVector::zeros(a.ncols());
+        let jacobi = Jacobi::from_csr(&a).unwrap();
+        let report = gmres(&a, &b, &mut x, &jacobi, restart, &options());
+        prop_assert!(report.converged());
+        prop_assert!(is_solution(&a, &x, &b));
+    }
+
+    #[test]
+    fn solvers_accept_nonzero_initial_guess(
+        (a, b) in with_rhs(spd_strategy()),
+        x0_value in value_strategy::<f64>()
+    ) {
+        let x0 = DVector::repeat(a.ncols(), x0_value);
+        let a_csc = CscMatrix::from(&a);
+        let a_dense = DMatrix::from(&a);
+
+        let mut x = x0.clone();
+        prop_assert!(cg(&a_csc, &b, &mut x, &IdentityPreconditioner, &options()).converged());
+        prop_assert!(is_solution(&a, &x, &b));
+
+        let mut x = x0.clone();
+        prop_assert!(minres(&a_dense, &b, &mut x, &IdentityPreconditioner, &options()).converged());
+        prop_assert!(is_solution(&a, &x, &b));
+
+        let mut x = x0.clone();
+        prop_assert!(bicgstab(&a, &b, &mut x, &IdentityPreconditioner, &options()).converged());
+        prop_assert!(is_solution(&a, &x, &b));
+
+        // Without restarts, GMRES converges in at most n iterations in exact arithmetic
+        let mut x = x0;
+        let restart = a.nrows().max(1);
+        let report = gmres(&a, &b, &mut x, &IdentityPreconditioner, restart, &options());
+        prop_assert!(report.converged());
+        prop_assert!(report.iterations() <= a.nrows() + 1);
+        prop_assert!(is_solution(&a, &x, &b));
+    }
+}
+
+#[test]
+fn preconditioners_accelerate_convergence_on_2d_laplacian() {
+    let a = laplacian_2d(16);
+    let n = a.nrows();
+    let b = DVector::from_fn(n, |i, _| (i as f64).sin());
+    let options = options();
+
+    let solve = |preconditioner: &dyn Preconditioner<f64>, symmetric: bool| {
+        let mut x = DVector::zeros(n);
+        let report = if symmetric {
+            cg(&a, &b, &mut x, preconditioner, &options)
+        } else {
+            gmres(&a, &b, &mut x, preconditioner, 30, &options)
+        };
+        assert!(report.converged());
+        assert!(is_solution(&a, &x, &b));
+        report.iterations()
+    };
+
+    let ic = Ic0::from_csr(&a).unwrap();
+    let ssor = Ssor::from_csr(&a, 1.5).unwrap();
+    let ilu = Ilu0::from_csr(&a).unwrap();
+    let unpreconditioned = solve(&IdentityPreconditioner, true);
+    assert!(solve(&ic, true) < unpreconditioned);
+    assert!(solve(&ssor, true) < unpreconditioned);
+    assert!(solve(&ilu, false) < solve(&IdentityPreconditioner, false));
+}
+
+#[test]
+fn solvers_converge_on_2d_laplacian() {
+    let a = laplacian_2d(8);
+    let n = a.nrows();
+    let b = DVector::from_fn(n, |i, _| (i as f64).sin());
+    let expected = DMatrix::from(&a).lu().solve(&b).unwrap();
+
+    let check = |x: &DVector<f64>, report: SolverReport<f64>| {
+        assert_eq!(report.termination_reason(), TerminationReason::Converged);
+        assert!(report.iterations() > 0);
+        assert!(report.residual_norm() <= 1e-12 * b.norm());
+        assert!((x - &expected).norm() <= 1e-9 * expected.norm());
+    };
+
+    let mut x = DVector::zeros(n);
+    let report = cg(&a, &b, &mut x, &IdentityPreconditioner, &options());
+    // In exact arithmetic CG terminates in at most n iterations
+    assert!(report.iterations() <= n);
+    check(&x, report);
+
+    let mut x = DVector::zeros(n);
+    let report = minres(&a, &b, &mut x, &IdentityPreconditioner, &options());
+    check(&x, report);
+
+    let mut x = DVector::zeros(n);
+    let report = bicgstab(&a, &b, &mut x, &IdentityPreconditioner, &options());
+    check(&x, report);
+
+    let mut x = DVector::zeros(n);
+    let options = SolverOptions {
+        max_iterations: 1000,
+        ..options()
+    };
+    let report = gmres(&a, &b, &mut x, &IdentityPreconditioner, 10, &options);
+    check(&x, report);
+}
+
+#[test]
+fn solvers_report_max_iterations() {
+    let a = laplacian_2d(5);
+    let b = DVector::repeat(a.nrows(), 1.0);
+    let options = SolverOptions {
+        max_iterations: 2,
+        ..options()
+    };
+
+    let mut x = DVector::zeros(a.nrows());
+    let report = cg(&a, &b, &mut x, &IdentityPreconditioner, &options);
+    assert_eq!(
+        report.termination_reason(),
+        TerminationReason::MaxIterationsReached
+    );
+    assert_eq!(report.iterations(), 2);
+    assert_eq!(report.residual_history().len(), 3);
+
+    let mut x = DVector::zeros(a.nrows());
+    let report = minres(&a, &b, &mut x, &IdentityPreconditioner, &options);
+    assert_eq!(
+        report.termination_reason(),
+        TerminationReason::MaxIterationsReached
+    );
+    assert_eq!(report.iterations(), 2);
+
+    let mut x = DVector::zeros(a.nrows());
+    let report = bicgstab(&a, &b, &mut x, &IdentityPreconditioner, &options);
+    assert_eq!(
+        report.termination_reason(),
+        TerminationReason::MaxIterationsReached
+    );
+    assert_eq!(report.iterations(), 2);
+
+    let mut x = DVector::zeros(a.nrows());
+    let report = gmres(&a, &b, &mut x, &IdentityPreconditioner, 1, &options);
+    assert_eq!(
+        report.termination_reason(),
+        TerminationReason::MaxIterationsReached
+    );
+    assert_eq!(report.iterations(), 2);
+}
+
+#[test]
+fn solvers_return_immediately_for_exact_initial_guess() {
+    let a = laplacian_2d(3);
+    let b = DVector::zeros(a.nrows());
+
+    let mut x = DVector::zeros(a.nrows());
+    for report in [
+        cg(&a, &b, &mut x, &IdentityPreconditioner, &options()),
+        minres(&a, &b, &mut x, &IdentityPreconditioner, &options()),
+        bicgstab(&a, &b, &mut x, &IdentityPreconditioner, &options()),
+        gmres(&a, &b, &mut x, &IdentityPreconditioner, 4, &options()),
+    ] {
+        assert!(report.converged());
+        assert_eq!(report.iterations(), 0);
+        assert_eq!(report.residual_history(), &[0.0]);
+    }
+    assert_eq!(x, DVector::zeros(a.nrows()));
+}
+
+#[test]
+fn cg_breaks_down_for_indefinite_matrix() {
+    let a = DMatrix::from_diagonal(&DVector::from_column_slice(&[1.0, -1.0]));
+    let b = DVector::from_column_slice(&[1.0, 1.0]);
+    let mut x = DVector::zeros(2);
+    let report = cg(&a, &b, &mut x, &IdentityPreconditioner, &options());
+    assert_eq!(report.termination_reason(), TerminationReason::Breakdown);
+
+    // MINRES on the other hand handles symmetric indefinite matrices
+    let mut x = DVector::zeros(2);
+    let report = minres(&a, &b, &mut x, &IdentityPreconditioner, &options());
+    assert!(report.converged());
+    assert!(is_solution(&a, &x, &b));
+}
+
+#[test]
+fn solvers_panic_on_dimension_mismatch() {
+    let a = CsrMatrix::<f64>::identity(3);
+    let b = DVector::zeros(3);
+    let options = options();
+    assert_panics!(cg(
+        &a,
+        &b,
+        &mut DVector::zeros(4),
+        &IdentityPreconditioner,
+        &options
+    ));
+    assert_panics!(minres(
+        &a,
+        &b,
+        &mut DVector::zeros(4),
+        &IdentityPreconditioner,
+        &options
+    ));
+    assert_panics!(bicgstab(
+        &a,
+        &DVector::zeros(2),
+        &mut DVector::zeros(3),
+        &IdentityPreconditioner,
+        &options
+    ));
+    assert_panics!(gmres(
+        &a,
+        &b,
+        &mut DVector::zeros(3),
+        &IdentityPreconditioner,
+        0,
+        &options
+    ));
+
+    let rectangular = CsrMatrix::<f64>::zeros(3, 2);
+    assert_panics!(cg(
+        &rectangular,
+        &b,
+        &mut DVector::zeros(2),
+        &IdentityPreconditioner,
+        &options
+    ));
+}
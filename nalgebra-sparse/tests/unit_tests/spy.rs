@@ -0,0 +1,157 @@
+use crate::assert_panics;
+use crate::common::{csr_strategy, PROPTEST_I32_VALUE_STRATEGY};
+use nalgebra::DMatrix;
+use nalgebra_sparse::io::{
+    save_spy_pbm, save_spy_plot, save_spy_plot_str, save_spy_svg, PatternStats, SpyCharset,
+};
+use nalgebra_sparse::pattern::SparsityPattern;
+use nalgebra_sparse::proptest::csr;
+use nalgebra_sparse::CsrMatrix;
+
+use proptest::prelude::*;
+
+/// The pattern of a `3 x 5` matrix with entries (0, 0), (0, 1), (1, 4), (2, 2) and (2, 3).
+fn example_pattern() -> SparsityPattern {
+    SparsityPattern::try_from_offsets_and_indices(3, 5, vec![0, 2, 3, 5], vec![0, 1, 4, 2, 3])
+        .unwrap()
+}
+
+#[test]
+fn ascii_spy_plot() {
+    let pattern = example_pattern();
+    assert_eq!(
+        save_spy_plot_str(&pattern, SpyCharset::Ascii, 80, 40),
+        "**...\n....*\n..**.\n"
+    );
+
+    // Downsampling covers 2 x 2 blocks with every character
+    assert_eq!(
+        save_spy_plot_str(&pattern, SpyCharset::Ascii, 3, 2),
+        "*.*\n.*.\n"
+    );
+
+    let mut bytes = Vec::new();
+    save_spy_plot(&mut bytes, &pattern, SpyCharset::Ascii, 80, 40).unwrap();
+    assert_eq!(bytes, b"**...\n....*\n..**.\n");
+
+    assert_eq!(
+        save_spy_plot_str(&SparsityPattern::zeros(0, 0), SpyCharset::Ascii, 1, 1),
+        ""
+    );
+    assert_panics!(save_spy_plot_str(&pattern, SpyCharset::Ascii, 0, 1));
+}
+
+#[test]
+fn unicode_spy_plot() {
+    // Every character holds 2 x 4 cells:
+    //   (0, 0) and (0, 1) -> dots 1 and 4, (1, 4) -> dot 2 of the third character,
+    //   (2, 2) -> dot 3 of the second character and (2, 3) -> dot 6
+    let plot = save_spy_plot_str(&example_pattern(), SpyCharset::Unicode, 80, 40);
+    assert_eq!(plot, "\u{2809}\u{2824}\u{2802}\n");
+
+    let identity = CsrMatrix::<f64>::identity(4);
+    let plot = save_spy_plot_str(identity.pattern(), SpyCharset::Unicode, 80, 40);
+    assert_eq!(plot, "\u{2811}\u{2884}\n");
+}
+
+#[test]
+fn pbm_spy_plot() {
+    let mut bytes = Vec::new();
+    save_spy_pbm(&mut bytes, &example_pattern(), 80, 40).unwrap();
+    let mut expected = b"P4\n5 3\n".to_vec();
+    expected.extend_from_slice(&[0b1100_0000, 0b0000_1000, 0b0011_0000]);
+    assert_eq!(bytes, expected);
+
+    // Rows wider than a byte are padded to whole bytes
+    let mut bytes = Vec::new();
+    save_spy_pbm(&mut bytes, CsrMatrix::<f64>::identity(9).pattern(), 80, 40).unwrap();
+    assert_eq!(&bytes[..7], b"P4\n9 9\n");
+    assert_eq!(bytes.len(), 7 + 9 * 2);
+    assert_eq!(&bytes[7 + 8 * 2..], &[0b0000_0000, 0b1000_0000]);
+}
+
+#[test]
+fn svg_spy_plot() {
+    let mut bytes = Vec::new();
+    save_spy_svg(&mut bytes, &example_pattern()).unwrap();
+    let svg = String::from_utf8(bytes).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"viewBox="0 0 5 3""#));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    // Consecutive entries in a row are merged
+    assert!(svg.contains(r#"<rect x="0" y="0" width="2" height="1"/>"#));
+    assert!(svg.contains(r#"<rect x="4" y="1" width="1" height="1"/>"#));
+    assert!(svg.contains(r#"<rect x="2" y="2" width="2" height="1"/>"#));
+    assert_eq!(svg.matches("<rect x=").count(), 3);
+}
+
+#[test]
+fn pattern_stats_of_example() {
+    let stats = PatternStats::from_pattern(&example_pattern());
+    assert_eq!((stats.nrows(), stats.ncols(), stats.nnz()), (3, 5, 5));
+    assert_eq!(stats.min_row_nnz(), 1);
+    assert_eq!(stats.max_row_nnz(), 2);
+    assert_eq!(stats.mean_row_nnz(), 5.0 / 3.0);
+    assert_eq!(stats.empty_rows(), 0);
+    assert_eq!(stats.bandwidth(), 3);
+    // Only (0, 1) is off-diagonal with a mirrored position in bounds, and (1, 0) is absent
+    assert_eq!(stats.symmetry_ratio(), 0.0);
+    assert_eq!(stats.diagonal_dominance(), None);
+
+    let matrix = CsrMatrix::from(&DMatrix::from_row_slice(
+        3,
+        3,
+        &[2.0, -1.0, 0.0, -1.0, 1.0, -1.0, 0.0, -1.0, 2.0],
+    ));
+    let stats = PatternStats::from_csr(&matrix);
+    assert_eq!(stats.symmetry_ratio(), 1.0);
+    assert_eq!(stats.bandwidth(), 1);
+    // The middle row is not diagonally dominant
+    assert_eq!(stats.diagonal_dominance(), Some(2.0 / 3.0));
+    assert_eq!(
+        stats.to_string(),
+        "3 x 3 pattern with 7 non-zeros\n  \
+         non-zeros per row:  min 2, mean 2.33, max 3\n  \
+         empty rows:         0\n  \
+         bandwidth:          1\n  \
+         symmetry ratio:     1.000\n  \
+         diagonal dominance: 0.667"
+    );
+
+    let stats = PatternStats::from_pattern(&SparsityPattern::zeros(0, 0));
+    assert_eq!((stats.min_row_nnz(), stats.max_row_nnz()), (0, 0));
+    assert_eq!(stats.mean_row_nnz(), 0.0);
+    assert_eq!(stats.symmetry_ratio(), 1.0);
+}
+
+proptest! {
+    #[test]
+    fn ascii_spy_plot_marks_exactly_the_entries(matrix in csr_strategy()) {
+        let plot = save_spy_plot_str(matrix.pattern(), SpyCharset::Ascii, 100, 100);
+        let lines: Vec<_> = plot.lines().collect();
+        prop_assert_eq!(lines.len(), matrix.nrows());
+        for (i, line) in lines.iter().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                prop_assert_eq!(c == '*', matrix.pattern().lane(i).contains(&j));
+            }
+            prop_assert_eq!(line.chars().count(), matrix.ncols());
+        }
+    }
+
+    #[test]
+    fn pattern_stats_agree_with_entries(
+        matrix in (0..=8usize).prop_flat_map(|n| csr(PROPTEST_I32_VALUE_STRATEGY, n, n, 30))
+    ) {
+        let stats = PatternStats::from_pattern(matrix.pattern());
+        let row_nnz: Vec<_> = matrix.row_iter().map(|row| row.nnz()).collect();
+        prop_assert_eq!(stats.min_row_nnz(), row_nnz.iter().copied().min().unwrap_or(0));
+        prop_assert_eq!(stats.max_row_nnz(), row_nnz.iter().copied().max().unwrap_or(0));
+        prop_assert_eq!(stats.empty_rows(), row_nnz.iter().filter(|&&nnz| nnz == 0).count());
+
+        // A pattern plus its transpose is structurally symmetric
+        let symmetric = &matrix + &matrix.transpose();
+        let symmetric_stats = PatternStats::from_pattern(symmetric.pattern());
+        prop_assert_eq!(symmetric_stats.symmetry_ratio(), 1.0);
+        prop_assert_eq!(symmetric_stats.bandwidth(), stats.bandwidth());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::common::{csc_strategy, csr_strategy, PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM};
+use nalgebra::{Complex, DMatrix, DVector};
+use nalgebra_sparse::csc::CscMatrix;
+use nalgebra_sparse::csr::CsrMatrix;
+use nalgebra_sparse::ops::serial::{spmv_csc_sparse, spmv_csr_sparse};
+use nalgebra_sparse::ops::Op;
+use nalgebra_sparse::proptest::{csc, csr};
+use nalgebra_sparse::vector::SparseVector;
+use nalgebra_sparse::{SparseEntry, SparseEntryMut, SparseFormatErrorKind};
+
+use proptest::prelude::*;
+
+/// Generates a sparse vector of the given length, possibly with explicitly stored zeros.
+fn sparse_vector_strategy(len: usize) -> impl Strategy<Value = SparseVector<i32>> {
+    csc(PROPTEST_I32_VALUE_STRATEGY, len, 1, len).prop_map(|x| x.col_as_vector(0))
+}
+
+/// Generates a pair of sparse vectors of the same length.
+fn sparse_vector_pair_strategy() -> impl Strategy<Value = (SparseVector<i32>, SparseVector<i32>)> {
+    PROPTEST_MATRIX_DIM.prop_flat_map(|n| (sparse_vector_strategy(n), sparse_vector_strategy(n)))
+}
+
+/// Generates a matrix `a` and a sparse vector `x` such that `a * x` is defined.
+fn csr_and_vector_strategy() -> impl Strategy<Value = (CsrMatrix<i32>, SparseVector<i32>)> {
+    (PROPTEST_MATRIX_DIM, PROPTEST_MATRIX_DIM).prop_flat_map(|(m, n)| {
+        (
+            csr(PROPTEST_I32_VALUE_STRATEGY, m, n, 20),
+            sparse_vector_strategy(n),
+        )
+    })
+}
+
+/// Checks that `y` is a sparse representation of the dense vector `y_dense` whose pattern is
+/// given by the indices in `pattern`.
+fn assert_sparse_vector_matches(y: &SparseVector<i32>, y_dense: &DVector<i32>, pattern: &[usize]) {
+    assert_eq!(&DVector::from(y), y_dense);
+    assert_eq!(y.indices(), pattern);
+}
+
+/// The indices of the rows of `a` whose pattern intersects the pattern of `x`.
+fn structural_spmv_pattern(a: &CsrMatrix<i32>, x: &SparseVector<i32>) -> Vec<usize> {
+    (0..a.nrows())
+        .filter(|&i| {
+            a.row(i)
+                .col_indices()
+                .iter()
+                .any(|j| x.indices().binary_search(j).is_ok())
+        })
+        .collect()
+}
+
+#[test]
+fn sparse_vector_valid_data() {
+    let x = SparseVector::try_from_indices_and_values(6, vec![0, 2, 5], vec![1, 0, 3]).unwrap();
+    assert_eq!(x.len(), 6);
+    assert!(!x.is_empty());
+    assert_eq!(x.nnz(), 3);
+    assert_eq!(x.indices(), &[0, 2, 5]);
+    assert_eq!(x.values(), &[1, 0, 3]);
+    assert_eq!(
+        x.iter().collect::<Vec<_>>(),
+        vec![(0, &1), (2, &0), (5, &3)]
+    );
+    assert_eq!(x.get_entry(0), Some(SparseEntry::NonZero(&1)));
+    assert_eq!(x.get_entry(1), Some(SparseEntry::Zero));
+    assert_eq!(x.get_entry(6), None);
+    assert_eq!(
+        DVector::from(&x),
+        DVector::from_column_slice(&[1, 0, 0, 0, 0, 3])
+    );
+
+    let (len, indices, values) = x.clone().disassemble();
+    assert_eq!(len, 6);
+    assert_eq!(indices, vec![0, 2, 5]);
+    assert_eq!(values, vec![1, 0, 3]);
+
+    let mut x = x;
+    x.values_mut()[0] = 4;
+    if let Some(SparseEntryMut::NonZero(v)) = x.get_entry_mut(5) {
+        *v = 7;
+    }
+    for (_, v) in x.iter_mut() {
+        *v += 1;
+    }
+    assert_eq!(x.values(), &[5, 1, 8]);
+
+    let zeros = SparseVector::<i32>::zeros(0);
+    assert!(zeros.is_empty());
+    assert_eq!(zeros.nnz(), 0);
+}
+
+#[test]
+fn sparse_vector_invalid_data() {
+    let error_kind = |len, indices, values| {
+        *SparseVector::<i32>::try_from_indices_and_values(len, indices, values)
+            .unwrap_err()
+            .kind()
+    };
+
+    assert_eq!(
+        error_kind(3, vec![0, 1], vec![1]),
+        SparseFormatErrorKind::InvalidStructure
+    );
+    assert_eq!(
+        error_kind(3, vec![0, 3], vec![1, 2]),
+        SparseFormatErrorKind::IndexOutOfBounds
+    );
+    assert_eq!(
+        error_kind(3, vec![1, 1], vec![1, 2]),
+        SparseFormatErrorKind::DuplicateEntry
+    );
+    assert_eq!(
+        error_kind(3, vec![2, 1], vec![1, 2]),
+        SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn sparse_vector_unsorted_data() {
+    let x = SparseVector::try_from_unsorted_indices_and_values(5, vec![4, 0, 2], vec![1, 2, 3])
+        .unwrap();
+    assert_eq!(x.indices(), &[0, 2, 4]);
+    assert_eq!(x.values(), &[2, 3, 1]);
+
+    assert_eq!(
+        SparseVector::try_from_unsorted_indices_and_values(5, vec![4, 0, 4], vec![1, 2, 3])
+            .unwrap_err()
+            .kind(),
+        &SparseFormatErrorKind::DuplicateEntry
+    );
+}
+
+#[test]
+fn sparse_vector_norms() {
+    let x = SparseVector::try_from_indices_and_values(5, vec![1, 3], vec![3.0, -4.0]).unwrap();
+    assert_eq!(x.norm_squared(), 25.0);
+    assert_eq!(x.norm(), 5.0);
+    assert_eq!(x.lp_norm(1), 7.0);
+    assert_eq!(x.amax(), 4.0);
+    assert_eq!(x.camax(), 4.0);
+    assert_eq!(SparseVector::<f64>::zeros(3).norm(), 0.0);
+
+    let x = SparseVector::try_from_indices_and_values(5, vec![1, 3], vec![3, -4]).unwrap();
+    assert_eq!(x.amax(), 4);
+    assert_eq!(SparseVector::<i32>::zeros(3).amax(), 0);
+
+    let x = SparseVector::try_from_indices_and_values(
+        3,
+        vec![0, 2],
+        vec![Complex::new(3.0, -4.0), Complex::new(0.0, 6.0)],
+    )
+    .unwrap();
+    assert_eq!(x.camax(), 7.0);
+}
+
+#[test]
+fn sparse_vector_axpy_to_dense_ignores_dense_values_for_zero_beta() {
+    let x = SparseVector::try_from_indices_and_values(3, vec![1], vec![2.0]).unwrap();
+    let mut y = DVector::from_element(3, f64::NAN);
+    x.axpy_to_dense(3.0, &mut y, 0.0);
+    assert_eq!(y, DVector::from_column_slice(&[0.0, 6.0, 0.0]));
+}
+
+#[test]
+fn col_and_row_as_vector() {
+    let dense = DMatrix::from_row_slice(3, 4, &[1, 0, 2, 0, 0, 0, 3, 0, 4, 0, 0, 5]);
+    let csc = CscMatrix::from(&dense);
+    let csr = CsrMatrix::from(&dense);
+
+    let col = csc.col_as_vector(2);
+    assert_eq!(col.len(), 3);
+    assert_eq!(col.indices(), &[0, 1]);
+    assert_eq!(col.values(), &[2, 3]);
+    assert_eq!(csc.as_view().col_as_vector(2), col);
+
+    let row = csr.row_as_vector(2);
+    assert_eq!(row.len(), 4);
+    assert_eq!(row.indices(), &[0, 3]);
+    assert_eq!(row.values(), &[4, 5]);
+    assert_eq!(csr.as_view().row_as_vector(2), row);
+}
+
+proptest! {
+    #[test]
+    fn sparse_vector_dense_roundtrip(x in sparse_vector_strategy(6)) {
+        let dense = DVector::from(&x);
+        let roundtrip = SparseVector::from(&dense);
+        // Explicit zeros are dropped when converting from dense
+        let nonzeros: Vec<_> = x.iter().filter(|(_, v)| **v != 0).map(|(i, _)| i).collect();
+        prop_assert_eq!(roundtrip.indices(), nonzeros.as_slice());
+        prop_assert_eq!(DVector::from(&roundtrip), dense);
+    }
+
+    #[test]
+    fn sparse_vector_dot_matches_dense((x, y) in sparse_vector_pair_strategy()) {
+        let (x_dense, y_dense) = (DVector::from(&x), DVector::from(&y));
+        prop_assert_eq!(x.dot(&y), x_dense.dot(&y_dense));
+        prop_assert_eq!(x.dot_dense(&y_dense), x_dense.dot(&y_dense));
+    }
+
+    #[test]
+    fn sparse_vector_axpy_matches_dense(
+        (x, y) in sparse_vector_pair_strategy(),
+        a in PROPTEST_I32_VALUE_STRATEGY,
+        b in PROPTEST_I32_VALUE_STRATEGY,
+    ) {
+        let mut expected = DVector::from(&y);
+        expected.axpy(a, &DVector::from(&x), b);
+
+        let mut pattern: Vec<_> = x.indices().iter().chain(y.indices()).copied().collect();
+        pattern.sort_unstable();
+        pattern.dedup();
+
+        let mut result = y.clone();
+        result.axpy(a, &x, b);
+        assert_sparse_vector_matches(&result, &expected, &pattern);
+
+        // Here the pattern of the right-hand side is contained in the pattern of `x`
+        let mut result = x.clone();
+        result.axpy(a, &x, b);
+        prop_assert_eq!(DVector::from(&result), DVector::from(&x) * (a + b));
+        prop_assert_eq!(result.indices(), x.indices());
+    }
+
+    #[test]
+    fn sparse_vector_axpy_to_dense_matches_dense(
+        (x, y) in sparse_vector_pair_strategy(),
+        a in PROPTEST_I32_VALUE_STRATEGY,
+        b in PROPTEST_I32_VALUE_STRATEGY,
+    ) {
+        let mut expected = DVector::from(&y);
+        expected.axpy(a, &DVector::from(&x), b);
+
+        let mut result = DVector::from(&y);
+        x.axpy_to_dense(a, &mut result, b);
+        prop_assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sparse_vector_norm_matches_dense(x in sparse_vector_strategy(6)) {
+        let x = SparseVector::try_from_indices_and_values(
+            x.len(),
+            x.indices().to_vec(),
+            x.values().iter().map(|&v| v as f64).collect(),
+        ).unwrap();
+        let dense = DVector::from(&x);
+        prop_assert_eq!(x.norm_squared(), dense.norm_squared());
+        prop_assert_eq!(x.norm(), dense.norm());
+        prop_assert_eq!(x.lp_norm(1), dense.lp_norm(1));
+        prop_assert_eq!(x.amax(), dense.amax());
+        prop_assert_eq!(x.camax(), dense.camax());
+    }
+
+    #[test]
+    fn spmv_csr_sparse_matches_dense((a, x) in csr_and_vector_strategy()) {
+        let expected = DMatrix::from(&a) * DVector::from(&x);
+        let pattern = structural_spmv_pattern(&a, &x);
+
+        assert_sparse_vector_matches(&spmv_csr_sparse(Op::NoOp(&a), &x), &expected, &pattern);
+        assert_sparse_vector_matches(&(&a * &x), &expected, &pattern);
+        assert_sparse_vector_matches(&(a.clone() * x.clone()), &expected, &pattern);
+
+        // The transpose of the transpose yields the same product, computed by scattering
+        let at = a.transpose();
+        assert_sparse_vector_matches(&spmv_csr_sparse(Op::Transpose(&at), &x), &expected, &pattern);
+    }
+
+    #[test]
+    fn spmv_csc_sparse_matches_dense((a, x) in csr_and_vector_strategy()) {
+        let expected = DMatrix::from(&a) * DVector::from(&x);
+        let pattern = structural_spmv_pattern(&a, &x);
+        let a_csc = CscMatrix::from(&a);
+
+        assert_sparse_vector_matches(&spmv_csc_sparse(Op::NoOp(&a_csc), &x), &expected, &pattern);
+        assert_sparse_vector_matches(&(&a_csc * &x), &expected, &pattern);
+        assert_sparse_vector_matches(&(a_csc * x.clone()), &expected, &pattern);
+
+        let at_csc = CscMatrix::from(&a.transpose());
+        assert_sparse_vector_matches(
+            &spmv_csc_sparse(Op::Transpose(at_csc.as_view()), &x),
+            &expected,
+            &pattern,
+        );
+    }
+
+    #[test]
+    fn col_as_vector_matches_col(a in csc_strategy()) {
+        for j in 0..a.ncols() {
+            let col = a.col_as_vector(j);
+            prop_assert_eq!(col.len(), a.nrows());
+            let a_col = a.col(j);
+            prop_assert_eq!(col.indices(), a_col.row_indices());
+            prop_assert_eq!(col.values(), a_col.values());
+        }
+    }
+
+    #[test]
+    fn row_as_vector_matches_row(a in csr_strategy()) {
+        for i in 0..a.nrows() {
+            let row = a.row_as_vector(i);
+            prop_assert_eq!(row.len(), a.ncols());
+            let a_row = a.row(i);
+            prop_assert_eq!(row.indices(), a_row.col_indices());
+            prop_assert_eq!(row.values(), a_row.values());
+        }
+    }
+}
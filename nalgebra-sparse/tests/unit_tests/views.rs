@@ -0,0 +1,398 @@
+use crate::common::{csc_strategy, csr_strategy, PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM};
+use nalgebra::proptest::matrix;
+use nalgebra::DMatrix;
+use nalgebra_sparse::csc::{CscMatrix, CscMatrixView, CscMatrixViewMut};
+use nalgebra_sparse::csr::{CsrMatrix, CsrMatrixView, CsrMatrixViewMut};
+use nalgebra_sparse::ops::serial::{
+    elementwise_union_csr, spadd_csr_prealloc, spmm_csc_dense, spmm_csc_prealloc, spmm_csr_dense,
+    spmm_csr_prealloc, spsolve_csr_lower_triangular,
+};
+use nalgebra_sparse::ops::Op;
+use nalgebra_sparse::proptest::{csc, csr};
+use nalgebra_sparse::{SparseEntryMut, SparseFormatErrorKind};
+
+use proptest::prelude::*;
+
+/// Generates a pair of CSR matrices `(a, b)` such that `a * b` is defined.
+fn csr_spmm_pair_strategy() -> impl Strategy<Value = (CsrMatrix<i32>, CsrMatrix<i32>)> {
+    (
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+    )
+        .prop_flat_map(|(m, k, n)| {
+            (
+                csr(PROPTEST_I32_VALUE_STRATEGY, m, k, 20),
+                csr(PROPTEST_I32_VALUE_STRATEGY, k, n, 20),
+            )
+        })
+}
+
+/// Generates a pair of CSC matrices `(a, b)` such that `a * b` is defined.
+fn csc_spmm_pair_strategy() -> impl Strategy<Value = (CscMatrix<i32>, CscMatrix<i32>)> {
+    (
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+        PROPTEST_MATRIX_DIM,
+    )
+        .prop_flat_map(|(m, k, n)| {
+            (
+                csc(PROPTEST_I32_VALUE_STRATEGY, m, k, 20),
+                csc(PROPTEST_I32_VALUE_STRATEGY, k, n, 20),
+            )
+        })
+}
+
+#[test]
+fn csr_view_from_valid_data() {
+    let offsets = vec![0, 2, 2, 5];
+    let indices = vec![0, 5, 1, 2, 3];
+    let values = vec![0, 1, 2, 3, 4];
+    let view = CsrMatrixView::try_from_csr_data(3, 6, &offsets, &indices, &values).unwrap();
+    let owned =
+        CsrMatrix::try_from_csr_data(3, 6, offsets.clone(), indices.clone(), values.clone())
+            .unwrap();
+
+    assert_eq!(view.nrows(), 3);
+    assert_eq!(view.ncols(), 6);
+    assert_eq!(view.nnz(), 5);
+    assert_eq!(view.row_offsets(), offsets.as_slice());
+    assert_eq!(view.col_indices(), indices.as_slice());
+    assert_eq!(view.values(), values.as_slice());
+    assert_eq!(view.row(2).col_indices(), &[1, 2, 3]);
+    assert_eq!(view.row(2).values(), &[2, 3, 4]);
+    assert!(view.get_row(3).is_none());
+    assert_eq!(
+        view.triplet_iter().collect::<Vec<_>>(),
+        owned.triplet_iter().collect::<Vec<_>>()
+    );
+    assert_eq!(view.get_entry(0, 5), owned.get_entry(0, 5));
+    assert_eq!(view.get_entry(1, 0), owned.get_entry(1, 0));
+    assert_eq!(view.clone_owned(), owned);
+    assert_eq!(view.transpose(), owned.transpose());
+    assert_eq!(owned.as_view(), view);
+    assert_eq!(CsrMatrixView::from(&owned), view);
+}
+
+#[test]
+fn csr_view_from_invalid_data() {
+    // Each case must be rejected with the same error kind as the owned constructor
+    let cases: Vec<(usize, usize, Vec<usize>, Vec<usize>, Vec<i32>)> = vec![
+        // Wrong number of offsets
+        (3, 6, vec![0, 2, 5], vec![0, 5, 1, 2, 3], vec![0; 5]),
+        // Column index out of bounds
+        (3, 6, vec![0, 2, 2, 5], vec![0, 6, 1, 2, 3], vec![0; 5]),
+        // Unsorted column indices
+        (3, 6, vec![0, 2, 2, 5], vec![5, 0, 1, 2, 3], vec![0; 5]),
+        // Duplicate column indices
+        (3, 6, vec![0, 2, 2, 5], vec![0, 5, 1, 1, 3], vec![0; 5]),
+    ];
+
+    for (nrows, ncols, offsets, indices, values) in cases {
+        let view_error =
+            CsrMatrixView::try_from_csr_data(nrows, ncols, &offsets, &indices, &values)
+                .unwrap_err();
+        let owned_error =
+            CsrMatrix::try_from_csr_data(nrows, ncols, offsets, indices, values).unwrap_err();
+        assert_eq!(view_error.kind(), owned_error.kind());
+    }
+
+    // Wrong number of values
+    let offsets = vec![0, 2, 2, 5];
+    let indices = vec![0, 5, 1, 2, 3];
+    let mut values = vec![0, 1, 2, 3];
+    assert_eq!(
+        CsrMatrixView::try_from_csr_data(3, 6, &offsets, &indices, &values)
+            .unwrap_err()
+            .kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+    assert_eq!(
+        CsrMatrixViewMut::try_from_csr_data(3, 6, &offsets, &indices, &mut values)
+            .unwrap_err()
+            .kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn csr_view_mut_modifies_underlying_values() {
+    let offsets = vec![0, 2, 2, 5];
+    let indices = vec![0, 5, 1, 2, 3];
+    let mut values = vec![0, 1, 2, 3, 4];
+
+    {
+        let mut view =
+            CsrMatrixViewMut::try_from_csr_data(3, 6, &offsets, &indices, &mut values).unwrap();
+        assert_eq!(view.nnz(), 5);
+        assert_eq!(view.as_view().row(0).values(), &[0, 1]);
+
+        view.values_mut()[0] = 10;
+        view.row_mut(2).values_mut()[0] = 20;
+        if let Some(SparseEntryMut::NonZero(v)) = view.get_entry_mut(0, 5) {
+            *v = 30;
+        }
+        for (i, j, v) in view.triplet_iter_mut() {
+            if (i, j) == (2, 3) {
+                *v = 40;
+            }
+        }
+        for mut row in view.row_iter_mut() {
+            for v in row.values_mut() {
+                *v += 1;
+            }
+        }
+
+        // Reborrowing yields a view of the same data
+        let reborrowed = view.as_view_mut();
+        assert_eq!(reborrowed.values(), &[11, 31, 21, 4, 41]);
+    }
+
+    assert_eq!(values, vec![11, 31, 21, 4, 41]);
+
+    let mut owned = CsrMatrix::try_from_csr_data(3, 6, offsets, indices, values).unwrap();
+    owned.as_view_mut().values_mut()[1] = 0;
+    assert_eq!(owned.values(), &[11, 0, 21, 4, 41]);
+    let view: CsrMatrixView<_> = CsrMatrixViewMut::from(&mut owned).into();
+    assert_eq!(view.values(), &[11, 0, 21, 4, 41]);
+}
+
+#[test]
+fn csr_view_with_u32_indices() {
+    let offsets: Vec<u32> = vec![0, 2, 2, 5];
+    let indices: Vec<u32> = vec![0, 5, 1, 2, 3];
+    let values = vec![0, 1, 2, 3, 4];
+    let view = CsrMatrixView::try_from_csr_data_generic(3, 6, &offsets, &indices, &values).unwrap();
+    assert_eq!(view.row_offsets(), offsets.as_slice());
+    assert_eq!(view.col_indices(), indices.as_slice());
+    assert_eq!(view.row(2).col_indices(), &[1, 2, 3]);
+
+    let owned = CsrMatrix::try_from_csr_data_generic(
+        3,
+        6,
+        offsets.clone(),
+        indices.clone(),
+        values.clone(),
+    )
+    .unwrap();
+    assert_eq!(view.clone_owned(), owned);
+
+    let bad_indices: Vec<u32> = vec![0, 6, 1, 2, 3];
+    let bad_values = vec![0; 5];
+    assert_eq!(
+        CsrMatrixView::try_from_csr_data_generic(3, 6, &offsets, &bad_indices, &bad_values)
+            .unwrap_err()
+            .kind(),
+        &SparseFormatErrorKind::IndexOutOfBounds
+    );
+}
+
+#[test]
+fn csc_view_from_valid_data() {
+    let offsets = vec![0, 2, 2, 5];
+    let indices = vec![0, 5, 1, 2, 3];
+    let mut values = vec![0, 1, 2, 3, 4];
+    let owned =
+        CscMatrix::try_from_csc_data(6, 3, offsets.clone(), indices.clone(), values.clone())
+            .unwrap();
+
+    {
+        let view = CscMatrixView::try_from_csc_data(6, 3, &offsets, &indices, &values).unwrap();
+        assert_eq!(view.nrows(), 6);
+        assert_eq!(view.ncols(), 3);
+        assert_eq!(view.nnz(), 5);
+        assert_eq!(view.col_offsets(), offsets.as_slice());
+        assert_eq!(view.row_indices(), indices.as_slice());
+        assert_eq!(view.col(2).row_indices(), &[1, 2, 3]);
+        assert_eq!(
+            view.triplet_iter().collect::<Vec<_>>(),
+            owned.triplet_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(view.get_entry(5, 0), owned.get_entry(5, 0));
+        assert_eq!(view.clone_owned(), owned);
+        assert_eq!(owned.as_view(), view);
+    }
+
+    let mut view =
+        CscMatrixViewMut::try_from_csc_data(6, 3, &offsets, &indices, &mut values).unwrap();
+    view.col_mut(2).values_mut()[2] = 10;
+    assert_eq!(values, vec![0, 1, 2, 3, 10]);
+
+    let mut bad_values = vec![0; 4];
+    assert_eq!(
+        CscMatrixViewMut::try_from_csc_data(6, 3, &offsets, &indices, &mut bad_values)
+            .unwrap_err()
+            .kind(),
+        &SparseFormatErrorKind::InvalidStructure
+    );
+}
+
+#[test]
+fn csr_view_lower_triangular_solve() {
+    let l = CsrMatrix::try_from_csr_data(
+        3,
+        3,
+        vec![0, 1, 3, 5],
+        vec![0, 0, 1, 1, 2],
+        vec![2.0, 1.0, 4.0, -1.0, 5.0],
+    )
+    .unwrap();
+    let b = DMatrix::from_column_slice(3, 1, &[2.0, 5.0, 4.0]);
+
+    let mut x_owned = b.clone();
+    spsolve_csr_lower_triangular(Op::NoOp(&l), &mut x_owned).unwrap();
+
+    let (offsets, indices, values) = l.csr_data();
+    let view = CsrMatrixView::try_from_csr_data(3, 3, offsets, indices, values).unwrap();
+    let mut x_view = b;
+    spsolve_csr_lower_triangular(Op::NoOp(view), &mut x_view).unwrap();
+
+    assert_eq!(x_view, x_owned);
+    assert_eq!(x_view, DMatrix::from_column_slice(3, 1, &[1.0, 1.0, 1.0]));
+}
+
+proptest! {
+    #[test]
+    fn csr_view_matches_owned(a in csr_strategy()) {
+        let (offsets, indices, values) = a.csr_data();
+        let view = CsrMatrixView::try_from_csr_data(a.nrows(), a.ncols(), offsets, indices, values)
+            .unwrap();
+
+        prop_assert_eq!(view, a.as_view());
+        prop_assert_eq!(view.clone_owned(), a.clone());
+        prop_assert_eq!(view.transpose(), a.transpose());
+        prop_assert_eq!(
+            view.triplet_iter().collect::<Vec<_>>(),
+            a.triplet_iter().collect::<Vec<_>>()
+        );
+        for (view_row, row) in view.row_iter().zip(a.row_iter()) {
+            prop_assert_eq!(view_row.col_indices(), row.col_indices());
+            prop_assert_eq!(view_row.values(), row.values());
+        }
+    }
+
+    #[test]
+    fn csc_view_matches_owned(a in csc_strategy()) {
+        let (offsets, indices, values) = a.csc_data();
+        let view = CscMatrixView::try_from_csc_data(a.nrows(), a.ncols(), offsets, indices, values)
+            .unwrap();
+
+        prop_assert_eq!(view, a.as_view());
+        prop_assert_eq!(view.clone_owned(), a.clone());
+        prop_assert_eq!(view.transpose(), a.transpose());
+        for (view_col, col) in view.col_iter().zip(a.col_iter()) {
+            prop_assert_eq!(view_col.row_indices(), col.row_indices());
+            prop_assert_eq!(view_col.values(), col.values());
+        }
+    }
+
+    #[test]
+    fn spmm_csr_dense_with_view_matches_owned(
+        (a, b) in csr_spmm_pair_strategy(),
+        transpose_a in proptest::bool::ANY,
+    ) {
+        let b = DMatrix::from(&b);
+        let a = if transpose_a { a.transpose() } else { a };
+        let op = if transpose_a { Op::Transpose(()) } else { Op::NoOp(()) };
+        let mut c_owned = DMatrix::zeros(
+            if transpose_a { a.ncols() } else { a.nrows() },
+            b.ncols(),
+        );
+        let mut c_view = c_owned.clone();
+
+        spmm_csr_dense(0, &mut c_owned, 1, op.map_same_op(|_| &a), Op::NoOp(&b));
+        spmm_csr_dense(0, &mut c_view, 1, op.map_same_op(|_| a.as_view()), Op::NoOp(&b));
+
+        prop_assert_eq!(c_view, c_owned);
+    }
+
+    #[test]
+    fn spmm_csc_dense_with_view_matches_owned((a, b) in csc_spmm_pair_strategy()) {
+        let b = DMatrix::from(&b);
+        let mut c_owned = DMatrix::zeros(a.nrows(), b.ncols());
+        let mut c_view = c_owned.clone();
+
+        spmm_csc_dense(0, &mut c_owned, 1, Op::NoOp(&a), Op::NoOp(&b));
+        spmm_csc_dense(0, &mut c_view, 1, Op::NoOp(a.as_view()), Op::NoOp(&b));
+
+        prop_assert_eq!(c_view, c_owned);
+    }
+
+    #[test]
+    fn spadd_csr_prealloc_with_views_matches_owned(
+        a in csr_strategy(),
+        beta in PROPTEST_I32_VALUE_STRATEGY,
+        alpha in PROPTEST_I32_VALUE_STRATEGY,
+    ) {
+        // The pattern of `a + a` is exactly the pattern of `a`
+        let c = &a + &a;
+        let mut c_owned = c.clone();
+        let mut c_view = c.clone();
+
+        spadd_csr_prealloc(beta, &mut c_owned, alpha, Op::NoOp(&a)).unwrap();
+        spadd_csr_prealloc(beta, c_view.as_view_mut(), alpha, Op::NoOp(a.as_view())).unwrap();
+
+        prop_assert_eq!(c_view, c_owned);
+    }
+
+    #[test]
+    fn spmm_csr_prealloc_with_views_matches_owned((a, b) in csr_spmm_pair_strategy()) {
+        let c = &a * &b;
+        let mut c_owned = c.clone();
+        let mut c_view = c.clone();
+
+        spmm_csr_prealloc(2, &mut c_owned, 3, Op::NoOp(&a), Op::NoOp(&b)).unwrap();
+        spmm_csr_prealloc(2, c_view.as_view_mut(), 3, Op::NoOp(a.as_view()), Op::NoOp(b.as_view()))
+            .unwrap();
+        prop_assert_eq!(c_view, c_owned);
+
+        // Transposed operands: (B^T A^T)^T = A B
+        let (at, bt) = (a.transpose(), b.transpose());
+        let mut ct = c.transpose();
+        spmm_csr_prealloc(0, ct.as_view_mut(), 1, Op::Transpose(b.as_view()), Op::Transpose(a.as_view()))
+            .unwrap();
+        prop_assert_eq!(ct, &bt * &at);
+    }
+
+    #[test]
+    fn spmm_csc_prealloc_with_views_matches_owned((a, b) in csc_spmm_pair_strategy()) {
+        let c = &a * &b;
+        let mut c_owned = c.clone();
+        let mut c_view = c.clone();
+
+        spmm_csc_prealloc(2, &mut c_owned, 3, Op::NoOp(&a), Op::NoOp(&b)).unwrap();
+        spmm_csc_prealloc(2, c_view.as_view_mut(), 3, Op::NoOp(a.as_view()), Op::NoOp(b.as_view()))
+            .unwrap();
+        prop_assert_eq!(c_view, c_owned);
+    }
+
+    #[test]
+    fn csr_view_mul_matches_owned(
+        (a, b) in csr_spmm_pair_strategy(),
+        x in matrix(PROPTEST_I32_VALUE_STRATEGY, PROPTEST_MATRIX_DIM, 2),
+    ) {
+        prop_assert_eq!(a.as_view() * b.as_view(), &a * &b);
+
+        // Adjust the number of rows in the dense operand to match
+        let x = x.resize_vertically(b.ncols(), 1);
+        prop_assert_eq!(b.as_view() * &x, &b * &x);
+        prop_assert_eq!(b.as_view() * x.clone(), &b * &x);
+    }
+
+    #[test]
+    fn csc_view_mul_matches_owned((a, b) in csc_spmm_pair_strategy()) {
+        prop_assert_eq!(a.as_view() * b.as_view(), &a * &b);
+
+        let x = DMatrix::from_fn(b.ncols(), 2, |i, j| (i + j) as i32);
+        prop_assert_eq!(b.as_view() * &x, &b * &x);
+    }
+
+    #[test]
+    fn elementwise_union_csr_with_views_matches_owned(a in csr_strategy()) {
+        let b = a.filter(|i, j, _| (i + j) % 2 == 0).map(|v| 2 * v);
+        prop_assert_eq!(
+            elementwise_union_csr(a.as_view(), b.as_view(), |x, y| x + y),
+            elementwise_union_csr(&a, &b, |x, y| x + y)
+        );
+    }
+}
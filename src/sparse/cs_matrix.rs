@@ -358,6 +358,12 @@ impl<T: Scalar, R: Dim, C: Dim, S: CsStorage<T, R, C>> CsMatrix<T, R, C, S> {
         }
     }
 
+    /// The storage of this matrix.
+    #[must_use]
+    pub fn data(&self) -> &S {
+        &self.data
+    }
+
     /// The size of the data buffer.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -1,4 +1,8 @@
 //! Sparse matrices.
+//!
+//! This module is superseded by the [`nalgebra-sparse`](https://crates.io/crates/nalgebra-sparse)
+//! crate, which offers the same functionality in addition to several other formats, and which
+//! can convert its CSC matrices and sparse vectors from and to the types of this module.
 
 pub use self::cs_matrix::{
     CsMatrix, CsStorage, CsStorageIter, CsStorageIterMut, CsStorageMut, CsVecStorage, CsVector,